@@ -0,0 +1,137 @@
+//! Criterion micro-benchmarks for authentication hot paths.
+//!
+//! Covers the building blocks whose regressions show up directly in login and
+//! request latency: Argon2 hashing/verification, JWT encode/decode, the
+//! `auth_middleware` layer, and the full login/refresh handlers against a
+//! mocked database.
+//!
+//! # Usage
+//!
+//! ```bash
+//! cargo bench --bench auth
+//! # Quick compile-and-run check (one iteration each), suitable for CI
+//! cargo bench --bench auth -- --test
+//! ```
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use cobalt_stack_backend::services::auth::{
+    create_access_token, create_refresh_token, hash_password, verify_access_token, verify_password,
+};
+use common::{auth_router, login_body, protected_router, Fixture, PASSWORD};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use tokio::runtime::Runtime;
+use tower::ServiceExt;
+
+fn bench_password(c: &mut Criterion) {
+    let mut group = c.benchmark_group("password");
+    group.sample_size(10);
+
+    let hash = hash_password(PASSWORD).unwrap();
+    group.bench_function("hash", |b| b.iter(|| hash_password(PASSWORD).unwrap()));
+    group.bench_function("verify", |b| {
+        b.iter(|| verify_password(PASSWORD, &hash).unwrap());
+    });
+    group.finish();
+}
+
+fn bench_jwt(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let fixture = Fixture::new();
+    let config = &fixture.jwt_config;
+    let user = &fixture.user;
+    let token = create_access_token(user.id, user.username.clone(), config).unwrap();
+
+    let mut group = c.benchmark_group("jwt");
+    group.bench_function("create_access_token", |b| {
+        b.iter(|| create_access_token(user.id, user.username.clone(), config).unwrap());
+    });
+    group.bench_function("create_refresh_token", |b| {
+        b.iter(|| create_refresh_token(user.id.into(), config).unwrap());
+    });
+    group.bench_function("verify_access_token", |b| {
+        b.to_async(&rt)
+            .iter(|| async { verify_access_token(&token, config).await.unwrap() });
+    });
+    group.finish();
+}
+
+fn bench_middleware(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let fixture = Fixture::new();
+    let token = create_access_token(
+        fixture.user.id,
+        fixture.user.username.clone(),
+        &fixture.jwt_config,
+    )
+    .unwrap();
+    let app = protected_router(fixture.jwt_config);
+
+    c.bench_function("auth_middleware/verify", |b| {
+        b.to_async(&rt).iter(|| async {
+            let req = Request::get("/api/v1/protected")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap();
+            let res = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        });
+    });
+}
+
+fn bench_handlers(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let fixture = Fixture::new();
+
+    let mut group = c.benchmark_group("handlers");
+    group.sample_size(10);
+
+    group.bench_function("login", |b| {
+        b.to_async(&rt).iter_batched(
+            || auth_router(fixture.app_state(fixture.login_db())),
+            |app| async move {
+                let req = Request::post("/api/v1/auth/login")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(login_body()))
+                    .unwrap();
+                let res = app.oneshot(req).await.unwrap();
+                assert_eq!(res.status(), StatusCode::OK);
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    group.bench_function("refresh", |b| {
+        b.to_async(&rt).iter_batched(
+            || {
+                let (token, jti) =
+                    create_refresh_token(fixture.user.id.into(), &fixture.jwt_config).unwrap();
+                let app = auth_router(fixture.app_state(fixture.refresh_db(jti, &token)));
+                (app, token)
+            },
+            |(app, token)| async move {
+                let req = Request::post("/api/v1/auth/refresh")
+                    .header(header::COOKIE, format!("refresh_token={token}"))
+                    .body(Body::empty())
+                    .unwrap();
+                let res = app.oneshot(req).await.unwrap();
+                assert_eq!(res.status(), StatusCode::OK);
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_password,
+    bench_jwt,
+    bench_middleware,
+    bench_handlers
+);
+criterion_main!(benches);
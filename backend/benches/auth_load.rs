@@ -0,0 +1,241 @@
+//! HTTP-level load scenario for authentication endpoints.
+//!
+//! Drives the real axum routers (routing, extractors, middleware, handlers)
+//! with concurrent requests and reports latency percentiles and throughput for
+//! login, refresh, and authenticated requests. The database is mocked so the
+//! numbers reflect application cost rather than Postgres round-trips.
+//!
+//! # Usage
+//!
+//! ```bash
+//! cargo bench --bench auth_load
+//! # CI smoke mode: fewer requests, fail if p95 exceeds thresholds
+//! BENCH_SMOKE=1 cargo bench --bench auth_load
+//! ```
+//!
+//! # Environment Variables
+//!
+//! - `BENCH_SMOKE`: Run reduced request counts (default: false)
+//! - `BENCH_CONCURRENCY`: Concurrent in-flight requests (default: 8)
+//! - `BENCH_MAX_P95_LOGIN_MS`: Login p95 threshold (default: 500)
+//! - `BENCH_MAX_P95_REFRESH_MS`: Refresh p95 threshold (default: 50)
+//! - `BENCH_MAX_P95_VERIFY_MS`: Authenticated request p95 threshold (default: 10)
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use cobalt_stack_backend::services::auth::{create_access_token, create_refresh_token};
+use common::{auth_router, login_body, protected_router, Fixture};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Semaphore;
+use tower::ServiceExt;
+
+/// Load settings for one run
+struct LoadConfig {
+    smoke: bool,
+    concurrency: usize,
+}
+
+impl LoadConfig {
+    fn from_env() -> Self {
+        Self {
+            smoke: std::env::var("BENCH_SMOKE").is_ok_and(|v| v == "1" || v == "true"),
+            concurrency: env_or("BENCH_CONCURRENCY", 8),
+        }
+    }
+
+    /// Request count scaled down in smoke mode
+    const fn requests(&self, full: usize) -> usize {
+        if self.smoke {
+            full / 10
+        } else {
+            full
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Latency summary for one scenario
+struct Report {
+    name: &'static str,
+    requests: usize,
+    elapsed: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+}
+
+impl Report {
+    fn new(name: &'static str, mut samples: Vec<Duration>, elapsed: Duration) -> Self {
+        samples.sort_unstable();
+        let pct = |p: usize| samples[(samples.len() * p / 100).min(samples.len() - 1)];
+        Self {
+            name,
+            requests: samples.len(),
+            elapsed,
+            p50: pct(50),
+            p95: pct(95),
+            p99: pct(99),
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn print(&self) {
+        println!(
+            "{:<10} {:>6} req  {:>9.1} req/s  p50 {:>8.2?}  p95 {:>8.2?}  p99 {:>8.2?}",
+            self.name,
+            self.requests,
+            self.requests as f64 / self.elapsed.as_secs_f64(),
+            self.p50,
+            self.p95,
+            self.p99,
+        );
+    }
+}
+
+/// Run `requests` calls of `make_call` with bounded concurrency.
+async fn run<F, Fut>(
+    name: &'static str,
+    requests: usize,
+    concurrency: usize,
+    make_call: F,
+) -> Report
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = StatusCode> + Send + 'static,
+{
+    let permits = Arc::new(Semaphore::new(concurrency));
+    let start = Instant::now();
+    let mut tasks = Vec::with_capacity(requests);
+
+    for _ in 0..requests {
+        let permit = Arc::clone(&permits).acquire_owned().await.unwrap();
+        let call = make_call();
+        tasks.push(tokio::spawn(async move {
+            let t = Instant::now();
+            let status = call.await;
+            drop(permit);
+            assert_eq!(status, StatusCode::OK, "{name} request failed");
+            t.elapsed()
+        }));
+    }
+
+    let mut samples = Vec::with_capacity(requests);
+    for task in tasks {
+        samples.push(task.await.unwrap());
+    }
+
+    Report::new(name, samples, start.elapsed())
+}
+
+async fn send(app: Router, req: Request<Body>) -> StatusCode {
+    app.oneshot(req).await.unwrap().status()
+}
+
+#[tokio::main]
+async fn main() {
+    let config = LoadConfig::from_env();
+    let fixture = Arc::new(Fixture::new());
+
+    println!(
+        "auth load scenario (smoke: {}, concurrency: {})",
+        config.smoke, config.concurrency
+    );
+
+    let login = {
+        let fixture = Arc::clone(&fixture);
+        run(
+            "login",
+            config.requests(200),
+            config.concurrency,
+            move || {
+                let app = auth_router(fixture.app_state(fixture.login_db()));
+                let req = Request::post("/api/v1/auth/login")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(login_body()))
+                    .unwrap();
+                send(app, req)
+            },
+        )
+        .await
+    };
+
+    let refresh = {
+        let fixture = Arc::clone(&fixture);
+        run(
+            "refresh",
+            config.requests(2_000),
+            config.concurrency,
+            move || {
+                let (token, jti) =
+                    create_refresh_token(fixture.user.id.into(), &fixture.jwt_config).unwrap();
+                let app = auth_router(fixture.app_state(fixture.refresh_db(jti, &token)));
+                let req = Request::post("/api/v1/auth/refresh")
+                    .header(header::COOKIE, format!("refresh_token={token}"))
+                    .body(Body::empty())
+                    .unwrap();
+                send(app, req)
+            },
+        )
+        .await
+    };
+
+    let verify = {
+        let token = create_access_token(
+            fixture.user.id,
+            fixture.user.username.clone(),
+            &fixture.jwt_config,
+        )
+        .unwrap();
+        let app = protected_router(fixture.jwt_config.clone());
+        run(
+            "verify",
+            config.requests(20_000),
+            config.concurrency,
+            move || {
+                let req = Request::get("/api/v1/protected")
+                    .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                    .body(Body::empty())
+                    .unwrap();
+                send(app.clone(), req)
+            },
+        )
+        .await
+    };
+
+    let checks = [
+        (&login, env_or("BENCH_MAX_P95_LOGIN_MS", 500_u64)),
+        (&refresh, env_or("BENCH_MAX_P95_REFRESH_MS", 50_u64)),
+        (&verify, env_or("BENCH_MAX_P95_VERIFY_MS", 10_u64)),
+    ];
+
+    let mut failed = false;
+    for (report, max_p95_ms) in checks {
+        report.print();
+        if report.p95 > Duration::from_millis(max_p95_ms) {
+            eprintln!(
+                "  ✗ {} p95 {:.2?} exceeds threshold {max_p95_ms}ms",
+                report.name, report.p95
+            );
+            failed = true;
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,194 @@
+//! Shared fixtures for auth benchmarks.
+//!
+//! Builds routers and mock databases mirroring the production wiring in
+//! `main.rs`, so benchmarks exercise real handlers and middleware without
+//! requiring Postgres.
+
+#![allow(dead_code)]
+
+use axum::{
+    middleware as axum_middleware,
+    routing::{get, post},
+    Router,
+};
+use chrono::{Duration, Utc};
+use cobalt_stack_backend::{
+    config::VerificationLinkConfig,
+    domain::ids::TokenId,
+    dto::health::ActiveModules,
+    handlers::auth::{login, refresh_token, AppState},
+    middleware::{
+        auth::{auth_middleware, AuthUser},
+        current_user::UserLoader,
+    },
+    models::{audit_logs, refresh_tokens, sea_orm_active_enums::UserRole, users},
+    services::{
+        auth::{hash_password, jwt::TokenValidation, JwtConfig, LoginMetrics, SeaOrmTokenStore},
+        runtime_switches::RuntimeSwitches,
+    },
+    utils::token::hash_token,
+};
+use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, MockExecResult};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub const USERNAME: &str = "bench_user";
+pub const PASSWORD: &str = "BenchPass123!";
+
+pub fn jwt_config() -> JwtConfig {
+    JwtConfig {
+        secret: "bench_secret_key".to_string(),
+        access_token_expiry_minutes: 30,
+        refresh_token_expiry_days: 7,
+        validation: TokenValidation::default(),
+        not_before: None,
+        blacklist: None,
+        dpop: None,
+    }
+}
+
+/// Fixed user with a pre-computed Argon2 hash (hashing is benchmarked separately).
+#[derive(Clone)]
+pub struct Fixture {
+    pub user: users::Model,
+    pub jwt_config: JwtConfig,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let now = Utc::now().into();
+        Self {
+            user: users::Model {
+                id: Uuid::new_v4(),
+                username: USERNAME.to_string(),
+                email: "bench@example.com".to_string(),
+                password_hash: Some(hash_password(PASSWORD).expect("hash bench password")),
+                email_verified: true,
+                created_at: now,
+                updated_at: now,
+                role: UserRole::User,
+                disabled_at: None,
+                last_login_at: None,
+                tokens_not_before: None,
+                password_changed_at: None,
+                password_rotation_required: false,
+            },
+            jwt_config: jwt_config(),
+        }
+    }
+
+    fn refresh_token_model(&self, jti: TokenId, token: &str) -> refresh_tokens::Model {
+        let now = Utc::now();
+        refresh_tokens::Model {
+            id: jti.into_uuid(),
+            user_id: self.user.id,
+            family_id: jti.into_uuid(),
+            token_hash: hash_token(token),
+            expires_at: (now + Duration::days(7)).into(),
+            revoked_at: None,
+            created_at: now.into(),
+            user_agent: None,
+            fingerprint: None,
+            ip: None,
+            last_used_at: None,
+        }
+    }
+
+    /// Audit log row returned by `INSERT ... RETURNING`.
+    fn audit_log_model(&self) -> audit_logs::Model {
+        audit_logs::Model {
+            id: Uuid::new_v4(),
+            event: "auth.login".to_string(),
+            actor_id: Some(self.user.id),
+            target_id: None,
+            ip: None,
+            user_agent: None,
+            details: None,
+            created_at: Utc::now().into(),
+        }
+    }
+
+    /// Mock database primed for exactly one successful login.
+    pub fn login_db(&self) -> DatabaseConnection {
+        MockDatabase::new(DatabaseBackend::Postgres)
+            // Users lookup by username/email
+            .append_query_results([[self.user.clone()]])
+            // Audit log entry of the sign-in
+            .append_query_results([[self.audit_log_model()]])
+            // INSERT for the new refresh token
+            .append_exec_results([one_row()])
+            .into_connection()
+    }
+
+    /// Mock database primed for exactly one successful refresh of `token`.
+    pub fn refresh_db(&self, jti: TokenId, token: &str) -> DatabaseConnection {
+        let stored = self.refresh_token_model(jti, token);
+        MockDatabase::new(DatabaseBackend::Postgres)
+            // validate_refresh_token
+            .append_query_results([[stored.clone()]])
+            // Users lookup for username
+            .append_query_results([[self.user.clone()]])
+            // rotate_refresh_token: lookup, conditional UPDATE, INSERT
+            .append_query_results([[stored]])
+            .append_exec_results([one_row(), one_row()])
+            .into_connection()
+    }
+
+    pub fn app_state(&self, db: DatabaseConnection) -> AppState {
+        let db = Arc::new(db);
+        AppState {
+            db: Arc::clone(&db),
+            users: UserLoader::new(Arc::clone(&db)),
+            jwt_config: self.jwt_config.clone(),
+            token_store: Arc::new(SeaOrmTokenStore::new(Arc::clone(&db))),
+            email_sender: None,
+            email_suppressions: None,
+            modules: ActiveModules {
+                chat: false,
+                admin_api: false,
+                email: false,
+            },
+            bind_refresh_tokens: false,
+            chat_quota: None,
+            password_expiry: None,
+            trusted_devices: None,
+            sandbox: None,
+            switches: Arc::new(RuntimeSwitches::new(db)),
+            verification_links: Arc::new(VerificationLinkConfig::default()),
+            login_metrics: Arc::new(LoginMetrics::new()),
+        }
+    }
+}
+
+/// Result of a statement affecting one row.
+const fn one_row() -> MockExecResult {
+    MockExecResult {
+        last_insert_id: 0,
+        rows_affected: 1,
+    }
+}
+
+/// Router exposing login and refresh, wired as in `create_app`.
+pub fn auth_router(state: AppState) -> Router {
+    Router::new()
+        .route("/api/v1/auth/login", post(login))
+        .route("/api/v1/auth/refresh", post(refresh_token))
+        .with_state(state)
+}
+
+/// Protected router with a no-op handler, isolating `auth_middleware` cost.
+pub fn protected_router(jwt_config: JwtConfig) -> Router {
+    Router::new()
+        .route(
+            "/api/v1/protected",
+            get(|user: AuthUser| async move { user.username }),
+        )
+        .layer(axum_middleware::from_fn_with_state(
+            jwt_config,
+            auth_middleware,
+        ))
+}
+
+pub fn login_body() -> String {
+    serde_json::json!({ "username_or_email": USERNAME, "password": PASSWORD }).to_string()
+}
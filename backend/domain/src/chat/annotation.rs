@@ -0,0 +1,277 @@
+//! Message annotations
+//!
+//! Users attach lightweight annotations to messages in their sessions to
+//! find their way back through long conversations: a bookmark, a label, or
+//! an emoji reaction. A user has at most one annotation per kind and value
+//! on a message, so adding the same one twice is a no-op.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::fmt;
+use uuid::Uuid;
+
+use super::entity::ChatMessage;
+use super::repository::RepositoryResult;
+
+/// Longest accepted label, in characters
+pub const MAX_LABEL_CHARS: usize = 64;
+
+/// Longest accepted reaction, in bytes (room for ZWJ emoji sequences)
+pub const MAX_REACTION_BYTES: usize = 32;
+
+/// Kind of an annotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnnotationKind {
+    Bookmark,
+    Label,
+    Reaction,
+}
+
+impl AnnotationKind {
+    /// Name used in storage and the API
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Bookmark => "bookmark",
+            Self::Label => "label",
+            Self::Reaction => "reaction",
+        }
+    }
+
+    /// Parse a kind from its name
+    ///
+    /// # Errors
+    /// Returns an error naming the valid kinds if `s` is not one of them
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "bookmark" => Ok(Self::Bookmark),
+            "label" => Ok(Self::Label),
+            "reaction" => Ok(Self::Reaction),
+            _ => Err(format!(
+                "Invalid annotation kind: {s} (expected bookmark, label or reaction)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AnnotationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An annotation's kind and value
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Annotation {
+    Bookmark,
+    /// Free-text label, trimmed and case-preserving
+    Label(String),
+    /// A single emoji (or emoji sequence)
+    Reaction(String),
+}
+
+impl Annotation {
+    /// Build and validate an annotation
+    ///
+    /// Bookmarks take no value; labels and reactions require one.
+    ///
+    /// # Errors
+    /// Returns an error if the value is missing, unexpected, or invalid for
+    /// the kind
+    pub fn new(kind: AnnotationKind, value: Option<&str>) -> Result<Self, String> {
+        let value = value.map(str::trim).filter(|value| !value.is_empty());
+        match (kind, value) {
+            (AnnotationKind::Bookmark, None) => Ok(Self::Bookmark),
+            (AnnotationKind::Bookmark, Some(_)) => Err("Bookmarks do not take a value".to_string()),
+            (AnnotationKind::Label | AnnotationKind::Reaction, None) => {
+                Err(format!("A {kind} needs a value"))
+            }
+            (AnnotationKind::Label, Some(label)) => {
+                if label.chars().count() > MAX_LABEL_CHARS {
+                    return Err(format!(
+                        "Labels must be at most {MAX_LABEL_CHARS} characters"
+                    ));
+                }
+                if label.chars().any(char::is_control) {
+                    return Err("Labels must not contain control characters".to_string());
+                }
+                Ok(Self::Label(label.to_string()))
+            }
+            (AnnotationKind::Reaction, Some(reaction)) => {
+                if reaction.len() > MAX_REACTION_BYTES
+                    || reaction.chars().any(|c| c.is_ascii() || c.is_whitespace())
+                {
+                    return Err("Reactions must be a single emoji".to_string());
+                }
+                Ok(Self::Reaction(reaction.to_string()))
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> AnnotationKind {
+        match self {
+            Self::Bookmark => AnnotationKind::Bookmark,
+            Self::Label(_) => AnnotationKind::Label,
+            Self::Reaction(_) => AnnotationKind::Reaction,
+        }
+    }
+
+    /// Label text or emoji (`None` for bookmarks)
+    #[must_use]
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Self::Bookmark => None,
+            Self::Label(value) | Self::Reaction(value) => Some(value),
+        }
+    }
+}
+
+/// A user's annotation on one message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageAnnotation {
+    pub id: Uuid,
+    pub message_id: Uuid,
+    /// Session of the message, for listing a session's annotations
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub annotation: Annotation,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MessageAnnotation {
+    /// Create an annotation by `user_id` on a message of `session_id`
+    #[must_use]
+    pub fn new(session_id: Uuid, message_id: Uuid, user_id: Uuid, annotation: Annotation) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            message_id,
+            session_id,
+            user_id,
+            annotation,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Selects messages by their annotations
+///
+/// Parsed from `bookmark`, `label`, `label:<text>`, `reaction` or
+/// `reaction:<emoji>`; without a value, any annotation of the kind matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotationFilter {
+    pub kind: AnnotationKind,
+    pub value: Option<String>,
+}
+
+impl AnnotationFilter {
+    /// Parse a filter expression
+    ///
+    /// # Errors
+    /// Returns an error if the kind is unknown or the value is invalid for it
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let (kind, value) = expression
+            .split_once(':')
+            .map_or((expression, None), |(kind, value)| (kind, Some(value)));
+        let kind = AnnotationKind::parse(kind.trim())?;
+        let value = match value {
+            Some(value) => Annotation::new(kind, Some(value))?
+                .value()
+                .map(ToString::to_string),
+            None => None,
+        };
+        Ok(Self { kind, value })
+    }
+
+    /// Whether `annotation` matches the filter
+    #[must_use]
+    pub fn matches(&self, annotation: &Annotation) -> bool {
+        annotation.kind() == self.kind
+            && self
+                .value
+                .as_deref()
+                .map_or(true, |value| annotation.value() == Some(value))
+    }
+}
+
+/// Annotation persistence
+#[async_trait]
+pub trait AnnotationRepository: Send + Sync {
+    /// Store an annotation, or return the identical one the user already has
+    ///
+    /// Returns `MessageNotFound` if the message is not in the session.
+    async fn add_annotation(
+        &self,
+        annotation: &MessageAnnotation,
+    ) -> RepositoryResult<MessageAnnotation>;
+
+    /// Delete one of the user's annotations on a message
+    ///
+    /// Returns `AnnotationNotFound` if there is no such annotation.
+    async fn remove_annotation(
+        &self,
+        user_id: Uuid,
+        message_id: Uuid,
+        annotation_id: Uuid,
+    ) -> RepositoryResult<()>;
+
+    /// The user's annotations in a session, oldest first
+    async fn find_annotations_by_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> RepositoryResult<Vec<MessageAnnotation>>;
+
+    /// Messages of a session the user annotated as `filter` selects, in
+    /// chronological order
+    async fn find_annotated_messages(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        filter: &AnnotationFilter,
+        limit: Option<u64>,
+    ) -> RepositoryResult<Vec<ChatMessage>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotation_validation() {
+        assert_eq!(
+            Annotation::new(AnnotationKind::Bookmark, None),
+            Ok(Annotation::Bookmark)
+        );
+        assert_eq!(
+            Annotation::new(AnnotationKind::Label, Some("  todo ")),
+            Ok(Annotation::Label("todo".to_string()))
+        );
+        assert_eq!(
+            Annotation::new(AnnotationKind::Reaction, Some("👍🏽")),
+            Ok(Annotation::Reaction("👍🏽".to_string()))
+        );
+
+        assert!(Annotation::new(AnnotationKind::Bookmark, Some("x")).is_err());
+        assert!(Annotation::new(AnnotationKind::Label, Some("  ")).is_err());
+        assert!(Annotation::new(AnnotationKind::Label, Some(&"a".repeat(65))).is_err());
+        assert!(Annotation::new(AnnotationKind::Reaction, Some(":+1:")).is_err());
+        assert!(Annotation::new(AnnotationKind::Reaction, Some("👍 👎")).is_err());
+    }
+
+    #[test]
+    fn test_annotation_filter() {
+        let any_label = AnnotationFilter::parse("label").unwrap();
+        let todo = AnnotationFilter::parse("label:todo").unwrap();
+        let bookmark = AnnotationFilter::parse("bookmark").unwrap();
+
+        assert!(any_label.matches(&Annotation::Label("done".to_string())));
+        assert!(todo.matches(&Annotation::Label("todo".to_string())));
+        assert!(!todo.matches(&Annotation::Label("done".to_string())));
+        assert!(bookmark.matches(&Annotation::Bookmark));
+        assert!(!bookmark.matches(&Annotation::Label("todo".to_string())));
+
+        assert!(AnnotationFilter::parse("star").is_err());
+        assert!(AnnotationFilter::parse("bookmark:x").is_err());
+    }
+}
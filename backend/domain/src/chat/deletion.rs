@@ -0,0 +1,93 @@
+//! Deletion of single messages
+//!
+//! Users can take back one message of their session without deleting the
+//! whole conversation. The deployment chooses what is left behind: nothing
+//! (the row is deleted) or a redacted message that keeps its role, token
+//! count and timestamp so the history still shows where it was. Either way
+//! the message is no longer sent to the LLM as context.
+
+use async_trait::async_trait;
+use std::fmt;
+use uuid::Uuid;
+
+use super::entity::ChatMessage;
+use super::repository::RepositoryResult;
+
+/// What deleting a message does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessageDeletionPolicy {
+    /// Delete the row, along with the annotations on it
+    Hard,
+    /// Replace the content and keep the metadata
+    #[default]
+    Redact,
+}
+
+impl MessageDeletionPolicy {
+    /// Name used in configuration and logs
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Hard => "hard",
+            Self::Redact => "redact",
+        }
+    }
+
+    /// Parse a policy from its name
+    ///
+    /// # Errors
+    /// Returns an error naming the valid policies if `s` is not one of them
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hard" | "delete" => Ok(Self::Hard),
+            "redact" => Ok(Self::Redact),
+            other => Err(format!(
+                "Invalid message deletion policy: {other} (expected hard or redact)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MessageDeletionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Persistence of message deletions
+#[async_trait]
+pub trait MessageDeletionRepository: Send + Sync {
+    /// Find a message of a session, redacted or not
+    async fn find_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> RepositoryResult<Option<ChatMessage>>;
+
+    /// Delete a message and its annotations in one transaction
+    ///
+    /// Read markers on the message move back to the previous message of the
+    /// session, so deleting it does not mark the whole session unread.
+    async fn delete_message(&self, message: &ChatMessage) -> RepositoryResult<()>;
+
+    /// Store the content and `redacted_at` of a redacted message
+    async fn redact_message(&self, message: &ChatMessage) -> RepositoryResult<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(
+            MessageDeletionPolicy::parse("hard"),
+            Ok(MessageDeletionPolicy::Hard)
+        );
+        assert_eq!(
+            MessageDeletionPolicy::parse(" Redact "),
+            Ok(MessageDeletionPolicy::Redact)
+        );
+        assert!(MessageDeletionPolicy::parse("archive").is_err());
+    }
+}
@@ -23,6 +23,9 @@ pub struct ChatSession {
     pub updated_at: DateTime<Utc>,
     /// Soft delete timestamp
     pub deleted_at: Option<DateTime<Utc>>,
+    /// Number of updates so far; an update based on an older version is
+    /// rejected (optimistic locking)
+    pub version: i32,
 }
 
 impl ChatSession {
@@ -42,6 +45,7 @@ impl ChatSession {
             created_at: now,
             updated_at: now,
             deleted_at: None,
+            version: 0,
         })
     }
 
@@ -58,7 +62,7 @@ impl ChatSession {
 
     /// Check if session is deleted (soft delete)
     #[must_use]
-    pub fn is_deleted(&self) -> bool {
+    pub const fn is_deleted(&self) -> bool {
         self.deleted_at.is_some()
     }
 
@@ -95,8 +99,13 @@ pub struct ChatMessage {
     pub token_count: Option<i32>,
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
+    /// Redaction timestamp; the content is then [`REDACTED_CONTENT`]
+    pub redacted_at: Option<DateTime<Utc>>,
 }
 
+/// Content of a redacted message
+pub const REDACTED_CONTENT: &str = "[message deleted]";
+
 impl ChatMessage {
     /// Create a new chat message
     ///
@@ -117,6 +126,7 @@ impl ChatMessage {
             content,
             token_count: None,
             created_at: Utc::now(),
+            redacted_at: None,
         })
     }
 
@@ -136,6 +146,18 @@ impl ChatMessage {
         Ok(message)
     }
 
+    /// Check if the message content was redacted
+    #[must_use]
+    pub const fn is_redacted(&self) -> bool {
+        self.redacted_at.is_some()
+    }
+
+    /// Replace the content with [`REDACTED_CONTENT`], keeping the metadata
+    pub fn redact(&mut self) {
+        self.content = REDACTED_CONTENT.to_string();
+        self.redacted_at = Some(Utc::now());
+    }
+
     /// Validate message content
     fn validate_content(content: &str) -> Result<(), String> {
         if content.is_empty() {
@@ -239,6 +261,22 @@ mod tests {
         assert_eq!(message.token_count, Some(42));
     }
 
+    #[test]
+    fn test_chat_message_redact() {
+        let session_id = Uuid::new_v4();
+        let mut message =
+            ChatMessage::new_with_tokens(session_id, MessageRole::User, "Secret".to_string(), 3)
+                .unwrap();
+
+        assert!(!message.is_redacted());
+
+        message.redact();
+
+        assert!(message.is_redacted());
+        assert_eq!(message.content, REDACTED_CONTENT);
+        assert_eq!(message.token_count, Some(3));
+    }
+
     #[test]
     fn test_chat_message_empty_content() {
         let session_id = Uuid::new_v4();
@@ -0,0 +1,105 @@
+//! Chat lifecycle events
+//!
+//! Use cases publish a [`ChatEvent`] once a change is stored. Side effects
+//! that do not decide the outcome of a request (usage accounting,
+//! notifications, session titles) subscribe to these events instead of being
+//! steps of the use cases, so they can neither delay nor fail a request or a
+//! reply stream.
+
+use uuid::Uuid;
+
+/// How a generated reply ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationOutcome {
+    /// The provider finished the reply and it was saved
+    Completed,
+    /// The provider failed, the reply could not be saved, or the stream
+    /// worker panicked
+    Failed,
+    /// The provider stream stopped without finishing the reply
+    Truncated,
+    /// The client went away before the reply finished
+    Disconnected,
+}
+
+impl GenerationOutcome {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Truncated => "truncated",
+            Self::Disconnected => "disconnected",
+        }
+    }
+}
+
+/// An assistant reply that stopped streaming, complete or not
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationCompleted {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub model: String,
+    pub outcome: GenerationOutcome,
+    /// Saved assistant message, `None` if nothing was received or saving
+    /// failed
+    pub message_id: Option<Uuid>,
+    /// Tokens of the context sent to the model
+    pub prompt_tokens: i32,
+    /// Tokens of the saved reply, if counted
+    pub completion_tokens: Option<i32>,
+    /// Milliseconds from the request to the first reply chunk, `None` if no
+    /// chunk arrived
+    pub latency_ms: Option<i32>,
+}
+
+/// Something that happened to a chat session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatEvent {
+    SessionCreated {
+        session_id: Uuid,
+        user_id: Uuid,
+    },
+    /// A user message was saved and a reply requested
+    MessageSent {
+        session_id: Uuid,
+        user_id: Uuid,
+        message_id: Uuid,
+        content: String,
+    },
+    GenerationCompleted(GenerationCompleted),
+    /// A message was deleted, or redacted if `redacted`
+    MessageDeleted {
+        session_id: Uuid,
+        user_id: Uuid,
+        message_id: Uuid,
+        redacted: bool,
+    },
+    /// The session was soft-deleted
+    SessionDeleted {
+        session_id: Uuid,
+        user_id: Uuid,
+    },
+}
+
+impl ChatEvent {
+    /// Short name used in logs
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::SessionCreated { .. } => "session_created",
+            Self::MessageSent { .. } => "message_sent",
+            Self::GenerationCompleted(_) => "generation_completed",
+            Self::MessageDeleted { .. } => "message_deleted",
+            Self::SessionDeleted { .. } => "session_deleted",
+        }
+    }
+}
+
+/// Hands events to their subscribers
+///
+/// Publishing must not block: implementations queue the event and return.
+pub trait EventPublisher: Send + Sync {
+    /// Publish an event that already happened
+    fn publish(&self, event: ChatEvent);
+}
@@ -0,0 +1,28 @@
+//! Bulk message persistence
+//!
+//! Clients can seed a session with an existing conversation (for example one
+//! exported from another tool) before asking for a reply. The messages of one
+//! import are stored together or not at all. A new session can likewise be
+//! created together with its first message.
+
+use async_trait::async_trait;
+
+use super::entity::{ChatMessage, ChatSession};
+use super::repository::RepositoryResult;
+
+/// Persistence of imported messages
+#[async_trait]
+pub trait MessageImportRepository: Send + Sync {
+    /// Save `messages` in one transaction, in order
+    async fn save_messages(&self, messages: &[ChatMessage]) -> RepositoryResult<()>;
+
+    /// Create `session` and save `messages` in one transaction
+    ///
+    /// Nothing is stored if any write fails, so a failed request leaves no
+    /// empty session behind.
+    async fn create_session_with_messages(
+        &self,
+        session: &ChatSession,
+        messages: &[ChatMessage],
+    ) -> RepositoryResult<()>;
+}
@@ -0,0 +1,304 @@
+//! Batch completion jobs
+//!
+//! A job sends many independent prompts to one model. Each prompt is a
+//! single-turn completion without session context, so jobs suit evaluation
+//! and bulk generation rather than conversations. Jobs are queued, run by
+//! background workers and kept with their results, so clients poll the
+//! status and fetch the outputs once done.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::repository::{RepositoryError, RepositoryResult};
+
+/// Lifecycle of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    /// Waiting for a worker
+    Queued,
+    /// Prompts are being sent to the provider
+    Running,
+    /// Every prompt finished; some may have failed
+    Completed,
+    /// The job could not run at all (e.g. its model was removed)
+    Failed,
+}
+
+impl JobStatus {
+    /// Convert to the stored string
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Parse a stored status
+    ///
+    /// # Errors
+    /// Returns an error if the status is unknown
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "queued" => Ok(Self::Queued),
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            _ => Err(format!("Invalid job status: {s}")),
+        }
+    }
+}
+
+/// Outcome of one prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobItemStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+impl JobItemStatus {
+    /// Convert to the stored string
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Parse a stored status
+    ///
+    /// # Errors
+    /// Returns an error if the status is unknown
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            _ => Err(format!("Invalid job item status: {s}")),
+        }
+    }
+}
+
+/// Batch of prompts for one model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatJob {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub model: String,
+    pub status: JobStatus,
+    pub total_items: u32,
+    pub completed_items: u32,
+    pub failed_items: u32,
+    /// Prompt tokens of the answered prompts
+    pub prompt_tokens: u64,
+    /// Completion tokens of the answered prompts
+    pub completion_tokens: u64,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ChatJob {
+    /// Queue `prompts` for `model`, returning the job and its items
+    ///
+    /// # Errors
+    /// Returns an error if there are no prompts or one is blank
+    pub fn new(
+        user_id: Uuid,
+        model: impl Into<String>,
+        prompts: Vec<String>,
+    ) -> Result<(Self, Vec<ChatJobItem>), String> {
+        if prompts.is_empty() {
+            return Err("A job needs at least one prompt".to_string());
+        }
+        if let Some(position) = prompts.iter().position(|p| p.trim().is_empty()) {
+            return Err(format!("Prompt {position} is empty"));
+        }
+
+        let job = Self {
+            id: Uuid::new_v4(),
+            user_id,
+            model: model.into(),
+            status: JobStatus::Queued,
+            total_items: u32::try_from(prompts.len()).unwrap_or(u32::MAX),
+            completed_items: 0,
+            failed_items: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        };
+        let items = prompts
+            .into_iter()
+            .zip(0..)
+            .map(|(prompt, position)| ChatJobItem {
+                id: Uuid::new_v4(),
+                job_id: job.id,
+                position,
+                prompt,
+                status: JobItemStatus::Pending,
+                output: None,
+                error: None,
+                prompt_tokens: None,
+                completion_tokens: None,
+                finished_at: None,
+            })
+            .collect();
+        Ok((job, items))
+    }
+
+    /// Whether workers are done with the job
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        matches!(self.status, JobStatus::Completed | JobStatus::Failed)
+    }
+}
+
+/// One prompt of a job and its result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatJobItem {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    /// Index in the submitted prompt list
+    pub position: u32,
+    pub prompt: String,
+    pub status: JobItemStatus,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ChatJobItem {
+    /// Record the model's reply and the token counts
+    pub fn complete(&mut self, output: String, prompt_tokens: u32, completion_tokens: u32) {
+        self.status = JobItemStatus::Completed;
+        self.output = Some(output);
+        self.prompt_tokens = Some(prompt_tokens);
+        self.completion_tokens = Some(completion_tokens);
+        self.finished_at = Some(Utc::now());
+    }
+
+    /// Record why the prompt could not be answered
+    pub fn fail(&mut self, error: String) {
+        self.status = JobItemStatus::Failed;
+        self.error = Some(error);
+        self.finished_at = Some(Utc::now());
+    }
+}
+
+/// Job persistence, also the queue workers take jobs from
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    /// Save a new job with all its items
+    async fn create_job(&self, job: &ChatJob, items: &[ChatJobItem]) -> RepositoryResult<()>;
+
+    /// Find a job by ID
+    ///
+    /// Not scoped to a user: for the job runner. Code acting for a user goes
+    /// through [`find_job_for_user`](Self::find_job_for_user).
+    async fn find_job(&self, id: Uuid) -> RepositoryResult<Option<ChatJob>>;
+
+    /// Find a job of `user_id`
+    ///
+    /// # Errors
+    /// Returns `JobNotFound` if the job does not exist or belongs to another
+    /// user, so a job id of someone else cannot be told from an unknown one
+    async fn find_job_for_user(&self, id: Uuid, user_id: Uuid) -> RepositoryResult<ChatJob> {
+        self.find_job(id)
+            .await?
+            .filter(|job| job.user_id == user_id)
+            .ok_or(RepositoryError::JobNotFound(id))
+    }
+
+    /// Number of the user's jobs that are queued or running
+    async fn count_active_jobs(&self, user_id: Uuid) -> RepositoryResult<u64>;
+
+    /// IDs of queued jobs, oldest first
+    async fn find_queued_jobs(&self, limit: u64) -> RepositoryResult<Vec<Uuid>>;
+
+    /// Move a job from queued to running
+    ///
+    /// Returns `false` if the job was not queued (another worker took it).
+    async fn claim_job(&self, id: Uuid) -> RepositoryResult<bool>;
+
+    /// Put jobs left running (e.g. by a crashed instance) back in the queue
+    ///
+    /// Their pending items are sent again; finished items are kept.
+    async fn requeue_running_jobs(&self) -> RepositoryResult<u64>;
+
+    /// Items of a job still waiting for a reply, in prompt order
+    async fn find_pending_items(&self, job_id: Uuid) -> RepositoryResult<Vec<ChatJobItem>>;
+
+    /// Store the result of a finished item and add it to the job's counters
+    ///
+    /// An item that is no longer pending is left unchanged.
+    async fn finish_item(&self, item: &ChatJobItem) -> RepositoryResult<()>;
+
+    /// Set the final status of a job
+    async fn finish_job(&self, id: Uuid, status: JobStatus) -> RepositoryResult<()>;
+
+    /// Page (0-based) of a job's items in prompt order, with the total item
+    /// count
+    async fn find_job_items(
+        &self,
+        job_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> RepositoryResult<(Vec<ChatJobItem>, u64)>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_job_queues_items_in_order() {
+        let user_id = Uuid::new_v4();
+        let (job, items) =
+            ChatJob::new(user_id, "llama", vec!["first".into(), "second".into()]).unwrap();
+
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.total_items, 2);
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| (item.position, item.prompt.as_str(), item.job_id))
+                .collect::<Vec<_>>(),
+            vec![(0, "first", job.id), (1, "second", job.id)]
+        );
+        assert!(items
+            .iter()
+            .all(|item| item.status == JobItemStatus::Pending));
+    }
+
+    #[test]
+    fn test_new_job_rejects_empty_prompts() {
+        assert!(ChatJob::new(Uuid::new_v4(), "llama", vec![]).is_err());
+        assert_eq!(
+            ChatJob::new(Uuid::new_v4(), "llama", vec!["ok".into(), " ".into()]).unwrap_err(),
+            "Prompt 1 is empty"
+        );
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            JobStatus::Queued,
+            JobStatus::Running,
+            JobStatus::Completed,
+            JobStatus::Failed,
+        ] {
+            assert_eq!(JobStatus::parse(status.as_str()), Ok(status));
+        }
+        assert!(JobStatus::parse("paused").is_err());
+    }
+}
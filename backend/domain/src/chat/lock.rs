@@ -0,0 +1,92 @@
+//! Conversation lock
+//!
+//! Serializes message generation per chat session. Without it, two concurrent
+//! sends on one session each load the other's user message into their context
+//! and the saved history interleaves two replies.
+//!
+//! Infrastructure provides the [`SessionLock`] implementations; the guard is
+//! held for the whole generation, including the streamed response.
+
+use async_trait::async_trait;
+use std::{fmt, time::Duration};
+use uuid::Uuid;
+
+use super::repository::{RepositoryError, RepositoryResult};
+
+/// Interval between attempts while waiting for a busy session
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What to do when a session already has a generation in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockPolicy {
+    /// Fail immediately with [`RepositoryError::GenerationInProgress`]
+    Reject,
+    /// Wait up to the given duration for the running generation to finish
+    Wait(Duration),
+}
+
+/// Proof of holding a session lock; releases it when dropped
+pub struct SessionLockGuard {
+    release: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl SessionLockGuard {
+    /// Create a guard that runs `release` exactly once when dropped
+    #[must_use]
+    pub fn new(release: impl FnOnce() + Send + Sync + 'static) -> Self {
+        Self {
+            release: Some(Box::new(release)),
+        }
+    }
+}
+
+impl Drop for SessionLockGuard {
+    fn drop(&mut self) {
+        if let Some(release) = self.release.take() {
+            release();
+        }
+    }
+}
+
+impl fmt::Debug for SessionLockGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionLockGuard").finish_non_exhaustive()
+    }
+}
+
+/// Per-session mutual exclusion for message generation
+#[async_trait]
+pub trait SessionLock: Send + Sync {
+    /// Take the lock without waiting
+    ///
+    /// Returns `None` if another generation holds the lock.
+    async fn try_acquire(&self, session_id: Uuid) -> RepositoryResult<Option<SessionLockGuard>>;
+
+    /// Take the lock according to `policy`
+    ///
+    /// # Errors
+    /// Returns `RepositoryError::GenerationInProgress` if the lock is still
+    /// held when the policy gives up, or the backend's error.
+    async fn acquire(
+        &self,
+        session_id: Uuid,
+        policy: LockPolicy,
+    ) -> RepositoryResult<SessionLockGuard> {
+        let deadline = match policy {
+            LockPolicy::Reject => None,
+            LockPolicy::Wait(timeout) => Some(tokio::time::Instant::now() + timeout),
+        };
+
+        loop {
+            if let Some(guard) = self.try_acquire(session_id).await? {
+                return Ok(guard);
+            }
+            match deadline {
+                Some(deadline) if tokio::time::Instant::now() < deadline => {
+                    tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+                }
+                _ => return Err(RepositoryError::GenerationInProgress(session_id)),
+            }
+        }
+    }
+}
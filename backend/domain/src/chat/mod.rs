@@ -0,0 +1,44 @@
+//! Chat domain module
+//!
+//! Contains entities, value objects, repository traits, content limits, the
+//! conversation lock, message annotations, message deletion, share links,
+//! message imports, usage records, batch jobs, lifecycle events,
+//! disabled-account restrictions, per-user chat defaults, completion
+//! webhooks and prompt size limits for chat functionality.
+//! Pure business logic with no infrastructure dependencies.
+
+pub mod annotation;
+pub mod deletion;
+pub mod entity;
+pub mod events;
+pub mod import;
+pub mod job;
+pub mod lock;
+pub mod policy;
+pub mod preferences;
+pub mod prompt_budget;
+pub mod read_state;
+pub mod repository;
+pub mod share;
+pub mod suspension;
+pub mod usage;
+pub mod value_objects;
+pub mod webhook;
+
+pub use annotation::{
+    Annotation, AnnotationFilter, AnnotationKind, AnnotationRepository, MessageAnnotation,
+};
+pub use deletion::{MessageDeletionPolicy, MessageDeletionRepository};
+pub use entity::{ChatMessage, ChatSession};
+pub use import::MessageImportRepository;
+pub use lock::{LockPolicy, SessionLock, SessionLockGuard};
+pub use policy::ChatPolicy;
+pub use preferences::{ChatDefaults, ChatDefaultsRepository};
+pub use prompt_budget::PromptOverflow;
+pub use read_state::{ReadState, ReadStateRepository};
+pub use repository::{ChatRepository, RepositoryError, RepositoryResult};
+pub use share::{ChatShare, SharePasswordAttempts, ShareRepository, ShareSigner};
+pub use suspension::SuspensionRepository;
+pub use usage::{UsageRecord, UsageRepository};
+pub use value_objects::MessageRole;
+pub use webhook::{ChatWebhook, WebhookDelivery, WebhookRepository};
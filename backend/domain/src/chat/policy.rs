@@ -0,0 +1,92 @@
+//! Limits on client-written chat content
+//!
+//! [`ChatPolicy`] holds the deployment's limits (see `ChatConfig` in the
+//! backend crate) and checks content against them before it is stored. The entity checks in
+//! [`ChatMessage`](super::entity::ChatMessage) still apply on top.
+
+use super::value_objects::MessageRole;
+
+/// Content limits for messages written by clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChatPolicy {
+    /// Maximum characters per message
+    pub max_message_length: usize,
+    /// Maximum messages per bulk import
+    pub max_import_messages: usize,
+}
+
+impl ChatPolicy {
+    /// Check one client-written message
+    ///
+    /// System messages steer the model, so clients may not write them.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the role is `system` or the content is blank or too
+    /// long
+    pub fn validate_message(&self, role: MessageRole, content: &str) -> Result<(), String> {
+        if role == MessageRole::System {
+            return Err("System messages cannot be written by clients".to_string());
+        }
+        if content.trim().is_empty() {
+            return Err("Message content cannot be empty".to_string());
+        }
+        if content.chars().count() > self.max_message_length {
+            return Err(format!(
+                "Message content cannot exceed {} characters",
+                self.max_message_length
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the size of a bulk import
+    ///
+    /// # Errors
+    ///
+    /// Returns error if there are no messages or more than
+    /// `max_import_messages`
+    pub fn validate_import_size(&self, count: usize) -> Result<(), String> {
+        if count == 0 {
+            return Err("At least one message is required".to_string());
+        }
+        if count > self.max_import_messages {
+            return Err(format!(
+                "Cannot import more than {} messages at once",
+                self.max_import_messages
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: ChatPolicy = ChatPolicy {
+        max_message_length: 5,
+        max_import_messages: 2,
+    };
+
+    #[test]
+    fn test_validate_message() {
+        assert!(POLICY.validate_message(MessageRole::User, "hello").is_ok());
+        assert!(POLICY
+            .validate_message(MessageRole::Assistant, "héllo")
+            .is_ok());
+        assert!(POLICY
+            .validate_message(MessageRole::User, "hello!")
+            .is_err());
+        assert!(POLICY.validate_message(MessageRole::User, "  ").is_err());
+        assert!(POLICY.validate_message(MessageRole::System, "hi").is_err());
+    }
+
+    #[test]
+    fn test_validate_import_size() {
+        assert!(POLICY.validate_import_size(1).is_ok());
+        assert!(POLICY.validate_import_size(2).is_ok());
+        assert!(POLICY.validate_import_size(0).is_err());
+        assert!(POLICY.validate_import_size(3).is_err());
+    }
+}
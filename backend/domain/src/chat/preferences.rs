@@ -0,0 +1,56 @@
+//! Per-user chat defaults
+//!
+//! Users can keep a default model, sampling temperature and persona in their
+//! preferences. When a send-message request leaves the model or temperature
+//! out, the stored default is used; values on the request always win. The
+//! persona is sent as a system message ahead of the conversation.
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::repository::RepositoryResult;
+
+/// Highest accepted sampling temperature
+pub const MAX_TEMPERATURE: f32 = 2.0;
+
+/// Maximum persona length in characters
+pub const MAX_PERSONA_LENGTH: usize = 1000;
+
+/// Whether `temperature` is within `0.0..=MAX_TEMPERATURE`
+#[must_use]
+pub fn is_valid_temperature(temperature: f32) -> bool {
+    (0.0..=MAX_TEMPERATURE).contains(&temperature)
+}
+
+/// A user's stored chat defaults
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChatDefaults {
+    /// Model used when the request names none
+    pub model: Option<String>,
+    /// Sampling temperature used when the request sets none
+    pub temperature: Option<f32>,
+    /// System prompt sent ahead of the conversation
+    pub persona: Option<String>,
+}
+
+/// Source of users' chat defaults
+#[async_trait]
+pub trait ChatDefaultsRepository: Send + Sync {
+    /// The user's chat defaults (all `None` when nothing is stored)
+    async fn chat_defaults(&self, user_id: Uuid) -> RepositoryResult<ChatDefaults>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temperature_range() {
+        assert!(is_valid_temperature(0.0));
+        assert!(is_valid_temperature(0.7));
+        assert!(is_valid_temperature(MAX_TEMPERATURE));
+        assert!(!is_valid_temperature(-0.1));
+        assert!(!is_valid_temperature(2.5));
+        assert!(!is_valid_temperature(f32::NAN));
+    }
+}
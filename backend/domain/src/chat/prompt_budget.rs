@@ -0,0 +1,76 @@
+//! Whether a prompt fits a model's context window
+//!
+//! The context window has to hold the prompt and the reply, so a request is
+//! refused up front when the prompt leaves less room than the tokens reserved
+//! for the reply, instead of failing at the provider.
+
+/// A prompt too large for the model's context window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "Prompt of {prompt_tokens} tokens plus {max_output_tokens} reserved for the reply exceeds \
+     the {context_window}-token context window"
+)]
+pub struct PromptOverflow {
+    /// Estimated tokens of the prompt
+    pub prompt_tokens: u32,
+    /// Tokens reserved for the reply
+    pub max_output_tokens: u32,
+    /// Tokens the model accepts in total
+    pub context_window: u32,
+}
+
+impl PromptOverflow {
+    /// Tokens to remove from the prompt for the request to fit
+    #[must_use]
+    pub const fn excess_tokens(&self) -> u32 {
+        self.prompt_tokens
+            .saturating_add(self.max_output_tokens)
+            .saturating_sub(self.context_window)
+    }
+}
+
+/// Check that `prompt_tokens` and `max_output_tokens` fit `context_window`
+///
+/// # Errors
+/// Returns the overflow if they do not
+pub const fn check_prompt_fits(
+    prompt_tokens: u32,
+    max_output_tokens: u32,
+    context_window: u32,
+) -> Result<(), PromptOverflow> {
+    let overflow = PromptOverflow {
+        prompt_tokens,
+        max_output_tokens,
+        context_window,
+    };
+    if overflow.excess_tokens() > 0 {
+        return Err(overflow);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_prompt_fits() {
+        assert!(check_prompt_fits(4096, 4096, 8192).is_ok());
+
+        let overflow = check_prompt_fits(5000, 4096, 8192).unwrap_err();
+        assert_eq!(overflow.excess_tokens(), 904);
+        assert_eq!(
+            overflow.to_string(),
+            "Prompt of 5000 tokens plus 4096 reserved for the reply exceeds the 8192-token \
+             context window"
+        );
+
+        // A reply larger than the window never fits
+        assert_eq!(
+            check_prompt_fits(0, 10_000, 8192)
+                .unwrap_err()
+                .excess_tokens(),
+            1808
+        );
+    }
+}
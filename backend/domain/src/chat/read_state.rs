@@ -0,0 +1,53 @@
+//! Read state repository trait
+//!
+//! Tracks the last message each user has read per session so clients can
+//! show unread counts. Messages created after the read position are unread;
+//! a session without a read position is entirely unread.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::repository::RepositoryResult;
+
+/// A user's read position in one session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadState {
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+    pub last_read_message_id: Uuid,
+    /// Creation time of the last read message
+    pub last_read_at: DateTime<Utc>,
+}
+
+/// Read state persistence
+#[async_trait]
+pub trait ReadStateRepository: Send + Sync {
+    /// Find a user's read position in a session
+    async fn find_read_state(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> RepositoryResult<Option<ReadState>>;
+
+    /// Move the read position to `message_id`
+    ///
+    /// The position only moves forward: marking an older message read is a
+    /// no-op. Returns `MessageNotFound` if the message is not in the session.
+    async fn mark_read(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> RepositoryResult<()>;
+
+    /// Count unread messages per session
+    ///
+    /// Sessions without unread messages may be missing from the map.
+    async fn count_unread(
+        &self,
+        user_id: Uuid,
+        session_ids: &[Uuid],
+    ) -> RepositoryResult<HashMap<Uuid, u64>>;
+}
@@ -0,0 +1,171 @@
+//! Chat repository trait
+//!
+//! Domain interface for chat data persistence.
+//! Infrastructure layer will implement these traits.
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::entity::{ChatMessage, ChatSession};
+use super::prompt_budget::PromptOverflow;
+
+/// Result type for repository operations
+pub type RepositoryResult<T> = Result<T, RepositoryError>;
+
+/// Repository errors
+#[derive(Debug, thiserror::Error)]
+pub enum RepositoryError {
+    /// Session not found
+    #[error("Session not found: {0}")]
+    SessionNotFound(Uuid),
+
+    /// Message not found
+    #[error("Message not found: {0}")]
+    MessageNotFound(Uuid),
+
+    /// Annotation not found on the message
+    #[error("Annotation not found: {0}")]
+    AnnotationNotFound(Uuid),
+
+    /// Batch job not found (or owned by another user)
+    #[error("Job not found: {0}")]
+    JobNotFound(Uuid),
+
+    /// The user already has as many unfinished jobs as allowed
+    #[error("At most {0} unfinished jobs are allowed")]
+    JobLimitReached(u64),
+
+    /// Webhook not found (or owned by another user)
+    #[error("Webhook not found: {0}")]
+    WebhookNotFound(Uuid),
+
+    /// The user already has as many webhooks as allowed
+    #[error("At most {0} webhooks are allowed")]
+    WebhookLimitReached(usize),
+
+    /// Database error
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    /// Validation error
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+
+    /// The prompt and the reply do not fit the model's context window
+    #[error("{0}")]
+    PromptTooLarge(PromptOverflow),
+
+    /// The model's provider is not configured, unreachable or failed
+    #[error("Provider unavailable: {0}")]
+    ProviderUnavailable(String),
+
+    /// Another message is still being generated for the session
+    #[error("Generation already in progress for session {0}")]
+    GenerationInProgress(Uuid),
+
+    /// Share link not found or its slug signature is invalid
+    #[error("Share not found")]
+    ShareNotFound,
+
+    /// Share link was revoked or has expired
+    #[error("Share link is no longer available")]
+    ShareUnavailable,
+
+    /// Share link requires a password that was missing or wrong
+    #[error("Share password missing or incorrect")]
+    InvalidSharePassword,
+
+    /// The viewer entered too many wrong passwords for the share
+    #[error("Too many incorrect share passwords, try again later")]
+    SharePasswordLocked,
+
+    /// The user's account is disabled, so chat generation is blocked
+    #[error("Account is disabled")]
+    AccountDisabled,
+
+    /// The session changed since the version an update was based on
+    #[error("Session {0} was modified by another request")]
+    SessionConflict(Uuid),
+}
+
+/// Chat repository trait for session and message persistence
+#[async_trait]
+pub trait ChatRepository: Send + Sync {
+    /// Create a new chat session
+    async fn create_session(&self, session: &ChatSession) -> RepositoryResult<()>;
+
+    /// Find session by ID, including soft-deleted sessions
+    ///
+    /// Not scoped to a user: only for background work and share links. Code
+    /// acting for a user goes through
+    /// [`find_active_session_for_user`](Self::find_active_session_for_user).
+    async fn find_session_by_id(&self, id: Uuid) -> RepositoryResult<Option<ChatSession>>;
+
+    /// Find a session that is not deleted and belongs to `user_id`
+    ///
+    /// Every use case acting on a user's session goes through this, so a
+    /// deleted session is reported missing everywhere.
+    ///
+    /// # Errors
+    /// Returns `SessionNotFound` if the session does not exist or is deleted,
+    /// and `ValidationError` if it belongs to another user
+    async fn find_active_session_for_user(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<ChatSession> {
+        let session = self
+            .find_session_by_id(session_id)
+            .await?
+            .filter(|session| !session.is_deleted())
+            .ok_or(RepositoryError::SessionNotFound(session_id))?;
+
+        if session.user_id != user_id {
+            return Err(RepositoryError::ValidationError(
+                "User not authorized for this session".to_string(),
+            ));
+        }
+        Ok(session)
+    }
+
+    /// Find all sessions for a user (excluding deleted)
+    async fn find_sessions_by_user(
+        &self,
+        user_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> RepositoryResult<(Vec<ChatSession>, u64)>;
+
+    /// Update a session's title
+    ///
+    /// Only succeeds if the stored session is still at `session.version`
+    /// (which is then incremented); otherwise fails with `SessionConflict`.
+    async fn update_session(&self, session: &ChatSession) -> RepositoryResult<()>;
+
+    /// Soft delete a session of `user_id`
+    ///
+    /// # Errors
+    /// Returns `SessionNotFound` if no session `id` of `user_id` exists that
+    /// is not deleted yet, so another user's session is never touched
+    async fn delete_session(&self, id: Uuid, user_id: Uuid) -> RepositoryResult<()>;
+
+    /// Save a message
+    async fn save_message(&self, message: &ChatMessage) -> RepositoryResult<()>;
+
+    /// Find messages for a session
+    async fn find_messages_by_session(
+        &self,
+        session_id: Uuid,
+        limit: Option<u64>,
+    ) -> RepositoryResult<Vec<ChatMessage>>;
+
+    /// Find recent messages for context building
+    ///
+    /// Redacted messages are left out: their content is a placeholder the
+    /// user took back.
+    async fn find_recent_messages(
+        &self,
+        session_id: Uuid,
+        limit: u64,
+    ) -> RepositoryResult<Vec<ChatMessage>>;
+}
@@ -0,0 +1,222 @@
+//! Public share links
+//!
+//! A share lets anyone holding its slug read a session without
+//! authenticating. Slugs are a random token plus an HMAC-SHA256 signature,
+//! so forged or mistyped slugs are rejected without a database lookup.
+//!
+//! Wrong passwords for a protected share are counted per slug and viewer
+//! (see [`SharePasswordAttempts`]); after [`MAX_SHARE_PASSWORD_FAILURES`] the
+//! viewer is locked out of that share for [`SHARE_PASSWORD_LOCKOUT`].
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::{fmt, time::Duration};
+use uuid::Uuid;
+
+use super::repository::RepositoryResult;
+use crate::token::{constant_time_eq, TokenGenerator};
+
+/// Random part of a slug: 16 bytes as hex
+const SLUG_TOKEN: TokenGenerator = TokenGenerator::new(16);
+
+/// Hex characters of the signature appended to the token (128 bits)
+const SLUG_SIGNATURE_LEN: usize = 32;
+
+/// Wrong passwords a viewer may try on one share before being locked out
+pub const MAX_SHARE_PASSWORD_FAILURES: u32 = 5;
+
+/// How long failures are remembered, counted from the first one
+pub const SHARE_PASSWORD_LOCKOUT: Duration = Duration::from_secs(15 * 60);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Public read-only link to a chat session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatShare {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    /// Session owner who created the share
+    pub user_id: Uuid,
+    pub slug: String,
+    /// Argon2 hash of the viewing password, if one is required
+    pub password_hash: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Set while the owner's account is disabled
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub view_count: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ChatShare {
+    /// Create a share for `session_id`
+    #[must_use]
+    pub fn new(
+        session_id: Uuid,
+        user_id: Uuid,
+        slug: String,
+        password_hash: Option<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            session_id,
+            user_id,
+            slug,
+            password_hash,
+            expires_at,
+            revoked_at: None,
+            suspended_at: None,
+            view_count: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Check whether the share can be viewed at `now`
+    #[must_use]
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none()
+            && self.suspended_at.is_none()
+            && self.expires_at.map_or(true, |expires_at| now < expires_at)
+    }
+
+    /// Check whether viewers must supply a password
+    #[must_use]
+    pub const fn requires_password(&self) -> bool {
+        self.password_hash.is_some()
+    }
+}
+
+/// Generates and verifies signed share slugs
+#[derive(Clone)]
+pub struct ShareSigner {
+    secret: String,
+}
+
+impl ShareSigner {
+    /// Create a signer keyed with `secret`
+    #[must_use]
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    /// Generate a new random signed slug
+    #[must_use]
+    pub fn generate_slug(&self) -> String {
+        let token = SLUG_TOKEN.generate();
+        let signature = self.sign(&token);
+        format!("{token}{signature}")
+    }
+
+    /// Check that `slug` was produced by [`Self::generate_slug`] with this secret
+    #[must_use]
+    pub fn verify_slug(&self, slug: &str) -> bool {
+        let token_len = SLUG_TOKEN.encoded_len();
+        if slug.len() != token_len + SLUG_SIGNATURE_LEN || !slug.is_ascii() {
+            return false;
+        }
+        let (token, signature) = slug.split_at(token_len);
+        constant_time_eq(self.sign(token).as_bytes(), signature.as_bytes())
+    }
+
+    fn sign(&self, token: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(token.as_bytes());
+        let mut signature = hex::encode(mac.finalize().into_bytes());
+        signature.truncate(SLUG_SIGNATURE_LEN);
+        signature
+    }
+}
+
+impl fmt::Debug for ShareSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShareSigner").finish_non_exhaustive()
+    }
+}
+
+/// Share link persistence
+#[async_trait]
+pub trait ShareRepository: Send + Sync {
+    /// Save a new share
+    async fn create_share(&self, share: &ChatShare) -> RepositoryResult<()>;
+
+    /// Find a share by its public slug
+    async fn find_share_by_slug(&self, slug: &str) -> RepositoryResult<Option<ChatShare>>;
+
+    /// List all shares of a session, newest first
+    async fn find_shares_by_session(&self, session_id: Uuid) -> RepositoryResult<Vec<ChatShare>>;
+
+    /// Mark a share as revoked
+    ///
+    /// Returns `ShareNotFound` if the share does not belong to the session.
+    async fn revoke_share(&self, session_id: Uuid, share_id: Uuid) -> RepositoryResult<()>;
+
+    /// Count one public view
+    async fn increment_share_views(&self, share_id: Uuid) -> RepositoryResult<()>;
+}
+
+/// Wrong share passwords, counted per slug and viewer
+///
+/// Infrastructure provides the implementations. `viewer` identifies the
+/// client by its IP address; clients without one are not let try passwords.
+#[async_trait]
+pub trait SharePasswordAttempts: Send + Sync {
+    /// Failures of `viewer` on `slug` within [`SHARE_PASSWORD_LOCKOUT`]
+    async fn failures(&self, slug: &str, viewer: &str) -> RepositoryResult<u32>;
+
+    /// Count one failure of `viewer` on `slug`
+    async fn record_failure(&self, slug: &str, viewer: &str) -> RepositoryResult<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_generated_slug_verifies() {
+        let signer = ShareSigner::new("secret");
+        let slug = signer.generate_slug();
+
+        assert_eq!(slug.len(), 64);
+        assert!(signer.verify_slug(&slug));
+        assert_ne!(slug, signer.generate_slug());
+    }
+
+    #[test]
+    fn test_tampered_slug_rejected() {
+        let signer = ShareSigner::new("secret");
+        let slug = signer.generate_slug();
+
+        let mut tampered = slug.clone().into_bytes();
+        tampered[0] = if tampered[0] == b'a' { b'b' } else { b'a' };
+        assert!(!signer.verify_slug(&String::from_utf8(tampered).unwrap()));
+        assert!(!ShareSigner::new("other").verify_slug(&slug));
+        assert!(!signer.verify_slug(&slug[..48]));
+        assert!(!signer.verify_slug(""));
+    }
+
+    #[test]
+    fn test_share_is_active() {
+        let now = Utc::now();
+        let mut share = ChatShare::new(Uuid::new_v4(), Uuid::new_v4(), "slug".into(), None, None);
+        assert!(share.is_active(now));
+
+        share.expires_at = Some(now + Duration::hours(1));
+        assert!(share.is_active(now));
+        assert!(!share.is_active(now + Duration::hours(2)));
+
+        share.expires_at = None;
+        share.revoked_at = Some(now);
+        assert!(!share.is_active(now));
+
+        share.revoked_at = None;
+        share.suspended_at = Some(now);
+        assert!(!share.is_active(now));
+    }
+}
@@ -0,0 +1,35 @@
+//! Chat restrictions for disabled accounts
+//!
+//! While an admin has disabled a user, the user's share links are suspended
+//! and no new replies are generated for them. Sessions of a user who stays
+//! disabled past a grace period can also be archived. Unlike a revocation by
+//! the owner, all of this is lifted when the account is enabled again.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::repository::RepositoryResult;
+
+/// Persistence of account-disable restrictions
+#[async_trait]
+pub trait SuspensionRepository: Send + Sync {
+    /// Whether the user's account is currently disabled
+    async fn is_account_disabled(&self, user_id: Uuid) -> RepositoryResult<bool>;
+
+    /// Suspend the user's shares that are not suspended yet, returning how many
+    async fn suspend_shares(&self, user_id: Uuid) -> RepositoryResult<u64>;
+
+    /// Lift the suspension of the user's shares, returning how many
+    async fn restore_shares(&self, user_id: Uuid) -> RepositoryResult<u64>;
+
+    /// Archive the sessions of users disabled since before `cutoff`,
+    /// returning how many
+    async fn archive_sessions_disabled_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> RepositoryResult<u64>;
+
+    /// Unarchive the user's sessions, returning how many
+    async fn unarchive_sessions(&self, user_id: Uuid) -> RepositoryResult<u64>;
+}
@@ -0,0 +1,112 @@
+//! Chat usage records and per-user aggregates
+//!
+//! One [`UsageRecord`] is written per assistant reply. Analytics never load
+//! the records themselves; [`UsageRepository`] returns aggregates computed by
+//! the database over a user's records since a point in time.
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use super::repository::RepositoryResult;
+
+/// Usage of one assistant reply
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageRecord {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+    pub model: String,
+    /// Tokens of the context sent to the model
+    pub prompt_tokens: i32,
+    /// Tokens of the reply
+    pub completion_tokens: i32,
+    /// Milliseconds from the request to the first reply chunk
+    pub latency_ms: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UsageRecord {
+    /// Create a record for a reply finished now
+    #[must_use]
+    pub fn new(
+        user_id: Uuid,
+        session_id: Uuid,
+        model: String,
+        prompt_tokens: i32,
+        completion_tokens: i32,
+        latency_ms: i32,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            session_id,
+            model,
+            prompt_tokens,
+            completion_tokens,
+            latency_ms,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Messages sent on one UTC day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyMessages {
+    pub date: NaiveDate,
+    pub messages: u64,
+}
+
+/// Token totals of one model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelTokens {
+    pub model: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// Message count of one session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionActivity {
+    pub session_id: Uuid,
+    pub title: String,
+    pub messages: u64,
+}
+
+/// Usage persistence and aggregates
+#[async_trait]
+pub trait UsageRepository: Send + Sync {
+    /// Save the usage of a reply
+    async fn record_usage(&self, record: &UsageRecord) -> RepositoryResult<()>;
+
+    /// Messages per UTC day since `since`, oldest first (days without
+    /// messages are omitted)
+    async fn daily_messages(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<DailyMessages>>;
+
+    /// Token totals per model since `since`, most tokens first
+    async fn tokens_by_model(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<ModelTokens>>;
+
+    /// Sessions with the most messages since `since`, at most `limit`
+    async fn most_active_sessions(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: u64,
+    ) -> RepositoryResult<Vec<SessionActivity>>;
+
+    /// Mean reply latency in milliseconds since `since` (`None` without
+    /// replies)
+    async fn average_latency_ms(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<Option<f64>>;
+}
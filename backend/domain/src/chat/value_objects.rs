@@ -2,6 +2,8 @@
 //!
 //! Immutable value objects that represent domain concepts.
 
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
 /// Role of a message participant
@@ -19,20 +21,24 @@ pub enum MessageRole {
 impl MessageRole {
     /// Convert role to string representation
     #[must_use]
-    pub fn as_str(&self) -> &'static str {
+    pub const fn as_str(&self) -> &'static str {
         match self {
             Self::User => "user",
             Self::Assistant => "assistant",
             Self::System => "system",
         }
     }
+}
+
+impl FromStr for MessageRole {
+    type Err = String;
 
     /// Parse role from string
     ///
     /// # Errors
     ///
     /// Returns error if the string is not a valid role
-    pub fn from_str(s: &str) -> Result<Self, String> {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "user" => Ok(Self::User),
             "assistant" => Ok(Self::Assistant),
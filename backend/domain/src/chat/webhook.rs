@@ -0,0 +1,126 @@
+//! Completion webhooks
+//!
+//! A webhook is a URL a user registers to be called when one of their chat
+//! replies finishes generating. Each webhook has its own random secret that
+//! signs the deliveries, so the receiver can check they come from us.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::repository::RepositoryResult;
+use crate::token::TokenGenerator;
+
+/// Signing secret of a webhook: `whsec_` and 32 random bytes as hex
+pub const WEBHOOK_SECRET: TokenGenerator = TokenGenerator::new(32).prefix("whsec_");
+
+/// Callback URL of a user, called when their replies complete
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatWebhook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub url: String,
+    /// Key of the HMAC signature of each delivery
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+    /// Time of the last delivery attempt
+    pub last_delivery_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last attempt (`None` if no response was received)
+    pub last_status: Option<u16>,
+    /// Why the last attempt failed, if it did
+    pub last_error: Option<String>,
+}
+
+impl ChatWebhook {
+    /// Create a webhook with a new random secret
+    #[must_use]
+    pub fn new(user_id: Uuid, url: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            url,
+            secret: WEBHOOK_SECRET.generate(),
+            created_at: Utc::now(),
+            last_delivery_at: None,
+            last_status: None,
+            last_error: None,
+        }
+    }
+}
+
+/// Outcome of one delivery attempt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    pub attempted_at: DateTime<Utc>,
+    /// HTTP status of the response (`None` if none was received)
+    pub status: Option<u16>,
+    /// Connection error or unexpected status
+    pub error: Option<String>,
+}
+
+impl WebhookDelivery {
+    /// Check whether the receiver accepted the delivery (2xx)
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.status
+            .is_some_and(|status| (200..300).contains(&status))
+    }
+}
+
+/// Webhook persistence
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    /// Save a new webhook
+    async fn create_webhook(&self, webhook: &ChatWebhook) -> RepositoryResult<()>;
+
+    /// List the webhooks of a user, oldest first
+    async fn find_webhooks_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<ChatWebhook>>;
+
+    /// Find a webhook of a user
+    async fn find_webhook(
+        &self,
+        user_id: Uuid,
+        webhook_id: Uuid,
+    ) -> RepositoryResult<Option<ChatWebhook>>;
+
+    /// Delete a webhook
+    ///
+    /// Returns `WebhookNotFound` if the webhook does not belong to the user.
+    async fn delete_webhook(&self, user_id: Uuid, webhook_id: Uuid) -> RepositoryResult<()>;
+
+    /// Store the outcome of the latest delivery attempt
+    async fn record_delivery(
+        &self,
+        webhook_id: Uuid,
+        delivery: &WebhookDelivery,
+    ) -> RepositoryResult<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_webhook_has_unique_secret() {
+        let user_id = Uuid::new_v4();
+        let first = ChatWebhook::new(user_id, "https://example.com/hook".to_string());
+        let second = ChatWebhook::new(user_id, "https://example.com/hook".to_string());
+
+        assert!(WEBHOOK_SECRET.is_well_formed(&first.secret));
+        assert_ne!(first.secret, second.secret);
+        assert_eq!(first.last_delivery_at, None);
+    }
+
+    #[test]
+    fn test_delivery_success() {
+        let delivery = |status| WebhookDelivery {
+            attempted_at: Utc::now(),
+            status,
+            error: None,
+        };
+        assert!(delivery(Some(204)).is_success());
+        assert!(!delivery(Some(500)).is_success());
+        assert!(!delivery(Some(302)).is_success());
+        assert!(!delivery(None).is_success());
+    }
+}
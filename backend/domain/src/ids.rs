@@ -0,0 +1,151 @@
+//! Typed identifiers.
+//!
+//! Users, chat sessions, chat messages and refresh tokens are all keyed by
+//! UUIDs. Passing them around as bare [`Uuid`]s lets a call swap two of them
+//! without a compiler error, so each kind of id gets its own newtype:
+//! [`UserId`], [`SessionId`], [`MessageId`] and [`TokenId`].
+//!
+//! The newtypes serialize as the plain UUID string, so wire formats do not
+//! change. Like the rest of the domain crate they know nothing of the
+//! database or the API docs: entities store the UUID and convert at the
+//! boundary, and DTO fields are documented with
+//! `#[schema(value_type = String, format = Uuid)]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use cobalt_stack_domain::ids::{TokenId, UserId};
+//! use uuid::Uuid;
+//!
+//! let raw = Uuid::new_v4();
+//! let user_id = UserId::from(raw);
+//! assert_eq!(user_id.into_uuid(), raw);
+//! assert_eq!(user_id.to_string(), raw.to_string());
+//!
+//! // Serialized as the bare UUID
+//! let json = serde_json::to_string(&user_id).unwrap();
+//! assert_eq!(json, format!("\"{raw}\""));
+//!
+//! let jti: TokenId = raw.to_string().parse().unwrap();
+//! assert_eq!(jti.into_uuid(), raw);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+macro_rules! typed_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(Uuid);
+
+        impl $name {
+            /// New random (v4) id
+            #[must_use]
+            pub fn new() -> Self {
+                Self(Uuid::new_v4())
+            }
+
+            /// Id wrapping `uuid`
+            #[must_use]
+            pub const fn from_uuid(uuid: Uuid) -> Self {
+                Self(uuid)
+            }
+
+            /// The wrapped UUID
+            #[must_use]
+            pub const fn into_uuid(self) -> Uuid {
+                self.0
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl From<Uuid> for $name {
+            fn from(uuid: Uuid) -> Self {
+                Self(uuid)
+            }
+        }
+
+        impl From<$name> for Uuid {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = uuid::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Uuid::parse_str(s).map(Self)
+            }
+        }
+    };
+}
+
+typed_id!(
+    /// Id of a user account (`users.id`)
+    UserId
+);
+
+typed_id!(
+    /// Id of a chat session (`chat_sessions.id`)
+    SessionId
+);
+
+typed_id!(
+    /// Id of a chat message (`chat_messages.id`)
+    MessageId
+);
+
+typed_id!(
+    /// Id of a refresh token: its `jti` claim and `refresh_tokens.id`
+    TokenId
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips_uuid() {
+        let uuid = Uuid::new_v4();
+
+        assert_eq!(UserId::from(uuid).into_uuid(), uuid);
+        assert_eq!(Uuid::from(SessionId::from_uuid(uuid)), uuid);
+        assert_eq!(
+            uuid.to_string().parse::<MessageId>().unwrap().into_uuid(),
+            uuid
+        );
+        assert!("not-a-uuid".parse::<TokenId>().is_err());
+    }
+
+    #[test]
+    fn test_serializes_as_bare_uuid() {
+        let id = TokenId::new();
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{id}\""));
+        assert_eq!(serde_json::from_str::<TokenId>(&json).unwrap(), id);
+    }
+
+    #[test]
+    fn test_new_ids_are_distinct() {
+        assert_ne!(UserId::new(), UserId::new());
+    }
+}
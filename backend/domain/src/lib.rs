@@ -0,0 +1,13 @@
+//! Cobalt Stack Domain Layer
+//!
+//! Pure business logic with zero dependencies on infrastructure: entities,
+//! value objects, domain services and repository traits, plus the typed ids
+//! and secure token primitives they build on.
+//!
+//! This crate sits at the bottom of the workspace. The backend crate
+//! implements its repository traits (`SeaORM`, Valkey) and re-exports it as
+//! `cobalt_stack_backend::domain`, so downstream code keeps one import path.
+
+pub mod chat;
+pub mod ids;
+pub mod token;
@@ -0,0 +1,368 @@
+//! Secure random token generation, hashing and verification.
+//!
+//! [`TokenGenerator`] produces random tokens of a configurable length,
+//! encoded as hex or URL-safe base64 and optionally tagged with a prefix
+//! (like `pk_live_...`) so leaked tokens are easy to recognize and scan
+//! for. Email verification links, device confirmations, unsubscribe links
+//! and share slugs all draw their tokens from it; API keys, invitations and
+//! magic links should too.
+//!
+//! Tokens are stored as SHA-256 hashes ([`hash_token`]) and checked with
+//! [`verify_token_hash`], which compares in constant time.
+//!
+//! # Security
+//!
+//! - **Random Generation**: Uses cryptographically secure RNG via `rand::thread_rng()`
+//! - **Hash Storage**: Tokens stored as SHA-256 hashes, never plaintext
+//! - **Token Length**: At least [`MIN_TOKEN_BYTES`] random bytes; 32 bytes
+//!   (64 hex characters) for verification tokens
+//! - **Comparison**: Constant time, so response timing reveals nothing about
+//!   how much of a guess was right
+//!
+//! # Examples
+//!
+//! ```
+//! use cobalt_stack_domain::token::{
+//!     generate_verification_token, hash_token, verify_token_hash, TokenEncoding, TokenGenerator,
+//! };
+//!
+//! // Generate a new verification token
+//! let token = generate_verification_token();
+//! assert_eq!(token.len(), 64); // 32 bytes as hex
+//!
+//! // Hash for database storage
+//! let hash = hash_token(&token);
+//! assert_eq!(hash.len(), 64); // SHA-256 hash as hex
+//! assert!(verify_token_hash(&token, &hash));
+//!
+//! // Prefixed, URL-safe tokens
+//! const API_KEY: TokenGenerator = TokenGenerator::new(24)
+//!     .encoding(TokenEncoding::Base64Url)
+//!     .prefix("pk_live_");
+//! let key = API_KEY.generate();
+//! assert!(key.starts_with("pk_live_"));
+//! assert!(API_KEY.is_well_formed(&key));
+//! ```
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Fewest random bytes a [`TokenGenerator`] accepts (128 bits of entropy)
+pub const MIN_TOKEN_BYTES: usize = 16;
+
+/// Text encoding of the random bytes of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenEncoding {
+    /// Lowercase hexadecimal, two characters per byte
+    Hex,
+    /// URL-safe base64 without padding, four characters per three bytes
+    Base64Url,
+}
+
+/// Generator of random tokens with a fixed length, encoding and prefix
+///
+/// Built with `const` methods so a kind of token is declared once, as a
+/// constant:
+///
+/// ```
+/// use cobalt_stack_domain::token::{TokenEncoding, TokenGenerator};
+///
+/// const INVITATION: TokenGenerator = TokenGenerator::new(32)
+///     .encoding(TokenEncoding::Base64Url)
+///     .prefix("inv_");
+///
+/// let token = INVITATION.generate();
+/// assert_eq!(token.len(), INVITATION.encoded_len());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenGenerator {
+    bytes: usize,
+    encoding: TokenEncoding,
+    prefix: &'static str,
+}
+
+impl TokenGenerator {
+    /// Generator of hex tokens of `bytes` random bytes, without prefix
+    ///
+    /// # Panics
+    /// Panics (at compile time when used in a constant) if `bytes` is below
+    /// [`MIN_TOKEN_BYTES`]
+    #[must_use]
+    pub const fn new(bytes: usize) -> Self {
+        assert!(
+            bytes >= MIN_TOKEN_BYTES,
+            "tokens need at least MIN_TOKEN_BYTES random bytes"
+        );
+        Self {
+            bytes,
+            encoding: TokenEncoding::Hex,
+            prefix: "",
+        }
+    }
+
+    /// Encode the random bytes with `encoding`
+    #[must_use]
+    pub const fn encoding(mut self, encoding: TokenEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Start every token with `prefix`
+    #[must_use]
+    pub const fn prefix(mut self, prefix: &'static str) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Length of the generated tokens, prefix included
+    #[must_use]
+    pub const fn encoded_len(&self) -> usize {
+        let body = match self.encoding {
+            TokenEncoding::Hex => self.bytes * 2,
+            TokenEncoding::Base64Url => (self.bytes * 4).div_ceil(3),
+        };
+        self.prefix.len() + body
+    }
+
+    /// Generate a new random token
+    #[must_use]
+    pub fn generate(&self) -> String {
+        let mut bytes = vec![0u8; self.bytes];
+        rand::thread_rng().fill(bytes.as_mut_slice());
+        let body = match self.encoding {
+            TokenEncoding::Hex => hex::encode(bytes),
+            TokenEncoding::Base64Url => URL_SAFE_NO_PAD.encode(bytes),
+        };
+        format!("{}{body}", self.prefix)
+    }
+
+    /// Whether `token` has the prefix, length and alphabet of this
+    /// generator's tokens
+    ///
+    /// A cheap check to reject garbage before a database lookup; it says
+    /// nothing about whether the token was ever issued.
+    #[must_use]
+    pub fn is_well_formed(&self, token: &str) -> bool {
+        if token.len() != self.encoded_len() {
+            return false;
+        }
+        let Some(body) = token.strip_prefix(self.prefix) else {
+            return false;
+        };
+        match self.encoding {
+            TokenEncoding::Hex => body
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)),
+            TokenEncoding::Base64Url => body
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'),
+        }
+    }
+}
+
+/// Email verification, device confirmation and unsubscribe tokens
+pub const VERIFICATION_TOKEN: TokenGenerator = TokenGenerator::new(32);
+
+/// Generate a cryptographically secure 32-byte random token as hex string.
+///
+/// Uses `rand::thread_rng()` to generate 32 random bytes and encodes them
+/// as a 64-character hexadecimal string. Each generated token is statistically
+/// unique with ~2^256 possible values.
+///
+/// # Returns
+///
+/// A 64-character hexadecimal string representing 32 random bytes.
+///
+/// # Examples
+///
+/// ```
+/// use cobalt_stack_domain::token::generate_verification_token;
+///
+/// let token = generate_verification_token();
+/// assert_eq!(token.len(), 64);
+/// assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+/// ```
+///
+/// # Security Notes
+///
+/// - Tokens are cryptographically random, suitable for security-sensitive operations
+/// - Each token should be used only once and stored as a hash
+/// - Tokens should expire after a reasonable time period (typically 24 hours)
+#[must_use]
+pub fn generate_verification_token() -> String {
+    VERIFICATION_TOKEN.generate()
+}
+
+/// Hash a token using SHA-256 for secure database storage.
+///
+/// Converts a plaintext token into a SHA-256 hash encoded as a 64-character
+/// hexadecimal string. This allows storing tokens securely in the database
+/// without exposing the actual token value.
+///
+/// # Arguments
+///
+/// * `token` - The plaintext token to hash
+///
+/// # Returns
+///
+/// A 64-character hexadecimal string representing the SHA-256 hash.
+///
+/// # Examples
+///
+/// ```
+/// use cobalt_stack_domain::token::hash_token;
+///
+/// let token = "my_secret_token";
+/// let hash = hash_token(token);
+///
+/// assert_eq!(hash.len(), 64);
+/// assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+///
+/// // Hashing is deterministic
+/// assert_eq!(hash_token(token), hash_token(token));
+/// ```
+///
+/// # Security Notes
+///
+/// - SHA-256 is a one-way hash function (cannot be reversed)
+/// - Same input always produces the same hash (deterministic)
+/// - Database stores hashes, not plaintext tokens
+/// - To verify a token, use [`verify_token_hash`]
+#[must_use]
+pub fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Check a presented token against the hash stored for it
+///
+/// Compares in constant time.
+#[must_use]
+pub fn verify_token_hash(token: &str, expected_hash: &str) -> bool {
+    constant_time_eq(hash_token(token).as_bytes(), expected_hash.as_bytes())
+}
+
+/// Compare two byte strings in time independent of where they differ
+///
+/// Only the lengths may leak, which are public for tokens and signatures.
+#[must_use]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_verification_token_length() {
+        let token = generate_verification_token();
+        // Token should be 32 bytes = 64 hex characters
+        assert_eq!(token.len(), 64);
+    }
+
+    #[test]
+    fn test_generate_verification_token_unique() {
+        let token1 = generate_verification_token();
+        let token2 = generate_verification_token();
+        // Each token should be unique
+        assert_ne!(token1, token2);
+    }
+
+    #[test]
+    fn test_generate_verification_token_hex_chars() {
+        let token = generate_verification_token();
+        // Token should only contain valid hex characters
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_hash_token_sha256_length() {
+        let token = "abc123def456";
+        let hashed = hash_token(token);
+        // SHA-256 produces 64 hex characters (32 bytes)
+        assert_eq!(hashed.len(), 64);
+    }
+
+    #[test]
+    fn test_hash_token_deterministic() {
+        let token = "test_token_12345";
+        let hash1 = hash_token(token);
+        let hash2 = hash_token(token);
+        // Same input should produce same hash
+        assert_eq!(hash1, hash2);
+    }
+
+    #[test]
+    fn test_hash_token_different_inputs() {
+        let hash1 = hash_token("token1");
+        let hash2 = hash_token("token2");
+        // Different inputs should produce different hashes
+        assert_ne!(hash1, hash2);
+    }
+
+    #[test]
+    fn test_verify_token_hash() {
+        let token = generate_verification_token();
+        let hash = hash_token(&token);
+        assert!(verify_token_hash(&token, &hash));
+        assert!(!verify_token_hash("other", &hash));
+        assert!(!verify_token_hash(&token, &hash[..32]));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn test_generator_hex() {
+        let generator = TokenGenerator::new(16);
+        let token = generator.generate();
+        assert_eq!(token.len(), 32);
+        assert_eq!(generator.encoded_len(), 32);
+        assert!(generator.is_well_formed(&token));
+        assert!(!generator.is_well_formed(&token.to_uppercase()));
+        assert!(!generator.is_well_formed(&token[1..]));
+        assert_ne!(token, generator.generate());
+    }
+
+    #[test]
+    fn test_generator_base64url_with_prefix() {
+        let generator = TokenGenerator::new(32)
+            .encoding(TokenEncoding::Base64Url)
+            .prefix("pk_live_");
+        let token = generator.generate();
+
+        // 32 bytes are 43 unpadded base64 characters
+        assert_eq!(token.len(), 8 + 43);
+        assert_eq!(generator.encoded_len(), token.len());
+        assert!(token.starts_with("pk_live_"));
+        assert!(!token.contains(['+', '/', '=']));
+        assert!(generator.is_well_formed(&token));
+
+        let wrong_prefix = token.replacen("pk_live_", "pk_test_", 1);
+        assert!(!generator.is_well_formed(&wrong_prefix));
+        let bad_char = format!("{}+", &token[..token.len() - 1]);
+        assert!(!generator.is_well_formed(&bad_char));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least MIN_TOKEN_BYTES")]
+    fn test_generator_rejects_short_tokens() {
+        let _ = TokenGenerator::new(8);
+    }
+
+    #[test]
+    fn test_hash_token_hex_chars() {
+        let token = "sample_token";
+        let hashed = hash_token(token);
+        // Hash should only contain valid hex characters
+        assert!(hashed.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
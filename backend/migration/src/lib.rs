@@ -4,6 +4,36 @@ mod m20250101_000001_create_initial_schema;
 mod m20250125_000001_create_auth_tables;
 mod m20250126_000001_add_email_verification_and_roles;
 mod m20250127_000001_create_chat_tables;
+mod m20250128_000001_create_chat_read_states;
+mod m20250129_000001_create_chat_shares;
+mod m20250130_000001_create_email_digest_subscriptions;
+mod m20250131_000001_create_branding_settings;
+mod m20250201_000001_create_user_preferences;
+mod m20250202_000001_add_refresh_token_user_agent;
+mod m20250203_000001_create_chat_usage;
+mod m20250204_000001_add_chat_suspension;
+mod m20250205_000001_add_chat_session_version;
+mod m20250206_000001_create_access_logs;
+mod m20250207_000001_create_message_annotations;
+mod m20250208_000001_create_scheduled_reports;
+mod m20250209_000001_add_users_tokens_not_before;
+mod m20250210_000001_add_refresh_token_fingerprint;
+mod m20250211_000001_create_chat_jobs;
+mod m20250212_000001_add_users_password_rotation;
+mod m20250213_000001_add_chat_message_redaction;
+mod m20250214_000001_create_trusted_devices;
+mod m20250215_000001_create_email_suppressions;
+mod m20250216_000001_create_guest_accounts;
+mod m20250217_000001_create_email_log;
+mod m20250218_000001_add_refresh_token_session_details;
+mod m20250219_000001_create_roles_and_permissions;
+mod m20250220_000001_create_chat_webhooks;
+mod m20250221_000001_create_audit_logs;
+mod m20250222_000001_create_sandbox_accounts;
+mod m20250223_000001_create_runtime_switches;
+mod m20250224_000001_add_refresh_token_family;
+mod m20250225_000001_create_admin_stats;
+mod m20250226_000001_add_email_verification_client;
 
 pub struct Migrator;
 
@@ -15,6 +45,36 @@ impl MigratorTrait for Migrator {
             Box::new(m20250125_000001_create_auth_tables::Migration),
             Box::new(m20250126_000001_add_email_verification_and_roles::Migration),
             Box::new(m20250127_000001_create_chat_tables::Migration),
+            Box::new(m20250128_000001_create_chat_read_states::Migration),
+            Box::new(m20250129_000001_create_chat_shares::Migration),
+            Box::new(m20250130_000001_create_email_digest_subscriptions::Migration),
+            Box::new(m20250131_000001_create_branding_settings::Migration),
+            Box::new(m20250201_000001_create_user_preferences::Migration),
+            Box::new(m20250202_000001_add_refresh_token_user_agent::Migration),
+            Box::new(m20250203_000001_create_chat_usage::Migration),
+            Box::new(m20250204_000001_add_chat_suspension::Migration),
+            Box::new(m20250205_000001_add_chat_session_version::Migration),
+            Box::new(m20250206_000001_create_access_logs::Migration),
+            Box::new(m20250207_000001_create_message_annotations::Migration),
+            Box::new(m20250208_000001_create_scheduled_reports::Migration),
+            Box::new(m20250209_000001_add_users_tokens_not_before::Migration),
+            Box::new(m20250210_000001_add_refresh_token_fingerprint::Migration),
+            Box::new(m20250211_000001_create_chat_jobs::Migration),
+            Box::new(m20250212_000001_add_users_password_rotation::Migration),
+            Box::new(m20250213_000001_add_chat_message_redaction::Migration),
+            Box::new(m20250214_000001_create_trusted_devices::Migration),
+            Box::new(m20250215_000001_create_email_suppressions::Migration),
+            Box::new(m20250216_000001_create_guest_accounts::Migration),
+            Box::new(m20250217_000001_create_email_log::Migration),
+            Box::new(m20250218_000001_add_refresh_token_session_details::Migration),
+            Box::new(m20250219_000001_create_roles_and_permissions::Migration),
+            Box::new(m20250220_000001_create_chat_webhooks::Migration),
+            Box::new(m20250221_000001_create_audit_logs::Migration),
+            Box::new(m20250222_000001_create_sandbox_accounts::Migration),
+            Box::new(m20250223_000001_create_runtime_switches::Migration),
+            Box::new(m20250224_000001_add_refresh_token_family::Migration),
+            Box::new(m20250225_000001_create_admin_stats::Migration),
+            Box::new(m20250226_000001_add_email_verification_client::Migration),
         ]
     }
 }
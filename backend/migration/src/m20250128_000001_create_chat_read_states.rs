@@ -0,0 +1,129 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create chat_read_states table (one row per user per session)
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatReadStates::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ChatReadStates::UserId).uuid().not_null())
+                    .col(ColumnDef::new(ChatReadStates::SessionId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(ChatReadStates::LastReadMessageId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChatReadStates::LastReadAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChatReadStates::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ChatReadStates::UserId)
+                            .col(ChatReadStates::SessionId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_read_states_user_id")
+                            .from(ChatReadStates::Table, ChatReadStates::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_read_states_session_id")
+                            .from(ChatReadStates::Table, ChatReadStates::SessionId)
+                            .to(ChatSessions::Table, ChatSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_read_states_last_read_message_id")
+                            .from(ChatReadStates::Table, ChatReadStates::LastReadMessageId)
+                            .to(ChatMessages::Table, ChatMessages::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Unread counts filter messages by session and creation time
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_chat_messages_session_id_created_at")
+                    .table(ChatMessages::Table)
+                    .col(ChatMessages::SessionId)
+                    .col(ChatMessages::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_chat_messages_session_id_created_at")
+                    .table(ChatMessages::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ChatReadStates::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for chat_read_states table
+#[derive(DeriveIden)]
+enum ChatReadStates {
+    Table,
+    UserId,
+    SessionId,
+    LastReadMessageId,
+    LastReadAt,
+    UpdatedAt,
+}
+
+/// Table and column identifiers for chat_sessions table (for foreign key)
+#[derive(DeriveIden)]
+enum ChatSessions {
+    Table,
+    Id,
+}
+
+/// Table and column identifiers for chat_messages table (for foreign key)
+#[derive(DeriveIden)]
+enum ChatMessages {
+    Table,
+    Id,
+    SessionId,
+    CreatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,113 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create chat_shares table (public read-only links to a session)
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatShares::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChatShares::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ChatShares::SessionId).uuid().not_null())
+                    .col(ColumnDef::new(ChatShares::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(ChatShares::Slug)
+                            .string_len(128)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ChatShares::PasswordHash).string())
+                    .col(ColumnDef::new(ChatShares::ExpiresAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(ChatShares::RevokedAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(ChatShares::ViewCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ChatShares::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_shares_session_id")
+                            .from(ChatShares::Table, ChatShares::SessionId)
+                            .to(ChatSessions::Table, ChatSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_shares_user_id")
+                            .from(ChatShares::Table, ChatShares::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Owners list the shares of a session
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_chat_shares_session_id")
+                    .table(ChatShares::Table)
+                    .col(ChatShares::SessionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatShares::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for chat_shares table
+#[derive(DeriveIden)]
+enum ChatShares {
+    Table,
+    Id,
+    SessionId,
+    UserId,
+    Slug,
+    PasswordHash,
+    ExpiresAt,
+    RevokedAt,
+    ViewCount,
+    CreatedAt,
+}
+
+/// Table and column identifiers for chat_sessions table (for foreign key)
+#[derive(DeriveIden)]
+enum ChatSessions {
+    Table,
+    Id,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
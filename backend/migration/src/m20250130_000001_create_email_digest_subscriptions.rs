@@ -0,0 +1,96 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create email_digest_subscriptions table (one row per opted-in user)
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmailDigestSubscriptions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EmailDigestSubscriptions::UserId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(EmailDigestSubscriptions::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(EmailDigestSubscriptions::UnsubscribeToken)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(EmailDigestSubscriptions::LastSentAt)
+                            .timestamp_with_time_zone(),
+                    )
+                    .col(
+                        ColumnDef::new(EmailDigestSubscriptions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(EmailDigestSubscriptions::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_email_digest_subscriptions_user_id")
+                            .from(
+                                EmailDigestSubscriptions::Table,
+                                EmailDigestSubscriptions::UserId,
+                            )
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(EmailDigestSubscriptions::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for email_digest_subscriptions table
+#[derive(DeriveIden)]
+enum EmailDigestSubscriptions {
+    Table,
+    UserId,
+    Enabled,
+    UnsubscribeToken,
+    LastSentAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create branding_settings table (single row of runtime overrides;
+        // NULL columns fall back to the BRANDING_* environment defaults)
+        manager
+            .create_table(
+                Table::create()
+                    .table(BrandingSettings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BrandingSettings::Id)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BrandingSettings::ProductName).string_len(100))
+                    .col(ColumnDef::new(BrandingSettings::LogoUrl).string_len(2048))
+                    .col(ColumnDef::new(BrandingSettings::SupportEmail).string_len(255))
+                    .col(ColumnDef::new(BrandingSettings::PrimaryColor).string_len(7))
+                    .col(ColumnDef::new(BrandingSettings::AccentColor).string_len(7))
+                    .col(ColumnDef::new(BrandingSettings::UpdatedBy).uuid())
+                    .col(
+                        ColumnDef::new(BrandingSettings::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_branding_settings_updated_by")
+                            .from(BrandingSettings::Table, BrandingSettings::UpdatedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BrandingSettings::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for branding_settings table
+#[derive(DeriveIden)]
+enum BrandingSettings {
+    Table,
+    Id,
+    ProductName,
+    LogoUrl,
+    SupportEmail,
+    PrimaryColor,
+    AccentColor,
+    UpdatedBy,
+    UpdatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
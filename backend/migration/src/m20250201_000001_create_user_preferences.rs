@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create user_preferences table (one JSONB document per user; users
+        // without a row use the default preferences)
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserPreferences::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserPreferences::UserId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserPreferences::Preferences)
+                            .json_binary()
+                            .not_null()
+                            .extra("DEFAULT '{}'::jsonb".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(UserPreferences::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_preferences_user_id")
+                            .from(UserPreferences::Table, UserPreferences::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserPreferences::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for user_preferences table
+#[derive(DeriveIden)]
+enum UserPreferences {
+    Table,
+    UserId,
+    Preferences,
+    UpdatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
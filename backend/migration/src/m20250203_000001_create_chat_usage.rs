@@ -0,0 +1,112 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create chat_usage table (one row per assistant reply)
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatUsage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChatUsage::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ChatUsage::UserId).uuid().not_null())
+                    .col(ColumnDef::new(ChatUsage::SessionId).uuid().not_null())
+                    .col(ColumnDef::new(ChatUsage::Model).string().not_null())
+                    .col(
+                        ColumnDef::new(ChatUsage::PromptTokens)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ChatUsage::CompletionTokens)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(ChatUsage::LatencyMs).integer().not_null())
+                    .col(
+                        ColumnDef::new(ChatUsage::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_usage_user_id")
+                            .from(ChatUsage::Table, ChatUsage::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_usage_session_id")
+                            .from(ChatUsage::Table, ChatUsage::SessionId)
+                            .to(ChatSessions::Table, ChatSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Analytics aggregate one user's rows over a time range
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_chat_usage_user_id_created_at")
+                    .table(ChatUsage::Table)
+                    .col(ChatUsage::UserId)
+                    .col(ChatUsage::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatUsage::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for chat_usage table
+#[derive(DeriveIden)]
+enum ChatUsage {
+    Table,
+    Id,
+    UserId,
+    SessionId,
+    Model,
+    PromptTokens,
+    CompletionTokens,
+    LatencyMs,
+    CreatedAt,
+}
+
+/// Table and column identifiers for chat_sessions table (for foreign key)
+#[derive(DeriveIden)]
+enum ChatSessions {
+    Table,
+    Id,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Set while the share owner's account is disabled (lifted on enable,
+        // unlike revoked_at)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatShares::Table)
+                    .add_column(
+                        ColumnDef::new(ChatShares::SuspendedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Set when a session is archived because its owner stayed disabled
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatSessions::Table)
+                    .add_column(
+                        ColumnDef::new(ChatSessions::ArchivedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatSessions::Table)
+                    .drop_column(ChatSessions::ArchivedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatShares::Table)
+                    .drop_column(ChatShares::SuspendedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for chat_shares table additions
+#[derive(DeriveIden)]
+enum ChatShares {
+    Table,
+    SuspendedAt,
+}
+
+/// Table and column identifiers for chat_sessions table additions
+#[derive(DeriveIden)]
+enum ChatSessions {
+    Table,
+    ArchivedAt,
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Incremented by every session update; an update naming an older
+        // version is rejected instead of overwriting a concurrent change
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatSessions::Table)
+                    .add_column(
+                        ColumnDef::new(ChatSessions::Version)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatSessions::Table)
+                    .drop_column(ChatSessions::Version)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Table and column identifiers for chat_sessions table additions
+#[derive(DeriveIden)]
+enum ChatSessions {
+    Table,
+    Version,
+}
@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create access_logs table (one row per recorded API request). No
+        // foreign key on user_id: records outlive the accounts they mention.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AccessLogs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AccessLogs::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AccessLogs::Method).string_len(16).not_null())
+                    .col(ColumnDef::new(AccessLogs::Path).string().not_null())
+                    .col(
+                        ColumnDef::new(AccessLogs::Status)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AccessLogs::LatencyMs).integer().not_null())
+                    .col(ColumnDef::new(AccessLogs::UserId).uuid().null())
+                    .col(ColumnDef::new(AccessLogs::Ip).string_len(45).null())
+                    .col(
+                        ColumnDef::new(AccessLogs::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Retention purges and time-range queries
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_access_logs_created_at")
+                    .table(AccessLogs::Table)
+                    .col(AccessLogs::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // "What did this user do" queries
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_access_logs_user_id_created_at")
+                    .table(AccessLogs::Table)
+                    .col(AccessLogs::UserId)
+                    .col(AccessLogs::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AccessLogs::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for access_logs table
+#[derive(DeriveIden)]
+enum AccessLogs {
+    Table,
+    Id,
+    Method,
+    Path,
+    Status,
+    LatencyMs,
+    UserId,
+    Ip,
+    CreatedAt,
+}
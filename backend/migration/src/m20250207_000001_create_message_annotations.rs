@@ -0,0 +1,148 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create message_annotations table (bookmarks, labels and reactions)
+        manager
+            .create_table(
+                Table::create()
+                    .table(MessageAnnotations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MessageAnnotations::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageAnnotations::MessageId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageAnnotations::SessionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MessageAnnotations::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(MessageAnnotations::Kind)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    // Empty for bookmarks, so the unique index also covers them
+                    .col(
+                        ColumnDef::new(MessageAnnotations::Value)
+                            .string_len(128)
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(MessageAnnotations::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_annotations_message_id")
+                            .from(MessageAnnotations::Table, MessageAnnotations::MessageId)
+                            .to(ChatMessages::Table, ChatMessages::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_annotations_session_id")
+                            .from(MessageAnnotations::Table, MessageAnnotations::SessionId)
+                            .to(ChatSessions::Table, ChatSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_annotations_user_id")
+                            .from(MessageAnnotations::Table, MessageAnnotations::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One annotation per kind and value per user on a message
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_message_annotations_unique")
+                    .table(MessageAnnotations::Table)
+                    .col(MessageAnnotations::MessageId)
+                    .col(MessageAnnotations::UserId)
+                    .col(MessageAnnotations::Kind)
+                    .col(MessageAnnotations::Value)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Listing and filtering a user's annotations in a session
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_message_annotations_session_id_user_id")
+                    .table(MessageAnnotations::Table)
+                    .col(MessageAnnotations::SessionId)
+                    .col(MessageAnnotations::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MessageAnnotations::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for message_annotations table
+#[derive(DeriveIden)]
+enum MessageAnnotations {
+    Table,
+    Id,
+    MessageId,
+    SessionId,
+    UserId,
+    Kind,
+    Value,
+    CreatedAt,
+}
+
+/// Table and column identifiers for chat_messages table (for foreign key)
+#[derive(DeriveIden)]
+enum ChatMessages {
+    Table,
+    Id,
+}
+
+/// Table and column identifiers for chat_sessions table (for foreign key)
+#[derive(DeriveIden)]
+enum ChatSessions {
+    Table,
+    Id,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create scheduled_reports table (one row per periodic report, so
+        // instances can claim a due report with a conditional update)
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScheduledReports::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScheduledReports::Name)
+                            .string_len(64)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledReports::LastSentAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScheduledReports::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for scheduled_reports table
+#[derive(DeriveIden)]
+enum ScheduledReports {
+    Table,
+    Name,
+    LastSentAt,
+}
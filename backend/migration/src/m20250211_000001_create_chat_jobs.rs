@@ -0,0 +1,197 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create chat_jobs table (batch completions run by background workers)
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatJobs::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ChatJobs::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(ChatJobs::UserId).uuid().not_null())
+                    .col(ColumnDef::new(ChatJobs::Model).string_len(100).not_null())
+                    .col(ColumnDef::new(ChatJobs::Status).string_len(16).not_null())
+                    .col(ColumnDef::new(ChatJobs::TotalItems).integer().not_null())
+                    .col(
+                        ColumnDef::new(ChatJobs::CompletedItems)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ChatJobs::FailedItems)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ChatJobs::PromptTokens)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ChatJobs::CompletionTokens)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ChatJobs::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(ChatJobs::StartedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChatJobs::FinishedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_jobs_user_id")
+                            .from(ChatJobs::Table, ChatJobs::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Workers pick the oldest queued job
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_chat_jobs_status_created_at")
+                    .table(ChatJobs::Table)
+                    .col(ChatJobs::Status)
+                    .col(ChatJobs::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create chat_job_items table (one prompt of a job and its result)
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatJobItems::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChatJobItems::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ChatJobItems::JobId).uuid().not_null())
+                    .col(ColumnDef::new(ChatJobItems::Position).integer().not_null())
+                    .col(ColumnDef::new(ChatJobItems::Prompt).text().not_null())
+                    .col(
+                        ColumnDef::new(ChatJobItems::Status)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChatJobItems::Output).text().null())
+                    .col(ColumnDef::new(ChatJobItems::Error).text().null())
+                    .col(ColumnDef::new(ChatJobItems::PromptTokens).integer().null())
+                    .col(
+                        ColumnDef::new(ChatJobItems::CompletionTokens)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChatJobItems::FinishedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_job_items_job_id")
+                            .from(ChatJobItems::Table, ChatJobItems::JobId)
+                            .to(ChatJobs::Table, ChatJobs::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Results are listed in prompt order
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_chat_job_items_job_id_position")
+                    .table(ChatJobItems::Table)
+                    .col(ChatJobItems::JobId)
+                    .col(ChatJobItems::Position)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatJobItems::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ChatJobs::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for chat_jobs table
+#[derive(DeriveIden)]
+enum ChatJobs {
+    Table,
+    Id,
+    UserId,
+    Model,
+    Status,
+    TotalItems,
+    CompletedItems,
+    FailedItems,
+    PromptTokens,
+    CompletionTokens,
+    CreatedAt,
+    StartedAt,
+    FinishedAt,
+}
+
+/// Table and column identifiers for chat_job_items table
+#[derive(DeriveIden)]
+enum ChatJobItems {
+    Table,
+    Id,
+    JobId,
+    Position,
+    Prompt,
+    Status,
+    Output,
+    Error,
+    PromptTokens,
+    CompletionTokens,
+    FinishedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Redacted messages keep their row (and metadata) but not their content
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatMessages::Table)
+                    .add_column(
+                        ColumnDef::new(ChatMessages::RedactedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatMessages::Table)
+                    .drop_column(ChatMessages::RedactedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Table and column identifiers for chat_messages table additions
+#[derive(DeriveIden)]
+enum ChatMessages {
+    Table,
+    RedactedAt,
+}
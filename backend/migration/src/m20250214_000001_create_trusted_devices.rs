@@ -0,0 +1,121 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create trusted_devices table (devices confirmed by email, and
+        // devices waiting for confirmation)
+        manager
+            .create_table(
+                Table::create()
+                    .table(TrustedDevices::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TrustedDevices::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TrustedDevices::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(TrustedDevices::DeviceHash)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TrustedDevices::UserAgent).text().null())
+                    .col(ColumnDef::new(TrustedDevices::Ip).string_len(45).null())
+                    .col(
+                        ColumnDef::new(TrustedDevices::ConfirmationTokenHash)
+                            .string_len(64)
+                            .null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TrustedDevices::ConfirmationExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TrustedDevices::ConfirmationSentAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TrustedDevices::ConfirmedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TrustedDevices::LastUsedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TrustedDevices::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_trusted_devices_user_id")
+                            .from(TrustedDevices::Table, TrustedDevices::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One record per device cookie and user
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_trusted_devices_user_id_device_hash")
+                    .table(TrustedDevices::Table)
+                    .col(TrustedDevices::UserId)
+                    .col(TrustedDevices::DeviceHash)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TrustedDevices::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for trusted_devices table
+#[derive(DeriveIden)]
+enum TrustedDevices {
+    Table,
+    Id,
+    UserId,
+    DeviceHash,
+    UserAgent,
+    Ip,
+    ConfirmationTokenHash,
+    ConfirmationExpiresAt,
+    ConfirmationSentAt,
+    ConfirmedAt,
+    LastUsedAt,
+    CreatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create email_suppressions table (addresses that bounced or
+        // complained, never sent to again). No foreign key: the address stays
+        // suppressed when the account is deleted and the address reused.
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmailSuppressions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EmailSuppressions::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(EmailSuppressions::Email)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(EmailSuppressions::Reason)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EmailSuppressions::Detail).text().null())
+                    .col(
+                        ColumnDef::new(EmailSuppressions::Reports)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(EmailSuppressions::LastReportedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(EmailSuppressions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EmailSuppressions::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for email_suppressions table
+#[derive(DeriveIden)]
+enum EmailSuppressions {
+    Table,
+    Id,
+    Email,
+    Reason,
+    Detail,
+    Reports,
+    LastReportedAt,
+    CreatedAt,
+}
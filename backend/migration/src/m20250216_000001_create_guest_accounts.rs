@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create guest_accounts table (anonymous accounts of the guest mode,
+        // deleted once claimed or expired)
+        manager
+            .create_table(
+                Table::create()
+                    .table(GuestAccounts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GuestAccounts::UserId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(GuestAccounts::Ip).string_len(45).null())
+                    .col(
+                        ColumnDef::new(GuestAccounts::MessagesSent)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(GuestAccounts::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GuestAccounts::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_guest_accounts_user_id")
+                            .from(GuestAccounts::Table, GuestAccounts::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Guests created per IP address, for the creation rate limit
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_guest_accounts_ip_created_at")
+                    .table(GuestAccounts::Table)
+                    .col(GuestAccounts::Ip)
+                    .col(GuestAccounts::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Expired guests, for the purge
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_guest_accounts_expires_at")
+                    .table(GuestAccounts::Table)
+                    .col(GuestAccounts::ExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GuestAccounts::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for guest_accounts table
+#[derive(DeriveIden)]
+enum GuestAccounts {
+    Table,
+    UserId,
+    Ip,
+    MessagesSent,
+    ExpiresAt,
+    CreatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
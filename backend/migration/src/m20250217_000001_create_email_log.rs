@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create email_log table (one row per outbound email). No foreign
+        // key: the history of an address outlives the account using it.
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmailLog::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(EmailLog::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(EmailLog::Kind).string_len(16).not_null())
+                    .col(
+                        ColumnDef::new(EmailLog::Recipient)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EmailLog::Template).string_len(64).null())
+                    .col(ColumnDef::new(EmailLog::MessageId).string_len(255).null())
+                    .col(ColumnDef::new(EmailLog::Status).string_len(16).not_null())
+                    .col(ColumnDef::new(EmailLog::Error).text().null())
+                    .col(
+                        ColumnDef::new(EmailLog::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // "Did this address get our email" queries
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_email_log_recipient_created_at")
+                    .table(EmailLog::Table)
+                    .col(EmailLog::Recipient)
+                    .col(EmailLog::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Lookups of a delivery reported by the provider
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_email_log_message_id")
+                    .table(EmailLog::Table)
+                    .col(EmailLog::MessageId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EmailLog::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for email_log table
+#[derive(DeriveIden)]
+enum EmailLog {
+    Table,
+    Id,
+    Kind,
+    Recipient,
+    Template,
+    MessageId,
+    Status,
+    Error,
+    CreatedAt,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // IP address of the client a session was signed in or last refreshed
+        // from, and when it was last refreshed
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RefreshTokens::Table)
+                    .add_column(ColumnDef::new(RefreshTokens::Ip).string_len(45).null())
+                    .add_column(
+                        ColumnDef::new(RefreshTokens::LastUsedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RefreshTokens::Table)
+                    .drop_column(RefreshTokens::Ip)
+                    .drop_column(RefreshTokens::LastUsedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for refresh_tokens table additions
+#[derive(DeriveIden)]
+enum RefreshTokens {
+    Table,
+    Ip,
+    LastUsedAt,
+}
@@ -0,0 +1,206 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create permissions table (the catalog known to the backend, kept
+        // in sync with the code at startup)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Permissions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Permissions::Name)
+                            .string_len(64)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Permissions::Description).text().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create roles table (custom roles defined by admins)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Roles::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Roles::Id).uuid().not_null().primary_key())
+                    .col(
+                        ColumnDef::new(Roles::Name)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Roles::Description).text().null())
+                    .col(ColumnDef::new(Roles::CreatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(Roles::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_roles_created_by")
+                            .from(Roles::Table, Roles::CreatedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create role_permissions table (permissions granted by each role)
+        manager
+            .create_table(
+                Table::create()
+                    .table(RolePermissions::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RolePermissions::RoleId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(RolePermissions::Permission)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(RolePermissions::RoleId)
+                            .col(RolePermissions::Permission),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_role_permissions_role_id")
+                            .from(RolePermissions::Table, RolePermissions::RoleId)
+                            .to(Roles::Table, Roles::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_role_permissions_permission")
+                            .from(RolePermissions::Table, RolePermissions::Permission)
+                            .to(Permissions::Table, Permissions::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create user_roles table (custom roles assigned to users, on top of
+        // the base role in users.role)
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserRoles::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserRoles::UserId).uuid().not_null())
+                    .col(ColumnDef::new(UserRoles::RoleId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(UserRoles::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(UserRoles::UserId)
+                            .col(UserRoles::RoleId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_roles_user_id")
+                            .from(UserRoles::Table, UserRoles::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_roles_role_id")
+                            .from(UserRoles::Table, UserRoles::RoleId)
+                            .to(Roles::Table, Roles::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Holders of a role, for assignment counts and role deletion
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_user_roles_role_id")
+                    .table(UserRoles::Table)
+                    .col(UserRoles::RoleId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserRoles::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(RolePermissions::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Roles::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Permissions::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for permissions table
+#[derive(DeriveIden)]
+enum Permissions {
+    Table,
+    Name,
+    Description,
+}
+
+/// Table and column identifiers for roles table
+#[derive(DeriveIden)]
+enum Roles {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedBy,
+    CreatedAt,
+}
+
+/// Table and column identifiers for role_permissions table
+#[derive(DeriveIden)]
+enum RolePermissions {
+    Table,
+    RoleId,
+    Permission,
+}
+
+/// Table and column identifiers for user_roles table
+#[derive(DeriveIden)]
+enum UserRoles {
+    Table,
+    UserId,
+    RoleId,
+    CreatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,95 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create chat_webhooks table (callbacks fired when a reply completes)
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatWebhooks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChatWebhooks::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ChatWebhooks::UserId).uuid().not_null())
+                    .col(ColumnDef::new(ChatWebhooks::Url).text().not_null())
+                    .col(
+                        ColumnDef::new(ChatWebhooks::Secret)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChatWebhooks::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(ChatWebhooks::LastDeliveryAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ChatWebhooks::LastStatus).integer().null())
+                    .col(ColumnDef::new(ChatWebhooks::LastError).text().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_chat_webhooks_user_id")
+                            .from(ChatWebhooks::Table, ChatWebhooks::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Webhooks of a user, looked up on every completed reply
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_chat_webhooks_user_id")
+                    .table(ChatWebhooks::Table)
+                    .col(ChatWebhooks::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatWebhooks::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for chat_webhooks table
+#[derive(DeriveIden)]
+enum ChatWebhooks {
+    Table,
+    Id,
+    UserId,
+    Url,
+    Secret,
+    CreatedAt,
+    LastDeliveryAt,
+    LastStatus,
+    LastError,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
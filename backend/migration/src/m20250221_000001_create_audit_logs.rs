@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create audit_logs table (security-sensitive events). No foreign
+        // keys on actor_id and target_id: entries outlive the accounts they
+        // mention.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLogs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLogs::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLogs::Event).string_len(64).not_null())
+                    .col(ColumnDef::new(AuditLogs::ActorId).uuid().null())
+                    .col(ColumnDef::new(AuditLogs::TargetId).uuid().null())
+                    .col(ColumnDef::new(AuditLogs::Ip).string_len(45).null())
+                    .col(ColumnDef::new(AuditLogs::UserAgent).text().null())
+                    .col(ColumnDef::new(AuditLogs::Details).json_binary().null())
+                    .col(
+                        ColumnDef::new(AuditLogs::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Time-range queries, optionally of one event type
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_audit_logs_event_created_at")
+                    .table(AuditLogs::Table)
+                    .col(AuditLogs::Event)
+                    .col(AuditLogs::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_audit_logs_created_at")
+                    .table(AuditLogs::Table)
+                    .col(AuditLogs::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // "What happened to or by this user" queries
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_audit_logs_actor_id_created_at")
+                    .table(AuditLogs::Table)
+                    .col(AuditLogs::ActorId)
+                    .col(AuditLogs::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_audit_logs_target_id_created_at")
+                    .table(AuditLogs::Table)
+                    .col(AuditLogs::TargetId)
+                    .col(AuditLogs::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLogs::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for audit_logs table
+#[derive(DeriveIden)]
+enum AuditLogs {
+    Table,
+    Id,
+    Event,
+    ActorId,
+    TargetId,
+    Ip,
+    UserAgent,
+    Details,
+    CreatedAt,
+}
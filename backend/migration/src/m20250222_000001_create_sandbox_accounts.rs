@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create sandbox_accounts table (accounts created in sandbox mode,
+        // deleted with their data once expired)
+        manager
+            .create_table(
+                Table::create()
+                    .table(SandboxAccounts::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SandboxAccounts::UserId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SandboxAccounts::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SandboxAccounts::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sandbox_accounts_user_id")
+                            .from(SandboxAccounts::Table, SandboxAccounts::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Expired accounts, for the purge
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_sandbox_accounts_expires_at")
+                    .table(SandboxAccounts::Table)
+                    .col(SandboxAccounts::ExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SandboxAccounts::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for sandbox_accounts table
+#[derive(DeriveIden)]
+enum SandboxAccounts {
+    Table,
+    UserId,
+    ExpiresAt,
+    CreatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create runtime_switches table (single row; without it registration
+        // is open and the API accepts writes)
+        manager
+            .create_table(
+                Table::create()
+                    .table(RuntimeSwitches::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RuntimeSwitches::Id)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RuntimeSwitches::RegistrationOpen)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(RuntimeSwitches::ReadOnly)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(RuntimeSwitches::ReadOnlyReason).string_len(500))
+                    .col(ColumnDef::new(RuntimeSwitches::UpdatedBy).uuid())
+                    .col(
+                        ColumnDef::new(RuntimeSwitches::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_runtime_switches_updated_by")
+                            .from(RuntimeSwitches::Table, RuntimeSwitches::UpdatedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RuntimeSwitches::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for runtime_switches table
+#[derive(DeriveIden)]
+enum RuntimeSwitches {
+    Table,
+    Id,
+    RegistrationOpen,
+    ReadOnly,
+    ReadOnlyReason,
+    UpdatedBy,
+    UpdatedAt,
+}
+
+/// Table and column identifiers for users table (for foreign key)
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Family of a token: the jti of the token the session signed in with,
+        // carried over on every rotation (nullable first for safe migration)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RefreshTokens::Table)
+                    .add_column(ColumnDef::new(RefreshTokens::FamilyId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing tokens each start their own family
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE refresh_tokens SET family_id = id WHERE family_id IS NULL;")
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("ALTER TABLE refresh_tokens ALTER COLUMN family_id SET NOT NULL;")
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_refresh_tokens_family_id")
+                    .table(RefreshTokens::Table)
+                    .col(RefreshTokens::FamilyId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_refresh_tokens_family_id")
+                    .table(RefreshTokens::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RefreshTokens::Table)
+                    .drop_column(RefreshTokens::FamilyId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for refresh_tokens table additions
+#[derive(DeriveIden)]
+enum RefreshTokens {
+    Table,
+    FamilyId,
+}
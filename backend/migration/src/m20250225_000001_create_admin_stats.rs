@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create admin_stats table (single row of user counts, refreshed by a
+        // scheduled job so the dashboard does not count users on every view)
+        manager
+            .create_table(
+                Table::create()
+                    .table(AdminStats::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AdminStats::Id)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AdminStats::TotalUsers)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(AdminStats::VerifiedUsers)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(AdminStats::AdminUsers)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(AdminStats::DisabledUsers)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(AdminStats::RefreshedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .extra("DEFAULT NOW()".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AdminStats::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Table and column identifiers for admin_stats table
+#[derive(DeriveIden)]
+enum AdminStats {
+    Table,
+    Id,
+    TotalUsers,
+    VerifiedUsers,
+    AdminUsers,
+    DisabledUsers,
+    RefreshedAt,
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Client the link was built for (`EMAIL_VERIFICATION_CLIENT_URLS`);
+        // NULL for the web link
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EmailVerifications::Table)
+                    .add_column(
+                        ColumnDef::new(EmailVerifications::Client)
+                            .string_len(50)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EmailVerifications::Table)
+                    .drop_column(EmailVerifications::Client)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Table and column identifiers for email_verifications table additions
+#[derive(DeriveIden)]
+enum EmailVerifications {
+    Table,
+    Client,
+}
@@ -0,0 +1,208 @@
+//! Periodic background jobs started by `main`.
+//!
+//! Each function schedules one job with [`services::scheduler::spawn_periodic`];
+//! whether a job runs at all is decided by the caller from the configuration.
+
+use crate::{application, config, infrastructure, services};
+use sea_orm::DatabaseConnection;
+use std::{sync::Arc, time::Duration};
+
+/// Email sender shared by the jobs that send mail
+type EmailSender = Arc<dyn services::email::EmailSender + Send + Sync>;
+
+/// Reload the suppressed email addresses each `interval`; the first run loads
+/// them at startup.
+pub fn spawn_suppression_refresh(
+    db: &Arc<DatabaseConnection>,
+    list: &Arc<services::email::suppression::SuppressionList>,
+    interval: Duration,
+) {
+    let db = Arc::clone(db);
+    let list = Arc::clone(list);
+    services::scheduler::spawn_periodic("email_suppression_refresh", interval, move || {
+        let db = Arc::clone(&db);
+        let list = Arc::clone(&list);
+        async move {
+            list.refresh(&db).await?;
+            Ok(())
+        }
+    });
+}
+
+/// Pick up registration and read-only switches changed through other
+/// instances; the first run loads them at startup.
+pub fn spawn_switches_refresh(switches: &Arc<services::runtime_switches::RuntimeSwitches>) {
+    let switches = Arc::clone(switches);
+    services::scheduler::spawn_periodic(
+        "runtime_switches_refresh",
+        services::runtime_switches::REFRESH_INTERVAL,
+        move || {
+            let switches = Arc::clone(&switches);
+            async move {
+                switches.refresh().await?;
+                Ok(())
+            }
+        },
+    );
+}
+
+/// Keep the user counts of the admin dashboard fresh instead of counting
+/// users on every view.
+pub fn spawn_admin_stats_refresh(db: &Arc<DatabaseConnection>) {
+    let db = Arc::clone(db);
+    services::scheduler::spawn_periodic(
+        "admin_stats_refresh",
+        services::admin_stats::REFRESH_INTERVAL,
+        move || {
+            let db = Arc::clone(&db);
+            async move {
+                services::admin_stats::refresh(&db).await?;
+                Ok(())
+            }
+        },
+    );
+}
+
+/// Delete expired sandbox accounts with their data.
+pub fn spawn_sandbox_purge(db: &Arc<DatabaseConnection>) {
+    let db = Arc::clone(db);
+    services::scheduler::spawn_periodic(
+        "sandbox_purge",
+        services::sandbox::PURGE_INTERVAL,
+        move || {
+            let db = Arc::clone(&db);
+            async move {
+                let purged = services::sandbox::purge_expired(&db).await?;
+                if purged > 0 {
+                    tracing::info!("Purged {} expired sandbox accounts", purged);
+                }
+                Ok(())
+            }
+        },
+    );
+}
+
+/// Send the weekly activity digests that are due.
+pub fn spawn_email_digests(
+    db: &Arc<DatabaseConnection>,
+    valkey: Option<services::valkey::ValkeyManager>,
+    email_sender: EmailSender,
+    digest_config: config::DigestConfig,
+) {
+    let db = Arc::clone(db);
+    services::scheduler::spawn_periodic("email_digest", digest_config.check_interval, move || {
+        let db = Arc::clone(&db);
+        let valkey = valkey.clone();
+        let email_sender = Arc::clone(&email_sender);
+        let digest_config = digest_config.clone();
+        async move {
+            let sent = services::email::digest::send_due_digests(
+                &db,
+                valkey.as_ref(),
+                email_sender.as_ref(),
+                &digest_config,
+            )
+            .await?;
+            if sent > 0 {
+                tracing::info!("Sent {} email digests", sent);
+            }
+            Ok(())
+        }
+    });
+}
+
+/// Email administrators the periodic stats report when it is due.
+pub fn spawn_stats_report(
+    db: &Arc<DatabaseConnection>,
+    email_sender: EmailSender,
+    report_config: config::StatsReportConfig,
+    model_pricing: &Arc<services::stats_report::ModelPricing>,
+) {
+    let db = Arc::clone(db);
+    let model_pricing = Arc::clone(model_pricing);
+    services::scheduler::spawn_periodic(
+        "admin_stats_report",
+        report_config.check_interval,
+        move || {
+            let db = Arc::clone(&db);
+            let email_sender = Arc::clone(&email_sender);
+            let report_config = report_config.clone();
+            let model_pricing = Arc::clone(&model_pricing);
+            async move {
+                let sent = services::stats_report::send_due_report(
+                    &db,
+                    email_sender.as_ref(),
+                    &report_config,
+                    &model_pricing,
+                )
+                .await?;
+                if sent > 0 {
+                    tracing::info!("Sent admin stats report to {} admins", sent);
+                }
+                Ok(())
+            }
+        },
+    );
+}
+
+/// Delete guests nobody claimed.
+pub fn spawn_guest_purge(db: &Arc<DatabaseConnection>) {
+    let db = Arc::clone(db);
+    services::scheduler::spawn_periodic(
+        "guest_purge",
+        services::auth::guest::PURGE_INTERVAL,
+        move || {
+            let db = Arc::clone(&db);
+            async move {
+                let purged = services::auth::guest::purge_expired(&db).await?;
+                if purged > 0 {
+                    tracing::info!("Purged {} expired guests", purged);
+                }
+                Ok(())
+            }
+        },
+    );
+}
+
+/// Archive the chat sessions of users who stay disabled.
+pub fn spawn_chat_archival(policy: &Arc<application::chat::AccountSuspensionPolicy>) {
+    let policy = Arc::clone(policy);
+    services::scheduler::spawn_periodic(
+        "chat_archival",
+        application::chat::account_suspension::ARCHIVE_CHECK_INTERVAL,
+        move || {
+            let policy = Arc::clone(&policy);
+            async move {
+                let archived = policy.archive_due().await?;
+                if archived > 0 {
+                    tracing::info!("Archived {} sessions of disabled users", archived);
+                }
+                Ok(())
+            }
+        },
+    );
+}
+
+/// Probe every LLM provider each `interval` and store the results in Valkey.
+pub fn spawn_provider_probes(
+    factory: Arc<infrastructure::llm::ProviderFactory>,
+    valkey: services::valkey::ValkeyManager,
+    interval: Duration,
+    timeout: Duration,
+) {
+    // Results survive a couple of missed probes, then expire
+    let ttl_secs = interval.as_secs().saturating_mul(3).max(60);
+
+    services::scheduler::spawn_periodic("llm_provider_probe", interval, move || {
+        let factory = Arc::clone(&factory);
+        let valkey = valkey.clone();
+        async move {
+            let probes = infrastructure::llm::probe::probe_all(&factory, timeout).await;
+            let mut conn = valkey.get_connection()?;
+            for probe in &probes {
+                services::valkey::provider_health::record_probe(&mut conn, probe, ttl_secs)?;
+            }
+            Ok(())
+        }
+    });
+}
@@ -0,0 +1,65 @@
+//! Listeners served next to the public one.
+
+use super::routes::{cors_layer, with_access_log};
+use crate::{config, middleware, server};
+use axum::{middleware as axum_middleware, Router};
+use std::sync::Arc;
+
+/// Serve the operational routes on the internal listener until shutdown.
+///
+/// Plain HTTP on a private interface; connection limits apply to the public
+/// listener only.
+pub async fn spawn_internal_listener(
+    internal: &config::server::InternalListenerConfig,
+    ops_routes: Router,
+    app_config: &config::AppConfig,
+    access_log: Option<&middleware::access_log::AccessLogState>,
+    shutdown: server::Shutdown,
+) -> anyhow::Result<()> {
+    let internal_listener = tokio::net::TcpListener::bind(internal.addr).await?;
+    tracing::info!(
+        "Serving metrics, readiness and admin APIs on internal listener {}",
+        internal.addr
+    );
+    let internal_config = config::ServerConfig {
+        unix_socket: None,
+        max_connections: None,
+        tls: None,
+        ..app_config.server.clone()
+    };
+    let trusted_proxies = Arc::new(app_config.trusted_proxies.clone());
+    let internal_app = ops_routes
+        .layer(axum_middleware::map_response(
+            middleware::timeout::timeout_error_body,
+        ))
+        .layer(axum::Extension(trusted_proxies))
+        .layer(cors_layer())
+        .layer(tower_http::trace::TraceLayer::new_for_http());
+    let internal_app = with_access_log(internal_app, access_log);
+    tokio::spawn(async move {
+        if let Err(e) =
+            server::serve_with_shutdown(internal_listener, internal_app, &internal_config, shutdown)
+                .await
+        {
+            tracing::error!("Internal listener failed: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Redirect plain HTTP to HTTPS when terminating TLS ourselves (if configured)
+pub fn spawn_https_redirect(server_config: &config::ServerConfig) {
+    let Some(http_port) = server_config
+        .tls
+        .as_ref()
+        .and_then(|tls| tls.redirect_http_port)
+    else {
+        return;
+    };
+    let tls_port = server_config.port;
+    tokio::spawn(async move {
+        if let Err(e) = server::redirect::serve_https_redirect(http_port, tls_port).await {
+            tracing::error!("HTTP redirect listener failed: {}", e);
+        }
+    });
+}
@@ -0,0 +1,15 @@
+//! Application wiring.
+//!
+//! Builds what `main` starts the server with: the shared services, the
+//! periodic jobs and the routers of the public and internal listeners.
+//!
+//! - [`state`] - Services built from the configuration (Valkey, token store,
+//!   email delivery, LLM providers, chat state)
+//! - [`jobs`] - Periodic background jobs
+//! - [`routes`] - Public, operational, admin and signed routers
+//! - [`listeners`] - The internal listener and the HTTPS redirect
+
+pub mod jobs;
+pub mod listeners;
+pub mod routes;
+pub mod state;
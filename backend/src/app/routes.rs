@@ -0,0 +1,766 @@
+//! Routers of the public and the internal listener.
+//!
+//! [`create_app`] builds the public router; [`create_ops_routes`] the
+//! operational routes, which it mounts too unless the internal listener
+//! serves them.
+
+use crate::{application, config, dto, handlers, infrastructure, middleware, openapi, services};
+use axum::{
+    extract::DefaultBodyLimit,
+    http::{header, HeaderValue, Method},
+    middleware as axum_middleware,
+    routing::{delete, get, patch, post, put},
+    Router,
+};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tower_http::cors::CorsLayer;
+use utoipa_swagger_ui::{Config as SwaggerConfig, SwaggerUi, Url};
+
+/// API version prefix for all routes
+pub const API_PREFIX: &str = "/api/v1";
+
+/// Create the Axum router with all routes, middleware, and state.
+///
+/// Configures the complete application including:
+/// - Public routes (register, login, refresh)
+/// - Protected routes (profile, logout)
+/// - Operational routes (admin APIs, metrics, readiness) unless served internally
+/// - Read-only mode, see [`middleware::read_only`]
+/// - CORS middleware
+/// - Swagger UI documentation
+///
+/// # Arguments
+///
+/// * `state` - Application state with database connection and JWT config
+/// * `jwt_config` - JWT configuration for authentication middleware
+/// * `metrics` - Request counters and latency budgets checked for every
+///   public request
+/// * `ops_routes` - Routes from [`create_ops_routes`] to mount publicly, or
+///   `None` when they are served on the internal listener
+/// * `app_config` - Request deadlines per route group and enabled subsystems
+///
+/// # Returns
+///
+/// Fully configured Axum [`Router`] ready to serve HTTP requests.
+///
+/// # CORS Configuration
+///
+/// See [`cors_layer`].
+#[allow(clippy::too_many_lines)]
+pub fn create_app(
+    state: handlers::auth::AppState,
+    jwt_config: services::auth::JwtConfig,
+    chat_state: Option<handlers::chat::ChatState>,
+    rate_limit_state: Option<middleware::chat_rate_limit::ChatRateLimitState>,
+    metrics: handlers::metrics::MetricsState,
+    ops_routes: Option<Router>,
+    app_config: &config::AppConfig,
+) -> Router {
+    use middleware::access::{AccessGuards, SecuredRouter};
+    use middleware::timeout::request_timeout;
+
+    let timeouts = &app_config.request_timeouts;
+    let branding_state = branding_state(&state, app_config);
+    let switches = Arc::clone(&state.switches);
+    let guards = AccessGuards {
+        jwt_config,
+        users: state.users.clone(),
+    };
+
+    // Auth routes, guarded by the access declared for each
+    let mut auth_routes = SecuredRouter::new()
+        .route(
+            &format!("{API_PREFIX}/auth/register"),
+            post(handlers::auth::register),
+        )
+        .route(
+            &format!("{API_PREFIX}/auth/login"),
+            post(handlers::auth::login),
+        )
+        .route(
+            &format!("{API_PREFIX}/auth/refresh"),
+            post(handlers::auth::refresh_token),
+        );
+    if state.trusted_devices.is_some() {
+        auth_routes = auth_routes.route(
+            &format!("{API_PREFIX}/auth/devices/confirm"),
+            post(handlers::auth::confirm_device),
+        );
+    }
+    if app_config.enable_email {
+        auth_routes = auth_routes
+            .route(
+                &format!("{API_PREFIX}/auth/verify-email"),
+                post(handlers::auth::verify_email),
+            )
+            .route(
+                &format!("{API_PREFIX}/email/unsubscribe"),
+                get(handlers::email::unsubscribe_page).post(handlers::email::unsubscribe),
+            );
+    }
+    if let (Some(suppressions), Some(secret)) = (
+        &state.email_suppressions,
+        app_config
+            .email_bounce
+            .as_ref()
+            .and_then(|bounce_config| bounce_config.webhook_secret.clone()),
+    ) {
+        auth_routes = auth_routes.route(
+            &format!("{API_PREFIX}/email/bounces"),
+            post(handlers::email::bounce_webhook).with_state(handlers::email::BounceWebhookState {
+                db: Arc::clone(&state.db),
+                suppressions: Arc::clone(suppressions),
+                secret,
+            }),
+        );
+    }
+    // Guests chat through the chat routes, so guest mode needs chat
+    if let (Some(guest_config), true) = (&app_config.guest, chat_state.is_some()) {
+        let guest_state = handlers::guest::GuestState {
+            db: Arc::clone(&state.db),
+            jwt_config: state.jwt_config.clone(),
+            config: *guest_config,
+        };
+        auth_routes = auth_routes
+            .route(
+                &format!("{API_PREFIX}/auth/guest"),
+                post(handlers::guest::create_guest_token).with_state(guest_state.clone()),
+            )
+            .route(
+                &format!("{API_PREFIX}/auth/guest/claim"),
+                post(handlers::guest::claim_guest).with_state(guest_state),
+            );
+    }
+    if let Some(oauth_config) = &app_config.oauth {
+        let providers = services::oauth::OAuthProviders::from_config(oauth_config)
+            .expect("Failed to create the HTTP client for sign-in providers");
+        tracing::info!(
+            "Social login enabled: {}",
+            providers.names().collect::<Vec<_>>().join(", ")
+        );
+        let oauth_state = handlers::oauth::OAuthState {
+            auth: state.clone(),
+            providers,
+        };
+        auth_routes = auth_routes
+            .route(
+                &format!("{API_PREFIX}/auth/oauth/:provider/authorize"),
+                get(handlers::oauth::authorize).with_state(oauth_state.clone()),
+            )
+            .route(
+                &format!("{API_PREFIX}/auth/oauth/:provider/callback"),
+                get(handlers::oauth::callback).with_state(oauth_state),
+            );
+    }
+    auth_routes = auth_routes
+        .route(
+            &format!("{API_PREFIX}/auth/me"),
+            get(handlers::auth::get_current_user).patch(handlers::auth::update_profile),
+        )
+        .route(
+            &format!("{API_PREFIX}/auth/logout"),
+            post(handlers::auth::logout),
+        )
+        .route(
+            &format!("{API_PREFIX}/auth/logout-all"),
+            post(handlers::auth::logout_all),
+        )
+        .route(
+            &format!("{API_PREFIX}/auth/sessions"),
+            get(handlers::auth::list_sessions),
+        )
+        .route(
+            &format!("{API_PREFIX}/auth/sessions/:id"),
+            delete(handlers::auth::revoke_session),
+        )
+        .route(
+            middleware::auth::PASSWORD_CHANGE_PATH,
+            post(handlers::auth::change_password),
+        )
+        .route(
+            &format!("{API_PREFIX}/auth/me/preferences"),
+            get(handlers::preferences::get_preferences)
+                .patch(handlers::preferences::update_preferences)
+                .layer(DefaultBodyLimit::max(
+                    services::preferences::MAX_PREFERENCES_BYTES,
+                )),
+        );
+    if app_config.enable_email {
+        auth_routes = auth_routes
+            .route(
+                &format!("{API_PREFIX}/auth/send-verification"),
+                post(handlers::auth::send_verification_email),
+            )
+            .route(
+                &format!("{API_PREFIX}/email/digest"),
+                get(handlers::email::get_digest_preference)
+                    .put(handlers::email::update_digest_preference),
+            );
+    }
+    if state.trusted_devices.is_some() {
+        auth_routes = auth_routes
+            .route(
+                &format!("{API_PREFIX}/auth/devices"),
+                get(handlers::auth::list_trusted_devices),
+            )
+            .route(
+                &format!("{API_PREFIX}/auth/devices/:id"),
+                delete(handlers::auth::remove_trusted_device),
+            );
+    }
+    let auth_routes = auth_routes
+        .with_state(state)
+        .guard(&guards)
+        .layer(request_timeout(timeouts, timeouts.auth));
+
+    // Health check, branding and API docs use the default deadline
+    let mut base_routes = SecuredRouter::new()
+        .route(
+            "/health",
+            get(handlers::health::health_check).with_state(active_modules(app_config)),
+        )
+        .route(
+            &format!("{API_PREFIX}/branding"),
+            get(handlers::branding::get_branding).with_state(branding_state),
+        );
+    if app_config.serve_typescript_types {
+        tracing::warn!("Serving TypeScript types at /openapi/types.ts - development only");
+        let types = openapi::typescript::typescript_types(&openapi::Audience::Admin.document());
+        base_routes = base_routes.route(
+            "/openapi/types.ts",
+            get(handlers::openapi::typescript_types).with_state(axum::body::Bytes::from(types)),
+        );
+    }
+    let base_routes = base_routes
+        .guard(&guards)
+        .merge(swagger_ui(app_config.json_case))
+        .layer(request_timeout(timeouts, timeouts.default));
+
+    let mut app = base_routes.merge(auth_routes);
+
+    if let Some(ops_routes) = ops_routes {
+        app = app.merge(ops_routes);
+    }
+
+    // Add chat routes if feature is enabled
+    if let Some(chat_state) = chat_state {
+        tracing::info!("Chat feature enabled - mounting chat routes");
+
+        // Polling generations and jobs is not a chat message, so no rate
+        // limiting; messages over the WebSocket are limited one by one
+        let chat_state = handlers::chat::ChatState {
+            rate_limit: rate_limit_state.clone(),
+            ..chat_state
+        };
+        let chat_routes = handlers::chat::public_routes(chat_state.clone())
+            .merge(handlers::chat::polling_routes(chat_state.clone()))
+            .merge(handlers::chat::socket_routes(chat_state.clone()));
+
+        // Chat message routes, rate limited when Valkey is available
+        let mut chat_protected_routes = handlers::chat::routes_v2(chat_state);
+        if let Some(rate_limit_state) = rate_limit_state {
+            // Notification inbox (quota warnings are the only producer so far)
+            let notification_routes = SecuredRouter::new()
+                .route(
+                    &format!("{API_PREFIX}/notifications"),
+                    get(handlers::notifications::list_notifications),
+                )
+                .with_state(rate_limit_state.valkey.clone())
+                .guard(&guards)
+                .layer(request_timeout(timeouts, timeouts.default));
+            app = app.merge(notification_routes);
+
+            chat_protected_routes =
+                chat_protected_routes.layer(axum_middleware::from_fn_with_state(
+                    rate_limit_state,
+                    middleware::chat_rate_limit::chat_rate_limit_middleware,
+                ));
+        } else {
+            tracing::warn!("Valkey unavailable - chat runs without rate limiting");
+        }
+        let chat_routes = chat_routes
+            .merge(chat_protected_routes)
+            .guard(&guards)
+            .layer(request_timeout(timeouts, timeouts.chat));
+
+        app = app.nest(handlers::chat::PREFIX, chat_routes);
+    } else {
+        tracing::info!("Chat feature disabled");
+    }
+
+    // Build main router
+    let trusted_proxies = Arc::new(app_config.trusted_proxies.clone());
+    let app = app
+        .layer(axum_middleware::from_fn_with_state(
+            switches,
+            middleware::read_only::reject_writes,
+        ))
+        .layer(axum_middleware::map_response(
+            middleware::timeout::timeout_error_body,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            metrics.latency,
+            middleware::latency_budget::check_latency_budget,
+        ));
+    let app = if app_config.enable_metrics {
+        app.layer(axum_middleware::from_fn_with_state(
+            metrics.http,
+            middleware::metrics::track_metrics,
+        ))
+    } else {
+        app
+    };
+    app.layer(axum::Extension(trusted_proxies))
+        .layer(cors_layer())
+        .layer(tower_http::trace::TraceLayer::new_for_http())
+}
+
+/// Start the access log writer for the configured sink, and the retention
+/// purge when records go to the database
+pub fn access_log_state(
+    access_log_config: config::AccessLogConfig,
+    db: &Arc<DatabaseConnection>,
+    jwt_config: &services::auth::JwtConfig,
+    app_config: &config::AppConfig,
+) -> middleware::access_log::AccessLogState {
+    use services::access_log::{
+        purge_expired, AccessLogSink, AccessLogger, DatabaseAccessLogSink, TracingAccessLogSink,
+    };
+
+    tracing::info!(?access_log_config, "Access log enabled");
+    let sink: Arc<dyn AccessLogSink> = match access_log_config.sink {
+        config::AccessLogSinkKind::Database => {
+            if let Some(retention) = access_log_config.retention {
+                let db = Arc::clone(db);
+                services::scheduler::spawn_periodic(
+                    "access_log_purge",
+                    access_log_config.purge_interval,
+                    move || {
+                        let db = Arc::clone(&db);
+                        async move {
+                            let purged = purge_expired(&db, retention).await?;
+                            if purged > 0 {
+                                tracing::info!("Purged {} expired access records", purged);
+                            }
+                            Ok(())
+                        }
+                    },
+                );
+            }
+            Arc::new(DatabaseAccessLogSink::new(Arc::clone(db)))
+        }
+        config::AccessLogSinkKind::Log => Arc::new(TracingAccessLogSink),
+    };
+
+    middleware::access_log::AccessLogState {
+        logger: AccessLogger::spawn(access_log_config, sink),
+        jwt_config: jwt_config.clone(),
+        trusted_proxies: Arc::new(app_config.trusted_proxies.clone()),
+    }
+}
+
+/// Wrap a router in the access log middleware (if enabled)
+///
+/// Added outermost, so the recorded latency covers every other layer.
+pub fn with_access_log(
+    router: Router,
+    access_log: Option<&middleware::access_log::AccessLogState>,
+) -> Router {
+    match access_log {
+        Some(state) => router.layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            middleware::access_log::record_access,
+        )),
+        None => router,
+    }
+}
+
+/// Shared services the admin API depends on, built once in `main`
+pub struct AdminDeps {
+    pub tokenizers: Arc<services::tokenizer::TokenizerService>,
+    pub account_hooks: Vec<Arc<dyn application::account::AccountLifecycleHook>>,
+    pub model_pricing: Arc<services::stats_report::ModelPricing>,
+    pub effective_config: Arc<services::effective_config::EffectiveConfig>,
+}
+
+/// Create the operational routes: admin APIs (if enabled), `/metrics`, `/health/ready` and
+/// `/health/detailed`.
+///
+/// Mounted on the internal listener when `INTERNAL_LISTEN_ADDR` is set so they
+/// are never reachable through the public listener, otherwise merged into the
+/// public router by [`create_app`].
+pub fn create_ops_routes(
+    state: &handlers::auth::AppState,
+    jwt_config: &services::auth::JwtConfig,
+    metrics: handlers::metrics::MetricsState,
+    readiness: handlers::health::ReadinessState,
+    admin_deps: AdminDeps,
+    app_config: &config::AppConfig,
+) -> Router {
+    let timeouts = &app_config.request_timeouts;
+    let guards = middleware::access::AccessGuards {
+        jwt_config: jwt_config.clone(),
+        users: state.users.clone(),
+    };
+
+    let ops_routes = middleware::access::SecuredRouter::new()
+        .route(
+            "/health/ready",
+            get(handlers::health::readiness_check).with_state(readiness.clone()),
+        )
+        .route(
+            "/ready",
+            get(handlers::health::readiness_check).with_state(readiness.clone()),
+        )
+        .route(
+            "/health/detailed",
+            get(handlers::health::detailed_health_check).with_state(readiness),
+        );
+    let ops_routes = if app_config.enable_metrics {
+        ops_routes.route(
+            "/metrics",
+            get(handlers::metrics::metrics).with_state(metrics),
+        )
+    } else {
+        tracing::info!("Metrics disabled");
+        ops_routes
+    }
+    .guard(&guards);
+
+    let ops_routes = if app_config.enable_admin_api {
+        ops_routes.merge(create_admin_routes(state, &guards, admin_deps, app_config))
+    } else {
+        tracing::info!("Admin API disabled");
+        ops_routes
+    };
+
+    ops_routes.layer(middleware::timeout::request_timeout(
+        timeouts,
+        timeouts.default,
+    ))
+}
+
+/// Create the admin API routes (protected - requires `admin:access` and the
+/// permission of each operation, see [`services::rbac`]).
+#[allow(clippy::too_many_lines)]
+pub fn create_admin_routes(
+    state: &handlers::auth::AppState,
+    guards: &middleware::access::AccessGuards,
+    admin_deps: AdminDeps,
+    app_config: &config::AppConfig,
+) -> Router {
+    use services::rbac;
+
+    let AdminDeps {
+        tokenizers,
+        account_hooks,
+        model_pricing,
+        effective_config,
+    } = admin_deps;
+    let debug_tokens_enabled = app_config.admin_debug_tokens;
+    if debug_tokens_enabled {
+        tracing::warn!("Admin debug tokens enabled - do not use in production");
+    }
+
+    let admin_state = handlers::admin::AdminState {
+        db: state.db.clone(),
+        jwt_config: guards.jwt_config.clone(),
+        debug_tokens_enabled,
+        modules: active_modules(app_config),
+        email_sender: state.email_sender.clone(),
+        email_suppressions: state.email_suppressions.clone(),
+        lifecycle_hooks: account_hooks,
+        model_pricing,
+        effective_config,
+        object_storage: Arc::new(infrastructure::object_storage::LocalObjectStorage::new(
+            app_config.object_storage.root.clone(),
+        )),
+        switches: Arc::clone(&state.switches),
+        token_store: Arc::clone(&state.token_store),
+        verification_links: Arc::clone(&state.verification_links),
+        users: state.users.clone(),
+    };
+
+    // Each operation requires its permission on top of `admin:access`
+    let checker = rbac::PermissionChecker::new(Arc::clone(&state.db));
+    let require = |permission: &'static str| {
+        axum_middleware::from_fn_with_state(
+            middleware::permission::RequiredPermission::new(checker.clone(), permission),
+            middleware::permission::require_permission,
+        )
+    };
+
+    let admin_routes = middleware::access::SecuredRouter::new()
+        .route(
+            &format!("{API_PREFIX}/admin/users"),
+            get(handlers::admin::list_users)
+                .layer(require(rbac::USERS_READ))
+                .merge(post(handlers::admin::create_user).layer(require(rbac::USERS_WRITE))),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/users/:id"),
+            get(handlers::admin::get_user)
+                .layer(require(rbac::USERS_READ))
+                .merge(delete(handlers::admin::delete_user).layer(require(rbac::USERS_WRITE))),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/users/:id/role"),
+            patch(handlers::admin::update_user_role).layer(require(rbac::ROLES_WRITE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/users/:id/disable"),
+            patch(handlers::admin::disable_user).layer(require(rbac::USERS_WRITE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/users/:id/enable"),
+            patch(handlers::admin::enable_user).layer(require(rbac::USERS_WRITE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/users/:id/require-password-change"),
+            patch(handlers::admin::require_password_change).layer(require(rbac::USERS_WRITE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/users/:id/roles"),
+            get(handlers::admin::get_user_roles)
+                .layer(require(rbac::USERS_READ))
+                .merge(put(handlers::admin::assign_user_roles).layer(require(rbac::ROLES_WRITE))),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/roles"),
+            get(handlers::admin::list_roles)
+                .layer(require(rbac::USERS_READ))
+                .merge(post(handlers::admin::create_role).layer(require(rbac::ROLES_WRITE))),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/roles/:id"),
+            delete(handlers::admin::delete_role).layer(require(rbac::ROLES_WRITE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/stats"),
+            get(handlers::admin::get_stats).layer(require(rbac::STATS_READ)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/stats/export"),
+            get(handlers::admin::export_stats).layer(require(rbac::STATS_READ)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/debug-token"),
+            post(handlers::admin::create_debug_token).layer(require(rbac::DEBUG_TOKENS)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/email-verifications"),
+            get(handlers::admin::list_email_verifications).layer(require(rbac::EMAIL_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/email-verifications/:id/resend"),
+            post(handlers::admin::resend_email_verification).layer(require(rbac::EMAIL_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/email-verifications/:id/verify"),
+            post(handlers::admin::force_verify_user_email).layer(require(rbac::EMAIL_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/email-suppressions"),
+            get(handlers::admin::list_email_suppressions).layer(require(rbac::EMAIL_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/email-suppressions/:id"),
+            delete(handlers::admin::lift_email_suppression).layer(require(rbac::EMAIL_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/email-log"),
+            get(handlers::admin::list_email_log).layer(require(rbac::EMAIL_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/backup"),
+            post(handlers::admin::create_backup).layer(require(rbac::BACKUP_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/backup/exports"),
+            post(handlers::admin::create_backup_export).layer(require(rbac::BACKUP_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/backup/exports/:id"),
+            get(handlers::admin::download_backup_export)
+                .delete(handlers::admin::delete_backup_export)
+                .layer(require(rbac::BACKUP_MANAGE)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/system/doctor"),
+            get(handlers::admin::system_doctor).layer(require(rbac::SYSTEM_READ)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/system/info"),
+            get(handlers::admin::system_info).layer(require(rbac::SYSTEM_READ)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/system/switches"),
+            get(handlers::admin::get_runtime_switches)
+                .layer(require(rbac::SYSTEM_READ))
+                .merge(
+                    put(handlers::admin::update_runtime_switches)
+                        .layer(require(rbac::SYSTEM_WRITE)),
+                ),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/access-logs"),
+            get(handlers::admin::list_access_logs).layer(require(rbac::ACCESS_LOGS_READ)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/audit-logs"),
+            get(handlers::admin::list_audit_logs).layer(require(rbac::AUDIT_LOGS_READ)),
+        )
+        .route(
+            &format!("{API_PREFIX}/admin/backup/restore"),
+            post(handlers::admin::restore_backup)
+                .layer(DefaultBodyLimit::max(services::backup::MAX_ARCHIVE_BYTES))
+                .layer(require(rbac::BACKUP_MANAGE)),
+        )
+        .with_state(admin_state.clone())
+        .route(
+            &format!("{API_PREFIX}/admin/branding"),
+            put(handlers::branding::update_branding)
+                .delete(handlers::branding::reset_branding)
+                .with_state(branding_state(state, app_config))
+                .layer(require(rbac::BRANDING_WRITE)),
+        )
+        .guard(guards);
+
+    match &app_config.request_signing {
+        Some(signing) => admin_routes.merge(create_signed_routes(admin_state, tokenizers, signing)),
+        None => admin_routes,
+    }
+}
+
+/// Read-only admin endpoints and token counting for sidecar services,
+/// authenticated by an HMAC request signature instead of a user JWT.
+pub fn create_signed_routes(
+    admin_state: handlers::admin::AdminState,
+    tokenizers: Arc<services::tokenizer::TokenizerService>,
+    signing: &config::RequestSigningConfig,
+) -> Router {
+    tracing::info!(?signing, "Signed internal routes enabled");
+    let verifier = Arc::new(middleware::request_signing::SignatureVerifier::new(
+        signing.clone(),
+    ));
+
+    Router::new()
+        .route(
+            &format!("{API_PREFIX}/internal/users"),
+            get(handlers::admin::list_users),
+        )
+        .route(
+            &format!("{API_PREFIX}/internal/users/:id"),
+            get(handlers::admin::get_user),
+        )
+        .route(
+            &format!("{API_PREFIX}/internal/stats"),
+            get(handlers::admin::get_stats),
+        )
+        .with_state(admin_state)
+        .route(
+            &format!("{API_PREFIX}/internal/tokens/count"),
+            post(handlers::tokenizer::count_tokens).with_state(tokenizers),
+        )
+        .layer(axum_middleware::from_fn_with_state(
+            verifier,
+            middleware::request_signing::signature_middleware,
+        ))
+}
+
+/// Branding state shared by the public and admin branding routes.
+#[must_use]
+pub fn branding_state(
+    state: &handlers::auth::AppState,
+    app_config: &config::AppConfig,
+) -> handlers::branding::BrandingState {
+    handlers::branding::BrandingState {
+        db: Arc::clone(&state.db),
+        defaults: app_config.branding.clone(),
+        max_age: app_config.http_cache.branding_max_age,
+    }
+}
+
+/// Summarize which optional subsystems are enabled.
+#[must_use]
+pub const fn active_modules(app_config: &config::AppConfig) -> dto::health::ActiveModules {
+    dto::health::ActiveModules {
+        chat: app_config.enable_chat,
+        admin_api: app_config.enable_admin_api,
+        email: app_config.enable_email,
+    }
+}
+
+/// Swagger UI with one spec per [`openapi::Audience`], authenticated selected first
+///
+/// Each spec only offers the security schemes of its audience in the
+/// "Authorize" dialog. Entered credentials persist across reloads, and "Try
+/// it out" requests send the refresh cookie. With the `camelCase` API enabled,
+/// each audience also gets a `/api/v2` spec.
+#[must_use]
+pub fn swagger_ui(json_case: config::JsonCase) -> SwaggerUi {
+    let mut urls: Vec<_> = openapi::Audience::ALL
+        .into_iter()
+        .map(|audience| {
+            let url = Url::with_primary(
+                audience.as_str(),
+                audience.spec_path(),
+                audience == openapi::Audience::Authenticated,
+            );
+            (url, audience.document())
+        })
+        .collect();
+    if json_case == config::JsonCase::Camel {
+        urls.extend(openapi::Audience::ALL.into_iter().map(|audience| {
+            let url = Url::new(
+                openapi::json_case::spec_name(audience),
+                openapi::json_case::spec_path(audience),
+            );
+            (url, openapi::json_case::camel_case(&audience.document()))
+        }));
+    }
+
+    SwaggerUi::new("/swagger-ui").urls(urls).config(
+        SwaggerConfig::default()
+            .persist_authorization(true)
+            .with_credentials(true),
+    )
+}
+
+/// Configure CORS with credentials support.
+///
+/// Allows requests from origins ending with `:2727` (frontend port) for development.
+/// In production, configure specific allowed origins via `CORS_ORIGINS`.
+pub fn cors_layer() -> CorsLayer {
+    // Get allowed origins from environment variable
+    let allowed_origins = std::env::var("CORS_ORIGINS")
+        .unwrap_or_else(|_| services::doctor::DEFAULT_CORS_ORIGINS.to_string());
+
+    let origins: Vec<HeaderValue> = allowed_origins
+        .split(',')
+        .filter_map(|origin| origin.trim().parse().ok())
+        .collect();
+
+    tracing::info!("CORS allowed origins: {:?}", origins);
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(vec![
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers(vec![
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::ACCEPT,
+            header::COOKIE,
+            header::HeaderName::from_static(handlers::admin::BACKUP_PASSPHRASE_HEADER),
+            header::HeaderName::from_static(services::auth::dpop::DPOP_HEADER),
+        ])
+        .allow_credentials(true)
+}
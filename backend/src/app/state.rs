@@ -0,0 +1,297 @@
+//! Services `main` builds from the configuration and hands to the routers.
+
+use crate::{application, config, domain, handlers, infrastructure, middleware, server, services};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+/// Connect to `VALKEY_URL` (default: `redis://127.0.0.1:6379`), failing a share
+/// of calls on purpose when fault injection is enabled.
+pub fn valkey_manager(
+    faults: Option<&Arc<infrastructure::fault_injection::FaultInjector>>,
+) -> anyhow::Result<services::valkey::ValkeyManager> {
+    let valkey_url =
+        std::env::var("VALKEY_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    let mut manager = services::valkey::ValkeyManager::with_pool_size(
+        &valkey_url,
+        services::valkey::pool_size_from_env(),
+    )?;
+    if let Some(faults) = faults {
+        manager = manager.with_faults(Arc::clone(faults));
+    }
+    tracing::info!("Valkey connected");
+    Ok(manager)
+}
+
+/// Refresh token store: Valkey when configured and connected, the database otherwise
+pub fn token_store(
+    db: &Arc<DatabaseConnection>,
+    valkey: Option<&services::valkey::ValkeyManager>,
+    tokens_in_valkey: bool,
+) -> Arc<dyn services::auth::TokenStore> {
+    match valkey {
+        Some(valkey) if tokens_in_valkey => {
+            tracing::info!("Refresh tokens stored in Valkey");
+            Arc::new(services::valkey::token_store::ValkeyTokenStore::new(
+                valkey.clone(),
+            ))
+        }
+        _ => {
+            if tokens_in_valkey {
+                tracing::warn!(
+                    "Demo mode runs without Valkey - refresh tokens stored in the database"
+                );
+            }
+            Arc::new(services::auth::SeaOrmTokenStore::new(Arc::clone(db)))
+        }
+    }
+}
+
+/// Email delivery skipping suppressed addresses and recording every send in
+/// the email log
+pub fn email_sender(
+    db: &Arc<DatabaseConnection>,
+    suppressions: &Arc<services::email::suppression::SuppressionList>,
+) -> Arc<dyn services::email::EmailSender + Send + Sync> {
+    let logger = services::email::delivery_log::DeliveryLogger::spawn(Arc::clone(db));
+    let logging = services::email::delivery_log::LoggingEmailSender::new(
+        Arc::new(services::email::MockEmailSender),
+        logger,
+    );
+    Arc::new(services::email::suppression::SuppressingEmailSender::new(
+        Arc::new(logging),
+        Arc::clone(suppressions),
+    ))
+}
+
+/// Trusted device policy, `None` without email to deliver confirmations
+pub fn trusted_device_policy(enable_email: bool) -> Option<services::auth::TrustedDevicePolicy> {
+    let policy = services::auth::TrustedDevicePolicy::from_env()?;
+    if !enable_email {
+        tracing::warn!(
+            "TRUSTED_DEVICES_ENABLED needs FEATURE_EMAIL_ENABLED to send confirmations - disabled"
+        );
+        return None;
+    }
+    Some(policy)
+}
+
+/// Provider factory for the LLM models in `models.toml`, reloaded on `SIGHUP`
+pub fn provider_factory(
+    faults: Option<&Arc<infrastructure::fault_injection::FaultInjector>>,
+) -> anyhow::Result<Arc<infrastructure::llm::ProviderFactory>> {
+    match infrastructure::llm::ProviderFactory::new() {
+        Ok(factory) => {
+            tracing::info!("LLM Provider Factory initialized successfully");
+            let factory = match faults {
+                Some(faults) => factory.with_faults(faults),
+                None => factory,
+            };
+            let factory = Arc::new(factory);
+            #[cfg(unix)]
+            infrastructure::llm::factory::spawn_sighup_reload(Arc::clone(&factory))?;
+            Ok(factory)
+        }
+        Err(e) => {
+            tracing::error!("Failed to initialize Provider Factory: {}", e);
+            anyhow::bail!("Provider Factory initialization failed: {e}");
+        }
+    }
+}
+
+/// Probe the LLM providers on a schedule (if configured) and return the
+/// readiness check reading the results
+#[must_use]
+pub fn provider_probes(
+    chat_config: &config::ChatConfig,
+    factory: &Arc<infrastructure::llm::ProviderFactory>,
+    valkey: &services::valkey::ValkeyManager,
+) -> Option<handlers::health::ProviderProbeCheck> {
+    let interval = chat_config.provider_probe_interval?;
+    super::jobs::spawn_provider_probes(
+        Arc::clone(factory),
+        valkey.clone(),
+        interval,
+        chat_config.provider_probe_timeout,
+    );
+    Some(handlers::health::ProviderProbeCheck {
+        valkey: valkey.clone(),
+        providers: factory
+            .probe_targets()
+            .into_iter()
+            .map(|(name, _, _)| name)
+            .collect(),
+        critical: chat_config.critical,
+    })
+}
+
+/// Shared services the chat state depends on, built once in `main`
+pub struct ChatDeps {
+    pub provider_factory: Arc<infrastructure::llm::ProviderFactory>,
+    /// `None` in demo mode, which runs without Valkey
+    pub valkey: Option<services::valkey::ValkeyManager>,
+    pub tokenizers: Arc<services::tokenizer::TokenizerService>,
+    pub stream_metrics: Arc<application::chat::StreamMetrics>,
+    pub token_metrics: Arc<application::chat::event_subscribers::TokenMetrics>,
+    pub model_pricing: Arc<services::stats_report::ModelPricing>,
+    pub shutdown: server::Shutdown,
+}
+
+/// Create the chat state, starting the batch job workers of this instance.
+///
+/// The rate limit is left unset; [`super::routes::create_app`] sets it with
+/// the rate limit middleware.
+#[must_use]
+#[allow(clippy::too_many_lines)]
+pub fn chat_state(
+    chat_config: &config::ChatConfig,
+    app_config: &config::AppConfig,
+    db: &Arc<DatabaseConnection>,
+    deps: ChatDeps,
+) -> handlers::chat::ChatState {
+    let ChatDeps {
+        provider_factory,
+        valkey,
+        tokenizers,
+        stream_metrics,
+        token_metrics,
+        model_pricing,
+        shutdown,
+    } = deps;
+    let chat_repository = Arc::new(infrastructure::persistence::SeaOrmChatRepository::new(
+        Arc::clone(db),
+    ));
+
+    // Run queued batch jobs (other instances may run them instead)
+    let job_runner = Arc::new(application::chat::JobRunner::new(
+        Arc::clone(&chat_repository) as Arc<_>,
+        Arc::clone(&provider_factory),
+        Arc::clone(&tokenizers),
+        chat_config.llm.max_tokens,
+        chat_config.jobs.provider_concurrency,
+    ));
+    if chat_config.jobs.workers > 0 {
+        job_runner.spawn(chat_config.jobs.workers);
+    }
+
+    // Signed callbacks to the users' completion webhooks
+    let webhooks = Arc::new(
+        application::chat::webhooks::WebhookDispatcher::new(
+            Arc::clone(&chat_repository) as Arc<_>,
+            application::chat::webhooks::WebhookSettings {
+                max_per_user: chat_config.webhooks.max_per_user,
+                max_attempts: chat_config.webhooks.max_attempts,
+                timeout: chat_config.webhooks.timeout,
+                content_chars: chat_config.webhooks.content_chars,
+                allow_private_urls: chat_config.webhooks.allow_private_urls,
+            },
+        )
+        .expect("Failed to build the webhook HTTP client"),
+    );
+
+    // Side effects of the chat lifecycle, run off the request path
+    let mut subscribers: Vec<Arc<dyn application::chat::events::EventSubscriber>> = vec![
+        Arc::new(application::chat::event_subscribers::UsageAccounting::new(
+            Arc::clone(&chat_repository) as Arc<_>,
+        )),
+        Arc::new(application::chat::event_subscribers::SessionTitles::new(
+            Arc::clone(&chat_repository) as Arc<_>,
+        )),
+        token_metrics,
+        Arc::new(
+            application::chat::event_subscribers::CompletionWebhooks::new(
+                Arc::clone(&chat_repository) as Arc<_>,
+                Arc::clone(&chat_repository) as Arc<_>,
+                Arc::clone(&webhooks),
+            ),
+        ),
+    ];
+    // The notification inbox lives in Valkey, absent in demo mode
+    if let Some(valkey) = &valkey {
+        subscribers.push(Arc::new(
+            application::chat::event_subscribers::FailureNotifier::new(
+                Arc::clone(&chat_repository) as Arc<_>,
+                valkey.clone(),
+            ),
+        ));
+    }
+
+    handlers::chat::ChatState {
+        repository: chat_repository,
+        llm_config: chat_config.llm.clone(),
+        provider_factory,
+        session_lock: match valkey.clone() {
+            Some(valkey) => Arc::new(infrastructure::session_lock::ValkeySessionLock::new(
+                valkey,
+                chat_config.session_lock_ttl,
+            )),
+            // Demo mode runs without Valkey
+            None => Arc::new(infrastructure::session_lock::InMemorySessionLock::new()),
+        },
+        session_lock_policy: chat_config.session_lock_policy,
+        share_signer: domain::chat::share::ShareSigner::new(chat_config.share_secret.clone()),
+        share_attempts: match valkey {
+            Some(valkey) => {
+                Arc::new(infrastructure::share_attempts::ValkeySharePasswordAttempts::new(valkey))
+            }
+            None => Arc::new(infrastructure::share_attempts::InMemorySharePasswordAttempts::new()),
+        },
+        policy: domain::chat::ChatPolicy {
+            max_message_length: chat_config.max_message_length,
+            max_import_messages: chat_config.max_import_messages,
+        },
+        message_deletion: chat_config.message_deletion,
+        generations: Arc::new(application::chat::GenerationStore::new()),
+        stream_metrics,
+        stream_heartbeat_interval: chat_config.stream_heartbeat_interval,
+        stream_idle_timeout: chat_config.stream_idle_timeout,
+        tokenizers,
+        models_max_age: app_config.http_cache.models_max_age,
+        job_limits: application::chat::batch_jobs::BatchJobLimits {
+            max_prompts: chat_config.jobs.max_prompts,
+            max_prompt_length: chat_config.max_message_length,
+            max_tokens: chat_config.llm.max_tokens,
+            active_jobs_per_user: chat_config.jobs.max_active,
+        },
+        job_runner,
+        model_pricing,
+        events: Arc::new(application::chat::EventBus::new(subscribers)),
+        webhooks,
+        guests: app_config.guest.as_ref().map(|guest| {
+            services::auth::guest::GuestQuota::new(Arc::clone(db), guest.max_messages)
+        }),
+        // Set with the rate limit middleware in `create_app`
+        rate_limit: None,
+        typing_relay: tokio::sync::broadcast::channel(handlers::chat::TYPING_RELAY_CAPACITY).0,
+        shutdown,
+    }
+}
+
+/// Per-minute limit and daily quota of chat messages, with the tighter
+/// sandbox limits for sandbox accounts (if sandbox mode is enabled)
+pub fn rate_limit_state(
+    valkey: services::valkey::ValkeyManager,
+    chat_config: &config::ChatConfig,
+    app_config: &config::AppConfig,
+    db: &Arc<DatabaseConnection>,
+    metrics: Arc<middleware::chat_rate_limit::RateLimitMetrics>,
+) -> middleware::chat_rate_limit::ChatRateLimitState {
+    middleware::chat_rate_limit::ChatRateLimitState {
+        valkey,
+        config: services::valkey::chat_rate_limit::ChatRateLimitConfig {
+            rate_limit_per_minute: chat_config.rate_limit_per_minute,
+            daily_message_quota: chat_config.daily_message_quota,
+            quota_warning_thresholds: chat_config.quota_warning_thresholds.clone(),
+        },
+        sandbox: app_config.sandbox.as_ref().map(|sandbox| {
+            middleware::chat_rate_limit::SandboxRateLimit {
+                db: Arc::clone(db),
+                config: services::valkey::chat_rate_limit::ChatRateLimitConfig {
+                    rate_limit_per_minute: sandbox.rate_limit_per_minute,
+                    daily_message_quota: sandbox.daily_message_quota,
+                    quota_warning_thresholds: chat_config.quota_warning_thresholds.clone(),
+                },
+            }
+        }),
+        metrics,
+    }
+}
@@ -0,0 +1,210 @@
+//! Current user profile use case (`GET /auth/me`)
+//!
+//! Assembles everything the frontend needs on load in one response: the
+//! user record, preferences, effective permissions, quota usage and, in
+//! sandbox mode, the banner and expiry of the account.
+
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+
+use crate::config::SandboxConfig;
+use crate::dto::auth::{Permission, QuotaSummary, SandboxStatus, UserResponse};
+use crate::dto::health::ActiveModules;
+use crate::middleware::auth::AuthUser;
+use crate::models::{sea_orm_active_enums::UserRole, users};
+use crate::services::auth::AuthError;
+use crate::services::sandbox;
+use crate::services::valkey::{chat_rate_limit, ValkeyManager};
+
+/// Where to read the daily chat quota from (only when chat is enabled)
+#[derive(Clone)]
+pub struct ChatQuotaSource {
+    pub valkey: ValkeyManager,
+    pub daily_limit: u64,
+}
+
+/// Use case building the `/auth/me` response
+pub struct GetCurrentUserUseCase {
+    db: Arc<DatabaseConnection>,
+    modules: ActiveModules,
+    chat_quota: Option<ChatQuotaSource>,
+    sandbox: Option<SandboxConfig>,
+}
+
+impl GetCurrentUserUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub const fn new(
+        db: Arc<DatabaseConnection>,
+        modules: ActiveModules,
+        chat_quota: Option<ChatQuotaSource>,
+        sandbox: Option<SandboxConfig>,
+    ) -> Self {
+        Self {
+            db,
+            modules,
+            chat_quota,
+            sandbox,
+        }
+    }
+
+    /// Build the profile of the authenticated user from their `user` record
+    ///
+    /// Permissions follow the token's scope when it carries one, so a debug
+    /// token acting as a plain user sees exactly what that user would.
+    ///
+    /// # Errors
+    /// Returns `AuthError` if database operations fail
+    pub async fn execute(
+        &self,
+        auth_user: &AuthUser,
+        user: &users::Model,
+    ) -> Result<UserResponse, AuthError> {
+        let preferences = crate::services::preferences::load(self.db.as_ref(), user.id)
+            .await
+            .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+        let effective_role = auth_user
+            .scope
+            .as_ref()
+            .map_or(&user.role, |scope| &scope.role);
+        let permissions = permissions_for(effective_role, self.modules);
+
+        let sandbox = match &self.sandbox {
+            Some(config) => Some(SandboxStatus {
+                banner: config.banner.clone(),
+                expires_at: sandbox::expires_at(self.db.as_ref(), user.id)
+                    .await
+                    .map_err(|e| AuthError::DatabaseError(e.to_string()))?,
+            }),
+            None => None,
+        };
+        // Sandbox accounts chat under the sandbox quota
+        let daily_limit = match (&self.sandbox, &sandbox) {
+            (Some(config), Some(status)) if status.expires_at.is_some() => {
+                Some(config.daily_message_quota)
+            }
+            _ => None,
+        };
+        let quota = self.quota_summary(user.id, daily_limit).await;
+
+        Ok(UserResponse {
+            id: user.id,
+            username: user.username.clone(),
+            email: user.email.clone(),
+            email_verified: user.email_verified,
+            role: user.role.clone(),
+            preferences,
+            permissions,
+            quota,
+            sandbox,
+        })
+    }
+
+    /// Read the daily chat usage against `daily_limit` (the configured quota
+    /// if `None`); `None` if chat is off or Valkey is unreachable
+    async fn quota_summary(
+        &self,
+        user_id: uuid::Uuid,
+        daily_limit: Option<u64>,
+    ) -> Option<QuotaSummary> {
+        let source = self.chat_quota.as_ref()?;
+        let daily_limit = daily_limit.unwrap_or(source.daily_limit);
+        let usage = async {
+            let mut conn = source.valkey.get_async_connection().await?;
+            chat_rate_limit::get_chat_usage(&mut conn, user_id).await
+        }
+        .await;
+        match usage {
+            Ok((_, daily_used)) => Some(QuotaSummary {
+                daily_limit,
+                daily_used,
+                daily_remaining: daily_limit.saturating_sub(daily_used),
+            }),
+            Err(e) => {
+                tracing::warn!("Failed to read chat quota for {}: {}", user_id, e);
+                None
+            }
+        }
+    }
+}
+
+/// Permissions granted by `role` on a deployment running `modules`
+#[must_use]
+pub fn permissions_for(role: &UserRole, modules: ActiveModules) -> Vec<Permission> {
+    let mut permissions = Vec::new();
+    if modules.chat {
+        permissions.extend([Permission::ChatUse, Permission::ChatShare]);
+    }
+    if *role == UserRole::Admin && modules.admin_api {
+        permissions.extend([
+            Permission::AdminUsers,
+            Permission::AdminStats,
+            Permission::AdminBranding,
+            Permission::AdminBackup,
+        ]);
+        if modules.email {
+            permissions.push(Permission::AdminEmailVerifications);
+        }
+    }
+    permissions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_MODULES: ActiveModules = ActiveModules {
+        chat: true,
+        admin_api: true,
+        email: true,
+    };
+
+    #[test]
+    fn test_user_permissions() {
+        assert_eq!(
+            permissions_for(&UserRole::User, ALL_MODULES),
+            vec![Permission::ChatUse, Permission::ChatShare]
+        );
+    }
+
+    #[test]
+    fn test_admin_permissions() {
+        let permissions = permissions_for(&UserRole::Admin, ALL_MODULES);
+        assert!(permissions.contains(&Permission::AdminUsers));
+        assert!(permissions.contains(&Permission::AdminEmailVerifications));
+        assert!(permissions.contains(&Permission::AdminBackup));
+        assert!(permissions.contains(&Permission::ChatUse));
+    }
+
+    #[test]
+    fn test_permissions_follow_enabled_modules() {
+        let modules = ActiveModules {
+            chat: false,
+            admin_api: true,
+            email: false,
+        };
+        let permissions = permissions_for(&UserRole::Admin, modules);
+        assert!(!permissions.contains(&Permission::ChatUse));
+        assert!(!permissions.contains(&Permission::AdminEmailVerifications));
+        assert!(permissions.contains(&Permission::AdminBranding));
+
+        let modules = ActiveModules {
+            admin_api: false,
+            ..ALL_MODULES
+        };
+        assert_eq!(
+            permissions_for(&UserRole::Admin, modules),
+            vec![Permission::ChatUse, Permission::ChatShare]
+        );
+    }
+
+    #[test]
+    fn test_permission_serialization() {
+        assert_eq!(
+            serde_json::to_string(&Permission::AdminEmailVerifications).unwrap(),
+            "\"admin:email_verifications\""
+        );
+    }
+}
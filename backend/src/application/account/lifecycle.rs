@@ -0,0 +1,23 @@
+//! Hooks run when an admin disables or enables an account
+//!
+//! Subsystems that keep per-user state (such as chat share links) implement
+//! [`AccountLifecycleHook`] to restrict it while the account is disabled and
+//! restore it on enable. Hooks run before the account itself is updated, so a
+//! failed hook leaves the account unchanged and the admin can retry; they must
+//! therefore be idempotent.
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// Reacts to an account being disabled or enabled
+#[async_trait]
+pub trait AccountLifecycleHook: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Restrict the user's data before the account is disabled
+    async fn on_disable(&self, user_id: Uuid) -> anyhow::Result<()>;
+
+    /// Lift the restrictions before the account is enabled
+    async fn on_enable(&self, user_id: Uuid) -> anyhow::Result<()>;
+}
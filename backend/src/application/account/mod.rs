@@ -0,0 +1,10 @@
+//! Account application layer
+//!
+//! Use cases for the authenticated user's own account, and the hooks other
+//! subsystems register for admin account changes.
+
+pub mod get_current_user;
+pub mod lifecycle;
+
+pub use get_current_user::{ChatQuotaSource, GetCurrentUserUseCase};
+pub use lifecycle::AccountLifecycleHook;
@@ -0,0 +1,180 @@
+//! Chat policy for disabled accounts
+//!
+//! Registered as an [`AccountLifecycleHook`]: disabling a user suspends their
+//! share links and enabling them restores the links and unarchives their
+//! sessions. The send-message use cases refuse new replies while the
+//! account is disabled (see [`ensure_can_generate`]). With an archive delay
+//! configured, [`AccountSuspensionPolicy::archive_due`] archives the sessions
+//! of users disabled for longer than that.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::application::account::AccountLifecycleHook;
+use crate::domain::chat::{
+    repository::{RepositoryError, RepositoryResult},
+    suspension::SuspensionRepository,
+};
+
+/// How often [`AccountSuspensionPolicy::archive_due`] is run
+pub const ARCHIVE_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Applies and lifts the chat restrictions of disabled accounts
+pub struct AccountSuspensionPolicy {
+    repository: Arc<dyn SuspensionRepository>,
+    archive_after: Option<Duration>,
+}
+
+impl AccountSuspensionPolicy {
+    /// Create a policy; sessions are archived `archive_after` the account was
+    /// disabled (never if `None`)
+    #[must_use]
+    pub fn new(repository: Arc<dyn SuspensionRepository>, archive_after: Option<Duration>) -> Self {
+        Self {
+            repository,
+            archive_after,
+        }
+    }
+
+    /// Archive the sessions of accounts disabled for longer than the archive
+    /// delay, returning how many were archived
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if the archive delay is out of range or the
+    /// update fails
+    pub async fn archive_due(&self) -> RepositoryResult<u64> {
+        let Some(archive_after) = self.archive_after else {
+            return Ok(0);
+        };
+        let archive_after = chrono::Duration::from_std(archive_after)
+            .map_err(|e| RepositoryError::ValidationError(e.to_string()))?;
+
+        self.repository
+            .archive_sessions_disabled_before(Utc::now() - archive_after)
+            .await
+    }
+}
+
+/// Refuse generation for a disabled account
+///
+/// # Errors
+/// Returns `AccountDisabled` if the account is disabled, or
+/// `RepositoryError` if the lookup fails
+pub async fn ensure_can_generate(
+    repository: &dyn SuspensionRepository,
+    user_id: Uuid,
+) -> RepositoryResult<()> {
+    if repository.is_account_disabled(user_id).await? {
+        return Err(RepositoryError::AccountDisabled);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl AccountLifecycleHook for AccountSuspensionPolicy {
+    fn name(&self) -> &'static str {
+        "chat"
+    }
+
+    async fn on_disable(&self, user_id: Uuid) -> anyhow::Result<()> {
+        let suspended_shares = self.repository.suspend_shares(user_id).await?;
+
+        tracing::info!(
+            target: "audit",
+            action = "chat.account_suspended",
+            user_id = %user_id,
+            suspended_shares,
+            "Chat shares suspended for disabled account"
+        );
+        Ok(())
+    }
+
+    async fn on_enable(&self, user_id: Uuid) -> anyhow::Result<()> {
+        let restored_shares = self.repository.restore_shares(user_id).await?;
+        let unarchived_sessions = self.repository.unarchive_sessions(user_id).await?;
+
+        tracing::info!(
+            target: "audit",
+            action = "chat.account_restored",
+            user_id = %user_id,
+            restored_shares,
+            unarchived_sessions,
+            "Chat shares and sessions restored for enabled account"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration as ChronoDuration};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockSuspensionRepository {
+        disabled: bool,
+        archive_cutoff: Mutex<Option<DateTime<Utc>>>,
+    }
+
+    #[async_trait]
+    impl SuspensionRepository for MockSuspensionRepository {
+        async fn is_account_disabled(&self, _user_id: Uuid) -> RepositoryResult<bool> {
+            Ok(self.disabled)
+        }
+
+        async fn suspend_shares(&self, _user_id: Uuid) -> RepositoryResult<u64> {
+            Ok(2)
+        }
+
+        async fn restore_shares(&self, _user_id: Uuid) -> RepositoryResult<u64> {
+            Ok(2)
+        }
+
+        async fn archive_sessions_disabled_before(
+            &self,
+            cutoff: DateTime<Utc>,
+        ) -> RepositoryResult<u64> {
+            *self.archive_cutoff.lock().unwrap() = Some(cutoff);
+            Ok(3)
+        }
+
+        async fn unarchive_sessions(&self, _user_id: Uuid) -> RepositoryResult<u64> {
+            Ok(3)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disabled_account_cannot_generate() {
+        let disabled = MockSuspensionRepository {
+            disabled: true,
+            ..Default::default()
+        };
+        let result = ensure_can_generate(&disabled, Uuid::new_v4()).await;
+        assert!(matches!(result, Err(RepositoryError::AccountDisabled)));
+
+        let enabled = MockSuspensionRepository::default();
+        assert!(ensure_can_generate(&enabled, Uuid::new_v4()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_archive_due() {
+        let repository = Arc::new(MockSuspensionRepository::default());
+
+        let never = AccountSuspensionPolicy::new(Arc::clone(&repository) as Arc<_>, None);
+        assert_eq!(never.archive_due().await.unwrap(), 0);
+        assert!(repository.archive_cutoff.lock().unwrap().is_none());
+
+        let policy = AccountSuspensionPolicy::new(
+            Arc::clone(&repository) as Arc<_>,
+            Some(Duration::from_secs(30 * 86400)),
+        );
+        assert_eq!(policy.archive_due().await.unwrap(), 3);
+        let cutoff = repository.archive_cutoff.lock().unwrap().unwrap();
+        assert!(cutoff <= Utc::now() - ChronoDuration::days(30));
+        assert!(cutoff > Utc::now() - ChronoDuration::days(31));
+    }
+}
@@ -0,0 +1,685 @@
+//! Batch completion jobs
+//!
+//! [`BatchJobsUseCase`] validates, prices and queues jobs and returns their
+//! status and results. [`JobRunner`] runs them in the background: workers
+//! claim the oldest queued job (a conditional update, so two workers never
+//! run the same job), send its prompts with at most `provider_concurrency`
+//! requests in flight per provider, and store each reply as it arrives.
+//!
+//! Submitting a job wakes the workers of the same instance; workers on other
+//! instances pick it up within [`QUEUE_POLL_INTERVAL`]. Jobs left running by
+//! a stopped instance are requeued when workers start, so run workers on a
+//! single instance (`CHAT_JOB_WORKERS=0` on the others): a job still running
+//! elsewhere would otherwise be requeued and its pending prompts sent twice.
+//!
+//! Job prompts have no session, so their tokens are totalled on the job
+//! rather than written to `chat_usage`.
+
+use futures::{stream, TryStreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::{Notify, Semaphore};
+use uuid::Uuid;
+
+use super::account_suspension::ensure_can_generate;
+use crate::domain::chat::{
+    job::{ChatJob, ChatJobItem, JobRepository, JobStatus},
+    repository::{RepositoryError, RepositoryResult},
+    suspension::SuspensionRepository,
+};
+use crate::infrastructure::llm::{
+    ChatCompletionRequest, ChatMessage, ChatRole, LlmProvider, ProviderFactory,
+};
+use crate::services::stats_report::ModelPricing;
+use crate::services::tokenizer::{Tokenizer, TokenizerService};
+
+/// How often idle workers look for jobs queued by other instances
+pub const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Queued jobs a worker tries to claim per look at the queue
+const CLAIM_CANDIDATES: u64 = 8;
+
+/// Deployment limits on batch jobs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchJobLimits {
+    /// Maximum prompts per job
+    pub max_prompts: usize,
+    /// Maximum characters per prompt
+    pub max_prompt_length: usize,
+    /// Completion tokens requested per prompt
+    pub max_tokens: u16,
+    /// Queued or running jobs allowed per user
+    pub active_jobs_per_user: u64,
+}
+
+/// Tokens and cost of a job before it runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostEstimate {
+    pub model: String,
+    pub prompts: usize,
+    /// Tokens of all prompts
+    pub prompt_tokens: u64,
+    /// Completion tokens if every reply uses `max_tokens`
+    pub max_completion_tokens: u64,
+    /// Cost in USD of the above, `None` for a model without a price
+    pub max_cost_usd: Option<f64>,
+}
+
+/// Estimate the tokens and the highest cost of sending `prompts` to `model`
+#[must_use]
+pub fn estimate_cost(
+    model: &str,
+    prompts: &[String],
+    tokenizer: &dyn Tokenizer,
+    max_tokens: u16,
+    pricing: &ModelPricing,
+) -> CostEstimate {
+    let prompt_tokens = prompts
+        .iter()
+        .map(|prompt| token_count(tokenizer, prompt))
+        .map(u64::from)
+        .sum();
+    let max_completion_tokens = u64::from(max_tokens) * prompts.len() as u64;
+
+    CostEstimate {
+        model: model.to_string(),
+        prompts: prompts.len(),
+        prompt_tokens,
+        max_completion_tokens,
+        max_cost_usd: pricing.cost(model, prompt_tokens, max_completion_tokens),
+    }
+}
+
+/// Request to queue a batch job
+#[derive(Debug, Clone)]
+pub struct BatchJobRequest {
+    pub user_id: Uuid,
+    /// Optional model ID to use (defaults to registry default)
+    pub model_id: Option<String>,
+    pub prompts: Vec<String>,
+}
+
+/// Use case for submitting batch jobs and reading their results
+pub struct BatchJobsUseCase {
+    repository: Arc<dyn JobRepository>,
+    provider_factory: Arc<ProviderFactory>,
+    tokenizers: Arc<TokenizerService>,
+    pricing: Arc<ModelPricing>,
+    limits: BatchJobLimits,
+    suspension: Option<Arc<dyn SuspensionRepository>>,
+    runner: Option<Arc<JobRunner>>,
+}
+
+impl BatchJobsUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn JobRepository>,
+        provider_factory: Arc<ProviderFactory>,
+        tokenizers: Arc<TokenizerService>,
+        pricing: Arc<ModelPricing>,
+        limits: BatchJobLimits,
+    ) -> Self {
+        Self {
+            repository,
+            provider_factory,
+            tokenizers,
+            pricing,
+            limits,
+            suspension: None,
+            runner: None,
+        }
+    }
+
+    /// Refuse jobs from users whose account is disabled
+    #[must_use]
+    pub fn with_suspension(mut self, suspension: Arc<dyn SuspensionRepository>) -> Self {
+        self.suspension = Some(suspension);
+        self
+    }
+
+    /// Wake the workers of `runner` when a job is queued
+    #[must_use]
+    pub fn with_runner(mut self, runner: Arc<JobRunner>) -> Self {
+        self.runner = Some(runner);
+        self
+    }
+
+    /// Estimate the cost of a job without queuing it
+    ///
+    /// # Errors
+    /// Returns `ValidationError` if the model or the prompts are invalid
+    pub fn estimate(
+        &self,
+        model_id: Option<&str>,
+        prompts: &[String],
+    ) -> RepositoryResult<CostEstimate> {
+        let model = self.resolve_model(model_id)?;
+        self.validate_prompts(prompts)?;
+        self.estimate_for(&model, prompts)
+    }
+
+    /// Queue a job, returning it with its cost estimate
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - User's account is disabled (`AccountDisabled`)
+    /// - Model or prompts are invalid (`ValidationError`)
+    /// - User has too many unfinished jobs (`JobLimitReached`)
+    /// - Repository operations fail
+    pub async fn submit(
+        &self,
+        request: BatchJobRequest,
+    ) -> RepositoryResult<(ChatJob, CostEstimate)> {
+        if let Some(suspension) = &self.suspension {
+            ensure_can_generate(suspension.as_ref(), request.user_id).await?;
+        }
+
+        let model = self.resolve_model(request.model_id.as_deref())?;
+        self.validate_prompts(&request.prompts)?;
+        let estimate = self.estimate_for(&model, &request.prompts)?;
+
+        // Best effort: concurrent submissions may pass the check together
+        if self.repository.count_active_jobs(request.user_id).await?
+            >= self.limits.active_jobs_per_user
+        {
+            return Err(RepositoryError::JobLimitReached(
+                self.limits.active_jobs_per_user,
+            ));
+        }
+
+        let (job, items) = ChatJob::new(request.user_id, model, request.prompts)
+            .map_err(RepositoryError::ValidationError)?;
+        self.repository.create_job(&job, &items).await?;
+        tracing::info!(
+            "Queued chat job {} with {} prompts for user {}",
+            job.id,
+            job.total_items,
+            job.user_id
+        );
+
+        if let Some(runner) = &self.runner {
+            runner.notify();
+        }
+        Ok((job, estimate))
+    }
+
+    /// Status and counters of a job
+    ///
+    /// # Errors
+    /// Returns `JobNotFound` if the job does not exist or belongs to another
+    /// user, or `RepositoryError` if the lookup fails
+    pub async fn status(&self, job_id: Uuid, user_id: Uuid) -> RepositoryResult<ChatJob> {
+        self.repository.find_job_for_user(job_id, user_id).await
+    }
+
+    /// A job with a page (0-based) of its items in prompt order and the
+    /// total item count
+    ///
+    /// Items still pending are included without output, so results can be
+    /// read while the job runs.
+    ///
+    /// # Errors
+    /// Returns `JobNotFound` if the job does not exist or belongs to another
+    /// user, or `RepositoryError` if the lookup fails
+    pub async fn results(
+        &self,
+        job_id: Uuid,
+        user_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> RepositoryResult<(ChatJob, Vec<ChatJobItem>, u64)> {
+        let job = self.status(job_id, user_id).await?;
+        let (items, total) = self
+            .repository
+            .find_job_items(job_id, page, per_page)
+            .await?;
+        Ok((job, items, total))
+    }
+
+    /// Requested model, or the default, if it exists and is enabled
+    fn resolve_model(&self, model_id: Option<&str>) -> RepositoryResult<String> {
+        let registry = self.provider_factory.model_registry();
+        let model = match model_id {
+            Some(id) => registry
+                .get_model(id)
+                .map_err(|e| RepositoryError::ValidationError(e.to_string()))?,
+            None => registry.default_model(),
+        };
+        if !model.enabled {
+            return Err(RepositoryError::ValidationError(format!(
+                "Model '{}' is disabled",
+                model.id
+            )));
+        }
+        Ok(model.id.clone())
+    }
+
+    fn validate_prompts(&self, prompts: &[String]) -> RepositoryResult<()> {
+        if prompts.len() > self.limits.max_prompts {
+            return Err(RepositoryError::ValidationError(format!(
+                "A job cannot have more than {} prompts",
+                self.limits.max_prompts
+            )));
+        }
+        if let Some(position) = prompts
+            .iter()
+            .position(|prompt| prompt.chars().count() > self.limits.max_prompt_length)
+        {
+            return Err(RepositoryError::ValidationError(format!(
+                "Prompt {position} exceeds {} characters",
+                self.limits.max_prompt_length
+            )));
+        }
+        Ok(())
+    }
+
+    fn estimate_for(&self, model: &str, prompts: &[String]) -> RepositoryResult<CostEstimate> {
+        let tokenizer = self
+            .tokenizers
+            .for_model(model)
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        Ok(estimate_cost(
+            model,
+            prompts,
+            tokenizer.as_ref(),
+            self.limits.max_tokens,
+            &self.pricing,
+        ))
+    }
+}
+
+/// Background workers running queued jobs
+pub struct JobRunner {
+    repository: Arc<dyn JobRepository>,
+    provider_factory: Arc<ProviderFactory>,
+    tokenizers: Arc<TokenizerService>,
+    max_tokens: u16,
+    provider_concurrency: usize,
+    /// Requests in flight per provider, shared by all workers
+    limiters: Mutex<HashMap<String, Arc<Semaphore>>>,
+    wake: Notify,
+}
+
+impl JobRunner {
+    /// Create a runner sending at most `provider_concurrency` requests at a
+    /// time to each provider
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn JobRepository>,
+        provider_factory: Arc<ProviderFactory>,
+        tokenizers: Arc<TokenizerService>,
+        max_tokens: u16,
+        provider_concurrency: usize,
+    ) -> Self {
+        Self {
+            repository,
+            provider_factory,
+            tokenizers,
+            max_tokens,
+            provider_concurrency: provider_concurrency.max(1),
+            limiters: Mutex::new(HashMap::new()),
+            wake: Notify::new(),
+        }
+    }
+
+    /// Wake idle workers to look at the queue
+    pub fn notify(&self) {
+        self.wake.notify_waiters();
+    }
+
+    /// Requeue interrupted jobs, then start `workers` worker tasks
+    pub fn spawn(self: &Arc<Self>, workers: usize) {
+        let runner = Arc::clone(self);
+        tokio::spawn(async move {
+            match runner.repository.requeue_running_jobs().await {
+                Ok(0) => {}
+                Ok(requeued) => tracing::info!("Requeued {} interrupted chat jobs", requeued),
+                Err(e) => tracing::error!("Failed to requeue interrupted chat jobs: {}", e),
+            }
+            for _ in 0..workers {
+                let runner = Arc::clone(&runner);
+                tokio::spawn(async move { runner.work().await });
+            }
+        });
+    }
+
+    async fn work(&self) {
+        loop {
+            match self.next_job().await {
+                Ok(Some(job)) => self.process(&job).await,
+                Ok(None) => {
+                    // Timing out is the poll for jobs queued on other instances
+                    let _ = tokio::time::timeout(QUEUE_POLL_INTERVAL, self.wake.notified()).await;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to take a chat job from the queue: {}", e);
+                    tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    /// Claim the oldest queued job another worker has not taken
+    async fn next_job(&self) -> RepositoryResult<Option<ChatJob>> {
+        for id in self.repository.find_queued_jobs(CLAIM_CANDIDATES).await? {
+            if self.repository.claim_job(id).await? {
+                return self.repository.find_job(id).await;
+            }
+        }
+        Ok(None)
+    }
+
+    async fn process(&self, job: &ChatJob) {
+        let outcome = match self.resolve(&job.model) {
+            Ok((provider, tokenizer)) => {
+                let limiter = self.limiter(provider.name());
+                run_job(
+                    self.repository.as_ref(),
+                    provider.as_ref(),
+                    &limiter,
+                    tokenizer.as_ref(),
+                    job,
+                    self.max_tokens,
+                )
+                .await
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Chat job {} cannot run on model '{}': {}",
+                    job.id,
+                    job.model,
+                    e
+                );
+                self.repository.finish_job(job.id, JobStatus::Failed).await
+            }
+        };
+
+        match outcome {
+            Ok(()) => tracing::info!("Chat job {} finished", job.id),
+            // Left running; requeued when workers next start
+            Err(e) => tracing::error!("Chat job {} interrupted: {}", job.id, e),
+        }
+    }
+
+    fn resolve(&self, model: &str) -> anyhow::Result<(Arc<dyn LlmProvider>, Arc<dyn Tokenizer>)> {
+        let provider = self.provider_factory.get_provider_for_model(model)?;
+        let tokenizer = self.tokenizers.for_model(model)?;
+        Ok((provider, tokenizer))
+    }
+
+    fn limiter(&self, provider: &str) -> Arc<Semaphore> {
+        let mut limiters = self.limiters.lock().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(
+            limiters
+                .entry(provider.to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(self.provider_concurrency))),
+        )
+    }
+}
+
+/// Send the pending prompts of a running job and mark it completed
+///
+/// Prompts are sent concurrently, each holding a permit of `limiter` while
+/// its request is in flight. A prompt the provider fails on is stored as
+/// failed; the job still completes.
+///
+/// # Errors
+/// Returns `RepositoryError` if storing a result fails; the job is then left
+/// running
+pub async fn run_job(
+    repository: &dyn JobRepository,
+    provider: &dyn LlmProvider,
+    limiter: &Semaphore,
+    tokenizer: &dyn Tokenizer,
+    job: &ChatJob,
+    max_tokens: u16,
+) -> RepositoryResult<()> {
+    let items = repository.find_pending_items(job.id).await?;
+
+    stream::iter(items.into_iter().map(Ok))
+        .try_for_each_concurrent(None, |mut item: ChatJobItem| async move {
+            let permit = limiter
+                .acquire()
+                .await
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+            let request = ChatCompletionRequest {
+                model: job.model.clone(),
+                messages: vec![ChatMessage {
+                    role: ChatRole::User,
+                    content: item.prompt.clone(),
+                }],
+                max_tokens,
+                temperature: None,
+                stream: false,
+            };
+            let result = provider.create_chat_completion(request).await;
+            drop(permit);
+
+            match result {
+                Ok(output) => {
+                    let prompt_tokens = token_count(tokenizer, &item.prompt);
+                    let completion_tokens = token_count(tokenizer, &output);
+                    item.complete(output, prompt_tokens, completion_tokens);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Prompt {} of chat job {} failed: {}",
+                        item.position,
+                        job.id,
+                        e
+                    );
+                    item.fail(e.to_string());
+                }
+            }
+            repository.finish_item(&item).await
+        })
+        .await?;
+
+    repository.finish_job(job.id, JobStatus::Completed).await
+}
+
+fn token_count(tokenizer: &dyn Tokenizer, text: &str) -> u32 {
+    u32::try_from(tokenizer.count_tokens(text)).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::chat::job::JobItemStatus;
+    use crate::infrastructure::llm::{LlmProviderError, LlmResult, StreamChunk};
+    use crate::services::stats_report::ModelPrice;
+    use async_trait::async_trait;
+    use futures::Stream;
+    use std::pin::Pin;
+
+    /// One token per whitespace-separated word
+    struct WordTokenizer;
+
+    impl Tokenizer for WordTokenizer {
+        fn encoding(&self) -> &'static str {
+            "words"
+        }
+
+        fn count_tokens(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    /// Echoes the prompt, failing on prompts starting with "fail"
+    struct EchoProvider;
+
+    #[async_trait]
+    impl LlmProvider for EchoProvider {
+        fn name(&self) -> &'static str {
+            "Echo"
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn create_chat_completion_stream(
+            &self,
+            request: ChatCompletionRequest,
+        ) -> LlmResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, LlmProviderError>> + Send>>>
+        {
+            let prompt = request.messages[0].content.clone();
+            if prompt.starts_with("fail") {
+                return Err(LlmProviderError::ApiError("rate limited".to_string()));
+            }
+            Ok(Box::pin(futures::stream::iter([Ok(StreamChunk {
+                content: format!("echo {prompt}"),
+                is_final: true,
+                finish_reason: Some("stop".to_string()),
+                usage: None,
+            })])))
+        }
+
+        fn max_context_tokens(&self, _model: &str) -> Option<u32> {
+            None
+        }
+
+        fn max_output_tokens(&self, _model: &str) -> Option<u32> {
+            None
+        }
+    }
+
+    struct MockJobRepository {
+        job: Mutex<ChatJob>,
+        items: Mutex<Vec<ChatJobItem>>,
+    }
+
+    #[async_trait]
+    impl JobRepository for MockJobRepository {
+        async fn create_job(&self, _job: &ChatJob, _items: &[ChatJobItem]) -> RepositoryResult<()> {
+            unimplemented!()
+        }
+
+        async fn find_job(&self, _id: Uuid) -> RepositoryResult<Option<ChatJob>> {
+            Ok(Some(self.job.lock().unwrap().clone()))
+        }
+
+        async fn count_active_jobs(&self, _user_id: Uuid) -> RepositoryResult<u64> {
+            unimplemented!()
+        }
+
+        async fn find_queued_jobs(&self, _limit: u64) -> RepositoryResult<Vec<Uuid>> {
+            unimplemented!()
+        }
+
+        async fn claim_job(&self, _id: Uuid) -> RepositoryResult<bool> {
+            unimplemented!()
+        }
+
+        async fn requeue_running_jobs(&self) -> RepositoryResult<u64> {
+            unimplemented!()
+        }
+
+        async fn find_pending_items(&self, _job_id: Uuid) -> RepositoryResult<Vec<ChatJobItem>> {
+            let items = self.items.lock().unwrap();
+            Ok(items
+                .iter()
+                .filter(|item| item.status == JobItemStatus::Pending)
+                .cloned()
+                .collect())
+        }
+
+        async fn finish_item(&self, item: &ChatJobItem) -> RepositoryResult<()> {
+            let mut job = self.job.lock().unwrap();
+            match item.status {
+                JobItemStatus::Completed => job.completed_items += 1,
+                JobItemStatus::Failed => job.failed_items += 1,
+                JobItemStatus::Pending => {}
+            }
+            job.prompt_tokens += u64::from(item.prompt_tokens.unwrap_or_default());
+            job.completion_tokens += u64::from(item.completion_tokens.unwrap_or_default());
+            drop(job);
+
+            let mut items = self.items.lock().unwrap();
+            let stored = items
+                .iter_mut()
+                .find(|stored| stored.id == item.id)
+                .unwrap();
+            *stored = item.clone();
+            drop(items);
+            Ok(())
+        }
+
+        async fn finish_job(&self, _id: Uuid, status: JobStatus) -> RepositoryResult<()> {
+            self.job.lock().unwrap().status = status;
+            Ok(())
+        }
+
+        async fn find_job_items(
+            &self,
+            _job_id: Uuid,
+            _page: u64,
+            _per_page: u64,
+        ) -> RepositoryResult<(Vec<ChatJobItem>, u64)> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let pricing = ModelPricing::from_iter([(
+            "llama".to_string(),
+            ModelPrice {
+                input_per_million: 1.0,
+                output_per_million: 2.0,
+            },
+        )]);
+        let prompts = vec!["one two three".to_string(), "four".to_string()];
+
+        let estimate = estimate_cost("llama", &prompts, &WordTokenizer, 100, &pricing);
+
+        assert_eq!(estimate.prompts, 2);
+        assert_eq!(estimate.prompt_tokens, 4);
+        assert_eq!(estimate.max_completion_tokens, 200);
+        assert_eq!(estimate.max_cost_usd, Some(404.0 / 1_000_000.0));
+        assert_eq!(
+            estimate_cost("unpriced", &prompts, &WordTokenizer, 100, &pricing).max_cost_usd,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_job_stores_replies_and_failures() {
+        let (mut job, items) = ChatJob::new(
+            Uuid::new_v4(),
+            "llama",
+            vec!["hello there".into(), "fail me".into(), "bye".into()],
+        )
+        .unwrap();
+        job.status = JobStatus::Running;
+        let repository = MockJobRepository {
+            job: Mutex::new(job.clone()),
+            items: Mutex::new(items),
+        };
+
+        run_job(
+            &repository,
+            &EchoProvider,
+            &Semaphore::new(2),
+            &WordTokenizer,
+            &job,
+            16,
+        )
+        .await
+        .unwrap();
+
+        let job = repository.job.lock().unwrap().clone();
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!((job.completed_items, job.failed_items), (2, 1));
+        // "hello there" + "bye" in, "echo hello there" + "echo bye" out
+        assert_eq!((job.prompt_tokens, job.completion_tokens), (3, 5));
+
+        let items = repository.items.lock().unwrap().clone();
+        assert_eq!(items[0].output.as_deref(), Some("echo hello there"));
+        assert_eq!(items[1].status, JobItemStatus::Failed);
+        assert!(items[1].error.as_deref().unwrap().contains("rate limited"));
+        assert_eq!(items[2].output.as_deref(), Some("echo bye"));
+    }
+}
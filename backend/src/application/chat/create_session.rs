@@ -4,8 +4,12 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::domain::chat::{
-    entity::ChatSession,
-    repository::{ChatRepository, RepositoryResult},
+    entity::{ChatMessage, ChatSession},
+    events::{ChatEvent, EventPublisher},
+    import::MessageImportRepository,
+    policy::ChatPolicy,
+    repository::{ChatRepository, RepositoryError, RepositoryResult},
+    value_objects::MessageRole,
 };
 
 /// Request to create a new chat session
@@ -13,6 +17,8 @@ use crate::domain::chat::{
 pub struct CreateSessionRequest {
     pub user_id: Uuid,
     pub title: String,
+    /// User message to store with the session
+    pub first_message: Option<String>,
 }
 
 /// Response containing created session details
@@ -20,35 +26,100 @@ pub struct CreateSessionRequest {
 pub struct CreateSessionResponse {
     pub session_id: Uuid,
     pub title: String,
+    /// Stored first message, if the request had one
+    pub first_message: Option<ChatMessage>,
 }
 
 /// Use case for creating a new chat session
 pub struct CreateSessionUseCase {
     repository: Arc<dyn ChatRepository>,
+    events: Option<Arc<dyn EventPublisher>>,
+    first_messages: Option<(Arc<dyn MessageImportRepository>, ChatPolicy)>,
 }
 
 impl CreateSessionUseCase {
     /// Create a new use case instance
     #[must_use]
     pub fn new(repository: Arc<dyn ChatRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            events: None,
+            first_messages: None,
+        }
+    }
+
+    /// Publish created sessions to `events`
+    #[must_use]
+    pub fn with_events(mut self, events: Arc<dyn EventPublisher>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Accept a first message, checked against `policy` and stored in the
+    /// same transaction as the session
+    #[must_use]
+    pub fn with_first_messages(
+        mut self,
+        imports: Arc<dyn MessageImportRepository>,
+        policy: ChatPolicy,
+    ) -> Self {
+        self.first_messages = Some((imports, policy));
+        self
     }
 
     /// Execute the use case to create a new session
     ///
+    /// With a first message, the session and the message are stored together
+    /// or not at all.
+    ///
     /// # Errors
-    /// Returns `RepositoryError` if session creation fails
-    pub async fn execute(&self, request: CreateSessionRequest) -> RepositoryResult<CreateSessionResponse> {
+    /// Returns `RepositoryError` if:
+    /// - The title or first message is invalid (`ValidationError`)
+    /// - A first message is given but not accepted (`ValidationError`)
+    /// - Session creation fails
+    pub async fn execute(
+        &self,
+        request: CreateSessionRequest,
+    ) -> RepositoryResult<CreateSessionResponse> {
         // Create domain entity with validation
         let session = ChatSession::new(request.user_id, request.title)
-            .map_err(|e| crate::domain::chat::repository::RepositoryError::ValidationError(e))?;
+            .map_err(RepositoryError::ValidationError)?;
+
+        let first_message = match request.first_message {
+            None => {
+                self.repository.create_session(&session).await?;
+                None
+            }
+            Some(content) => {
+                let Some((imports, policy)) = &self.first_messages else {
+                    return Err(RepositoryError::ValidationError(
+                        "First messages are not accepted".to_string(),
+                    ));
+                };
+                policy
+                    .validate_message(MessageRole::User, &content)
+                    .map_err(RepositoryError::ValidationError)?;
+                let message = ChatMessage::new(session.id, MessageRole::User, content)
+                    .map_err(RepositoryError::ValidationError)?;
+
+                imports
+                    .create_session_with_messages(&session, std::slice::from_ref(&message))
+                    .await?;
+                Some(message)
+            }
+        };
 
-        // Persist to repository
-        self.repository.create_session(&session).await?;
+        if let Some(events) = &self.events {
+            events.publish(ChatEvent::SessionCreated {
+                session_id: session.id,
+                user_id: session.user_id,
+            });
+        }
 
         Ok(CreateSessionResponse {
             session_id: session.id,
             title: session.title,
+            first_message,
         })
     }
 }
@@ -56,115 +127,146 @@ impl CreateSessionUseCase {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::chat::{entity::ChatSession, repository::RepositoryError};
-    use async_trait::async_trait;
-    use std::sync::Mutex;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
 
-    // Mock repository for testing
-    struct MockChatRepository {
-        sessions: Mutex<Vec<ChatSession>>,
-    }
-
-    #[async_trait]
-    impl ChatRepository for MockChatRepository {
-        async fn create_session(&self, session: &ChatSession) -> RepositoryResult<()> {
-            self.sessions.lock().unwrap().push(session.clone());
-            Ok(())
-        }
-
-        async fn find_session_by_id(&self, _id: Uuid) -> RepositoryResult<Option<ChatSession>> {
-            unimplemented!()
-        }
-
-        async fn find_sessions_by_user(
-            &self,
-            _user_id: Uuid,
-            _page: u64,
-            _per_page: u64,
-        ) -> RepositoryResult<(Vec<ChatSession>, u64)> {
-            unimplemented!()
-        }
-
-        async fn update_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn delete_session(&self, _id: Uuid) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn save_message(&self, _message: &crate::domain::chat::entity::ChatMessage) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_messages_by_session(
-            &self,
-            _session_id: Uuid,
-            _limit: Option<u64>,
-        ) -> RepositoryResult<Vec<crate::domain::chat::entity::ChatMessage>> {
-            unimplemented!()
-        }
-
-        async fn find_recent_messages(
-            &self,
-            _session_id: Uuid,
-            _limit: u64,
-        ) -> RepositoryResult<Vec<crate::domain::chat::entity::ChatMessage>> {
-            unimplemented!()
-        }
+    fn first_message_use_case(repository: &Arc<InMemoryChatRepository>) -> CreateSessionUseCase {
+        CreateSessionUseCase::new(Arc::clone(repository) as Arc<_>).with_first_messages(
+            Arc::clone(repository) as Arc<_>,
+            ChatPolicy {
+                max_message_length: 100,
+                max_import_messages: 10,
+            },
+        )
     }
 
     #[tokio::test]
     async fn test_create_session_success() {
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(Vec::new()),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
         let use_case = CreateSessionUseCase::new(mock_repo.clone());
 
         let request = CreateSessionRequest {
             user_id: Uuid::new_v4(),
             title: "Test Session".to_string(),
+            first_message: None,
         };
 
         let response = use_case.execute(request.clone()).await.unwrap();
 
         assert_eq!(response.title, "Test Session");
-        assert_eq!(mock_repo.sessions.lock().unwrap().len(), 1);
+        assert_eq!(mock_repo.sessions().len(), 1);
     }
 
     #[tokio::test]
     async fn test_create_session_empty_title() {
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(Vec::new()),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
         let use_case = CreateSessionUseCase::new(mock_repo.clone());
 
         let request = CreateSessionRequest {
             user_id: Uuid::new_v4(),
             title: "".to_string(),
+            first_message: None,
         };
 
         let result = use_case.execute(request).await;
 
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), RepositoryError::ValidationError(_)));
+        assert!(matches!(
+            result.unwrap_err(),
+            RepositoryError::ValidationError(_)
+        ));
     }
 
     #[tokio::test]
     async fn test_create_session_title_too_long() {
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(Vec::new()),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
         let use_case = CreateSessionUseCase::new(mock_repo.clone());
 
         let request = CreateSessionRequest {
             user_id: Uuid::new_v4(),
             title: "a".repeat(256),
+            first_message: None,
         };
 
         let result = use_case.execute(request).await;
 
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), RepositoryError::ValidationError(_)));
+        assert!(matches!(
+            result.unwrap_err(),
+            RepositoryError::ValidationError(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_session_with_first_message() {
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
+        let use_case = first_message_use_case(&mock_repo);
+
+        let response = use_case
+            .execute(CreateSessionRequest {
+                user_id: Uuid::new_v4(),
+                title: "Trip".to_string(),
+                first_message: Some("Plan a weekend in Kyoto".to_string()),
+            })
+            .await
+            .unwrap();
+
+        let message = response.first_message.unwrap();
+        assert_eq!(message.session_id, response.session_id);
+        assert_eq!(message.role, MessageRole::User);
+        assert_eq!(mock_repo.sessions().len(), 1);
+        assert_eq!(mock_repo.messages().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_session_invalid_first_message_stores_nothing() {
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
+        let use_case = first_message_use_case(&mock_repo);
+
+        for content in ["   ".to_string(), "a".repeat(101)] {
+            let result = use_case
+                .execute(CreateSessionRequest {
+                    user_id: Uuid::new_v4(),
+                    title: "Trip".to_string(),
+                    first_message: Some(content),
+                })
+                .await;
+
+            assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+        }
+        assert!(mock_repo.sessions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_session_failed_transaction_stores_nothing() {
+        let mock_repo = Arc::new(InMemoryChatRepository::new().failing_writes());
+        let use_case = first_message_use_case(&mock_repo);
+
+        let result = use_case
+            .execute(CreateSessionRequest {
+                user_id: Uuid::new_v4(),
+                title: "Trip".to_string(),
+                first_message: Some("Hello".to_string()),
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::DatabaseError(_))));
+        assert!(mock_repo.sessions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_session_first_message_not_accepted() {
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
+        let use_case = CreateSessionUseCase::new(mock_repo.clone());
+
+        let result = use_case
+            .execute(CreateSessionRequest {
+                user_id: Uuid::new_v4(),
+                title: "Trip".to_string(),
+                first_message: Some("Hello".to_string()),
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+        assert!(mock_repo.sessions().is_empty());
     }
 }
@@ -0,0 +1,231 @@
+//! Delete message use case
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    deletion::{MessageDeletionPolicy, MessageDeletionRepository},
+    events::{ChatEvent, EventPublisher},
+    repository::{ChatRepository, RepositoryError, RepositoryResult},
+};
+
+/// Request to delete one message of a session
+#[derive(Debug, Clone)]
+pub struct DeleteMessageRequest {
+    /// Session the message belongs to
+    pub session: Uuid,
+    /// Message to delete
+    pub message: Uuid,
+    /// Requesting user, who must own the session
+    pub user: Uuid,
+}
+
+/// Response describing what was done to the message
+#[derive(Debug, Clone)]
+pub struct DeleteMessageResponse {
+    pub session_id: Uuid,
+    pub message_id: Uuid,
+    /// `true` if the message was redacted rather than deleted
+    pub redacted: bool,
+}
+
+/// Use case for deleting (or redacting) a single message
+///
+/// Only the session owner may delete its messages. Redacted messages stay in
+/// the history but are no longer sent to the LLM; a deleted one is gone
+/// along with its annotations.
+pub struct DeleteMessageUseCase {
+    repository: Arc<dyn ChatRepository>,
+    messages: Arc<dyn MessageDeletionRepository>,
+    policy: MessageDeletionPolicy,
+    events: Option<Arc<dyn EventPublisher>>,
+}
+
+impl DeleteMessageUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn ChatRepository>,
+        messages: Arc<dyn MessageDeletionRepository>,
+        policy: MessageDeletionPolicy,
+    ) -> Self {
+        Self {
+            repository,
+            messages,
+            policy,
+            events: None,
+        }
+    }
+
+    /// Publish deleted messages to `events`
+    #[must_use]
+    pub fn with_events(mut self, events: Arc<dyn EventPublisher>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Execute the use case to delete a message
+    ///
+    /// Redacting an already redacted message changes nothing.
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized (session belongs to different user)
+    /// - Message not found in the session
+    /// - Deletion fails
+    pub async fn execute(
+        &self,
+        request: DeleteMessageRequest,
+    ) -> RepositoryResult<DeleteMessageResponse> {
+        self.repository
+            .find_active_session_for_user(request.session, request.user)
+            .await?;
+
+        let mut message = self
+            .messages
+            .find_message(request.session, request.message)
+            .await?
+            .ok_or(RepositoryError::MessageNotFound(request.message))?;
+
+        let redacted = match self.policy {
+            MessageDeletionPolicy::Hard => {
+                self.messages.delete_message(&message).await?;
+                false
+            }
+            MessageDeletionPolicy::Redact if message.is_redacted() => {
+                return Ok(DeleteMessageResponse {
+                    session_id: request.session,
+                    message_id: request.message,
+                    redacted: true,
+                });
+            }
+            MessageDeletionPolicy::Redact => {
+                message.redact();
+                self.messages.redact_message(&message).await?;
+                true
+            }
+        };
+
+        tracing::info!(
+            target: "audit",
+            action = "chat.message_deleted",
+            user_id = %request.user,
+            session_id = %request.session,
+            message_id = %request.message,
+            role = message.role.as_str(),
+            policy = self.policy.as_str(),
+            "Chat message deleted"
+        );
+        if let Some(events) = &self.events {
+            events.publish(ChatEvent::MessageDeleted {
+                session_id: request.session,
+                user_id: request.user,
+                message_id: request.message,
+                redacted,
+            });
+        }
+
+        Ok(DeleteMessageResponse {
+            session_id: request.session,
+            message_id: request.message,
+            redacted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+    use crate::domain::chat::{
+        entity::{ChatMessage, ChatSession, REDACTED_CONTENT},
+        value_objects::MessageRole,
+    };
+
+    fn setup(
+        policy: MessageDeletionPolicy,
+    ) -> (
+        Arc<InMemoryChatRepository>,
+        DeleteMessageUseCase,
+        DeleteMessageRequest,
+    ) {
+        let user_id = Uuid::new_v4();
+        let session = ChatSession::new(user_id, "Test Session".to_string()).unwrap();
+        let message =
+            ChatMessage::new(session.id, MessageRole::User, "Secret".to_string()).unwrap();
+        let request = DeleteMessageRequest {
+            session: session.id,
+            message: message.id,
+            user: user_id,
+        };
+
+        let repository = Arc::new(
+            InMemoryChatRepository::new()
+                .with_session(session)
+                .with_messages([message]),
+        );
+        let use_case = DeleteMessageUseCase::new(
+            Arc::clone(&repository) as Arc<_>,
+            Arc::clone(&repository) as Arc<_>,
+            policy,
+        );
+        (repository, use_case, request)
+    }
+
+    #[tokio::test]
+    async fn test_redact_message() {
+        let (repository, use_case, request) = setup(MessageDeletionPolicy::Redact);
+
+        let response = use_case.execute(request.clone()).await.unwrap();
+        assert!(response.redacted);
+
+        let message = repository.messages()[0].clone();
+        assert!(message.is_redacted());
+        assert_eq!(message.content, REDACTED_CONTENT);
+
+        // Redacting again is a no-op
+        assert!(use_case.execute(request).await.unwrap().redacted);
+    }
+
+    #[tokio::test]
+    async fn test_hard_delete_message() {
+        let (repository, use_case, request) = setup(MessageDeletionPolicy::Hard);
+
+        let response = use_case.execute(request.clone()).await.unwrap();
+        assert!(!response.redacted);
+        assert!(repository.messages().is_empty());
+
+        let result = use_case.execute(request).await;
+        assert!(matches!(result, Err(RepositoryError::MessageNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_message_unauthorized() {
+        let (repository, use_case, request) = setup(MessageDeletionPolicy::Hard);
+
+        let result = use_case
+            .execute(DeleteMessageRequest {
+                user: Uuid::new_v4(),
+                ..request
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+        assert_eq!(repository.messages().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_message_from_other_session() {
+        let (_, use_case, request) = setup(MessageDeletionPolicy::Redact);
+
+        let result = use_case
+            .execute(DeleteMessageRequest {
+                message: Uuid::new_v4(),
+                ..request
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::MessageNotFound(_))));
+    }
+}
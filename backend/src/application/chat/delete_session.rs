@@ -3,7 +3,10 @@
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::domain::chat::repository::{ChatRepository, RepositoryResult};
+use crate::domain::chat::{
+    events::{ChatEvent, EventPublisher},
+    repository::{ChatRepository, RepositoryResult},
+};
 
 /// Request to delete a chat session
 #[derive(Debug, Clone)]
@@ -21,13 +24,24 @@ pub struct DeleteSessionResponse {
 /// Use case for deleting a chat session (soft delete)
 pub struct DeleteSessionUseCase {
     repository: Arc<dyn ChatRepository>,
+    events: Option<Arc<dyn EventPublisher>>,
 }
 
 impl DeleteSessionUseCase {
     /// Create a new use case instance
     #[must_use]
     pub fn new(repository: Arc<dyn ChatRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            events: None,
+        }
+    }
+
+    /// Publish deleted sessions to `events`
+    #[must_use]
+    pub fn with_events(mut self, events: Arc<dyn EventPublisher>) -> Self {
+        self.events = Some(events);
+        self
     }
 
     /// Execute the use case to delete a session
@@ -37,25 +51,25 @@ impl DeleteSessionUseCase {
     /// - Session not found
     /// - User not authorized (session belongs to different user)
     /// - Deletion fails
-    pub async fn execute(&self, request: DeleteSessionRequest) -> RepositoryResult<DeleteSessionResponse> {
-        // Verify session exists and belongs to user
-        let session = self
-            .repository
-            .find_session_by_id(request.session_id)
-            .await?
-            .ok_or(crate::domain::chat::repository::RepositoryError::SessionNotFound(
-                request.session_id,
-            ))?;
-
-        // Authorization check
-        if session.user_id != request.user_id {
-            return Err(crate::domain::chat::repository::RepositoryError::ValidationError(
-                "User not authorized to delete this session".to_string(),
-            ));
-        }
+    pub async fn execute(
+        &self,
+        request: DeleteSessionRequest,
+    ) -> RepositoryResult<DeleteSessionResponse> {
+        // Verify session exists, is not deleted yet and belongs to user
+        self.repository
+            .find_active_session_for_user(request.session_id, request.user_id)
+            .await?;
 
         // Perform soft delete
-        self.repository.delete_session(request.session_id).await?;
+        self.repository
+            .delete_session(request.session_id, request.user_id)
+            .await?;
+        if let Some(events) = &self.events {
+            events.publish(ChatEvent::SessionDeleted {
+                session_id: request.session_id,
+                user_id: request.user_id,
+            });
+        }
 
         Ok(DeleteSessionResponse {
             session_id: request.session_id,
@@ -66,69 +80,8 @@ impl DeleteSessionUseCase {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::chat::{entity::{ChatSession, ChatMessage}, repository::RepositoryError};
-    use async_trait::async_trait;
-    use chrono::Utc;
-    use std::sync::Mutex;
-
-    struct MockChatRepository {
-        sessions: Mutex<Vec<ChatSession>>,
-    }
-
-    #[async_trait]
-    impl ChatRepository for MockChatRepository {
-        async fn create_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_session_by_id(&self, id: Uuid) -> RepositoryResult<Option<ChatSession>> {
-            let sessions = self.sessions.lock().unwrap();
-            Ok(sessions.iter().find(|s| s.id == id).cloned())
-        }
-
-        async fn find_sessions_by_user(
-            &self,
-            _user_id: Uuid,
-            _page: u64,
-            _per_page: u64,
-        ) -> RepositoryResult<(Vec<ChatSession>, u64)> {
-            unimplemented!()
-        }
-
-        async fn update_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn delete_session(&self, id: Uuid) -> RepositoryResult<()> {
-            let mut sessions = self.sessions.lock().unwrap();
-            if let Some(session) = sessions.iter_mut().find(|s| s.id == id) {
-                session.mark_deleted();
-                Ok(())
-            } else {
-                Err(RepositoryError::SessionNotFound(id))
-            }
-        }
-
-        async fn save_message(&self, _message: &ChatMessage) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_messages_by_session(
-            &self,
-            _session_id: Uuid,
-            _limit: Option<u64>,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            unimplemented!()
-        }
-
-        async fn find_recent_messages(
-            &self,
-            _session_id: Uuid,
-            _limit: u64,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            unimplemented!()
-        }
-    }
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+    use crate::domain::chat::{entity::ChatSession, repository::RepositoryError};
 
     #[tokio::test]
     async fn test_delete_session_success() {
@@ -136,19 +89,20 @@ mod tests {
         let session = ChatSession::new(user_id, "Test Session".to_string()).unwrap();
         let session_id = session.id;
 
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(vec![session]),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new().with_session(session));
         let use_case = DeleteSessionUseCase::new(mock_repo.clone());
 
-        let request = DeleteSessionRequest { session_id, user_id };
+        let request = DeleteSessionRequest {
+            session_id,
+            user_id,
+        };
 
         let response = use_case.execute(request).await.unwrap();
 
         assert_eq!(response.session_id, session_id);
 
         // Verify soft delete was performed
-        let deleted_session = mock_repo.sessions.lock().unwrap()[0].clone();
+        let deleted_session = mock_repo.sessions()[0].clone();
         assert!(deleted_session.deleted_at.is_some());
     }
 
@@ -157,17 +111,40 @@ mod tests {
         let user_id = Uuid::new_v4();
         let session_id = Uuid::new_v4();
 
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(Vec::new()),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
         let use_case = DeleteSessionUseCase::new(mock_repo);
 
-        let request = DeleteSessionRequest { session_id, user_id };
+        let request = DeleteSessionRequest {
+            session_id,
+            user_id,
+        };
 
         let result = use_case.execute(request).await;
 
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), RepositoryError::SessionNotFound(_)));
+        assert!(matches!(
+            result.unwrap_err(),
+            RepositoryError::SessionNotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_session_twice_is_not_found() {
+        let user_id = Uuid::new_v4();
+        let session = ChatSession::new(user_id, "Test Session".to_string()).unwrap();
+        let session_id = session.id;
+
+        let mock_repo = Arc::new(InMemoryChatRepository::new().with_session(session));
+        let use_case = DeleteSessionUseCase::new(mock_repo);
+        let request = DeleteSessionRequest {
+            session_id,
+            user_id,
+        };
+
+        use_case.execute(request.clone()).await.unwrap();
+        let result = use_case.execute(request).await;
+
+        assert!(matches!(result, Err(RepositoryError::SessionNotFound(id)) if id == session_id));
     }
 
     #[tokio::test]
@@ -177,9 +154,7 @@ mod tests {
         let session = ChatSession::new(owner_id, "Test Session".to_string()).unwrap();
         let session_id = session.id;
 
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(vec![session]),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new().with_session(session));
         let use_case = DeleteSessionUseCase::new(mock_repo);
 
         let request = DeleteSessionRequest {
@@ -190,6 +165,9 @@ mod tests {
         let result = use_case.execute(request).await;
 
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), RepositoryError::ValidationError(_)));
+        assert!(matches!(
+            result.unwrap_err(),
+            RepositoryError::ValidationError(_)
+        ));
     }
 }
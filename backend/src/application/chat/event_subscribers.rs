@@ -0,0 +1,422 @@
+//! Subscribers to chat events
+//!
+//! - [`UsageAccounting`] writes the `chat_usage` record of each saved reply
+//! - [`TokenMetrics`] counts the tokens of each reply per model, rendered at
+//!   `/metrics`
+//! - [`SessionTitles`] names a session still called [`PLACEHOLDER_TITLE`]
+//!   after its first message
+//! - [`FailureNotifier`] adds a notification to the user's inbox when a reply
+//!   fails or is cut off
+//! - [`CompletionWebhooks`] calls the user's webhooks when a reply stops
+//!   generating
+
+use async_trait::async_trait;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use super::events::EventSubscriber;
+use super::webhooks::{GenerationPayload, WebhookDispatcher, WebhookPayload};
+use crate::domain::chat::{
+    deletion::MessageDeletionRepository,
+    events::{ChatEvent, GenerationCompleted, GenerationOutcome},
+    repository::{ChatRepository, RepositoryError},
+    usage::{UsageRecord, UsageRepository},
+    webhook::WebhookRepository,
+};
+use crate::services::valkey::{
+    notifications::{push_notification, Notification},
+    ValkeyManager,
+};
+
+/// Title the web client gives new sessions
+pub const PLACEHOLDER_TITLE: &str = "New Chat";
+
+/// Longest generated title, in characters, before the ellipsis
+pub const MAX_GENERATED_TITLE_CHARS: usize = 60;
+
+/// Notification kind of failed replies
+pub const GENERATION_FAILED_KIND: &str = "generation_failed";
+
+/// Session title from the first line of a message
+///
+/// Whitespace is collapsed and the title is cut at a word boundary. Returns
+/// `None` for a blank message.
+#[must_use]
+pub fn title_from_message(content: &str) -> Option<String> {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+
+    let mut title = String::new();
+    let mut length = 0;
+    for word in line.split_whitespace() {
+        let word_length = word.chars().count();
+        let separator = usize::from(length > 0);
+        if length + separator + word_length > MAX_GENERATED_TITLE_CHARS {
+            if length == 0 {
+                title = word.chars().take(MAX_GENERATED_TITLE_CHARS).collect();
+            }
+            title.push('…');
+            return Some(title);
+        }
+        if separator > 0 {
+            title.push(' ');
+        }
+        title.push_str(word);
+        length += separator + word_length;
+    }
+    Some(title)
+}
+
+/// Records the model, tokens and latency of each saved reply
+pub struct UsageAccounting {
+    repository: Arc<dyn UsageRepository>,
+}
+
+impl UsageAccounting {
+    #[must_use]
+    pub fn new(repository: Arc<dyn UsageRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for UsageAccounting {
+    fn name(&self) -> &'static str {
+        "usage"
+    }
+
+    async fn handle(&self, event: &ChatEvent) -> anyhow::Result<()> {
+        // Analytics count replies that were saved, complete or not
+        let ChatEvent::GenerationCompleted(GenerationCompleted {
+            session_id,
+            user_id,
+            model,
+            message_id: Some(_),
+            prompt_tokens,
+            completion_tokens,
+            latency_ms: Some(latency_ms),
+            ..
+        }) = event
+        else {
+            return Ok(());
+        };
+
+        let record = UsageRecord::new(
+            *user_id,
+            *session_id,
+            model.clone(),
+            *prompt_tokens,
+            completion_tokens.unwrap_or(0),
+            *latency_ms,
+        );
+        self.repository.record_usage(&record).await?;
+        Ok(())
+    }
+}
+
+/// Prompt and completion tokens per model, in the Prometheus text format
+///
+/// Counts every finished reply, saved or not: the provider was paid for the
+/// tokens either way. Batch jobs are not counted.
+#[derive(Debug, Default)]
+pub struct TokenMetrics {
+    /// Prompt and completion tokens by model
+    tokens: Mutex<BTreeMap<String, (u64, u64)>>,
+}
+
+impl TokenMetrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the tokens of a reply by `model`
+    pub fn record(&self, model: &str, prompt_tokens: u64, completion_tokens: u64) {
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        let (prompt, completion) = tokens.entry(model.to_string()).or_default();
+        *prompt += prompt_tokens;
+        *completion += completion_tokens;
+        drop(tokens);
+    }
+
+    /// Prompt and completion tokens recorded for `model`
+    #[must_use]
+    pub fn tokens_total(&self, model: &str) -> (u64, u64) {
+        let tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        tokens.get(model).copied().unwrap_or_default()
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP llm_tokens_total Tokens of chat replies by model and kind.\n");
+        out.push_str("# TYPE llm_tokens_total counter\n");
+        let tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        for (model, (prompt, completion)) in &*tokens {
+            let _ = writeln!(
+                out,
+                "llm_tokens_total{{model=\"{model}\",kind=\"prompt\"}} {prompt}"
+            );
+            let _ = writeln!(
+                out,
+                "llm_tokens_total{{model=\"{model}\",kind=\"completion\"}} {completion}"
+            );
+        }
+        drop(tokens);
+
+        out
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for TokenMetrics {
+    fn name(&self) -> &'static str {
+        "token_metrics"
+    }
+
+    async fn handle(&self, event: &ChatEvent) -> anyhow::Result<()> {
+        if let ChatEvent::GenerationCompleted(GenerationCompleted {
+            model,
+            prompt_tokens,
+            completion_tokens,
+            ..
+        }) = event
+        {
+            let count = |tokens: i32| u64::try_from(tokens).unwrap_or(0);
+            self.record(
+                model,
+                count(*prompt_tokens),
+                count(completion_tokens.unwrap_or(0)),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Names untitled sessions after their first message
+pub struct SessionTitles {
+    repository: Arc<dyn ChatRepository>,
+}
+
+impl SessionTitles {
+    #[must_use]
+    pub fn new(repository: Arc<dyn ChatRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for SessionTitles {
+    fn name(&self) -> &'static str {
+        "session_titles"
+    }
+
+    async fn handle(&self, event: &ChatEvent) -> anyhow::Result<()> {
+        let ChatEvent::MessageSent {
+            session_id,
+            content,
+            ..
+        } = event
+        else {
+            return Ok(());
+        };
+        let Some(title) = title_from_message(content) else {
+            return Ok(());
+        };
+        let Some(mut session) = self.repository.find_session_by_id(*session_id).await? else {
+            return Ok(());
+        };
+        if session.is_deleted() || session.title != PLACEHOLDER_TITLE {
+            return Ok(());
+        }
+
+        session.update_title(title).map_err(anyhow::Error::msg)?;
+        match self.repository.update_session(&session).await {
+            // Renamed or deleted meanwhile: the user's change wins
+            Ok(())
+            | Err(RepositoryError::SessionConflict(_) | RepositoryError::SessionNotFound(_)) => {
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Tells users about replies that failed or were cut off
+pub struct FailureNotifier {
+    repository: Arc<dyn ChatRepository>,
+    valkey: ValkeyManager,
+}
+
+impl FailureNotifier {
+    #[must_use]
+    pub fn new(repository: Arc<dyn ChatRepository>, valkey: ValkeyManager) -> Self {
+        Self { repository, valkey }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for FailureNotifier {
+    fn name(&self) -> &'static str {
+        "failure_notifications"
+    }
+
+    async fn handle(&self, event: &ChatEvent) -> anyhow::Result<()> {
+        let ChatEvent::GenerationCompleted(generation) = event else {
+            return Ok(());
+        };
+        let problem = match generation.outcome {
+            GenerationOutcome::Failed => "failed",
+            GenerationOutcome::Truncated => "was cut off",
+            GenerationOutcome::Completed | GenerationOutcome::Disconnected => return Ok(()),
+        };
+
+        let title = self
+            .repository
+            .find_session_by_id(generation.session_id)
+            .await?
+            .map_or_else(|| PLACEHOLDER_TITLE.to_string(), |session| session.title);
+        let saved = if generation.message_id.is_some() {
+            " The partial reply was saved."
+        } else {
+            ""
+        };
+        let notification = Notification::new(
+            GENERATION_FAILED_KIND,
+            format!("The reply in \"{title}\" {problem}.{saved}"),
+        );
+
+        let mut conn = self.valkey.get_async_connection().await?;
+        push_notification(&mut conn, generation.user_id, &notification).await
+    }
+}
+
+/// Posts each reply that stopped generating to the user's webhooks
+///
+/// Deliveries and their retries run on their own tasks, so a slow receiver
+/// does not hold back the events of other users.
+pub struct CompletionWebhooks {
+    webhooks: Arc<dyn WebhookRepository>,
+    messages: Arc<dyn MessageDeletionRepository>,
+    dispatcher: Arc<WebhookDispatcher>,
+}
+
+impl CompletionWebhooks {
+    #[must_use]
+    pub fn new(
+        webhooks: Arc<dyn WebhookRepository>,
+        messages: Arc<dyn MessageDeletionRepository>,
+        dispatcher: Arc<WebhookDispatcher>,
+    ) -> Self {
+        Self {
+            webhooks,
+            messages,
+            dispatcher,
+        }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for CompletionWebhooks {
+    fn name(&self) -> &'static str {
+        "completion_webhooks"
+    }
+
+    async fn handle(&self, event: &ChatEvent) -> anyhow::Result<()> {
+        let ChatEvent::GenerationCompleted(generation) = event else {
+            return Ok(());
+        };
+        let webhooks = self
+            .webhooks
+            .find_webhooks_by_user(generation.user_id)
+            .await?;
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        // A reply deleted since is sent without its content
+        let content = match generation.message_id {
+            Some(message_id) => self
+                .messages
+                .find_message(generation.session_id, message_id)
+                .await?
+                .filter(|message| !message.is_redacted())
+                .map(|message| message.content),
+            None => None,
+        };
+        let payload = GenerationPayload::new(
+            generation,
+            content.as_deref(),
+            self.dispatcher.settings().content_chars,
+        );
+
+        for webhook in webhooks {
+            self.dispatcher
+                .spawn_delivery(webhook, WebhookPayload::generation(payload.clone()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_from_message() {
+        assert_eq!(
+            title_from_message("\n  How do I   write a parser?\nIn Rust.").as_deref(),
+            Some("How do I write a parser?")
+        );
+        assert_eq!(title_from_message(" \n\t"), None);
+    }
+
+    #[test]
+    fn test_title_from_long_message() {
+        let title = title_from_message(&"word ".repeat(30)).unwrap();
+        assert_eq!(title, format!("{}…", ["word"; 12].join(" ")));
+        assert!(title.chars().count() <= MAX_GENERATED_TITLE_CHARS + 1);
+
+        let title = title_from_message(&"x".repeat(100)).unwrap();
+        assert_eq!(title.chars().count(), MAX_GENERATED_TITLE_CHARS + 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_metrics_count_replies_per_model() {
+        let metrics = TokenMetrics::new();
+        let completed = |model: &str, message_id, completion_tokens| {
+            ChatEvent::GenerationCompleted(GenerationCompleted {
+                session_id: uuid::Uuid::new_v4(),
+                user_id: uuid::Uuid::new_v4(),
+                model: model.to_string(),
+                outcome: GenerationOutcome::Completed,
+                message_id,
+                prompt_tokens: 100,
+                completion_tokens,
+                latency_ms: Some(50),
+            })
+        };
+
+        // Unsaved and uncounted replies still used the prompt
+        for event in [
+            completed("llama", Some(uuid::Uuid::new_v4()), Some(20)),
+            completed("llama", None, None),
+            completed("gpt", Some(uuid::Uuid::new_v4()), Some(5)),
+        ] {
+            metrics.handle(&event).await.unwrap();
+        }
+
+        assert_eq!(metrics.tokens_total("llama"), (200, 20));
+        assert_eq!(metrics.tokens_total("gpt"), (100, 5));
+        assert!(metrics
+            .render()
+            .contains("llm_tokens_total{model=\"llama\",kind=\"completion\"} 20"));
+    }
+}
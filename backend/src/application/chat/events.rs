@@ -0,0 +1,138 @@
+//! Delivery of chat events to subscribers
+//!
+//! [`EventBus`] gives every subscriber its own queue and task, so each
+//! subscriber sees events in publish order without waiting for the others.
+//! Events live in memory only: a subscriber more than [`QUEUE_CAPACITY`]
+//! events behind misses the overflow, and queued events are lost when the
+//! process stops. Both, and subscriber errors, are logged.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::domain::chat::events::{ChatEvent, EventPublisher};
+
+/// Events queued per subscriber before new ones are dropped
+pub const QUEUE_CAPACITY: usize = 1024;
+
+/// Reacts to chat events
+#[async_trait]
+pub trait EventSubscriber: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Handle one event; events the subscriber does not need are ignored
+    async fn handle(&self, event: &ChatEvent) -> anyhow::Result<()>;
+}
+
+/// [`EventPublisher`] delivering events to subscribers on background tasks
+pub struct EventBus {
+    queues: Vec<(&'static str, mpsc::Sender<ChatEvent>)>,
+}
+
+impl EventBus {
+    /// Start a delivery task per subscriber
+    ///
+    /// Must be called within a Tokio runtime.
+    #[must_use]
+    pub fn new(subscribers: Vec<Arc<dyn EventSubscriber>>) -> Self {
+        let queues = subscribers
+            .into_iter()
+            .map(|subscriber| {
+                let (sender, mut receiver) = mpsc::channel::<ChatEvent>(QUEUE_CAPACITY);
+                let name = subscriber.name();
+                tokio::spawn(async move {
+                    while let Some(event) = receiver.recv().await {
+                        if let Err(e) = subscriber.handle(&event).await {
+                            tracing::warn!(
+                                subscriber = subscriber.name(),
+                                event = event.name(),
+                                "Chat event subscriber failed: {:#}",
+                                e
+                            );
+                        }
+                    }
+                });
+                (name, sender)
+            })
+            .collect();
+        Self { queues }
+    }
+}
+
+impl EventPublisher for EventBus {
+    fn publish(&self, event: ChatEvent) {
+        for (name, queue) in &self.queues {
+            match queue.try_send(event.clone()) {
+                Ok(()) => {}
+                Err(TrySendError::Full(event)) => tracing::warn!(
+                    subscriber = name,
+                    event = event.name(),
+                    "Chat event dropped, subscriber queue is full"
+                ),
+                Err(TrySendError::Closed(event)) => tracing::error!(
+                    subscriber = name,
+                    event = event.name(),
+                    "Chat event dropped, subscriber task has stopped"
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct Recorder {
+        seen: Mutex<Vec<&'static str>>,
+    }
+
+    #[async_trait]
+    impl EventSubscriber for Recorder {
+        fn name(&self) -> &'static str {
+            "recorder"
+        }
+
+        async fn handle(&self, event: &ChatEvent) -> anyhow::Result<()> {
+            self.seen.lock().unwrap().push(event.name());
+            anyhow::ensure!(
+                !matches!(event, ChatEvent::SessionDeleted { .. }),
+                "refusing deletes"
+            );
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bus_delivers_in_order_after_errors() {
+        let recorder = Arc::new(Recorder::default());
+        let bus = EventBus::new(vec![Arc::clone(&recorder) as Arc<dyn EventSubscriber>]);
+        let session_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+
+        bus.publish(ChatEvent::SessionDeleted {
+            session_id,
+            user_id,
+        });
+        bus.publish(ChatEvent::SessionCreated {
+            session_id,
+            user_id,
+        });
+
+        for _ in 0..100 {
+            if recorder.seen.lock().unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            *recorder.seen.lock().unwrap(),
+            ["session_deleted", "session_created"]
+        );
+    }
+}
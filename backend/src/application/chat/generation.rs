@@ -0,0 +1,365 @@
+//! Buffered generations for clients that cannot consume a streamed response
+//!
+//! Some proxies buffer or strip SSE, so a polling client starts a generation,
+//! gets its id back immediately, and then fetches the output piece by piece.
+//! [`GenerationStore::start`] drains the same stream the SSE handler sends
+//! (see [`super::send_message_v2`]) into an in-process buffer; persistence of
+//! the assistant message is unchanged.
+//!
+//! Buffers live in the instance that started the generation, so deployments
+//! with several replicas need sticky routing for the polling endpoints.
+//! Finished generations are kept for [`GENERATION_RETENTION`] so a client can
+//! fetch the tail after completion.
+
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+pub use super::stream_supervisor::ChunkStream;
+
+/// How long a finished generation can still be polled
+pub const GENERATION_RETENTION: Duration = Duration::from_secs(300);
+
+/// Lifecycle of a buffered generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Output of a generation past a cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationSnapshot {
+    pub session_id: Uuid,
+    /// Content appended since the requested cursor
+    pub content: String,
+    /// Cursor to send with the next poll
+    pub cursor: usize,
+    pub status: GenerationStatus,
+    pub error: Option<String>,
+}
+
+/// Why a poll could not be answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollError {
+    /// Unknown or expired generation, or owned by another user
+    NotFound,
+    /// Cursor is not one previously returned for this generation
+    InvalidCursor,
+}
+
+struct Generation {
+    user_id: Uuid,
+    session_id: Uuid,
+    content: String,
+    status: GenerationStatus,
+    error: Option<String>,
+    finished_at: Option<Instant>,
+    updated: Arc<Notify>,
+}
+
+impl Generation {
+    /// Whether a poller waiting past `cursor` has something to return
+    fn has_update(&self, cursor: usize) -> bool {
+        self.content.len() > cursor || self.status != GenerationStatus::Running
+    }
+
+    fn snapshot(&self, cursor: usize) -> GenerationSnapshot {
+        GenerationSnapshot {
+            session_id: self.session_id,
+            content: self.content[cursor..].to_string(),
+            cursor: self.content.len(),
+            status: self.status,
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// In-process buffer of running and recently finished generations
+#[derive(Default)]
+pub struct GenerationStore {
+    generations: Mutex<HashMap<Uuid, Generation>>,
+}
+
+impl GenerationStore {
+    /// Create an empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer `stream` in the background and return the generation id
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn start(self: &Arc<Self>, user_id: Uuid, session_id: Uuid, stream: ChunkStream) -> Uuid {
+        let id = Uuid::new_v4();
+        {
+            let mut generations = self.lock();
+            generations.retain(|_, generation| {
+                generation
+                    .finished_at
+                    .map_or(true, |finished| finished.elapsed() < GENERATION_RETENTION)
+            });
+            generations.insert(
+                id,
+                Generation {
+                    user_id,
+                    session_id,
+                    content: String::new(),
+                    status: GenerationStatus::Running,
+                    error: None,
+                    finished_at: None,
+                    updated: Arc::new(Notify::new()),
+                },
+            );
+        }
+
+        let store = Arc::clone(self);
+        tokio::spawn(async move { store.drain(id, stream).await });
+
+        id
+    }
+
+    /// Return output after `cursor`, waiting up to `wait` for new output
+    ///
+    /// Returns as soon as there is content past the cursor or the generation
+    /// has finished; otherwise returns an empty snapshot after `wait`.
+    ///
+    /// # Errors
+    /// Returns `PollError` if the generation is not visible to `user_id` or
+    /// the cursor is invalid
+    pub async fn poll(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        cursor: usize,
+        wait: Duration,
+    ) -> Result<GenerationSnapshot, PollError> {
+        let deadline = Instant::now() + wait;
+        loop {
+            let (ready, updated) = self
+                .lock()
+                .get(&id)
+                .filter(|generation| generation.user_id == user_id)
+                .map(|generation| {
+                    let ready = if !generation.content.is_char_boundary(cursor) {
+                        Err(PollError::InvalidCursor)
+                    } else if generation.has_update(cursor) {
+                        Ok(Some(generation.snapshot(cursor)))
+                    } else {
+                        Ok(None)
+                    };
+                    (ready, Arc::clone(&generation.updated))
+                })
+                .ok_or(PollError::NotFound)?;
+            if let Some(snapshot) = ready? {
+                return Ok(snapshot);
+            }
+
+            // Register before re-checking so an update in between is not missed
+            let notified = updated.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            let changed = self
+                .lock()
+                .get(&id)
+                .map_or(true, |generation| generation.has_update(cursor));
+            if changed {
+                continue;
+            }
+
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return self
+                    .lock()
+                    .get(&id)
+                    .map(|generation| generation.snapshot(cursor))
+                    .ok_or(PollError::NotFound);
+            }
+        }
+    }
+
+    async fn drain(&self, id: Uuid, mut stream: ChunkStream) {
+        while let Some(item) = stream.next().await {
+            let finished = match item {
+                Ok(chunk) if chunk.is_final => self.update(id, |generation| {
+                    generation.status = GenerationStatus::Completed;
+                }),
+                Ok(chunk) => {
+                    self.update(id, |generation| generation.content.push_str(&chunk.content))
+                }
+                Err(e) => self.update(id, |generation| {
+                    generation.status = GenerationStatus::Failed;
+                    generation.error = Some(e);
+                }),
+            };
+            if finished {
+                return;
+            }
+        }
+
+        // Stream ended without a final chunk
+        self.update(id, |generation| {
+            if generation.status == GenerationStatus::Running {
+                generation.status = GenerationStatus::Completed;
+            }
+        });
+    }
+
+    /// Apply `change` and wake pollers; returns whether the generation finished
+    fn update(&self, id: Uuid, change: impl FnOnce(&mut Generation)) -> bool {
+        self.lock().get_mut(&id).map_or(true, |generation| {
+            change(generation);
+            let finished = generation.status != GenerationStatus::Running;
+            if finished {
+                generation.finished_at = Some(Instant::now());
+            }
+            generation.updated.notify_waiters();
+            finished
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Generation>> {
+        self.generations
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::chat::stream_supervisor::StreamChunk;
+    use futures::stream;
+
+    fn chunk(content: &str, is_final: bool) -> StreamChunk {
+        StreamChunk {
+            content: content.to_string(),
+            is_final,
+            usage: None,
+        }
+    }
+
+    fn channel_stream() -> (
+        tokio::sync::mpsc::UnboundedSender<Result<StreamChunk, String>>,
+        ChunkStream,
+    ) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let stream = stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        });
+        (tx, Box::pin(stream))
+    }
+
+    #[tokio::test]
+    async fn test_poll_returns_incremental_content() {
+        let store = Arc::new(GenerationStore::new());
+        let user_id = Uuid::new_v4();
+        let (tx, stream) = channel_stream();
+        let id = store.start(user_id, Uuid::new_v4(), stream);
+        let wait = Duration::from_secs(2);
+
+        tx.send(Ok(chunk("Hello", false))).unwrap();
+        let first = store.poll(id, user_id, 0, wait).await.unwrap();
+        assert_eq!(first.content, "Hello");
+        assert_eq!(first.status, GenerationStatus::Running);
+
+        tx.send(Ok(chunk(", world", false))).unwrap();
+        tx.send(Ok(chunk("", true))).unwrap();
+        let mut cursor = first.cursor;
+        let mut rest = String::new();
+        loop {
+            let next = store.poll(id, user_id, cursor, wait).await.unwrap();
+            rest.push_str(&next.content);
+            cursor = next.cursor;
+            if next.status == GenerationStatus::Completed {
+                break;
+            }
+        }
+        assert_eq!(rest, ", world");
+
+        // Polling past the end of a finished generation returns nothing new
+        let done = store.poll(id, user_id, cursor, wait).await.unwrap();
+        assert_eq!(done.content, "");
+        assert_eq!(done.status, GenerationStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_poll_times_out_without_new_content() {
+        let store = Arc::new(GenerationStore::new());
+        let user_id = Uuid::new_v4();
+        let (_tx, stream) = channel_stream();
+        let id = store.start(user_id, Uuid::new_v4(), stream);
+
+        let snapshot = store
+            .poll(id, user_id, 0, Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert_eq!(snapshot.content, "");
+        assert_eq!(snapshot.cursor, 0);
+        assert_eq!(snapshot.status, GenerationStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_failed_generation_reports_error() {
+        let store = Arc::new(GenerationStore::new());
+        let user_id = Uuid::new_v4();
+        let stream = stream::iter(vec![
+            Ok(chunk("partial", false)),
+            Err("Provider error".to_string()),
+        ]);
+        let id = store.start(user_id, Uuid::new_v4(), Box::pin(stream));
+
+        let mut snapshot = store
+            .poll(id, user_id, 0, Duration::from_secs(2))
+            .await
+            .unwrap();
+        while snapshot.status == GenerationStatus::Running {
+            snapshot = store
+                .poll(id, user_id, snapshot.cursor, Duration::from_secs(2))
+                .await
+                .unwrap();
+        }
+        assert_eq!(snapshot.status, GenerationStatus::Failed);
+        assert_eq!(snapshot.error.as_deref(), Some("Provider error"));
+    }
+
+    #[tokio::test]
+    async fn test_poll_rejects_other_users_and_bad_cursors() {
+        let store = Arc::new(GenerationStore::new());
+        let user_id = Uuid::new_v4();
+        let stream = stream::iter(vec![Ok(chunk("héllo", false)), Ok(chunk("", true))]);
+        let id = store.start(user_id, Uuid::new_v4(), Box::pin(stream));
+        let wait = Duration::from_secs(2);
+
+        assert_eq!(
+            store.poll(id, Uuid::new_v4(), 0, wait).await,
+            Err(PollError::NotFound)
+        );
+        assert_eq!(
+            store.poll(Uuid::new_v4(), user_id, 0, wait).await,
+            Err(PollError::NotFound)
+        );
+
+        // Wait for the content to arrive, then use a cursor inside "é"
+        let mut snapshot = store.poll(id, user_id, 0, wait).await.unwrap();
+        while snapshot.status == GenerationStatus::Running {
+            snapshot = store
+                .poll(id, user_id, snapshot.cursor, wait)
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            store.poll(id, user_id, 2, wait).await,
+            Err(PollError::InvalidCursor)
+        );
+        assert_eq!(
+            store.poll(id, user_id, 100, wait).await,
+            Err(PollError::InvalidCursor)
+        );
+    }
+}
@@ -4,7 +4,8 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::domain::chat::{
-    entity::ChatMessage,
+    annotation::{AnnotationFilter, AnnotationRepository, MessageAnnotation},
+    entity::{ChatMessage, ChatSession},
     repository::{ChatRepository, RepositoryResult},
 };
 
@@ -12,127 +13,130 @@ use crate::domain::chat::{
 #[derive(Debug, Clone)]
 pub struct GetSessionHistoryRequest {
     pub session_id: Uuid,
+    pub user_id: Uuid,
     pub limit: Option<u64>,
+    /// Only return messages the user annotated as the filter selects
+    pub annotation: Option<AnnotationFilter>,
 }
 
 /// Response containing message history
 #[derive(Debug, Clone)]
 pub struct GetSessionHistoryResponse {
+    pub session: ChatSession,
     pub messages: Vec<ChatMessage>,
+    /// The user's annotations on the returned messages
+    pub annotations: Vec<MessageAnnotation>,
 }
 
 /// Use case for retrieving chat session history
 pub struct GetSessionHistoryUseCase {
     repository: Arc<dyn ChatRepository>,
+    annotations: Arc<dyn AnnotationRepository>,
 }
 
 impl GetSessionHistoryUseCase {
     /// Create a new use case instance
     #[must_use]
-    pub fn new(repository: Arc<dyn ChatRepository>) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: Arc<dyn ChatRepository>,
+        annotations: Arc<dyn AnnotationRepository>,
+    ) -> Self {
+        Self {
+            repository,
+            annotations,
+        }
     }
 
     /// Execute the use case to get session history
     ///
     /// # Errors
-    /// Returns `RepositoryError` if retrieval fails
-    pub async fn execute(&self, request: GetSessionHistoryRequest) -> RepositoryResult<GetSessionHistoryResponse> {
-        let messages = self
+    /// Returns `RepositoryError` if:
+    /// - Session not found or deleted
+    /// - User not authorized
+    /// - Retrieval fails
+    pub async fn execute(
+        &self,
+        request: GetSessionHistoryRequest,
+    ) -> RepositoryResult<GetSessionHistoryResponse> {
+        let session = self
             .repository
-            .find_messages_by_session(request.session_id, request.limit)
+            .find_active_session_for_user(request.session_id, request.user_id)
+            .await?;
+
+        let messages = match &request.annotation {
+            Some(filter) => {
+                self.annotations
+                    .find_annotated_messages(
+                        request.user_id,
+                        request.session_id,
+                        filter,
+                        request.limit,
+                    )
+                    .await?
+            }
+            None => {
+                self.repository
+                    .find_messages_by_session(request.session_id, request.limit)
+                    .await?
+            }
+        };
+
+        let mut annotations = self
+            .annotations
+            .find_annotations_by_session(request.user_id, request.session_id)
             .await?;
+        annotations.retain(|annotation| {
+            messages
+                .iter()
+                .any(|message| message.id == annotation.message_id)
+        });
 
-        Ok(GetSessionHistoryResponse { messages })
+        Ok(GetSessionHistoryResponse {
+            session,
+            messages,
+            annotations,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::chat::{entity::ChatSession, value_objects::MessageRole, repository::RepositoryError};
-    use async_trait::async_trait;
-    use chrono::Utc;
-    use std::sync::Mutex;
-
-    struct MockChatRepository {
-        messages: Mutex<Vec<ChatMessage>>,
-    }
-
-    #[async_trait]
-    impl ChatRepository for MockChatRepository {
-        async fn create_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_session_by_id(&self, _id: Uuid) -> RepositoryResult<Option<ChatSession>> {
-            unimplemented!()
-        }
-
-        async fn find_sessions_by_user(
-            &self,
-            _user_id: Uuid,
-            _page: u64,
-            _per_page: u64,
-        ) -> RepositoryResult<(Vec<ChatSession>, u64)> {
-            unimplemented!()
-        }
-
-        async fn update_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn delete_session(&self, _id: Uuid) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn save_message(&self, _message: &ChatMessage) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_messages_by_session(
-            &self,
-            _session_id: Uuid,
-            limit: Option<u64>,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            let messages = self.messages.lock().unwrap();
-            let result = if let Some(limit_value) = limit {
-                messages.iter().take(limit_value as usize).cloned().collect()
-            } else {
-                messages.clone()
-            };
-            Ok(result)
-        }
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+    use crate::domain::chat::{
+        annotation::Annotation, repository::RepositoryError, value_objects::MessageRole,
+    };
+
+    fn mock_repo(messages: &[(MessageRole, &str)]) -> (ChatSession, Arc<InMemoryChatRepository>) {
+        let session = ChatSession::new(Uuid::new_v4(), "Test Session".to_string()).unwrap();
+        let messages = messages.iter().map(|(role, content)| {
+            ChatMessage::new(session.id, *role, (*content).to_string()).unwrap()
+        });
 
-        async fn find_recent_messages(
-            &self,
-            _session_id: Uuid,
-            _limit: u64,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            unimplemented!()
-        }
+        let repository = InMemoryChatRepository::new()
+            .with_session(session.clone())
+            .with_messages(messages);
+        (session, Arc::new(repository))
     }
 
     #[tokio::test]
     async fn test_get_session_history_all() {
-        let session_id = Uuid::new_v4();
-        let messages = vec![
-            ChatMessage::new(session_id, MessageRole::User, "Hello".to_string()).unwrap(),
-            ChatMessage::new(session_id, MessageRole::Assistant, "Hi!".to_string()).unwrap(),
-        ];
-
-        let mock_repo = Arc::new(MockChatRepository {
-            messages: Mutex::new(messages.clone()),
-        });
-        let use_case = GetSessionHistoryUseCase::new(mock_repo);
+        let (session, mock_repo) = mock_repo(&[
+            (MessageRole::User, "Hello"),
+            (MessageRole::Assistant, "Hi!"),
+        ]);
+        let use_case = GetSessionHistoryUseCase::new(mock_repo.clone(), mock_repo.clone());
 
         let request = GetSessionHistoryRequest {
-            session_id,
+            session_id: session.id,
+            user_id: session.user_id,
             limit: None,
+            annotation: None,
         };
 
         let response = use_case.execute(request).await.unwrap();
 
+        assert_eq!(response.session, session);
         assert_eq!(response.messages.len(), 2);
         assert_eq!(response.messages[0].content, "Hello");
         assert_eq!(response.messages[1].content, "Hi!");
@@ -140,25 +144,97 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_session_history_with_limit() {
-        let session_id = Uuid::new_v4();
-        let messages = vec![
-            ChatMessage::new(session_id, MessageRole::User, "Message 1".to_string()).unwrap(),
-            ChatMessage::new(session_id, MessageRole::Assistant, "Response 1".to_string()).unwrap(),
-            ChatMessage::new(session_id, MessageRole::User, "Message 2".to_string()).unwrap(),
-        ];
-
-        let mock_repo = Arc::new(MockChatRepository {
-            messages: Mutex::new(messages),
-        });
-        let use_case = GetSessionHistoryUseCase::new(mock_repo);
+        let (session, mock_repo) = mock_repo(&[
+            (MessageRole::User, "Message 1"),
+            (MessageRole::Assistant, "Response 1"),
+            (MessageRole::User, "Message 2"),
+        ]);
+        let use_case = GetSessionHistoryUseCase::new(mock_repo.clone(), mock_repo.clone());
 
         let request = GetSessionHistoryRequest {
-            session_id,
+            session_id: session.id,
+            user_id: session.user_id,
             limit: Some(2),
+            annotation: None,
         };
 
         let response = use_case.execute(request).await.unwrap();
 
         assert_eq!(response.messages.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_get_session_history_checks_ownership_and_deletion() {
+        let (session, mock_repo) = mock_repo(&[(MessageRole::User, "Hello")]);
+        let use_case = GetSessionHistoryUseCase::new(mock_repo.clone(), mock_repo.clone());
+
+        let other_user = GetSessionHistoryRequest {
+            session_id: session.id,
+            user_id: Uuid::new_v4(),
+            limit: None,
+            annotation: None,
+        };
+        assert!(matches!(
+            use_case.execute(other_user).await,
+            Err(RepositoryError::ValidationError(_))
+        ));
+
+        mock_repo
+            .delete_session(session.id, session.user_id)
+            .await
+            .unwrap();
+        let request = GetSessionHistoryRequest {
+            session_id: session.id,
+            user_id: session.user_id,
+            limit: None,
+            annotation: None,
+        };
+        let result = use_case.execute(request).await;
+        assert!(matches!(result, Err(RepositoryError::SessionNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_session_history_annotation_filter() {
+        let (session, repo) = mock_repo(&[
+            (MessageRole::User, "Question"),
+            (MessageRole::Assistant, "Answer"),
+        ]);
+        let messages = repo.messages();
+        let (session_id, user_id) = (session.id, session.user_id);
+        let repo = Arc::new(
+            InMemoryChatRepository::new()
+                .with_session(session)
+                .with_messages(messages.clone())
+                .with_annotations([
+                    MessageAnnotation::new(
+                        session_id,
+                        messages[1].id,
+                        user_id,
+                        Annotation::Bookmark,
+                    ),
+                    MessageAnnotation::new(
+                        session_id,
+                        messages[0].id,
+                        user_id,
+                        Annotation::Label("todo".to_string()),
+                    ),
+                ]),
+        );
+        let use_case = GetSessionHistoryUseCase::new(repo.clone(), repo);
+
+        let response = use_case
+            .execute(GetSessionHistoryRequest {
+                session_id,
+                user_id,
+                limit: None,
+                annotation: Some(AnnotationFilter::parse("bookmark").unwrap()),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(response.messages[0].content, "Answer");
+        assert_eq!(response.annotations.len(), 1);
+        assert_eq!(response.annotations[0].annotation, Annotation::Bookmark);
+    }
 }
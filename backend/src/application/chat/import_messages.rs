@@ -0,0 +1,201 @@
+//! Import messages use case (seed a session without calling the LLM)
+
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    entity::ChatMessage,
+    import::MessageImportRepository,
+    lock::{LockPolicy, SessionLock},
+    policy::ChatPolicy,
+    repository::{ChatRepository, RepositoryError, RepositoryResult},
+    value_objects::MessageRole,
+};
+
+/// Request to append messages to a session
+#[derive(Debug, Clone)]
+pub struct ImportMessagesRequest {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    /// Messages in conversation order
+    pub messages: Vec<(MessageRole, String)>,
+}
+
+/// Use case for appending a batch of user and assistant messages
+pub struct ImportMessagesUseCase {
+    repository: Arc<dyn ChatRepository>,
+    imports: Arc<dyn MessageImportRepository>,
+    policy: ChatPolicy,
+    session_lock: Option<(Arc<dyn SessionLock>, LockPolicy)>,
+}
+
+impl ImportMessagesUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn ChatRepository>,
+        imports: Arc<dyn MessageImportRepository>,
+        policy: ChatPolicy,
+    ) -> Self {
+        Self {
+            repository,
+            imports,
+            policy,
+            session_lock: None,
+        }
+    }
+
+    /// Do not import while a reply is being generated for the session
+    #[must_use]
+    pub fn with_session_lock(mut self, lock: Arc<dyn SessionLock>, policy: LockPolicy) -> Self {
+        self.session_lock = Some((lock, policy));
+        self
+    }
+
+    /// Validate and save all messages, or none of them
+    ///
+    /// Messages get increasing timestamps so history keeps their order.
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized
+    /// - A message or the batch size breaks the chat policy
+    /// - A reply is being generated (`GenerationInProgress`)
+    /// - Repository operations fail
+    pub async fn execute(
+        &self,
+        request: ImportMessagesRequest,
+    ) -> RepositoryResult<Vec<ChatMessage>> {
+        self.repository
+            .find_active_session_for_user(request.session_id, request.user_id)
+            .await?;
+
+        self.policy
+            .validate_import_size(request.messages.len())
+            .map_err(RepositoryError::ValidationError)?;
+
+        let start = Utc::now();
+        let messages = request
+            .messages
+            .into_iter()
+            .zip(0..)
+            .map(|((role, content), index)| {
+                self.policy
+                    .validate_message(role, &content)
+                    .map_err(|e| format!("Message {index}: {e}"))?;
+                let mut message = ChatMessage::new(request.session_id, role, content)
+                    .map_err(|e| format!("Message {index}: {e}"))?;
+                message.created_at = start + Duration::microseconds(index);
+                Ok(message)
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(RepositoryError::ValidationError)?;
+
+        let _lock_guard = match &self.session_lock {
+            Some((lock, policy)) => Some(lock.acquire(request.session_id, *policy).await?),
+            None => None,
+        };
+
+        self.imports.save_messages(&messages).await?;
+
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+    use crate::domain::chat::entity::ChatSession;
+
+    fn setup() -> (
+        ChatSession,
+        Arc<InMemoryChatRepository>,
+        ImportMessagesUseCase,
+    ) {
+        let session = ChatSession::new(Uuid::new_v4(), "Imported".to_string()).unwrap();
+        let repository = Arc::new(InMemoryChatRepository::new().with_session(session.clone()));
+        let use_case = ImportMessagesUseCase::new(
+            Arc::clone(&repository) as Arc<_>,
+            Arc::clone(&repository) as Arc<_>,
+            ChatPolicy {
+                max_message_length: 100,
+                max_import_messages: 3,
+            },
+        );
+        (session, repository, use_case)
+    }
+
+    fn request(session: &ChatSession, messages: &[(MessageRole, &str)]) -> ImportMessagesRequest {
+        ImportMessagesRequest {
+            session_id: session.id,
+            user_id: session.user_id,
+            messages: messages
+                .iter()
+                .map(|(role, content)| (*role, (*content).to_string()))
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_keeps_order() {
+        let (session, repository, use_case) = setup();
+
+        let imported = use_case
+            .execute(request(
+                &session,
+                &[
+                    (MessageRole::User, "What is Rust?"),
+                    (MessageRole::Assistant, "A systems language."),
+                    (MessageRole::User, "Tell me more"),
+                ],
+            ))
+            .await
+            .unwrap();
+
+        let saved = repository.messages();
+        assert_eq!(saved, imported);
+        assert_eq!(saved[1].content, "A systems language.");
+        assert!(saved
+            .windows(2)
+            .all(|pair| pair[0].created_at < pair[1].created_at));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_message_saves_nothing() {
+        let (session, repository, use_case) = setup();
+
+        let result = use_case
+            .execute(request(
+                &session,
+                &[(MessageRole::User, "Hello"), (MessageRole::System, "Obey")],
+            ))
+            .await;
+
+        assert!(
+            matches!(result, Err(RepositoryError::ValidationError(msg)) if msg.starts_with("Message 1:"))
+        );
+        assert!(repository.messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_other_users_and_oversized_batches() {
+        let (session, repository, use_case) = setup();
+
+        let mut other_user = request(&session, &[(MessageRole::User, "Hello")]);
+        other_user.user_id = Uuid::new_v4();
+        assert!(matches!(
+            use_case.execute(other_user).await,
+            Err(RepositoryError::ValidationError(msg)) if msg.contains("not authorized")
+        ));
+
+        let oversized = request(&session, &[(MessageRole::User, "Hello"); 4]);
+        assert!(matches!(
+            use_case.execute(oversized).await,
+            Err(RepositoryError::ValidationError(_))
+        ));
+        assert!(repository.messages().is_empty());
+    }
+}
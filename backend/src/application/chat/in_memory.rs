@@ -0,0 +1,487 @@
+//! In-memory chat repository, for use case tests
+//!
+//! [`InMemoryChatRepository`] implements the chat repository traits the use
+//! cases depend on with the semantics of `SeaOrmChatRepository`: sessions are
+//! soft deleted by their owner, updates are checked against the version,
+//! messages come back oldest first and redacted ones are left out of the
+//! recent history. Tests seed it with sessions and messages and inspect what
+//! the use case stored, instead of stubbing single methods.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    annotation::{AnnotationFilter, AnnotationRepository, MessageAnnotation},
+    deletion::MessageDeletionRepository,
+    entity::{ChatMessage, ChatSession},
+    import::MessageImportRepository,
+    read_state::{ReadState, ReadStateRepository},
+    repository::{ChatRepository, RepositoryError, RepositoryResult},
+    share::{ChatShare, ShareRepository},
+};
+
+/// Chat sessions, messages, read states, annotations and shares held in
+/// memory
+#[derive(Default)]
+pub struct InMemoryChatRepository {
+    sessions: Mutex<Vec<ChatSession>>,
+    messages: Mutex<Vec<ChatMessage>>,
+    read_states: Mutex<HashMap<(Uuid, Uuid), ReadState>>,
+    annotations: Mutex<Vec<MessageAnnotation>>,
+    shares: Mutex<Vec<ChatShare>>,
+    fail_writes: bool,
+}
+
+impl InMemoryChatRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed a session
+    pub fn with_session(self, session: ChatSession) -> Self {
+        self.with_sessions([session])
+    }
+
+    /// Seed sessions
+    pub fn with_sessions(mut self, sessions: impl IntoIterator<Item = ChatSession>) -> Self {
+        self.sessions.get_mut().unwrap().extend(sessions);
+        self
+    }
+
+    /// Seed messages, which must be in the order they were sent
+    pub fn with_messages(mut self, messages: impl IntoIterator<Item = ChatMessage>) -> Self {
+        self.messages.get_mut().unwrap().extend(messages);
+        self
+    }
+
+    /// Seed annotations
+    pub fn with_annotations(
+        mut self,
+        annotations: impl IntoIterator<Item = MessageAnnotation>,
+    ) -> Self {
+        self.annotations.get_mut().unwrap().extend(annotations);
+        self
+    }
+
+    /// Make every write fail with a database error, as if the connection
+    /// dropped
+    pub const fn failing_writes(mut self) -> Self {
+        self.fail_writes = true;
+        self
+    }
+
+    /// Stored sessions, deleted ones included
+    pub fn sessions(&self) -> Vec<ChatSession> {
+        lock(&self.sessions).clone()
+    }
+
+    /// Stored messages in the order they were saved
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        lock(&self.messages).clone()
+    }
+
+    /// Stored annotations
+    pub fn annotations(&self) -> Vec<MessageAnnotation> {
+        lock(&self.annotations).clone()
+    }
+
+    fn write(&self) -> RepositoryResult<()> {
+        if self.fail_writes {
+            return Err(RepositoryError::DatabaseError(
+                "connection reset".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Messages of a session, oldest first
+    fn session_messages(&self, session_id: Uuid) -> Vec<ChatMessage> {
+        let mut messages: Vec<_> = lock(&self.messages)
+            .iter()
+            .filter(|m| m.session_id == session_id)
+            .cloned()
+            .collect();
+        messages.sort_by_key(|m| m.created_at);
+        messages
+    }
+
+    fn find_session_message(&self, session_id: Uuid, message_id: Uuid) -> Option<ChatMessage> {
+        lock(&self.messages)
+            .iter()
+            .find(|m| m.id == message_id && m.session_id == session_id)
+            .cloned()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap()
+}
+
+fn take<T>(items: Vec<T>, limit: Option<u64>) -> Vec<T> {
+    let limit = limit.map_or(usize::MAX, |limit| usize::try_from(limit).unwrap());
+    items.into_iter().take(limit).collect()
+}
+
+#[async_trait]
+impl ChatRepository for InMemoryChatRepository {
+    async fn create_session(&self, session: &ChatSession) -> RepositoryResult<()> {
+        self.write()?;
+        lock(&self.sessions).push(session.clone());
+        Ok(())
+    }
+
+    async fn find_session_by_id(&self, id: Uuid) -> RepositoryResult<Option<ChatSession>> {
+        Ok(lock(&self.sessions).iter().find(|s| s.id == id).cloned())
+    }
+
+    async fn find_sessions_by_user(
+        &self,
+        user_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> RepositoryResult<(Vec<ChatSession>, u64)> {
+        let mut sessions: Vec<_> = lock(&self.sessions)
+            .iter()
+            .filter(|s| s.user_id == user_id && !s.is_deleted())
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+
+        let total = sessions.len() as u64;
+        let skip = usize::try_from(page * per_page).unwrap();
+        let sessions = take(sessions.into_iter().skip(skip).collect(), Some(per_page));
+        Ok((sessions, total))
+    }
+
+    async fn update_session(&self, session: &ChatSession) -> RepositoryResult<()> {
+        self.write()?;
+        let mut sessions = lock(&self.sessions);
+        let stored = sessions
+            .iter_mut()
+            .find(|s| s.id == session.id && !s.is_deleted())
+            .ok_or(RepositoryError::SessionNotFound(session.id))?;
+        if stored.version != session.version {
+            return Err(RepositoryError::SessionConflict(session.id));
+        }
+        stored.title.clone_from(&session.title);
+        stored.updated_at = Utc::now();
+        stored.version += 1;
+        drop(sessions);
+        Ok(())
+    }
+
+    async fn delete_session(&self, id: Uuid, user_id: Uuid) -> RepositoryResult<()> {
+        self.write()?;
+        let mut sessions = lock(&self.sessions);
+        let stored = sessions
+            .iter_mut()
+            .find(|s| s.id == id && s.user_id == user_id && !s.is_deleted())
+            .ok_or(RepositoryError::SessionNotFound(id))?;
+        stored.mark_deleted();
+        drop(sessions);
+        Ok(())
+    }
+
+    async fn save_message(&self, message: &ChatMessage) -> RepositoryResult<()> {
+        self.write()?;
+        lock(&self.messages).push(message.clone());
+        Ok(())
+    }
+
+    async fn find_messages_by_session(
+        &self,
+        session_id: Uuid,
+        limit: Option<u64>,
+    ) -> RepositoryResult<Vec<ChatMessage>> {
+        Ok(take(self.session_messages(session_id), limit))
+    }
+
+    async fn find_recent_messages(
+        &self,
+        session_id: Uuid,
+        limit: u64,
+    ) -> RepositoryResult<Vec<ChatMessage>> {
+        let mut recent: Vec<_> = self
+            .session_messages(session_id)
+            .into_iter()
+            .filter(|m| m.redacted_at.is_none())
+            .rev()
+            .collect();
+        recent = take(recent, Some(limit));
+        recent.reverse();
+        Ok(recent)
+    }
+}
+
+#[async_trait]
+impl MessageImportRepository for InMemoryChatRepository {
+    async fn save_messages(&self, messages: &[ChatMessage]) -> RepositoryResult<()> {
+        self.write()?;
+        lock(&self.messages).extend_from_slice(messages);
+        Ok(())
+    }
+
+    async fn create_session_with_messages(
+        &self,
+        session: &ChatSession,
+        messages: &[ChatMessage],
+    ) -> RepositoryResult<()> {
+        self.write()?;
+        lock(&self.sessions).push(session.clone());
+        lock(&self.messages).extend_from_slice(messages);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageDeletionRepository for InMemoryChatRepository {
+    async fn find_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> RepositoryResult<Option<ChatMessage>> {
+        Ok(self.find_session_message(session_id, message_id))
+    }
+
+    async fn delete_message(&self, message: &ChatMessage) -> RepositoryResult<()> {
+        self.write()?;
+        // Read markers on the message move to the one before it
+        let previous = self
+            .session_messages(message.session_id)
+            .into_iter()
+            .filter(|m| m.id != message.id && m.created_at <= message.created_at)
+            .next_back();
+        let mut read_states = lock(&self.read_states);
+        match previous {
+            Some(previous) => read_states
+                .values_mut()
+                .filter(|state| state.last_read_message_id == message.id)
+                .for_each(|state| state.last_read_message_id = previous.id),
+            None => read_states.retain(|_, state| state.last_read_message_id != message.id),
+        }
+        drop(read_states);
+        lock(&self.annotations).retain(|a| a.message_id != message.id);
+
+        let mut messages = lock(&self.messages);
+        let before = messages.len();
+        messages.retain(|m| m.id != message.id);
+        let removed = messages.len() < before;
+        drop(messages);
+        if removed {
+            Ok(())
+        } else {
+            Err(RepositoryError::MessageNotFound(message.id))
+        }
+    }
+
+    async fn redact_message(&self, message: &ChatMessage) -> RepositoryResult<()> {
+        self.write()?;
+        let mut messages = lock(&self.messages);
+        let stored = messages
+            .iter_mut()
+            .find(|m| m.id == message.id)
+            .ok_or(RepositoryError::MessageNotFound(message.id))?;
+        stored.content.clone_from(&message.content);
+        stored.redacted_at = message.redacted_at;
+        drop(messages);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReadStateRepository for InMemoryChatRepository {
+    async fn find_read_state(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> RepositoryResult<Option<ReadState>> {
+        Ok(lock(&self.read_states).get(&(user_id, session_id)).cloned())
+    }
+
+    async fn mark_read(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> RepositoryResult<()> {
+        self.write()?;
+        let message = self
+            .find_session_message(session_id, message_id)
+            .ok_or(RepositoryError::MessageNotFound(message_id))?;
+
+        // Never move the read position backwards
+        let mut read_states = lock(&self.read_states);
+        let state = read_states
+            .entry((user_id, session_id))
+            .or_insert_with(|| ReadState {
+                user_id,
+                session_id,
+                last_read_message_id: message.id,
+                last_read_at: message.created_at,
+            });
+        if state.last_read_at < message.created_at {
+            state.last_read_message_id = message.id;
+            state.last_read_at = message.created_at;
+        }
+        drop(read_states);
+        Ok(())
+    }
+
+    async fn count_unread(
+        &self,
+        user_id: Uuid,
+        session_ids: &[Uuid],
+    ) -> RepositoryResult<HashMap<Uuid, u64>> {
+        let read_at: HashMap<_, _> = lock(&self.read_states)
+            .values()
+            .filter(|state| state.user_id == user_id)
+            .map(|state| (state.session_id, state.last_read_at))
+            .collect();
+
+        // Sessions without unread messages are left out
+        Ok(session_ids
+            .iter()
+            .map(|id| {
+                let unread = self
+                    .session_messages(*id)
+                    .iter()
+                    .filter(|m| read_at.get(id).map_or(true, |at| m.created_at > *at))
+                    .count() as u64;
+                (*id, unread)
+            })
+            .filter(|(_, unread)| *unread > 0)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl AnnotationRepository for InMemoryChatRepository {
+    async fn add_annotation(
+        &self,
+        annotation: &MessageAnnotation,
+    ) -> RepositoryResult<MessageAnnotation> {
+        self.write()?;
+        self.find_session_message(annotation.session_id, annotation.message_id)
+            .ok_or(RepositoryError::MessageNotFound(annotation.message_id))?;
+
+        let mut annotations = lock(&self.annotations);
+        let existing = annotations
+            .iter()
+            .find(|a| {
+                a.message_id == annotation.message_id
+                    && a.user_id == annotation.user_id
+                    && a.annotation == annotation.annotation
+            })
+            .cloned();
+        let added = existing.unwrap_or_else(|| {
+            annotations.push(annotation.clone());
+            annotation.clone()
+        });
+        drop(annotations);
+        Ok(added)
+    }
+
+    async fn remove_annotation(
+        &self,
+        user_id: Uuid,
+        message_id: Uuid,
+        annotation_id: Uuid,
+    ) -> RepositoryResult<()> {
+        self.write()?;
+        let mut annotations = lock(&self.annotations);
+        let before = annotations.len();
+        annotations
+            .retain(|a| (a.id, a.message_id, a.user_id) != (annotation_id, message_id, user_id));
+        let removed = annotations.len() < before;
+        drop(annotations);
+        if removed {
+            Ok(())
+        } else {
+            Err(RepositoryError::AnnotationNotFound(annotation_id))
+        }
+    }
+
+    async fn find_annotations_by_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> RepositoryResult<Vec<MessageAnnotation>> {
+        let mut annotations: Vec<_> = lock(&self.annotations)
+            .iter()
+            .filter(|a| a.user_id == user_id && a.session_id == session_id)
+            .cloned()
+            .collect();
+        annotations.sort_by_key(|a| a.created_at);
+        Ok(annotations)
+    }
+
+    async fn find_annotated_messages(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        filter: &AnnotationFilter,
+        limit: Option<u64>,
+    ) -> RepositoryResult<Vec<ChatMessage>> {
+        let annotated: Vec<_> = lock(&self.annotations)
+            .iter()
+            .filter(|a| {
+                a.user_id == user_id && a.session_id == session_id && filter.matches(&a.annotation)
+            })
+            .map(|a| a.message_id)
+            .collect();
+        let messages = self
+            .session_messages(session_id)
+            .into_iter()
+            .filter(|m| annotated.contains(&m.id))
+            .collect();
+        Ok(take(messages, limit))
+    }
+}
+
+#[async_trait]
+impl ShareRepository for InMemoryChatRepository {
+    async fn create_share(&self, share: &ChatShare) -> RepositoryResult<()> {
+        self.write()?;
+        lock(&self.shares).push(share.clone());
+        Ok(())
+    }
+
+    async fn find_share_by_slug(&self, slug: &str) -> RepositoryResult<Option<ChatShare>> {
+        Ok(lock(&self.shares).iter().find(|s| s.slug == slug).cloned())
+    }
+
+    async fn find_shares_by_session(&self, session_id: Uuid) -> RepositoryResult<Vec<ChatShare>> {
+        let mut shares: Vec<_> = lock(&self.shares)
+            .iter()
+            .filter(|s| s.session_id == session_id)
+            .cloned()
+            .collect();
+        shares.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        Ok(shares)
+    }
+
+    async fn revoke_share(&self, session_id: Uuid, share_id: Uuid) -> RepositoryResult<()> {
+        self.write()?;
+        // Revoking twice is fine; only an unknown share is an error
+        lock(&self.shares)
+            .iter_mut()
+            .find(|s| s.id == share_id && s.session_id == session_id)
+            .ok_or(RepositoryError::ShareNotFound)?
+            .revoked_at
+            .get_or_insert_with(Utc::now);
+        Ok(())
+    }
+
+    async fn increment_share_views(&self, share_id: Uuid) -> RepositoryResult<()> {
+        self.write()?;
+        if let Some(share) = lock(&self.shares).iter_mut().find(|s| s.id == share_id) {
+            share.view_count += 1;
+        }
+        Ok(())
+    }
+}
@@ -41,7 +41,10 @@ impl ListUserSessionsUseCase {
     ///
     /// # Errors
     /// Returns `RepositoryError` if retrieval fails
-    pub async fn execute(&self, request: ListUserSessionsRequest) -> RepositoryResult<ListUserSessionsResponse> {
+    pub async fn execute(
+        &self,
+        request: ListUserSessionsRequest,
+    ) -> RepositoryResult<ListUserSessionsResponse> {
         let (sessions, total) = self
             .repository
             .find_sessions_by_user(request.user_id, request.page, request.per_page)
@@ -59,73 +62,7 @@ impl ListUserSessionsUseCase {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::chat::{entity::ChatMessage, repository::RepositoryError};
-    use async_trait::async_trait;
-    use std::sync::Mutex;
-
-    struct MockChatRepository {
-        sessions: Mutex<Vec<ChatSession>>,
-    }
-
-    #[async_trait]
-    impl ChatRepository for MockChatRepository {
-        async fn create_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_session_by_id(&self, _id: Uuid) -> RepositoryResult<Option<ChatSession>> {
-            unimplemented!()
-        }
-
-        async fn find_sessions_by_user(
-            &self,
-            user_id: Uuid,
-            page: u64,
-            per_page: u64,
-        ) -> RepositoryResult<(Vec<ChatSession>, u64)> {
-            let sessions = self.sessions.lock().unwrap();
-            let user_sessions: Vec<_> = sessions
-                .iter()
-                .filter(|s| s.user_id == user_id && s.deleted_at.is_none())
-                .cloned()
-                .collect();
-
-            let total = user_sessions.len() as u64;
-            let start = (page * per_page) as usize;
-            let end = std::cmp::min(start + per_page as usize, user_sessions.len());
-            let page_sessions = user_sessions[start..end].to_vec();
-
-            Ok((page_sessions, total))
-        }
-
-        async fn update_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn delete_session(&self, _id: Uuid) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn save_message(&self, _message: &ChatMessage) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_messages_by_session(
-            &self,
-            _session_id: Uuid,
-            _limit: Option<u64>,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            unimplemented!()
-        }
-
-        async fn find_recent_messages(
-            &self,
-            _session_id: Uuid,
-            _limit: u64,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            unimplemented!()
-        }
-    }
+    use crate::application::chat::in_memory::InMemoryChatRepository;
 
     #[tokio::test]
     async fn test_list_user_sessions() {
@@ -136,9 +73,7 @@ mod tests {
             ChatSession::new(user_id, "Session 3".to_string()).unwrap(),
         ];
 
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(sessions),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new().with_sessions(sessions));
         let use_case = ListUserSessionsUseCase::new(mock_repo);
 
         let request = ListUserSessionsRequest {
@@ -164,9 +99,7 @@ mod tests {
             ChatSession::new(user_id, "Session 3".to_string()).unwrap(),
         ];
 
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(sessions),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new().with_sessions(sessions));
         let use_case = ListUserSessionsUseCase::new(mock_repo);
 
         let request = ListUserSessionsRequest {
@@ -0,0 +1,237 @@
+//! Message annotation use case (bookmarks, labels and reactions)
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    Annotation, AnnotationFilter, AnnotationKind, AnnotationRepository, ChatRepository,
+    MessageAnnotation, RepositoryError, RepositoryResult,
+};
+
+/// Request to annotate a message
+#[derive(Debug, Clone)]
+pub struct AddAnnotationRequest {
+    pub session_id: Uuid,
+    pub message_id: Uuid,
+    pub user_id: Uuid,
+    pub kind: AnnotationKind,
+    /// Label text or emoji; must be absent for bookmarks
+    pub value: Option<String>,
+}
+
+/// Use case for adding, removing and listing a user's message annotations
+pub struct MessageAnnotationsUseCase {
+    repository: Arc<dyn ChatRepository>,
+    annotations: Arc<dyn AnnotationRepository>,
+}
+
+impl MessageAnnotationsUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn ChatRepository>,
+        annotations: Arc<dyn AnnotationRepository>,
+    ) -> Self {
+        Self {
+            repository,
+            annotations,
+        }
+    }
+
+    /// Annotate a message, returning the existing annotation if the user
+    /// already added the same one
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized
+    /// - Value invalid for the kind
+    /// - Message not found in the session
+    /// - Repository operations fail
+    pub async fn add(&self, request: AddAnnotationRequest) -> RepositoryResult<MessageAnnotation> {
+        self.authorize(request.session_id, request.user_id).await?;
+
+        let annotation = Annotation::new(request.kind, request.value.as_deref())
+            .map_err(RepositoryError::ValidationError)?;
+
+        self.annotations
+            .add_annotation(&MessageAnnotation::new(
+                request.session_id,
+                request.message_id,
+                request.user_id,
+                annotation,
+            ))
+            .await
+    }
+
+    /// Remove one of the user's annotations from a message
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized
+    /// - Annotation not found on the message
+    /// - Repository operations fail
+    pub async fn remove(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+        annotation_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<()> {
+        self.authorize(session_id, user_id).await?;
+
+        self.annotations
+            .remove_annotation(user_id, message_id, annotation_id)
+            .await
+    }
+
+    /// List the user's annotations in a session, optionally only those
+    /// `filter` selects
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized
+    /// - Repository operations fail
+    pub async fn list(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        filter: Option<&AnnotationFilter>,
+    ) -> RepositoryResult<Vec<MessageAnnotation>> {
+        self.authorize(session_id, user_id).await?;
+
+        let mut annotations = self
+            .annotations
+            .find_annotations_by_session(user_id, session_id)
+            .await?;
+        if let Some(filter) = filter {
+            annotations.retain(|annotation| filter.matches(&annotation.annotation));
+        }
+        Ok(annotations)
+    }
+
+    async fn authorize(&self, session_id: Uuid, user_id: Uuid) -> RepositoryResult<()> {
+        self.repository
+            .find_active_session_for_user(session_id, user_id)
+            .await
+            .map(drop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+    use crate::domain::chat::{
+        entity::{ChatMessage, ChatSession},
+        value_objects::MessageRole,
+    };
+
+    fn setup() -> (Arc<InMemoryChatRepository>, MessageAnnotationsUseCase) {
+        let session = ChatSession::new(Uuid::new_v4(), "Research".to_string()).unwrap();
+        let messages = vec![
+            ChatMessage::new(session.id, MessageRole::User, "Question".to_string()).unwrap(),
+            ChatMessage::new(session.id, MessageRole::Assistant, "Answer".to_string()).unwrap(),
+        ];
+        let repo = Arc::new(
+            InMemoryChatRepository::new()
+                .with_session(session)
+                .with_messages(messages),
+        );
+        let use_case = MessageAnnotationsUseCase::new(repo.clone(), repo.clone());
+        (repo, use_case)
+    }
+
+    fn request(
+        repo: &InMemoryChatRepository,
+        kind: AnnotationKind,
+        value: Option<&str>,
+    ) -> AddAnnotationRequest {
+        let session = &repo.sessions()[0];
+        AddAnnotationRequest {
+            session_id: session.id,
+            message_id: repo.messages()[1].id,
+            user_id: session.user_id,
+            kind,
+            value: value.map(ToString::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_is_idempotent() {
+        let (repo, use_case) = setup();
+
+        let first = use_case
+            .add(request(&repo, AnnotationKind::Reaction, Some("👍")))
+            .await
+            .unwrap();
+        let second = use_case
+            .add(request(&repo, AnnotationKind::Reaction, Some("👍")))
+            .await
+            .unwrap();
+
+        assert_eq!(first.id, second.id);
+        assert_eq!(repo.annotations().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_add_validates_value() {
+        let (repo, use_case) = setup();
+
+        let result = use_case
+            .add(request(&repo, AnnotationKind::Label, None))
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_with_filter_and_remove() {
+        let (repo, use_case) = setup();
+        let session = repo.sessions()[0].clone();
+        let bookmark = use_case
+            .add(request(&repo, AnnotationKind::Bookmark, None))
+            .await
+            .unwrap();
+        use_case
+            .add(request(&repo, AnnotationKind::Label, Some("todo")))
+            .await
+            .unwrap();
+
+        let filter = AnnotationFilter::parse("bookmark").unwrap();
+        let bookmarks = use_case
+            .list(session.id, session.user_id, Some(&filter))
+            .await
+            .unwrap();
+        assert_eq!(bookmarks, vec![bookmark.clone()]);
+
+        use_case
+            .remove(
+                session.id,
+                bookmark.message_id,
+                bookmark.id,
+                session.user_id,
+            )
+            .await
+            .unwrap();
+        let remaining = use_case
+            .list(session.id, session.user_id, None)
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_other_user_is_rejected() {
+        let (repo, use_case) = setup();
+        let mut other = request(&repo, AnnotationKind::Bookmark, None);
+        other.user_id = Uuid::new_v4();
+
+        assert!(matches!(
+            use_case.add(other).await,
+            Err(RepositoryError::ValidationError(msg)) if msg.contains("not authorized")
+        ));
+    }
+}
@@ -2,16 +2,45 @@
 //!
 //! Use cases for chat session and message management.
 
+pub mod account_suspension;
+pub mod batch_jobs;
 pub mod create_session;
-pub mod send_message;
-pub mod send_message_v2; // New provider-based implementation
+pub mod delete_message;
+pub mod delete_session;
+pub mod event_subscribers;
+pub mod events;
+pub mod generation;
 pub mod get_session_history;
+pub mod import_messages;
+#[cfg(test)]
+pub(crate) mod in_memory;
 pub mod list_user_sessions;
-pub mod delete_session;
+pub mod message_annotations;
+pub mod rename_session;
+pub mod send_message;
+pub mod send_message_v2; // New provider-based implementation
+pub mod session_read_state;
+pub mod share_session;
+pub mod stream_supervisor;
+pub mod usage_analytics;
+pub mod webhooks;
 
+pub use account_suspension::AccountSuspensionPolicy;
+pub use batch_jobs::{BatchJobsUseCase, JobRunner};
 pub use create_session::CreateSessionUseCase;
-pub use send_message::SendMessageUseCase;
-pub use send_message_v2::SendMessageUseCase as SendMessageUseCaseV2;
+pub use delete_message::DeleteMessageUseCase;
+pub use delete_session::DeleteSessionUseCase;
+pub use events::EventBus;
+pub use generation::GenerationStore;
 pub use get_session_history::GetSessionHistoryUseCase;
+pub use import_messages::ImportMessagesUseCase;
 pub use list_user_sessions::ListUserSessionsUseCase;
-pub use delete_session::DeleteSessionUseCase;
+pub use message_annotations::MessageAnnotationsUseCase;
+pub use rename_session::RenameSessionUseCase;
+pub use send_message::SendMessageUseCase;
+pub use send_message_v2::SendMessageUseCase as SendMessageUseCaseV2;
+pub use session_read_state::SessionReadStateUseCase;
+pub use share_session::ShareSessionUseCase;
+pub use stream_supervisor::StreamMetrics;
+pub use usage_analytics::UsageAnalyticsUseCase;
+pub use webhooks::{ChatWebhooksUseCase, WebhookDispatcher};
@@ -0,0 +1,116 @@
+//! Rename chat session use case
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    entity::ChatSession,
+    repository::{ChatRepository, RepositoryError, RepositoryResult},
+};
+
+/// Request to rename a chat session
+#[derive(Debug, Clone)]
+pub struct RenameSessionRequest {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+    /// Session version the client last read
+    pub version: i32,
+}
+
+/// Use case for renaming a chat session with optimistic locking
+pub struct RenameSessionUseCase {
+    repository: Arc<dyn ChatRepository>,
+}
+
+impl RenameSessionUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub fn new(repository: Arc<dyn ChatRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Rename the session, returning it at its new version
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found or deleted
+    /// - User not authorized
+    /// - Title is invalid
+    /// - The session changed since `version` (`SessionConflict`)
+    /// - Repository operations fail
+    pub async fn execute(&self, request: RenameSessionRequest) -> RepositoryResult<ChatSession> {
+        let mut session = self
+            .repository
+            .find_active_session_for_user(request.session_id, request.user_id)
+            .await?;
+
+        if session.version != request.version {
+            return Err(RepositoryError::SessionConflict(session.id));
+        }
+
+        session
+            .update_title(request.title)
+            .map_err(RepositoryError::ValidationError)?;
+        self.repository.update_session(&session).await?;
+        session.version += 1;
+
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+
+    fn setup() -> (ChatSession, RenameSessionUseCase) {
+        let session = ChatSession::new(Uuid::new_v4(), "Original".to_string()).unwrap();
+        let repository = Arc::new(InMemoryChatRepository::new().with_session(session.clone()));
+        (session, RenameSessionUseCase::new(repository))
+    }
+
+    fn rename(session: &ChatSession, title: &str, version: i32) -> RenameSessionRequest {
+        RenameSessionRequest {
+            session_id: session.id,
+            user_id: session.user_id,
+            title: title.to_string(),
+            version,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rename_session() {
+        let (session, use_case) = setup();
+
+        let renamed = use_case
+            .execute(rename(&session, "Renamed", 0))
+            .await
+            .unwrap();
+
+        assert_eq!(renamed.title, "Renamed");
+        assert_eq!(renamed.version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stale_rename_conflicts() {
+        let (session, use_case) = setup();
+
+        use_case
+            .execute(rename(&session, "First", 0))
+            .await
+            .unwrap();
+        let result = use_case.execute(rename(&session, "Second", 0)).await;
+
+        assert!(matches!(result, Err(RepositoryError::SessionConflict(id)) if id == session.id));
+    }
+
+    #[tokio::test]
+    async fn test_rename_rejects_invalid_title() {
+        let (session, use_case) = setup();
+
+        let result = use_case.execute(rename(&session, "", 0)).await;
+
+        assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+    }
+}
@@ -1,24 +1,32 @@
 //! Send message use case with LLM streaming
 
-use std::sync::Arc;
-use uuid::Uuid;
 use async_openai::{
-    Client,
     config::OpenAIConfig,
     types::{
-        ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
-        ChatCompletionRequestUserMessageArgs, ChatCompletionRequestAssistantMessageArgs,
+        ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
         CreateChatCompletionRequestArgs, Role,
     },
+    Client,
+};
+use futures::StreamExt;
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use super::account_suspension::ensure_can_generate;
+use super::stream_supervisor::{
+    counted_message, supervise, GenerationTracking, StreamContext, StreamMetrics,
 };
-use futures::Stream;
-use std::pin::Pin;
 
 use crate::domain::chat::{
     entity::{ChatMessage, ChatSession},
+    events::{ChatEvent, EventPublisher},
+    lock::{LockPolicy, SessionLock, SessionLockGuard},
     repository::{ChatRepository, RepositoryError, RepositoryResult},
+    suspension::SuspensionRepository,
     value_objects::MessageRole,
 };
+use crate::services::tokenizer::{Tokenizer, TokenizerService};
 
 /// Request to send a message in a chat session
 #[derive(Debug, Clone)]
@@ -28,12 +36,7 @@ pub struct SendMessageRequest {
     pub content: String,
 }
 
-/// Streaming chunk from LLM response
-#[derive(Debug, Clone)]
-pub struct StreamChunk {
-    pub content: String,
-    pub is_final: bool,
-}
+pub use super::stream_supervisor::{ChunkStream, StreamChunk};
 
 /// Configuration for LLM client
 #[derive(Debug, Clone)]
@@ -49,6 +52,12 @@ pub struct LlmConfig {
 pub struct SendMessageUseCase {
     repository: Arc<dyn ChatRepository>,
     llm_config: LlmConfig,
+    session_lock: Option<(Arc<dyn SessionLock>, LockPolicy)>,
+    stream_metrics: Option<Arc<StreamMetrics>>,
+    stream_idle_timeout: Option<Duration>,
+    tokenizers: Option<Arc<TokenizerService>>,
+    events: Option<Arc<dyn EventPublisher>>,
+    suspension: Option<Arc<dyn SuspensionRepository>>,
 }
 
 impl SendMessageUseCase {
@@ -58,6 +67,74 @@ impl SendMessageUseCase {
         Self {
             repository,
             llm_config,
+            session_lock: None,
+            stream_metrics: None,
+            stream_idle_timeout: None,
+            tokenizers: None,
+            events: None,
+            suspension: None,
+        }
+    }
+
+    /// Serialize generations on a session (see [`SessionLock`])
+    ///
+    /// The returned guard must live until the response stream completes.
+    #[must_use]
+    pub fn with_session_lock(mut self, lock: Arc<dyn SessionLock>, policy: LockPolicy) -> Self {
+        self.session_lock = Some((lock, policy));
+        self
+    }
+
+    /// Record stream outcomes and durations in `metrics`
+    #[must_use]
+    pub fn with_stream_metrics(mut self, metrics: Arc<StreamMetrics>) -> Self {
+        self.stream_metrics = Some(metrics);
+        self
+    }
+
+    /// Abort the reply when the client stops reading for `idle_timeout`
+    /// (`None` = wait as long as the connection stays open)
+    #[must_use]
+    pub const fn with_stream_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.stream_idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Store token counts on the user and assistant messages
+    #[must_use]
+    pub fn with_tokenizers(mut self, tokenizers: Arc<TokenizerService>) -> Self {
+        self.tokenizers = Some(tokenizers);
+        self
+    }
+
+    /// Publish the sent message and the end of each reply to `events`
+    #[must_use]
+    pub fn with_events(mut self, events: Arc<dyn EventPublisher>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Refuse to generate for users whose account is disabled
+    #[must_use]
+    pub fn with_suspension(mut self, suspension: Arc<dyn SuspensionRepository>) -> Self {
+        self.suspension = Some(suspension);
+        self
+    }
+
+    /// Tokenizer for `model`, if token counting is enabled
+    fn tokenizer(&self, model: &str) -> Option<Arc<dyn Tokenizer>> {
+        let tokenizers = self.tokenizers.as_ref()?;
+        tokenizers
+            .for_model(model)
+            .map_err(|e| tracing::warn!("No tokenizer for model '{}': {}", model, e))
+            .ok()
+    }
+
+    /// Take the session lock if one is configured
+    async fn lock_session(&self, session_id: Uuid) -> RepositoryResult<Option<SessionLockGuard>> {
+        match &self.session_lock {
+            Some((lock, policy)) => lock.acquire(session_id, *policy).await.map(Some),
+            None => Ok(None),
         }
     }
 
@@ -67,34 +144,45 @@ impl SendMessageUseCase {
     /// Returns `RepositoryError` if:
     /// - Session not found
     /// - User not authorized
+    /// - User's account is disabled (`AccountDisabled`)
     /// - Message validation fails
     /// - Repository operations fail
+    /// - Another generation holds the session lock (`GenerationInProgress`)
     pub async fn execute(
         &self,
         request: SendMessageRequest,
-    ) -> RepositoryResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, String>> + Send>>> {
+    ) -> RepositoryResult<ChunkStream> {
         // Verify session exists and belongs to user
-        let session = self
-            .repository
-            .find_session_by_id(request.session_id)
-            .await?
-            .ok_or(RepositoryError::SessionNotFound(request.session_id))?;
-
-        if session.user_id != request.user_id {
-            return Err(RepositoryError::ValidationError(
-                "User not authorized for this session".to_string(),
-            ));
+        self.repository
+            .find_active_session_for_user(request.session_id, request.user_id)
+            .await?;
+
+        if let Some(suspension) = &self.suspension {
+            ensure_can_generate(suspension.as_ref(), request.user_id).await?;
         }
 
+        // Held until the response stream finishes so sends cannot interleave
+        let lock_guard = self.lock_session(request.session_id).await?;
+
         // Create and save user message
-        let user_message = ChatMessage::new(
+        let tokenizer = self.tokenizer(&self.llm_config.model);
+        let user_message = counted_message(
             request.session_id,
             MessageRole::User,
             request.content.clone(),
+            tokenizer.as_deref(),
         )
         .map_err(|e| RepositoryError::ValidationError(e))?;
 
         self.repository.save_message(&user_message).await?;
+        if let Some(events) = &self.events {
+            events.publish(ChatEvent::MessageSent {
+                session_id: request.session_id,
+                user_id: request.user_id,
+                message_id: user_message.id,
+                content: request.content.clone(),
+            });
+        }
 
         // Get recent context messages
         let context_messages = self
@@ -105,8 +193,25 @@ impl SendMessageUseCase {
         // Build LLM request
         let llm_messages = self.build_llm_messages(&context_messages)?;
 
+        let tracking = self.events.as_ref().map(|events| {
+            GenerationTracking::new(
+                Arc::clone(events),
+                request.user_id,
+                &self.llm_config.model,
+                &context_messages,
+            )
+        });
+
         // Create streaming response
-        let stream = self.create_llm_stream(llm_messages, request.session_id).await?;
+        let stream = self
+            .create_llm_stream(
+                llm_messages,
+                request.session_id,
+                lock_guard,
+                tokenizer,
+                tracking,
+            )
+            .await?;
 
         Ok(stream)
     }
@@ -150,7 +255,10 @@ impl SendMessageUseCase {
         &self,
         messages: Vec<ChatCompletionRequestMessage>,
         session_id: Uuid,
-    ) -> RepositoryResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, String>> + Send>>> {
+        lock_guard: Option<SessionLockGuard>,
+        tokenizer: Option<Arc<dyn Tokenizer>>,
+        tracking: Option<GenerationTracking>,
+    ) -> RepositoryResult<ChunkStream> {
         // Configure OpenAI client for SambaNova API
         let config = OpenAIConfig::new()
             .with_api_base(&self.llm_config.api_base)
@@ -167,165 +275,57 @@ impl SendMessageUseCase {
             .build()
             .map_err(|e| RepositoryError::ValidationError(e.to_string()))?;
 
-        tracing::info!("Initiating LLM stream request to: {}", self.llm_config.api_base);
+        tracing::info!(
+            "Initiating LLM stream request to: {}",
+            self.llm_config.api_base
+        );
 
         // Start streaming
-        let mut stream = client
-            .chat()
-            .create_stream(request)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to create LLM stream: {}", e);
-                RepositoryError::DatabaseError(e.to_string())
-            })?;
+        let stream = client.chat().create_stream(request).await.map_err(|e| {
+            tracing::error!("Failed to create LLM stream: {}", e);
+            RepositoryError::ProviderUnavailable(e.to_string())
+        })?;
 
         tracing::info!("LLM stream created successfully");
 
-        // Process stream and save assistant message
-        let repository = Arc::clone(&self.repository);
-        let mut accumulated_content = String::new();
-
-        use futures::StreamExt;
-        let output_stream = async_stream::stream! {
-            tracing::info!("Starting LLM stream processing");
-            let mut chunk_count = 0;
-
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok(response) => {
-                        tracing::debug!("Received stream response with {} choices", response.choices.len());
-
-                        for choice in response.choices {
-                            if let Some(content) = &choice.delta.content {
-                                chunk_count += 1;
-                                tracing::debug!("Chunk #{}: {} bytes", chunk_count, content.len());
-                                accumulated_content.push_str(content);
-                                yield Ok(StreamChunk {
-                                    content: content.clone(),
-                                    is_final: false,
-                                });
-                            }
-
-                            // Check if streaming is done
-                            if let Some(reason) = &choice.finish_reason {
-                                tracing::info!("Stream finished: reason={:?}, total_chunks={}, content_length={}",
-                                    reason, chunk_count, accumulated_content.len());
-
-                                // Save complete assistant message
-                                if !accumulated_content.is_empty() {
-                                    let assistant_message = match ChatMessage::new(
-                                        session_id,
-                                        MessageRole::Assistant,
-                                        accumulated_content.clone(),
-                                    ) {
-                                        Ok(msg) => msg,
-                                        Err(e) => {
-                                            tracing::error!("Failed to create message: {}", e);
-                                            yield Err(format!("Failed to create message: {}", e));
-                                            return;
-                                        }
-                                    };
-
-                                    if let Err(e) = repository.save_message(&assistant_message).await {
-                                        tracing::error!("Failed to save message: {}", e);
-                                        yield Err(format!("Failed to save message: {}", e));
-                                        return;
-                                    }
-
-                                    tracing::info!("Assistant message saved successfully");
-                                }
-
-                                yield Ok(StreamChunk {
-                                    content: String::new(),
-                                    is_final: true,
-                                });
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("LLM stream error: {}", e);
-                        yield Err(format!("Stream error: {}", e));
-                        return;
-                    }
-                }
-            }
-
-            tracing::warn!("Stream ended without finish_reason (chunks received: {})", chunk_count);
-        };
+        tracing::info!("Starting LLM stream processing");
+        let source = stream.flat_map(|result| {
+            let chunks: Vec<Result<StreamChunk, String>> = match result {
+                Ok(response) => response
+                    .choices
+                    .into_iter()
+                    .map(|choice| {
+                        Ok(StreamChunk {
+                            content: choice.delta.content.unwrap_or_default(),
+                            is_final: choice.finish_reason.is_some(),
+                            usage: None,
+                        })
+                    })
+                    .collect(),
+                Err(e) => vec![Err(e.to_string())],
+            };
+            futures::stream::iter(chunks)
+        });
 
-        Ok(Box::pin(output_stream))
+        // Saves the reply (or whatever arrived of it) and releases the lock
+        let context = StreamContext {
+            session_id,
+            repository: Arc::clone(&self.repository),
+            lock_guard,
+            metrics: self.stream_metrics.clone(),
+            tokenizer,
+            tracking,
+            idle_timeout: self.stream_idle_timeout,
+        };
+        Ok(supervise(Box::pin(source), context))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
     use crate::domain::chat::{entity::ChatSession, repository::RepositoryError};
-    use async_trait::async_trait;
-    use std::sync::Mutex;
-
-    struct MockChatRepository {
-        sessions: Mutex<Vec<ChatSession>>,
-        messages: Mutex<Vec<ChatMessage>>,
-    }
-
-    #[async_trait]
-    impl ChatRepository for MockChatRepository {
-        async fn create_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_session_by_id(&self, id: Uuid) -> RepositoryResult<Option<ChatSession>> {
-            let sessions = self.sessions.lock().unwrap();
-            Ok(sessions.iter().find(|s| s.id == id).cloned())
-        }
-
-        async fn find_sessions_by_user(
-            &self,
-            _user_id: Uuid,
-            _page: u64,
-            _per_page: u64,
-        ) -> RepositoryResult<(Vec<ChatSession>, u64)> {
-            unimplemented!()
-        }
-
-        async fn update_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn delete_session(&self, _id: Uuid) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn save_message(&self, message: &ChatMessage) -> RepositoryResult<()> {
-            self.messages.lock().unwrap().push(message.clone());
-            Ok(())
-        }
-
-        async fn find_messages_by_session(
-            &self,
-            _session_id: Uuid,
-            _limit: Option<u64>,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            unimplemented!()
-        }
-
-        async fn find_recent_messages(
-            &self,
-            session_id: Uuid,
-            limit: u64,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            let messages = self.messages.lock().unwrap();
-            let recent: Vec<_> = messages
-                .iter()
-                .filter(|m| m.session_id == session_id)
-                .rev()
-                .take(limit as usize)
-                .cloned()
-                .collect();
-            Ok(recent.into_iter().rev().collect())
-        }
-    }
 
     #[tokio::test]
     async fn test_send_message_validation() {
@@ -333,10 +333,7 @@ mod tests {
         let session = ChatSession::new(user_id, "Test".to_string()).unwrap();
         let session_id = session.id;
 
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(vec![session]),
-            messages: Mutex::new(Vec::new()),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new().with_session(session));
 
         let config = LlmConfig {
             api_base: "http://localhost".to_string(),
@@ -364,10 +361,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_send_message_session_not_found() {
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(Vec::new()),
-            messages: Mutex::new(Vec::new()),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
 
         let config = LlmConfig {
             api_base: "http://localhost".to_string(),
@@ -2,19 +2,30 @@
 //!
 //! Refactored version using LlmProvider trait and ProviderFactory
 
-use std::sync::Arc;
+use futures::StreamExt;
+use std::{sync::Arc, time::Duration};
 use uuid::Uuid;
-use futures::Stream;
-use std::pin::Pin;
+
+use super::account_suspension::ensure_can_generate;
+use super::stream_supervisor::{
+    counted_message, supervise, GenerationTracking, StreamContext, StreamMetrics,
+};
 
 use crate::domain::chat::{
-    entity::ChatMessage,
+    events::{ChatEvent, EventPublisher},
+    lock::{LockPolicy, SessionLock, SessionLockGuard},
+    preferences::{is_valid_temperature, ChatDefaults, ChatDefaultsRepository, MAX_TEMPERATURE},
+    prompt_budget::check_prompt_fits,
     repository::{ChatRepository, RepositoryError, RepositoryResult},
+    suspension::SuspensionRepository,
     value_objects::MessageRole,
 };
 use crate::infrastructure::llm::{
-    ProviderFactory, ChatCompletionRequest, ChatMessage as ProviderMessage, LlmProviderError,
+    ChatCompletionRequest, ChatMessage as ProviderMessage, ChatRole, LlmProviderError,
+    ModelRegistry, ProviderFactory,
 };
+use crate::services::request_timing::{self, Dependency};
+use crate::services::tokenizer::{Tokenizer, TokenizerService};
 
 /// Request to send a message in a chat session
 #[derive(Debug, Clone)]
@@ -22,16 +33,17 @@ pub struct SendMessageRequest {
     pub session_id: Uuid,
     pub user_id: Uuid,
     pub content: String,
-    /// Optional model ID to use (defaults to registry default)
+    /// Optional model ID to use (defaults to the user's default model, then
+    /// the registry default)
     pub model_id: Option<String>,
+    /// Optional sampling temperature (defaults to the user's default)
+    pub temperature: Option<f32>,
 }
 
-/// Streaming chunk from LLM response
-#[derive(Debug, Clone)]
-pub struct StreamChunk {
-    pub content: String,
-    pub is_final: bool,
-}
+pub use super::stream_supervisor::{ChunkStream, StreamChunk};
+
+/// Tokens a chat template adds around each message (role and separators)
+const MESSAGE_OVERHEAD_TOKENS: u32 = 4;
 
 /// Configuration for the use case
 #[derive(Debug, Clone)]
@@ -45,6 +57,13 @@ pub struct SendMessageUseCase {
     repository: Arc<dyn ChatRepository>,
     provider_factory: Arc<ProviderFactory>,
     config: UseCaseConfig,
+    session_lock: Option<(Arc<dyn SessionLock>, LockPolicy)>,
+    stream_metrics: Option<Arc<StreamMetrics>>,
+    stream_idle_timeout: Option<Duration>,
+    tokenizers: Option<Arc<TokenizerService>>,
+    events: Option<Arc<dyn EventPublisher>>,
+    suspension: Option<Arc<dyn SuspensionRepository>>,
+    chat_defaults: Option<Arc<dyn ChatDefaultsRepository>>,
 }
 
 impl SendMessageUseCase {
@@ -58,6 +77,91 @@ impl SendMessageUseCase {
             repository,
             provider_factory,
             config,
+            session_lock: None,
+            stream_metrics: None,
+            stream_idle_timeout: None,
+            tokenizers: None,
+            events: None,
+            suspension: None,
+            chat_defaults: None,
+        }
+    }
+
+    /// Serialize generations on a session (see [`SessionLock`])
+    ///
+    /// The returned guard must live until the response stream completes.
+    #[must_use]
+    pub fn with_session_lock(mut self, lock: Arc<dyn SessionLock>, policy: LockPolicy) -> Self {
+        self.session_lock = Some((lock, policy));
+        self
+    }
+
+    /// Record stream outcomes and durations in `metrics`
+    #[must_use]
+    pub fn with_stream_metrics(mut self, metrics: Arc<StreamMetrics>) -> Self {
+        self.stream_metrics = Some(metrics);
+        self
+    }
+
+    /// Abort the reply when the client stops reading for `idle_timeout`
+    /// (`None` = wait as long as the connection stays open)
+    #[must_use]
+    pub const fn with_stream_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.stream_idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Store token counts on the user and assistant messages
+    #[must_use]
+    pub fn with_tokenizers(mut self, tokenizers: Arc<TokenizerService>) -> Self {
+        self.tokenizers = Some(tokenizers);
+        self
+    }
+
+    /// Publish the sent message and the end of each reply to `events`
+    #[must_use]
+    pub fn with_events(mut self, events: Arc<dyn EventPublisher>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Refuse to generate for users whose account is disabled
+    #[must_use]
+    pub fn with_suspension(mut self, suspension: Arc<dyn SuspensionRepository>) -> Self {
+        self.suspension = Some(suspension);
+        self
+    }
+
+    /// Fill in the model, temperature and persona from the user's chat
+    /// defaults where the request leaves them out
+    #[must_use]
+    pub fn with_chat_defaults(mut self, chat_defaults: Arc<dyn ChatDefaultsRepository>) -> Self {
+        self.chat_defaults = Some(chat_defaults);
+        self
+    }
+
+    /// The user's chat defaults, if they are applied
+    async fn chat_defaults(&self, user_id: Uuid) -> RepositoryResult<ChatDefaults> {
+        match &self.chat_defaults {
+            Some(chat_defaults) => chat_defaults.chat_defaults(user_id).await,
+            None => Ok(ChatDefaults::default()),
+        }
+    }
+
+    /// Tokenizer for `model`, if token counting is enabled
+    fn tokenizer(&self, model: &str) -> Option<Arc<dyn Tokenizer>> {
+        let tokenizers = self.tokenizers.as_ref()?;
+        tokenizers
+            .for_model(model)
+            .map_err(|e| tracing::warn!("No tokenizer for model '{}': {}", model, e))
+            .ok()
+    }
+
+    /// Take the session lock if one is configured
+    async fn lock_session(&self, session_id: Uuid) -> RepositoryResult<Option<SessionLockGuard>> {
+        match &self.session_lock {
+            Some((lock, policy)) => lock.acquire(session_id, *policy).await.map(Some),
+            None => Ok(None),
         }
     }
 
@@ -67,248 +171,239 @@ impl SendMessageUseCase {
     /// Returns `RepositoryError` if:
     /// - Session not found
     /// - User not authorized
-    /// - Message validation fails
+    /// - User's account is disabled (`AccountDisabled`)
+    /// - Message validation fails or the temperature is out of range
     /// - Repository operations fail
-    /// - Provider/model errors
+    /// - The model is unknown (`ValidationError`)
+    /// - The prompt and the reply do not fit the model's context window
+    ///   (`PromptTooLarge`)
+    /// - The model's provider is not configured or fails (`ProviderUnavailable`)
+    /// - Another generation holds the session lock (`GenerationInProgress`)
     pub async fn execute(
         &self,
         request: SendMessageRequest,
-    ) -> RepositoryResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, String>> + Send>>> {
+    ) -> RepositoryResult<ChunkStream> {
         // Verify session exists and belongs to user
-        let session = self
-            .repository
-            .find_session_by_id(request.session_id)
-            .await?
-            .ok_or(RepositoryError::SessionNotFound(request.session_id))?;
-
-        if session.user_id != request.user_id {
-            return Err(RepositoryError::ValidationError(
-                "User not authorized for this session".to_string(),
-            ));
-        }
+        self.repository
+            .find_active_session_for_user(request.session_id, request.user_id)
+            .await?;
 
-        // Create and save user message
-        let user_message = ChatMessage::new(
-            request.session_id,
-            MessageRole::User,
-            request.content.clone(),
-        )
-        .map_err(|e| RepositoryError::ValidationError(e))?;
+        if let Some(suspension) = &self.suspension {
+            ensure_can_generate(suspension.as_ref(), request.user_id).await?;
+        }
 
-        self.repository.save_message(&user_message).await?;
+        if request
+            .temperature
+            .is_some_and(|temperature| !is_valid_temperature(temperature))
+        {
+            return Err(RepositoryError::ValidationError(format!(
+                "Temperature must be between 0 and {MAX_TEMPERATURE}"
+            )));
+        }
+        let defaults = self.chat_defaults(request.user_id).await?;
 
-        // Get recent context messages
-        let context_messages = self
-            .repository
-            .find_recent_messages(request.session_id, self.config.max_context_messages)
-            .await?;
+        // Held until the response stream finishes so sends cannot interleave
+        let lock_guard = self.lock_session(request.session_id).await?;
 
         // Determine which model to use
-        let model_id = request
-            .model_id
-            .as_deref()
-            .unwrap_or_else(|| {
-                self.provider_factory.model_registry().default_model().id.as_str()
-            });
+        let model_registry = self.provider_factory.model_registry();
+        let model_id = resolve_model(
+            request.model_id.as_deref(),
+            defaults.model.as_deref(),
+            &model_registry,
+        );
 
         tracing::info!(
             "Using model '{}' for session {}",
             model_id,
             request.session_id
         );
+        let tokenizer = self.tokenizer(model_id);
+
+        // An unknown model is the client's mistake; a known model without a
+        // configured provider is not
+        let model = model_registry
+            .get_model(model_id)
+            .map_err(|e| RepositoryError::ValidationError(e.to_string()))?;
+        let max_output_tokens = model
+            .max_output_tokens
+            .min(u32::from(self.config.max_tokens));
+
+        let user_message = counted_message(
+            request.session_id,
+            MessageRole::User,
+            request.content.clone(),
+            tokenizer.as_deref(),
+        )
+        .map_err(RepositoryError::ValidationError)?;
 
-        // Get provider for the model
-        let provider = self
-            .provider_factory
-            .get_provider_for_model(model_id)
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        // Recent context with the new message last
+        let mut context_messages = self
+            .repository
+            .find_recent_messages(
+                request.session_id,
+                self.config.max_context_messages.saturating_sub(1),
+            )
+            .await?;
+        context_messages.push(user_message.clone());
+
+        // Build provider request, the persona ahead of the conversation
+        let provider_messages: Vec<ProviderMessage> = defaults
+            .persona
+            .map(|persona| ProviderMessage {
+                role: ChatRole::System,
+                content: persona,
+            })
+            .into_iter()
+            .chain(context_messages.iter().map(Into::into))
+            .collect();
 
-        tracing::info!("Selected provider: {}", provider.name());
+        // Refuse a prompt the model cannot take before anything is saved
+        if let Some(tokenizer) = &tokenizer {
+            let prompt_tokens = prompt_tokens(tokenizer.as_ref(), &provider_messages);
+            check_prompt_fits(prompt_tokens, max_output_tokens, model.context_window)
+                .map_err(RepositoryError::PromptTooLarge)?;
+        }
 
-        // Build provider request
-        let provider_messages: Vec<ProviderMessage> = context_messages
-            .iter()
-            .map(|msg| msg.into())
-            .collect();
+        self.repository.save_message(&user_message).await?;
+        if let Some(events) = &self.events {
+            events.publish(ChatEvent::MessageSent {
+                session_id: request.session_id,
+                user_id: request.user_id,
+                message_id: user_message.id,
+                content: request.content.clone(),
+            });
+        }
+
+        let provider = self.provider_factory.get_provider_for_model(model_id)?;
+
+        tracing::info!("Selected provider: {}", provider.name());
 
         let llm_request = ChatCompletionRequest {
             model: model_id.to_string(),
             messages: provider_messages,
-            max_tokens: self.config.max_tokens,
+            max_tokens: u16::try_from(max_output_tokens).unwrap_or(self.config.max_tokens),
+            temperature: request.temperature.or(defaults.temperature),
             stream: true,
         };
 
+        let tracking = self.events.as_ref().map(|events| {
+            GenerationTracking::new(
+                Arc::clone(events),
+                request.user_id,
+                model_id,
+                &context_messages,
+            )
+        });
+
         // Create streaming response
         let stream = self
-            .create_llm_stream(provider, llm_request, request.session_id)
+            .create_llm_stream(
+                provider,
+                llm_request,
+                request.session_id,
+                lock_guard,
+                tokenizer,
+                tracking,
+            )
             .await?;
 
         Ok(stream)
     }
 
     /// Create streaming LLM response with message persistence
+    ///
+    /// The provider stream runs under [`supervise`], which saves the reply
+    /// (or whatever arrived of it) and releases the session lock.
     async fn create_llm_stream(
         &self,
         provider: Arc<dyn crate::infrastructure::llm::LlmProvider>,
         request: ChatCompletionRequest,
         session_id: Uuid,
-    ) -> RepositoryResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, String>> + Send>>> {
-        // Start streaming from provider
-        let mut provider_stream = provider
-            .create_chat_completion_stream(request)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to create provider stream: {}", e);
-                RepositoryError::DatabaseError(e.to_string())
-            })?;
-
-        // Process stream and save assistant message
-        let repository = Arc::clone(&self.repository);
-        let mut accumulated_content = String::new();
-
-        use futures::StreamExt;
-        let output_stream = async_stream::stream! {
-            tracing::info!("Starting provider stream processing");
-            let mut chunk_count = 0;
-
-            while let Some(result) = provider_stream.next().await {
-                match result {
-                    Ok(chunk) => {
-                        if !chunk.content.is_empty() {
-                            chunk_count += 1;
-                            tracing::debug!("Chunk #{}: {} bytes", chunk_count, chunk.content.len());
-                            accumulated_content.push_str(&chunk.content);
-
-                            yield Ok(StreamChunk {
-                                content: chunk.content,
-                                is_final: false,
-                            });
-                        }
-
-                        // Check if streaming is done
-                        if chunk.is_final {
-                            tracing::info!(
-                                "Stream finished: finish_reason={:?}, chunks={}, content_length={}",
-                                chunk.finish_reason,
-                                chunk_count,
-                                accumulated_content.len()
-                            );
-
-                            // Save complete assistant message
-                            if !accumulated_content.is_empty() {
-                                let assistant_message = match ChatMessage::new(
-                                    session_id,
-                                    MessageRole::Assistant,
-                                    accumulated_content.clone(),
-                                ) {
-                                    Ok(msg) => msg,
-                                    Err(e) => {
-                                        tracing::error!("Failed to create message: {}", e);
-                                        yield Err(format!("Failed to create message: {}", e));
-                                        return;
-                                    }
-                                };
-
-                                if let Err(e) = repository.save_message(&assistant_message).await {
-                                    tracing::error!("Failed to save message: {}", e);
-                                    yield Err(format!("Failed to save message: {}", e));
-                                    return;
-                                }
-
-                                tracing::info!("Assistant message saved successfully");
-                            }
-
-                            yield Ok(StreamChunk {
-                                content: String::new(),
-                                is_final: true,
-                            });
-                            return;
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("Provider stream error: {}", e);
-                        yield Err(format!("Stream error: {}", e));
-                        return;
-                    }
-                }
-            }
-
-            tracing::warn!("Stream ended without final chunk (chunks: {})", chunk_count);
+        lock_guard: Option<SessionLockGuard>,
+        tokenizer: Option<Arc<dyn Tokenizer>>,
+        tracking: Option<GenerationTracking>,
+    ) -> RepositoryResult<ChunkStream> {
+        // Start streaming from provider; the time until it answers counts
+        // towards the request's latency budget
+        let provider_stream = request_timing::timed(
+            Dependency::Provider,
+            provider.create_chat_completion_stream(request),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create provider stream: {}", e);
+            RepositoryError::from(e)
+        })?;
+
+        tracing::info!("Starting provider stream processing");
+        let source = provider_stream.map(|result| {
+            result
+                .map(|chunk| StreamChunk {
+                    content: chunk.content,
+                    is_final: chunk.is_final,
+                    usage: chunk.usage,
+                })
+                .map_err(|e: LlmProviderError| e.to_string())
+        });
+
+        let context = StreamContext {
+            session_id,
+            repository: Arc::clone(&self.repository),
+            lock_guard,
+            metrics: self.stream_metrics.clone(),
+            tokenizer,
+            tracking,
+            idle_timeout: self.stream_idle_timeout,
         };
+        Ok(supervise(Box::pin(source), context))
+    }
+}
 
-        Ok(Box::pin(output_stream))
+/// Estimated tokens of `messages` as sent to the model
+fn prompt_tokens(tokenizer: &dyn Tokenizer, messages: &[ProviderMessage]) -> u32 {
+    messages
+        .iter()
+        .map(|message| {
+            u32::try_from(tokenizer.count_tokens(&message.content))
+                .unwrap_or(u32::MAX)
+                .saturating_add(MESSAGE_OVERHEAD_TOKENS)
+        })
+        .fold(0, u32::saturating_add)
+}
+
+/// Model for a message: the requested one, else the user's default while it
+/// is still an enabled model, else the registry default
+///
+/// A requested model is passed on as is, so an unknown one is reported to
+/// the client rather than silently replaced.
+fn resolve_model<'a>(
+    requested: Option<&'a str>,
+    preferred: Option<&'a str>,
+    registry: &'a ModelRegistry,
+) -> &'a str {
+    if let Some(model) = requested {
+        return model;
+    }
+    match preferred {
+        Some(model) if registry.get_model(model).is_ok_and(|m| m.enabled) => model,
+        Some(model) => {
+            tracing::warn!(
+                "Default model '{}' is not available, using the registry default",
+                model
+            );
+            registry.default_model().id.as_str()
+        }
+        None => registry.default_model().id.as_str(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
     use crate::domain::chat::{entity::ChatSession, repository::RepositoryError};
-    use async_trait::async_trait;
-    use std::sync::Mutex;
-
-    struct MockChatRepository {
-        sessions: Mutex<Vec<ChatSession>>,
-        messages: Mutex<Vec<ChatMessage>>,
-    }
-
-    #[async_trait]
-    impl ChatRepository for MockChatRepository {
-        async fn create_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn find_session_by_id(&self, id: Uuid) -> RepositoryResult<Option<ChatSession>> {
-            let sessions = self.sessions.lock().unwrap();
-            Ok(sessions.iter().find(|s| s.id == id).cloned())
-        }
-
-        async fn find_sessions_by_user(
-            &self,
-            _user_id: Uuid,
-            _page: u64,
-            _per_page: u64,
-        ) -> RepositoryResult<(Vec<ChatSession>, u64)> {
-            unimplemented!()
-        }
-
-        async fn update_session(&self, _session: &ChatSession) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn delete_session(&self, _id: Uuid) -> RepositoryResult<()> {
-            unimplemented!()
-        }
-
-        async fn save_message(&self, message: &ChatMessage) -> RepositoryResult<()> {
-            self.messages.lock().unwrap().push(message.clone());
-            Ok(())
-        }
-
-        async fn find_messages_by_session(
-            &self,
-            _session_id: Uuid,
-            _limit: Option<u64>,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            unimplemented!()
-        }
-
-        async fn find_recent_messages(
-            &self,
-            session_id: Uuid,
-            limit: u64,
-        ) -> RepositoryResult<Vec<ChatMessage>> {
-            let messages = self.messages.lock().unwrap();
-            let recent: Vec<_> = messages
-                .iter()
-                .filter(|m| m.session_id == session_id)
-                .rev()
-                .take(limit as usize)
-                .cloned()
-                .collect();
-            Ok(recent.into_iter().rev().collect())
-        }
-    }
+    use crate::infrastructure::llm::replay;
 
     #[tokio::test]
     async fn test_send_message_validation() {
@@ -316,21 +411,15 @@ mod tests {
         let session = ChatSession::new(user_id, "Test".to_string()).unwrap();
         let session_id = session.id;
 
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(vec![session]),
-            messages: Mutex::new(Vec::new()),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new().with_session(session));
 
         let config = UseCaseConfig {
             max_context_messages: 20,
             max_tokens: 2048,
         };
 
-        // Skip test if models.toml not available
-        let Ok(use_case) = SendMessageUseCase::new(mock_repo.clone(), config) else {
-            eprintln!("Skipping test: ProviderFactory initialization failed");
-            return;
-        };
+        let factory = Arc::new(ProviderFactory::from_registry(replay::registry()).unwrap());
+        let use_case = SendMessageUseCase::new(mock_repo.clone(), factory, config);
 
         // Test unauthorized user
         let request = SendMessageRequest {
@@ -338,6 +427,7 @@ mod tests {
             user_id: Uuid::new_v4(), // Different user
             content: "Hello".to_string(),
             model_id: None,
+            temperature: None,
         };
 
         let result = use_case.execute(request).await;
@@ -349,27 +439,22 @@ mod tests {
 
     #[tokio::test]
     async fn test_send_message_session_not_found() {
-        let mock_repo = Arc::new(MockChatRepository {
-            sessions: Mutex::new(Vec::new()),
-            messages: Mutex::new(Vec::new()),
-        });
+        let mock_repo = Arc::new(InMemoryChatRepository::new());
 
         let config = UseCaseConfig {
             max_context_messages: 20,
             max_tokens: 2048,
         };
 
-        // Skip test if models.toml not available
-        let Ok(use_case) = SendMessageUseCase::new(mock_repo, config) else {
-            eprintln!("Skipping test: ProviderFactory initialization failed");
-            return;
-        };
+        let factory = Arc::new(ProviderFactory::from_registry(replay::registry()).unwrap());
+        let use_case = SendMessageUseCase::new(mock_repo, factory, config);
 
         let request = SendMessageRequest {
             session_id: Uuid::new_v4(),
             user_id: Uuid::new_v4(),
             content: "Hello".to_string(),
             model_id: None,
+            temperature: None,
         };
 
         let result = use_case.execute(request).await;
@@ -378,4 +463,48 @@ mod tests {
             assert!(matches!(e, RepositoryError::SessionNotFound(_)));
         }
     }
+
+    #[test]
+    fn test_resolve_model_prefers_request_then_user_default() {
+        let registry = ModelRegistry::load_from_path(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/llm/models.toml"
+        ))
+        .unwrap();
+
+        assert_eq!(
+            resolve_model(Some("gpt-4o-mini"), Some("no-streaming"), &registry),
+            "gpt-4o-mini"
+        );
+        assert_eq!(
+            resolve_model(None, Some("gpt-4o-mini"), &registry),
+            "gpt-4o-mini"
+        );
+        // A default that was removed from models.toml falls back
+        assert_eq!(
+            resolve_model(None, Some("retired-model"), &registry),
+            "llama-3.3-70b"
+        );
+        assert_eq!(resolve_model(None, None, &registry), "llama-3.3-70b");
+    }
+
+    #[test]
+    fn test_prompt_tokens_include_message_overhead() {
+        let tokenizer = TokenizerService::new().for_model("gpt-4").unwrap();
+        let messages = [
+            ProviderMessage {
+                role: ChatRole::System,
+                content: "hello world".to_string(),
+            },
+            ProviderMessage {
+                role: ChatRole::User,
+                content: "hello".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            prompt_tokens(tokenizer.as_ref(), &messages),
+            3 + 2 * MESSAGE_OVERHEAD_TOKENS
+        );
+    }
 }
@@ -0,0 +1,227 @@
+//! Session read state use case (last-read tracking and unread counts)
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    read_state::ReadStateRepository,
+    repository::{ChatRepository, RepositoryResult},
+};
+
+/// Request to mark messages in a session as read
+#[allow(clippy::struct_field_names)]
+#[derive(Debug, Clone)]
+pub struct MarkSessionReadRequest {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    /// Last message read; defaults to the newest message in the session
+    pub message_id: Option<Uuid>,
+}
+
+/// A user's read position and unread count for one session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionReadStateResponse {
+    pub session_id: Uuid,
+    pub last_read_message_id: Option<Uuid>,
+    pub unread_count: u64,
+}
+
+/// Use case for reading and moving a user's read position in a session
+pub struct SessionReadStateUseCase {
+    repository: Arc<dyn ChatRepository>,
+    read_states: Arc<dyn ReadStateRepository>,
+}
+
+impl SessionReadStateUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn ChatRepository>,
+        read_states: Arc<dyn ReadStateRepository>,
+    ) -> Self {
+        Self {
+            repository,
+            read_states,
+        }
+    }
+
+    /// Get the user's read position in a session
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized
+    /// - Repository operations fail
+    pub async fn get(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<SessionReadStateResponse> {
+        self.authorize(session_id, user_id).await?;
+        self.read_state(session_id, user_id).await
+    }
+
+    /// Mark messages up to `message_id` (or the newest message) as read
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized
+    /// - Message not found in the session
+    /// - Repository operations fail
+    pub async fn mark_read(
+        &self,
+        request: MarkSessionReadRequest,
+    ) -> RepositoryResult<SessionReadStateResponse> {
+        self.authorize(request.session_id, request.user_id).await?;
+
+        let message_id = match request.message_id {
+            Some(message_id) => Some(message_id),
+            None => self
+                .repository
+                .find_recent_messages(request.session_id, 1)
+                .await?
+                .last()
+                .map(|message| message.id),
+        };
+
+        if let Some(message_id) = message_id {
+            self.read_states
+                .mark_read(request.user_id, request.session_id, message_id)
+                .await?;
+        }
+
+        self.read_state(request.session_id, request.user_id).await
+    }
+
+    async fn authorize(&self, session_id: Uuid, user_id: Uuid) -> RepositoryResult<()> {
+        self.repository
+            .find_active_session_for_user(session_id, user_id)
+            .await
+            .map(drop)
+    }
+
+    async fn read_state(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<SessionReadStateResponse> {
+        let state = self
+            .read_states
+            .find_read_state(user_id, session_id)
+            .await?;
+        let unread = self
+            .read_states
+            .count_unread(user_id, &[session_id])
+            .await?;
+
+        Ok(SessionReadStateResponse {
+            session_id,
+            last_read_message_id: state.map(|state| state.last_read_message_id),
+            unread_count: unread.get(&session_id).copied().unwrap_or(0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+    use crate::domain::chat::{
+        entity::{ChatMessage, ChatSession},
+        repository::RepositoryError,
+        value_objects::MessageRole,
+    };
+
+    fn setup(message_count: usize) -> (Arc<InMemoryChatRepository>, ChatSession, Vec<ChatMessage>) {
+        let session = ChatSession::new(Uuid::new_v4(), "Test".to_string()).unwrap();
+        let messages: Vec<_> = (0..message_count)
+            .map(|i| {
+                let mut message =
+                    ChatMessage::new(session.id, MessageRole::User, format!("Message {i}"))
+                        .unwrap();
+                message.created_at += chrono::Duration::seconds(i64::try_from(i).unwrap());
+                message
+            })
+            .collect();
+        let repo = Arc::new(
+            InMemoryChatRepository::new()
+                .with_session(session.clone())
+                .with_messages(messages.clone()),
+        );
+        (repo, session, messages)
+    }
+
+    #[tokio::test]
+    async fn test_unread_before_any_read() {
+        let (repo, session, _) = setup(3);
+        let use_case = SessionReadStateUseCase::new(repo.clone(), repo);
+
+        let state = use_case.get(session.id, session.user_id).await.unwrap();
+
+        assert_eq!(state.last_read_message_id, None);
+        assert_eq!(state.unread_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_mark_read_defaults_to_newest_message() {
+        let (repo, session, messages) = setup(3);
+        let use_case = SessionReadStateUseCase::new(repo.clone(), repo);
+
+        let state = use_case
+            .mark_read(MarkSessionReadRequest {
+                session_id: session.id,
+                user_id: session.user_id,
+                message_id: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(state.last_read_message_id, Some(messages[2].id));
+        assert_eq!(state.unread_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_mark_read_specific_message() {
+        let (repo, session, messages) = setup(3);
+        let use_case = SessionReadStateUseCase::new(repo.clone(), repo);
+
+        let state = use_case
+            .mark_read(MarkSessionReadRequest {
+                session_id: session.id,
+                user_id: session.user_id,
+                message_id: Some(messages[0].id),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(state.last_read_message_id, Some(messages[0].id));
+        assert_eq!(state.unread_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_mark_read_unauthorized() {
+        let (repo, session, _) = setup(1);
+        let use_case = SessionReadStateUseCase::new(repo.clone(), repo);
+
+        let result = use_case
+            .mark_read(MarkSessionReadRequest {
+                session_id: session.id,
+                user_id: Uuid::new_v4(),
+                message_id: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_session_not_found() {
+        let (repo, _, _) = setup(0);
+        let use_case = SessionReadStateUseCase::new(repo.clone(), repo);
+
+        let result = use_case.get(Uuid::new_v4(), Uuid::new_v4()).await;
+
+        assert!(matches!(result, Err(RepositoryError::SessionNotFound(_))));
+    }
+}
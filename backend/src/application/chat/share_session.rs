@@ -0,0 +1,389 @@
+//! Share session use case (public read-only links)
+
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    entity::{ChatMessage, ChatSession},
+    repository::{ChatRepository, RepositoryError, RepositoryResult},
+    share::{
+        ChatShare, SharePasswordAttempts, ShareRepository, ShareSigner, MAX_SHARE_PASSWORD_FAILURES,
+    },
+};
+use crate::services::auth::{hash_password, verify_password};
+
+/// Share password length limits (same as account passwords)
+pub const MIN_SHARE_PASSWORD_LENGTH: usize = 8;
+pub const MAX_SHARE_PASSWORD_LENGTH: usize = 128;
+
+/// Request to create a share link
+#[derive(Debug, Clone)]
+pub struct CreateShareRequest {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    /// Lifetime of the link; never expires if `None`
+    pub expires_in: Option<Duration>,
+    /// Password viewers must supply
+    pub password: Option<String>,
+}
+
+/// A shared conversation as seen by a public viewer
+#[derive(Debug, Clone)]
+pub struct SharedConversation {
+    pub share: ChatShare,
+    pub session: ChatSession,
+    pub messages: Vec<ChatMessage>,
+}
+
+/// Use case for creating, listing, revoking and viewing share links
+pub struct ShareSessionUseCase {
+    repository: Arc<dyn ChatRepository>,
+    shares: Arc<dyn ShareRepository>,
+    signer: ShareSigner,
+    attempts: Arc<dyn SharePasswordAttempts>,
+}
+
+impl ShareSessionUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn ChatRepository>,
+        shares: Arc<dyn ShareRepository>,
+        signer: ShareSigner,
+        attempts: Arc<dyn SharePasswordAttempts>,
+    ) -> Self {
+        Self {
+            repository,
+            shares,
+            signer,
+            attempts,
+        }
+    }
+
+    /// Create a share link for a session the user owns
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized
+    /// - Expiry or password is invalid
+    /// - Repository operations fail
+    pub async fn create(&self, request: CreateShareRequest) -> RepositoryResult<ChatShare> {
+        self.authorize(request.session_id, request.user_id).await?;
+
+        if request
+            .expires_in
+            .is_some_and(|expires_in| expires_in <= Duration::zero())
+        {
+            return Err(RepositoryError::ValidationError(
+                "Share expiry must be in the future".to_string(),
+            ));
+        }
+
+        let password_hash = match request.password.as_deref() {
+            Some(password) => {
+                if !(MIN_SHARE_PASSWORD_LENGTH..=MAX_SHARE_PASSWORD_LENGTH)
+                    .contains(&password.len())
+                {
+                    return Err(RepositoryError::ValidationError(format!(
+                        "Share password must be {MIN_SHARE_PASSWORD_LENGTH}-{MAX_SHARE_PASSWORD_LENGTH} characters"
+                    )));
+                }
+                Some(hash_password(password).map_err(|e| {
+                    RepositoryError::DatabaseError(format!("Failed to hash share password: {e}"))
+                })?)
+            }
+            None => None,
+        };
+
+        let share = ChatShare::new(
+            request.session_id,
+            request.user_id,
+            self.signer.generate_slug(),
+            password_hash,
+            request.expires_in.map(|expires_in| Utc::now() + expires_in),
+        );
+        self.shares.create_share(&share).await?;
+
+        Ok(share)
+    }
+
+    /// List all share links of a session, including revoked and expired ones
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session not found
+    /// - User not authorized
+    /// - Repository operations fail
+    pub async fn list(&self, session_id: Uuid, user_id: Uuid) -> RepositoryResult<Vec<ChatShare>> {
+        self.authorize(session_id, user_id).await?;
+        self.shares.find_shares_by_session(session_id).await
+    }
+
+    /// Revoke a share link
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - Session or share not found
+    /// - User not authorized
+    /// - Repository operations fail
+    pub async fn revoke(
+        &self,
+        session_id: Uuid,
+        share_id: Uuid,
+        user_id: Uuid,
+    ) -> RepositoryResult<()> {
+        self.authorize(session_id, user_id).await?;
+        self.shares.revoke_share(session_id, share_id).await
+    }
+
+    /// View a shared conversation without authentication and count the view
+    ///
+    /// `viewer` identifies the client (its IP address) for counting wrong
+    /// passwords. A missing password is not counted: that is how a viewer
+    /// learns the share is protected.
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if:
+    /// - The slug is invalid, unknown, or its session was deleted (`ShareNotFound`)
+    /// - The share was revoked or expired (`ShareUnavailable`)
+    /// - The viewer entered too many wrong passwords (`SharePasswordLocked`)
+    /// - The password is missing or wrong (`InvalidSharePassword`)
+    /// - Repository operations fail
+    pub async fn view(
+        &self,
+        slug: &str,
+        password: Option<&str>,
+        viewer: &str,
+    ) -> RepositoryResult<SharedConversation> {
+        if !self.signer.verify_slug(slug) {
+            return Err(RepositoryError::ShareNotFound);
+        }
+
+        let mut share = self
+            .shares
+            .find_share_by_slug(slug)
+            .await?
+            .ok_or(RepositoryError::ShareNotFound)?;
+
+        if !share.is_active(Utc::now()) {
+            return Err(RepositoryError::ShareUnavailable);
+        }
+
+        if let Some(password_hash) = &share.password_hash {
+            let Some(password) = password else {
+                return Err(RepositoryError::InvalidSharePassword);
+            };
+            if self.attempts.failures(slug, viewer).await? >= MAX_SHARE_PASSWORD_FAILURES {
+                return Err(RepositoryError::SharePasswordLocked);
+            }
+            if !verify_password(password, password_hash).unwrap_or(false) {
+                self.attempts.record_failure(slug, viewer).await?;
+                return Err(RepositoryError::InvalidSharePassword);
+            }
+        }
+
+        let session = self
+            .repository
+            .find_session_by_id(share.session_id)
+            .await?
+            .filter(|session| !session.is_deleted())
+            .ok_or(RepositoryError::ShareNotFound)?;
+
+        let messages = self
+            .repository
+            .find_messages_by_session(share.session_id, None)
+            .await?;
+
+        self.shares.increment_share_views(share.id).await?;
+        share.view_count += 1;
+
+        Ok(SharedConversation {
+            share,
+            session,
+            messages,
+        })
+    }
+
+    async fn authorize(&self, session_id: Uuid, user_id: Uuid) -> RepositoryResult<()> {
+        self.repository
+            .find_active_session_for_user(session_id, user_id)
+            .await
+            .map(drop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+    use crate::domain::chat::value_objects::MessageRole;
+    use crate::infrastructure::share_attempts::InMemorySharePasswordAttempts;
+
+    const VIEWER: &str = "198.51.100.7";
+
+    fn setup() -> (ShareSessionUseCase, ChatSession) {
+        let session = ChatSession::new(Uuid::new_v4(), "Shared".to_string()).unwrap();
+        let message = ChatMessage::new(session.id, MessageRole::User, "Hello".to_string()).unwrap();
+        let repo = Arc::new(
+            InMemoryChatRepository::new()
+                .with_session(session.clone())
+                .with_messages([message]),
+        );
+        let use_case = ShareSessionUseCase::new(
+            repo.clone(),
+            repo,
+            ShareSigner::new("secret"),
+            Arc::new(InMemorySharePasswordAttempts::new()),
+        );
+        (use_case, session)
+    }
+
+    fn create_request(session: &ChatSession) -> CreateShareRequest {
+        CreateShareRequest {
+            session_id: session.id,
+            user_id: session.user_id,
+            expires_in: None,
+            password: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_view_share() {
+        let (use_case, session) = setup();
+
+        let share = use_case.create(create_request(&session)).await.unwrap();
+        let shared = use_case.view(&share.slug, None, VIEWER).await.unwrap();
+
+        assert_eq!(shared.session.id, session.id);
+        assert_eq!(shared.messages.len(), 1);
+        assert_eq!(shared.share.view_count, 1);
+
+        let shared = use_case.view(&share.slug, None, VIEWER).await.unwrap();
+        assert_eq!(shared.share.view_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_share_unauthorized() {
+        let (use_case, session) = setup();
+
+        let result = use_case
+            .create(CreateShareRequest {
+                user_id: Uuid::new_v4(),
+                ..create_request(&session)
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_view_forged_slug() {
+        let (use_case, session) = setup();
+        use_case.create(create_request(&session)).await.unwrap();
+
+        let forged = ShareSigner::new("other").generate_slug();
+        let result = use_case.view(&forged, None, VIEWER).await;
+
+        assert!(matches!(result, Err(RepositoryError::ShareNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_view_revoked_share() {
+        let (use_case, session) = setup();
+        let share = use_case.create(create_request(&session)).await.unwrap();
+
+        use_case
+            .revoke(session.id, share.id, session.user_id)
+            .await
+            .unwrap();
+        let result = use_case.view(&share.slug, None, VIEWER).await;
+
+        assert!(matches!(result, Err(RepositoryError::ShareUnavailable)));
+    }
+
+    #[tokio::test]
+    async fn test_create_share_rejects_past_expiry() {
+        let (use_case, session) = setup();
+
+        let result = use_case
+            .create(CreateShareRequest {
+                expires_in: Some(Duration::zero()),
+                ..create_request(&session)
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_share_rejects_short_password() {
+        let (use_case, session) = setup();
+
+        let result = use_case
+            .create(CreateShareRequest {
+                password: Some("short".to_string()),
+                ..create_request(&session)
+            })
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_password_protected_share() {
+        let (use_case, session) = setup();
+        let share = use_case
+            .create(CreateShareRequest {
+                password: Some("correct horse".to_string()),
+                ..create_request(&session)
+            })
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            use_case.view(&share.slug, None, VIEWER).await,
+            Err(RepositoryError::InvalidSharePassword)
+        ));
+        assert!(matches!(
+            use_case.view(&share.slug, Some("wrong"), VIEWER).await,
+            Err(RepositoryError::InvalidSharePassword)
+        ));
+        assert!(use_case
+            .view(&share.slug, Some("correct horse"), VIEWER)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_passwords_lock_out_the_viewer() {
+        let (use_case, session) = setup();
+        let share = use_case
+            .create(CreateShareRequest {
+                password: Some("correct horse".to_string()),
+                ..create_request(&session)
+            })
+            .await
+            .unwrap();
+
+        for _ in 0..MAX_SHARE_PASSWORD_FAILURES {
+            assert!(matches!(
+                use_case.view(&share.slug, Some("wrong"), VIEWER).await,
+                Err(RepositoryError::InvalidSharePassword)
+            ));
+        }
+        assert!(matches!(
+            use_case
+                .view(&share.slug, Some("correct horse"), VIEWER)
+                .await,
+            Err(RepositoryError::SharePasswordLocked)
+        ));
+
+        // Other viewers are not affected
+        assert!(use_case
+            .view(&share.slug, Some("correct horse"), "203.0.113.9")
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,914 @@
+//! Supervised execution of streamed chat replies
+//!
+//! The send-message use cases hand their normalized provider stream to
+//! [`supervise`], which runs it on a worker task inside a [`JoinSet`] owned by
+//! a supervisor task. The client reads the reply from a channel, so:
+//!
+//! - a panic in the worker becomes an error event instead of a silently
+//!   closed stream
+//! - the content received so far is persisted as the assistant message
+//!   however the worker ends: completion, provider error, panic, a stream that
+//!   stops without a final chunk, or the client disconnecting
+//! - the duration and outcome of every stream are recorded in
+//!   [`StreamMetrics`] (rendered at `/metrics`), along with why the client
+//!   went away when it did
+//! - with an idle timeout, a client that stops reading (e.g. a connection a
+//!   proxy keeps open after the browser is gone) no longer holds the
+//!   generation and the session lock: the reply is aborted once it has
+//!   waited that long for room in the channel
+//! - a [`ChatEvent::GenerationCompleted`] is published once the stream ends,
+//!   for usage accounting and notifications, with the token counts the
+//!   provider reported on its final chunk when it did
+//!
+//! The session lock guard is held by the supervisor, so the next send on the
+//! session waits until the reply is persisted.
+
+use futures::{Stream, StreamExt};
+use std::any::Any;
+use std::fmt::Write;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    entity::ChatMessage,
+    events::{ChatEvent, EventPublisher, GenerationCompleted, GenerationOutcome},
+    lock::SessionLockGuard,
+    repository::ChatRepository,
+    value_objects::MessageRole,
+};
+use crate::infrastructure::llm::TokenUsage;
+use crate::services::tokenizer::{to_token_count, Tokenizer};
+
+/// Chunks buffered between the worker and a slow client
+const CHANNEL_CAPACITY: usize = 32;
+
+/// Upper bounds of the `chat_stream_duration_seconds` histogram buckets
+const DURATION_BUCKETS_SECS: [f64; 8] = [1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+
+/// Streaming chunk from LLM response
+#[derive(Debug, Clone)]
+pub struct StreamChunk {
+    pub content: String,
+    pub is_final: bool,
+    /// Token counts reported by the provider, on the final chunk
+    pub usage: Option<TokenUsage>,
+}
+
+/// Stream of reply chunks, as produced by the provider and sent to the client
+pub type ChunkStream = Pin<Box<dyn Stream<Item = Result<StreamChunk, String>> + Send>>;
+
+/// How a supervised stream ended, reported as the `outcome` metric label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamOutcome {
+    /// The provider sent its final chunk and the reply was saved
+    Completed,
+    /// The provider failed, or the reply could not be saved
+    Failed,
+    /// The provider stream stopped without a final chunk
+    Truncated,
+    /// The client went away before the reply finished
+    Disconnected,
+    /// The worker task panicked
+    Panicked,
+}
+
+impl StreamOutcome {
+    const ALL: [Self; 5] = [
+        Self::Completed,
+        Self::Failed,
+        Self::Truncated,
+        Self::Disconnected,
+        Self::Panicked,
+    ];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Truncated => "truncated",
+            Self::Disconnected => "disconnected",
+            Self::Panicked => "panicked",
+        }
+    }
+
+    /// Outcome reported in [`GenerationCompleted`]
+    #[must_use]
+    pub const fn generation_outcome(self) -> GenerationOutcome {
+        match self {
+            Self::Completed => GenerationOutcome::Completed,
+            Self::Failed | Self::Panicked => GenerationOutcome::Failed,
+            Self::Truncated => GenerationOutcome::Truncated,
+            Self::Disconnected => GenerationOutcome::Disconnected,
+        }
+    }
+}
+
+/// Why a client went away before its reply finished, reported as the
+/// `cause` metric label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectCause {
+    /// The connection was closed
+    Closed,
+    /// The client stopped reading for longer than the idle timeout
+    IdleTimeout,
+}
+
+impl DisconnectCause {
+    const ALL: [Self; 2] = [Self::Closed, Self::IdleTimeout];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::IdleTimeout => "idle_timeout",
+        }
+    }
+}
+
+/// Stream counters and durations in the Prometheus text format
+#[derive(Debug, Default)]
+pub struct StreamMetrics {
+    outcomes: [AtomicU64; 5],
+    disconnects: [AtomicU64; 2],
+    active: AtomicI64,
+    duration_buckets: [AtomicU64; DURATION_BUCKETS_SECS.len()],
+    duration_count: AtomicU64,
+    duration_sum_millis: AtomicU64,
+}
+
+impl StreamMetrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a finished stream
+    pub fn record(&self, outcome: StreamOutcome, duration: Duration) {
+        self.outcomes[outcome as usize].fetch_add(1, Ordering::Relaxed);
+
+        let secs = duration.as_secs_f64();
+        for (bound, bucket) in DURATION_BUCKETS_SECS.iter().zip(&self.duration_buckets) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.duration_count.fetch_add(1, Ordering::Relaxed);
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        self.duration_sum_millis
+            .fetch_add(millis, Ordering::Relaxed);
+    }
+
+    /// Record why a client went away, on top of its `Disconnected` outcome
+    pub fn record_disconnect(&self, cause: DisconnectCause) {
+        self.disconnects[cause as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Disconnects recorded with a cause
+    #[must_use]
+    pub fn disconnects_total(&self, cause: DisconnectCause) -> u64 {
+        self.disconnects[cause as usize].load(Ordering::Relaxed)
+    }
+
+    /// Streams recorded with an outcome
+    #[must_use]
+    pub fn streams_total(&self, outcome: StreamOutcome) -> u64 {
+        self.outcomes[outcome as usize].load(Ordering::Relaxed)
+    }
+
+    /// Streams currently running
+    #[must_use]
+    pub fn active(&self) -> i64 {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP chat_streams_total Streamed chat replies by outcome.\n");
+        out.push_str("# TYPE chat_streams_total counter\n");
+        for (outcome, counter) in StreamOutcome::ALL.iter().zip(&self.outcomes) {
+            let _ = writeln!(
+                out,
+                "chat_streams_total{{outcome=\"{}\"}} {}",
+                outcome.as_str(),
+                counter.load(Ordering::Relaxed)
+            );
+        }
+
+        out.push_str(
+            "# HELP chat_stream_disconnects_total Streamed chat replies abandoned by the client, by cause.\n",
+        );
+        out.push_str("# TYPE chat_stream_disconnects_total counter\n");
+        for (cause, counter) in DisconnectCause::ALL.iter().zip(&self.disconnects) {
+            let _ = writeln!(
+                out,
+                "chat_stream_disconnects_total{{cause=\"{}\"}} {}",
+                cause.as_str(),
+                counter.load(Ordering::Relaxed)
+            );
+        }
+
+        out.push_str("# HELP chat_streams_active Streamed chat replies in progress.\n");
+        out.push_str("# TYPE chat_streams_active gauge\n");
+        let _ = writeln!(out, "chat_streams_active {}", self.active());
+
+        out.push_str("# HELP chat_stream_duration_seconds Duration of streamed chat replies.\n");
+        out.push_str("# TYPE chat_stream_duration_seconds histogram\n");
+        for (bound, bucket) in DURATION_BUCKETS_SECS.iter().zip(&self.duration_buckets) {
+            let _ = writeln!(
+                out,
+                "chat_stream_duration_seconds_bucket{{le=\"{bound}\"}} {}",
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let count = self.duration_count.load(Ordering::Relaxed);
+        let _ = writeln!(
+            out,
+            "chat_stream_duration_seconds_bucket{{le=\"+Inf\"}} {count}"
+        );
+        #[allow(clippy::cast_precision_loss)]
+        let sum = self.duration_sum_millis.load(Ordering::Relaxed) as f64 / 1000.0;
+        let _ = writeln!(out, "chat_stream_duration_seconds_sum {sum}");
+        let _ = writeln!(out, "chat_stream_duration_seconds_count {count}");
+
+        out
+    }
+}
+
+/// What a supervised reply belongs to and where it is saved
+pub struct StreamContext {
+    pub session_id: Uuid,
+    pub repository: Arc<dyn ChatRepository>,
+    /// Released once the reply is persisted
+    pub lock_guard: Option<SessionLockGuard>,
+    pub metrics: Option<Arc<StreamMetrics>>,
+    /// Counts the tokens of the saved reply when the provider does not
+    pub tokenizer: Option<Arc<dyn Tokenizer>>,
+    /// Where to publish the end of the reply
+    pub tracking: Option<GenerationTracking>,
+    /// How long a chunk may wait for the client to read before the reply is
+    /// aborted (`None` = as long as the connection stays open)
+    pub idle_timeout: Option<Duration>,
+}
+
+/// Details of the [`GenerationCompleted`] event published when the reply ends
+pub struct GenerationTracking {
+    pub events: Arc<dyn EventPublisher>,
+    pub user_id: Uuid,
+    pub model: String,
+    /// Estimated prompt size, used when the provider reports no usage
+    pub prompt_tokens: i32,
+    /// When the provider request was sent, for the first-chunk latency
+    pub requested_at: Instant,
+}
+
+impl GenerationTracking {
+    /// Track a reply requested now with `context` as its prompt
+    ///
+    /// Prompt tokens are the stored counts of the context messages; messages
+    /// saved before token counting existed count as zero.
+    #[must_use]
+    pub fn new(
+        events: Arc<dyn EventPublisher>,
+        user_id: Uuid,
+        model: impl Into<String>,
+        context: &[ChatMessage],
+    ) -> Self {
+        let prompt_tokens = context
+            .iter()
+            .filter_map(|message| message.token_count)
+            .fold(0i32, i32::saturating_add);
+        Self {
+            events,
+            user_id,
+            model: model.into(),
+            prompt_tokens,
+            requested_at: Instant::now(),
+        }
+    }
+}
+
+/// Reply received so far, shared with the worker so it survives a panic
+#[derive(Default)]
+struct Reply {
+    content: String,
+    first_chunk_at: Option<Instant>,
+    usage: Option<TokenUsage>,
+}
+
+/// How the worker stopped reading the provider stream
+enum WorkerEnd {
+    Completed,
+    ProviderError(String),
+    Truncated,
+    Disconnected(DisconnectCause),
+}
+
+/// Run `source` on a supervised task and return the stream for the client
+///
+/// `source` yields the provider's content chunks, with `is_final` set on the
+/// last one. The returned stream carries the same chunks, then either a final
+/// empty chunk once the reply is saved or a single error.
+#[must_use]
+pub fn supervise(source: ChunkStream, context: StreamContext) -> ChunkStream {
+    let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+    tokio::spawn(run_supervisor(source, context, sender));
+
+    Box::pin(futures::stream::unfold(
+        receiver,
+        |mut receiver| async move { receiver.recv().await.map(|item| (item, receiver)) },
+    ))
+}
+
+async fn run_supervisor(
+    source: ChunkStream,
+    StreamContext {
+        session_id,
+        repository,
+        lock_guard,
+        metrics,
+        tokenizer,
+        tracking,
+        idle_timeout,
+    }: StreamContext,
+    sender: mpsc::Sender<Result<StreamChunk, String>>,
+) {
+    let _lock_guard = lock_guard;
+    let _active = metrics.as_deref().map(ActiveGuard::new);
+    let started = Instant::now();
+
+    let reply = Arc::new(Mutex::new(Reply::default()));
+    let mut tasks = JoinSet::new();
+    tasks.spawn(run_worker(
+        source,
+        sender.clone(),
+        Arc::clone(&reply),
+        idle_timeout,
+    ));
+
+    let end = match tasks.join_next().await {
+        Some(Ok(end)) => Ok(end),
+        Some(Err(e)) if e.is_panic() => Err(panic_message(&*e.into_panic())),
+        Some(Err(e)) => Err(e.to_string()),
+        None => Err("worker task missing".to_string()),
+    };
+
+    let Reply {
+        content,
+        first_chunk_at,
+        usage,
+    } = std::mem::take(&mut *reply.lock().unwrap_or_else(PoisonError::into_inner));
+    let token_count = reply_token_count(usage.as_ref(), tokenizer.as_deref(), &content);
+    let saved = persist(repository.as_ref(), session_id, &content, token_count).await;
+
+    let (outcome, event) = match (end, &saved) {
+        (Ok(WorkerEnd::Completed), Ok(_)) => (
+            StreamOutcome::Completed,
+            Some(Ok(StreamChunk {
+                content: String::new(),
+                is_final: true,
+                usage: None,
+            })),
+        ),
+        (Ok(WorkerEnd::Completed), Err(e)) => (StreamOutcome::Failed, Some(Err(e.clone()))),
+        (Ok(WorkerEnd::ProviderError(e)), _) => {
+            tracing::error!("Provider stream error: {}", e);
+            (
+                StreamOutcome::Failed,
+                Some(Err(format!("Stream error: {e}"))),
+            )
+        }
+        (Ok(WorkerEnd::Truncated), _) => (
+            StreamOutcome::Truncated,
+            Some(Err("Stream ended unexpectedly".to_string())),
+        ),
+        (Ok(WorkerEnd::Disconnected(cause)), _) => {
+            tracing::info!(%session_id, cause = cause.as_str(), "Client left before the reply finished");
+            if let Some(metrics) = &metrics {
+                metrics.record_disconnect(cause);
+            }
+            (StreamOutcome::Disconnected, None)
+        }
+        (Err(message), _) => {
+            tracing::error!(%session_id, "Stream worker panicked: {}", message);
+            (
+                StreamOutcome::Panicked,
+                Some(Err("Internal error while streaming the reply".to_string())),
+            )
+        }
+    };
+
+    let duration = started.elapsed();
+    tracing::info!(
+        %session_id,
+        outcome = outcome.as_str(),
+        content_length = content.len(),
+        duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+        "Stream finished"
+    );
+    if let Some(metrics) = &metrics {
+        metrics.record(outcome, duration);
+    }
+
+    if let Some(event) = event {
+        // Nobody to tell if the client is gone or no longer reading
+        let _ = send(&sender, event, idle_timeout).await;
+    }
+
+    if let Some(tracking) = tracking {
+        let latency_ms = first_chunk_at.map(|first_chunk_at| {
+            let latency = first_chunk_at.saturating_duration_since(tracking.requested_at);
+            i32::try_from(latency.as_millis()).unwrap_or(i32::MAX)
+        });
+        tracking
+            .events
+            .publish(ChatEvent::GenerationCompleted(GenerationCompleted {
+                session_id,
+                user_id: tracking.user_id,
+                model: tracking.model,
+                outcome: outcome.generation_outcome(),
+                message_id: saved.ok().flatten(),
+                prompt_tokens: usage.map_or(tracking.prompt_tokens, |usage| {
+                    i32::try_from(usage.prompt_tokens).unwrap_or(i32::MAX)
+                }),
+                completion_tokens: token_count,
+                latency_ms,
+            }));
+    }
+}
+
+/// Forward content chunks to the client, collecting them into `reply`
+async fn run_worker(
+    mut source: ChunkStream,
+    sender: mpsc::Sender<Result<StreamChunk, String>>,
+    reply: Arc<Mutex<Reply>>,
+    idle_timeout: Option<Duration>,
+) -> WorkerEnd {
+    let mut chunk_count = 0;
+    loop {
+        let item = tokio::select! {
+            item = source.next() => item,
+            () = sender.closed() => return WorkerEnd::Disconnected(DisconnectCause::Closed),
+        };
+
+        match item {
+            Some(Ok(chunk)) => {
+                if !chunk.content.is_empty() {
+                    chunk_count += 1;
+                    tracing::debug!("Chunk #{}: {} bytes", chunk_count, chunk.content.len());
+                    {
+                        let mut received = reply.lock().unwrap_or_else(PoisonError::into_inner);
+                        received.first_chunk_at.get_or_insert_with(Instant::now);
+                        received.content.push_str(&chunk.content);
+                    }
+
+                    let event = Ok(StreamChunk {
+                        content: chunk.content,
+                        is_final: false,
+                        usage: None,
+                    });
+                    if let Err(cause) = send(&sender, event, idle_timeout).await {
+                        return WorkerEnd::Disconnected(cause);
+                    }
+                }
+                if chunk.is_final {
+                    reply.lock().unwrap_or_else(PoisonError::into_inner).usage = chunk.usage;
+                    return WorkerEnd::Completed;
+                }
+            }
+            Some(Err(e)) => return WorkerEnd::ProviderError(e),
+            None => {
+                tracing::warn!("Stream ended without final chunk (chunks: {})", chunk_count);
+                return WorkerEnd::Truncated;
+            }
+        }
+    }
+}
+
+/// Send `event` to the client, waiting at most `idle_timeout` for room
+async fn send(
+    sender: &mpsc::Sender<Result<StreamChunk, String>>,
+    event: Result<StreamChunk, String>,
+    idle_timeout: Option<Duration>,
+) -> Result<(), DisconnectCause> {
+    let sent = match idle_timeout {
+        Some(idle_timeout) => tokio::time::timeout(idle_timeout, sender.send(event))
+            .await
+            .map_err(|_| DisconnectCause::IdleTimeout)?,
+        None => sender.send(event).await,
+    };
+    sent.map_err(|_| DisconnectCause::Closed)
+}
+
+/// Tokens of the reply as the provider reported them, else as `tokenizer`
+/// counts them
+fn reply_token_count(
+    usage: Option<&TokenUsage>,
+    tokenizer: Option<&dyn Tokenizer>,
+    content: &str,
+) -> Option<i32> {
+    usage.map_or_else(
+        || tokenizer.map(|tokenizer| to_token_count(tokenizer.count_tokens(content))),
+        |usage| Some(i32::try_from(usage.completion_tokens).unwrap_or(i32::MAX)),
+    )
+}
+
+/// Save the reply (complete or partial) as the assistant message
+///
+/// Returns the ID of the saved message, `None` if there was no content.
+async fn persist(
+    repository: &dyn ChatRepository,
+    session_id: Uuid,
+    content: &str,
+    token_count: Option<i32>,
+) -> Result<Option<Uuid>, String> {
+    if content.is_empty() {
+        return Ok(None);
+    }
+
+    let mut message = ChatMessage::new(session_id, MessageRole::Assistant, content.to_string())
+        .map_err(|e| {
+            tracing::error!("Failed to create message: {}", e);
+            format!("Failed to create message: {e}")
+        })?;
+    message.token_count = token_count;
+    repository.save_message(&message).await.map_err(|e| {
+        tracing::error!("Failed to save message: {}", e);
+        format!("Failed to save message: {e}")
+    })?;
+    Ok(Some(message.id))
+}
+
+/// Create a message, with its token count when a tokenizer is given
+///
+/// # Errors
+/// Returns an error if the content is empty or too long
+pub fn counted_message(
+    session_id: Uuid,
+    role: MessageRole,
+    content: String,
+    tokenizer: Option<&dyn Tokenizer>,
+) -> Result<ChatMessage, String> {
+    match tokenizer {
+        Some(tokenizer) => {
+            let tokens = to_token_count(tokenizer.count_tokens(&content));
+            ChatMessage::new_with_tokens(session_id, role, content, tokens)
+        }
+        None => ChatMessage::new(session_id, role, content),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(ToString::to_string)
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Keeps `chat_streams_active` accurate even if the supervisor is cancelled
+struct ActiveGuard<'a>(&'a StreamMetrics);
+
+impl<'a> ActiveGuard<'a> {
+    fn new(metrics: &'a StreamMetrics) -> Self {
+        metrics.active.fetch_add(1, Ordering::Relaxed);
+        Self(metrics)
+    }
+}
+
+impl Drop for ActiveGuard<'_> {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::chat::in_memory::InMemoryChatRepository;
+    use crate::services::tokenizer::TokenizerService;
+
+    /// Contents of the messages the supervisor saved
+    fn saved(repository: &InMemoryChatRepository) -> Vec<String> {
+        repository
+            .messages()
+            .into_iter()
+            .map(|message| message.content)
+            .collect()
+    }
+
+    fn chunk(content: &str, is_final: bool) -> StreamChunk {
+        StreamChunk {
+            content: content.to_string(),
+            is_final,
+            usage: None,
+        }
+    }
+
+    fn source(items: Vec<Result<StreamChunk, String>>) -> ChunkStream {
+        Box::pin(futures::stream::iter(items))
+    }
+
+    async fn run(
+        source: ChunkStream,
+        repository: Arc<InMemoryChatRepository>,
+        metrics: &Arc<StreamMetrics>,
+    ) -> Vec<Result<StreamChunk, String>> {
+        let context = StreamContext {
+            session_id: Uuid::new_v4(),
+            repository,
+            lock_guard: None,
+            metrics: Some(Arc::clone(metrics)),
+            tokenizer: None,
+            tracking: None,
+            idle_timeout: None,
+        };
+        supervise(source, context).collect().await
+    }
+
+    /// Wait for the supervisor to finish after the client stopped reading
+    async fn wait_for_record(metrics: &StreamMetrics, outcome: StreamOutcome) {
+        for _ in 0..100 {
+            if metrics.streams_total(outcome) > 0 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("stream not recorded as {}", outcome.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_completed_stream_is_saved() {
+        let repository = Arc::new(InMemoryChatRepository::new());
+        let metrics = Arc::new(StreamMetrics::new());
+
+        let events = run(
+            source(vec![
+                Ok(chunk("Hel", false)),
+                Ok(chunk("lo", false)),
+                Ok(chunk("", true)),
+            ]),
+            Arc::clone(&repository),
+            &metrics,
+        )
+        .await;
+
+        let contents: Vec<_> = events
+            .iter()
+            .map(|event| event.as_ref().unwrap().content.as_str())
+            .collect();
+        assert_eq!(contents, ["Hel", "lo", ""]);
+        assert!(events.last().unwrap().as_ref().unwrap().is_final);
+        assert_eq!(saved(&repository), ["Hello"]);
+        assert_eq!(metrics.streams_total(StreamOutcome::Completed), 1);
+        assert_eq!(metrics.active(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_provider_error_saves_partial_content() {
+        let repository = Arc::new(InMemoryChatRepository::new());
+        let metrics = Arc::new(StreamMetrics::new());
+
+        let events = run(
+            source(vec![
+                Ok(chunk("partial", false)),
+                Err("timeout".to_string()),
+            ]),
+            Arc::clone(&repository),
+            &metrics,
+        )
+        .await;
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].as_ref().unwrap_err(), "Stream error: timeout");
+        assert_eq!(saved(&repository), ["partial"]);
+        assert_eq!(metrics.streams_total(StreamOutcome::Failed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_panic_becomes_error_event() {
+        let repository = Arc::new(InMemoryChatRepository::new());
+        let metrics = Arc::new(StreamMetrics::new());
+        let panicking = futures::stream::iter(vec![Ok(chunk("before", false))]).chain(
+            futures::stream::poll_fn(
+                |_| -> std::task::Poll<Option<Result<StreamChunk, String>>> {
+                    panic!("provider bug")
+                },
+            ),
+        );
+
+        let events = run(Box::pin(panicking), Arc::clone(&repository), &metrics).await;
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].as_ref().unwrap().content, "before");
+        assert!(events[1].is_err());
+        assert_eq!(saved(&repository), ["before"]);
+        assert_eq!(metrics.streams_total(StreamOutcome::Panicked), 1);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_stream_and_failed_save() {
+        let metrics = Arc::new(StreamMetrics::new());
+        let repository = Arc::new(InMemoryChatRepository::new());
+        let events = run(
+            source(vec![Ok(chunk("cut", false))]),
+            Arc::clone(&repository),
+            &metrics,
+        )
+        .await;
+        assert!(events.last().unwrap().is_err());
+        assert_eq!(saved(&repository), ["cut"]);
+        assert_eq!(metrics.streams_total(StreamOutcome::Truncated), 1);
+
+        let failing = Arc::new(InMemoryChatRepository::new().failing_writes());
+        let events = run(source(vec![Ok(chunk("done", true))]), failing, &metrics).await;
+        assert!(events.last().unwrap().is_err());
+        assert_eq!(metrics.streams_total(StreamOutcome::Failed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_client_disconnect_saves_partial_content() {
+        let repository = Arc::new(InMemoryChatRepository::new());
+        let metrics = Arc::new(StreamMetrics::new());
+        let endless = futures::stream::iter(vec![Ok(chunk("so far", false))])
+            .chain(futures::stream::pending());
+        let context = StreamContext {
+            session_id: Uuid::new_v4(),
+            repository: Arc::clone(&repository) as Arc<dyn ChatRepository>,
+            lock_guard: None,
+            metrics: Some(Arc::clone(&metrics)),
+            tokenizer: None,
+            tracking: None,
+            idle_timeout: None,
+        };
+
+        let mut stream = supervise(Box::pin(endless), context);
+        assert_eq!(stream.next().await.unwrap().unwrap().content, "so far");
+        drop(stream);
+
+        wait_for_record(&metrics, StreamOutcome::Disconnected).await;
+        assert_eq!(saved(&repository), ["so far"]);
+        assert_eq!(metrics.disconnects_total(DisconnectCause::Closed), 1);
+        assert_eq!(metrics.active(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_idle_client_aborts_generation() {
+        let repository = Arc::new(InMemoryChatRepository::new());
+        let metrics = Arc::new(StreamMetrics::new());
+        let endless = futures::stream::repeat_with(|| Ok(chunk("x", false)));
+        let context = StreamContext {
+            session_id: Uuid::new_v4(),
+            repository: Arc::clone(&repository) as Arc<dyn ChatRepository>,
+            lock_guard: None,
+            metrics: Some(Arc::clone(&metrics)),
+            tokenizer: None,
+            tracking: None,
+            idle_timeout: Some(Duration::from_millis(50)),
+        };
+
+        // Connected but never reading
+        let _stream = supervise(Box::pin(endless), context);
+
+        wait_for_record(&metrics, StreamOutcome::Disconnected).await;
+        assert_eq!(metrics.disconnects_total(DisconnectCause::IdleTimeout), 1);
+        let saved = saved(&repository);
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].starts_with("xxx"));
+        assert_eq!(metrics.active(), 0);
+    }
+
+    #[derive(Default)]
+    struct RecordingPublisher {
+        events: Mutex<Vec<ChatEvent>>,
+    }
+
+    impl EventPublisher for RecordingPublisher {
+        fn publish(&self, event: ChatEvent) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generation_completed_is_published() {
+        let repository = Arc::new(InMemoryChatRepository::new());
+        let publisher = Arc::new(RecordingPublisher::default());
+        let user_id = Uuid::new_v4();
+        let context = StreamContext {
+            session_id: Uuid::new_v4(),
+            repository: Arc::clone(&repository) as Arc<dyn ChatRepository>,
+            lock_guard: None,
+            metrics: None,
+            tokenizer: None,
+            tracking: Some(GenerationTracking::new(
+                Arc::clone(&publisher) as Arc<dyn EventPublisher>,
+                user_id,
+                "gpt-4",
+                &[],
+            )),
+            idle_timeout: None,
+        };
+
+        let events: Vec<_> = supervise(source(vec![Ok(chunk("partial", false))]), context)
+            .collect()
+            .await;
+        assert!(events.last().unwrap().is_err());
+
+        let recorded = std::mem::take(&mut *publisher.events.lock().unwrap());
+        let [ChatEvent::GenerationCompleted(generation)] = recorded.as_slice() else {
+            panic!("expected one generation event, got {recorded:?}");
+        };
+        assert_eq!(generation.user_id, user_id);
+        assert_eq!(generation.model, "gpt-4");
+        assert_eq!(generation.outcome, GenerationOutcome::Truncated);
+        assert!(generation.message_id.is_some());
+        assert!(generation.latency_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_reported_usage_replaces_estimates() {
+        let repository = Arc::new(InMemoryChatRepository::new());
+        let publisher = Arc::new(RecordingPublisher::default());
+        let context = StreamContext {
+            session_id: Uuid::new_v4(),
+            repository: Arc::clone(&repository) as Arc<dyn ChatRepository>,
+            lock_guard: None,
+            metrics: None,
+            tokenizer: Some(TokenizerService::new().for_model("gpt-4").unwrap()),
+            tracking: Some(GenerationTracking::new(
+                Arc::clone(&publisher) as Arc<dyn EventPublisher>,
+                Uuid::new_v4(),
+                "gpt-4",
+                &[],
+            )),
+            idle_timeout: None,
+        };
+        let last = StreamChunk {
+            usage: Some(TokenUsage {
+                prompt_tokens: 42,
+                completion_tokens: 7,
+            }),
+            ..chunk("", true)
+        };
+
+        let events: Vec<_> = supervise(source(vec![Ok(chunk("hello", false)), Ok(last)]), context)
+            .collect()
+            .await;
+        assert!(events.last().unwrap().as_ref().unwrap().is_final);
+
+        let recorded = std::mem::take(&mut *publisher.events.lock().unwrap());
+        let [ChatEvent::GenerationCompleted(generation)] = recorded.as_slice() else {
+            panic!("expected one generation event, got {recorded:?}");
+        };
+        assert_eq!(generation.outcome, GenerationOutcome::Completed);
+        assert_eq!(generation.prompt_tokens, 42);
+        assert_eq!(generation.completion_tokens, Some(7));
+    }
+
+    #[test]
+    fn test_counted_message() {
+        let tokenizer = TokenizerService::new().for_model("gpt-4").unwrap();
+        let session_id = Uuid::new_v4();
+
+        let counted = counted_message(
+            session_id,
+            MessageRole::User,
+            "hello world".to_string(),
+            Some(tokenizer.as_ref()),
+        )
+        .unwrap();
+        assert_eq!(counted.token_count, Some(2));
+
+        let uncounted =
+            counted_message(session_id, MessageRole::User, "hello".to_string(), None).unwrap();
+        assert_eq!(uncounted.token_count, None);
+    }
+
+    #[test]
+    fn test_render_metrics() {
+        let metrics = StreamMetrics::new();
+        metrics.record(StreamOutcome::Completed, Duration::from_millis(1500));
+        metrics.record(StreamOutcome::Failed, Duration::from_secs(400));
+        metrics.record_disconnect(DisconnectCause::IdleTimeout);
+
+        let output = metrics.render();
+        assert!(output.contains("chat_streams_total{outcome=\"completed\"} 1"));
+        assert!(output.contains("chat_streams_total{outcome=\"panicked\"} 0"));
+        assert!(output.contains("chat_stream_disconnects_total{cause=\"closed\"} 0"));
+        assert!(output.contains("chat_stream_disconnects_total{cause=\"idle_timeout\"} 1"));
+        assert!(output.contains("chat_stream_duration_seconds_bucket{le=\"1\"} 0"));
+        assert!(output.contains("chat_stream_duration_seconds_bucket{le=\"2.5\"} 1"));
+        assert!(output.contains("chat_stream_duration_seconds_bucket{le=\"300\"} 1"));
+        assert!(output.contains("chat_stream_duration_seconds_bucket{le=\"+Inf\"} 2"));
+        assert!(output.contains("chat_stream_duration_seconds_sum 401.5"));
+        assert!(output.contains("chat_stream_duration_seconds_count 2"));
+    }
+}
@@ -0,0 +1,217 @@
+//! Chat usage analytics use case
+//!
+//! Summarizes a user's own chat usage over the last N UTC days from the
+//! per-reply usage records (see [`UsageRepository`]).
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    repository::{RepositoryError, RepositoryResult},
+    usage::{DailyMessages, ModelTokens, SessionActivity, UsageRepository},
+};
+
+/// Period used when the request does not choose one
+pub const DEFAULT_ANALYTICS_DAYS: u32 = 30;
+
+/// Longest selectable period
+pub const MAX_ANALYTICS_DAYS: u32 = 365;
+
+/// Sessions listed as most active
+const MOST_ACTIVE_SESSIONS: u64 = 5;
+
+/// A user's usage over a period
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageAnalytics {
+    pub days: u32,
+    /// Start of the first day of the period (UTC midnight)
+    pub since: DateTime<Utc>,
+    /// One entry per day of the period, oldest first, including empty days
+    pub messages_per_day: Vec<DailyMessages>,
+    pub tokens_per_model: Vec<ModelTokens>,
+    pub most_active_sessions: Vec<SessionActivity>,
+    pub average_latency_ms: Option<f64>,
+}
+
+/// Use case for reading a user's chat analytics
+pub struct UsageAnalyticsUseCase {
+    repository: Arc<dyn UsageRepository>,
+}
+
+impl UsageAnalyticsUseCase {
+    /// Create a new use case instance
+    #[must_use]
+    pub fn new(repository: Arc<dyn UsageRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Analytics of `user_id` over the last `days` days, today included
+    ///
+    /// # Errors
+    /// Returns `ValidationError` if `days` is not within
+    /// 1..=[`MAX_ANALYTICS_DAYS`], or `RepositoryError` if a query fails
+    pub async fn execute(&self, user_id: Uuid, days: u32) -> RepositoryResult<UsageAnalytics> {
+        self.execute_at(user_id, days, Utc::now()).await
+    }
+
+    async fn execute_at(
+        &self,
+        user_id: Uuid,
+        days: u32,
+        now: DateTime<Utc>,
+    ) -> RepositoryResult<UsageAnalytics> {
+        if !(1..=MAX_ANALYTICS_DAYS).contains(&days) {
+            return Err(RepositoryError::ValidationError(format!(
+                "days must be between 1 and {MAX_ANALYTICS_DAYS}"
+            )));
+        }
+
+        let first_day = now.date_naive() - Duration::days(i64::from(days) - 1);
+        let since = first_day.and_time(chrono::NaiveTime::MIN).and_utc();
+
+        let (daily, tokens_per_model, most_active_sessions, average_latency_ms) = tokio::try_join!(
+            self.repository.daily_messages(user_id, since),
+            self.repository.tokens_by_model(user_id, since),
+            self.repository
+                .most_active_sessions(user_id, since, MOST_ACTIVE_SESSIONS),
+            self.repository.average_latency_ms(user_id, since),
+        )?;
+
+        Ok(UsageAnalytics {
+            days,
+            since,
+            messages_per_day: fill_days(first_day, days, &daily),
+            tokens_per_model,
+            most_active_sessions,
+            average_latency_ms,
+        })
+    }
+}
+
+/// Expand sparse daily counts to every day of the period
+fn fill_days(first_day: NaiveDate, days: u32, daily: &[DailyMessages]) -> Vec<DailyMessages> {
+    first_day
+        .iter_days()
+        .take(days as usize)
+        .map(|date| DailyMessages {
+            date,
+            messages: daily
+                .iter()
+                .find(|day| day.date == date)
+                .map_or(0, |day| day.messages),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::chat::usage::UsageRecord;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockUsageRepository {
+        daily: Vec<DailyMessages>,
+        since: Mutex<Option<DateTime<Utc>>>,
+    }
+
+    #[async_trait]
+    impl UsageRepository for MockUsageRepository {
+        async fn record_usage(&self, _record: &UsageRecord) -> RepositoryResult<()> {
+            unimplemented!()
+        }
+
+        async fn daily_messages(
+            &self,
+            _user_id: Uuid,
+            since: DateTime<Utc>,
+        ) -> RepositoryResult<Vec<DailyMessages>> {
+            *self.since.lock().unwrap() = Some(since);
+            Ok(self.daily.clone())
+        }
+
+        async fn tokens_by_model(
+            &self,
+            _user_id: Uuid,
+            _since: DateTime<Utc>,
+        ) -> RepositoryResult<Vec<ModelTokens>> {
+            Ok(vec![ModelTokens {
+                model: "llama-3.3-70b".to_string(),
+                prompt_tokens: 120,
+                completion_tokens: 80,
+            }])
+        }
+
+        async fn most_active_sessions(
+            &self,
+            _user_id: Uuid,
+            _since: DateTime<Utc>,
+            limit: u64,
+        ) -> RepositoryResult<Vec<SessionActivity>> {
+            assert_eq!(limit, MOST_ACTIVE_SESSIONS);
+            Ok(Vec::new())
+        }
+
+        async fn average_latency_ms(
+            &self,
+            _user_id: Uuid,
+            _since: DateTime<Utc>,
+        ) -> RepositoryResult<Option<f64>> {
+            Ok(Some(420.5))
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 2, day).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_period_is_filled_with_empty_days() {
+        let repository = Arc::new(MockUsageRepository {
+            daily: vec![
+                DailyMessages {
+                    date: date(2),
+                    messages: 3,
+                },
+                DailyMessages {
+                    date: date(4),
+                    messages: 1,
+                },
+            ],
+            ..Default::default()
+        });
+        let now = Utc.with_ymd_and_hms(2025, 2, 4, 15, 30, 0).unwrap();
+
+        let analytics = UsageAnalyticsUseCase::new(Arc::clone(&repository) as Arc<_>)
+            .execute_at(Uuid::new_v4(), 3, now)
+            .await
+            .unwrap();
+
+        let expected_since = Utc.with_ymd_and_hms(2025, 2, 2, 0, 0, 0).unwrap();
+        assert_eq!(analytics.since, expected_since);
+        assert_eq!(*repository.since.lock().unwrap(), Some(expected_since));
+        let counts: Vec<_> = analytics
+            .messages_per_day
+            .iter()
+            .map(|day| (day.date, day.messages))
+            .collect();
+        assert_eq!(counts, [(date(2), 3), (date(3), 0), (date(4), 1)]);
+        assert_eq!(analytics.tokens_per_model.len(), 1);
+        assert_eq!(analytics.average_latency_ms, Some(420.5));
+    }
+
+    #[tokio::test]
+    async fn test_period_must_be_in_range() {
+        let use_case = UsageAnalyticsUseCase::new(Arc::new(MockUsageRepository::default()));
+
+        for days in [0, MAX_ANALYTICS_DAYS + 1] {
+            assert!(matches!(
+                use_case.execute(Uuid::new_v4(), days).await,
+                Err(RepositoryError::ValidationError(_))
+            ));
+        }
+    }
+}
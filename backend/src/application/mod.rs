@@ -3,4 +3,5 @@
 //! Contains use cases that orchestrate domain logic and coordinate
 //! between domain entities and infrastructure services.
 
+pub mod account;
 pub mod chat;
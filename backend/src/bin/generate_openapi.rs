@@ -1,25 +1,43 @@
 //! `OpenAPI` Schema Generator
 //!
 //! Standalone binary to generate `OpenAPI` schema without database connection.
-//! This binary only generates the schema file and exits immediately.
+//! This binary only generates the schema file and exits immediately. The
+//! server binary offers the same through `cobalt-stack-backend export-openapi`.
 //!
 //! # Usage
 //!
 //! ```bash
-//! cargo run --bin generate_openapi
+//! cargo run --bin generate_openapi -- --out ../openapi/schema.json
+//! cargo run --bin generate_openapi -- --audience public --out public.json
+//! cargo run --bin generate_openapi -- --json-case camelCase --out camel.json
 //! ```
 //!
 //! # Output
 //!
-//! Writes `OpenAPI` schema to `openapi/schema.json` in project root.
+//! Writes `OpenAPI` schema to the `--out` path (default `openapi/schema.json`
+//! relative to the working directory). `--audience` selects the public,
+//! authenticated or admin document (default: admin, the full spec).
+//! `--json-case camelCase` describes the `/api/v2` API instead of `/api/v1`.
 
 use cobalt_stack_backend::openapi;
 
 fn main() {
+    let export = match openapi::parse_export_args(std::env::args().skip(1)) {
+        Ok(export) => export,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            std::process::exit(2);
+        }
+    };
+
     // Generate OpenAPI schema
-    match openapi::write_openapi_schema() {
+    match openapi::write_openapi_schema(&export.out, export.audience, export.json_case) {
         Ok(()) => {
-            println!("✅ OpenAPI schema generated at openapi/schema.json");
+            println!(
+                "✅ OpenAPI schema ({}) generated at {}",
+                export.audience,
+                export.out.display()
+            );
             std::process::exit(0);
         }
         Err(e) => {
@@ -4,6 +4,10 @@
 //! the application. It's designed to be run once during initial setup or when
 //! resetting the admin account.
 //!
+//! Deployments can instead set `BOOTSTRAP_ADMIN_EMAIL` and
+//! `BOOTSTRAP_ADMIN_PASSWORD`: the server then creates the admin at startup,
+//! with a password that must be changed at first login.
+//!
 //! # Usage
 //!
 //! ```bash
@@ -89,6 +93,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         email_verified: Set(true), // Auto-verify admin email
         disabled_at: Set(None),
         last_login_at: Set(None),
+        tokens_not_before: Set(None),
+        password_changed_at: Set(None),
+        password_rotation_required: Set(false),
         created_at: Set(chrono::Utc::now().into()),
         updated_at: Set(chrono::Utc::now().into()),
     };
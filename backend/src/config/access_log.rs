@@ -0,0 +1,129 @@
+//! API access log configuration
+
+use std::env;
+use std::time::Duration;
+
+/// Where access records are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLogSinkKind {
+    /// `access_logs` table, queryable through the admin API
+    Database,
+    /// Structured log events with target `access_log`, for shipping to an
+    /// external store by the log collector
+    Log,
+}
+
+/// Request-level access records for deployments that need a compliance trail
+///
+/// Responses with status 400 or above are always recorded; other requests
+/// are sampled.
+#[derive(Debug, Clone)]
+pub struct AccessLogConfig {
+    /// Where records are written
+    pub sink: AccessLogSinkKind,
+    /// Fraction of successful requests to record (`0.0`..=`1.0`)
+    pub sample_rate: f64,
+    /// Path prefixes never recorded (health checks, scrapes)
+    pub exclude_paths: Vec<String>,
+    /// Age after which database records are deleted (`None` = keep forever)
+    pub retention: Option<Duration>,
+    /// How often expired records are deleted
+    pub purge_interval: Duration,
+}
+
+impl AccessLogConfig {
+    /// Load configuration from environment variables
+    ///
+    /// Returns `None` (nothing recorded) unless `ACCESS_LOG_ENABLED` is `true`.
+    ///
+    /// # Panics
+    /// Panics if a variable is set but cannot be parsed, or
+    /// `ACCESS_LOG_SAMPLE_RATE` is outside `0.0`..=`1.0`
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let enabled: bool = env::var("ACCESS_LOG_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .expect("ACCESS_LOG_ENABLED must be a boolean");
+        if !enabled {
+            return None;
+        }
+
+        let sink = match env::var("ACCESS_LOG_SINK").as_deref() {
+            Err(_) | Ok("database") => AccessLogSinkKind::Database,
+            Ok("log") => AccessLogSinkKind::Log,
+            Ok(other) => panic!("ACCESS_LOG_SINK must be 'database' or 'log', got '{other}'"),
+        };
+
+        let sample_rate = env::var("ACCESS_LOG_SAMPLE_RATE")
+            .unwrap_or_else(|_| "1.0".to_string())
+            .parse()
+            .ok()
+            .filter(|rate| (0.0..=1.0).contains(rate))
+            .expect("ACCESS_LOG_SAMPLE_RATE must be a number between 0.0 and 1.0");
+
+        let exclude_paths = parse_paths(
+            &env::var("ACCESS_LOG_EXCLUDE_PATHS")
+                .unwrap_or_else(|_| "/health,/ready,/metrics".to_string()),
+        );
+
+        let retention_days: u64 = env::var("ACCESS_LOG_RETENTION_DAYS")
+            .unwrap_or_else(|_| "90".to_string())
+            .parse()
+            .expect("ACCESS_LOG_RETENTION_DAYS must be a number");
+
+        let purge_interval_secs: u64 = env::var("ACCESS_LOG_PURGE_INTERVAL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .expect("ACCESS_LOG_PURGE_INTERVAL_SECS must be a positive number");
+
+        Some(Self {
+            sink,
+            sample_rate,
+            exclude_paths,
+            retention: (retention_days > 0).then(|| Duration::from_secs(retention_days * 86400)),
+            purge_interval: Duration::from_secs(purge_interval_secs),
+        })
+    }
+
+    /// Whether requests to `path` are never recorded
+    #[must_use]
+    pub fn is_excluded(&self, path: &str) -> bool {
+        self.exclude_paths
+            .iter()
+            .any(|prefix| path.starts_with(prefix.as_str()))
+    }
+}
+
+/// Parse a comma-separated list of path prefixes
+fn parse_paths(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_excluded_matches_prefixes() {
+        let config = AccessLogConfig {
+            sink: AccessLogSinkKind::Log,
+            sample_rate: 1.0,
+            exclude_paths: parse_paths(" /health, ,/metrics"),
+            retention: None,
+            purge_interval: Duration::from_secs(3600),
+        };
+
+        assert_eq!(config.exclude_paths, vec!["/health", "/metrics"]);
+        assert!(config.is_excluded("/health"));
+        assert!(config.is_excluded("/health/ready"));
+        assert!(!config.is_excluded("/api/v1/auth/login"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Top-level application configuration
+
+use std::env;
+
+use super::access_log::AccessLogConfig;
+use super::bootstrap_admin::BootstrapAdminConfig;
+use super::branding::BrandingConfig;
+use super::cache::HttpCacheConfig;
+use super::email_bounce::EmailBounceConfig;
+use super::fault_injection::FaultInjectionConfig;
+use super::guest::GuestConfig;
+use super::json_case::JsonCase;
+use super::latency_budget::LatencyBudgetConfig;
+use super::oauth::OAuthConfig;
+use super::object_storage::ObjectStorageConfig;
+use super::proxy::TrustedProxyConfig;
+use super::sandbox::SandboxConfig;
+use super::schema_check::SchemaCheckPolicy;
+use super::server::{InternalListenerConfig, ServerConfig};
+use super::signing::RequestSigningConfig;
+use super::timeout::RequestTimeoutConfig;
+use super::token_store::TokenStoreBackend;
+
+/// Process-wide configuration assembled from the individual sections
+///
+/// The `enable_*` toggles allow modular deployments (e.g. auth only, without
+/// chat): a disabled subsystem is neither initialized nor routed.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    /// Public listener settings
+    pub server: ServerConfig,
+    /// Internal listener for `/metrics`, `/health/ready` and admin APIs
+    /// (`None` = serve them on the public listener)
+    pub internal_listener: Option<InternalListenerConfig>,
+    /// Request deadlines per route group
+    pub request_timeouts: RequestTimeoutConfig,
+    /// Per-route latency budgets, reported when exceeded
+    pub latency_budgets: LatencyBudgetConfig,
+    /// Default white-label branding (admins can override it at runtime)
+    pub branding: BrandingConfig,
+    /// Max-age of cacheable responses (models, branding)
+    pub http_cache: HttpCacheConfig,
+    /// Mount `/api/v1/chat/*` and initialize the LLM providers and Valkey
+    pub enable_chat: bool,
+    /// Mount `/api/v1/admin/*`
+    pub enable_admin_api: bool,
+    /// Send verification emails and mount the email verification endpoints
+    pub enable_email: bool,
+    /// Mount `/metrics` and count requests per route
+    pub enable_metrics: bool,
+    /// Bounce webhook and suppression list (`None` when email is disabled)
+    pub email_bounce: Option<EmailBounceConfig>,
+    /// Anonymous chat tokens (`None` unless `GUEST_MODE_ENABLED`; ignored
+    /// when chat is disabled)
+    pub guest: Option<GuestConfig>,
+    /// Accounts deleted after a retention period, with their own chat limits
+    /// (`None` unless `SANDBOX_MODE_ENABLED`)
+    pub sandbox: Option<SandboxConfig>,
+    /// Social login providers (`None` when none is configured)
+    pub oauth: Option<OAuthConfig>,
+    /// Admin account created at startup while there is no admin (`None`
+    /// unless configured)
+    pub bootstrap_admin: Option<BootstrapAdminConfig>,
+    /// Keys for HMAC-signed internal routes (`None` = routes not mounted)
+    pub request_signing: Option<RequestSigningConfig>,
+    /// Reverse proxies allowed to report the client IP
+    pub trusted_proxies: TrustedProxyConfig,
+    /// Where refresh tokens are stored (Valkey is initialized when needed)
+    pub token_store: TokenStoreBackend,
+    /// Bind refresh tokens to a device cookie and the `User-Agent`
+    pub bind_refresh_tokens: bool,
+    /// API access records (`None` = not recorded)
+    pub access_log: Option<AccessLogConfig>,
+    /// Field name case of JSON bodies (`camelCase` mounts `/api/v2`)
+    pub json_case: JsonCase,
+    /// Reaction to applied migrations that differ from this binary's
+    pub schema_check: SchemaCheckPolicy,
+    /// Mount `GET /openapi/types.ts` (development only)
+    pub serve_typescript_types: bool,
+    /// Let admins mint scoped access tokens (development only)
+    pub admin_debug_tokens: bool,
+    /// Fail a share of dependency calls on purpose (development only)
+    pub fault_injection: Option<FaultInjectionConfig>,
+    /// Storage of backup exports served for resumable download
+    pub object_storage: ObjectStorageConfig,
+}
+
+impl AppConfig {
+    /// Load configuration from environment variables
+    ///
+    /// # Panics
+    /// Panics if a variable is set but cannot be parsed
+    #[must_use]
+    pub fn from_env() -> Self {
+        let enable_email = flag_from_env("FEATURE_EMAIL_ENABLED", true);
+        Self {
+            server: ServerConfig::from_env(),
+            internal_listener: InternalListenerConfig::from_env(),
+            request_timeouts: RequestTimeoutConfig::from_env(),
+            latency_budgets: LatencyBudgetConfig::from_env(),
+            branding: BrandingConfig::from_env(),
+            http_cache: HttpCacheConfig::from_env(),
+            enable_chat: flag_from_env("FEATURE_CHAT_ENABLED", false),
+            enable_admin_api: flag_from_env("FEATURE_ADMIN_API_ENABLED", true),
+            enable_email,
+            enable_metrics: flag_from_env("FEATURE_METRICS_ENABLED", true),
+            email_bounce: enable_email.then(EmailBounceConfig::from_env),
+            guest: GuestConfig::from_env(),
+            sandbox: SandboxConfig::from_env(),
+            oauth: OAuthConfig::from_env(),
+            bootstrap_admin: BootstrapAdminConfig::from_env(),
+            request_signing: RequestSigningConfig::from_env(),
+            trusted_proxies: TrustedProxyConfig::from_env(),
+            token_store: TokenStoreBackend::from_env(),
+            bind_refresh_tokens: flag_from_env("REFRESH_TOKEN_BINDING_ENABLED", false),
+            access_log: AccessLogConfig::from_env(),
+            json_case: JsonCase::from_env(),
+            schema_check: SchemaCheckPolicy::from_env(),
+            serve_typescript_types: flag_from_env("OPENAPI_TYPES_ENABLED", false),
+            admin_debug_tokens: flag_from_env("ADMIN_DEBUG_TOKENS_ENABLED", false),
+            fault_injection: FaultInjectionConfig::from_env(),
+            object_storage: ObjectStorageConfig::from_env(),
+        }
+    }
+}
+
+fn flag_from_env(key: &str, default: bool) -> bool {
+    env::var(key)
+        .unwrap_or_else(|_| default.to_string())
+        .parse()
+        .unwrap_or_else(|_| panic!("{key} must be a boolean"))
+}
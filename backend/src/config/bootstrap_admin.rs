@@ -0,0 +1,59 @@
+//! Initial admin account configuration
+
+use std::{env, fmt};
+
+/// Admin account created at startup while the deployment has no admin
+///
+/// Only loaded when `BOOTSTRAP_ADMIN_EMAIL` and `BOOTSTRAP_ADMIN_PASSWORD`
+/// are set; see [`crate::services::auth::bootstrap`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct BootstrapAdminConfig {
+    /// `BOOTSTRAP_ADMIN_EMAIL`
+    pub email: String,
+    /// `BOOTSTRAP_ADMIN_USERNAME` (default: admin), used when the account is
+    /// created
+    pub username: String,
+    /// `BOOTSTRAP_ADMIN_PASSWORD`, to be changed at first login
+    pub password: String,
+}
+
+impl fmt::Debug for BootstrapAdminConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BootstrapAdminConfig")
+            .field("email", &self.email)
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BootstrapAdminConfig {
+    /// Load configuration from environment variables, `None` unless both the
+    /// email and the password are set
+    ///
+    /// # Panics
+    /// Panics if only one of `BOOTSTRAP_ADMIN_EMAIL` and
+    /// `BOOTSTRAP_ADMIN_PASSWORD` is set, or the email is not an address
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let non_empty = |key: &str| env::var(key).ok().filter(|value| !value.is_empty());
+
+        let (email, password) = match (
+            non_empty("BOOTSTRAP_ADMIN_EMAIL"),
+            non_empty("BOOTSTRAP_ADMIN_PASSWORD"),
+        ) {
+            (Some(email), Some(password)) => (email.trim().to_string(), password),
+            (None, None) => return None,
+            _ => panic!("BOOTSTRAP_ADMIN_EMAIL and BOOTSTRAP_ADMIN_PASSWORD must be set together"),
+        };
+        assert!(
+            email.contains('@'),
+            "BOOTSTRAP_ADMIN_EMAIL must be an email address"
+        );
+
+        Some(Self {
+            email,
+            username: non_empty("BOOTSTRAP_ADMIN_USERNAME").unwrap_or_else(|| "admin".to_string()),
+            password,
+        })
+    }
+}
@@ -0,0 +1,64 @@
+//! White-label branding configuration
+
+use std::env;
+
+/// Default branding of a deployment
+///
+/// Admins can override each field at runtime (see
+/// [`crate::services::branding`]); these values apply to every field that
+/// has no override.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrandingConfig {
+    /// Product name shown in the UI and emails
+    pub product_name: String,
+    /// Absolute or site-relative logo URL
+    pub logo_url: Option<String>,
+    /// Address users are told to contact for help
+    pub support_email: Option<String>,
+    /// Primary theme color (`#rrggbb`)
+    pub primary_color: String,
+    /// Accent theme color (`#rrggbb`)
+    pub accent_color: String,
+}
+
+impl Default for BrandingConfig {
+    fn default() -> Self {
+        Self {
+            product_name: "Cobalt Stack".to_string(),
+            logo_url: None,
+            support_email: None,
+            primary_color: "#18181b".to_string(),
+            accent_color: "#2563eb".to_string(),
+        }
+    }
+}
+
+impl BrandingConfig {
+    /// Load configuration from environment variables
+    ///
+    /// # Panics
+    /// Panics if a variable is set to an invalid value
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let config = Self {
+            product_name: non_empty_var("BRANDING_PRODUCT_NAME").unwrap_or(defaults.product_name),
+            logo_url: non_empty_var("BRANDING_LOGO_URL"),
+            support_email: non_empty_var("BRANDING_SUPPORT_EMAIL"),
+            primary_color: non_empty_var("BRANDING_PRIMARY_COLOR")
+                .unwrap_or(defaults.primary_color),
+            accent_color: non_empty_var("BRANDING_ACCENT_COLOR").unwrap_or(defaults.accent_color),
+        };
+
+        if let Err(e) = crate::services::branding::validate(&config) {
+            panic!("Invalid BRANDING_* configuration: {e}");
+        }
+
+        config
+    }
+}
+
+fn non_empty_var(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|value| !value.is_empty())
+}
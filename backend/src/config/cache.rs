@@ -0,0 +1,59 @@
+//! HTTP caching configuration
+
+use std::env;
+use std::time::Duration;
+
+/// How long clients may reuse rarely changing responses without revalidating
+///
+/// Responses always carry an `ETag` derived from their content, so once the
+/// max-age has passed a revalidation costs a `304 Not Modified` and an admin
+/// change is picked up on the next one. Zero makes clients revalidate every
+/// time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpCacheConfig {
+    /// Max-age of `GET /api/v1/chat/models`
+    pub models_max_age: Duration,
+    /// Max-age of `GET /api/v1/branding`
+    pub branding_max_age: Duration,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        Self {
+            models_max_age: Duration::from_secs(300),
+            branding_max_age: Duration::from_secs(60),
+        }
+    }
+}
+
+impl HttpCacheConfig {
+    /// Load configuration from environment variables
+    ///
+    /// # Panics
+    /// Panics if a max-age is not a number of seconds
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            models_max_age: secs_from_env(
+                "HTTP_CACHE_MODELS_MAX_AGE_SECS",
+                defaults.models_max_age,
+            ),
+            branding_max_age: secs_from_env(
+                "HTTP_CACHE_BRANDING_MAX_AGE_SECS",
+                defaults.branding_max_age,
+            ),
+        }
+    }
+}
+
+fn secs_from_env(key: &str, default: Duration) -> Duration {
+    env::var(key).map_or(default, |value| {
+        Duration::from_secs(
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("{key} must be a number of seconds")),
+        )
+    })
+}
@@ -1,24 +1,121 @@
 //! Chat feature configuration
 
-use std::env;
+use std::{env, time::Duration};
 
 use crate::application::chat::send_message::LlmConfig;
+use crate::domain::chat::deletion::MessageDeletionPolicy;
+use crate::domain::chat::lock::LockPolicy;
 
 /// Chat feature configuration
+///
+/// Only loaded when chat is enabled (see [`super::AppConfig::enable_chat`]).
 #[derive(Debug, Clone)]
 pub struct ChatConfig {
-    /// Whether chat feature is enabled
-    pub enabled: bool,
     /// LLM API configuration
     pub llm: LlmConfig,
     /// Maximum context messages to send to LLM
     pub max_context_messages: u64,
     /// Maximum message content length
     pub max_message_length: usize,
+    /// Maximum messages per bulk import
+    pub max_import_messages: usize,
+    /// Delete or redact a message its owner deletes
+    pub message_deletion: MessageDeletionPolicy,
     /// Daily message quota per user
     pub daily_message_quota: u64,
     /// Rate limit (messages per minute)
     pub rate_limit_per_minute: u64,
+    /// Daily quota percentages that add `X-Quota-Warning` and notify the user
+    pub quota_warning_thresholds: Vec<u8>,
+    /// Reject (409) or wait when a session is already generating a response
+    pub session_lock_policy: LockPolicy,
+    /// Expiry of a session lock that was never released (e.g. instance crash)
+    pub session_lock_ttl: Duration,
+    /// Key signing public share link slugs
+    pub share_secret: String,
+    /// How often to send a synthetic completion to each provider (`None` = never)
+    pub provider_probe_interval: Option<Duration>,
+    /// Time a provider has to answer a probe
+    pub provider_probe_timeout: Duration,
+    /// Fail `/health/ready` when every probed provider is down
+    pub critical: bool,
+    /// Archive the sessions of users disabled for this long (`None` = never)
+    pub archive_disabled_after: Option<Duration>,
+    /// Interval of the heartbeat comments on SSE reply streams
+    pub stream_heartbeat_interval: Duration,
+    /// Abort a reply the client has not read for this long (`None` = never)
+    pub stream_idle_timeout: Option<Duration>,
+    /// Batch job limits and workers
+    pub jobs: JobConfig,
+    /// Delivery of completion webhooks
+    pub webhooks: WebhookConfig,
+}
+
+/// Batch completion job configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobConfig {
+    /// Maximum prompts per job
+    pub max_prompts: usize,
+    /// Maximum queued or running jobs per user
+    pub max_active: u64,
+    /// Worker tasks on this instance (0 = jobs are run by other instances)
+    pub workers: usize,
+    /// Requests in flight per provider across all workers
+    pub provider_concurrency: usize,
+}
+
+impl JobConfig {
+    /// Load configuration from `CHAT_JOB_*` environment variables
+    ///
+    /// # Panics
+    /// Panics if a variable is not a number, or a limit is zero
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self {
+            max_prompts: positive_from_env("CHAT_JOB_MAX_PROMPTS", 100),
+            max_active: positive_from_env("CHAT_JOB_MAX_ACTIVE", 1),
+            workers: env::var("CHAT_JOB_WORKERS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .expect("CHAT_JOB_WORKERS must be a number (0 disables workers)"),
+            provider_concurrency: positive_from_env("CHAT_JOB_PROVIDER_CONCURRENCY", 4),
+        }
+    }
+}
+
+/// Completion webhook configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebhookConfig {
+    /// Maximum webhooks per user
+    pub max_per_user: usize,
+    /// Attempts per delivery, the first one included
+    pub max_attempts: u32,
+    /// Time the receiver has to answer an attempt
+    pub timeout: Duration,
+    /// Characters of the reply included in the payload
+    pub content_chars: usize,
+    /// Accept URLs on loopback and private networks (local development)
+    pub allow_private_urls: bool,
+}
+
+impl WebhookConfig {
+    /// Load configuration from `CHAT_WEBHOOK_*` environment variables
+    ///
+    /// # Panics
+    /// Panics if a variable is not a number, or a limit is zero
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self {
+            max_per_user: positive_from_env("CHAT_WEBHOOK_MAX_PER_USER", 5),
+            max_attempts: positive_from_env("CHAT_WEBHOOK_MAX_ATTEMPTS", 5),
+            timeout: Duration::from_secs(positive_from_env("CHAT_WEBHOOK_TIMEOUT_SECS", 10)),
+            content_chars: positive_from_env("CHAT_WEBHOOK_CONTENT_CHARS", 1000),
+            allow_private_urls: env::var("CHAT_WEBHOOK_ALLOW_PRIVATE_URLS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .expect("CHAT_WEBHOOK_ALLOW_PRIVATE_URLS must be true or false"),
+        }
+    }
 }
 
 impl ChatConfig {
@@ -27,12 +124,8 @@ impl ChatConfig {
     /// # Panics
     /// Panics if required environment variables are missing or invalid
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub fn from_env() -> Self {
-        let enabled = env::var("FEATURE_CHAT_ENABLED")
-            .unwrap_or_else(|_| "false".to_string())
-            .parse()
-            .expect("FEATURE_CHAT_ENABLED must be a boolean");
-
         let api_base = env::var("SAMBANOVA_API_BASE")
             .unwrap_or_else(|_| "https://api.sambanova.ai/v1".to_string());
 
@@ -67,8 +160,61 @@ impl ChatConfig {
             .parse()
             .expect("CHAT_RATE_LIMIT_PER_MINUTE must be a number");
 
+        let quota_warning_thresholds = parse_thresholds(
+            &env::var("CHAT_QUOTA_WARNING_THRESHOLDS").unwrap_or_else(|_| "80".to_string()),
+        )
+        .expect("CHAT_QUOTA_WARNING_THRESHOLDS must be comma-separated percentages (1-99)");
+
+        let session_lock_policy = parse_lock_policy(
+            &env::var("CHAT_SESSION_LOCK_POLICY").unwrap_or_else(|_| "reject".to_string()),
+            env::var("CHAT_SESSION_LOCK_WAIT_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .expect("CHAT_SESSION_LOCK_WAIT_SECS must be a number"),
+        )
+        .expect("CHAT_SESSION_LOCK_POLICY must be 'reject' or 'wait'");
+
+        let session_lock_ttl = Duration::from_secs(
+            env::var("CHAT_SESSION_LOCK_TTL_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .expect("CHAT_SESSION_LOCK_TTL_SECS must be a positive number"),
+        );
+
+        // Its own key: share slugs must not be signed with the JWT secret
+        let share_secret = env::var("CHAT_SHARE_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .expect("CHAT_SHARE_SECRET must be set when chat is enabled");
+
+        let provider_probe_interval = env::var("CHAT_PROVIDER_PROBE_INTERVAL_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<u64>()
+            .map(|secs| (secs > 0).then(|| Duration::from_secs(secs)))
+            .expect("CHAT_PROVIDER_PROBE_INTERVAL_SECS must be a number (0 disables probes)");
+
+        let provider_probe_timeout = Duration::from_secs(
+            env::var("CHAT_PROVIDER_PROBE_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .expect("CHAT_PROVIDER_PROBE_TIMEOUT_SECS must be a positive number"),
+        );
+
+        let critical = env::var("CHAT_CRITICAL_DEPENDENCY")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .expect("CHAT_CRITICAL_DEPENDENCY must be a boolean");
+        if critical && provider_probe_interval.is_none() {
+            tracing::warn!(
+                "CHAT_CRITICAL_DEPENDENCY has no effect without CHAT_PROVIDER_PROBE_INTERVAL_SECS"
+            );
+        }
+
         Self {
-            enabled,
             llm: LlmConfig {
                 api_base,
                 api_key,
@@ -78,8 +224,125 @@ impl ChatConfig {
             },
             max_context_messages,
             max_message_length,
+            max_import_messages: import_limit_from_env(),
+            message_deletion: message_deletion_from_env(),
             daily_message_quota,
             rate_limit_per_minute,
+            quota_warning_thresholds,
+            session_lock_policy,
+            session_lock_ttl,
+            share_secret,
+            provider_probe_interval,
+            provider_probe_timeout,
+            critical,
+            archive_disabled_after: archive_delay_from_env(),
+            stream_heartbeat_interval: Duration::from_secs(positive_from_env(
+                "CHAT_STREAM_HEARTBEAT_SECS",
+                15,
+            )),
+            stream_idle_timeout: stream_idle_timeout_from_env(),
+            jobs: JobConfig::from_env(),
+            webhooks: WebhookConfig::from_env(),
         }
     }
 }
+
+/// Read `CHAT_MAX_IMPORT_MESSAGES` (default 100)
+fn import_limit_from_env() -> usize {
+    env::var("CHAT_MAX_IMPORT_MESSAGES")
+        .unwrap_or_else(|_| "100".to_string())
+        .parse()
+        .expect("CHAT_MAX_IMPORT_MESSAGES must be a number")
+}
+
+/// Read `CHAT_MESSAGE_DELETION` (default `redact`)
+fn message_deletion_from_env() -> MessageDeletionPolicy {
+    env::var("CHAT_MESSAGE_DELETION").map_or_else(
+        |_| MessageDeletionPolicy::default(),
+        |policy| {
+            MessageDeletionPolicy::parse(&policy)
+                .expect("CHAT_MESSAGE_DELETION must be 'redact' or 'hard'")
+        },
+    )
+}
+
+/// Read a positive number, `default` if unset
+fn positive_from_env<T: std::str::FromStr + Default + PartialOrd>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .map_or(Some(default), |value| {
+            value.parse().ok().filter(|v| *v > T::default())
+        })
+        .unwrap_or_else(|| panic!("{name} must be a positive number"))
+}
+
+/// Read `CHAT_ARCHIVE_DISABLED_AFTER_DAYS` (0 or unset disables archival)
+fn archive_delay_from_env() -> Option<Duration> {
+    env::var("CHAT_ARCHIVE_DISABLED_AFTER_DAYS")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse::<u64>()
+        .map(|days| (days > 0).then(|| Duration::from_secs(days * 86400)))
+        .expect("CHAT_ARCHIVE_DISABLED_AFTER_DAYS must be a number (0 disables archival)")
+}
+
+/// Read `CHAT_STREAM_IDLE_TIMEOUT_SECS` (default 60, 0 disables the timeout)
+fn stream_idle_timeout_from_env() -> Option<Duration> {
+    env::var("CHAT_STREAM_IDLE_TIMEOUT_SECS")
+        .unwrap_or_else(|_| "60".to_string())
+        .parse::<u64>()
+        .map(|secs| (secs > 0).then(|| Duration::from_secs(secs)))
+        .expect("CHAT_STREAM_IDLE_TIMEOUT_SECS must be a number (0 disables the timeout)")
+}
+
+/// Parse the session lock policy; `wait_secs` only applies to `wait`.
+fn parse_lock_policy(policy: &str, wait_secs: u64) -> Option<LockPolicy> {
+    match policy.trim().to_ascii_lowercase().as_str() {
+        "reject" => Some(LockPolicy::Reject),
+        "wait" => Some(LockPolicy::Wait(Duration::from_secs(wait_secs))),
+        _ => None,
+    }
+}
+
+/// Parse comma-separated percentages, sorted and deduplicated.
+///
+/// An empty string disables warnings.
+fn parse_thresholds(value: &str) -> Option<Vec<u8>> {
+    let mut thresholds = value
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse().ok().filter(|t| (1..100).contains(t)))
+        .collect::<Option<Vec<u8>>>()?;
+    thresholds.sort_unstable();
+    thresholds.dedup();
+    Some(thresholds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_thresholds() {
+        assert_eq!(parse_thresholds("80"), Some(vec![80]));
+        assert_eq!(parse_thresholds("95, 80,80"), Some(vec![80, 95]));
+        assert_eq!(parse_thresholds(""), Some(vec![]));
+    }
+
+    #[test]
+    fn test_parse_thresholds_rejects_invalid() {
+        assert_eq!(parse_thresholds("0"), None);
+        assert_eq!(parse_thresholds("100"), None);
+        assert_eq!(parse_thresholds("eighty"), None);
+    }
+
+    #[test]
+    fn test_parse_lock_policy() {
+        assert_eq!(parse_lock_policy("reject", 30), Some(LockPolicy::Reject));
+        assert_eq!(
+            parse_lock_policy("Wait", 5),
+            Some(LockPolicy::Wait(Duration::from_secs(5)))
+        );
+        assert_eq!(parse_lock_policy("queue", 5), None);
+    }
+}
@@ -0,0 +1,52 @@
+//! Weekly email digest configuration
+
+use std::{env, time::Duration};
+
+/// Email digest configuration
+///
+/// Only loaded when email is enabled (see [`super::AppConfig::enable_email`]).
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    /// Time between two digests for the same user
+    pub period: Duration,
+    /// How often the scheduler looks for users with a digest due
+    pub check_interval: Duration,
+    /// Public base URL used for links in emails (no trailing slash)
+    pub public_base_url: String,
+}
+
+impl DigestConfig {
+    /// Load configuration from environment variables
+    ///
+    /// # Panics
+    /// Panics if a variable is set but cannot be parsed
+    #[must_use]
+    pub fn from_env() -> Self {
+        let period_days: u64 = env::var("EMAIL_DIGEST_PERIOD_DAYS")
+            .unwrap_or_else(|_| "7".to_string())
+            .parse()
+            .ok()
+            .filter(|days| *days > 0)
+            .expect("EMAIL_DIGEST_PERIOD_DAYS must be a positive number");
+
+        let check_interval_secs: u64 = env::var("EMAIL_DIGEST_CHECK_INTERVAL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .expect("EMAIL_DIGEST_CHECK_INTERVAL_SECS must be a positive number");
+
+        let public_base_url = env::var("APP_PUBLIC_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| "http://localhost:2727".to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        Self {
+            period: Duration::from_secs(period_days * 86400),
+            check_interval: Duration::from_secs(check_interval_secs),
+            public_base_url,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Email bounce and complaint handling configuration
+
+use std::{env, time::Duration};
+
+/// Bounce webhook and suppression list configuration
+///
+/// Only loaded when email is enabled (see [`super::AppConfig::enable_email`]).
+#[derive(Debug, Clone)]
+pub struct EmailBounceConfig {
+    /// Shared secret the provider passes as `?token=` on the bounce webhook;
+    /// the webhook is not mounted without one
+    pub webhook_secret: Option<String>,
+    /// How often each instance reloads the suppression list from the database
+    pub refresh_interval: Duration,
+}
+
+impl EmailBounceConfig {
+    /// Load configuration from environment variables
+    ///
+    /// # Panics
+    /// Panics if a variable is set but cannot be parsed, or if the webhook
+    /// secret is shorter than 16 characters
+    #[must_use]
+    pub fn from_env() -> Self {
+        let webhook_secret = env::var("EMAIL_BOUNCE_WEBHOOK_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty());
+        if let Some(secret) = &webhook_secret {
+            assert!(
+                secret.len() >= 16,
+                "EMAIL_BOUNCE_WEBHOOK_SECRET must be at least 16 characters"
+            );
+        }
+
+        let refresh_secs: u64 = env::var("EMAIL_SUPPRESSION_REFRESH_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .expect("EMAIL_SUPPRESSION_REFRESH_SECS must be a positive number");
+
+        Self {
+            webhook_secret,
+            refresh_interval: Duration::from_secs(refresh_secs),
+        }
+    }
+}
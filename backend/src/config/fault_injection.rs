@@ -0,0 +1,109 @@
+//! Fault injection configuration (development and integration tests only)
+
+use std::env;
+use std::time::Duration;
+
+/// Share of requests failed per dependency, and how the failures behave
+///
+/// Loaded only with `FAULT_INJECTION_ENABLED=true`; every percentage
+/// defaults to 0, so only the faults that are configured are injected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaultInjectionConfig {
+    /// Percentage of API requests failed as if the database timed out
+    pub database_timeout_percent: u8,
+    /// Time a request with a database fault hangs before failing
+    pub database_timeout_delay: Duration,
+    /// Percentage of Valkey connections refused
+    pub valkey_unavailable_percent: u8,
+    /// Percentage of LLM provider requests answered with a 500
+    pub provider_error_percent: u8,
+    /// Percentage of LLM provider streams slowed down
+    pub provider_slow_percent: u8,
+    /// Delay before each chunk of a slowed provider stream
+    pub provider_chunk_delay: Duration,
+}
+
+impl Default for FaultInjectionConfig {
+    fn default() -> Self {
+        Self {
+            database_timeout_percent: 0,
+            database_timeout_delay: Duration::from_secs(5),
+            valkey_unavailable_percent: 0,
+            provider_error_percent: 0,
+            provider_slow_percent: 0,
+            provider_chunk_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl FaultInjectionConfig {
+    /// Load configuration from environment variables
+    ///
+    /// Returns `None` unless `FAULT_INJECTION_ENABLED=true`.
+    ///
+    /// # Panics
+    /// Panics if a percentage is not a number from 0 to 100, or a delay is not
+    /// a number of milliseconds
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let enabled = env::var("FAULT_INJECTION_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .expect("FAULT_INJECTION_ENABLED must be a boolean");
+        if !enabled {
+            return None;
+        }
+
+        let defaults = Self::default();
+        Some(Self {
+            database_timeout_percent: percent_from_env("FAULT_DATABASE_TIMEOUT_PERCENT"),
+            database_timeout_delay: millis_from_env(
+                "FAULT_DATABASE_TIMEOUT_DELAY_MS",
+                defaults.database_timeout_delay,
+            ),
+            valkey_unavailable_percent: percent_from_env("FAULT_VALKEY_UNAVAILABLE_PERCENT"),
+            provider_error_percent: percent_from_env("FAULT_PROVIDER_ERROR_PERCENT"),
+            provider_slow_percent: percent_from_env("FAULT_PROVIDER_SLOW_PERCENT"),
+            provider_chunk_delay: millis_from_env(
+                "FAULT_PROVIDER_CHUNK_DELAY_MS",
+                defaults.provider_chunk_delay,
+            ),
+        })
+    }
+}
+
+/// Read a percentage, 0 if unset
+fn percent_from_env(name: &str) -> u8 {
+    env::var(name).map_or(0, |value| {
+        parse_percent(&value).unwrap_or_else(|| panic!("{name} must be a number from 0 to 100"))
+    })
+}
+
+fn parse_percent(value: &str) -> Option<u8> {
+    value.trim().parse().ok().filter(|percent| *percent <= 100)
+}
+
+/// Read a number of milliseconds, `default` if unset
+fn millis_from_env(name: &str, default: Duration) -> Duration {
+    env::var(name).map_or(default, |value| {
+        value.trim().parse::<u64>().map_or_else(
+            |_| panic!("{name} must be a number of milliseconds"),
+            Duration::from_millis,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_percent() {
+        assert_eq!(parse_percent("0"), Some(0));
+        assert_eq!(parse_percent(" 25 "), Some(25));
+        assert_eq!(parse_percent("100"), Some(100));
+        assert_eq!(parse_percent("101"), None);
+        assert_eq!(parse_percent("-1"), None);
+        assert_eq!(parse_percent("half"), None);
+    }
+}
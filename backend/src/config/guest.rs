@@ -0,0 +1,54 @@
+//! Guest mode configuration
+
+use std::{env, time::Duration};
+
+/// Anonymous chat access for demo deployments
+///
+/// Only loaded when `GUEST_MODE_ENABLED=true`; guest mode also needs chat
+/// (see [`super::AppConfig::enable_chat`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuestConfig {
+    /// Lifetime of a guest token; unclaimed guests are deleted after it
+    pub token_ttl: Duration,
+    /// Chat messages a guest may send
+    pub max_messages: u32,
+    /// Guests that may be created from one IP address per `ip_window`
+    pub max_per_ip: u32,
+    /// Window of the per-IP creation limit
+    pub ip_window: Duration,
+}
+
+impl GuestConfig {
+    /// Load configuration from environment variables, `None` unless
+    /// `GUEST_MODE_ENABLED=true`
+    ///
+    /// # Panics
+    /// Panics if a variable is set but is not a positive number
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let enabled: bool = env::var("GUEST_MODE_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .expect("GUEST_MODE_ENABLED must be a boolean");
+        if !enabled {
+            return None;
+        }
+
+        Some(Self {
+            token_ttl: Duration::from_secs(positive("GUEST_TOKEN_TTL_MINUTES", 60) * 60),
+            max_messages: u32::try_from(positive("GUEST_MAX_MESSAGES", 10))
+                .expect("GUEST_MAX_MESSAGES is too large"),
+            max_per_ip: u32::try_from(positive("GUEST_MAX_PER_IP", 3))
+                .expect("GUEST_MAX_PER_IP is too large"),
+            ip_window: Duration::from_secs(positive("GUEST_IP_WINDOW_SECS", 3600)),
+        })
+    }
+}
+
+fn positive(key: &str, default: u64) -> u64 {
+    env::var(key)
+        .map_or(Ok(default), |value| value.parse())
+        .ok()
+        .filter(|value| *value > 0)
+        .unwrap_or_else(|| panic!("{key} must be a positive number"))
+}
@@ -0,0 +1,78 @@
+//! JSON field name case configuration
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// Case of the field names in JSON request and response bodies
+///
+/// `/api/v1` always uses `snake_case`, the case DTOs serialize in, so
+/// existing clients keep working. `camelCase` additionally serves the API
+/// under `/api/v2` with `camelCase` bodies (see
+/// [`crate::middleware::json_case`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonCase {
+    /// `snake_case` only (`/api/v1`)
+    #[default]
+    Snake,
+    /// `camelCase` under `/api/v2`, `snake_case` under `/api/v1`
+    Camel,
+}
+
+impl JsonCase {
+    /// Load the case from `API_JSON_CASE` (`snake_case` or `camelCase`)
+    ///
+    /// # Panics
+    /// Panics if the variable names another case
+    #[must_use]
+    pub fn from_env() -> Self {
+        env::var("API_JSON_CASE").map_or_else(
+            |_| Self::default(),
+            |value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|e| panic!("API_JSON_CASE: {e}"))
+            },
+        )
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Snake => "snake_case",
+            Self::Camel => "camelCase",
+        }
+    }
+}
+
+impl fmt::Display for JsonCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JsonCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "snake_case" | "snake" => Ok(Self::Snake),
+            "camelcase" | "camel" => Ok(Self::Camel),
+            other => Err(format!(
+                "unknown case '{other}' (expected snake_case or camelCase)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_case() {
+        assert_eq!("snake_case".parse(), Ok(JsonCase::Snake));
+        assert_eq!("camelCase".parse(), Ok(JsonCase::Camel));
+        assert!("kebab-case".parse::<JsonCase>().is_err());
+    }
+}
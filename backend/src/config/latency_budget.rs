@@ -0,0 +1,170 @@
+//! Latency budget configuration
+
+use std::env;
+use std::time::Duration;
+
+/// Budget of the routes under one path prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteBudget {
+    /// Path prefix, e.g. `/api/v1/chat`
+    pub prefix: String,
+    /// Time until the response headers are sent
+    pub budget: Duration,
+}
+
+/// Per-route latency budgets
+///
+/// A request that takes longer than the budget of its route is reported with
+/// a warning and counted at `/metrics`; it is not cut off (see
+/// [`super::RequestTimeoutConfig`] for deadlines).
+#[derive(Debug, Clone)]
+pub struct LatencyBudgetConfig {
+    /// Budget of routes not matching any prefix in `routes`
+    pub default: Duration,
+    /// Budgets by path prefix; the longest matching prefix wins
+    pub routes: Vec<RouteBudget>,
+}
+
+impl Default for LatencyBudgetConfig {
+    fn default() -> Self {
+        Self {
+            default: Duration::from_secs(1),
+            routes: vec![RouteBudget {
+                prefix: "/api/v1/chat".to_string(),
+                budget: Duration::from_secs(5),
+            }],
+        }
+    }
+}
+
+impl LatencyBudgetConfig {
+    /// Load configuration from environment variables
+    ///
+    /// `LATENCY_BUDGETS` is a comma-separated list of `prefix=milliseconds`
+    /// pairs, e.g. `/api/v1/auth=300,/api/v1/chat=5000`; an empty value
+    /// leaves every route on the default budget.
+    ///
+    /// # Panics
+    /// Panics if a budget is not a positive number of milliseconds, or if an
+    /// entry of `LATENCY_BUDGETS` is not `prefix=milliseconds`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let default = match env::var("LATENCY_BUDGET_MS") {
+            Ok(value) => parse_millis("LATENCY_BUDGET_MS", &value),
+            Err(_) => defaults.default,
+        };
+        let routes = match env::var("LATENCY_BUDGETS") {
+            Ok(value) => parse_routes(&value),
+            Err(_) => defaults.routes,
+        };
+
+        Self { default, routes }
+    }
+
+    /// Budget of `path` and the prefix it was matched by (`None` for the
+    /// default budget)
+    #[must_use]
+    pub fn budget_for(&self, path: &str) -> (Option<&str>, Duration) {
+        self.routes
+            .iter()
+            .filter(|route| matches_prefix(path, &route.prefix))
+            .max_by_key(|route| route.prefix.len())
+            .map_or((None, self.default), |route| {
+                (Some(route.prefix.as_str()), route.budget)
+            })
+    }
+}
+
+/// `prefix` matches whole path segments: `/api/v1/chat` covers
+/// `/api/v1/chat/sessions` but not `/api/v1/chatter`
+fn matches_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+fn parse_routes(value: &str) -> Vec<RouteBudget> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (prefix, millis) = entry
+                .split_once('=')
+                .filter(|(prefix, _)| prefix.starts_with('/'))
+                .unwrap_or_else(|| {
+                    panic!("LATENCY_BUDGETS entries must be prefix=milliseconds, got {entry:?}")
+                });
+            RouteBudget {
+                prefix: prefix.trim().to_string(),
+                budget: parse_millis("LATENCY_BUDGETS", millis),
+            }
+        })
+        .collect()
+}
+
+fn parse_millis(key: &str, value: &str) -> Duration {
+    let millis: u64 = value
+        .trim()
+        .parse()
+        .unwrap_or_else(|_| panic!("{key} must be a number of milliseconds"));
+    assert!(millis > 0, "{key} must be greater than zero");
+    Duration::from_millis(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_routes() {
+        let routes = parse_routes(" /api/v1/auth=300, /api/v1/chat=5000,");
+
+        assert_eq!(
+            routes,
+            vec![
+                RouteBudget {
+                    prefix: "/api/v1/auth".to_string(),
+                    budget: Duration::from_millis(300),
+                },
+                RouteBudget {
+                    prefix: "/api/v1/chat".to_string(),
+                    budget: Duration::from_secs(5),
+                },
+            ]
+        );
+        assert!(parse_routes("").is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "prefix=milliseconds")]
+    fn test_parse_routes_rejects_missing_budget() {
+        parse_routes("/api/v1/auth");
+    }
+
+    #[test]
+    fn test_budget_for_uses_longest_prefix() {
+        let config = LatencyBudgetConfig {
+            default: Duration::from_secs(1),
+            routes: parse_routes("/api/v1/chat=5000,/api/v1/chat/sessions/export=20000"),
+        };
+
+        assert_eq!(
+            config.budget_for("/api/v1/chat/sessions"),
+            (Some("/api/v1/chat"), Duration::from_secs(5))
+        );
+        assert_eq!(
+            config.budget_for("/api/v1/chat/sessions/export"),
+            (
+                Some("/api/v1/chat/sessions/export"),
+                Duration::from_secs(20)
+            )
+        );
+        assert_eq!(
+            config.budget_for("/api/v1/chatter"),
+            (None, Duration::from_secs(1))
+        );
+    }
+}
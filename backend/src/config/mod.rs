@@ -1,5 +1,46 @@
 //! Configuration module for application features
 
+pub mod access_log;
+pub mod app;
+pub mod bootstrap_admin;
+pub mod branding;
+pub mod cache;
 pub mod chat;
+pub mod digest;
+pub mod email_bounce;
+pub mod fault_injection;
+pub mod guest;
+pub mod json_case;
+pub mod latency_budget;
+pub mod oauth;
+pub mod object_storage;
+pub mod proxy;
+pub mod sandbox;
+pub mod schema_check;
+pub mod server;
+pub mod signing;
+pub mod stats_report;
+pub mod timeout;
+pub mod token_store;
+pub mod verification_links;
 
+pub use access_log::{AccessLogConfig, AccessLogSinkKind};
+pub use app::AppConfig;
+pub use bootstrap_admin::BootstrapAdminConfig;
+pub use branding::BrandingConfig;
 pub use chat::ChatConfig;
+pub use digest::DigestConfig;
+pub use fault_injection::FaultInjectionConfig;
+pub use guest::GuestConfig;
+pub use json_case::JsonCase;
+pub use latency_budget::LatencyBudgetConfig;
+pub use oauth::{OAuthClientConfig, OAuthConfig};
+pub use proxy::{ForwardedHeader, TrustedProxyConfig};
+pub use sandbox::SandboxConfig;
+pub use schema_check::SchemaCheckPolicy;
+pub use server::{ServerConfig, TlsConfig, UnixSocketConfig};
+pub use signing::RequestSigningConfig;
+pub use stats_report::StatsReportConfig;
+pub use timeout::RequestTimeoutConfig;
+pub use token_store::TokenStoreBackend;
+pub use verification_links::VerificationLinkConfig;
@@ -0,0 +1,75 @@
+//! `OAuth2` social login configuration
+
+use std::{env, fmt};
+
+/// Credentials of the OAuth app registered with one provider
+#[derive(Clone, PartialEq, Eq)]
+pub struct OAuthClientConfig {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl fmt::Debug for OAuthClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuthClientConfig")
+            .field("client_id", &self.client_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Social login providers
+///
+/// A provider is enabled by setting both its client id and secret. Providers
+/// redirect the browser to `{public_base_url}/oauth/{provider}/callback`, a
+/// frontend page that hands `code` and `state` to the backend callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthConfig {
+    /// `OAUTH_GOOGLE_CLIENT_ID` / `OAUTH_GOOGLE_CLIENT_SECRET`
+    pub google: Option<OAuthClientConfig>,
+    /// `OAUTH_GITHUB_CLIENT_ID` / `OAUTH_GITHUB_CLIENT_SECRET`
+    pub github: Option<OAuthClientConfig>,
+    /// Address users open (`APP_PUBLIC_URL`)
+    pub public_base_url: String,
+}
+
+impl OAuthConfig {
+    /// Load configuration from environment variables, `None` when no
+    /// provider is configured
+    ///
+    /// # Panics
+    /// Panics if only one of a provider's client id and secret is set
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let google = client_from_env("GOOGLE");
+        let github = client_from_env("GITHUB");
+        if google.is_none() && github.is_none() {
+            return None;
+        }
+
+        let public_base_url = env::var("APP_PUBLIC_URL")
+            .unwrap_or_else(|_| "http://localhost:2727".to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        Some(Self {
+            google,
+            github,
+            public_base_url,
+        })
+    }
+}
+
+fn client_from_env(provider: &str) -> Option<OAuthClientConfig> {
+    let id_key = format!("OAUTH_{provider}_CLIENT_ID");
+    let secret_key = format!("OAUTH_{provider}_CLIENT_SECRET");
+    let non_empty = |key: &str| env::var(key).ok().filter(|value| !value.is_empty());
+
+    match (non_empty(&id_key), non_empty(&secret_key)) {
+        (Some(client_id), Some(client_secret)) => Some(OAuthClientConfig {
+            client_id,
+            client_secret,
+        }),
+        (None, None) => None,
+        _ => panic!("{id_key} and {secret_key} must be set together"),
+    }
+}
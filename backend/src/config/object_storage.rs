@@ -0,0 +1,25 @@
+//! Object storage configuration
+
+use std::{env, path::PathBuf};
+
+/// Where large generated files (backup exports) are kept for download
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectStorageConfig {
+    /// Directory holding the objects; created on first write
+    pub root: PathBuf,
+}
+
+impl ObjectStorageConfig {
+    /// Load configuration from environment variables
+    #[must_use]
+    pub fn from_env() -> Self {
+        let root = env::var("OBJECT_STORAGE_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or_else(|| "data/objects".to_string());
+
+        Self {
+            root: PathBuf::from(root),
+        }
+    }
+}
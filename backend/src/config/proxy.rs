@@ -0,0 +1,158 @@
+//! Trusted reverse proxy configuration
+
+use ipnet::IpNet;
+use std::env;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// Reverse proxies whose forwarding headers are believed
+///
+/// The client IP header is only honored when the connecting peer is in one
+/// of these networks, since any client can send it.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxyConfig {
+    /// Networks of trusted proxies (bare addresses are single-host networks)
+    pub networks: Vec<IpNet>,
+    /// The one header the proxies set; the others pass through from the
+    /// client untouched and are ignored
+    pub header: ForwardedHeader,
+}
+
+/// Header carrying the client address, as set by the trusted proxies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ForwardedHeader {
+    /// `X-Forwarded-For`, appended to by each proxy (nginx and most load
+    /// balancers)
+    #[default]
+    XForwardedFor,
+    /// `Forwarded` (RFC 7239), `for=` parameters
+    Forwarded,
+    /// `CF-Connecting-IP`, set by Cloudflare
+    CfConnectingIp,
+}
+
+impl ForwardedHeader {
+    /// Header name, lowercase
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::XForwardedFor => "x-forwarded-for",
+            Self::Forwarded => "forwarded",
+            Self::CfConnectingIp => "cf-connecting-ip",
+        }
+    }
+}
+
+impl fmt::Display for ForwardedHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ForwardedHeader {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "x-forwarded-for" => Ok(Self::XForwardedFor),
+            "forwarded" => Ok(Self::Forwarded),
+            "cf-connecting-ip" => Ok(Self::CfConnectingIp),
+            other => Err(format!(
+                "unknown header '{other}' (expected x-forwarded-for, forwarded or \
+                 cf-connecting-ip)"
+            )),
+        }
+    }
+}
+
+impl TrustedProxyConfig {
+    /// Load configuration from environment variables
+    ///
+    /// Reads `TRUSTED_PROXIES`, a comma-separated list of CIDRs or addresses
+    /// (e.g. `10.0.0.0/8,127.0.0.1`), and `TRUSTED_PROXY_HEADER`, the header
+    /// they set (`x-forwarded-for`, `forwarded` or `cf-connecting-ip`; default
+    /// `x-forwarded-for`). Unset trusts no proxy.
+    ///
+    /// # Panics
+    /// Panics if an entry is neither a CIDR nor an IP address, or the header
+    /// is not one of the above
+    #[must_use]
+    pub fn from_env() -> Self {
+        let networks = parse_networks(&env::var("TRUSTED_PROXIES").unwrap_or_default())
+            .unwrap_or_else(|e| panic!("TRUSTED_PROXIES: {e}"));
+        let header = env::var("TRUSTED_PROXY_HEADER").map_or_else(
+            |_| ForwardedHeader::default(),
+            |value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|e| panic!("TRUSTED_PROXY_HEADER: {e}"))
+            },
+        );
+        Self { networks, header }
+    }
+
+    /// Whether `ip` belongs to a trusted proxy
+    #[must_use]
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.networks.iter().any(|network| network.contains(&ip))
+    }
+}
+
+/// Parse a comma-separated list of CIDRs or bare addresses
+fn parse_networks(value: &str) -> Result<Vec<IpNet>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| format!("invalid network '{entry}'"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_networks() {
+        let config = TrustedProxyConfig {
+            networks: parse_networks("10.0.0.0/8, 127.0.0.1,fd00::/8").unwrap(),
+            header: ForwardedHeader::default(),
+        };
+        assert_eq!(config.networks.len(), 3);
+
+        assert!(config.is_trusted("10.1.2.3".parse().unwrap()));
+        assert!(config.is_trusted("127.0.0.1".parse().unwrap()));
+        assert!(config.is_trusted("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(config.is_trusted("fd12::1".parse().unwrap()));
+        assert!(!config.is_trusted("127.0.0.2".parse().unwrap()));
+        assert!(!config.is_trusted("203.0.113.7".parse().unwrap()));
+
+        assert!(parse_networks("").unwrap().is_empty());
+        assert!(parse_networks("10.0.0.0/33").is_err());
+        assert!(parse_networks("proxy.internal").is_err());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            " X-Forwarded-For".parse::<ForwardedHeader>(),
+            Ok(ForwardedHeader::XForwardedFor)
+        );
+        assert_eq!(
+            "forwarded".parse::<ForwardedHeader>(),
+            Ok(ForwardedHeader::Forwarded)
+        );
+        assert_eq!(
+            "CF-Connecting-IP".parse::<ForwardedHeader>(),
+            Ok(ForwardedHeader::CfConnectingIp)
+        );
+        assert!("x-real-ip".parse::<ForwardedHeader>().is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! Sandbox mode configuration
+
+use std::{env, time::Duration};
+
+/// Self-cleaning demo deployments
+///
+/// Only loaded when `SANDBOX_MODE_ENABLED=true`. Accounts created while
+/// sandbox mode is on are deleted with their data `retention` after sign-up,
+/// and chat under the sandbox limits instead of the regular ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxConfig {
+    /// Lifetime of a sandbox account
+    pub retention: Duration,
+    /// Notice the frontend shows on every page
+    pub banner: String,
+    /// Chat messages a sandbox account may send per minute
+    pub rate_limit_per_minute: u64,
+    /// Chat messages a sandbox account may send per day
+    pub daily_message_quota: u64,
+}
+
+impl SandboxConfig {
+    /// Load configuration from environment variables, `None` unless
+    /// `SANDBOX_MODE_ENABLED=true`
+    ///
+    /// # Panics
+    /// Panics if a variable is set but is not a positive number
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let enabled: bool = env::var("SANDBOX_MODE_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .expect("SANDBOX_MODE_ENABLED must be a boolean");
+        if !enabled {
+            return None;
+        }
+
+        let retention_days = positive("SANDBOX_RETENTION_DAYS", 7);
+        let banner = env::var("SANDBOX_BANNER")
+            .ok()
+            .filter(|banner| !banner.trim().is_empty())
+            .unwrap_or_else(|| default_banner(retention_days));
+
+        Some(Self {
+            retention: Duration::from_secs(retention_days * 86_400),
+            banner,
+            rate_limit_per_minute: positive("SANDBOX_CHAT_RATE_LIMIT_PER_MINUTE", 5),
+            daily_message_quota: positive("SANDBOX_CHAT_DAILY_MESSAGE_QUOTA", 20),
+        })
+    }
+}
+
+fn default_banner(retention_days: u64) -> String {
+    let unit = if retention_days == 1 { "day" } else { "days" };
+    format!(
+        "This is a public sandbox: accounts and their data are deleted \
+         {retention_days} {unit} after sign-up."
+    )
+}
+
+fn positive(key: &str, default: u64) -> u64 {
+    env::var(key)
+        .map_or(Ok(default), |value| value.parse())
+        .ok()
+        .filter(|value| *value > 0)
+        .unwrap_or_else(|| panic!("{key} must be a positive number"))
+}
@@ -0,0 +1,67 @@
+//! Database schema version check configuration
+
+use std::env;
+use std::str::FromStr;
+
+/// What to do when the applied migrations differ from the ones this binary
+/// was built with
+///
+/// A database behind the binary (pending migrations) or ahead of it (applied
+/// by a newer release) makes entities and tables disagree, which surfaces as
+/// query errors on some routes only. See [`crate::services::schema_version`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaCheckPolicy {
+    /// Do not compare the migrations
+    Off,
+    /// Log the differences and report them in `/health/ready`
+    #[default]
+    Warn,
+    /// Also refuse to start, and fail `/health/ready` while they differ
+    Refuse,
+}
+
+impl SchemaCheckPolicy {
+    /// Load the policy from `SCHEMA_VERSION_CHECK` (`off`, `warn` or `refuse`)
+    ///
+    /// # Panics
+    /// Panics if the variable names another policy
+    #[must_use]
+    pub fn from_env() -> Self {
+        env::var("SCHEMA_VERSION_CHECK").map_or_else(
+            |_| Self::default(),
+            |value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|e| panic!("SCHEMA_VERSION_CHECK: {e}"))
+            },
+        )
+    }
+}
+
+impl FromStr for SchemaCheckPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "refuse" | "strict" => Ok(Self::Refuse),
+            other => Err(format!(
+                "unknown policy '{other}' (expected off, warn or refuse)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!("off".parse(), Ok(SchemaCheckPolicy::Off));
+        assert_eq!("Warn".parse(), Ok(SchemaCheckPolicy::Warn));
+        assert_eq!("refuse".parse(), Ok(SchemaCheckPolicy::Refuse));
+        assert!("ignore".parse::<SchemaCheckPolicy>().is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! HTTP server tuning configuration
+
+use std::env;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Connection-level settings applied when serving the router
+///
+/// Defaults suit a mix of short API calls and long-lived SSE streams.
+/// Operators fronting mostly streaming traffic typically raise
+/// `http2_max_concurrent_streams`; those behind a load balancer with its own
+/// idle timeout should keep `http1_keep_alive_timeout` slightly above it.
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// TCP port to listen on (ignored when bound to a Unix socket)
+    pub port: u16,
+    /// Listen on a Unix domain socket instead of TCP (e.g. behind nginx)
+    ///
+    /// A socket passed by systemd socket activation (`LISTEN_FDS`) takes
+    /// precedence over both this and `port`.
+    pub unix_socket: Option<UnixSocketConfig>,
+    /// Idle time allowed between HTTP/1 requests on a kept-alive connection
+    /// (`None` disables keep-alive)
+    pub http1_keep_alive_timeout: Option<Duration>,
+    /// Whether to accept HTTP/2 (prior knowledge / h2c) connections
+    pub http2_enabled: bool,
+    /// Maximum concurrent streams per HTTP/2 connection
+    pub http2_max_concurrent_streams: u32,
+    /// Set `TCP_NODELAY` on accepted TCP sockets
+    pub tcp_nodelay: bool,
+    /// Maximum simultaneously open connections (`None` = unlimited)
+    pub max_connections: Option<usize>,
+    /// TLS termination settings (`None` = plain HTTP, e.g. behind a proxy)
+    pub tls: Option<TlsConfig>,
+    /// Time open connections get to finish their requests after `SIGTERM`
+    /// before they are closed
+    pub shutdown_timeout: Duration,
+    /// Time between `SIGTERM` and closing the listener, during which the
+    /// instance reports unready but still accepts and serves connections so
+    /// a load balancer can stop routing to it first
+    pub shutdown_predrain: Duration,
+}
+
+/// TLS termination settings
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// PEM certificate chain (leaf first)
+    pub cert_path: PathBuf,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1)
+    pub key_path: PathBuf,
+    /// Plain-HTTP port that redirects to HTTPS (`None` = no redirect listener)
+    pub redirect_http_port: Option<u16>,
+}
+
+/// Internal listener for operational endpoints (metrics, readiness, admin APIs)
+///
+/// Bind it to a private interface (loopback, cluster network) so these
+/// endpoints stay unreachable from the public listener even if a proxy in
+/// front of it is misconfigured.
+#[derive(Debug, Clone)]
+pub struct InternalListenerConfig {
+    /// Address to bind, e.g. `127.0.0.1:9090`
+    pub addr: SocketAddr,
+}
+
+impl InternalListenerConfig {
+    /// Load internal listener settings; returns `None` unless `INTERNAL_LISTEN_ADDR` is set
+    ///
+    /// # Panics
+    /// Panics if `INTERNAL_LISTEN_ADDR` is not a valid socket address
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let addr = env::var("INTERNAL_LISTEN_ADDR")
+            .ok()?
+            .parse()
+            .expect("INTERNAL_LISTEN_ADDR must be a socket address (e.g. 127.0.0.1:9090)");
+        Some(Self { addr })
+    }
+}
+
+/// Unix domain socket listener settings
+#[derive(Debug, Clone)]
+pub struct UnixSocketConfig {
+    /// Filesystem path of the socket; a stale socket left by a previous run is replaced
+    pub path: PathBuf,
+    /// Permission bits applied to the socket file after binding (e.g. `0o660`
+    /// so only the owner and the proxy's group can connect)
+    pub mode: u32,
+}
+
+impl UnixSocketConfig {
+    /// Load Unix socket settings; returns `None` unless `SERVER_UNIX_SOCKET_PATH` is set
+    ///
+    /// # Panics
+    /// Panics if `SERVER_UNIX_SOCKET_MODE` is not an octal permission mode
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let path = PathBuf::from(env::var("SERVER_UNIX_SOCKET_PATH").ok()?);
+
+        let mode = env::var("SERVER_UNIX_SOCKET_MODE").unwrap_or_else(|_| "660".to_string());
+        let mode = u32::from_str_radix(mode.trim_start_matches("0o"), 8)
+            .ok()
+            .filter(|mode| *mode <= 0o777)
+            .expect("SERVER_UNIX_SOCKET_MODE must be an octal permission mode (e.g. 660)");
+
+        Some(Self { path, mode })
+    }
+}
+
+impl TlsConfig {
+    /// Load TLS settings; returns `None` unless both cert and key paths are set
+    ///
+    /// # Panics
+    /// Panics if only one of `TLS_CERT_PATH`/`TLS_KEY_PATH` is set, or if
+    /// `TLS_REDIRECT_HTTP_PORT` is not a valid port
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let cert_path = env::var("TLS_CERT_PATH").ok();
+        let key_path = env::var("TLS_KEY_PATH").ok();
+
+        let (cert_path, key_path) = match (cert_path, key_path) {
+            (Some(cert), Some(key)) => (PathBuf::from(cert), PathBuf::from(key)),
+            (None, None) => return None,
+            _ => panic!("TLS_CERT_PATH and TLS_KEY_PATH must be set together"),
+        };
+
+        let redirect_http_port = env::var("TLS_REDIRECT_HTTP_PORT").ok().map(|port| {
+            port.parse()
+                .expect("TLS_REDIRECT_HTTP_PORT must be a valid port number")
+        });
+
+        Some(Self {
+            cert_path,
+            key_path,
+            redirect_http_port,
+        })
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: 3000,
+            unix_socket: None,
+            http1_keep_alive_timeout: Some(Duration::from_secs(60)),
+            http2_enabled: true,
+            http2_max_concurrent_streams: 200,
+            tcp_nodelay: true,
+            max_connections: None,
+            tls: None,
+            shutdown_timeout: Duration::from_secs(30),
+            shutdown_predrain: Duration::ZERO,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Load configuration from environment variables
+    ///
+    /// # Panics
+    /// Panics if a variable is set but cannot be parsed
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let port = env::var("PORT")
+            .unwrap_or_else(|_| defaults.port.to_string())
+            .parse()
+            .expect("PORT must be a valid port number");
+
+        let keep_alive_secs: u64 = env::var("SERVER_HTTP1_KEEP_ALIVE_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .expect("SERVER_HTTP1_KEEP_ALIVE_TIMEOUT_SECS must be a number");
+
+        let http2_enabled = env::var("SERVER_HTTP2_ENABLED")
+            .unwrap_or_else(|_| defaults.http2_enabled.to_string())
+            .parse()
+            .expect("SERVER_HTTP2_ENABLED must be a boolean");
+
+        let http2_max_concurrent_streams = env::var("SERVER_HTTP2_MAX_CONCURRENT_STREAMS")
+            .unwrap_or_else(|_| defaults.http2_max_concurrent_streams.to_string())
+            .parse()
+            .expect("SERVER_HTTP2_MAX_CONCURRENT_STREAMS must be a number");
+
+        let tcp_nodelay = env::var("SERVER_TCP_NODELAY")
+            .unwrap_or_else(|_| defaults.tcp_nodelay.to_string())
+            .parse()
+            .expect("SERVER_TCP_NODELAY must be a boolean");
+
+        let max_connections: usize = env::var("SERVER_MAX_CONNECTIONS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .expect("SERVER_MAX_CONNECTIONS must be a number");
+
+        let shutdown_timeout_secs: u64 = env::var("SERVER_SHUTDOWN_TIMEOUT_SECS")
+            .unwrap_or_else(|_| defaults.shutdown_timeout.as_secs().to_string())
+            .parse()
+            .expect("SERVER_SHUTDOWN_TIMEOUT_SECS must be a number");
+
+        let shutdown_predrain_secs: u64 = env::var("SERVER_SHUTDOWN_PREDRAIN_SECS")
+            .unwrap_or_else(|_| defaults.shutdown_predrain.as_secs().to_string())
+            .parse()
+            .expect("SERVER_SHUTDOWN_PREDRAIN_SECS must be a number");
+
+        Self {
+            port,
+            unix_socket: UnixSocketConfig::from_env(),
+            http1_keep_alive_timeout: (keep_alive_secs > 0)
+                .then(|| Duration::from_secs(keep_alive_secs)),
+            http2_enabled,
+            http2_max_concurrent_streams,
+            tcp_nodelay,
+            max_connections: (max_connections > 0).then_some(max_connections),
+            tls: TlsConfig::from_env(),
+            shutdown_timeout: Duration::from_secs(shutdown_timeout_secs),
+            shutdown_predrain: Duration::from_secs(shutdown_predrain_secs),
+        }
+    }
+}
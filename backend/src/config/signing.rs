@@ -0,0 +1,111 @@
+//! Request signing configuration for internal service calls
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::time::Duration;
+
+/// Shortest accepted signing secret, in bytes
+pub const MIN_SIGNING_SECRET_BYTES: usize = 32;
+
+/// Shared HMAC keys for sidecar services calling the signed internal routes
+///
+/// Several keys can be active at once, so a key is rotated by adding the new
+/// one, moving callers over, then removing the old one.
+#[derive(Clone)]
+pub struct RequestSigningConfig {
+    /// Secret per key id (sent by callers in `X-Signature-Key-Id`)
+    pub keys: HashMap<String, Vec<u8>>,
+    /// Largest accepted difference between a request timestamp and now
+    pub window: Duration,
+}
+
+impl RequestSigningConfig {
+    /// Load configuration from environment variables
+    ///
+    /// Returns `None` (signed routes not mounted) unless
+    /// `INTERNAL_SIGNING_KEYS` lists at least one `key_id:secret` pair.
+    ///
+    /// # Panics
+    /// Panics if a key entry is malformed, a secret is shorter than
+    /// [`MIN_SIGNING_SECRET_BYTES`], or `INTERNAL_SIGNATURE_WINDOW_SECS` is not
+    /// a positive number
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let keys = parse_keys(&env::var("INTERNAL_SIGNING_KEYS").unwrap_or_default())
+            .unwrap_or_else(|e| panic!("INTERNAL_SIGNING_KEYS: {e}"));
+        if keys.is_empty() {
+            return None;
+        }
+
+        let window = Duration::from_secs(
+            env::var("INTERNAL_SIGNATURE_WINDOW_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .expect("INTERNAL_SIGNATURE_WINDOW_SECS must be a positive number"),
+        );
+
+        Some(Self { keys, window })
+    }
+}
+
+impl fmt::Debug for RequestSigningConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut key_ids: Vec<_> = self.keys.keys().collect();
+        key_ids.sort();
+        f.debug_struct("RequestSigningConfig")
+            .field("key_ids", &key_ids)
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+/// Parse a comma-separated list of `key_id:secret` pairs
+fn parse_keys(value: &str) -> Result<HashMap<String, Vec<u8>>, String> {
+    let mut keys = HashMap::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (key_id, secret) = entry
+            .split_once(':')
+            .filter(|(key_id, _)| !key_id.is_empty())
+            .ok_or_else(|| format!("expected key_id:secret, got '{entry}'"))?;
+        if secret.len() < MIN_SIGNING_SECRET_BYTES {
+            return Err(format!(
+                "secret for '{key_id}' must be at least {MIN_SIGNING_SECRET_BYTES} bytes"
+            ));
+        }
+        if keys
+            .insert(key_id.to_string(), secret.as_bytes().to_vec())
+            .is_some()
+        {
+            return Err(format!("duplicate key id '{key_id}'"));
+        }
+    }
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_parse_keys() {
+        let keys = parse_keys(&format!("sidecar:{SECRET}, backup-agent:{SECRET}x")).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys["sidecar"], SECRET.as_bytes());
+        assert_eq!(keys["backup-agent"], format!("{SECRET}x").as_bytes());
+
+        assert!(parse_keys("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_keys_rejects_bad_entries() {
+        assert!(parse_keys("no-separator").is_err());
+        assert!(parse_keys(&format!(":{SECRET}")).is_err());
+        assert!(parse_keys("sidecar:short").is_err());
+        assert!(parse_keys(&format!("a:{SECRET},a:{SECRET}")).is_err());
+    }
+}
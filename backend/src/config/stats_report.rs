@@ -0,0 +1,53 @@
+//! Scheduled admin stats report configuration
+
+use std::{env, time::Duration};
+
+/// Periodic email to administrators summarizing signups, active users, chat
+/// volume and LLM cost
+///
+/// Only loaded when email is enabled (see [`super::AppConfig::enable_email`]).
+#[derive(Debug, Clone)]
+pub struct StatsReportConfig {
+    /// Period covered by one report
+    pub period: Duration,
+    /// How often the scheduler checks whether a report is due
+    pub check_interval: Duration,
+}
+
+impl StatsReportConfig {
+    /// Load configuration from environment variables
+    ///
+    /// Returns `None` (no report) unless `ADMIN_STATS_REPORT_ENABLED` is `true`.
+    ///
+    /// # Panics
+    /// Panics if a variable is set but cannot be parsed
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let enabled: bool = env::var("ADMIN_STATS_REPORT_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .expect("ADMIN_STATS_REPORT_ENABLED must be a boolean");
+        if !enabled {
+            return None;
+        }
+
+        let period_days: u64 = env::var("ADMIN_STATS_REPORT_PERIOD_DAYS")
+            .unwrap_or_else(|_| "7".to_string())
+            .parse()
+            .ok()
+            .filter(|days| *days > 0)
+            .expect("ADMIN_STATS_REPORT_PERIOD_DAYS must be a positive number");
+
+        let check_interval_secs: u64 = env::var("ADMIN_STATS_REPORT_CHECK_INTERVAL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .expect("ADMIN_STATS_REPORT_CHECK_INTERVAL_SECS must be a positive number");
+
+        Some(Self {
+            period: Duration::from_secs(period_days * 86400),
+            check_interval: Duration::from_secs(check_interval_secs),
+        })
+    }
+}
@@ -0,0 +1,68 @@
+//! Request timeout configuration
+
+use axum::http::StatusCode;
+use std::env;
+use std::time::Duration;
+
+/// Per-route-group request deadlines
+///
+/// A deadline bounds the time until the response headers are sent, so a stuck
+/// database query cannot pin a connection forever. Streaming bodies (SSE) are
+/// not cut off once the response has started.
+#[derive(Debug, Clone)]
+pub struct RequestTimeoutConfig {
+    /// Deadline for routes without a group-specific override (admin, health, docs)
+    pub default: Duration,
+    /// Deadline for `/api/v1/auth/*`
+    pub auth: Duration,
+    /// Deadline for `/api/v1/chat/*` (non-streaming completions can take a while)
+    pub chat: Duration,
+    /// Status returned on timeout: `408 Request Timeout` or `504 Gateway Timeout`
+    pub status: StatusCode,
+}
+
+impl Default for RequestTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            default: Duration::from_secs(30),
+            auth: Duration::from_secs(10),
+            chat: Duration::from_secs(300),
+            status: StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+}
+
+impl RequestTimeoutConfig {
+    /// Load configuration from environment variables
+    ///
+    /// # Panics
+    /// Panics if a timeout is not a positive number of seconds, or if
+    /// `REQUEST_TIMEOUT_STATUS` is not `408` or `504`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let status = match env::var("REQUEST_TIMEOUT_STATUS").as_deref() {
+            Err(_) => defaults.status,
+            Ok("408") => StatusCode::REQUEST_TIMEOUT,
+            Ok("504") => StatusCode::GATEWAY_TIMEOUT,
+            Ok(_) => panic!("REQUEST_TIMEOUT_STATUS must be 408 or 504"),
+        };
+
+        Self {
+            default: secs_from_env("REQUEST_TIMEOUT_SECS", defaults.default),
+            auth: secs_from_env("REQUEST_TIMEOUT_AUTH_SECS", defaults.auth),
+            chat: secs_from_env("REQUEST_TIMEOUT_CHAT_SECS", defaults.chat),
+            status,
+        }
+    }
+}
+
+fn secs_from_env(key: &str, default: Duration) -> Duration {
+    let secs: u64 = env::var(key)
+        .unwrap_or_else(|_| default.as_secs().to_string())
+        .parse()
+        .unwrap_or_else(|_| panic!("{key} must be a number"));
+    assert!(secs > 0, "{key} must be greater than zero");
+    Duration::from_secs(secs)
+}
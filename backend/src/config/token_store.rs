@@ -0,0 +1,62 @@
+//! Refresh token storage configuration
+
+use std::env;
+use std::str::FromStr;
+
+/// Where issued refresh tokens are kept
+///
+/// Both stores apply the same rotation and reuse-detection rules. With
+/// `valkey` the database is not written on login or refresh; tokens are lost
+/// if Valkey loses its data, which signs every user out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TokenStoreBackend {
+    /// The `refresh_tokens` table
+    #[default]
+    Database,
+    /// Valkey hashes expiring with the token (stateless database mode)
+    Valkey,
+}
+
+impl TokenStoreBackend {
+    /// Load the backend from `REFRESH_TOKEN_STORE` (`database` or `valkey`)
+    ///
+    /// # Panics
+    /// Panics if the variable names another store
+    #[must_use]
+    pub fn from_env() -> Self {
+        env::var("REFRESH_TOKEN_STORE").map_or_else(
+            |_| Self::default(),
+            |value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|e| panic!("REFRESH_TOKEN_STORE: {e}"))
+            },
+        )
+    }
+}
+
+impl FromStr for TokenStoreBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "database" | "db" => Ok(Self::Database),
+            "valkey" | "redis" => Ok(Self::Valkey),
+            other => Err(format!(
+                "unknown store '{other}' (expected database or valkey)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_backend() {
+        assert_eq!("database".parse(), Ok(TokenStoreBackend::Database));
+        assert_eq!("Valkey".parse(), Ok(TokenStoreBackend::Valkey));
+        assert!("memory".parse::<TokenStoreBackend>().is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Email verification link configuration
+
+use std::collections::BTreeMap;
+use std::env;
+
+/// Placeholder replaced with the verification token
+pub const TOKEN_PLACEHOLDER: &str = "{token}";
+
+/// Longest client name (length of `email_verifications.client`)
+const MAX_CLIENT_LEN: usize = 50;
+
+/// Verification link templates, by client
+///
+/// Clients pass a hint at registration (e.g. `"client": "ios"`); a hint with
+/// a template here gets a link into that client, such as a mobile app's
+/// custom scheme (`myapp://verify?token={token}`). Any other hint, or none,
+/// gets the web link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationLinkConfig {
+    /// Link for browsers and clients without a template of their own
+    pub web: String,
+    /// Links by client name
+    pub clients: BTreeMap<String, String>,
+}
+
+impl Default for VerificationLinkConfig {
+    fn default() -> Self {
+        Self {
+            web: format!("http://localhost:2727/verify-email?token={TOKEN_PLACEHOLDER}"),
+            clients: BTreeMap::new(),
+        }
+    }
+}
+
+impl VerificationLinkConfig {
+    /// Load configuration from environment variables
+    ///
+    /// - `EMAIL_VERIFICATION_URL`: Web link (default:
+    ///   `{APP_PUBLIC_URL}/verify-email?token={token}`)
+    /// - `EMAIL_VERIFICATION_CLIENT_URLS`: Comma-separated `client=template`
+    ///   pairs, e.g. `ios=myapp://verify?token={token}`
+    ///
+    /// # Panics
+    /// Panics if a template lacks `{token}` or has no URL scheme, or if an
+    /// entry of `EMAIL_VERIFICATION_CLIENT_URLS` is not `client=template`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let web = env::var("EMAIL_VERIFICATION_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| {
+                let public_base_url = env::var("APP_PUBLIC_URL")
+                    .ok()
+                    .filter(|url| !url.is_empty())
+                    .unwrap_or_else(|| "http://localhost:2727".to_string());
+                format!(
+                    "{}/verify-email?token={TOKEN_PLACEHOLDER}",
+                    public_base_url.trim_end_matches('/')
+                )
+            });
+        check_template("EMAIL_VERIFICATION_URL", &web);
+
+        let clients = env::var("EMAIL_VERIFICATION_CLIENT_URLS")
+            .map(|value| parse_clients(&value))
+            .unwrap_or_default();
+
+        Self { web, clients }
+    }
+
+    /// Client a registration hint selects, if it has a template
+    ///
+    /// Hints are matched case-insensitively; the returned name is the one
+    /// to store with the token.
+    #[must_use]
+    pub fn client(&self, hint: Option<&str>) -> Option<&str> {
+        let hint = hint?.trim().to_ascii_lowercase();
+        self.clients
+            .get_key_value(&hint)
+            .map(|(client, _)| client.as_str())
+    }
+
+    /// Verification link of `token` for `client` (the web link for `None`
+    /// or a client no longer configured)
+    #[must_use]
+    pub fn url(&self, client: Option<&str>, token: &str) -> String {
+        client
+            .and_then(|client| self.clients.get(client))
+            .unwrap_or(&self.web)
+            .replace(TOKEN_PLACEHOLDER, token)
+    }
+}
+
+fn parse_clients(value: &str) -> BTreeMap<String, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (client, template) = entry
+                .split_once('=')
+                .map(|(client, template)| (client.trim().to_ascii_lowercase(), template.trim()))
+                .filter(|(client, _)| {
+                    !client.is_empty()
+                        && client.len() <= MAX_CLIENT_LEN
+                        && client
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                })
+                .unwrap_or_else(|| {
+                    panic!(
+                        "EMAIL_VERIFICATION_CLIENT_URLS entries must be client=template, got {entry:?}"
+                    )
+                });
+            check_template("EMAIL_VERIFICATION_CLIENT_URLS", template);
+            (client, template.to_string())
+        })
+        .collect()
+}
+
+/// A template must carry the token and open something: `https://...`, or a
+/// custom scheme such as `myapp://...`
+fn check_template(key: &str, template: &str) {
+    assert!(
+        template.contains(TOKEN_PLACEHOLDER),
+        "{key} templates must contain {TOKEN_PLACEHOLDER}, got {template:?}"
+    );
+    let scheme = template.split_once(':').map_or("", |(scheme, _)| scheme);
+    assert!(
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')),
+        "{key} templates must start with a URL scheme, got {template:?}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> VerificationLinkConfig {
+        VerificationLinkConfig {
+            web: "https://app.example.com/verify-email?token={token}".to_string(),
+            clients: parse_clients(
+                " iOS=myapp://verify?token={token}, android=https://m.example.com/v/{token},",
+            ),
+        }
+    }
+
+    #[test]
+    fn test_url_per_client() {
+        let config = config();
+
+        assert_eq!(config.client(Some(" IOS ")), Some("ios"));
+        assert_eq!(
+            config.url(config.client(Some("ios")), "abc"),
+            "myapp://verify?token=abc"
+        );
+        assert_eq!(
+            config.url(Some("android"), "abc"),
+            "https://m.example.com/v/abc"
+        );
+
+        // Unknown hints get the web link
+        assert_eq!(config.client(Some("desktop")), None);
+        assert_eq!(
+            config.url(None, "abc"),
+            "https://app.example.com/verify-email?token=abc"
+        );
+        assert_eq!(
+            config.url(Some("removed"), "abc"),
+            "https://app.example.com/verify-email?token=abc"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must contain {token}")]
+    fn test_parse_clients_rejects_template_without_token() {
+        parse_clients("ios=myapp://verify");
+    }
+
+    #[test]
+    #[should_panic(expected = "URL scheme")]
+    fn test_parse_clients_rejects_template_without_scheme() {
+        parse_clients("ios=/verify?token={token}");
+    }
+}
@@ -0,0 +1,230 @@
+//! In-memory demo mode (`cobalt-stack-backend --demo`).
+//!
+//! Runs the backend without Postgres or Valkey so the API can be evaluated
+//! with a single command:
+//!
+//! ```bash
+//! cargo run --features demo -- --demo
+//! ```
+//!
+//! The database is an in-memory `SQLite` instance whose schema is generated
+//! from the `SeaORM` entities (the Postgres migrations are not used, but are
+//! recorded as applied so the schema version check passes), seeded with a
+//! demo admin and a sample chat session. Everything is lost when the process
+//! exits.
+//!
+//! Without Valkey, chat (if enabled) uses an in-process session lock and runs
+//! without rate limiting, quota reporting, notifications or provider probes.
+//! Generating replies still needs LLM provider credentials.
+
+use anyhow::Result;
+use chrono::Utc;
+use migration::{Alias, Migrator, MigratorTrait, Query};
+use sea_orm::{
+    ActiveModelTrait, ConnectOptions, ConnectionTrait, Database, DatabaseConnection, Schema, Set,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::domain::chat::{
+    entity::{ChatMessage, ChatSession},
+    repository::ChatRepository,
+    value_objects::MessageRole,
+};
+use crate::infrastructure::persistence::SeaOrmChatRepository;
+use crate::models::{
+    access_logs, admin_stats, audit_logs, branding_settings, chat_job_items, chat_jobs,
+    chat_messages, chat_read_states, chat_sessions, chat_shares, chat_usage, chat_webhooks,
+    email_digest_subscriptions, email_log, email_suppressions, email_verifications, guest_accounts,
+    message_annotations, o_auth_accounts, permissions, refresh_tokens, role_permissions, roles,
+    runtime_switches, sandbox_accounts, scheduled_reports, sea_orm_active_enums::UserRole,
+    trusted_devices, user_preferences, user_roles, users,
+};
+use crate::services::auth::hash_password;
+use crate::services::schema_version::known_migrations;
+
+/// Username of the seeded admin
+pub const DEMO_ADMIN_USERNAME: &str = "demo-admin";
+/// Email of the seeded admin
+pub const DEMO_ADMIN_EMAIL: &str = "admin@demo.local";
+/// Password of the seeded admin
+pub const DEMO_ADMIN_PASSWORD: &str = "demo-password";
+
+/// Connections are never recycled: the database lives only as long as one
+/// of them stays open
+const KEEP_ALIVE: Duration = Duration::from_secs(60 * 60 * 24 * 365);
+
+/// Open a fresh in-memory database with the full schema and demo data
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be created or seeded.
+pub async fn connect() -> Result<Arc<DatabaseConnection>> {
+    let db = open().await?;
+    seed(&db).await?;
+    Ok(db)
+}
+
+/// Open a fresh in-memory database with the full schema and no data
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be created.
+pub async fn open() -> Result<Arc<DatabaseConnection>> {
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options
+        .min_connections(1)
+        .max_connections(1)
+        .idle_timeout(KEEP_ALIVE)
+        .max_lifetime(KEEP_ALIVE)
+        .sqlx_logging(false);
+    let db = Arc::new(Database::connect(options).await?);
+
+    create_schema(&db).await?;
+
+    Ok(db)
+}
+
+/// Create every table from its entity definition
+///
+/// # Errors
+///
+/// Returns an error if a table cannot be created.
+pub async fn create_schema(db: &DatabaseConnection) -> Result<()> {
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+
+    // Parents before children so foreign keys resolve
+    let tables = vec![
+        schema.create_table_from_entity(users::Entity),
+        schema.create_table_from_entity(refresh_tokens::Entity),
+        schema.create_table_from_entity(email_verifications::Entity),
+        schema.create_table_from_entity(o_auth_accounts::Entity),
+        schema.create_table_from_entity(trusted_devices::Entity),
+        schema.create_table_from_entity(guest_accounts::Entity),
+        schema.create_table_from_entity(sandbox_accounts::Entity),
+        schema.create_table_from_entity(permissions::Entity),
+        schema.create_table_from_entity(roles::Entity),
+        schema.create_table_from_entity(role_permissions::Entity),
+        schema.create_table_from_entity(user_roles::Entity),
+        schema.create_table_from_entity(user_preferences::Entity),
+        schema.create_table_from_entity(email_digest_subscriptions::Entity),
+        schema.create_table_from_entity(email_suppressions::Entity),
+        schema.create_table_from_entity(email_log::Entity),
+        schema.create_table_from_entity(branding_settings::Entity),
+        schema.create_table_from_entity(runtime_switches::Entity),
+        schema.create_table_from_entity(admin_stats::Entity),
+        schema.create_table_from_entity(chat_sessions::Entity),
+        schema.create_table_from_entity(chat_messages::Entity),
+        schema.create_table_from_entity(chat_read_states::Entity),
+        schema.create_table_from_entity(chat_shares::Entity),
+        schema.create_table_from_entity(chat_usage::Entity),
+        schema.create_table_from_entity(chat_webhooks::Entity),
+        schema.create_table_from_entity(chat_jobs::Entity),
+        schema.create_table_from_entity(chat_job_items::Entity),
+        schema.create_table_from_entity(message_annotations::Entity),
+        schema.create_table_from_entity(access_logs::Entity),
+        schema.create_table_from_entity(audit_logs::Entity),
+        schema.create_table_from_entity(scheduled_reports::Entity),
+    ];
+    for table in &tables {
+        db.execute(backend.build(table)).await?;
+    }
+
+    // The entities are the schema of the latest migration
+    Migrator::install(db).await?;
+    let applied_at = Utc::now().timestamp();
+    let mut insert = Query::insert()
+        .into_table(Migrator::migration_table_name())
+        .columns([Alias::new("version"), Alias::new("applied_at")])
+        .to_owned();
+    for name in known_migrations() {
+        insert.values_panic([name.into(), applied_at.into()]);
+    }
+    db.execute(backend.build(&insert)).await?;
+
+    Ok(())
+}
+
+/// Insert the demo admin and a sample chat session owned by it
+///
+/// # Errors
+///
+/// Returns an error if the data cannot be inserted.
+pub async fn seed(db: &Arc<DatabaseConnection>) -> Result<()> {
+    let now = Utc::now();
+    let password_hash = hash_password(DEMO_ADMIN_PASSWORD)?;
+    let admin = users::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        username: Set(DEMO_ADMIN_USERNAME.to_string()),
+        email: Set(DEMO_ADMIN_EMAIL.to_string()),
+        password_hash: Set(Some(password_hash)),
+        role: Set(UserRole::Admin),
+        email_verified: Set(true),
+        disabled_at: Set(None),
+        last_login_at: Set(None),
+        tokens_not_before: Set(None),
+        password_changed_at: Set(None),
+        password_rotation_required: Set(false),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+    }
+    .insert(db.as_ref())
+    .await?;
+
+    let repository = SeaOrmChatRepository::new(Arc::clone(db));
+    let session = ChatSession::new(admin.id, "Welcome to Cobalt Stack".to_string())
+        .map_err(anyhow::Error::msg)?;
+    repository.create_session(&session).await?;
+    for (role, content) in [
+        (MessageRole::User, "What can I try in this demo?"),
+        (
+            MessageRole::Assistant,
+            "Everything runs in memory: sign in, browse the admin API and this chat \
+             history, or create new sessions. Data disappears when the server stops.",
+        ),
+    ] {
+        let message =
+            ChatMessage::new(session.id, role, content.to_string()).map_err(anyhow::Error::msg)?;
+        repository.save_message(&message).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::auth::verify_password;
+    use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+    #[tokio::test]
+    async fn test_connect_creates_seeded_database() {
+        let db = connect().await.unwrap();
+
+        let admin = users::Entity::find()
+            .filter(users::Column::Username.eq(DEMO_ADMIN_USERNAME))
+            .one(db.as_ref())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(admin.role, UserRole::Admin);
+        assert!(
+            verify_password(DEMO_ADMIN_PASSWORD, admin.password_hash.as_deref().unwrap()).unwrap()
+        );
+
+        let sessions = chat_sessions::Entity::find()
+            .filter(chat_sessions::Column::UserId.eq(admin.id))
+            .all(db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+        let messages = chat_messages::Entity::find()
+            .filter(chat_messages::Column::SessionId.eq(sessions[0].id))
+            .count(db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(messages, 2);
+    }
+}
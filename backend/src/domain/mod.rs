@@ -2,5 +2,10 @@
 //!
 //! Pure business logic with zero dependencies on infrastructure.
 //! Contains entities, value objects, domain services, and repository traits.
+//!
+//! Lives in the `cobalt-stack-domain` workspace crate, which compiles
+//! independently of the HTTP and persistence code; re-exported here so
+//! `crate::domain` paths stay the same.
 
-pub mod chat;
+pub use cobalt_stack_domain::chat;
+pub use cobalt_stack_domain::ids;
@@ -0,0 +1,882 @@
+//! Admin user management request/response types.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::health::ActiveModules;
+use crate::models::{
+    access_logs, audit_logs, email_log, email_suppressions, sea_orm_active_enums::UserRole, users,
+};
+use crate::services::doctor::{DoctorReport, Severity};
+use crate::services::effective_config::{ConfigSource, EffectiveConfig};
+use crate::services::rbac::RoleWithPermissions;
+use crate::services::runtime_switches::Switches;
+use crate::utils::pagination::QueryField;
+
+/// Query parameters for listing users, besides pagination, sort and filter
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListUsersQuery {
+    /// Search by username or email
+    pub search: Option<String>,
+}
+
+/// Fields users can be sorted by (default: `created_at:desc`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserSortField {
+    CreatedAt,
+    Username,
+    Email,
+    LastLoginAt,
+}
+
+impl QueryField for UserSortField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("created_at", Self::CreatedAt),
+        ("username", Self::Username),
+        ("email", Self::Email),
+        ("last_login_at", Self::LastLoginAt),
+    ];
+}
+
+/// Fields users can be filtered by: `role` (`admin`/`user`), `email_verified`
+/// and `disabled` (`true`/`false`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserFilterField {
+    Role,
+    EmailVerified,
+    Disabled,
+}
+
+impl QueryField for UserFilterField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("role", Self::Role),
+        ("email_verified", Self::EmailVerified),
+        ("disabled", Self::Disabled),
+    ];
+}
+
+/// User response for admin view (includes all fields)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminUserResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub role: UserRole,
+    pub email_verified: bool,
+    pub disabled_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    pub last_login_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// Password change required by an admin and not yet made
+    pub password_rotation_required: bool,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    pub updated_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl From<users::Model> for AdminUserResponse {
+    fn from(user: users::Model) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            role: user.role,
+            email_verified: user.email_verified,
+            disabled_at: user.disabled_at,
+            last_login_at: user.last_login_at,
+            password_rotation_required: user.password_rotation_required,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+/// Request to create a user account
+///
+/// The account gets a temporary password, returned once, which must be
+/// changed at first login.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub email: String,
+    /// Base role (default: `user`)
+    pub role: Option<UserRole>,
+    /// Mark the email as verified instead of sending a verification email
+    #[serde(default)]
+    pub email_verified: bool,
+}
+
+/// Created account with its temporary password
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedUserResponse {
+    pub user: AdminUserResponse,
+    /// Only returned here; hand it to the user over a secure channel
+    pub temporary_password: String,
+}
+
+/// Request to change the base role of a user
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateUserRoleRequest {
+    pub role: UserRole,
+}
+
+/// Paginated list response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserListResponse {
+    pub users: Vec<AdminUserResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+    pub total_pages: u64,
+}
+
+/// Admin statistics
+#[allow(clippy::struct_field_names)]
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminStatsResponse {
+    pub total_users: u64,
+    pub verified_users: u64,
+    pub admin_users: u64,
+    pub disabled_users: u64,
+    /// When the user counts were taken; they are refreshed every
+    /// `refresh_interval_secs`
+    pub refreshed_at: chrono::DateTime<chrono::Utc>,
+    pub refresh_interval_secs: u64,
+    /// Subsystems enabled on this deployment
+    pub modules: ActiveModules,
+}
+
+/// Query parameters for exporting admin statistics
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StatsExportQuery {
+    /// Export format; only `csv` is supported
+    #[param(default = "csv")]
+    pub format: Option<String>,
+    /// Start of the period (RFC 3339; default: 30 days before `to`)
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// End of the period, exclusive (RFC 3339; default: now)
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Request to mint a short-lived debug token
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DebugTokenRequest {
+    /// Role the token should act as (cannot exceed the caller's role)
+    #[schema(example = "user")]
+    pub role: UserRole,
+
+    /// Token lifetime in minutes (1-15)
+    #[serde(default = "default_debug_token_ttl")]
+    #[schema(example = 5)]
+    pub ttl_minutes: i64,
+}
+
+#[must_use]
+pub const fn default_debug_token_ttl() -> i64 {
+    5
+}
+
+/// Short-lived scoped access token for Swagger UI testing
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DebugTokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub role: UserRole,
+}
+
+/// Where a user stands in the email verification flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    /// At least one unused, unexpired link is outstanding
+    Pending,
+    /// Links were sent but none of them can be used anymore
+    Expired,
+    /// No verification email was ever issued
+    NeverSent,
+}
+
+/// Query parameters for listing unverified users, besides pagination, sort
+/// and filter
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListEmailVerificationsQuery {
+    /// Search by username or email
+    pub search: Option<String>,
+}
+
+/// Fields unverified users can be sorted by (default: `created_at:desc`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationSortField {
+    CreatedAt,
+    Username,
+    Email,
+}
+
+impl QueryField for VerificationSortField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("created_at", Self::CreatedAt),
+        ("username", Self::Username),
+        ("email", Self::Email),
+    ];
+}
+
+/// Fields unverified users can be filtered by: `status` (`pending`,
+/// `expired` or `never_sent`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationFilterField {
+    Status,
+}
+
+impl QueryField for VerificationFilterField {
+    const FIELDS: &'static [(&'static str, Self)] = &[("status", Self::Status)];
+}
+
+/// Verification state of one unverified user
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailVerificationResponse {
+    pub user_id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub status: VerificationStatus,
+    /// Number of verification emails issued so far
+    pub tokens_sent: u64,
+    /// When the most recent verification email was issued
+    pub last_sent_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// When the most recent link expires (or expired)
+    pub last_expires_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    pub user_created_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+/// Paginated list of unverified users
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailVerificationListResponse {
+    pub users: Vec<EmailVerificationResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+    pub total_pages: u64,
+}
+
+/// Request to mark a user's email as verified without a token
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ForceVerifyRequest {
+    /// Why the address is being verified manually (recorded in the audit log)
+    #[schema(example = "Confirmed ownership over support ticket #1234")]
+    pub reason: String,
+}
+
+/// Request to download an encrypted backup
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateBackupRequest {
+    /// Passphrase the archive is encrypted with (at least 12 characters);
+    /// it is not stored and is required again for restore
+    #[schema(example = "correct horse battery staple")]
+    pub passphrase: String,
+}
+
+/// A backup archive stored for resumable download
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupExportResponse {
+    pub id: Uuid,
+    #[schema(example = "cobalt-backup-20250127T100000Z.csbk")]
+    pub filename: String,
+    /// Archive size in bytes
+    #[schema(example = 73_400_320)]
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the archive, also sent as the download `ETag`
+    pub sha256: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Where to download the archive (supports `Range` requests)
+    #[schema(example = "/api/v1/admin/backup/exports/7c9e6679-7425-40de-944b-e07fc1f90ae7")]
+    pub download_url: String,
+}
+
+/// Result of restoring a backup
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreBackupResponse {
+    pub message: String,
+    /// Users restored (the bootstrap admin that ran the restore is replaced)
+    #[schema(example = 12)]
+    pub users: usize,
+    #[schema(example = 40)]
+    pub chat_sessions: usize,
+    #[schema(example = 815)]
+    pub chat_messages: usize,
+}
+
+/// Outcome of one configuration check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorSeverity {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl From<Severity> for DoctorSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Ok => Self::Ok,
+            Severity::Warning => Self::Warning,
+            Severity::Error => Self::Error,
+        }
+    }
+}
+
+/// One configuration check and how to fix it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DoctorFinding {
+    #[schema(example = "jwt_secret")]
+    pub check: String,
+    pub severity: DoctorSeverity,
+    #[schema(example = "JWT_SECRET is 12 bytes, shorter than 32")]
+    pub message: String,
+    /// What to change (absent when there is nothing to do)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+/// Configuration checks of this deployment
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DoctorReportResponse {
+    /// `false` if any check has severity `error`
+    pub healthy: bool,
+    pub findings: Vec<DoctorFinding>,
+}
+
+impl From<DoctorReport> for DoctorReportResponse {
+    fn from(report: DoctorReport) -> Self {
+        Self {
+            healthy: !report.has_errors(),
+            findings: report
+                .findings
+                .into_iter()
+                .map(|finding| DoctorFinding {
+                    check: finding.check.to_string(),
+                    severity: finding.severity.into(),
+                    message: finding.message,
+                    hint: finding.hint,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Where a setting comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigEntrySource {
+    /// The environment variable is set
+    Env,
+    /// The variable is unset; the default applies
+    Default,
+    /// A configuration file (the value is its path)
+    File,
+}
+
+impl From<ConfigSource> for ConfigEntrySource {
+    fn from(source: ConfigSource) -> Self {
+        match source {
+            ConfigSource::Env => Self::Env,
+            ConfigSource::Default => Self::Default,
+            ConfigSource::File => Self::File,
+        }
+    }
+}
+
+/// One setting in effect
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfigEntryResponse {
+    /// Environment variable, or file name
+    #[schema(example = "JWT_ACCESS_EXPIRY_MINUTES")]
+    pub key: String,
+    /// Value in effect; secrets are masked
+    #[schema(example = "30")]
+    pub value: String,
+    pub source: ConfigEntrySource,
+}
+
+/// Build and configuration of this instance
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SystemInfoResponse {
+    #[schema(example = "0.1.0")]
+    pub version: String,
+    pub config: Vec<ConfigEntryResponse>,
+}
+
+impl SystemInfoResponse {
+    #[must_use]
+    pub fn new(config: &EffectiveConfig) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config: config
+                .entries
+                .iter()
+                .map(|entry| ConfigEntryResponse {
+                    key: entry.key.to_string(),
+                    value: entry.value.clone(),
+                    source: entry.source.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Registration and read-only switches of the deployment
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RuntimeSwitchesResponse {
+    /// Whether new accounts can be registered (sign-up and social login)
+    pub registration_open: bool,
+    /// Whether mutating requests are rejected with 503
+    pub read_only: bool,
+    /// Reason returned with rejected requests
+    #[schema(example = "Database upgrade, back at 14:00 UTC")]
+    pub read_only_reason: Option<String>,
+}
+
+impl From<Switches> for RuntimeSwitchesResponse {
+    fn from(switches: Switches) -> Self {
+        Self {
+            registration_open: switches.registration_open,
+            read_only: switches.read_only,
+            read_only_reason: switches.read_only_reason,
+        }
+    }
+}
+
+/// Replace the registration and read-only switches
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateRuntimeSwitchesRequest {
+    pub registration_open: bool,
+    pub read_only: bool,
+    /// Ignored unless `read_only`; blank means no reason
+    pub read_only_reason: Option<String>,
+}
+
+impl From<UpdateRuntimeSwitchesRequest> for Switches {
+    fn from(request: UpdateRuntimeSwitchesRequest) -> Self {
+        Self {
+            registration_open: request.registration_open,
+            read_only: request.read_only,
+            read_only_reason: request
+                .read_only_reason
+                .filter(|_| request.read_only)
+                .map(|reason| reason.trim().to_string())
+                .filter(|reason| !reason.is_empty()),
+        }
+    }
+}
+
+/// Query parameters for listing access records, besides pagination, sort
+/// and filter
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListAccessLogsQuery {
+    /// Only records at or after this time (RFC 3339)
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only records before this time (RFC 3339)
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Fields access records can be sorted by (default: `created_at:desc`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLogSortField {
+    CreatedAt,
+    LatencyMs,
+    Status,
+}
+
+impl QueryField for AccessLogSortField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("created_at", Self::CreatedAt),
+        ("latency_ms", Self::LatencyMs),
+        ("status", Self::Status),
+    ];
+}
+
+/// Fields access records can be filtered by: `user_id`, `ip`, `method`,
+/// `path` (prefix) and `status` (a code such as `404`, or a class such as
+/// `5xx`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLogFilterField {
+    UserId,
+    Ip,
+    Method,
+    Path,
+    Status,
+}
+
+impl QueryField for AccessLogFilterField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("user_id", Self::UserId),
+        ("ip", Self::Ip),
+        ("method", Self::Method),
+        ("path", Self::Path),
+        ("status", Self::Status),
+    ];
+}
+
+/// One recorded API request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccessLogResponse {
+    pub id: Uuid,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    #[schema(example = "GET")]
+    pub method: String,
+    #[schema(example = "/api/v1/auth/me")]
+    pub path: String,
+    #[schema(example = 200)]
+    pub status: u16,
+    /// Time until the response headers were ready
+    pub latency_ms: u32,
+    pub user_id: Option<Uuid>,
+    pub ip: Option<String>,
+}
+
+impl From<access_logs::Model> for AccessLogResponse {
+    fn from(record: access_logs::Model) -> Self {
+        Self {
+            id: record.id,
+            created_at: record.created_at,
+            method: record.method,
+            path: record.path,
+            status: u16::try_from(record.status).unwrap_or_default(),
+            latency_ms: u32::try_from(record.latency_ms).unwrap_or_default(),
+            user_id: record.user_id,
+            ip: record.ip,
+        }
+    }
+}
+
+/// Paginated list of access records
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccessLogListResponse {
+    pub records: Vec<AccessLogResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+    pub total_pages: u64,
+}
+
+/// Query parameters for listing audit log entries, besides pagination, sort
+/// and filter
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListAuditLogsQuery {
+    /// Only entries at or after this time (RFC 3339)
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only entries before this time (RFC 3339)
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Fields audit log entries can be sorted by (default: `created_at:desc`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditLogSortField {
+    CreatedAt,
+    Event,
+}
+
+impl QueryField for AuditLogSortField {
+    const FIELDS: &'static [(&'static str, Self)] =
+        &[("created_at", Self::CreatedAt), ("event", Self::Event)];
+}
+
+/// Fields audit log entries can be filtered by: `user_id` (actor or
+/// target), `actor_id`, `target_id`, `event` (e.g. `auth.login_failed`)
+/// and `ip`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditLogFilterField {
+    UserId,
+    ActorId,
+    TargetId,
+    Event,
+    Ip,
+}
+
+impl QueryField for AuditLogFilterField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("user_id", Self::UserId),
+        ("actor_id", Self::ActorId),
+        ("target_id", Self::TargetId),
+        ("event", Self::Event),
+        ("ip", Self::Ip),
+    ];
+}
+
+/// One audited event
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub id: Uuid,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    #[schema(example = "auth.login")]
+    pub event: String,
+    /// User who did it
+    pub actor_id: Option<Uuid>,
+    /// User it was done to, if not the actor
+    pub target_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// Event-specific fields
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+}
+
+impl From<audit_logs::Model> for AuditLogResponse {
+    fn from(entry: audit_logs::Model) -> Self {
+        Self {
+            id: entry.id,
+            created_at: entry.created_at,
+            event: entry.event,
+            actor_id: entry.actor_id,
+            target_id: entry.target_id,
+            ip: entry.ip,
+            user_agent: entry.user_agent,
+            details: entry.details,
+        }
+    }
+}
+
+/// Paginated list of audit log entries
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditLogListResponse {
+    pub records: Vec<AuditLogResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+    pub total_pages: u64,
+}
+
+/// Query parameters for listing suppressed email addresses, besides
+/// pagination, sort and filter
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListEmailSuppressionsQuery {
+    /// Search by email address
+    pub search: Option<String>,
+}
+
+/// Fields suppressions can be sorted by (default: `last_reported_at:desc`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionSortField {
+    CreatedAt,
+    LastReportedAt,
+    Email,
+    Reports,
+}
+
+impl QueryField for SuppressionSortField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("created_at", Self::CreatedAt),
+        ("last_reported_at", Self::LastReportedAt),
+        ("email", Self::Email),
+        ("reports", Self::Reports),
+    ];
+}
+
+/// Fields suppressions can be filtered by: `reason` (`bounce` or `complaint`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionFilterField {
+    Reason,
+}
+
+impl QueryField for SuppressionFilterField {
+    const FIELDS: &'static [(&'static str, Self)] = &[("reason", Self::Reason)];
+}
+
+/// An email address mail is no longer sent to
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailSuppressionResponse {
+    pub id: Uuid,
+    #[schema(example = "gone@example.com")]
+    pub email: String,
+    /// `bounce` (hard bounce) or `complaint` (marked as spam)
+    #[schema(example = "bounce")]
+    pub reason: String,
+    /// Diagnostic reported by the provider
+    #[schema(example = "smtp; 550 5.1.1 user unknown")]
+    pub detail: Option<String>,
+    /// Reports received for the address
+    pub reports: i32,
+    pub last_reported_at: chrono::DateTime<chrono::FixedOffset>,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl From<email_suppressions::Model> for EmailSuppressionResponse {
+    fn from(suppression: email_suppressions::Model) -> Self {
+        Self {
+            id: suppression.id,
+            email: suppression.email,
+            reason: suppression.reason,
+            detail: suppression.detail,
+            reports: suppression.reports,
+            last_reported_at: suppression.last_reported_at,
+            created_at: suppression.created_at,
+        }
+    }
+}
+
+/// Paginated list of suppressed email addresses
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailSuppressionListResponse {
+    pub suppressions: Vec<EmailSuppressionResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+    pub total_pages: u64,
+}
+
+/// Query parameters for searching the email log, besides pagination, sort
+/// and filter
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListEmailLogQuery {
+    /// Search by recipient address
+    pub search: Option<String>,
+    /// Only emails sent at or after this time (RFC 3339)
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only emails sent before this time (RFC 3339)
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Fields the email log can be sorted by (default: `created_at:desc`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailLogSortField {
+    CreatedAt,
+    Recipient,
+}
+
+impl QueryField for EmailLogSortField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("created_at", Self::CreatedAt),
+        ("recipient", Self::Recipient),
+    ];
+}
+
+/// Fields the email log can be filtered by: `kind` (`verification`,
+/// `transactional` or `bulk`), `status` (`sent` or `failed`), `template` and
+/// `message_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailLogFilterField {
+    Kind,
+    Status,
+    Template,
+    MessageId,
+}
+
+impl QueryField for EmailLogFilterField {
+    const FIELDS: &'static [(&'static str, Self)] = &[
+        ("kind", Self::Kind),
+        ("status", Self::Status),
+        ("template", Self::Template),
+        ("message_id", Self::MessageId),
+    ];
+}
+
+/// One outbound email
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailLogResponse {
+    pub id: Uuid,
+    /// `verification`, `transactional` or `bulk`
+    #[schema(example = "verification")]
+    pub kind: String,
+    #[schema(example = "alice@example.com")]
+    pub recipient: String,
+    /// Template the email was rendered from
+    #[schema(example = "new_login")]
+    pub template: Option<String>,
+    /// Message id assigned by the mail provider, if it reports one
+    pub message_id: Option<String>,
+    /// `sent` (accepted by the provider) or `failed`
+    #[schema(example = "sent")]
+    pub status: String,
+    /// Why delivery failed
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl From<email_log::Model> for EmailLogResponse {
+    fn from(entry: email_log::Model) -> Self {
+        Self {
+            id: entry.id,
+            kind: entry.kind,
+            recipient: entry.recipient,
+            template: entry.template,
+            message_id: entry.message_id,
+            status: entry.status,
+            error: entry.error,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Paginated list of outbound emails
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailLogListResponse {
+    pub emails: Vec<EmailLogResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+    pub total_pages: u64,
+}
+
+/// A permission roles can grant
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PermissionResponse {
+    #[schema(example = "users:write")]
+    pub name: String,
+    #[schema(example = "Disable, enable and force password changes of user accounts")]
+    pub description: String,
+}
+
+/// A custom role and the permissions it grants
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RoleResponse {
+    pub id: Uuid,
+    #[schema(example = "support")]
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl From<RoleWithPermissions> for RoleResponse {
+    fn from(role: RoleWithPermissions) -> Self {
+        Self {
+            id: role.role.id,
+            name: role.role.name,
+            description: role.role.description,
+            permissions: role.permissions,
+            created_at: role.role.created_at,
+        }
+    }
+}
+
+/// Custom roles and the permissions available to them
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RoleListResponse {
+    pub roles: Vec<RoleResponse>,
+    pub permissions: Vec<PermissionResponse>,
+}
+
+/// Request to create a custom role
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateRoleRequest {
+    /// Lowercase letters, digits, `-` and `_`; not `admin` or `user`
+    #[schema(example = "support")]
+    pub name: String,
+    pub description: Option<String>,
+    /// Names of the permissions the role grants
+    pub permissions: Vec<String>,
+}
+
+/// Request to replace the custom roles of a user
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AssignRolesRequest {
+    pub role_ids: Vec<Uuid>,
+}
+
+/// Roles and resulting permissions of a user
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserRolesResponse {
+    pub user_id: Uuid,
+    /// Base role of the account
+    pub role: UserRole,
+    pub roles: Vec<RoleResponse>,
+    /// Every permission the user holds through the base and custom roles
+    pub permissions: Vec<String>,
+}
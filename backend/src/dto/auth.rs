@@ -0,0 +1,251 @@
+//! Authentication request/response types.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::preferences::UserPreferences;
+use crate::domain::ids::TokenId;
+use crate::models::sea_orm_active_enums::UserRole;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterRequest {
+    #[schema(example = "alice")]
+    pub username: String,
+
+    #[schema(example = "alice@example.com")]
+    pub email: String,
+
+    #[schema(example = "SecurePass123!")]
+    pub password: String,
+
+    /// Client registering, e.g. `ios`; selects the verification link of a
+    /// client configured in `EMAIL_VERIFICATION_CLIENT_URLS` (the web link
+    /// otherwise)
+    #[serde(default)]
+    #[schema(example = "ios")]
+    pub client: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LoginRequest {
+    /// Username or email address
+    #[schema(example = "alice")]
+    pub username_or_email: String,
+
+    #[schema(example = "SecurePass123!")]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[schema(example = "SecurePass123!")]
+    pub current_password: String,
+
+    #[schema(example = "EvenMoreSecure456!")]
+    pub new_password: String,
+}
+
+/// Profile fields to change; omitted fields are kept
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateProfileRequest {
+    #[schema(example = "alice")]
+    pub username: Option<String>,
+
+    /// A new address must be verified again
+    #[schema(example = "alice@example.org")]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    /// The password has expired: the access token is only accepted by
+    /// `POST /api/v1/auth/change-password`
+    pub password_expired: bool,
+    /// End of the grace window of a password past its maximum age
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_change_deadline: Option<chrono::DateTime<chrono::Utc>>,
+    /// The device is not trusted yet: no refresh token was issued, and a
+    /// confirmation link was emailed to the account owner
+    pub device_confirmation_required: bool,
+}
+
+/// Access token of a new guest (guest mode)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GuestTokenResponse {
+    /// Only accepted by the chat routes open to guests; no refresh token is
+    /// issued
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    #[schema(example = "guest_3f2a9c1b7d4e")]
+    pub username: String,
+    /// Chat messages the guest may send
+    #[schema(example = 10)]
+    pub max_messages: u32,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ClaimGuestRequest {
+    /// Access token from `POST /api/v1/auth/guest`
+    pub guest_token: String,
+}
+
+/// Result of claiming a guest
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClaimGuestResponse {
+    /// Chat sessions moved to the account
+    #[schema(example = 2)]
+    pub sessions_claimed: u64,
+}
+
+/// Parameters the provider redirected back with
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OAuthCallbackQuery {
+    /// Authorization code
+    pub code: String,
+    /// State issued by the authorize endpoint
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserResponse {
+    #[schema(value_type = String, example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub role: UserRole,
+    /// Preferences the frontend applies on load
+    pub preferences: UserPreferences,
+    /// What the current token may do, given the role and enabled modules
+    pub permissions: Vec<Permission>,
+    /// Daily chat quota usage (omitted when chat is disabled or unavailable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<QuotaSummary>,
+    /// Notice to show on every page (omitted unless sandbox mode is on)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxStatus>,
+}
+
+/// Capability the frontend can use to show or hide features
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub enum Permission {
+    #[serde(rename = "chat:use")]
+    ChatUse,
+    #[serde(rename = "chat:share")]
+    ChatShare,
+    #[serde(rename = "admin:users")]
+    AdminUsers,
+    #[serde(rename = "admin:stats")]
+    AdminStats,
+    #[serde(rename = "admin:branding")]
+    AdminBranding,
+    #[serde(rename = "admin:email_verifications")]
+    AdminEmailVerifications,
+    #[serde(rename = "admin:backup")]
+    AdminBackup,
+}
+
+/// Usage of the daily chat message quota
+#[allow(clippy::struct_field_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct QuotaSummary {
+    #[schema(example = 100)]
+    pub daily_limit: u64,
+    #[schema(example = 12)]
+    pub daily_used: u64,
+    #[schema(example = 88)]
+    pub daily_remaining: u64,
+}
+
+/// Sandbox mode as it applies to the current user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SandboxStatus {
+    #[schema(
+        example = "This is a public sandbox: accounts and their data are deleted 7 days after sign-up."
+    )]
+    pub banner: String,
+    /// When this account and its data are deleted (`null` for accounts
+    /// created before sandbox mode, which are kept)
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Confirmation of a (re)sent verification email
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SendVerificationResponse {
+    /// Human-readable confirmation message
+    pub message: String,
+    /// Seconds until another verification email can be requested
+    #[schema(example = 60)]
+    pub cooldown_secs: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyEmailRequest {
+    #[schema(example = "abc123def456")]
+    pub token: String,
+}
+
+/// An active login session (refresh token)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
+    #[schema(value_type = String, format = Uuid)]
+    pub id: TokenId,
+    /// Friendly device label parsed from the User-Agent
+    #[schema(example = "Chrome on macOS")]
+    pub device: String,
+    /// Raw User-Agent the session was issued to, if sent
+    pub user_agent: Option<String>,
+    /// IP address of the sign-in or latest refresh, if known
+    #[schema(example = "203.0.113.7")]
+    pub ip: Option<String>,
+    /// When the session signed in
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    /// Sign-in or latest refresh
+    pub last_used_at: chrono::DateTime<chrono::FixedOffset>,
+    pub expires_at: chrono::DateTime<chrono::FixedOffset>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+/// Active sessions of the current user, newest first
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionResponse>,
+}
+
+/// A device confirmed for long-lived sessions
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrustedDeviceResponse {
+    pub id: Uuid,
+    /// Friendly device label parsed from the User-Agent
+    #[schema(example = "Chrome on macOS")]
+    pub device: String,
+    /// Raw User-Agent of the sign-in that added the device, if sent
+    pub user_agent: Option<String>,
+    /// IP address of that sign-in, if known
+    pub ip: Option<String>,
+    /// When the device was confirmed
+    pub confirmed_at: chrono::DateTime<chrono::FixedOffset>,
+    /// Last sign-in or refresh from the device
+    pub last_used_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// Whether this is the device making the request
+    pub current: bool,
+}
+
+/// Trusted devices of the current user, most recently used first
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrustedDeviceListResponse {
+    pub devices: Vec<TrustedDeviceResponse>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmDeviceRequest {
+    /// Token from the confirmation email
+    #[schema(example = "abc123def456")]
+    pub token: String,
+}
@@ -0,0 +1,61 @@
+//! Data Transfer Objects for white-label branding
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::BrandingConfig;
+use crate::services::branding::BrandingOverrides;
+
+/// Effective branding of this deployment
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BrandingResponse {
+    /// Product name shown in the UI and emails
+    pub product_name: String,
+    /// Absolute or site-relative logo URL
+    pub logo_url: Option<String>,
+    /// Address users are told to contact for help
+    pub support_email: Option<String>,
+    /// Primary theme color (`#rrggbb`)
+    #[schema(example = "#18181b")]
+    pub primary_color: String,
+    /// Accent theme color (`#rrggbb`)
+    #[schema(example = "#2563eb")]
+    pub accent_color: String,
+}
+
+impl From<BrandingConfig> for BrandingResponse {
+    fn from(branding: BrandingConfig) -> Self {
+        Self {
+            product_name: branding.product_name,
+            logo_url: branding.logo_url,
+            support_email: branding.support_email,
+            primary_color: branding.primary_color,
+            accent_color: branding.accent_color,
+        }
+    }
+}
+
+/// Replace the runtime branding overrides
+///
+/// Omitted, `null` or blank fields fall back to the deployment defaults.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct UpdateBrandingRequest {
+    pub product_name: Option<String>,
+    pub logo_url: Option<String>,
+    pub support_email: Option<String>,
+    pub primary_color: Option<String>,
+    pub accent_color: Option<String>,
+}
+
+impl From<UpdateBrandingRequest> for BrandingOverrides {
+    fn from(request: UpdateBrandingRequest) -> Self {
+        Self {
+            product_name: request.product_name,
+            logo_url: request.logo_url,
+            support_email: request.support_email,
+            primary_color: request.primary_color,
+            accent_color: request.accent_color,
+        }
+    }
+}
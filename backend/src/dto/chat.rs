@@ -0,0 +1,1000 @@
+//! Data Transfer Objects for chat API
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::application::chat::batch_jobs::CostEstimate;
+use crate::application::chat::generation::{GenerationSnapshot, GenerationStatus};
+use crate::application::chat::session_read_state::SessionReadStateResponse;
+use crate::application::chat::share_session::SharedConversation;
+use crate::application::chat::usage_analytics::{UsageAnalytics, DEFAULT_ANALYTICS_DAYS};
+use crate::domain::chat::annotation::MessageAnnotation;
+use crate::domain::chat::entity::{ChatMessage, ChatSession};
+use crate::domain::chat::job::{ChatJob, ChatJobItem, JobItemStatus, JobStatus};
+use crate::domain::chat::prompt_budget::PromptOverflow;
+use crate::domain::chat::share::ChatShare;
+use crate::domain::chat::webhook::{ChatWebhook, WebhookDelivery};
+
+/// Request to create a new chat session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateSessionRequest {
+    /// Session title
+    #[schema(example = "My Chat Session")]
+    pub title: String,
+    /// User message stored with the session, in the same transaction
+    #[serde(default)]
+    #[schema(example = "Plan a weekend in Kyoto")]
+    pub first_message: Option<String>,
+}
+
+/// Response containing created session details
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateSessionResponse {
+    /// Session ID
+    pub session_id: Uuid,
+    /// Session title
+    pub title: String,
+    /// The stored first message, if the request had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_message: Option<MessageDto>,
+}
+
+/// Request to send a message
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SendMessageRequest {
+    /// Message content
+    #[schema(example = "Hello, how are you?")]
+    pub content: String,
+    /// Optional model ID to use (defaults to the user's default model, then
+    /// the configured default)
+    #[serde(default)]
+    #[schema(example = "llama-3.3-70b")]
+    pub model_id: Option<String>,
+    /// Optional sampling temperature, 0-2 (defaults to the user's default,
+    /// then the model's)
+    #[serde(default)]
+    #[schema(example = 0.7)]
+    pub temperature: Option<f32>,
+}
+
+/// One message of a bulk import
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportMessageDto {
+    /// Message role (`user` or `assistant`)
+    #[schema(example = "user")]
+    pub role: String,
+    /// Message content
+    #[schema(example = "What is Rust?")]
+    pub content: String,
+}
+
+/// Request to append messages to a session without generating a reply
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportMessagesRequest {
+    /// Messages in conversation order
+    pub messages: Vec<ImportMessageDto>,
+}
+
+/// Messages saved by a bulk import
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportMessagesResponse {
+    /// Saved messages in conversation order
+    pub messages: Vec<MessageDto>,
+}
+
+/// Request to rename a chat session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateSessionRequest {
+    /// New session title
+    #[schema(example = "Trip planning")]
+    pub title: String,
+    /// Session `version` the client last read; a newer stored version fails
+    /// with 409
+    pub version: i32,
+}
+
+/// Session details
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SessionDto {
+    /// Session ID
+    pub id: Uuid,
+    /// Session title
+    pub title: String,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// Last update timestamp
+    pub updated_at: DateTime<Utc>,
+    /// Incremented by every update; send it back when renaming
+    pub version: i32,
+    /// Messages the current user has not read (session listings only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unread_count: Option<u64>,
+}
+
+impl From<ChatSession> for SessionDto {
+    fn from(session: ChatSession) -> Self {
+        Self {
+            id: session.id,
+            title: session.title,
+            created_at: session.created_at,
+            updated_at: session.updated_at,
+            version: session.version,
+            unread_count: None,
+        }
+    }
+}
+
+/// Message details
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MessageDto {
+    /// Message ID
+    pub id: Uuid,
+    /// Message role
+    pub role: String,
+    /// Message content
+    pub content: String,
+    /// Token count (if available)
+    pub token_count: Option<i32>,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// When the content was redacted; it is then a placeholder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redacted_at: Option<DateTime<Utc>>,
+    /// The current user's annotations (history only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<AnnotationDto>,
+}
+
+impl From<ChatMessage> for MessageDto {
+    fn from(message: ChatMessage) -> Self {
+        Self {
+            id: message.id,
+            role: message.role.as_str().to_string(),
+            content: message.content,
+            token_count: message.token_count,
+            created_at: message.created_at,
+            redacted_at: message.redacted_at,
+            annotations: Vec::new(),
+        }
+    }
+}
+
+/// Response containing message history
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GetHistoryResponse {
+    /// Session details
+    pub session: SessionDto,
+    /// Messages in chronological order
+    pub messages: Vec<MessageDto>,
+}
+
+/// Response containing paginated sessions
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ListSessionsResponse {
+    /// Sessions for the current page
+    pub sessions: Vec<SessionDto>,
+    /// Total number of sessions
+    pub total: u64,
+    /// Current page number (1-based)
+    pub page: u64,
+    /// Items per page
+    pub per_page: u64,
+    /// Number of pages
+    pub total_pages: u64,
+}
+
+/// Response confirming deletion
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeleteSessionResponse {
+    /// Deleted session ID
+    pub session_id: Uuid,
+    /// Confirmation message
+    pub message: String,
+}
+
+/// Response confirming deletion of a message
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeleteMessageResponse {
+    /// Session ID
+    pub session_id: Uuid,
+    /// Deleted message ID
+    pub message_id: Uuid,
+    /// `true` if the message was redacted (kept with its content replaced)
+    /// rather than deleted
+    pub redacted: bool,
+}
+
+/// Query parameters for history endpoint
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Maximum number of messages to return
+    pub limit: Option<u64>,
+    /// Only messages with a matching annotation, e.g. `bookmark` or `label:todo`
+    pub annotation: Option<String>,
+}
+
+/// Request to mark a session as read
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct MarkReadRequest {
+    /// Last message read; defaults to the newest message in the session
+    #[serde(default)]
+    pub message_id: Option<Uuid>,
+}
+
+/// The current user's read position in a session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReadStateResponse {
+    /// Session ID
+    pub session_id: Uuid,
+    /// Last message read, if any
+    pub last_read_message_id: Option<Uuid>,
+    /// Messages created after the last read message
+    pub unread_count: u64,
+}
+
+impl From<SessionReadStateResponse> for ReadStateResponse {
+    fn from(state: SessionReadStateResponse) -> Self {
+        Self {
+            session_id: state.session_id,
+            last_read_message_id: state.last_read_message_id,
+            unread_count: state.unread_count,
+        }
+    }
+}
+
+/// Request to annotate a message
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddAnnotationRequest {
+    /// Annotation kind: `bookmark`, `label` or `reaction`
+    #[schema(example = "reaction")]
+    pub kind: String,
+    /// Label text or emoji; omitted for bookmarks
+    #[serde(default)]
+    #[schema(example = "👍")]
+    pub value: Option<String>,
+}
+
+/// A message annotation of the current user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnnotationDto {
+    /// Annotation ID
+    pub id: Uuid,
+    /// Annotated message ID
+    pub message_id: Uuid,
+    /// Annotation kind: `bookmark`, `label` or `reaction`
+    pub kind: String,
+    /// Label text or emoji (absent for bookmarks)
+    pub value: Option<String>,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<MessageAnnotation> for AnnotationDto {
+    fn from(annotation: MessageAnnotation) -> Self {
+        Self {
+            id: annotation.id,
+            message_id: annotation.message_id,
+            kind: annotation.annotation.kind().as_str().to_string(),
+            value: annotation.annotation.value().map(ToString::to_string),
+            created_at: annotation.created_at,
+        }
+    }
+}
+
+/// Query parameters for listing annotations
+#[derive(Debug, Deserialize)]
+pub struct AnnotationsQuery {
+    /// Only matching annotations, e.g. `bookmark` or `reaction:👍`
+    pub annotation: Option<String>,
+}
+
+/// The current user's annotations in a session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ListAnnotationsResponse {
+    /// Annotations, oldest first
+    pub annotations: Vec<AnnotationDto>,
+}
+
+/// Generation started in polling mode
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GenerationStartedResponse {
+    /// Generation ID to poll
+    pub generation_id: Uuid,
+    /// Session ID
+    pub session_id: Uuid,
+}
+
+/// Query parameters for polling a generation
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PollGenerationQuery {
+    /// Cursor returned by the previous poll (0 for the first poll)
+    #[serde(default)]
+    pub cursor: usize,
+    /// Seconds to wait for new output before returning an empty update
+    #[serde(default = "default_poll_wait_secs")]
+    pub wait_secs: u64,
+}
+
+const fn default_poll_wait_secs() -> u64 {
+    25
+}
+
+/// State of a polled generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationStatusDto {
+    Running,
+    Completed,
+    Failed,
+}
+
+impl From<GenerationStatus> for GenerationStatusDto {
+    fn from(status: GenerationStatus) -> Self {
+        match status {
+            GenerationStatus::Running => Self::Running,
+            GenerationStatus::Completed => Self::Completed,
+            GenerationStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+/// Incremental output of a generation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GenerationPollResponse {
+    /// Generation ID
+    pub generation_id: Uuid,
+    /// Session ID
+    pub session_id: Uuid,
+    /// Content produced since the requested cursor
+    pub content: String,
+    /// Cursor to pass to the next poll
+    pub cursor: usize,
+    /// Keep polling while `running`
+    pub status: GenerationStatusDto,
+    /// Failure reason when `failed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl GenerationPollResponse {
+    /// Build the response for `generation_id` from a store snapshot
+    #[must_use]
+    pub fn from_snapshot(generation_id: Uuid, snapshot: GenerationSnapshot) -> Self {
+        Self {
+            generation_id,
+            session_id: snapshot.session_id,
+            content: snapshot.content,
+            cursor: snapshot.cursor,
+            status: snapshot.status.into(),
+            error: snapshot.error,
+        }
+    }
+}
+
+/// Request to run many prompts as a batch job
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateJobRequest {
+    /// Prompts, each sent as a single-turn completion
+    pub prompts: Vec<String>,
+    /// Optional model ID to use (defaults to the default model)
+    #[serde(default)]
+    pub model_id: Option<String>,
+}
+
+/// Tokens and highest cost of a job
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobCostEstimateDto {
+    /// Model the prompts are sent to
+    pub model: String,
+    /// Number of prompts
+    pub prompts: usize,
+    /// Tokens of all prompts
+    pub prompt_tokens: u64,
+    /// Completion tokens if every reply uses the maximum
+    pub max_completion_tokens: u64,
+    /// Cost in USD of the above (null for a model without a price)
+    pub max_cost_usd: Option<f64>,
+}
+
+impl From<CostEstimate> for JobCostEstimateDto {
+    fn from(estimate: CostEstimate) -> Self {
+        Self {
+            model: estimate.model,
+            prompts: estimate.prompts,
+            prompt_tokens: estimate.prompt_tokens,
+            max_completion_tokens: estimate.max_completion_tokens,
+            max_cost_usd: estimate.max_cost_usd,
+        }
+    }
+}
+
+/// State of a batch job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatusDto {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl From<JobStatus> for JobStatusDto {
+    fn from(status: JobStatus) -> Self {
+        match status {
+            JobStatus::Queued => Self::Queued,
+            JobStatus::Running => Self::Running,
+            JobStatus::Completed => Self::Completed,
+            JobStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+/// Batch job status and progress
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobDto {
+    /// Job ID
+    pub id: Uuid,
+    /// Model the prompts are sent to
+    pub model: String,
+    /// Keep polling while `queued` or `running`
+    pub status: JobStatusDto,
+    /// Number of prompts
+    pub total_items: u32,
+    /// Prompts answered
+    pub completed_items: u32,
+    /// Prompts the provider failed on
+    pub failed_items: u32,
+    /// Prompt tokens of the answered prompts
+    pub prompt_tokens: u64,
+    /// Completion tokens of the answered prompts
+    pub completion_tokens: u64,
+    /// Cost in USD of the tokens so far (null for a model without a price)
+    pub cost_usd: Option<f64>,
+    /// Queued timestamp
+    pub created_at: DateTime<Utc>,
+    /// Timestamp a worker started the job
+    pub started_at: Option<DateTime<Utc>>,
+    /// Timestamp the job finished
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl JobDto {
+    /// Build the response from a job and its cost so far
+    #[must_use]
+    pub fn new(job: ChatJob, cost_usd: Option<f64>) -> Self {
+        Self {
+            id: job.id,
+            model: job.model,
+            status: job.status.into(),
+            total_items: job.total_items,
+            completed_items: job.completed_items,
+            failed_items: job.failed_items,
+            prompt_tokens: job.prompt_tokens,
+            completion_tokens: job.completion_tokens,
+            cost_usd,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            finished_at: job.finished_at,
+        }
+    }
+}
+
+/// Batch job queued
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateJobResponse {
+    /// Queued job; poll `GET /api/v1/chat/jobs/{id}` for progress
+    pub job: JobDto,
+    /// Estimate made when the job was queued
+    pub estimate: JobCostEstimateDto,
+}
+
+/// Outcome of one prompt of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobItemStatusDto {
+    Pending,
+    Completed,
+    Failed,
+}
+
+impl From<JobItemStatus> for JobItemStatusDto {
+    fn from(status: JobItemStatus) -> Self {
+        match status {
+            JobItemStatus::Pending => Self::Pending,
+            JobItemStatus::Completed => Self::Completed,
+            JobItemStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+/// One prompt of a job and its result
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobItemDto {
+    /// Index in the submitted prompt list
+    pub position: u32,
+    /// Submitted prompt
+    pub prompt: String,
+    /// Prompt outcome
+    pub status: JobItemStatusDto,
+    /// Model reply when `completed`
+    pub output: Option<String>,
+    /// Failure reason when `failed`
+    pub error: Option<String>,
+    /// Tokens of the prompt
+    pub prompt_tokens: Option<u32>,
+    /// Tokens of the reply
+    pub completion_tokens: Option<u32>,
+    /// Timestamp the prompt finished
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl From<ChatJobItem> for JobItemDto {
+    fn from(item: ChatJobItem) -> Self {
+        Self {
+            position: item.position,
+            prompt: item.prompt,
+            status: item.status.into(),
+            output: item.output,
+            error: item.error,
+            prompt_tokens: item.prompt_tokens,
+            completion_tokens: item.completion_tokens,
+            finished_at: item.finished_at,
+        }
+    }
+}
+
+/// Page of a job's results in prompt order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobResultsResponse {
+    /// Job status and progress
+    pub job: JobDto,
+    /// Prompts of the current page
+    pub items: Vec<JobItemDto>,
+    /// Total number of prompts
+    pub total: u64,
+    /// Current page number (1-based)
+    pub page: u64,
+    /// Items per page
+    pub per_page: u64,
+    /// Number of pages
+    pub total_pages: u64,
+}
+
+/// Kind of failure reported by a chat endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatErrorCode {
+    /// Session, message, job, webhook or share not found
+    NotFound,
+    /// Not the owner of the resource, account disabled or guest mode off
+    Forbidden,
+    /// Share password missing or incorrect
+    Unauthorized,
+    /// Share revoked or expired
+    Gone,
+    /// A reply is being generated, the session changed, or a limit on stored
+    /// resources was reached
+    Conflict,
+    /// Rate, guest or job limit reached
+    RateLimited,
+    /// The request is invalid (content, model, parameters)
+    ValidationFailed,
+    /// The prompt and the reply do not fit the model's context window
+    PromptTooLarge,
+    /// The model's provider is not configured or failed
+    ProviderUnavailable,
+    /// Unexpected server failure
+    Internal,
+}
+
+/// Error body of the chat endpoints
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChatErrorResponse {
+    /// Machine-readable kind of failure
+    pub error: ChatErrorCode,
+    /// Human-readable description
+    pub message: String,
+    /// Seconds to wait before retrying (rate limits only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+    /// By how much the prompt is too large (`prompt_too_large` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<PromptOverflowDto>,
+}
+
+/// Token budget of a request that does not fit the model's context window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct PromptOverflowDto {
+    /// Estimated tokens of the prompt (persona, context and the new message)
+    pub prompt_tokens: u32,
+    /// Tokens reserved for the reply
+    pub max_output_tokens: u32,
+    /// Tokens the model accepts in total
+    pub context_window: u32,
+    /// Tokens to remove from the prompt for the request to fit
+    pub excess_tokens: u32,
+}
+
+impl From<PromptOverflow> for PromptOverflowDto {
+    fn from(overflow: PromptOverflow) -> Self {
+        Self {
+            prompt_tokens: overflow.prompt_tokens,
+            max_output_tokens: overflow.max_output_tokens,
+            context_window: overflow.context_window,
+            excess_tokens: overflow.excess_tokens(),
+        }
+    }
+}
+
+/// One line of the `application/x-ndjson` message stream
+///
+/// Lines arrive as `content` pieces, then `usage` and `final` on success, or
+/// a single `error` that ends the stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamLine {
+    /// Next piece of the assistant reply
+    Content { content: String },
+    /// Size of the reply (token counts are recorded for usage analytics)
+    Usage { chunks: u64, characters: u64 },
+    /// Reply complete and saved
+    Final,
+    /// Generation failed
+    Error { error: String },
+}
+
+/// Message a client sends over the chat WebSocket
+///
+/// One socket serves any number of the user's sessions; every message names
+/// the session it is about.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsClientMessage {
+    /// Send a message and stream the reply (same fields as
+    /// [`SendMessageRequest`])
+    Send {
+        session_id: Uuid,
+        content: String,
+        #[serde(default)]
+        model_id: Option<String>,
+        #[serde(default)]
+        temperature: Option<f32>,
+    },
+    /// Stop the reply being generated; what was generated so far is saved
+    Cancel { session_id: Uuid },
+    /// The user started or stopped typing, relayed to the user's other
+    /// sockets
+    Typing { session_id: Uuid, typing: bool },
+}
+
+/// Who is typing in a [`WsServerMessage::Typing`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TypingRole {
+    /// The user, on another of their sockets
+    User,
+    /// The model, while a reply is generated
+    Assistant,
+}
+
+/// Message the server sends over the chat WebSocket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsServerMessage {
+    /// Someone started or stopped typing in the session
+    Typing {
+        session_id: Uuid,
+        role: TypingRole,
+        typing: bool,
+    },
+    /// Next piece of the assistant reply
+    Chunk { session_id: Uuid, content: String },
+    /// Reply complete and saved
+    Done { session_id: Uuid },
+    /// Reply stopped by a `cancel`; the partial reply is saved
+    Cancelled { session_id: Uuid },
+    /// A client message was rejected or generation failed
+    ///
+    /// `status` is the HTTP status the same failure has on the REST
+    /// endpoints; `session_id` is absent for messages that could not be read.
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        session_id: Option<Uuid>,
+        status: u16,
+        error: String,
+    },
+}
+
+/// Request to create a public share link
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CreateShareRequest {
+    /// Link lifetime in seconds; never expires if omitted
+    #[serde(default)]
+    pub expires_in_secs: Option<u64>,
+    /// Password viewers must send in the `X-Share-Password` header
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Share link details (owner view)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ShareDto {
+    /// Share ID
+    pub id: Uuid,
+    /// Shared session ID
+    pub session_id: Uuid,
+    /// Signed public identifier
+    pub slug: String,
+    /// Public path of the shared conversation
+    pub url: String,
+    /// Whether viewers need a password
+    pub password_protected: bool,
+    /// Expiry timestamp (never if absent)
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Revocation timestamp
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Set while the owner's account is disabled (the link is unavailable)
+    pub suspended_at: Option<DateTime<Utc>>,
+    /// Number of public views
+    pub view_count: u64,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ChatShare> for ShareDto {
+    fn from(share: ChatShare) -> Self {
+        Self {
+            id: share.id,
+            session_id: share.session_id,
+            url: format!("/api/v1/chat/shared/{}", share.slug),
+            password_protected: share.requires_password(),
+            slug: share.slug,
+            expires_at: share.expires_at,
+            revoked_at: share.revoked_at,
+            suspended_at: share.suspended_at,
+            view_count: share.view_count,
+            created_at: share.created_at,
+        }
+    }
+}
+
+/// Share links of a session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ListSharesResponse {
+    /// Shares, newest first
+    pub shares: Vec<ShareDto>,
+}
+
+/// Read-only conversation behind a public share link
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SharedConversationResponse {
+    /// Session title
+    pub title: String,
+    /// Session creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// Messages in chronological order
+    pub messages: Vec<MessageDto>,
+    /// Number of public views, including this one
+    pub view_count: u64,
+}
+
+impl From<SharedConversation> for SharedConversationResponse {
+    fn from(shared: SharedConversation) -> Self {
+        Self {
+            title: shared.session.title,
+            created_at: shared.session.created_at,
+            messages: shared.messages.into_iter().map(MessageDto::from).collect(),
+            view_count: shared.share.view_count,
+        }
+    }
+}
+
+/// Request to register a completion webhook
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// http(s) URL posted a signed payload when a reply completes
+    #[schema(example = "https://automation.example.com/hooks/chat")]
+    pub url: String,
+}
+
+/// Completion webhook details
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDto {
+    /// Webhook ID
+    pub id: Uuid,
+    /// URL the payloads are posted to
+    pub url: String,
+    /// Key of the `X-Webhook-Signature` HMAC, only returned on creation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// Time of the last delivery attempt
+    pub last_delivery_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last attempt (absent without a response)
+    pub last_status: Option<u16>,
+    /// Why the last attempt failed
+    pub last_error: Option<String>,
+}
+
+impl WebhookDto {
+    /// Details including the signing secret, for the creation response
+    #[must_use]
+    pub fn with_secret(webhook: ChatWebhook) -> Self {
+        let secret = webhook.secret.clone();
+        Self {
+            secret: Some(secret),
+            ..webhook.into()
+        }
+    }
+}
+
+impl From<ChatWebhook> for WebhookDto {
+    fn from(webhook: ChatWebhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            secret: None,
+            created_at: webhook.created_at,
+            last_delivery_at: webhook.last_delivery_at,
+            last_status: webhook.last_status,
+            last_error: webhook.last_error,
+        }
+    }
+}
+
+/// Completion webhooks of the user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ListWebhooksResponse {
+    /// Webhooks, oldest first
+    pub webhooks: Vec<WebhookDto>,
+}
+
+/// Outcome of a test delivery
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookTestResponse {
+    /// Whether the receiver answered with a 2xx status
+    pub delivered: bool,
+    /// HTTP status of the response (absent without a response)
+    pub status: Option<u16>,
+    /// Why the delivery failed
+    pub error: Option<String>,
+    /// Time of the attempt
+    pub attempted_at: DateTime<Utc>,
+}
+
+impl From<WebhookDelivery> for WebhookTestResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            delivered: delivery.is_success(),
+            status: delivery.status,
+            error: delivery.error,
+            attempted_at: delivery.attempted_at,
+        }
+    }
+}
+
+/// Query parameters for the chat analytics endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ChatAnalyticsQuery {
+    /// Number of days to cover, today included (1-365)
+    #[serde(default = "default_analytics_days")]
+    pub days: u32,
+}
+
+const fn default_analytics_days() -> u32 {
+    DEFAULT_ANALYTICS_DAYS
+}
+
+/// Replies on one UTC day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DailyMessagesDto {
+    pub date: NaiveDate,
+    pub messages: u64,
+}
+
+/// Tokens used with one model
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModelTokensDto {
+    pub model: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Replies in one session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SessionActivityDto {
+    pub session_id: Uuid,
+    pub title: String,
+    pub messages: u64,
+}
+
+/// The current user's chat usage over a period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChatAnalyticsResponse {
+    /// Number of days covered
+    pub days: u32,
+    /// Start of the period (UTC midnight of the first day)
+    pub since: DateTime<Utc>,
+    /// Replies per day, oldest first, including days without any
+    pub messages_per_day: Vec<DailyMessagesDto>,
+    /// Token usage per model, most tokens first
+    pub tokens_per_model: Vec<ModelTokensDto>,
+    /// Sessions with the most replies, most active first
+    pub most_active_sessions: Vec<SessionActivityDto>,
+    /// Mean time to the first reply chunk (absent without replies)
+    pub average_latency_ms: Option<f64>,
+}
+
+impl From<UsageAnalytics> for ChatAnalyticsResponse {
+    fn from(analytics: UsageAnalytics) -> Self {
+        Self {
+            days: analytics.days,
+            since: analytics.since,
+            messages_per_day: analytics
+                .messages_per_day
+                .into_iter()
+                .map(|day| DailyMessagesDto {
+                    date: day.date,
+                    messages: day.messages,
+                })
+                .collect(),
+            tokens_per_model: analytics
+                .tokens_per_model
+                .into_iter()
+                .map(|usage| ModelTokensDto {
+                    total_tokens: usage.prompt_tokens + usage.completion_tokens,
+                    model: usage.model,
+                    prompt_tokens: usage.prompt_tokens,
+                    completion_tokens: usage.completion_tokens,
+                })
+                .collect(),
+            most_active_sessions: analytics
+                .most_active_sessions
+                .into_iter()
+                .map(|session| SessionActivityDto {
+                    session_id: session.session_id,
+                    title: session.title,
+                    messages: session.messages,
+                })
+                .collect(),
+            average_latency_ms: analytics.average_latency_ms,
+        }
+    }
+}
+
+/// Model information for API response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ModelInfo {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub description: Option<String>,
+    pub context_window: u32,
+    pub max_output_tokens: u32,
+    pub supports_streaming: bool,
+    pub supports_function_calling: bool,
+    pub cost_per_million_input_tokens: f64,
+    pub cost_per_million_output_tokens: f64,
+    pub tags: Vec<String>,
+    pub recommended_for: Vec<String>,
+}
+
+/// Model group information
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ModelGroupInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub models: Vec<String>,
+}
+
+/// API response with models and groups
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListModelsResponse {
+    pub models: Vec<ModelInfo>,
+    pub groups: Vec<ModelGroupInfo>,
+    pub default_model: String,
+}
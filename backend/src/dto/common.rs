@@ -0,0 +1,18 @@
+//! Generic response envelopes shared across all API areas.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Error body returned by failing endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Human-readable error message
+    pub error: String,
+}
+
+/// Generic confirmation message.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MessageResponse {
+    /// Human-readable confirmation message
+    pub message: String,
+}
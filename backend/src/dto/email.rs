@@ -0,0 +1,54 @@
+//! Data Transfer Objects for email preferences
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::email_digest_subscriptions;
+
+/// The current user's weekly digest preference
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DigestPreferenceResponse {
+    /// Whether the weekly digest is sent
+    pub enabled: bool,
+    /// When the last digest was sent, if ever
+    pub last_sent_at: Option<DateTime<Utc>>,
+}
+
+impl From<email_digest_subscriptions::Model> for DigestPreferenceResponse {
+    fn from(subscription: email_digest_subscriptions::Model) -> Self {
+        Self {
+            enabled: subscription.enabled,
+            last_sent_at: subscription.last_sent_at.map(|at| at.with_timezone(&Utc)),
+        }
+    }
+}
+
+/// Request to turn the weekly digest on or off
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateDigestPreferenceRequest {
+    pub enabled: bool,
+}
+
+/// Query parameters of the one-click unsubscribe link
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UnsubscribeQuery {
+    /// Token from the unsubscribe link
+    pub token: String,
+}
+
+/// Query parameters of the bounce webhook
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct BounceWebhookQuery {
+    /// Shared secret configured as `EMAIL_BOUNCE_WEBHOOK_SECRET`
+    pub token: String,
+}
+
+/// Outcome of a bounce or complaint report
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BounceWebhookResponse {
+    /// Recipients now suppressed (hard bounces and complaints)
+    pub suppressed: usize,
+    /// Recipients left alone (soft bounces)
+    pub ignored: usize,
+}
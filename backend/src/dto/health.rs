@@ -0,0 +1,126 @@
+//! Health check response types.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::services::schema_version::SchemaStatus;
+use crate::services::valkey::provider_health::ProviderProbe;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct HealthResponse {
+    /// Health status of the service
+    #[schema(example = "healthy")]
+    pub status: String,
+
+    /// Subsystems enabled on this deployment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modules: Option<ActiveModules>,
+
+    /// Latest LLM provider probes (readiness only, when chat is critical)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub providers: Option<Vec<ProviderHealth>>,
+
+    /// Applied migrations against this binary's (readiness only, unless
+    /// `SCHEMA_VERSION_CHECK=off`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<SchemaHealth>,
+}
+
+/// Status of every dependency, for dashboards and load balancers
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct DetailedHealthResponse {
+    /// `healthy`, `degraded` (a dependency chat or caching needs is failing)
+    /// or `unhealthy` (requests cannot be served)
+    #[schema(example = "healthy")]
+    pub status: String,
+
+    /// Postgres round trip
+    pub database: DependencyHealth,
+
+    /// Valkey `PING` (absent when Valkey is not configured)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valkey: Option<DependencyHealth>,
+
+    /// Latest LLM provider probes (absent when providers are not probed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub providers: Option<Vec<ProviderHealth>>,
+}
+
+/// Result of checking one dependency
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct DependencyHealth {
+    pub healthy: bool,
+    /// Time until the dependency answered or the check gave up
+    pub latency_ms: u64,
+    /// Failure reason, if any
+    pub error: Option<String>,
+}
+
+/// Database schema version as seen by this binary
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct SchemaHealth {
+    /// `up_to_date`, `behind` (pending migrations) or `ahead` (applied
+    /// migrations unknown to this binary)
+    #[schema(example = "up_to_date")]
+    pub state: String,
+    /// Migrations this binary was built with
+    pub expected_migrations: usize,
+    /// Migrations recorded in the database
+    pub applied_migrations: usize,
+    /// Migrations not applied yet
+    pub pending: Vec<String>,
+    /// Applied migrations this binary does not know
+    pub unknown: Vec<String>,
+}
+
+impl From<SchemaStatus> for SchemaHealth {
+    fn from(status: SchemaStatus) -> Self {
+        Self {
+            state: status.state().to_string(),
+            expected_migrations: status.expected,
+            applied_migrations: status.applied,
+            pending: status.pending,
+            unknown: status.unknown,
+        }
+    }
+}
+
+/// Result of the latest synthetic probe of an LLM provider
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct ProviderHealth {
+    #[schema(example = "sambanova")]
+    pub provider: String,
+    /// Model used for the probe
+    pub model: String,
+    pub healthy: bool,
+    pub latency_ms: u64,
+    /// Failure reason, if any
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl From<ProviderProbe> for ProviderHealth {
+    fn from(probe: ProviderProbe) -> Self {
+        Self {
+            provider: probe.provider,
+            model: probe.model,
+            healthy: probe.healthy,
+            latency_ms: probe.latency_ms,
+            error: probe.error,
+            checked_at: probe.checked_at,
+        }
+    }
+}
+
+/// Optional subsystems and whether this deployment runs them
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct ActiveModules {
+    /// Chat sessions and LLM completions (`/api/v1/chat`)
+    pub chat: bool,
+    /// Admin user management (`/api/v1/admin`)
+    pub admin_api: bool,
+    /// Verification emails (`/api/v1/auth/*verif*`)
+    pub email: bool,
+}
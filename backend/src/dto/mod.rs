@@ -0,0 +1,52 @@
+//! Shared Data Transfer Objects for the HTTP API.
+//!
+//! This module is the single home for every request/response type exchanged
+//! over HTTP. Handlers, the `OpenAPI` document, and API clients all reference
+//! these types so that the wire format is defined exactly once.
+//!
+//! # Modules
+//!
+//! - **common**: Generic envelopes shared by all endpoints (`ErrorResponse`, `MessageResponse`)
+//! - **auth**: Registration, login, token and email verification payloads
+//! - **admin**: Admin user management payloads and query parameters
+//! - **branding**: White-label branding payloads
+//! - **chat**: Chat session, message and model catalog payloads
+//! - **email**: Email digest preference and unsubscribe payloads
+//! - **health**: Health check payloads
+//! - **notifications**: User notification inbox payloads
+//! - **preferences**: User preference payloads (also the stored JSONB document)
+//! - **tokenizer**: Token counting payloads
+//!
+//! # Conventions
+//!
+//! - Every type derives `serde` traits for the direction it travels
+//! - Every type derives `utoipa::ToSchema` (or `IntoParams` for query strings)
+//! - Types contain no business logic; validation lives with the handlers
+//! - Field names are `snake_case` on the wire (the serde default for Rust
+//!   fields): `rename_all` only renames enum variants (values), and there
+//!   are no maps keyed by client data, so the `/api/v2` `camelCase` API can
+//!   rename every key (see [`crate::middleware::json_case`])
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use cobalt_stack_backend::dto::{auth::LoginRequest, MessageResponse};
+//!
+//! let req: LoginRequest = serde_json::from_str(
+//!     r#"{"username_or_email":"alice","password":"SecurePass123!"}"#,
+//! ).unwrap();
+//! let ok = MessageResponse { message: "done".to_string() };
+//! ```
+
+pub mod admin;
+pub mod auth;
+pub mod branding;
+pub mod chat;
+pub mod common;
+pub mod email;
+pub mod health;
+pub mod notifications;
+pub mod preferences;
+pub mod tokenizer;
+
+pub use common::{ErrorResponse, MessageResponse};
@@ -0,0 +1,40 @@
+//! Data Transfer Objects for user notifications
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::services::valkey::notifications::Notification;
+
+/// A notification in the user's inbox
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationDto {
+    /// Notification ID
+    pub id: Uuid,
+    /// Notification category
+    #[schema(example = "quota_warning")]
+    pub kind: String,
+    /// Human-readable message
+    #[schema(example = "You have used 80 of 100 daily chat messages (80% of your quota).")]
+    pub message: String,
+    /// When the notification was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Notification> for NotificationDto {
+    fn from(notification: Notification) -> Self {
+        Self {
+            id: notification.id,
+            kind: notification.kind,
+            message: notification.message,
+            created_at: notification.created_at,
+        }
+    }
+}
+
+/// Notifications for the current user, newest first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<NotificationDto>,
+}
@@ -0,0 +1,110 @@
+//! Data Transfer Objects for user preferences
+//!
+//! [`UserPreferences`] is also the document stored in the
+//! `user_preferences.preferences` JSONB column, so every field has a default
+//! and older documents stay readable when keys are added.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+
+/// UI color scheme
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    /// Follow the operating system setting
+    #[default]
+    System,
+}
+
+/// Which notifications the user wants to receive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    /// Notify when approaching the daily chat quota
+    pub quota_warnings: bool,
+    /// Show notifications in the app
+    pub in_app: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            quota_warnings: true,
+            in_app: true,
+        }
+    }
+}
+
+/// A user's application preferences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct UserPreferences {
+    pub theme: Theme,
+    /// Model preselected for new chat sessions and used for messages that
+    /// name no model (`None` = deployment default)
+    #[schema(example = "Llama-4-Maverick-17B-128E-Instruct")]
+    pub default_model: Option<String>,
+    /// Sampling temperature (0-2) for messages that set none
+    /// (`None` = model default)
+    #[schema(example = 0.7)]
+    pub temperature: Option<f32>,
+    /// Instructions sent to the model as a system message ahead of every
+    /// conversation
+    #[schema(example = "You are a concise assistant. Answer in plain English.")]
+    pub persona: Option<String>,
+    /// BCP 47 language tag
+    #[schema(example = "en")]
+    pub language: String,
+    pub notifications: NotificationPreferences,
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            default_model: None,
+            temperature: None,
+            persona: None,
+            language: "en".to_string(),
+            notifications: NotificationPreferences::default(),
+        }
+    }
+}
+
+/// Partial update of [`NotificationPreferences`]
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateNotificationPreferences {
+    pub quota_warnings: Option<bool>,
+    pub in_app: Option<bool>,
+}
+
+/// Partial update of [`UserPreferences`]; omitted keys are left unchanged
+///
+/// Unknown keys are rejected. An empty `default_model` or `persona` and a
+/// `null` `temperature` reset them to the deployment defaults.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdatePreferencesRequest {
+    pub theme: Option<Theme>,
+    pub default_model: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<f32>)]
+    #[allow(clippy::option_option)]
+    pub temperature: Option<Option<f32>>,
+    pub persona: Option<String>,
+    pub language: Option<String>,
+    pub notifications: Option<UpdateNotificationPreferences>,
+}
+
+/// Tell a key sent as `null` (`Some(None)`) from an omitted one (`None`)
+#[allow(clippy::option_option)]
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
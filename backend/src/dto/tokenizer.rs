@@ -0,0 +1,28 @@
+//! Data Transfer Objects for token counting
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Request to count the tokens of a text
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CountTokensRequest {
+    /// Text to tokenize
+    #[schema(example = "How many tokens is this?")]
+    pub text: String,
+    /// Model whose tokenizer to use (defaults to `cl100k_base`)
+    #[serde(default)]
+    #[schema(example = "gpt-4o")]
+    pub model: Option<String>,
+}
+
+/// Token count of a text
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CountTokensResponse {
+    /// Model from the request, if any
+    pub model: Option<String>,
+    /// Encoding the text was tokenized with
+    #[schema(example = "o200k_base")]
+    pub encoding: String,
+    /// Number of tokens
+    pub tokens: usize,
+}
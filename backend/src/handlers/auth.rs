@@ -1,99 +1,95 @@
+use crate::dto::auth::{
+    AuthResponse, ChangePasswordRequest, ConfirmDeviceRequest, LoginRequest, RegisterRequest,
+    SendVerificationResponse, SessionListResponse, SessionResponse, TrustedDeviceListResponse,
+    TrustedDeviceResponse, UpdateProfileRequest, UserResponse, VerifyEmailRequest,
+};
+use crate::dto::{ErrorResponse, MessageResponse};
 use crate::services::auth::{AuthError, Result};
-use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
-use uuid::Uuid;
 
 // ============================================================================
-// DTOs (Data Transfer Objects)
+// Validation
 // ============================================================================
 
-#[derive(Debug, Deserialize, ToSchema)]
-pub struct RegisterRequest {
-    #[schema(example = "alice")]
-    pub username: String,
-
-    #[schema(example = "alice@example.com")]
-    pub email: String,
-
-    #[schema(example = "SecurePass123!")]
-    pub password: String,
-}
-
-#[derive(Debug, Deserialize, ToSchema)]
-pub struct LoginRequest {
-    /// Username or email address
-    #[schema(example = "alice")]
-    pub username_or_email: String,
-
-    #[schema(example = "SecurePass123!")]
-    pub password: String,
+impl RegisterRequest {
+    pub fn validate(&self) -> Result<()> {
+        validate_username(&self.username)?;
+        validate_email(&self.email)?;
+        validate_new_password(&self.password)
+    }
 }
 
-#[derive(Debug, Serialize, ToSchema)]
-pub struct AuthResponse {
-    pub access_token: String,
-    pub token_type: String,
-    pub expires_in: i64,
+impl UpdateProfileRequest {
+    pub fn validate(&self) -> Result<()> {
+        if self.username.is_none() && self.email.is_none() {
+            return Err(AuthError::InvalidInput("Nothing to update".to_string()).into());
+        }
+        if let Some(username) = &self.username {
+            validate_username(username)?;
+        }
+        if let Some(email) = &self.email {
+            validate_email(email)?;
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Serialize, ToSchema)]
-pub struct UserResponse {
-    #[schema(value_type = String, example = "550e8400-e29b-41d4-a716-446655440000")]
-    pub id: Uuid,
-    pub username: String,
-    pub email: String,
-    pub email_verified: bool,
-    pub role: crate::models::sea_orm_active_enums::UserRole,
+pub fn validate_username(username: &str) -> Result<()> {
+    if username.is_empty() {
+        return Err(AuthError::InvalidInput("Username cannot be empty".to_string()).into());
+    }
+    if username.len() < 3 || username.len() > 50 {
+        return Err(AuthError::InvalidInput(
+            "Username must be between 3 and 50 characters".to_string(),
+        )
+        .into());
+    }
+    Ok(())
 }
 
-#[derive(Debug, Serialize, ToSchema)]
-pub struct ErrorResponse {
-    pub error: String,
+/// Basic email format check
+pub fn validate_email(email: &str) -> Result<()> {
+    if email.is_empty() {
+        return Err(AuthError::InvalidInput("Email cannot be empty".to_string()).into());
+    }
+    if !email.contains('@') {
+        return Err(AuthError::InvalidInput("Invalid email format".to_string()).into());
+    }
+    Ok(())
 }
 
-// ============================================================================
-// Validation
-// ============================================================================
-
-impl RegisterRequest {
+impl ChangePasswordRequest {
     pub fn validate(&self) -> Result<()> {
-        // Username validation
-        if self.username.is_empty() {
-            return Err(AuthError::InvalidInput("Username cannot be empty".to_string()).into());
-        }
-        if self.username.len() < 3 || self.username.len() > 50 {
-            return Err(AuthError::InvalidInput(
-                "Username must be between 3 and 50 characters".to_string(),
-            )
-            .into());
-        }
-
-        // Email validation (basic)
-        if self.email.is_empty() {
-            return Err(AuthError::InvalidInput("Email cannot be empty".to_string()).into());
-        }
-        if !self.email.contains('@') {
-            return Err(AuthError::InvalidInput("Invalid email format".to_string()).into());
-        }
-
-        // Password validation
-        if self.password.len() < 8 {
-            return Err(AuthError::InvalidInput(
-                "Password must be at least 8 characters".to_string(),
-            )
-            .into());
+        if self.current_password.is_empty() {
+            return Err(
+                AuthError::InvalidInput("Current password cannot be empty".to_string()).into(),
+            );
         }
-        if self.password.len() > 128 {
+        validate_new_password(&self.new_password)?;
+        if self.new_password == self.current_password {
             return Err(AuthError::InvalidInput(
-                "Password must not exceed 128 characters".to_string(),
+                "New password must differ from the current password".to_string(),
             )
             .into());
         }
-
         Ok(())
     }
 }
 
+/// Length rules for a password being set
+fn validate_new_password(password: &str) -> Result<()> {
+    if password.len() < 8 {
+        return Err(
+            AuthError::InvalidInput("Password must be at least 8 characters".to_string()).into(),
+        );
+    }
+    if password.len() > 128 {
+        return Err(
+            AuthError::InvalidInput("Password must not exceed 128 characters".to_string()).into(),
+        );
+    }
+    Ok(())
+}
+
 impl LoginRequest {
     pub fn validate(&self) -> Result<()> {
         if self.username_or_email.is_empty() {
@@ -112,27 +108,88 @@ impl LoginRequest {
 // Handlers
 // ============================================================================
 
-use crate::models::{prelude::*, users};
+use crate::application::account::{ChatQuotaSource, GetCurrentUserUseCase};
+use crate::config::{SandboxConfig, VerificationLinkConfig};
+use crate::domain::ids::{TokenId, UserId};
+use crate::dto::health::ActiveModules;
+use crate::middleware::auth::{AuthUser, DpopProof};
+use crate::middleware::client_ip::ClientIp;
+use crate::middleware::current_user::{CurrentUser, UserLoader};
+use crate::models::{prelude::*, sea_orm_active_enums::UserRole, users};
+use crate::services::audit::{self, AuditEntry, AuditEvent};
+use crate::services::auth::token_binding::{
+    fingerprint, new_device_id, DEVICE_COOKIE, DEVICE_COOKIE_MAX_AGE_DAYS,
+};
+use crate::services::auth::trusted_devices::{
+    self, device_hash, is_trusted_device, request_device_confirmation, trust_device,
+};
 use crate::services::auth::{
-    create_access_token, create_refresh_token, hash_password, store_refresh_token, verify_password,
-    JwtConfig,
+    create_access_token, create_bound_access_token, create_bound_refresh_token,
+    create_password_change_token, create_refresh_token, hash_password, list_active_sessions,
+    login_metrics::PASSWORD_METHOD, password_status, store_refresh_token, verify_password,
+    DeviceClient, JwtConfig, LoginMetrics, PasswordExpiryPolicy, PasswordStatus, TokenClient,
+    TokenStore, TrustedDevicePolicy,
+};
+use crate::services::email::login_alert::{
+    is_new_device, render_device_confirmation, render_new_login, render_sessions_revoked,
 };
+use crate::services::email::{suppression::SuppressionList, EmailSender};
+use crate::services::runtime_switches::RuntimeSwitches;
+use crate::services::sandbox;
+use crate::utils::user_agent::{self, device_label};
 use axum::{
-    extract::State,
-    http::{header, StatusCode},
+    extract::{FromRef, Path, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
-use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
 use std::sync::Arc;
 
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
+    /// Row of the authenticated user, read once per request (see [`CurrentUser`])
+    pub users: UserLoader,
     pub jwt_config: JwtConfig,
+    /// Where refresh tokens are kept (database or Valkey, per `REFRESH_TOKEN_STORE`)
+    pub token_store: Arc<dyn TokenStore>,
+    /// Email backend (`None` when email is disabled via `FEATURE_EMAIL_ENABLED`)
+    pub email_sender: Option<Arc<dyn EmailSender + Send + Sync>>,
+    /// Addresses mail is not sent to (`None` when email is disabled)
+    pub email_suppressions: Option<Arc<SuppressionList>>,
+    /// Subsystems enabled on this deployment, used to derive permissions
+    pub modules: ActiveModules,
+    /// Bind refresh tokens to the client, per `REFRESH_TOKEN_BINDING_ENABLED`
+    pub bind_refresh_tokens: bool,
+    /// Daily chat quota reported by `/auth/me` (`None` when chat is disabled)
+    pub chat_quota: Option<ChatQuotaSource>,
+    /// Maximum password age (`None` when passwords never expire)
+    pub password_expiry: Option<PasswordExpiryPolicy>,
+    /// Email confirmation of new devices (`None` when `TRUSTED_DEVICES_ENABLED`
+    /// is off or email is disabled)
+    pub trusted_devices: Option<TrustedDevicePolicy>,
+    /// Expiry of new accounts and the banner of `/auth/me` (`None` unless
+    /// `SANDBOX_MODE_ENABLED`)
+    pub sandbox: Option<SandboxConfig>,
+    /// Registration and read-only switches set by admins
+    pub switches: Arc<RuntimeSwitches>,
+    /// Verification links per client
+    pub verification_links: Arc<VerificationLinkConfig>,
+    /// Sign-in counters, rendered at `/metrics`
+    pub login_metrics: Arc<LoginMetrics>,
+}
+
+impl FromRef<AppState> for UserLoader {
+    fn from_ref(state: &AppState) -> Self {
+        state.users.clone()
+    }
 }
 
 /// POST /api/auth/register - Register a new user
@@ -146,14 +203,24 @@ pub struct AppState {
     responses(
         (status = 200, description = "User registered successfully", body = AuthResponse),
         (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 403, description = "Registration is closed", body = ErrorResponse),
         (status = 409, description = "User already exists", body = ErrorResponse),
     ),
     tag = "Authentication"
 )]
 pub async fn register(
     State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    dpop: DpopProof,
+    jar: CookieJar,
     Json(req): Json<RegisterRequest>,
 ) -> std::result::Result<impl IntoResponse, AuthError> {
+    let key = dpop.verify(&state.jwt_config)?;
+    if !state.switches.registration_open() {
+        return Err(AuthError::RegistrationClosed);
+    }
+
     // Validate input
     req.validate().map_err(|e| {
         // The validate() function already returns AuthError wrapped in anyhow::Error
@@ -187,46 +254,85 @@ pub async fn register(
 
     // Create user
     let user = users::ActiveModel {
+        id: Set(uuid::Uuid::new_v4()),
         username: Set(req.username.clone()),
         email: Set(req.email.clone()),
         password_hash: Set(Some(password_hash)),
+        role: Set(UserRole::User),
         email_verified: Set(false),
+        password_rotation_required: Set(false),
         created_at: Set(Utc::now().into()),
         updated_at: Set(Utc::now().into()),
         ..Default::default()
     };
 
-    let user = user.insert(state.db.as_ref()).await?;
-
-    // Send verification email
-    {
-        use crate::services::email::{create_verification_token, EmailSender, MockEmailSender};
-
-        // Create verification token
-        let token = create_verification_token(state.db.as_ref(), user.id)
+    // A sandbox account is created together with its expiry
+    let txn = state.db.begin().await?;
+    let user = user.insert(&txn).await?;
+    if let Some(sandbox) = &state.sandbox {
+        sandbox::enroll(&txn, user.id, sandbox).await?;
+    }
+    txn.commit().await?;
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.registered",
+        user_id = %user.id,
+        ip = %client_ip,
+        "User registered"
+    );
+
+    // Send verification email (skipped when email is disabled)
+    if let Some(email_sender) = &state.email_sender {
+        use crate::services::email::create_verification_token;
+
+        // Create verification token, remembering the client the link is for
+        let client = state.verification_links.client(req.client.as_deref());
+        let token = create_verification_token(state.db.as_ref(), user.id, client)
             .await
             .map_err(|e| AuthError::DatabaseError(format!("Failed to create token: {e}")))?;
 
         // Send verification email
-        let email_sender = MockEmailSender;
         email_sender
-            .send_verification_email(&user.email, &token)
+            .send_verification_email(&user.email, &state.verification_links.url(client, &token))
             .map_err(|_| AuthError::InternalError)?;
     }
 
     // Generate tokens
-    let access_token = create_access_token(user.id, user.username.clone(), &state.jwt_config)
-        .map_err(|_| AuthError::JwtEncodingError)?;
-    let (refresh_token, refresh_jti) = create_refresh_token(user.id, &state.jwt_config)
-        .map_err(|_| AuthError::JwtEncodingError)?;
+    let user_agent = user_agent::from_headers(&headers);
+    let response = issue_access_token(&state, &user, key.as_deref())?;
+    let (refresh_token, refresh_jti) = issue_refresh_token(&state, user.id.into(), key)?;
+
+    // The device the account was created on is trusted right away
+    let jar = if state.trusted_devices.is_some() {
+        let (jar, device_id) = device_cookie(jar);
+        let ip = client_ip.0.map(|ip| ip.to_string());
+        let client = DeviceClient {
+            user_agent: user_agent.as_deref(),
+            ip: ip.as_deref(),
+        };
+        trust_device(state.db.as_ref(), user.id, &device_id, client)
+            .await
+            .map_err(|e| AuthError::DatabaseError(format!("Failed to trust device: {e}")))?;
+        jar
+    } else {
+        jar
+    };
 
-    // Store refresh token in database
+    // Store refresh token
+    let (jar, fingerprint) = bind_client(&state, jar, user_agent.as_deref());
+    let ip = client_ip.0.map(|ip| ip.to_string());
     store_refresh_token(
-        state.db.as_ref(),
-        user.id,
+        state.token_store.as_ref(),
+        user.id.into(),
         &refresh_token,
         refresh_jti,
         state.jwt_config.refresh_token_expiry_days,
+        TokenClient {
+            user_agent: user_agent.as_deref(),
+            fingerprint: fingerprint.as_deref(),
+            ip: ip.as_deref(),
+        },
     )
     .await
     .map_err(|_| AuthError::DatabaseError("Failed to store refresh token".to_string()))?;
@@ -243,17 +349,7 @@ pub async fn register(
         .build();
 
     // Return response with cookie
-    let response = AuthResponse {
-        access_token,
-        token_type: "Bearer".to_string(),
-        expires_in: state.jwt_config.access_token_expiry_minutes * 60,
-    };
-
-    Ok((
-        StatusCode::OK,
-        [(header::SET_COOKIE, cookie.to_string())],
-        Json(response),
-    ))
+    Ok((StatusCode::OK, jar.add(cookie), Json(response)))
 }
 
 /// POST /api/auth/login - Login with username/password
@@ -274,8 +370,14 @@ pub async fn register(
 )]
 pub async fn login(
     State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    dpop: DpopProof,
+    jar: CookieJar,
     Json(req): Json<LoginRequest>,
 ) -> std::result::Result<impl IntoResponse, AuthError> {
+    let key = dpop.verify(&state.jwt_config)?;
+
     // Validate input
     req.validate().map_err(|e| {
         // The validate() function already returns AuthError wrapped in anyhow::Error
@@ -284,43 +386,112 @@ pub async fn login(
             .unwrap_or_else(|_| AuthError::InvalidInput("Validation failed".to_string()))
     })?;
 
-    // Find user by username or email
-    let user = Users::find()
-        .filter(
-            users::Column::Username
-                .eq(&req.username_or_email)
-                .or(users::Column::Email.eq(&req.username_or_email)),
-        )
-        .one(state.db.as_ref())
-        .await?
-        .ok_or(AuthError::InvalidCredentials)?;
+    let user = match verify_credentials(state.db.as_ref(), &req).await {
+        Ok(user) => user,
+        Err(e) => {
+            state.login_metrics.record(PASSWORD_METHOD, false);
+            tracing::warn!(
+                target: "audit",
+                action = "auth.login_failed",
+                ip = %client_ip,
+                error = %e,
+                "Failed login attempt"
+            );
+            audit::record(
+                state.db.as_ref(),
+                AuditEntry::new(AuditEvent::LoginFailed)
+                    .client(client_ip, &headers)
+                    .details(serde_json::json!({ "username_or_email": req.username_or_email })),
+            )
+            .await;
+            return Err(e);
+        }
+    };
 
-    // Verify password
-    let password_hash = user.password_hash.ok_or(AuthError::InvalidCredentials)?;
-    let is_valid = verify_password(&req.password, &password_hash)
-        .map_err(|_| AuthError::InvalidCredentials)?;
+    state.login_metrics.record(PASSWORD_METHOD, true);
+    tracing::info!(
+        target: "audit",
+        action = "auth.login",
+        user_id = %user.id,
+        ip = %client_ip,
+        "User logged in"
+    );
+    audit::record(
+        state.db.as_ref(),
+        AuditEntry::new(AuditEvent::LoginSucceeded)
+            .actor(user.id)
+            .client(client_ip, &headers)
+            .details(serde_json::json!({ "method": "password" })),
+    )
+    .await;
 
-    if !is_valid {
-        return Err(AuthError::InvalidCredentials);
+    let (jar, response) = complete_sign_in(&state, jar, &headers, client_ip, &user, key).await?;
+    Ok((StatusCode::OK, jar, Json(response)))
+}
+
+/// Tokens for `user`, whose identity has been verified
+///
+/// Shared by password and social login: alerts about a new device, withholds
+/// the refresh token from untrusted devices and sets the refresh cookie.
+/// Tokens are bound to `key`, the thumbprint of the client's `DPoP` key, if
+/// set.
+pub async fn complete_sign_in(
+    state: &AppState,
+    jar: CookieJar,
+    headers: &HeaderMap,
+    client_ip: ClientIp,
+    user: &users::Model,
+    key: Option<String>,
+) -> std::result::Result<(CookieJar, AuthResponse), AuthError> {
+    // Sessions before this login, to recognize the device
+    let user_agent = user_agent::from_headers(headers);
+    let notify_new_device = if state.email_sender.is_some() && user.email_verified {
+        match list_active_sessions(state.token_store.as_ref(), user.id.into()).await {
+            Ok(sessions) => is_new_device(&sessions, user_agent.as_deref()),
+            Err(e) => {
+                tracing::warn!("Failed to load sessions for new device check: {}", e);
+                false
+            }
+        }
+    } else {
+        false
+    };
+
+    // Generate tokens (restricted to a password change once expired)
+    let mut response = issue_access_token(state, user, key.as_deref())?;
+
+    // An untrusted device gets no refresh token until it is confirmed
+    let (jar, trusted) =
+        check_trusted_device(state, jar, user, user_agent.as_deref(), client_ip).await?;
+    if !trusted {
+        response.device_confirmation_required = true;
+        return Ok((jar, response));
     }
 
-    // Generate tokens
-    let access_token = create_access_token(user.id, user.username.clone(), &state.jwt_config)
-        .map_err(|_| AuthError::JwtEncodingError)?;
-    let (refresh_token, refresh_jti) = create_refresh_token(user.id, &state.jwt_config)
-        .map_err(|_| AuthError::JwtEncodingError)?;
+    let (refresh_token, refresh_jti) = issue_refresh_token(state, user.id.into(), key)?;
 
-    // Store refresh token in database
+    // Store refresh token
+    let (jar, fingerprint) = bind_client(state, jar, user_agent.as_deref());
+    let ip = client_ip.0.map(|ip| ip.to_string());
     store_refresh_token(
-        state.db.as_ref(),
-        user.id,
+        state.token_store.as_ref(),
+        user.id.into(),
         &refresh_token,
         refresh_jti,
         state.jwt_config.refresh_token_expiry_days,
+        TokenClient {
+            user_agent: user_agent.as_deref(),
+            fingerprint: fingerprint.as_deref(),
+            ip: ip.as_deref(),
+        },
     )
     .await
     .map_err(|_| AuthError::DatabaseError("Failed to store refresh token".to_string()))?;
 
+    if notify_new_device {
+        send_new_login_alert(state, user, user_agent.as_deref(), client_ip);
+    }
+
     // Create HttpOnly cookie for refresh token
     let cookie = Cookie::build(("refresh_token", refresh_token))
         .http_only(true)
@@ -332,39 +503,298 @@ pub async fn login(
         ))
         .build();
 
-    // Return response with cookie
-    let response = AuthResponse {
+    Ok((jar.add(cookie), response))
+}
+
+/// Find the user by username or email and check the password
+async fn verify_credentials(
+    db: &DatabaseConnection,
+    req: &LoginRequest,
+) -> std::result::Result<users::Model, AuthError> {
+    let user = Users::find()
+        .filter(
+            users::Column::Username
+                .eq(&req.username_or_email)
+                .or(users::Column::Email.eq(&req.username_or_email)),
+        )
+        .one(db)
+        .await?
+        .ok_or(AuthError::InvalidCredentials)?;
+
+    let password_hash = user
+        .password_hash
+        .as_deref()
+        .ok_or(AuthError::InvalidCredentials)?;
+    let is_valid =
+        verify_password(&req.password, password_hash).map_err(|_| AuthError::InvalidCredentials)?;
+
+    if !is_valid {
+        return Err(AuthError::InvalidCredentials);
+    }
+
+    Ok(user)
+}
+
+/// Access token for `user`, with their password expiry status
+///
+/// Once the password has expired the token is only accepted by
+/// [`change_password`]. With a `key` thumbprint, the token is bound to the
+/// client's `DPoP` key.
+fn issue_access_token(
+    state: &AppState,
+    user: &users::Model,
+    key: Option<&str>,
+) -> std::result::Result<AuthResponse, AuthError> {
+    let status = password_status(user, state.password_expiry.as_ref(), Utc::now());
+    let password_expired = status == PasswordStatus::Expired;
+    let username = user.username.clone();
+    let access_token = match key {
+        Some(jkt) => create_bound_access_token(
+            user.id,
+            username,
+            password_expired,
+            jkt.to_string(),
+            &state.jwt_config,
+        ),
+        None if password_expired => {
+            create_password_change_token(user.id, username, &state.jwt_config)
+        }
+        None => create_access_token(user.id, username, &state.jwt_config),
+    }
+    .map_err(|_| AuthError::JwtEncodingError)?;
+
+    Ok(AuthResponse {
         access_token,
-        token_type: "Bearer".to_string(),
+        token_type: if key.is_some() { "DPoP" } else { "Bearer" }.to_string(),
         expires_in: state.jwt_config.access_token_expiry_minutes * 60,
+        password_expired,
+        password_change_deadline: match status {
+            PasswordStatus::Grace { deadline } => Some(deadline),
+            PasswordStatus::Current | PasswordStatus::Expired => None,
+        },
+        device_confirmation_required: false,
+    })
+}
+
+/// Refresh token for `user_id`, bound to the client's `DPoP` key if `key` is set
+fn issue_refresh_token(
+    state: &AppState,
+    user_id: UserId,
+    key: Option<String>,
+) -> std::result::Result<(String, TokenId), AuthError> {
+    key.map_or_else(
+        || create_refresh_token(user_id, &state.jwt_config),
+        |jkt| create_bound_refresh_token(user_id, jkt, &state.jwt_config),
+    )
+    .map_err(|_| AuthError::JwtEncodingError)
+}
+
+/// Email the user about a sign-in from an unrecognized device
+///
+/// Delivery failures are logged; they never fail the login.
+fn send_new_login_alert(
+    state: &AppState,
+    user: &users::Model,
+    user_agent: Option<&str>,
+    client_ip: ClientIp,
+) {
+    let Some(email_sender) = &state.email_sender else {
+        return;
     };
+    let result = render_new_login(
+        user,
+        &device_label(user_agent),
+        &client_ip.to_string(),
+        Utc::now(),
+    )
+    .and_then(|message| email_sender.send_email(&message));
+    if let Err(e) = result {
+        tracing::warn!(user_id = %user.id, "Failed to send new sign-in email: {}", e);
+    }
+}
 
-    Ok((
-        StatusCode::OK,
-        [(header::SET_COOKIE, cookie.to_string())],
-        Json(response),
-    ))
+/// Fingerprint to bind a new refresh token to, `None` when binding is disabled
+///
+/// A client without a device cookie is handed a new one, added to `jar`.
+fn bind_client(
+    state: &AppState,
+    jar: CookieJar,
+    user_agent: Option<&str>,
+) -> (CookieJar, Option<String>) {
+    if !state.bind_refresh_tokens {
+        return (jar, None);
+    }
+    let (jar, device_id) = device_cookie(jar);
+    let fingerprint = fingerprint(user_agent, Some(&device_id));
+    (jar, Some(fingerprint))
+}
+
+/// Device identifier of the client
+///
+/// A client without a device cookie is handed a new one, added to `jar`.
+fn device_cookie(jar: CookieJar) -> (CookieJar, String) {
+    if let Some(device_id) = jar.get(DEVICE_COOKIE) {
+        let device_id = device_id.value().to_string();
+        return (jar, device_id);
+    }
+
+    let device_id = new_device_id();
+    let cookie = Cookie::build((DEVICE_COOKIE, device_id.clone()))
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Strict)
+        .path("/")
+        .max_age(time::Duration::days(DEVICE_COOKIE_MAX_AGE_DAYS))
+        .build();
+    (jar.add(cookie), device_id)
+}
+
+/// Whether `user` signs in from a trusted device, always `true` when trusted
+/// devices are disabled
+///
+/// A client without a device cookie is handed a new one, added to `jar`. For
+/// an untrusted device a confirmation link is emailed to the user, unless
+/// one was sent for it moments ago.
+async fn check_trusted_device(
+    state: &AppState,
+    jar: CookieJar,
+    user: &users::Model,
+    user_agent: Option<&str>,
+    client_ip: ClientIp,
+) -> std::result::Result<(CookieJar, bool), AuthError> {
+    let (Some(policy), Some(email_sender)) = (&state.trusted_devices, &state.email_sender) else {
+        return Ok((jar, true));
+    };
+    let (jar, device_id) = device_cookie(jar);
+    let trusted = is_trusted_device(state.db.as_ref(), user.id, &device_id)
+        .await
+        .map_err(|e| AuthError::DatabaseError(format!("Failed to check device: {e}")))?;
+    if trusted {
+        return Ok((jar, true));
+    }
+
+    let device = device_label(user_agent);
+    let ip = client_ip.0.map(|ip| ip.to_string());
+    let client = DeviceClient {
+        user_agent,
+        ip: ip.as_deref(),
+    };
+    let now = Utc::now();
+    let sent = request_device_confirmation(
+        state.db.as_ref(),
+        user.id,
+        &device_id,
+        client,
+        policy.confirmation_ttl,
+        |token| {
+            let message = render_device_confirmation(
+                user,
+                &device,
+                &client_ip.to_string(),
+                now,
+                &policy.confirmation_url(token),
+                policy.confirmation_ttl.num_minutes(),
+            )?;
+            email_sender.send_email(&message)
+        },
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(user_id = %user.id, "Failed to send device confirmation: {}", e);
+        AuthError::InternalError
+    })?;
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.device_confirmation_requested",
+        user_id = %user.id,
+        ip = %client_ip,
+        device = %device,
+        email_sent = sent,
+        "Sign-in from an untrusted device"
+    );
+    Ok((jar, false))
+}
+
+/// Email the user after their refresh token was presented by another client
+///
+/// The store has already revoked the token's family. Failures are logged; the
+/// request is rejected either way.
+async fn handle_binding_mismatch(
+    state: &AppState,
+    user_id: uuid::Uuid,
+    user_agent: Option<&str>,
+    client_ip: ClientIp,
+) {
+    let Some(email_sender) = &state.email_sender else {
+        return;
+    };
+    let user = match Users::find_by_id(user_id).one(state.db.as_ref()).await {
+        Ok(Some(user)) if user.email_verified => user,
+        Ok(_) => return,
+        Err(e) => {
+            tracing::warn!(user_id = %user_id, "Failed to load user for sessions email: {}", e);
+            return;
+        }
+    };
+    let result = render_sessions_revoked(
+        &user,
+        &device_label(user_agent),
+        &client_ip.to_string(),
+        Utc::now(),
+    )
+    .and_then(|message| email_sender.send_email(&message));
+    if let Err(e) = result {
+        tracing::warn!(user_id = %user_id, "Failed to send sessions revoked email: {}", e);
+    }
+}
+
+/// Record the reuse of an already rotated refresh token in the audit log
+///
+/// The store has already revoked the token's family.
+async fn record_token_reuse(
+    state: &AppState,
+    user_id: UserId,
+    jti: TokenId,
+    client_ip: ClientIp,
+    headers: &HeaderMap,
+) {
+    audit::record(
+        state.db.as_ref(),
+        AuditEntry::new(AuditEvent::RefreshTokenReused)
+            .actor(user_id.into())
+            .client(client_ip, headers)
+            .details(serde_json::json!({ "jti": jti })),
+    )
+    .await;
 }
 
 /// POST /api/auth/refresh - Refresh access token using refresh token
 ///
-/// Rotates refresh token and returns new access token.
+/// Rotates refresh token and returns new access token. Presenting a token
+/// that was already rotated revokes every token rotated from the same
+/// sign-in and fails with "Refresh token reuse detected". With refresh token
+/// binding enabled, a token presented by another client than it was issued
+/// to revokes its family the same way.
 #[utoipa::path(
     post,
     path = "/api/v1/auth/refresh",
     responses(
         (status = 200, description = "Token refreshed", body = AuthResponse),
-        (status = 401, description = "Invalid or expired token", body = ErrorResponse),
+        (status = 401, description = "Invalid, expired or reused token", body = ErrorResponse),
     ),
     tag = "Authentication"
 )]
+#[allow(clippy::too_many_lines)]
 pub async fn refresh_token(
     State(state): State<AppState>,
-    jar: axum_extra::extract::CookieJar,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    dpop: DpopProof,
+    jar: CookieJar,
 ) -> std::result::Result<impl IntoResponse, AuthError> {
     use crate::services::auth::{
-        create_access_token, create_refresh_token, rotate_refresh_token, validate_refresh_token,
-        verify_refresh_token,
+        revoke_refresh_token, rotate_refresh_token, validate_refresh_token, verify_refresh_token,
     };
 
     // Extract refresh token from cookie
@@ -378,37 +808,112 @@ pub async fn refresh_token(
     let claims = verify_refresh_token(&old_refresh_token, &state.jwt_config)
         .map_err(|_| AuthError::InvalidToken)?;
 
-    // Validate token in database (checks revocation, expiry, hash match)
-    let user_id = validate_refresh_token(state.db.as_ref(), &old_refresh_token, claims.jti)
-        .await
-        .map_err(|_| AuthError::InvalidToken)?;
+    // A bound token is only refreshed by the holder of its key; an unbound
+    // one is bound to the key of the proof sent, if any
+    let key = dpop.verify(&state.jwt_config)?;
+    if let Some(cnf) = &claims.cnf {
+        if key.as_deref() != Some(cnf.jkt.as_str()) {
+            return Err(AuthError::InvalidDpopProof);
+        }
+    }
 
-    // Generate new tokens
-    let username = {
-        use crate::models::prelude::*;
-        let user = Users::find_by_id(user_id)
-            .one(state.db.as_ref())
-            .await?
-            .ok_or(AuthError::UserNotFound)?;
-        user.username
+    // Validate token in the store (checks revocation, expiry, hash match and
+    // the client it was bound to)
+    let user_agent = user_agent::from_headers(&headers);
+    let presented = state.bind_refresh_tokens.then(|| {
+        fingerprint(
+            user_agent.as_deref(),
+            jar.get(DEVICE_COOKIE).map(Cookie::value),
+        )
+    });
+    let user_id = match validate_refresh_token(
+        state.token_store.as_ref(),
+        &old_refresh_token,
+        claims.jti,
+        presented.as_deref(),
+    )
+    .await
+    {
+        Ok(user_id) => user_id,
+        Err(e) => match e.downcast_ref::<AuthError>() {
+            Some(AuthError::TokenReuseDetected) => {
+                record_token_reuse(&state, claims.sub, claims.jti, client_ip, &headers).await;
+                return Err(AuthError::TokenReuseDetected);
+            }
+            Some(AuthError::TokenBindingMismatch) => {
+                handle_binding_mismatch(
+                    &state,
+                    claims.sub.into(),
+                    user_agent.as_deref(),
+                    client_ip,
+                )
+                .await;
+                return Err(AuthError::InvalidToken);
+            }
+            _ => return Err(AuthError::InvalidToken),
+        },
     };
 
-    let new_access_token = create_access_token(user_id, username, &state.jwt_config)
-        .map_err(|_| AuthError::JwtEncodingError)?;
-    let (new_refresh_token, new_refresh_jti) = create_refresh_token(user_id, &state.jwt_config)
-        .map_err(|_| AuthError::JwtEncodingError)?;
+    // Trust in the device may have been withdrawn since the token was issued
+    if state.trusted_devices.is_some() {
+        let trusted = match jar.get(DEVICE_COOKIE) {
+            Some(device_id) => {
+                is_trusted_device(state.db.as_ref(), user_id.into(), device_id.value())
+                    .await
+                    .map_err(|e| AuthError::DatabaseError(format!("Failed to check device: {e}")))?
+            }
+            None => false,
+        };
+        if !trusted {
+            if let Err(e) = revoke_refresh_token(state.token_store.as_ref(), claims.jti).await {
+                tracing::warn!(user_id = %user_id, "Failed to revoke refresh token: {}", e);
+            }
+            tracing::warn!(
+                target: "audit",
+                action = "auth.untrusted_device_refresh",
+                user_id = %user_id,
+                ip = %client_ip,
+                "Refresh from an untrusted device rejected"
+            );
+            return Err(AuthError::InvalidToken);
+        }
+    }
+
+    // Generate new tokens
+    let user = Users::find_by_id(user_id.into_uuid())
+        .one(state.db.as_ref())
+        .await?
+        .ok_or(AuthError::UserNotFound)?;
+    let response = issue_access_token(&state, &user, key.as_deref())?;
+    let (new_refresh_token, new_refresh_jti) = issue_refresh_token(&state, user_id, key)?;
 
     // Rotate refresh token (revoke old, store new)
-    rotate_refresh_token(
-        state.db.as_ref(),
+    let (jar, fingerprint) = bind_client(&state, jar, user_agent.as_deref());
+    let ip = client_ip.0.map(|ip| ip.to_string());
+    let rotated = rotate_refresh_token(
+        state.token_store.as_ref(),
         claims.jti,
         &new_refresh_token,
         new_refresh_jti,
         user_id,
         state.jwt_config.refresh_token_expiry_days,
+        TokenClient {
+            user_agent: user_agent.as_deref(),
+            fingerprint: fingerprint.as_deref(),
+            ip: ip.as_deref(),
+        },
     )
-    .await
-    .map_err(|_| AuthError::DatabaseError("Failed to rotate token".to_string()))?;
+    .await;
+    if let Err(e) = rotated {
+        // A lost rotation race is token reuse, reported as such
+        let err = e
+            .downcast::<AuthError>()
+            .unwrap_or_else(|_| AuthError::DatabaseError("Failed to rotate token".to_string()));
+        if matches!(err, AuthError::TokenReuseDetected) {
+            record_token_reuse(&state, user_id, claims.jti, client_ip, &headers).await;
+        }
+        return Err(err);
+    }
 
     // Create new HttpOnly cookie for new refresh token
     let cookie = Cookie::build(("refresh_token", new_refresh_token))
@@ -421,23 +926,22 @@ pub async fn refresh_token(
         ))
         .build();
 
-    // Return response with new access token
-    let response = AuthResponse {
-        access_token: new_access_token,
-        token_type: "Bearer".to_string(),
-        expires_in: state.jwt_config.access_token_expiry_minutes * 60,
-    };
+    audit::record(
+        state.db.as_ref(),
+        AuditEntry::new(AuditEvent::TokenRefreshed)
+            .actor(user_id.into())
+            .client(client_ip, &headers),
+    )
+    .await;
 
-    Ok((
-        StatusCode::OK,
-        [(header::SET_COOKIE, cookie.to_string())],
-        Json(response),
-    ))
+    // Return response with new access token
+    Ok((StatusCode::OK, jar.add(cookie), Json(response)))
 }
 
 /// POST /api/auth/logout - Logout and invalidate tokens
 ///
-/// Revokes refresh token and blacklists access token.
+/// Blacklists the access token the request was made with until it expires,
+/// then revokes the refresh token.
 #[utoipa::path(
     post,
     path = "/api/v1/auth/logout",
@@ -449,9 +953,25 @@ pub async fn refresh_token(
 )]
 pub async fn logout(
     State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
     jar: axum_extra::extract::CookieJar,
 ) -> std::result::Result<impl IntoResponse, AuthError> {
-    use crate::services::auth::{revoke_refresh_token, verify_refresh_token};
+    use crate::middleware::auth::extract_token_from_header;
+    use crate::services::auth::{decode_access_token, revoke_refresh_token, verify_refresh_token};
+
+    // Revoke the access token (already verified by the auth middleware)
+    if let Some(blacklist) = &state.jwt_config.blacklist {
+        let access_token = extract_token_from_header(&headers)?;
+        let claims = decode_access_token(&access_token, &state.jwt_config)
+            .map_err(|_| AuthError::InvalidToken)?;
+        if let Some(jti) = claims.jti {
+            blacklist.add(jti, claims.exp).await.map_err(|e| {
+                tracing::error!("Failed to blacklist access token: {:?}", e);
+                AuthError::RedisError("Failed to revoke token".to_string())
+            })?;
+        }
+    }
 
     // Extract refresh token from cookie
     let refresh_token = jar
@@ -464,11 +984,19 @@ pub async fn logout(
     let claims = verify_refresh_token(&refresh_token, &state.jwt_config)
         .map_err(|_| AuthError::InvalidToken)?;
 
-    // Revoke refresh token in database
-    revoke_refresh_token(state.db.as_ref(), claims.jti)
+    // Revoke refresh token
+    revoke_refresh_token(state.token_store.as_ref(), claims.jti)
         .await
         .map_err(|_| AuthError::DatabaseError("Failed to revoke token".to_string()))?;
 
+    audit::record(
+        state.db.as_ref(),
+        AuditEntry::new(AuditEvent::Logout)
+            .actor(claims.sub.into())
+            .client(client_ip, &headers),
+    )
+    .await;
+
     // Clear refresh token cookie (set Max-Age=0)
     let cookie = Cookie::build(("refresh_token", ""))
         .http_only(true)
@@ -491,90 +1019,414 @@ pub async fn logout(
         (status = 200, description = "User information", body = UserResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
     ),
-    tag = "Authentication",
-    security(
-        ("bearer_auth" = [])
-    )
+    tag = "Authentication"
 )]
 pub async fn get_current_user(
     State(state): State<AppState>,
-    req: axum::http::Request<axum::body::Body>,
+    auth_user: AuthUser,
+    CurrentUser(user): CurrentUser,
 ) -> std::result::Result<impl IntoResponse, AuthError> {
-    use crate::middleware::auth::AuthUser;
+    let use_case = GetCurrentUserUseCase::new(
+        Arc::clone(&state.db),
+        state.modules,
+        state.chat_quota.clone(),
+        state.sandbox.clone(),
+    );
+    let response = use_case.execute(&auth_user, &user).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
 
-    // Extract AuthUser from request extensions (injected by middleware)
-    let auth_user = req
-        .extensions()
-        .get::<AuthUser>()
-        .ok_or(AuthError::InvalidToken)?;
+/// PATCH /api/auth/me - Update the current user's username or email
+///
+/// Protected route - requires valid access token. A changed email address is
+/// unverified until the link sent to it is followed.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/auth/me",
+    request_body = UpdateProfileRequest,
+    responses(
+        (status = 200, description = "Updated user information", body = UserResponse),
+        (status = 400, description = "Invalid username or email", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Username or email taken", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn update_profile(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    Json(req): Json<UpdateProfileRequest>,
+) -> std::result::Result<Json<UserResponse>, AuthError> {
+    req.validate().map_err(|e| {
+        e.downcast::<AuthError>()
+            .unwrap_or_else(|_| AuthError::InvalidInput("Validation failed".to_string()))
+    })?;
 
-    // Fetch full user information from database
     let user = Users::find_by_id(auth_user.user_id)
         .one(state.db.as_ref())
         .await?
         .ok_or(AuthError::UserNotFound)?;
+    let username = req.username.filter(|username| *username != user.username);
+    let email = req.email.filter(|email| *email != user.email);
+
+    // Taken by another account
+    if let Some(username) = &username {
+        let existing = Users::find()
+            .filter(users::Column::Username.eq(username))
+            .one(state.db.as_ref())
+            .await?;
+        if existing.is_some() {
+            return Err(AuthError::UserAlreadyExists);
+        }
+    }
+    if let Some(email) = &email {
+        let existing = Users::find()
+            .filter(users::Column::Email.eq(email))
+            .one(state.db.as_ref())
+            .await?;
+        if existing.is_some() {
+            return Err(AuthError::UserAlreadyExists);
+        }
+    }
 
-    // Return user response
-    let response = UserResponse {
-        id: user.id,
-        username: user.username,
-        email: user.email,
-        email_verified: user.email_verified,
-        role: user.role,
+    let changed: Vec<&str> = [("username", username.is_some()), ("email", email.is_some())]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect();
+    let user = if changed.is_empty() {
+        user
+    } else {
+        let mut active_user: users::ActiveModel = user.into();
+        if let Some(username) = username {
+            active_user.username = Set(username);
+        }
+        if let Some(email) = &email {
+            active_user.email = Set(email.clone());
+            active_user.email_verified = Set(false);
+        }
+        active_user.updated_at = Set(Utc::now().into());
+        let user = active_user.update(state.db.as_ref()).await?;
+        state.users.invalidate(user.id).await;
+
+        audit::record(
+            state.db.as_ref(),
+            AuditEntry::new(AuditEvent::ProfileUpdated)
+                .actor(user.id)
+                .client(client_ip, &headers)
+                .details(serde_json::json!({ "fields": changed })),
+        )
+        .await;
+
+        // The new address is verified like at sign-up; a failed delivery
+        // leaves the resend endpoint to the user
+        if let (Some(email_sender), Some(email)) = (&state.email_sender, email) {
+            use crate::services::email::create_verification_token;
+
+            let links = &state.verification_links;
+            let sent = create_verification_token(state.db.as_ref(), user.id, None)
+                .await
+                .and_then(|token| {
+                    email_sender.send_verification_email(&email, &links.url(None, &token))
+                });
+            if let Err(e) = sent {
+                tracing::warn!(user_id = %user.id, "Failed to send verification email: {}", e);
+            }
+        }
+        user
     };
 
-    Ok((StatusCode::OK, Json(response)))
+    let use_case = GetCurrentUserUseCase::new(
+        Arc::clone(&state.db),
+        state.modules,
+        state.chat_quota.clone(),
+        state.sandbox.clone(),
+    );
+    Ok(Json(use_case.execute(&auth_user, &user).await?))
 }
 
-// ============================================================================
-// Email Verification
-// ============================================================================
+/// GET /api/auth/sessions - List active sessions
+///
+/// Protected route - requires valid access token. Each session is a device
+/// signed in with a refresh token; `current` marks the one whose refresh
+/// cookie accompanies the request.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/sessions",
+    responses(
+        (status = 200, description = "Active sessions, newest first", body = SessionListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    jar: axum_extra::extract::CookieJar,
+) -> std::result::Result<Json<SessionListResponse>, AuthError> {
+    use crate::services::auth::verify_refresh_token;
 
-#[derive(Debug, Deserialize, ToSchema)]
-pub struct VerifyEmailRequest {
-    #[schema(example = "abc123def456")]
-    pub token: String,
+    let current_jti = jar
+        .get("refresh_token")
+        .and_then(|cookie| verify_refresh_token(cookie.value(), &state.jwt_config).ok())
+        .map(|claims| claims.jti);
+
+    let sessions = list_active_sessions(state.token_store.as_ref(), auth_user.user_id.into())
+        .await
+        .map_err(|_| AuthError::DatabaseError("Failed to load sessions".to_string()))?
+        .into_iter()
+        .map(|session| SessionResponse {
+            id: session.jti(),
+            device: device_label(session.user_agent.as_deref()),
+            current: current_jti == Some(session.jti()),
+            user_agent: session.user_agent,
+            ip: session.ip,
+            last_used_at: session.last_used_at.unwrap_or(session.created_at),
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+        })
+        .collect();
+
+    Ok(Json(SessionListResponse { sessions }))
 }
 
-#[derive(Debug, Serialize, ToSchema)]
-pub struct MessageResponse {
-    pub message: String,
+/// DELETE /api/auth/sessions/{id} - Sign out one session
+///
+/// Protected route - requires valid access token. The session's refresh
+/// token is revoked; access tokens already issued to it stay valid until
+/// they expire.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions/{id}",
+    params(
+        ("id" = uuid::Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 204, description = "Session signed out"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    client_ip: ClientIp,
+    Path(session_id): Path<TokenId>,
+) -> std::result::Result<StatusCode, AuthError> {
+    use crate::services::auth::revoke_user_session;
+
+    let user_id = auth_user.user_id.into();
+    let revoked = revoke_user_session(state.token_store.as_ref(), user_id, session_id)
+        .await
+        .map_err(|_| AuthError::DatabaseError("Failed to revoke session".to_string()))?;
+    if !revoked {
+        return Err(AuthError::SessionNotFound);
+    }
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.session_revoked",
+        user_id = %auth_user.user_id,
+        session_id = %session_id,
+        ip = %client_ip,
+        "Session signed out"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
-/// POST /api/auth/send-verification - Send verification email
+/// POST /api/auth/logout-all - Sign out every session
 ///
-/// Protected route - requires valid access token.
+/// Protected route - requires valid access token. Revokes every refresh
+/// token of the user and cuts off every access token issued so far,
+/// including the one of this request.
 #[utoipa::path(
     post,
-    path = "/api/v1/auth/send-verification",
+    path = "/api/v1/auth/logout-all",
     responses(
-        (status = 200, description = "Verification email sent", body = MessageResponse),
+        (status = 200, description = "Every session signed out"),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 400, description = "Email already verified", body = ErrorResponse),
     ),
-    tag = "Authentication",
-    security(
-        ("bearer_auth" = [])
-    )
+    tag = "Authentication"
 )]
-pub async fn send_verification_email(
+pub async fn logout_all(
     State(state): State<AppState>,
-    req: axum::http::Request<axum::body::Body>,
+    auth_user: AuthUser,
+    client_ip: ClientIp,
+    headers: HeaderMap,
 ) -> std::result::Result<impl IntoResponse, AuthError> {
-    use crate::middleware::auth::AuthUser;
-    use crate::services::email::{create_verification_token, EmailSender, MockEmailSender};
+    use crate::services::auth::token_rotation::revoke_all_user_tokens;
+
+    revoke_all_user_tokens(state.token_store.as_ref(), auth_user.user_id.into())
+        .await
+        .map_err(|_| AuthError::DatabaseError("Failed to revoke sessions".to_string()))?;
+    if let Some(not_before) = &state.jwt_config.not_before {
+        not_before
+            .bump(auth_user.user_id)
+            .await
+            .map_err(|e| AuthError::DatabaseError(format!("Failed to cut off tokens: {e}")))?;
+    }
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.logout_all",
+        user_id = %auth_user.user_id,
+        ip = %client_ip,
+        "Every session signed out"
+    );
+    audit::record(
+        state.db.as_ref(),
+        AuditEntry::new(AuditEvent::Logout)
+            .actor(auth_user.user_id)
+            .client(client_ip, &headers)
+            .details(serde_json::json!({ "all": true })),
+    )
+    .await;
+
+    let cookie = Cookie::build(("refresh_token", ""))
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Strict)
+        .path("/")
+        .max_age(time::Duration::seconds(0))
+        .build();
+
+    Ok((StatusCode::OK, [(header::SET_COOKIE, cookie.to_string())]))
+}
 
-    // Extract AuthUser from request extensions
-    let auth_user = req
-        .extensions()
-        .get::<AuthUser>()
-        .ok_or(AuthError::InvalidToken)?;
+/// POST /api/auth/change-password - Change the current user's password
+///
+/// Protected route - requires valid access token, and is the only route
+/// accepting the restricted token issued once a password has expired.
+/// Clears a rotation required by an admin, signs out every other session and
+/// cuts off access tokens issued before the change; the response carries a
+/// fresh one.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/change-password",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Password changed", body = AuthResponse),
+        (status = 400, description = "Invalid new password or account without password", body = ErrorResponse),
+        (status = 401, description = "Unauthorized or wrong current password", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Json(req): Json<ChangePasswordRequest>,
+) -> std::result::Result<Json<AuthResponse>, AuthError> {
+    use crate::services::auth::token_rotation::revoke_other_user_tokens;
+    use crate::services::auth::verify_refresh_token;
+
+    req.validate().map_err(|e| {
+        e.downcast::<AuthError>()
+            .unwrap_or_else(|_| AuthError::InvalidInput("Validation failed".to_string()))
+    })?;
 
-    // Get user from database
     let user = Users::find_by_id(auth_user.user_id)
         .one(state.db.as_ref())
         .await?
         .ok_or(AuthError::UserNotFound)?;
+    let current_hash = user
+        .password_hash
+        .as_deref()
+        .ok_or_else(|| AuthError::InvalidInput("Account has no password to change".to_string()))?;
+    let is_valid = verify_password(&req.current_password, current_hash)
+        .map_err(|_| AuthError::InvalidCredentials)?;
+    if !is_valid {
+        return Err(AuthError::InvalidCredentials);
+    }
+
+    let password_hash =
+        hash_password(&req.new_password).map_err(|_| AuthError::PasswordHashError)?;
+    let now = Utc::now();
+    let mut active_user: users::ActiveModel = user.into();
+    active_user.password_hash = Set(Some(password_hash));
+    active_user.password_changed_at = Set(Some(now.into()));
+    active_user.password_rotation_required = Set(false);
+    active_user.updated_at = Set(now.into());
+    let user = active_user.update(state.db.as_ref()).await?;
+
+    // Other devices are signed out; the session making the change is kept
+    let current_jti = jar
+        .get("refresh_token")
+        .and_then(|cookie| verify_refresh_token(cookie.value(), &state.jwt_config).ok())
+        .filter(|claims| claims.sub == user.id.into())
+        .map(|claims| claims.jti);
+    revoke_other_user_tokens(state.token_store.as_ref(), user.id.into(), current_jti)
+        .await
+        .map_err(|_| AuthError::DatabaseError("Failed to revoke sessions".to_string()))?;
+
+    // Tokens issued before the change, including restricted ones, stop working
+    if let Some(not_before) = &state.jwt_config.not_before {
+        not_before
+            .bump(user.id)
+            .await
+            .map_err(|e| AuthError::DatabaseError(format!("Failed to cut off tokens: {e}")))?;
+    }
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.password_changed",
+        user_id = %user.id,
+        ip = %client_ip,
+        "Password changed"
+    );
+    audit::record(
+        state.db.as_ref(),
+        AuditEntry::new(AuditEvent::PasswordChanged)
+            .actor(user.id)
+            .client(client_ip, &headers),
+    )
+    .await;
+
+    // A token bound to a key is replaced by one bound to the same key
+    Ok(Json(issue_access_token(
+        &state,
+        &user,
+        auth_user.key_thumbprint.as_deref(),
+    )?))
+}
+
+// ============================================================================
+// Email Verification
+// ============================================================================
+
+/// POST /api/auth/send-verification - Send verification email
+///
+/// Protected route - requires valid access token. Issues a new token,
+/// invalidating earlier links. Requests within the cooldown are rejected
+/// with 429 and the seconds remaining.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/send-verification",
+    responses(
+        (status = 200, description = "Verification email sent", body = SendVerificationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 400, description = "Email already verified", body = ErrorResponse),
+        (status = 404, description = "Email is disabled on this server", body = ErrorResponse),
+        (status = 429, description = "Verification email sent too recently; see `retry_after_secs`", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn send_verification_email(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> std::result::Result<impl IntoResponse, AuthError> {
+    use crate::services::email::{resend_verification_token, ResendOutcome, RESEND_COOLDOWN_SECS};
+
+    let email_sender = state
+        .email_sender
+        .as_ref()
+        .ok_or(AuthError::EmailDisabled)?;
 
     // Check if already verified
     if user.email_verified {
@@ -583,23 +1435,34 @@ pub async fn send_verification_email(
         ));
     }
 
-    // Create verification token
-    let token = create_verification_token(state.db.as_ref(), user.id)
-        .await
-        .map_err(|e| AuthError::DatabaseError(format!("Failed to create token: {e}")))?;
-
-    // Send verification email
-    let email_sender = MockEmailSender;
-    email_sender
-        .send_verification_email(&user.email, &token)
-        .map_err(|_e| AuthError::InternalError)?;
+    // Issue a new token and send it (rolled back if sending fails)
+    let outcome = resend_verification_token(
+        state.db.as_ref(),
+        user.id,
+        chrono::Duration::seconds(RESEND_COOLDOWN_SECS),
+        |token, client| {
+            let url = state.verification_links.url(client, token);
+            email_sender.send_verification_email(&user.email, &url)
+        },
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to resend verification email: {}", e);
+        AuthError::InternalError
+    })?;
 
-    Ok((
-        StatusCode::OK,
-        Json(MessageResponse {
-            message: "Verification email sent".to_string(),
-        }),
-    ))
+    match outcome {
+        ResendOutcome::Sent { cooldown_secs } => Ok((
+            StatusCode::OK,
+            Json(SendVerificationResponse {
+                message: "Verification email sent".to_string(),
+                cooldown_secs,
+            }),
+        )),
+        ResendOutcome::CoolingDown { retry_after_secs } => {
+            Err(AuthError::VerificationCooldown { retry_after_secs })
+        }
+    }
 }
 
 /// POST /api/auth/verify-email - Verify email with token
@@ -617,14 +1480,25 @@ pub async fn send_verification_email(
 )]
 pub async fn verify_email(
     State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
     Json(req): Json<VerifyEmailRequest>,
 ) -> std::result::Result<impl IntoResponse, AuthError> {
     use crate::services::email::verify_email_token;
 
     // Verify the token
-    verify_email_token(state.db.as_ref(), &req.token)
+    let user_id = verify_email_token(state.db.as_ref(), &req.token)
         .await
         .map_err(|e| AuthError::InvalidInput(format!("Verification failed: {e}")))?;
+    state.users.invalidate(user_id).await;
+
+    audit::record(
+        state.db.as_ref(),
+        AuditEntry::new(AuditEvent::EmailVerified)
+            .actor(user_id)
+            .client(client_ip, &headers),
+    )
+    .await;
 
     Ok((
         StatusCode::OK,
@@ -634,6 +1508,137 @@ pub async fn verify_email(
     ))
 }
 
+// ============================================================================
+// Trusted Devices
+// ============================================================================
+
+/// GET /api/auth/devices - List trusted devices
+///
+/// Protected route - requires valid access token. Mounted when
+/// `TRUSTED_DEVICES_ENABLED=true`. `current` marks the device making the
+/// request.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/devices",
+    responses(
+        (status = 200, description = "Trusted devices, most recently used first", body = TrustedDeviceListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn list_trusted_devices(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    jar: CookieJar,
+) -> std::result::Result<Json<TrustedDeviceListResponse>, AuthError> {
+    let current_hash = jar
+        .get(DEVICE_COOKIE)
+        .map(|device_id| device_hash(device_id.value()));
+
+    let devices = trusted_devices::list_trusted_devices(state.db.as_ref(), auth_user.user_id)
+        .await
+        .map_err(|_| AuthError::DatabaseError("Failed to load devices".to_string()))?
+        .into_iter()
+        .filter_map(|device| {
+            Some(TrustedDeviceResponse {
+                id: device.id,
+                device: device_label(device.user_agent.as_deref()),
+                current: current_hash.as_deref() == Some(device.device_hash.as_str()),
+                confirmed_at: device.confirmed_at?,
+                user_agent: device.user_agent,
+                ip: device.ip,
+                last_used_at: device.last_used_at,
+            })
+        })
+        .collect();
+
+    Ok(Json(TrustedDeviceListResponse { devices }))
+}
+
+/// DELETE /api/auth/devices/{id} - Remove a trusted device
+///
+/// Protected route - requires valid access token. Sessions on the device
+/// end at their next refresh, and signing in on it again requires a new
+/// email confirmation.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/devices/{id}",
+    params(
+        ("id" = uuid::Uuid, Path, description = "Device ID")
+    ),
+    responses(
+        (status = 204, description = "Device removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Device not found", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn remove_trusted_device(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    client_ip: ClientIp,
+    Path(device_id): Path<uuid::Uuid>,
+) -> std::result::Result<StatusCode, AuthError> {
+    let removed =
+        trusted_devices::remove_trusted_device(state.db.as_ref(), auth_user.user_id, device_id)
+            .await
+            .map_err(|_| AuthError::DatabaseError("Failed to remove device".to_string()))?;
+    if !removed {
+        return Err(AuthError::DeviceNotFound);
+    }
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.device_removed",
+        user_id = %auth_user.user_id,
+        device_id = %device_id,
+        ip = %client_ip,
+        "Trusted device removed"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/auth/devices/confirm - Confirm a device with token
+///
+/// Public route - trusts the device using the token from the confirmation
+/// email. Signing in again on that device issues a refresh token.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/devices/confirm",
+    request_body = ConfirmDeviceRequest,
+    responses(
+        (status = 200, description = "Device confirmed", body = MessageResponse),
+        (status = 400, description = "Invalid or expired token", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn confirm_device(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    Json(req): Json<ConfirmDeviceRequest>,
+) -> std::result::Result<Json<MessageResponse>, AuthError> {
+    let device = trusted_devices::confirm_device(state.db.as_ref(), &req.token)
+        .await
+        .map_err(|_| AuthError::DatabaseError("Failed to confirm device".to_string()))?
+        .ok_or_else(|| {
+            AuthError::InvalidInput("Invalid or expired confirmation link".to_string())
+        })?;
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.device_confirmed",
+        user_id = %device.user_id,
+        device_id = %device.id,
+        ip = %client_ip,
+        "Trusted device confirmed"
+    );
+
+    Ok(Json(MessageResponse {
+        message: "Device confirmed; sign in on it again to stay signed in".to_string(),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -686,6 +1691,7 @@ mod tests {
             username: "alice".to_string(),
             email: "alice@example.com".to_string(),
             password: "SecurePass123!".to_string(),
+            client: None,
         };
         assert!(req.validate().is_ok());
     }
@@ -696,6 +1702,7 @@ mod tests {
             username: String::new(),
             email: "alice@example.com".to_string(),
             password: "SecurePass123!".to_string(),
+            client: None,
         };
         assert!(req.validate().is_err());
     }
@@ -706,6 +1713,7 @@ mod tests {
             username: "ab".to_string(),
             email: "alice@example.com".to_string(),
             password: "SecurePass123!".to_string(),
+            client: None,
         };
         let result = req.validate();
         assert!(result.is_err());
@@ -718,6 +1726,7 @@ mod tests {
             username: "a".repeat(51),
             email: "alice@example.com".to_string(),
             password: "SecurePass123!".to_string(),
+            client: None,
         };
         let result = req.validate();
         assert!(result.is_err());
@@ -730,6 +1739,7 @@ mod tests {
             username: "alice".to_string(),
             email: "not-an-email".to_string(),
             password: "SecurePass123!".to_string(),
+            client: None,
         };
         let result = req.validate();
         assert!(result.is_err());
@@ -742,6 +1752,7 @@ mod tests {
             username: "alice".to_string(),
             email: "alice@example.com".to_string(),
             password: "short".to_string(),
+            client: None,
         };
         let result = req.validate();
         assert!(result.is_err());
@@ -757,6 +1768,7 @@ mod tests {
             username: "alice".to_string(),
             email: "alice@example.com".to_string(),
             password: "a".repeat(129),
+            client: None,
         };
         let result = req.validate();
         assert!(result.is_err());
@@ -792,4 +1804,61 @@ mod tests {
         };
         assert!(req.validate().is_err());
     }
+
+    #[test]
+    fn test_change_password_request_validation() {
+        let req = ChangePasswordRequest {
+            current_password: "SecurePass123!".to_string(),
+            new_password: "EvenMoreSecure456!".to_string(),
+        };
+        assert!(req.validate().is_ok());
+
+        let req = ChangePasswordRequest {
+            current_password: "SecurePass123!".to_string(),
+            new_password: "short".to_string(),
+        };
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_update_profile_request_validation() {
+        let req = UpdateProfileRequest {
+            username: None,
+            email: Some("alice@example.org".to_string()),
+        };
+        assert!(req.validate().is_ok());
+
+        let req = UpdateProfileRequest {
+            username: None,
+            email: None,
+        };
+        assert!(req
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("Nothing to update"));
+
+        let req = UpdateProfileRequest {
+            username: Some("al".to_string()),
+            email: None,
+        };
+        assert!(req.validate().is_err());
+
+        let req = UpdateProfileRequest {
+            username: None,
+            email: Some("not-an-email".to_string()),
+        };
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_change_password_request_validation_same_password() {
+        let req = ChangePasswordRequest {
+            current_password: "SecurePass123!".to_string(),
+            new_password: "SecurePass123!".to_string(),
+        };
+        let result = req.validate();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("must differ"));
+    }
 }
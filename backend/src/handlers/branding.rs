@@ -0,0 +1,116 @@
+//! White-label branding endpoints
+//!
+//! `GET /api/v1/branding` is public so the frontend can theme the login page;
+//! the admin endpoints change the branding at runtime.
+
+use axum::{extract::State, http::HeaderMap, response::Response, Json};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+    config::BrandingConfig,
+    dto::{
+        branding::{BrandingResponse, UpdateBrandingRequest},
+        ErrorResponse,
+    },
+    middleware::auth::AuthUser,
+    services::{
+        auth::AuthError,
+        branding::{self, BrandingOverrides},
+    },
+    utils::http_cache::cached_json,
+};
+
+/// Application state for branding handlers
+#[derive(Clone)]
+pub struct BrandingState {
+    pub db: Arc<DatabaseConnection>,
+    /// Branding from the environment, used for fields without an override
+    pub defaults: BrandingConfig,
+    /// Client cache lifetime of `GET /api/v1/branding`
+    pub max_age: Duration,
+}
+
+/// GET /api/v1/branding - Get the branding of this deployment
+///
+/// Cacheable: the `ETag` changes as soon as an admin updates or resets the
+/// branding, so revalidating clients pick up the change.
+#[utoipa::path(
+    get,
+    path = "/api/v1/branding",
+    responses(
+        (status = 200, description = "Effective branding", body = BrandingResponse),
+        (status = 304, description = "Not modified since the ETag in If-None-Match"),
+    ),
+    tag = "branding"
+)]
+pub async fn get_branding(
+    State(state): State<BrandingState>,
+    headers: HeaderMap,
+) -> Result<Response, AuthError> {
+    let branding = branding::current(state.db.as_ref(), &state.defaults)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    Ok(cached_json(
+        &headers,
+        state.max_age,
+        &BrandingResponse::from(branding),
+    ))
+}
+
+/// PUT /api/v1/admin/branding - Replace the runtime branding overrides
+///
+/// Fields left out fall back to the deployment defaults.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/branding",
+    request_body = UpdateBrandingRequest,
+    responses(
+        (status = 200, description = "Branding updated", body = BrandingResponse),
+        (status = 400, description = "Invalid branding field", body = ErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin only"),
+    ),
+    tag = "Admin"
+)]
+pub async fn update_branding(
+    State(state): State<BrandingState>,
+    auth_user: AuthUser,
+    Json(req): Json<UpdateBrandingRequest>,
+) -> Result<Json<BrandingResponse>, AuthError> {
+    let overrides = BrandingOverrides::from(req).normalized();
+    let updated = branding::apply(&state.defaults, &overrides);
+    branding::validate(&updated).map_err(|e| AuthError::InvalidInput(e.to_string()))?;
+
+    branding::save_overrides(state.db.as_ref(), overrides, auth_user.user_id)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    tracing::info!("Branding updated by admin {}", auth_user.user_id);
+    Ok(Json(updated.into()))
+}
+
+/// DELETE /api/v1/admin/branding - Restore the deployment default branding
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/branding",
+    responses(
+        (status = 200, description = "Branding reset to defaults", body = BrandingResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin only"),
+    ),
+    tag = "Admin"
+)]
+pub async fn reset_branding(
+    State(state): State<BrandingState>,
+    auth_user: AuthUser,
+) -> Result<Json<BrandingResponse>, AuthError> {
+    branding::reset(state.db.as_ref())
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    tracing::info!("Branding reset by admin {}", auth_user.user_id);
+    Ok(Json(state.defaults.into()))
+}
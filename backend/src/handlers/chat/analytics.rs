@@ -0,0 +1,47 @@
+//! Chat usage analytics endpoint handler
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    application::chat::UsageAnalyticsUseCase,
+    dto::chat::{ChatAnalyticsQuery, ChatAnalyticsResponse, ChatErrorResponse},
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+/// Get the current user's chat usage over the last `days` days
+///
+/// Counts replies per day, tokens per model, the most active sessions and the
+/// mean time to the first reply chunk.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - `days` is outside 1-365 (400)
+/// - Database error (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/analytics",
+    tag = "chat",
+    params(ChatAnalyticsQuery),
+    responses(
+        (status = 200, description = "Usage analytics", body = ChatAnalyticsResponse),
+        (status = 400, description = "Invalid period", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn get_chat_analytics(
+    State(state): State<ChatState>,
+    auth_user: AuthUser,
+    Query(query): Query<ChatAnalyticsQuery>,
+) -> Result<Json<ChatAnalyticsResponse>, ChatApiError> {
+    let analytics = UsageAnalyticsUseCase::new(Arc::clone(&state.repository) as Arc<_>)
+        .execute(auth_user.user_id, query.days)
+        .await?;
+
+    Ok(Json(analytics.into()))
+}
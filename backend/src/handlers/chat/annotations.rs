@@ -0,0 +1,169 @@
+//! Message annotation endpoint handlers
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::chat::{
+        message_annotations::AddAnnotationRequest as UseCaseRequest, MessageAnnotationsUseCase,
+    },
+    domain::chat::annotation::{AnnotationFilter, AnnotationKind},
+    dto::chat::{
+        AddAnnotationRequest, AnnotationDto, AnnotationsQuery, ChatErrorResponse,
+        ListAnnotationsResponse,
+    },
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+fn use_case(state: &ChatState) -> MessageAnnotationsUseCase {
+    MessageAnnotationsUseCase::new(
+        Arc::clone(&state.repository) as Arc<_>,
+        Arc::clone(&state.repository) as Arc<_>,
+    )
+}
+
+/// Parse the `annotation` query parameter shared by history and listing
+///
+/// # Errors
+/// Returns 400 if the filter expression is invalid
+pub(super) fn parse_filter(
+    annotation: Option<&str>,
+) -> Result<Option<AnnotationFilter>, ChatApiError> {
+    annotation
+        .map(AnnotationFilter::parse)
+        .transpose()
+        .map_err(ChatApiError::ValidationFailed)
+}
+
+/// List the current user's annotations in a session
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Invalid annotation filter (400)
+/// - User not authorized (403)
+/// - Session not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/sessions/{id}/annotations",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Session ID"),
+        ("annotation" = Option<String>, Query, description = "Only matching annotations: `bookmark`, `label`, `label:<text>`, `reaction` or `reaction:<emoji>`")
+    ),
+    responses(
+        (status = 200, description = "Annotations retrieved", body = ListAnnotationsResponse),
+        (status = 400, description = "Invalid annotation filter", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn list_annotations(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    Query(query): Query<AnnotationsQuery>,
+    auth_user: AuthUser,
+) -> Result<Json<ListAnnotationsResponse>, ChatApiError> {
+    let filter = parse_filter(query.annotation.as_deref())?;
+
+    let annotations = use_case(&state)
+        .list(session_id, auth_user.user_id, filter.as_ref())
+        .await?;
+
+    Ok(Json(ListAnnotationsResponse {
+        annotations: annotations.into_iter().map(AnnotationDto::from).collect(),
+    }))
+}
+
+/// Bookmark, label or react to a message
+///
+/// Adding an annotation the user already has returns the existing one.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Invalid kind or value (400)
+/// - User not authorized (403)
+/// - Session or message not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/sessions/{id}/messages/{message_id}/annotations",
+    tag = "chat",
+    request_body = AddAnnotationRequest,
+    params(
+        ("id" = Uuid, Path, description = "Session ID"),
+        ("message_id" = Uuid, Path, description = "Message ID")
+    ),
+    responses(
+        (status = 201, description = "Annotation added", body = AnnotationDto),
+        (status = 400, description = "Invalid kind or value", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session or message not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn add_annotation(
+    State(state): State<ChatState>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    auth_user: AuthUser,
+    Json(request): Json<AddAnnotationRequest>,
+) -> Result<(StatusCode, Json<AnnotationDto>), ChatApiError> {
+    let kind = AnnotationKind::parse(&request.kind).map_err(ChatApiError::ValidationFailed)?;
+
+    let annotation = use_case(&state)
+        .add(UseCaseRequest {
+            session_id,
+            message_id,
+            user_id: auth_user.user_id,
+            kind,
+            value: request.value,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(annotation.into())))
+}
+
+/// Remove an annotation from a message
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - User not authorized (403)
+/// - Session or annotation not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chat/sessions/{id}/messages/{message_id}/annotations/{annotation_id}",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Session ID"),
+        ("message_id" = Uuid, Path, description = "Message ID"),
+        ("annotation_id" = Uuid, Path, description = "Annotation ID")
+    ),
+    responses(
+        (status = 204, description = "Annotation removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session or annotation not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn remove_annotation(
+    State(state): State<ChatState>,
+    Path((session_id, message_id, annotation_id)): Path<(Uuid, Uuid, Uuid)>,
+    auth_user: AuthUser,
+) -> Result<StatusCode, ChatApiError> {
+    use_case(&state)
+        .remove(session_id, message_id, annotation_id, auth_user.user_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -5,53 +5,63 @@ use std::sync::Arc;
 
 use crate::{
     application::chat::create_session::{CreateSessionUseCase, CreateSessionRequest as UseCaseRequest},
-    handlers::chat::{dto::{CreateSessionRequest, CreateSessionResponse}, ChatState},
+    dto::chat::{ChatErrorResponse, CreateSessionRequest, CreateSessionResponse},
+    handlers::chat::{ChatApiError, ChatState},
     middleware::auth::AuthUser,
 };
 
 /// Create a new chat session
 ///
+/// An optional `first_message` is stored as the session's first user message.
+/// The session and the message are written in one transaction, so a failure
+/// leaves neither behind. No reply is generated for it.
+///
 /// # Errors
 /// Returns HTTP error if:
-/// - Title validation fails (400)
+/// - Title or first message validation fails (400)
+/// - A guest has used its message allowance (429)
 /// - Database error occurs (500)
 #[utoipa::path(
     post,
-    path = "/api/chat/sessions",
+    path = "/api/v1/chat/sessions",
     tag = "chat",
     request_body = CreateSessionRequest,
     responses(
         (status = 201, description = "Session created successfully", body = CreateSessionResponse),
-        (status = 400, description = "Invalid request"),
+        (status = 400, description = "Invalid request", body = ChatErrorResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 500, description = "Internal server error")
-    ),
-    security(
-        ("bearer_auth" = [])
+        (status = 429, description = "Guest message limit reached", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
     )
 )]
 pub async fn create_session(
     State(state): State<ChatState>,
     auth_user: AuthUser,
     Json(request): Json<CreateSessionRequest>,
-) -> Result<(StatusCode, Json<CreateSessionResponse>), (StatusCode, String)> {
-    let use_case = CreateSessionUseCase::new(Arc::clone(&state.repository) as Arc<_>);
+) -> Result<(StatusCode, Json<CreateSessionResponse>), ChatApiError> {
+    // A first message counts against the allowance of a guest
+    if request.first_message.is_some() {
+        state.charge_guest(&auth_user).await?;
+    }
+
+    let use_case = CreateSessionUseCase::new(Arc::clone(&state.repository) as Arc<_>)
+        .with_events(Arc::clone(&state.events))
+        .with_first_messages(Arc::clone(&state.repository) as Arc<_>, state.policy);
 
     let use_case_request = UseCaseRequest {
         user_id: auth_user.user_id,
         title: request.title,
+        first_message: request.first_message,
     };
 
-    let response = use_case
-        .execute(use_case_request)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let response = use_case.execute(use_case_request).await?;
 
     Ok((
         StatusCode::CREATED,
         Json(CreateSessionResponse {
             session_id: response.session_id,
             title: response.title,
+            first_message: response.first_message.map(Into::into),
         }),
     ))
 }
@@ -0,0 +1,69 @@
+//! Delete message endpoint handler
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::chat::{delete_message::DeleteMessageRequest, DeleteMessageUseCase},
+    dto::chat::{ChatErrorResponse, DeleteMessageResponse},
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+/// Delete one message of a chat session
+///
+/// Depending on `CHAT_MESSAGE_DELETION`, the message is either deleted with
+/// its annotations or redacted: kept in the history with its content
+/// replaced. Either way it is no longer sent to the model as context.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - User not authorized (403)
+/// - Session or message not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chat/sessions/{id}/messages/{message_id}",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Session ID"),
+        ("message_id" = Uuid, Path, description = "Message ID")
+    ),
+    responses(
+        (status = 200, description = "Message deleted or redacted", body = DeleteMessageResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session or message not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn delete_message(
+    State(state): State<ChatState>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    auth_user: AuthUser,
+) -> Result<Json<DeleteMessageResponse>, ChatApiError> {
+    let use_case = DeleteMessageUseCase::new(
+        Arc::clone(&state.repository) as Arc<_>,
+        Arc::clone(&state.repository) as Arc<_>,
+        state.message_deletion,
+    )
+    .with_events(Arc::clone(&state.events));
+
+    let request = DeleteMessageRequest {
+        session: session_id,
+        message: message_id,
+        user: auth_user.user_id,
+    };
+
+    let response = use_case.execute(request).await?;
+
+    Ok(Json(DeleteMessageResponse {
+        session_id: response.session_id,
+        message_id: response.message_id,
+        redacted: response.redacted,
+    }))
+}
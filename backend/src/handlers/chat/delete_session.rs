@@ -1,13 +1,13 @@
 //! Delete session endpoint handler
 
-use axum::{extract::{Path, State}, http::StatusCode, Json};
+use axum::{extract::{Path, State}, Json};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
-    application::chat::delete_session::{DeleteSessionRequest, DeleteSessionUseCase},
-    domain::chat::repository::RepositoryError,
-    handlers::chat::{dto::DeleteSessionResponse, ChatState},
+    application::chat::{delete_session::DeleteSessionRequest, DeleteSessionUseCase},
+    dto::chat::{ChatErrorResponse, DeleteSessionResponse},
+    handlers::chat::{ChatApiError, ChatState},
     middleware::auth::AuthUser,
 };
 
@@ -20,7 +20,7 @@ use crate::{
 /// - Database error (500)
 #[utoipa::path(
     delete,
-    path = "/api/chat/sessions/{id}",
+    path = "/api/v1/chat/sessions/{id}",
     tag = "chat",
     params(
         ("id" = Uuid, Path, description = "Session ID")
@@ -28,33 +28,25 @@ use crate::{
     responses(
         (status = 200, description = "Session deleted", body = DeleteSessionResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - user does not own this session"),
-        (status = 404, description = "Session not found"),
-        (status = 500, description = "Internal server error")
-    ),
-    security(
-        ("bearer_auth" = [])
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
     )
 )]
 pub async fn delete_session(
     State(state): State<ChatState>,
     Path(session_id): Path<Uuid>,
     auth_user: AuthUser,
-) -> Result<Json<DeleteSessionResponse>, (StatusCode, String)> {
-    let use_case = DeleteSessionUseCase::new(Arc::clone(&state.repository) as Arc<_>);
+) -> Result<Json<DeleteSessionResponse>, ChatApiError> {
+    let use_case = DeleteSessionUseCase::new(Arc::clone(&state.repository) as Arc<_>)
+        .with_events(Arc::clone(&state.events));
 
     let request = DeleteSessionRequest {
         session_id,
         user_id: auth_user.user_id,
     };
 
-    let response = use_case.execute(request).await.map_err(|e| match e {
-        RepositoryError::SessionNotFound(_) => (StatusCode::NOT_FOUND, "Session not found".to_string()),
-        RepositoryError::ValidationError(msg) if msg.contains("not authorized") => {
-            (StatusCode::FORBIDDEN, msg)
-        }
-        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-    })?;
+    let response = use_case.execute(request).await?;
 
     Ok(Json(DeleteSessionResponse {
         session_id: response.session_id,
@@ -0,0 +1,372 @@
+//! Errors of the chat HTTP endpoints
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    application::chat::generation::PollError,
+    domain::chat::{
+        prompt_budget::PromptOverflow, repository::RepositoryError, share::SHARE_PASSWORD_LOCKOUT,
+    },
+    dto::chat::{ChatErrorCode, ChatErrorResponse},
+    infrastructure::llm::LlmProviderError,
+};
+
+/// Error returned by the chat endpoints
+///
+/// Built from the use cases' [`RepositoryError`] and from provider failures
+/// ([`LlmProviderError`]), and rendered as a [`ChatErrorResponse`] body.
+/// `Internal` and `ProviderUnavailable` details are logged, not sent.
+///
+/// # HTTP Status Mapping
+///
+/// | Error | HTTP Status |
+/// |-------|-------------|
+/// | `ValidationFailed` | 400 Bad Request |
+/// | `Unauthorized` | 401 Unauthorized |
+/// | `Forbidden` | 403 Forbidden |
+/// | `NotFound` | 404 Not Found |
+/// | `Conflict` | 409 Conflict |
+/// | `Gone` | 410 Gone |
+/// | `PromptTooLarge` | 422 Unprocessable Entity (with the overflow) |
+/// | `RateLimited` | 429 Too Many Requests (with `Retry-After` when known) |
+/// | `Internal` | 500 Internal Server Error |
+/// | `ProviderUnavailable` | 503 Service Unavailable |
+#[derive(Debug, thiserror::Error)]
+pub enum ChatApiError {
+    /// The request is invalid (content, model, parameters)
+    #[error("{0}")]
+    ValidationFailed(String),
+
+    /// Share password missing or incorrect
+    #[error("{0}")]
+    Unauthorized(String),
+
+    /// Not the owner of the resource, account disabled, guest mode off, or a
+    /// share password sent by a client without a known address
+    #[error("{0}")]
+    Forbidden(String),
+
+    /// Session, message, job, webhook, generation or share not found
+    #[error("{0}")]
+    NotFound(String),
+
+    /// A reply is being generated, the session changed, or a limit on stored
+    /// resources was reached
+    #[error("{0}")]
+    Conflict(String),
+
+    /// Share revoked or expired
+    #[error("{0}")]
+    Gone(String),
+
+    /// The prompt and the reply do not fit the model's context window
+    #[error("{0}")]
+    PromptTooLarge(PromptOverflow),
+
+    /// Rate, guest or job limit reached
+    #[error("{message}")]
+    RateLimited {
+        message: String,
+        /// Seconds until the limit resets, when known
+        retry_after_secs: Option<u64>,
+    },
+
+    /// The model's provider is not configured or failed
+    #[error("Provider unavailable: {0}")]
+    ProviderUnavailable(String),
+
+    /// Unexpected failure, e.g. of the database
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl ChatApiError {
+    /// HTTP status of the error
+    #[must_use]
+    pub const fn status(&self) -> StatusCode {
+        match self {
+            Self::ValidationFailed(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Gone(_) => StatusCode::GONE,
+            Self::PromptTooLarge(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::ProviderUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Machine-readable kind of the error
+    #[must_use]
+    pub const fn code(&self) -> ChatErrorCode {
+        match self {
+            Self::ValidationFailed(_) => ChatErrorCode::ValidationFailed,
+            Self::Unauthorized(_) => ChatErrorCode::Unauthorized,
+            Self::Forbidden(_) => ChatErrorCode::Forbidden,
+            Self::NotFound(_) => ChatErrorCode::NotFound,
+            Self::Conflict(_) => ChatErrorCode::Conflict,
+            Self::Gone(_) => ChatErrorCode::Gone,
+            Self::PromptTooLarge(_) => ChatErrorCode::PromptTooLarge,
+            Self::RateLimited { .. } => ChatErrorCode::RateLimited,
+            Self::Internal(_) => ChatErrorCode::Internal,
+            Self::ProviderUnavailable(_) => ChatErrorCode::ProviderUnavailable,
+        }
+    }
+
+    /// Description sent to the client
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            Self::ValidationFailed(msg)
+            | Self::Unauthorized(msg)
+            | Self::Forbidden(msg)
+            | Self::NotFound(msg)
+            | Self::Conflict(msg)
+            | Self::Gone(msg)
+            | Self::RateLimited { message: msg, .. } => msg.clone(),
+            Self::PromptTooLarge(overflow) => format!(
+                "{overflow}; shorten the message or start a new session to remove at least {} \
+                 tokens",
+                overflow.excess_tokens()
+            ),
+            Self::ProviderUnavailable(_) => {
+                "The model provider is unavailable; try again later".to_string()
+            }
+            Self::Internal(_) => "Internal server error".to_string(),
+        }
+    }
+
+    /// Body of the error response
+    #[must_use]
+    pub fn body(&self) -> ChatErrorResponse {
+        ChatErrorResponse {
+            error: self.code(),
+            message: self.message(),
+            retry_after: match self {
+                Self::RateLimited {
+                    retry_after_secs, ..
+                } => *retry_after_secs,
+                _ => None,
+            },
+            overflow: match self {
+                Self::PromptTooLarge(overflow) => Some((*overflow).into()),
+                _ => None,
+            },
+        }
+    }
+
+    /// Log the details the client does not get
+    pub fn log(&self) {
+        match self {
+            Self::Internal(detail) => tracing::error!("Chat request failed: {}", detail),
+            Self::ProviderUnavailable(detail) => {
+                tracing::warn!("Model provider unavailable: {}", detail);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl IntoResponse for ChatApiError {
+    fn into_response(self) -> Response {
+        self.log();
+
+        let body = self.body();
+        match body.retry_after {
+            Some(retry_after) => (
+                self.status(),
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(body),
+            )
+                .into_response(),
+            None => (self.status(), Json(body)).into_response(),
+        }
+    }
+}
+
+/// Map use case failures to their HTTP meaning
+impl From<RepositoryError> for ChatApiError {
+    fn from(err: RepositoryError) -> Self {
+        match err {
+            RepositoryError::SessionNotFound(_) => Self::NotFound("Session not found".to_string()),
+            RepositoryError::MessageNotFound(_) => {
+                Self::NotFound("Message not found in this session".to_string())
+            }
+            RepositoryError::AnnotationNotFound(_) => {
+                Self::NotFound("Annotation not found".to_string())
+            }
+            RepositoryError::JobNotFound(_) => Self::NotFound("Job not found".to_string()),
+            RepositoryError::WebhookNotFound(_) => Self::NotFound("Webhook not found".to_string()),
+            RepositoryError::ShareNotFound => Self::NotFound(err.to_string()),
+            RepositoryError::ShareUnavailable => Self::Gone(err.to_string()),
+            RepositoryError::InvalidSharePassword => Self::Unauthorized(err.to_string()),
+            RepositoryError::SharePasswordLocked => Self::RateLimited {
+                message: err.to_string(),
+                retry_after_secs: Some(SHARE_PASSWORD_LOCKOUT.as_secs()),
+            },
+            // Ownership checks report through validation errors
+            RepositoryError::ValidationError(msg) if msg.contains("not authorized") => {
+                Self::Forbidden(msg)
+            }
+            RepositoryError::ValidationError(msg) => Self::ValidationFailed(msg),
+            RepositoryError::AccountDisabled => Self::Forbidden(err.to_string()),
+            RepositoryError::GenerationInProgress(_) => {
+                Self::Conflict("A response is already being generated for this session".to_string())
+            }
+            RepositoryError::SessionConflict(_) | RepositoryError::WebhookLimitReached(_) => {
+                Self::Conflict(err.to_string())
+            }
+            RepositoryError::JobLimitReached(_) => Self::RateLimited {
+                message: err.to_string(),
+                retry_after_secs: None,
+            },
+            RepositoryError::PromptTooLarge(overflow) => Self::PromptTooLarge(overflow),
+            RepositoryError::ProviderUnavailable(msg) => Self::ProviderUnavailable(msg),
+            RepositoryError::DatabaseError(msg) => Self::Internal(msg),
+        }
+    }
+}
+
+impl From<LlmProviderError> for ChatApiError {
+    fn from(err: LlmProviderError) -> Self {
+        RepositoryError::from(err).into()
+    }
+}
+
+impl From<PollError> for ChatApiError {
+    fn from(err: PollError) -> Self {
+        match err {
+            PollError::NotFound => Self::NotFound("Generation not found".to_string()),
+            PollError::InvalidCursor => Self::ValidationFailed("Invalid cursor".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_repository_error_status_codes() {
+        let cases = [
+            (
+                RepositoryError::SessionNotFound(Uuid::nil()),
+                StatusCode::NOT_FOUND,
+            ),
+            (
+                RepositoryError::ValidationError("User not authorized".to_string()),
+                StatusCode::FORBIDDEN,
+            ),
+            (
+                RepositoryError::ValidationError("Content is empty".to_string()),
+                StatusCode::BAD_REQUEST,
+            ),
+            (RepositoryError::AccountDisabled, StatusCode::FORBIDDEN),
+            (
+                RepositoryError::GenerationInProgress(Uuid::nil()),
+                StatusCode::CONFLICT,
+            ),
+            (
+                RepositoryError::JobLimitReached(3),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            (RepositoryError::ShareUnavailable, StatusCode::GONE),
+            (
+                RepositoryError::InvalidSharePassword,
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                RepositoryError::SharePasswordLocked,
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            (
+                RepositoryError::ProviderUnavailable("timeout".to_string()),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                RepositoryError::DatabaseError("connection reset".to_string()),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+
+        for (err, status) in cases {
+            assert_eq!(ChatApiError::from(err).status(), status);
+        }
+    }
+
+    #[test]
+    fn test_provider_errors() {
+        let err = ChatApiError::from(LlmProviderError::ApiError("401 Unauthorized".to_string()));
+        assert_eq!(err.code(), ChatErrorCode::ProviderUnavailable);
+        // Provider details stay in the logs
+        assert!(!err.message().contains("401"));
+
+        let err = ChatApiError::from(LlmProviderError::InvalidRequest("No turns".to_string()));
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.message(), "No turns");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_response() {
+        let response = ChatApiError::RateLimited {
+            message: "Slow down".to_string(),
+            retry_after_secs: Some(30),
+        }
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "rate_limited");
+        assert_eq!(json["message"], "Slow down");
+        assert_eq!(json["retry_after"], 30);
+    }
+
+    #[tokio::test]
+    async fn test_prompt_too_large_response() {
+        let overflow = PromptOverflow {
+            prompt_tokens: 9000,
+            max_output_tokens: 2048,
+            context_window: 8192,
+        };
+        let response =
+            ChatApiError::from(RepositoryError::PromptTooLarge(overflow)).into_response();
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "prompt_too_large");
+        let message = json["message"].as_str().unwrap();
+        assert!(message.contains("at least 2856 tokens"), "{message}");
+        assert_eq!(json["overflow"]["context_window"], 8192);
+        assert_eq!(json["overflow"]["excess_tokens"], 2856);
+    }
+
+    #[tokio::test]
+    async fn test_internal_error_hides_detail() {
+        let response = ChatApiError::from(RepositoryError::DatabaseError("secret dsn".to_string()))
+            .into_response();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "internal");
+        assert_eq!(json["message"], "Internal server error");
+        assert!(json.get("retry_after").is_none());
+        assert!(json.get("overflow").is_none());
+    }
+}
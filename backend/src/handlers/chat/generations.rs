@@ -0,0 +1,121 @@
+//! Polling-mode message endpoints for clients that cannot use SSE
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use super::send_message_v2::execute_send;
+use crate::{
+    dto::chat::{
+        ChatErrorResponse, GenerationPollResponse, GenerationStartedResponse, PollGenerationQuery,
+        SendMessageRequest,
+    },
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+/// Upper bound on how long a single poll may wait for output
+pub const MAX_POLL_WAIT_SECS: u64 = 30;
+
+/// Send a message and buffer the LLM response for polling
+///
+/// Returns immediately with a generation ID; fetch the response with
+/// `GET /api/v1/chat/generations/{id}`. Validation, locking and persistence
+/// are the same as for the streaming endpoint.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Session not found (404)
+/// - User not authorized or account disabled (403)
+/// - Message validation fails (400)
+/// - A response is already being generated for the session (409)
+/// - Model not found (400)
+/// - Database error (500)
+/// - Model provider not configured or failing (503)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/sessions/{id}/generations",
+    tag = "chat",
+    request_body = SendMessageRequest,
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 202, description = "Generation started", body = GenerationStartedResponse),
+        (status = 400, description = "Invalid message content or model", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session or account is disabled", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 409, description = "A response is already being generated for this session", body = ChatErrorResponse),
+        (status = 422, description = "Message and context do not fit the model's context window", body = ChatErrorResponse),
+        (status = 429, description = "Guest message limit reached", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse),
+        (status = 503, description = "Model provider unavailable", body = ChatErrorResponse)
+    )
+)]
+pub async fn start_generation(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(request): Json<SendMessageRequest>,
+) -> Result<impl IntoResponse, ChatApiError> {
+    let stream = execute_send(&state, session_id, &auth_user, request).await?;
+    let generation_id = state
+        .generations
+        .start(auth_user.user_id, session_id, stream);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(GenerationStartedResponse {
+            generation_id,
+            session_id,
+        }),
+    ))
+}
+
+/// Fetch output of a generation past a cursor (long polling)
+///
+/// Waits up to `wait_secs` (at most 30) for new output. Poll again with the
+/// returned cursor while the status is `running`.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Generation not found, expired, or owned by another user (404)
+/// - Cursor was not returned by a previous poll (400)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/generations/{id}",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Generation ID"),
+        PollGenerationQuery
+    ),
+    responses(
+        (status = 200, description = "New output since the cursor", body = GenerationPollResponse),
+        (status = 400, description = "Invalid cursor", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Generation not found", body = ChatErrorResponse)
+    )
+)]
+pub async fn poll_generation(
+    State(generations): State<Arc<crate::application::chat::GenerationStore>>,
+    Path(generation_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Query(query): Query<PollGenerationQuery>,
+) -> Result<impl IntoResponse, ChatApiError> {
+    let wait = Duration::from_secs(query.wait_secs.min(MAX_POLL_WAIT_SECS));
+
+    let snapshot = generations
+        .poll(generation_id, auth_user.user_id, query.cursor, wait)
+        .await?;
+
+    Ok(Json(GenerationPollResponse::from_snapshot(
+        generation_id,
+        snapshot,
+    )))
+}
@@ -2,10 +2,8 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
     Json,
 };
-use serde::Deserialize;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -13,41 +11,40 @@ use crate::{
     application::chat::get_session_history::{
         GetSessionHistoryRequest, GetSessionHistoryUseCase,
     },
-    domain::chat::repository::ChatRepository,
-    handlers::chat::{dto::{GetHistoryResponse, MessageDto}, ChatState},
+    domain::chat::read_state::ReadStateRepository,
+    dto::chat::{AnnotationDto, ChatErrorResponse, GetHistoryResponse, HistoryQuery, MessageDto},
+    handlers::chat::{annotations::parse_filter, ChatApiError, ChatState},
     middleware::auth::AuthUser,
 };
 
-/// Query parameters for history endpoint
-#[derive(Debug, Deserialize)]
-pub struct HistoryQuery {
-    /// Maximum number of messages to return
-    pub limit: Option<u64>,
-}
-
 /// Get chat session message history
 ///
+/// Marks the returned messages as read for the current user. Each message
+/// carries the user's annotations; `annotation` narrows the history to
+/// annotated messages, e.g. `?annotation=bookmark`.
+///
 /// # Errors
 /// Returns HTTP error if:
+/// - Invalid annotation filter (400)
 /// - Session not found (404)
 /// - User not authorized (403)
 /// - Database error (500)
 #[utoipa::path(
     get,
-    path = "/api/chat/sessions/{id}/messages",
+    path = "/api/v1/chat/sessions/{id}/messages",
     tag = "chat",
     params(
         ("id" = Uuid, Path, description = "Session ID"),
-        ("limit" = Option<u64>, Query, description = "Maximum number of messages to return")
+        ("limit" = Option<u64>, Query, description = "Maximum number of messages to return"),
+        ("annotation" = Option<String>, Query, description = "Only messages with a matching annotation: `bookmark`, `label`, `label:<text>`, `reaction` or `reaction:<emoji>`")
     ),
     responses(
         (status = 200, description = "Message history retrieved", body = GetHistoryResponse),
+        (status = 400, description = "Invalid annotation filter", body = ChatErrorResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 404, description = "Session not found"),
-        (status = 500, description = "Internal server error")
-    ),
-    security(
-        ("bearer_auth" = [])
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
     )
 )]
 pub async fn get_session_history(
@@ -55,40 +52,62 @@ pub async fn get_session_history(
     Path(session_id): Path<Uuid>,
     Query(query): Query<HistoryQuery>,
     auth_user: AuthUser,
-) -> Result<Json<GetHistoryResponse>, (StatusCode, String)> {
-    // First, verify the session exists and belongs to the user
-    let session = state
-        .repository
-        .find_session_by_id(session_id)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-        .ok_or((StatusCode::NOT_FOUND, "Session not found".to_string()))?;
-
-    // Verify ownership
-    if session.user_id != auth_user.user_id {
-        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
-    }
-
-    let use_case = GetSessionHistoryUseCase::new(Arc::clone(&state.repository) as Arc<_>);
+) -> Result<Json<GetHistoryResponse>, ChatApiError> {
+    let annotation = parse_filter(query.annotation.as_deref())?;
+    let use_case = GetSessionHistoryUseCase::new(
+        Arc::clone(&state.repository) as Arc<_>,
+        Arc::clone(&state.repository) as Arc<_>,
+    );
 
     let request = GetSessionHistoryRequest {
         session_id,
+        user_id: auth_user.user_id,
         limit: query.limit,
+        annotation,
     };
 
-    let response = use_case
-        .execute(request)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let response = use_case.execute(request).await?;
+
+    // Fetching history counts as reading it; a failure here must not fail the fetch.
+    // A filtered history skips messages, so it does not move the read position.
+    if let Some(last) = response
+        .messages
+        .last()
+        .filter(|_| query.annotation.is_none())
+    {
+        if let Err(e) = state
+            .repository
+            .mark_read(auth_user.user_id, session_id, last.id)
+            .await
+        {
+            tracing::warn!(
+                "Failed to update read state for session {}: {}",
+                session_id,
+                e
+            );
+        }
+    }
 
+    let annotations = response.annotations;
     let messages = response
         .messages
         .into_iter()
-        .map(MessageDto::from)
+        .map(|message| {
+            let message_id = message.id;
+            MessageDto {
+                annotations: annotations
+                    .iter()
+                    .filter(|annotation| annotation.message_id == message_id)
+                    .cloned()
+                    .map(AnnotationDto::from)
+                    .collect(),
+                ..MessageDto::from(message)
+            }
+        })
         .collect();
 
     Ok(Json(GetHistoryResponse {
-        session: session.into(),
+        session: response.session.into(),
         messages,
     }))
 }
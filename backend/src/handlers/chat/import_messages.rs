@@ -0,0 +1,91 @@
+//! Bulk message import endpoint handler
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::chat::{
+        import_messages::ImportMessagesRequest as UseCaseRequest, ImportMessagesUseCase,
+    },
+    domain::chat::value_objects::MessageRole,
+    dto::chat::{ChatErrorResponse, ImportMessagesRequest, ImportMessagesResponse},
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+/// Append messages to a chat session without generating a reply
+///
+/// Seeds a conversation (for example one exported from another tool) as
+/// context for the next message. Either every message is saved or none is.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Session not found (404)
+/// - User not authorized (403)
+/// - A message or the batch size is invalid (400)
+/// - A response is being generated for the session (409)
+/// - Database error (500)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/sessions/{id}/messages/bulk",
+    tag = "chat",
+    request_body = ImportMessagesRequest,
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 201, description = "Messages saved", body = ImportMessagesResponse),
+        (status = 400, description = "Invalid role, content or number of messages", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 409, description = "A response is being generated for this session", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn import_messages(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(request): Json<ImportMessagesRequest>,
+) -> Result<(StatusCode, Json<ImportMessagesResponse>), ChatApiError> {
+    let messages = request
+        .messages
+        .into_iter()
+        .enumerate()
+        .map(|(index, message)| {
+            let role = message
+                .role
+                .parse::<MessageRole>()
+                .map_err(|e| ChatApiError::ValidationFailed(format!("Message {index}: {e}")))?;
+            Ok((role, message.content))
+        })
+        .collect::<Result<Vec<_>, ChatApiError>>()?;
+
+    let use_case = ImportMessagesUseCase::new(
+        Arc::clone(&state.repository) as Arc<_>,
+        Arc::clone(&state.repository) as Arc<_>,
+        state.policy,
+    )
+    .with_session_lock(Arc::clone(&state.session_lock), state.session_lock_policy);
+
+    let imported = use_case
+        .execute(UseCaseRequest {
+            session_id,
+            user_id: auth_user.user_id,
+            messages,
+        })
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ImportMessagesResponse {
+            messages: imported.into_iter().map(Into::into).collect(),
+        }),
+    ))
+}
@@ -0,0 +1,199 @@
+//! Batch completion job endpoint handlers
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::chat::{batch_jobs::BatchJobRequest, BatchJobsUseCase},
+    domain::chat::job::ChatJob,
+    dto::chat::{
+        ChatErrorResponse, CreateJobRequest, CreateJobResponse, JobCostEstimateDto, JobDto,
+        JobItemDto, JobResultsResponse,
+    },
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+    utils::pagination::Pagination,
+};
+
+fn use_case(state: &ChatState) -> BatchJobsUseCase {
+    BatchJobsUseCase::new(
+        Arc::clone(&state.repository) as Arc<_>,
+        Arc::clone(&state.provider_factory),
+        Arc::clone(&state.tokenizers),
+        Arc::clone(&state.model_pricing),
+        state.job_limits,
+    )
+    .with_suspension(Arc::clone(&state.repository) as Arc<_>)
+    .with_runner(Arc::clone(&state.job_runner))
+}
+
+fn job_dto(state: &ChatState, job: ChatJob) -> JobDto {
+    let cost_usd = state
+        .model_pricing
+        .cost(&job.model, job.prompt_tokens, job.completion_tokens);
+    JobDto::new(job, cost_usd)
+}
+
+/// Queue many prompts as a batch job
+///
+/// Each prompt is sent to the model as a single-turn completion, without
+/// session context, by background workers. Poll
+/// `GET /api/v1/chat/jobs/{id}` for progress and fetch the replies from
+/// `GET /api/v1/chat/jobs/{id}/results`.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Prompts or model are invalid (400)
+/// - Account is disabled (403)
+/// - User already has the maximum number of unfinished jobs (429)
+/// - Database error (500)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/jobs",
+    tag = "chat",
+    request_body = CreateJobRequest,
+    responses(
+        (status = 202, description = "Job queued", body = CreateJobResponse),
+        (status = 400, description = "Invalid prompts or model", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Account is disabled", body = ChatErrorResponse),
+        (status = 429, description = "Too many unfinished jobs", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn create_job(
+    State(state): State<ChatState>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateJobRequest>,
+) -> Result<impl IntoResponse, ChatApiError> {
+    let (job, estimate) = use_case(&state)
+        .submit(BatchJobRequest {
+            user_id: auth_user.user_id,
+            model_id: request.model_id,
+            prompts: request.prompts,
+        })
+        .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(CreateJobResponse {
+            job: job_dto(&state, job),
+            estimate: estimate.into(),
+        }),
+    ))
+}
+
+/// Estimate the tokens and highest cost of a batch job without queuing it
+///
+/// The cost assumes every reply uses the maximum completion tokens, so the
+/// actual cost is usually lower.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Prompts or model are invalid (400)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/jobs/estimate",
+    tag = "chat",
+    request_body = CreateJobRequest,
+    responses(
+        (status = 200, description = "Cost estimate", body = JobCostEstimateDto),
+        (status = 400, description = "Invalid prompts or model", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn estimate_job(
+    State(state): State<ChatState>,
+    _auth_user: AuthUser,
+    Json(request): Json<CreateJobRequest>,
+) -> Result<Json<JobCostEstimateDto>, ChatApiError> {
+    let estimate = use_case(&state).estimate(request.model_id.as_deref(), &request.prompts)?;
+
+    Ok(Json(estimate.into()))
+}
+
+/// Get the status and progress of a batch job
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Job not found or owned by another user (404)
+/// - Database error (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/jobs/{id}",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "Job status", body = JobDto),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Job not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn get_job(
+    State(state): State<ChatState>,
+    Path(job_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<Json<JobDto>, ChatApiError> {
+    let job = use_case(&state).status(job_id, auth_user.user_id).await?;
+
+    Ok(Json(job_dto(&state, job)))
+}
+
+/// Get the prompts and replies of a batch job, in prompt order
+///
+/// Available while the job runs; prompts not answered yet are `pending`.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Job not found or owned by another user (404)
+/// - Database error (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/jobs/{id}/results",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Job ID"),
+        Pagination
+    ),
+    responses(
+        (status = 200, description = "Page of job results", body = JobResultsResponse),
+        (status = 400, description = "Invalid pagination", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Job not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn get_job_results(
+    State(state): State<ChatState>,
+    Path(job_id): Path<Uuid>,
+    pagination: Pagination,
+    auth_user: AuthUser,
+) -> Result<Json<JobResultsResponse>, ChatApiError> {
+    let (job, items, total) = use_case(&state)
+        .results(
+            job_id,
+            auth_user.user_id,
+            pagination.index(),
+            pagination.per_page,
+        )
+        .await?;
+
+    Ok(Json(JobResultsResponse {
+        job: job_dto(&state, job),
+        items: items.into_iter().map(JobItemDto::from).collect(),
+        total,
+        page: pagination.page,
+        per_page: pagination.per_page,
+        total_pages: pagination.total_pages(total),
+    }))
+}
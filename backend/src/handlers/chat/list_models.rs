@@ -1,47 +1,16 @@
 //! List available LLM models endpoint
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use axum::{extract::State, http::HeaderMap, response::IntoResponse};
 
-use crate::handlers::chat::ChatState;
-
-/// Model information for API response
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
-pub struct ModelInfo {
-    pub id: String,
-    pub name: String,
-    pub provider: String,
-    pub description: Option<String>,
-    pub context_window: u32,
-    pub max_output_tokens: u32,
-    pub supports_streaming: bool,
-    pub supports_function_calling: bool,
-    pub cost_per_million_input_tokens: f64,
-    pub cost_per_million_output_tokens: f64,
-    pub tags: Vec<String>,
-    pub recommended_for: Vec<String>,
-}
-
-/// Model group information
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
-pub struct ModelGroupInfo {
-    pub name: String,
-    pub description: Option<String>,
-    pub models: Vec<String>,
-}
-
-/// API response with models and groups
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
-pub struct ListModelsResponse {
-    pub models: Vec<ModelInfo>,
-    pub groups: Vec<ModelGroupInfo>,
-    pub default_model: String,
-}
+use crate::dto::chat::{ChatErrorResponse, ListModelsResponse, ModelGroupInfo, ModelInfo};
+use crate::handlers::chat::{ChatApiError, ChatState};
+use crate::utils::http_cache::cached_json;
 
 /// Get list of available LLM models
 ///
 /// Returns all enabled models from the model registry along with their metadata.
+/// The response is cacheable (`Cache-Control`, `ETag`); send `If-None-Match`
+/// to get `304 Not Modified` while the list is unchanged.
 ///
 /// # Errors
 /// Returns HTTP error if:
@@ -52,12 +21,14 @@ pub struct ListModelsResponse {
     tag = "chat",
     responses(
         (status = 200, description = "List of available models", body = ListModelsResponse),
-        (status = 500, description = "Internal server error")
+        (status = 304, description = "Not modified since the ETag in If-None-Match"),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
     )
 )]
 pub async fn list_models(
     State(state): State<ChatState>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ChatApiError> {
     let registry = state.provider_factory.model_registry();
 
     // Get all enabled models
@@ -94,9 +65,10 @@ pub async fn list_models(
 
     let default_model = registry.default_model().id.clone();
 
-    Ok(Json(ListModelsResponse {
+    let response = ListModelsResponse {
         models,
         groups,
         default_model,
-    }))
+    };
+    Ok(cached_json(&headers, state.models_max_age, &response))
 }
@@ -1,82 +1,76 @@
 //! List user sessions endpoint handler
 
-use axum::{extract::{Query, State}, http::StatusCode, Json};
-use serde::Deserialize;
+use axum::{extract::State, Json};
 use std::sync::Arc;
 
 use crate::{
     application::chat::list_user_sessions::{
         ListUserSessionsRequest, ListUserSessionsUseCase,
     },
-    handlers::chat::{dto::{ListSessionsResponse, SessionDto}, ChatState},
+    domain::chat::read_state::ReadStateRepository,
+    dto::chat::{ChatErrorResponse, ListSessionsResponse, SessionDto},
+    handlers::chat::{ChatApiError, ChatState},
     middleware::auth::AuthUser,
+    utils::pagination::Pagination,
 };
 
-/// Query parameters for list sessions endpoint
-#[derive(Debug, Deserialize)]
-pub struct ListSessionsQuery {
-    /// Page number (0-indexed)
-    #[serde(default)]
-    pub page: u64,
-    /// Items per page
-    #[serde(default = "default_per_page")]
-    pub per_page: u64,
-}
-
-fn default_per_page() -> u64 {
-    20
-}
-
 /// List user's chat sessions with pagination
 ///
+/// Each session includes the current user's `unread_count`.
+///
 /// # Errors
 /// Returns HTTP error if:
 /// - Database error occurs (500)
 #[utoipa::path(
     get,
-    path = "/api/chat/sessions",
+    path = "/api/v1/chat/sessions",
     tag = "chat",
-    params(
-        ("page" = u64, Query, description = "Page number (0-indexed)"),
-        ("per_page" = u64, Query, description = "Items per page (default: 20)")
-    ),
+    params(Pagination),
     responses(
         (status = 200, description = "Sessions retrieved", body = ListSessionsResponse),
+        (status = 400, description = "Invalid pagination", body = ChatErrorResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 500, description = "Internal server error")
-    ),
-    security(
-        ("bearer_auth" = [])
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
     )
 )]
 pub async fn list_user_sessions(
     State(state): State<ChatState>,
-    Query(query): Query<ListSessionsQuery>,
+    pagination: Pagination,
     auth_user: AuthUser,
-) -> Result<Json<ListSessionsResponse>, (StatusCode, String)> {
+) -> Result<Json<ListSessionsResponse>, ChatApiError> {
     let use_case = ListUserSessionsUseCase::new(Arc::clone(&state.repository) as Arc<_>);
 
     let request = ListUserSessionsRequest {
         user_id: auth_user.user_id,
-        page: query.page,
-        per_page: query.per_page,
+        page: pagination.index(),
+        per_page: pagination.per_page,
     };
 
-    let response = use_case
-        .execute(request)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let response = use_case.execute(request).await?;
+
+    let session_ids: Vec<_> = response.sessions.iter().map(|s| s.id).collect();
+    let unread = state
+        .repository
+        .count_unread(auth_user.user_id, &session_ids)
+        .await?;
 
     let sessions = response
         .sessions
         .into_iter()
-        .map(SessionDto::from)
+        .map(|session| {
+            let unread_count = unread.get(&session.id).copied().unwrap_or(0);
+            SessionDto {
+                unread_count: Some(unread_count),
+                ..SessionDto::from(session)
+            }
+        })
         .collect();
 
     Ok(Json(ListSessionsResponse {
         sessions,
         total: response.total,
-        page: response.page,
-        per_page: response.per_page,
+        page: pagination.page,
+        per_page: pagination.per_page,
+        total_pages: pagination.total_pages(response.total),
     }))
 }
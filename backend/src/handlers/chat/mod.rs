@@ -2,31 +2,89 @@
 //!
 //! REST API endpoints for chat session and message management.
 
+mod analytics;
+mod annotations;
 mod create_session;
+mod delete_message;
 mod delete_session;
+mod error;
+mod generations;
 mod get_history;
+mod import_messages;
+mod jobs;
 mod list_models;
 mod list_sessions;
+mod read_state;
+mod rename_session;
 mod send_message;
 mod send_message_v2; // New provider-based handler
+mod share;
+mod stream_message;
+mod webhooks;
+mod ws;
 
-pub mod dto;
-
+pub use analytics::{get_chat_analytics, __path_get_chat_analytics};
+pub use annotations::{
+    add_annotation, list_annotations, remove_annotation, __path_add_annotation,
+    __path_list_annotations, __path_remove_annotation,
+};
 pub use create_session::{create_session, __path_create_session};
+pub use delete_message::{delete_message, __path_delete_message};
 pub use delete_session::{delete_session, __path_delete_session};
+pub use error::ChatApiError;
+pub use generations::{
+    poll_generation, start_generation, __path_poll_generation, __path_start_generation,
+};
 pub use get_history::{get_session_history, __path_get_session_history};
+pub use import_messages::{import_messages, __path_import_messages};
+pub use jobs::{
+    create_job, estimate_job, get_job, get_job_results, __path_create_job, __path_estimate_job,
+    __path_get_job, __path_get_job_results,
+};
 pub use list_models::{list_models, __path_list_models};
 pub use list_sessions::{list_user_sessions, __path_list_user_sessions};
-pub use send_message::{send_message, __path_send_message};
+pub use read_state::{
+    get_read_state, mark_session_read, __path_get_read_state, __path_mark_session_read,
+};
+pub use rename_session::{rename_session, __path_rename_session};
+pub use send_message::send_message;
 pub use send_message_v2::{send_message_v2, __path_send_message_v2};
+pub use share::{
+    create_share, list_shares, revoke_share, view_shared_session, __path_create_share,
+    __path_list_shares, __path_revoke_share, __path_view_shared_session,
+};
+pub use stream_message::{stream_message, __path_stream_message};
+pub use webhooks::{
+    create_webhook, delete_webhook, list_webhooks, test_webhook, __path_create_webhook,
+    __path_delete_webhook, __path_list_webhooks, __path_test_webhook,
+};
+pub use ws::{chat_socket, TypingEvent, TYPING_RELAY_CAPACITY, __path_chat_socket};
 
-use axum::{routing::{get, post, delete}, Router};
+use axum::{response::sse::KeepAlive, routing::{get, post, delete}};
+use futures::StreamExt;
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
 
 use crate::infrastructure::persistence::SeaOrmChatRepository;
 use crate::infrastructure::llm::ProviderFactory;
 use crate::application::chat::send_message::LlmConfig;
+use crate::application::chat::{GenerationStore, JobRunner, StreamMetrics, WebhookDispatcher};
+use crate::application::chat::generation::ChunkStream;
+use crate::application::chat::batch_jobs::BatchJobLimits;
+use crate::middleware::access::SecuredRouter;
+use crate::middleware::auth::AuthUser;
+use crate::middleware::chat_rate_limit::ChatRateLimitState;
+use crate::server::Shutdown;
+use crate::services::auth::guest::GuestQuota;
+use crate::services::stats_report::ModelPricing;
+use crate::services::tokenizer::TokenizerService;
+use crate::domain::chat::deletion::MessageDeletionPolicy;
+use crate::domain::chat::events::EventPublisher;
+use crate::domain::chat::lock::{LockPolicy, SessionLock};
+use crate::domain::chat::share::{SharePasswordAttempts, ShareSigner};
+use crate::domain::chat::policy::ChatPolicy;
 
 /// Chat API state
 #[derive(Clone)]
@@ -34,37 +92,208 @@ pub struct ChatState {
     pub repository: Arc<SeaOrmChatRepository>,
     pub llm_config: LlmConfig,
     pub provider_factory: Arc<ProviderFactory>,
+    /// Serializes message generation per session
+    pub session_lock: Arc<dyn SessionLock>,
+    /// Reject or wait when a session is already generating
+    pub session_lock_policy: LockPolicy,
+    /// Signs public share link slugs
+    pub share_signer: ShareSigner,
+    /// Wrong share passwords by slug and client IP
+    pub share_attempts: Arc<dyn SharePasswordAttempts>,
+    /// Limits on client-written messages
+    pub policy: ChatPolicy,
+    /// Delete or redact a message its owner deletes
+    pub message_deletion: MessageDeletionPolicy,
+    /// Buffered responses for polling-mode clients
+    pub generations: Arc<GenerationStore>,
+    /// Outcomes and durations of streamed replies
+    pub stream_metrics: Arc<StreamMetrics>,
+    /// Interval of the SSE heartbeat comments
+    pub stream_heartbeat_interval: Duration,
+    /// Abort a reply the client has not read for this long
+    pub stream_idle_timeout: Option<Duration>,
+    /// Per-model tokenizers for message token counts
+    pub tokenizers: Arc<TokenizerService>,
+    /// Client cache lifetime of the model list
+    pub models_max_age: Duration,
+    /// Limits on batch jobs
+    pub job_limits: BatchJobLimits,
+    /// Background workers woken when a batch job is queued
+    pub job_runner: Arc<JobRunner>,
+    /// Model prices for batch job cost estimates
+    pub model_pricing: Arc<ModelPricing>,
+    /// Delivers chat lifecycle events to their subscribers
+    pub events: Arc<dyn EventPublisher>,
+    /// Sends signed completion webhook deliveries
+    pub webhooks: Arc<WebhookDispatcher>,
+    /// Message allowance of guests (`None` when guest mode is off)
+    pub guests: Option<GuestQuota>,
+    /// Chat rate limit of messages sent over the WebSocket, which bypass
+    /// the per-request middleware (`None` without Valkey)
+    pub rate_limit: Option<ChatRateLimitState>,
+    /// Typing indicators passed between a user's WebSocket connections
+    pub typing_relay: broadcast::Sender<TypingEvent>,
+    /// Ends reply streams and closes WebSocket connections when the server stops
+    pub shutdown: Shutdown,
+}
+
+/// Error event ending a reply cut short by a server shutdown
+const SHUTTING_DOWN: &str = "Server is shutting down; the reply so far was saved";
+
+impl ChatState {
+    /// Heartbeat comments for SSE reply streams
+    ///
+    /// Some proxies close a connection that has been silent for a while,
+    /// which would cut a reply while the model is still thinking. The
+    /// heartbeats also make a connection that is gone fail on the next write,
+    /// so the reply is aborted instead of generated for nobody.
+    fn sse_keep_alive(&self) -> KeepAlive {
+        KeepAlive::new()
+            .interval(self.stream_heartbeat_interval)
+            .text("heartbeat")
+    }
+
+    /// End a reply stream with an error once the server starts shutting down
+    ///
+    /// Connections only get `SERVER_SHUTDOWN_TIMEOUT_SECS` to drain, which a
+    /// long reply could outlast; ending it at once lets the client retry on
+    /// another instance. Dropping the reply stream makes the stream
+    /// supervisor save the partial reply, as on a disconnect.
+    fn until_shutdown(&self, mut stream: ChunkStream) -> ChunkStream {
+        let cancelled = self.shutdown.cancelled();
+        Box::pin(async_stream::stream! {
+            tokio::pin!(cancelled);
+            loop {
+                let (next, interrupted) = tokio::select! {
+                    next = stream.next() => (next, false),
+                    () = &mut cancelled => (Some(Err(SHUTTING_DOWN.to_string())), true),
+                };
+                let Some(next) = next else { break };
+                yield next;
+                if interrupted {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Count a message against the allowance of a guest; other users pass
+    async fn charge_guest(&self, auth_user: &AuthUser) -> Result<(), ChatApiError> {
+        if !auth_user.guest {
+            return Ok(());
+        }
+        let Some(guests) = &self.guests else {
+            return Err(ChatApiError::Forbidden("Guest mode is disabled".to_string()));
+        };
+        match guests.consume(auth_user.user_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(ChatApiError::RateLimited {
+                message: "Guest message limit reached; register to keep chatting".to_string(),
+                retry_after_secs: None,
+            }),
+            Err(e) => Err(ChatApiError::Internal(e.to_string())),
+        }
+    }
 }
 
+/// Where the chat routes are nested
+pub const PREFIX: &str = "/api/v1/chat";
 
 /// Create chat routes
-#[must_use]
-pub fn routes(state: ChatState) -> Router {
-    Router::new()
+pub fn routes(state: ChatState) -> SecuredRouter {
+    SecuredRouter::nested(PREFIX)
         .route("/sessions", post(create_session))
         .route("/sessions", get(list_user_sessions))
         .route("/sessions/:id/messages", post(send_message))
         .route("/sessions/:id/messages", get(get_session_history))
-        .route("/sessions/:id", delete(delete_session))
+        .route("/sessions/:id/messages/bulk", post(import_messages))
+        .route("/sessions/:id/messages/:message_id", delete(delete_message))
+        .route(
+            "/sessions/:id/messages/:message_id/annotations",
+            post(add_annotation),
+        )
+        .route(
+            "/sessions/:id/messages/:message_id/annotations/:annotation_id",
+            delete(remove_annotation),
+        )
+        .route("/sessions/:id/annotations", get(list_annotations))
+        .route(
+            "/sessions/:id/read",
+            get(get_read_state).post(mark_session_read),
+        )
+        .route("/sessions/:id/share", get(list_shares).post(create_share))
+        .route("/sessions/:id/share/:share_id", delete(revoke_share))
+        .route("/sessions/:id", delete(delete_session).patch(rename_session))
+        .route("/analytics", get(get_chat_analytics))
         .with_state(state)
 }
 
 /// Create v2 chat routes with provider abstraction
-#[must_use]
-pub fn routes_v2(state: ChatState) -> Router {
-    Router::new()
+pub fn routes_v2(state: ChatState) -> SecuredRouter {
+    SecuredRouter::nested(PREFIX)
         .route("/sessions", post(create_session))
         .route("/sessions", get(list_user_sessions))
         .route("/sessions/:id/messages", post(send_message_v2)) // Use v2 handler with model selection
+        .route("/sessions/:id/messages/stream", post(stream_message))
+        .route("/sessions/:id/generations", post(start_generation))
         .route("/sessions/:id/messages", get(get_session_history))
-        .route("/sessions/:id", delete(delete_session))
+        .route("/sessions/:id/messages/bulk", post(import_messages))
+        .route("/sessions/:id/messages/:message_id", delete(delete_message))
+        .route(
+            "/sessions/:id/messages/:message_id/annotations",
+            post(add_annotation),
+        )
+        .route(
+            "/sessions/:id/messages/:message_id/annotations/:annotation_id",
+            delete(remove_annotation),
+        )
+        .route("/sessions/:id/annotations", get(list_annotations))
+        .route(
+            "/sessions/:id/read",
+            get(get_read_state).post(mark_session_read),
+        )
+        .route("/sessions/:id/share", get(list_shares).post(create_share))
+        .route("/sessions/:id/share/:share_id", delete(revoke_share))
+        .route("/sessions/:id", delete(delete_session).patch(rename_session))
+        .route("/analytics", get(get_chat_analytics))
+        .route("/jobs", post(create_job))
+        .route("/jobs/estimate", post(estimate_job))
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/webhooks/:id", delete(delete_webhook))
+        .route("/webhooks/:id/test", post(test_webhook))
         .with_state(state)
 }
 
 /// Create public routes for chat (no authentication required)
-#[must_use]
-pub fn public_routes(state: ChatState) -> Router {
-    Router::new()
+pub fn public_routes(state: ChatState) -> SecuredRouter {
+    SecuredRouter::nested(PREFIX)
         .route("/models", get(list_models)) // List available models - public endpoint
+        .route("/shared/:slug", get(view_shared_session)) // Shared conversations - public endpoint
         .with_state(state)
 }
+
+/// Create the WebSocket route
+///
+/// Kept apart from [`routes_v2`] so opening the socket is not counted by the
+/// chat rate limiter; each message sent over it is.
+pub fn socket_routes(state: ChatState) -> SecuredRouter {
+    SecuredRouter::nested(PREFIX)
+        .route("/ws", get(chat_socket))
+        .with_state(state)
+}
+
+/// Create routes for polling buffered generations and batch jobs
+///
+/// Kept apart from [`routes_v2`] so polls are not counted by the chat rate
+/// limiter; starting a generation or a job is.
+pub fn polling_routes(state: ChatState) -> SecuredRouter {
+    SecuredRouter::nested(PREFIX)
+        .route("/generations/:id", get(poll_generation))
+        .with_state(Arc::clone(&state.generations))
+        .merge(
+            SecuredRouter::nested(PREFIX)
+                .route("/jobs/:id", get(get_job))
+                .route("/jobs/:id/results", get(get_job_results))
+                .with_state(state),
+        )
+}
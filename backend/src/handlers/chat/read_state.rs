@@ -0,0 +1,98 @@
+//! Session read state endpoint handlers
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::chat::{session_read_state::MarkSessionReadRequest, SessionReadStateUseCase},
+    dto::chat::{ChatErrorResponse, MarkReadRequest, ReadStateResponse},
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+fn use_case(state: &ChatState) -> SessionReadStateUseCase {
+    SessionReadStateUseCase::new(
+        Arc::clone(&state.repository) as Arc<_>,
+        Arc::clone(&state.repository) as Arc<_>,
+    )
+}
+
+/// Get the current user's read position and unread count for a session
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Session not found (404)
+/// - User not authorized (403)
+/// - Database error (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/sessions/{id}/read",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "Read state retrieved", body = ReadStateResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn get_read_state(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<Json<ReadStateResponse>, ChatApiError> {
+    let response = use_case(&state).get(session_id, auth_user.user_id).await?;
+
+    Ok(Json(response.into()))
+}
+
+/// Mark a session as read up to a message (default: the newest message)
+///
+/// The read position never moves backwards.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Session or message not found (404)
+/// - User not authorized (403)
+/// - Database error (500)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/sessions/{id}/read",
+    tag = "chat",
+    request_body(content = Option<MarkReadRequest>, description = "Omit to mark every message read"),
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "Session marked as read", body = ReadStateResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session or message not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn mark_session_read(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    auth_user: AuthUser,
+    request: Option<Json<MarkReadRequest>>,
+) -> Result<Json<ReadStateResponse>, ChatApiError> {
+    let Json(request) = request.unwrap_or_default();
+
+    let response = use_case(&state)
+        .mark_read(MarkSessionReadRequest {
+            session_id,
+            user_id: auth_user.user_id,
+            message_id: request.message_id,
+        })
+        .await?;
+
+    Ok(Json(response.into()))
+}
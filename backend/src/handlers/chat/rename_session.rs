@@ -0,0 +1,66 @@
+//! Rename session endpoint handler
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::chat::{rename_session::RenameSessionRequest, RenameSessionUseCase},
+    dto::chat::{ChatErrorResponse, SessionDto, UpdateSessionRequest},
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+/// Rename a chat session
+///
+/// The request names the session `version` the client last read; if the
+/// session has been updated since, nothing is changed and 409 is returned so
+/// the client can reload and retry.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Title validation fails (400)
+/// - User not authorized (403)
+/// - Session not found (404)
+/// - Session changed since `version` (409)
+/// - Database error (500)
+#[utoipa::path(
+    patch,
+    path = "/api/v1/chat/sessions/{id}",
+    tag = "chat",
+    request_body = UpdateSessionRequest,
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "Session renamed", body = SessionDto),
+        (status = 400, description = "Invalid title", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 409, description = "Session was modified since the given version", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn rename_session(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(request): Json<UpdateSessionRequest>,
+) -> Result<Json<SessionDto>, ChatApiError> {
+    let use_case = RenameSessionUseCase::new(Arc::clone(&state.repository) as Arc<_>);
+
+    let session = use_case
+        .execute(RenameSessionRequest {
+            session_id,
+            user_id: auth_user.user_id,
+            title: request.title,
+            version: request.version,
+        })
+        .await?;
+
+    Ok(Json(session.into()))
+}
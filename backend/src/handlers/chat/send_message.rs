@@ -2,9 +2,8 @@
 
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
     response::{
-        sse::{Event, KeepAlive, Sse},
+        sse::{Event, Sse},
         IntoResponse,
     },
     Json,
@@ -15,8 +14,8 @@ use uuid::Uuid;
 
 use crate::{
     application::chat::send_message::{SendMessageRequest as UseCaseRequest, SendMessageUseCase},
-    domain::chat::repository::RepositoryError,
-    handlers::chat::{dto::SendMessageRequest, ChatState},
+    dto::chat::{ChatErrorResponse, SendMessageRequest},
+    handlers::chat::{ChatApiError, ChatState},
     middleware::auth::AuthUser,
 };
 
@@ -27,12 +26,14 @@ use crate::{
 /// # Errors
 /// Returns HTTP error if:
 /// - Session not found (404)
-/// - User not authorized (403)
+/// - User not authorized or account disabled (403)
 /// - Message validation fails (400)
+/// - A response is already being generated for the session (409)
 /// - Database error (500)
+/// - Model provider failing (503)
 #[utoipa::path(
     post,
-    path = "/api/chat/sessions/{id}/messages",
+    path = "/api/v1/chat/sessions/{id}/messages",
     tag = "chat",
     request_body = SendMessageRequest,
     params(
@@ -40,14 +41,13 @@ use crate::{
     ),
     responses(
         (status = 200, description = "SSE stream of message chunks", content_type = "text/event-stream"),
-        (status = 400, description = "Invalid message content"),
+        (status = 400, description = "Invalid message content", body = ChatErrorResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - user does not own this session"),
-        (status = 404, description = "Session not found"),
-        (status = 500, description = "Internal server error")
-    ),
-    security(
-        ("bearer_auth" = [])
+        (status = 403, description = "Forbidden - user does not own this session or account is disabled", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 409, description = "A response is already being generated for this session", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse),
+        (status = 503, description = "Model provider unavailable", body = ChatErrorResponse)
     )
 )]
 pub async fn send_message(
@@ -55,11 +55,17 @@ pub async fn send_message(
     Path(session_id): Path<Uuid>,
     auth_user: AuthUser,
     Json(request): Json<SendMessageRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
+) -> Result<impl IntoResponse, ChatApiError> {
     let use_case = SendMessageUseCase::new(
         Arc::clone(&state.repository) as Arc<_>,
         state.llm_config.clone(),
-    );
+    )
+    .with_session_lock(Arc::clone(&state.session_lock), state.session_lock_policy)
+    .with_stream_metrics(Arc::clone(&state.stream_metrics))
+    .with_stream_idle_timeout(state.stream_idle_timeout)
+    .with_tokenizers(Arc::clone(&state.tokenizers))
+    .with_events(Arc::clone(&state.events))
+    .with_suspension(Arc::clone(&state.repository) as Arc<_>);
 
     let use_case_request = UseCaseRequest {
         session_id,
@@ -68,21 +74,12 @@ pub async fn send_message(
     };
 
     // Execute use case to get streaming response
-    let stream = use_case.execute(use_case_request).await.map_err(|e| match e {
-        RepositoryError::SessionNotFound(_) => {
-            (StatusCode::NOT_FOUND, "Session not found".to_string())
-        }
-        RepositoryError::ValidationError(msg) if msg.contains("not authorized") => {
-            (StatusCode::FORBIDDEN, msg)
-        }
-        RepositoryError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
-        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-    })?;
+    let stream = use_case.execute(use_case_request).await?;
 
     // Convert to SSE stream
-    let sse_stream = convert_to_sse_stream(stream);
+    let sse_stream = convert_to_sse_stream(state.until_shutdown(stream));
 
-    Ok(Sse::new(sse_stream).keep_alive(KeepAlive::default()))
+    Ok(Sse::new(sse_stream).keep_alive(state.sse_keep_alive()))
 }
 
 /// Convert application stream to SSE event stream
@@ -1,11 +1,12 @@
 //! Send message endpoint handler with provider abstraction and model selection
 
 use axum::{
+    body::{Body, Bytes},
     extract::{Path, State},
-    http::StatusCode,
+    http::{header, HeaderMap},
     response::{
-        sse::{Event, KeepAlive, Sse},
-        IntoResponse,
+        sse::{Event, Sse},
+        IntoResponse, Response,
     },
     Json,
 };
@@ -14,26 +15,33 @@ use std::{convert::Infallible, sync::Arc};
 use uuid::Uuid;
 
 use crate::{
-    application::chat::{SendMessageUseCaseV2, send_message_v2::{
+    application::chat::{generation::ChunkStream, SendMessageUseCaseV2, send_message_v2::{
         SendMessageRequest as UseCaseRequest, UseCaseConfig,
     }},
-    domain::chat::repository::RepositoryError,
-    handlers::chat::{dto::SendMessageRequest, ChatState},
+    dto::chat::{ChatErrorResponse, SendMessageRequest, StreamLine},
+    handlers::chat::{ChatApiError, ChatState},
     middleware::auth::AuthUser,
 };
 
+/// Media type of the newline-delimited JSON stream
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
 /// Send a message in a chat session with model selection and stream LLM response
 ///
-/// Returns Server-Sent Events (SSE) stream with message chunks
+/// Returns Server-Sent Events (SSE) stream with message chunks, or
+/// newline-delimited JSON ([`StreamLine`] per line) when the request sends
+/// `Accept: application/x-ndjson`
 ///
 /// # Errors
 /// Returns HTTP error if:
 /// - Session not found (404)
-/// - User not authorized (403)
-/// - Message validation fails (400)
+/// - User not authorized or account disabled (403)
+/// - Message validation fails or the temperature is out of range (400)
+/// - A response is already being generated for the session (409)
+/// - A guest has used its message allowance (429)
 /// - Model not found (400)
-/// - Provider error (500)
 /// - Database error (500)
+/// - Model provider not configured or failing (503)
 #[utoipa::path(
     post,
     path = "/api/v1/chat/sessions/{id}/messages",
@@ -43,23 +51,74 @@ use crate::{
         ("id" = Uuid, Path, description = "Session ID")
     ),
     responses(
-        (status = 200, description = "SSE stream of message chunks", content_type = "text/event-stream"),
-        (status = 400, description = "Invalid message content or model"),
+        (status = 200, description = "Stream of message chunks: SSE, or one StreamLine per line with Accept: application/x-ndjson",
+            content(
+                (String = "text/event-stream"),
+                (StreamLine = "application/x-ndjson")
+            )),
+        (status = 400, description = "Invalid message content or model", body = ChatErrorResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - user does not own this session"),
-        (status = 404, description = "Session not found"),
-        (status = 500, description = "Internal server error")
-    ),
-    security(
-        ("bearer_auth" = [])
+        (status = 403, description = "Forbidden - user does not own this session or account is disabled", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 409, description = "A response is already being generated for this session", body = ChatErrorResponse),
+        (status = 422, description = "Message and context do not fit the model's context window", body = ChatErrorResponse),
+        (status = 429, description = "Guest message limit reached", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse),
+        (status = 503, description = "Model provider unavailable", body = ChatErrorResponse)
     )
 )]
 pub async fn send_message_v2(
     State(state): State<ChatState>,
     Path(session_id): Path<Uuid>,
     auth_user: AuthUser,
+    headers: HeaderMap,
     Json(request): Json<SendMessageRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
+) -> Result<Response, ChatApiError> {
+    let stream = execute_send(&state, session_id, &auth_user, request).await?;
+
+    if accepts_ndjson(&headers) {
+        return Ok((
+            [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+            Body::from_stream(convert_to_ndjson_stream(stream)),
+        )
+            .into_response());
+    }
+
+    // Convert to SSE stream
+    let sse_stream = convert_to_sse_stream(stream);
+
+    Ok(Sse::new(sse_stream)
+        .keep_alive(state.sse_keep_alive())
+        .into_response())
+}
+
+/// Whether the client asked for NDJSON instead of SSE
+fn accepts_ndjson(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media_type| {
+            media_type
+                .split(';')
+                .next()
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case(NDJSON_CONTENT_TYPE))
+        })
+}
+
+/// Run the send-message use case and return its chunk stream
+///
+/// Shared by the SSE handler and the polling-mode handler so both paths
+/// validate, lock, charge guests and persist identically.
+pub(super) async fn execute_send(
+    state: &ChatState,
+    session_id: Uuid,
+    auth_user: &AuthUser,
+    request: SendMessageRequest,
+) -> Result<ChunkStream, ChatApiError> {
+    state.charge_guest(auth_user).await?;
+
     // Create use case with shared provider factory
     let config = UseCaseConfig {
         max_context_messages: state.llm_config.max_context_messages,
@@ -70,45 +129,31 @@ pub async fn send_message_v2(
         Arc::clone(&state.repository) as Arc<_>,
         Arc::clone(&state.provider_factory),
         config,
-    );
+    )
+    .with_session_lock(Arc::clone(&state.session_lock), state.session_lock_policy)
+    .with_stream_metrics(Arc::clone(&state.stream_metrics))
+    .with_stream_idle_timeout(state.stream_idle_timeout)
+    .with_tokenizers(Arc::clone(&state.tokenizers))
+    .with_events(Arc::clone(&state.events))
+    .with_suspension(Arc::clone(&state.repository) as Arc<_>)
+    .with_chat_defaults(Arc::clone(&state.repository) as Arc<_>);
 
     let use_case_request = UseCaseRequest {
         session_id,
         user_id: auth_user.user_id,
         content: request.content,
         model_id: request.model_id, // Pass model selection
+        temperature: request.temperature,
     };
 
     // Execute use case to get streaming response
-    let stream = use_case.execute(use_case_request).await.map_err(|e| match e {
-        RepositoryError::SessionNotFound(_) => {
-            (StatusCode::NOT_FOUND, "Session not found".to_string())
-        }
-        RepositoryError::ValidationError(msg) if msg.contains("not authorized") => {
-            (StatusCode::FORBIDDEN, msg)
-        }
-        RepositoryError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
-        RepositoryError::DatabaseError(msg) if msg.contains("Model") || msg.contains("Provider") => {
-            (StatusCode::BAD_REQUEST, msg)
-        }
-        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-    })?;
-
-    // Convert to SSE stream
-    let sse_stream = convert_to_sse_stream(stream);
+    let stream = use_case.execute(use_case_request).await?;
 
-    Ok(Sse::new(sse_stream).keep_alive(KeepAlive::default()))
+    Ok(state.until_shutdown(stream))
 }
 
 /// Convert application stream to SSE event stream
-fn convert_to_sse_stream(
-    stream: std::pin::Pin<
-        Box<
-            dyn Stream<Item = Result<crate::application::chat::send_message_v2::StreamChunk, String>>
-                + Send,
-        >,
-    >,
-) -> impl Stream<Item = Result<Event, Infallible>> {
+fn convert_to_sse_stream(stream: ChunkStream) -> impl Stream<Item = Result<Event, Infallible>> {
     use futures::StreamExt;
 
     stream.map(|result| match result {
@@ -132,3 +177,39 @@ fn convert_to_sse_stream(
         }
     })
 }
+
+/// Convert application stream to newline-delimited JSON
+///
+/// Emits a `usage` line just before `final`, counted from the content lines.
+fn convert_to_ndjson_stream(stream: ChunkStream) -> impl Stream<Item = Result<Bytes, Infallible>> {
+    use futures::StreamExt;
+
+    stream
+        .scan((0u64, 0u64), |(chunks, characters), result| {
+            let lines = match result {
+                Ok(chunk) if chunk.is_final => vec![
+                    StreamLine::Usage {
+                        chunks: *chunks,
+                        characters: *characters,
+                    },
+                    StreamLine::Final,
+                ],
+                Ok(chunk) => {
+                    *chunks += 1;
+                    *characters += chunk.content.chars().count() as u64;
+                    vec![StreamLine::Content {
+                        content: chunk.content,
+                    }]
+                }
+                Err(error) => vec![StreamLine::Error { error }],
+            };
+            futures::future::ready(Some(lines))
+        })
+        .flat_map(|lines| {
+            futures::stream::iter(lines.into_iter().map(|line| {
+                let mut json = serde_json::to_vec(&line).unwrap_or_default();
+                json.push(b'\n');
+                Ok(Bytes::from(json))
+            }))
+        })
+}
@@ -0,0 +1,216 @@
+//! Public share link endpoint handlers
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use chrono::Duration;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::chat::{share_session::CreateShareRequest as UseCaseRequest, ShareSessionUseCase},
+    dto::chat::{
+        ChatErrorResponse, CreateShareRequest, ListSharesResponse, ShareDto,
+        SharedConversationResponse,
+    },
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::{auth::AuthUser, client_ip::ClientIp},
+};
+
+/// Header carrying the password of a protected share
+pub const SHARE_PASSWORD_HEADER: &str = "x-share-password";
+
+fn use_case(state: &ChatState) -> ShareSessionUseCase {
+    ShareSessionUseCase::new(
+        Arc::clone(&state.repository) as Arc<_>,
+        Arc::clone(&state.repository) as Arc<_>,
+        state.share_signer.clone(),
+        Arc::clone(&state.share_attempts),
+    )
+}
+
+/// Create a read-only public link to a session
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Expiry or password is invalid (400)
+/// - User not authorized (403)
+/// - Session not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/sessions/{id}/share",
+    tag = "chat",
+    request_body(content = Option<CreateShareRequest>, description = "Omit for a link without expiry or password"),
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 201, description = "Share link created", body = ShareDto),
+        (status = 400, description = "Invalid expiry or password", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn create_share(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    auth_user: AuthUser,
+    request: Option<Json<CreateShareRequest>>,
+) -> Result<(StatusCode, Json<ShareDto>), ChatApiError> {
+    let Json(request) = request.unwrap_or_default();
+
+    let expires_in = request
+        .expires_in_secs
+        .map(|secs| {
+            i64::try_from(secs)
+                .ok()
+                .and_then(Duration::try_seconds)
+                .ok_or_else(|| {
+                    ChatApiError::ValidationFailed("expires_in_secs is too large".to_string())
+                })
+        })
+        .transpose()?;
+
+    let share = use_case(&state)
+        .create(UseCaseRequest {
+            session_id,
+            user_id: auth_user.user_id,
+            expires_in,
+            password: request.password,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(share.into())))
+}
+
+/// List the share links of a session, including revoked and expired ones
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - User not authorized (403)
+/// - Session not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/sessions/{id}/share",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "Share links retrieved", body = ListSharesResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn list_shares(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<Json<ListSharesResponse>, ChatApiError> {
+    let shares = use_case(&state).list(session_id, auth_user.user_id).await?;
+
+    Ok(Json(ListSharesResponse {
+        shares: shares.into_iter().map(ShareDto::from).collect(),
+    }))
+}
+
+/// Revoke a share link; the public URL stops working immediately
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - User not authorized (403)
+/// - Session or share not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chat/sessions/{id}/share/{share_id}",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Session ID"),
+        ("share_id" = Uuid, Path, description = "Share ID")
+    ),
+    responses(
+        (status = 204, description = "Share link revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session", body = ChatErrorResponse),
+        (status = 404, description = "Session or share not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn revoke_share(
+    State(state): State<ChatState>,
+    Path((session_id, share_id)): Path<(Uuid, Uuid)>,
+    auth_user: AuthUser,
+) -> Result<StatusCode, ChatApiError> {
+    use_case(&state)
+        .revoke(session_id, share_id, auth_user.user_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// View a shared conversation (no authentication required)
+///
+/// Each successful view increments the share's view counter. A client that
+/// sends too many wrong passwords for a share is locked out of it for a while.
+/// Failures are counted per client address, so a client whose address cannot
+/// be resolved may not send a password at all.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Password missing or wrong (401)
+/// - Password sent by a client without a known address (403)
+/// - Share not found (404)
+/// - Share revoked or expired (410)
+/// - Too many wrong passwords (429)
+/// - Database error (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/shared/{slug}",
+    tag = "chat",
+    params(
+        ("slug" = String, Path, description = "Share slug"),
+        ("X-Share-Password" = Option<String>, Header, description = "Password of a protected share")
+    ),
+    responses(
+        (status = 200, description = "Shared conversation", body = SharedConversationResponse),
+        (status = 401, description = "Password missing or incorrect", body = ChatErrorResponse),
+        (status = 403, description = "Password sent by a client without a known address", body = ChatErrorResponse),
+        (status = 404, description = "Share not found", body = ChatErrorResponse),
+        (status = 410, description = "Share revoked or expired", body = ChatErrorResponse),
+        (status = 429, description = "Too many wrong passwords", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn view_shared_session(
+    State(state): State<ChatState>,
+    Path(slug): Path<String>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+) -> Result<Json<SharedConversationResponse>, ChatApiError> {
+    let password = headers
+        .get(SHARE_PASSWORD_HEADER)
+        .and_then(|value| value.to_str().ok());
+    // Without an address every such client would share one lockout
+    let viewer = match (client_ip.0, password) {
+        (Some(ip), _) => ip.to_string(),
+        (None, None) => String::new(),
+        (None, Some(_)) => {
+            return Err(ChatApiError::Forbidden(
+                "Share passwords need a known client address".to_string(),
+            ))
+        }
+    };
+
+    let shared = use_case(&state).view(&slug, password, &viewer).await?;
+
+    Ok(Json(shared.into()))
+}
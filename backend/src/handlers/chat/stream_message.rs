@@ -0,0 +1,110 @@
+//! Send message endpoint streaming the reply as typed Server-Sent Events
+
+use axum::{
+    extract::{Path, State},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse,
+    },
+    Json,
+};
+use futures::{Stream, StreamExt};
+use serde_json::json;
+use std::convert::Infallible;
+use uuid::Uuid;
+
+use super::send_message_v2::execute_send;
+use crate::{
+    application::chat::generation::ChunkStream,
+    dto::chat::{ChatErrorResponse, SendMessageRequest},
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+/// Event carrying the next piece of the reply
+pub const CHUNK_EVENT: &str = "chunk";
+
+/// Event sent once the reply is complete and saved
+pub const DONE_EVENT: &str = "done";
+
+/// Event sent when generation fails; it ends the stream
+pub const ERROR_EVENT: &str = "error";
+
+/// Send a message and stream the LLM response as named SSE events
+///
+/// Emits `chunk` events (`{"content": "..."}`), then a single `done` event
+/// (`{"chunks": n, "characters": n}`), or an `error` event
+/// (`{"error": "..."}`) if generation fails. Heartbeat comments keep the
+/// connection alive while the model is thinking. Closing the connection
+/// cancels generation; the content received so far is saved.
+///
+/// Validation, locking and persistence are the same as for
+/// `POST /api/v1/chat/sessions/{id}/messages`.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Session not found (404)
+/// - User not authorized or account disabled (403)
+/// - Message validation fails or the temperature is out of range (400)
+/// - A response is already being generated for the session (409)
+/// - A guest has used its message allowance (429)
+/// - Model not found (400)
+/// - Database error (500)
+/// - Model provider not configured or failing (503)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/sessions/{id}/messages/stream",
+    tag = "chat",
+    request_body = SendMessageRequest,
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "SSE stream of chunk events, ended by a done or error event", content_type = "text/event-stream"),
+        (status = 400, description = "Invalid message content or model", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - user does not own this session or account is disabled", body = ChatErrorResponse),
+        (status = 404, description = "Session not found", body = ChatErrorResponse),
+        (status = 409, description = "A response is already being generated for this session", body = ChatErrorResponse),
+        (status = 422, description = "Message and context do not fit the model's context window", body = ChatErrorResponse),
+        (status = 429, description = "Guest message limit reached", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse),
+        (status = 503, description = "Model provider unavailable", body = ChatErrorResponse)
+    )
+)]
+pub async fn stream_message(
+    State(state): State<ChatState>,
+    Path(session_id): Path<Uuid>,
+    auth_user: AuthUser,
+    Json(request): Json<SendMessageRequest>,
+) -> Result<impl IntoResponse, ChatApiError> {
+    let stream = execute_send(&state, session_id, &auth_user, request).await?;
+
+    // Dropping the response on disconnect drops the chunk stream, which the
+    // stream supervisor reports as a disconnect and saves the partial reply
+    Ok(Sse::new(convert_to_event_stream(stream)).keep_alive(state.sse_keep_alive()))
+}
+
+/// Convert application stream to named SSE events
+///
+/// Counts the content sent so the `done` event can report the reply size.
+fn convert_to_event_stream(stream: ChunkStream) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream.scan((0u64, 0u64), |(chunks, characters), result| {
+        let event = match result {
+            Ok(chunk) if chunk.is_final => Event::default()
+                .event(DONE_EVENT)
+                .data(json!({ "chunks": *chunks, "characters": *characters }).to_string()),
+            Ok(chunk) => {
+                *chunks += 1;
+                *characters += chunk.content.chars().count() as u64;
+                Event::default()
+                    .event(CHUNK_EVENT)
+                    .data(json!({ "content": chunk.content }).to_string())
+            }
+            Err(error) => Event::default()
+                .event(ERROR_EVENT)
+                .data(json!({ "error": error }).to_string()),
+        };
+        futures::future::ready(Some(Ok(event)))
+    })
+}
@@ -0,0 +1,151 @@
+//! Completion webhook endpoint handlers
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::chat::ChatWebhooksUseCase,
+    dto::chat::{
+        ChatErrorResponse, CreateWebhookRequest, ListWebhooksResponse, WebhookDto,
+        WebhookTestResponse,
+    },
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+};
+
+fn use_case(state: &ChatState) -> ChatWebhooksUseCase {
+    ChatWebhooksUseCase::new(
+        Arc::clone(&state.repository) as Arc<_>,
+        Arc::clone(&state.webhooks),
+    )
+}
+
+/// Register a webhook called when one of the user's replies completes
+///
+/// The response is the only one carrying the signing secret.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - URL is invalid or points to a private address (400)
+/// - User already has the maximum number of webhooks (409)
+/// - Database error (500)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/webhooks",
+    tag = "chat",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered, with its secret", body = WebhookDto),
+        (status = 400, description = "Invalid URL", body = ChatErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Webhook limit reached", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn create_webhook(
+    State(state): State<ChatState>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookDto>), ChatApiError> {
+    let webhook = use_case(&state)
+        .create(auth_user.user_id, &request.url)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(WebhookDto::with_secret(webhook))))
+}
+
+/// List the user's webhooks with the outcome of their last delivery
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Database error (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/webhooks",
+    tag = "chat",
+    responses(
+        (status = 200, description = "Webhooks retrieved", body = ListWebhooksResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn list_webhooks(
+    State(state): State<ChatState>,
+    auth_user: AuthUser,
+) -> Result<Json<ListWebhooksResponse>, ChatApiError> {
+    let webhooks = use_case(&state).list(auth_user.user_id).await?;
+
+    Ok(Json(ListWebhooksResponse {
+        webhooks: webhooks.into_iter().map(WebhookDto::from).collect(),
+    }))
+}
+
+/// Delete a webhook; pending retries of its deliveries are dropped
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Webhook not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chat/webhooks/{id}",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 204, description = "Webhook deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Webhook not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn delete_webhook(
+    State(state): State<ChatState>,
+    Path(webhook_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<StatusCode, ChatApiError> {
+    use_case(&state)
+        .delete(auth_user.user_id, webhook_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Send a signed `webhook.test` delivery and report how the receiver answered
+///
+/// The delivery is attempted once, without retries. A receiver that fails
+/// still yields `200` with `delivered: false`.
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Webhook not found (404)
+/// - Database error (500)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/webhooks/{id}/test",
+    tag = "chat",
+    params(
+        ("id" = Uuid, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Test delivery attempted", body = WebhookTestResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Webhook not found", body = ChatErrorResponse),
+        (status = 500, description = "Internal server error", body = ChatErrorResponse)
+    )
+)]
+pub async fn test_webhook(
+    State(state): State<ChatState>,
+    Path(webhook_id): Path<Uuid>,
+    auth_user: AuthUser,
+) -> Result<Json<WebhookTestResponse>, ChatApiError> {
+    let delivery = use_case(&state).test(auth_user.user_id, webhook_id).await?;
+
+    Ok(Json(delivery.into()))
+}
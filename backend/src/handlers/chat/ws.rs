@@ -0,0 +1,355 @@
+//! WebSocket transport for chat sessions
+//!
+//! `GET /api/v1/chat/ws` upgrades to a socket that carries JSON text frames
+//! ([`WsClientMessage`] in, [`WsServerMessage`] out) for any number of the
+//! user's sessions at once. A `send` runs the same use case as the SSE
+//! endpoints (validation, guest allowance, session lock, persistence) and
+//! streams the reply as `chunk` frames on a task of its own, so the client
+//! can `cancel` it while it is generating. Cancelling, like closing the
+//! socket, drops the reply stream: the stream supervisor saves what was
+//! generated so far.
+//!
+//! Typing indicators are relayed between the sockets of the same user on
+//! this instance, and the assistant is reported as typing while a reply is
+//! generated.
+//!
+//! When the server shuts down, the socket is closed with `1001 Going Away`
+//! so the client reconnects to another instance.
+
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::StatusCode,
+    response::IntoResponse,
+};
+use futures::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use tokio::{
+    sync::{broadcast, mpsc},
+    task::JoinHandle,
+};
+use uuid::Uuid;
+
+use super::send_message_v2::execute_send;
+use crate::{
+    dto::chat::{SendMessageRequest, TypingRole, WsClientMessage, WsServerMessage},
+    handlers::chat::{ChatApiError, ChatState},
+    middleware::auth::AuthUser,
+    services::valkey::chat_rate_limit::{self, ChatRateLimitConfig},
+};
+
+/// Server messages buffered for a socket that is slow to read
+const OUTBOX_CAPACITY: usize = 64;
+
+/// Typing indicators buffered per socket before old ones are dropped
+pub const TYPING_RELAY_CAPACITY: usize = 256;
+
+/// A user started or stopped typing on one of their sockets
+#[derive(Debug, Clone, Copy)]
+pub struct TypingEvent {
+    pub user_id: Uuid,
+    /// Socket the user types in, which does not get the event back
+    pub connection_id: Uuid,
+    pub session_id: Uuid,
+    pub typing: bool,
+}
+
+/// Open a WebSocket carrying chat messages for the user's sessions
+///
+/// Authenticate the upgrade request like any other (`Authorization`
+/// header). Frames are JSON text: see `WsClientMessage` and
+/// `WsServerMessage`. Each `send` counts against the chat rate limit.
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/ws",
+    tag = "chat",
+    responses(
+        (status = 101, description = "Switching to the WebSocket protocol"),
+        (status = 400, description = "Not a WebSocket upgrade request"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn chat_socket(
+    State(state): State<ChatState>,
+    auth_user: AuthUser,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    upgrade.on_upgrade(move |socket| serve(socket, state, auth_user))
+}
+
+/// Run one socket until the client closes it
+async fn serve(socket: WebSocket, state: ChatState, auth_user: AuthUser) {
+    let connection_id = Uuid::new_v4();
+    let (mut sink, mut incoming) = socket.split();
+    let (outbox, mut outgoing) = mpsc::channel::<WsServerMessage>(OUTBOX_CAPACITY);
+    let mut typing = state.typing_relay.subscribe();
+    let mut generations: HashMap<Uuid, JoinHandle<()>> = HashMap::new();
+    let mut heartbeat = tokio::time::interval(state.stream_heartbeat_interval);
+    // Whether the account is a sandbox one does not change while connected
+    let limits = match &state.rate_limit {
+        Some(rate_limit) => Some(rate_limit.config_for(auth_user.user_id).await.clone()),
+        None => None,
+    };
+    let shutdown = state.shutdown.cancelled();
+    tokio::pin!(shutdown);
+
+    'socket: loop {
+        // Replies to the client's own messages skip the outbox, which the
+        // generation tasks may have filled while this loop waits to drain it
+        let replies = tokio::select! {
+            frame = incoming.next() => {
+                let text = match frame {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                    // Pongs are answered by axum, binary frames are not used
+                    Some(Ok(_)) => continue,
+                };
+                match serde_json::from_str::<WsClientMessage>(&text) {
+                    Ok(message) => handle(
+                        &state,
+                        limits.as_ref(),
+                        &auth_user,
+                        connection_id,
+                        message,
+                        &outbox,
+                        &mut generations,
+                    )
+                    .await,
+                    Err(e) => vec![api_error(None, &ChatApiError::ValidationFailed(e.to_string()))],
+                }
+            }
+            Some(message) = outgoing.recv() => vec![message],
+            event = typing.recv() => match event {
+                Ok(event) if event.user_id == auth_user.user_id
+                    && event.connection_id != connection_id =>
+                {
+                    vec![WsServerMessage::Typing {
+                        session_id: event.session_id,
+                        role: TypingRole::User,
+                        typing: event.typing,
+                    }]
+                }
+                // Indicators of other users, or ones missed while lagging
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = heartbeat.tick() => {
+                if sink.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            () = &mut shutdown => {
+                let close = CloseFrame {
+                    code: close_code::AWAY,
+                    reason: "Server is shutting down".into(),
+                };
+                let _ = sink.send(Message::Close(Some(close))).await;
+                break;
+            }
+        };
+
+        for reply in replies {
+            let Ok(json) = serde_json::to_string(&reply) else {
+                continue;
+            };
+            if sink.send(Message::Text(json)).await.is_err() {
+                break 'socket;
+            }
+        }
+    }
+
+    // Replies still generating are cut short and saved as they are
+    for generation in generations.into_values() {
+        generation.abort();
+    }
+}
+
+/// Act on one client message, returning the replies for the client
+async fn handle(
+    state: &ChatState,
+    limits: Option<&ChatRateLimitConfig>,
+    auth_user: &AuthUser,
+    connection_id: Uuid,
+    message: WsClientMessage,
+    outbox: &mpsc::Sender<WsServerMessage>,
+    generations: &mut HashMap<Uuid, JoinHandle<()>>,
+) -> Vec<WsServerMessage> {
+    match message {
+        WsClientMessage::Send {
+            session_id,
+            content,
+            model_id,
+            temperature,
+        } => {
+            // The session lock would reject the send as well, or hold it
+            // until this reply is saved
+            if generations
+                .get(&session_id)
+                .is_some_and(|generation| !generation.is_finished())
+            {
+                return vec![api_error(
+                    Some(session_id),
+                    &ChatApiError::Conflict(
+                        "A response is already being generated for this session".to_string(),
+                    ),
+                )];
+            }
+            if let Err(e) = check_rate_limit(state, limits, auth_user).await {
+                return vec![api_error(Some(session_id), &e)];
+            }
+
+            let request = SendMessageRequest {
+                content,
+                model_id,
+                temperature,
+            };
+            // Waiting for the session lock must not stall the other sessions
+            // of the socket, so the whole send runs on the task
+            let generation = tokio::spawn(generate(
+                state.clone(),
+                auth_user.clone(),
+                session_id,
+                request,
+                outbox.clone(),
+            ));
+            generations.retain(|_, generation| !generation.is_finished());
+            generations.insert(session_id, generation);
+            Vec::new()
+        }
+        WsClientMessage::Cancel { session_id } => match generations.remove(&session_id) {
+            Some(generation) if !generation.is_finished() => {
+                generation.abort();
+                vec![
+                    WsServerMessage::Typing {
+                        session_id,
+                        role: TypingRole::Assistant,
+                        typing: false,
+                    },
+                    WsServerMessage::Cancelled { session_id },
+                ]
+            }
+            _ => vec![api_error(
+                Some(session_id),
+                &ChatApiError::NotFound(
+                    "No reply is being generated for this session on this socket".to_string(),
+                ),
+            )],
+        },
+        WsClientMessage::Typing { session_id, typing } => {
+            // Nobody listening is not an error
+            let _ = state.typing_relay.send(TypingEvent {
+                user_id: auth_user.user_id,
+                connection_id,
+                session_id,
+                typing,
+            });
+            Vec::new()
+        }
+    }
+}
+
+/// Send a message and stream the reply to the socket's outbox
+async fn generate(
+    state: ChatState,
+    auth_user: AuthUser,
+    session_id: Uuid,
+    request: SendMessageRequest,
+    outbox: mpsc::Sender<WsServerMessage>,
+) {
+    let mut stream = match execute_send(&state, session_id, &auth_user, request).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            let _ = outbox.send(api_error(Some(session_id), &e)).await;
+            return;
+        }
+    };
+
+    let typing = |typing| WsServerMessage::Typing {
+        session_id,
+        role: TypingRole::Assistant,
+        typing,
+    };
+    if outbox.send(typing(true)).await.is_err() {
+        return;
+    }
+
+    while let Some(result) = stream.next().await {
+        let (message, last) = match result {
+            Ok(chunk) if chunk.is_final => (WsServerMessage::Done { session_id }, true),
+            Ok(chunk) => (
+                WsServerMessage::Chunk {
+                    session_id,
+                    content: chunk.content,
+                },
+                false,
+            ),
+            Err(e) => (error(Some(session_id), StatusCode::BAD_GATEWAY, e), true),
+        };
+        if last {
+            let _ = outbox.send(typing(false)).await;
+        }
+        // A closed outbox means the socket is gone: dropping the stream
+        // lets the supervisor save the partial reply
+        if outbox.send(message).await.is_err() || last {
+            return;
+        }
+    }
+}
+
+/// Count a message against the user's chat rate `limits`
+async fn check_rate_limit(
+    state: &ChatState,
+    limits: Option<&ChatRateLimitConfig>,
+    auth_user: &AuthUser,
+) -> Result<(), ChatApiError> {
+    let (Some(rate_limit), Some(limits)) = (&state.rate_limit, limits) else {
+        return Ok(());
+    };
+    let failed = |e: anyhow::Error| ChatApiError::Internal(format!("Rate limit check failed: {e}"));
+
+    let mut conn = rate_limit
+        .valkey
+        .get_async_connection()
+        .await
+        .map_err(failed)?;
+    let result = chat_rate_limit::check_chat_rate_limit(&mut conn, auth_user.user_id, limits)
+        .await
+        .map_err(failed)?;
+    if !result.exceeded {
+        return Ok(());
+    }
+    if let Some(limit) = result.limit_type {
+        rate_limit.metrics.record(limit);
+    }
+    let limit_type = result
+        .limit_type
+        .map_or("per_minute", |limit| limit.as_str());
+    let retry_after = result
+        .retry_after
+        .and_then(|secs| u64::try_from(secs).ok())
+        .unwrap_or(60);
+    Err(ChatApiError::RateLimited {
+        message: format!(
+            "You have exceeded the {limit_type} rate limit. Please try again in {retry_after} \
+             seconds."
+        ),
+        retry_after_secs: Some(retry_after),
+    })
+}
+
+/// Error frame with the status and message the REST endpoints would answer
+fn api_error(session_id: Option<Uuid>, err: &ChatApiError) -> WsServerMessage {
+    err.log();
+    error(session_id, err.status(), err.message())
+}
+
+const fn error(session_id: Option<Uuid>, status: StatusCode, error: String) -> WsServerMessage {
+    WsServerMessage::Error {
+        session_id,
+        status: status.as_u16(),
+        error,
+    }
+}
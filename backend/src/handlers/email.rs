@@ -0,0 +1,223 @@
+//! Email preference endpoints (weekly digest opt-in and one-click unsubscribe)
+//! and the bounce webhook of the mail provider
+
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    response::Html,
+    Json,
+};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+use crate::{
+    dto::{
+        email::{
+            BounceWebhookQuery, BounceWebhookResponse, DigestPreferenceResponse, UnsubscribeQuery,
+            UpdateDigestPreferenceRequest,
+        },
+        ErrorResponse, MessageResponse,
+    },
+    handlers::auth::AppState,
+    middleware::auth::AuthUser,
+    services::{
+        auth::AuthError,
+        email::{
+            bounce::{self, BounceReport},
+            digest,
+            suppression::{self, SuppressionList},
+        },
+    },
+    utils::token::constant_time_eq,
+};
+
+/// Application state for the bounce webhook
+#[derive(Clone)]
+pub struct BounceWebhookState {
+    pub db: Arc<DatabaseConnection>,
+    pub suppressions: Arc<SuppressionList>,
+    /// Expected `?token=` value
+    pub secret: String,
+}
+
+/// GET /api/v1/email/digest - Get the weekly digest preference
+///
+/// Users who never opted in get `enabled: false`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/email/digest",
+    responses(
+        (status = 200, description = "Digest preference", body = DigestPreferenceResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    tag = "email"
+)]
+pub async fn get_digest_preference(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<DigestPreferenceResponse>, AuthError> {
+    let subscription = digest::find_subscription(state.db.as_ref(), auth_user.user_id)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    Ok(Json(subscription.map_or(
+        DigestPreferenceResponse {
+            enabled: false,
+            last_sent_at: None,
+        },
+        DigestPreferenceResponse::from,
+    )))
+}
+
+/// PUT /api/v1/email/digest - Turn the weekly digest on or off
+#[utoipa::path(
+    put,
+    path = "/api/v1/email/digest",
+    request_body = UpdateDigestPreferenceRequest,
+    responses(
+        (status = 200, description = "Digest preference updated", body = DigestPreferenceResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    tag = "email"
+)]
+pub async fn update_digest_preference(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(req): Json<UpdateDigestPreferenceRequest>,
+) -> Result<Json<DigestPreferenceResponse>, AuthError> {
+    let subscription = digest::set_subscription(state.db.as_ref(), auth_user.user_id, req.enabled)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    Ok(Json(subscription.into()))
+}
+
+/// Confirmation page shown when an unsubscribe link is opened
+///
+/// The form posts back to the page's own URL, token included.
+const UNSUBSCRIBE_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>Unsubscribe</title>
+</head>
+<body>
+<h1>Unsubscribe from the weekly digest?</h1>
+<form method="post">
+<input type="hidden" name="List-Unsubscribe" value="One-Click">
+<button type="submit">Unsubscribe</button>
+</form>
+</body>
+</html>
+"#;
+
+/// GET /api/v1/email/unsubscribe - Confirm unsubscribing from the digest
+///
+/// Public route. Only renders a page asking to confirm: link scanners and
+/// previews open links in emails, so opening one must not unsubscribe.
+#[utoipa::path(
+    get,
+    path = "/api/v1/email/unsubscribe",
+    params(UnsubscribeQuery),
+    responses(
+        (status = 200, description = "Confirmation page", body = String, content_type = "text/html"),
+    ),
+    tag = "email"
+)]
+#[allow(clippy::unused_async)]
+pub async fn unsubscribe_page(Query(_query): Query<UnsubscribeQuery>) -> Html<&'static str> {
+    Html(UNSUBSCRIBE_PAGE)
+}
+
+/// POST /api/v1/email/unsubscribe - One-click unsubscribe from the digest
+///
+/// Public route - the token in the link identifies the subscription. Sent by
+/// the confirmation page and by mail clients implementing RFC 8058 one-click
+/// unsubscribe (body `List-Unsubscribe=One-Click`, which is not checked).
+#[utoipa::path(
+    post,
+    path = "/api/v1/email/unsubscribe",
+    params(UnsubscribeQuery),
+    responses(
+        (status = 200, description = "Unsubscribed", body = MessageResponse),
+        (status = 400, description = "Invalid unsubscribe token", body = ErrorResponse),
+    ),
+    tag = "email"
+)]
+pub async fn unsubscribe(
+    State(state): State<AppState>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<Json<MessageResponse>, AuthError> {
+    let found = digest::unsubscribe(state.db.as_ref(), &query.token)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    if !found {
+        return Err(AuthError::InvalidInput(
+            "Invalid unsubscribe token".to_string(),
+        ));
+    }
+
+    Ok(Json(MessageResponse {
+        message: "You have been unsubscribed from the weekly digest".to_string(),
+    }))
+}
+
+/// POST /api/v1/email/bounces - Bounce and complaint reports from the mail provider
+///
+/// Public route - the provider authenticates with the shared secret in
+/// `?token=`. Accepts Amazon SES notifications (directly or through an SNS
+/// subscription) and a generic `{"type", "email", "detail"}` report; see
+/// [`bounce`] for the formats. SNS subscription confirmations are logged with
+/// their confirmation URL for an operator to open.
+#[utoipa::path(
+    post,
+    path = "/api/v1/email/bounces",
+    params(BounceWebhookQuery),
+    request_body(content = String, description = "SES/SNS notification or generic report (JSON)"),
+    responses(
+        (status = 200, description = "Report processed", body = BounceWebhookResponse),
+        (status = 400, description = "Unrecognized report", body = ErrorResponse),
+        (status = 401, description = "Invalid webhook token", body = ErrorResponse),
+    ),
+    tag = "email"
+)]
+pub async fn bounce_webhook(
+    State(state): State<BounceWebhookState>,
+    Query(query): Query<BounceWebhookQuery>,
+    body: Bytes,
+) -> Result<Json<BounceWebhookResponse>, AuthError> {
+    if !constant_time_eq(query.token.as_bytes(), state.secret.as_bytes()) {
+        return Err(AuthError::InvalidToken);
+    }
+
+    let events = match bounce::parse_report(&body).map_err(AuthError::InvalidInput)? {
+        BounceReport::SubscriptionConfirmation { subscribe_url } => {
+            tracing::warn!(
+                subscribe_url = %subscribe_url,
+                "SNS subscription to the bounce webhook awaits confirmation; open the URL to confirm"
+            );
+            Vec::new()
+        }
+        BounceReport::Events(events) => events,
+    };
+
+    let mut response = BounceWebhookResponse {
+        suppressed: 0,
+        ignored: 0,
+    };
+    for event in &events {
+        let reason = suppression::record_event(state.db.as_ref(), &state.suppressions, event)
+            .await
+            .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+        if reason.is_some() {
+            response.suppressed += 1;
+        } else {
+            response.ignored += 1;
+        }
+    }
+
+    Ok(Json(response))
+}
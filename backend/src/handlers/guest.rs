@@ -0,0 +1,111 @@
+//! Guest mode endpoints: anonymous chat tokens and claiming them after
+//! registration
+//!
+//! Only mounted when `GUEST_MODE_ENABLED` is on and chat is enabled. See
+//! [`services::auth::guest`](crate::services::auth::guest) for the lifecycle
+//! of a guest.
+
+use axum::{extract::State, Json};
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+use crate::{
+    config::GuestConfig,
+    dto::{
+        auth::{ClaimGuestRequest, ClaimGuestResponse, GuestTokenResponse},
+        ErrorResponse,
+    },
+    middleware::{auth::AuthUser, client_ip::ClientIp},
+    services::auth::{
+        create_guest_access_token, decode_access_token,
+        guest::{self, GuestAccount},
+        AuthError, JwtConfig,
+    },
+};
+
+/// Application state for the guest endpoints
+#[derive(Clone)]
+pub struct GuestState {
+    pub db: Arc<DatabaseConnection>,
+    pub jwt_config: JwtConfig,
+    pub config: GuestConfig,
+}
+
+/// POST /api/v1/auth/guest - Start an anonymous chat
+///
+/// Public route. The token is accepted by the chat session and message
+/// routes only, for `GUEST_TOKEN_TTL_MINUTES`, and lets the guest send
+/// `max_messages` messages. Each address gets `GUEST_MAX_PER_IP` guests per
+/// `GUEST_IP_WINDOW_SECS`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/guest",
+    responses(
+        (status = 200, description = "Guest created", body = GuestTokenResponse),
+        (status = 429, description = "Too many guests from this address", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn create_guest_token(
+    State(state): State<GuestState>,
+    client_ip: ClientIp,
+) -> Result<Json<GuestTokenResponse>, AuthError> {
+    let GuestAccount {
+        user_id,
+        username,
+        expires_at,
+    } = guest::create_guest(state.db.as_ref(), &state.config, client_ip.0)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?
+        .ok_or(AuthError::GuestLimitExceeded)?;
+
+    let ttl = expires_at - Utc::now();
+    let access_token = create_guest_access_token(user_id, username.clone(), ttl, &state.jwt_config)
+        .map_err(|_| AuthError::JwtEncodingError)?;
+
+    Ok(Json(GuestTokenResponse {
+        access_token,
+        token_type: "Bearer".to_string(),
+        expires_in: ttl.num_seconds(),
+        username,
+        max_messages: state.config.max_messages,
+    }))
+}
+
+/// POST /api/v1/auth/guest/claim - Move a guest's chat sessions to the
+/// current account
+///
+/// Called with the access token of the registered (or signed in) account and
+/// the guest token in the body. The guest is deleted afterwards, so a token
+/// can be claimed once.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/guest/claim",
+    request_body = ClaimGuestRequest,
+    responses(
+        (status = 200, description = "Sessions claimed", body = ClaimGuestResponse),
+        (status = 400, description = "Not a guest token, or the guest no longer exists", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Guests cannot claim", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn claim_guest(
+    State(state): State<GuestState>,
+    auth_user: AuthUser,
+    Json(req): Json<ClaimGuestRequest>,
+) -> Result<Json<ClaimGuestResponse>, AuthError> {
+    let claims = decode_access_token(&req.guest_token, &state.jwt_config)
+        .map_err(|_| AuthError::InvalidInput("Invalid guest token".to_string()))?;
+    if !claims.guest {
+        return Err(AuthError::InvalidInput("Not a guest token".to_string()));
+    }
+
+    let sessions_claimed = guest::claim_guest(state.db.as_ref(), claims.sub, auth_user.user_id)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| AuthError::InvalidInput("Guest expired or already claimed".to_string()))?;
+
+    Ok(Json(ClaimGuestResponse { sessions_claimed }))
+}
@@ -1,17 +1,54 @@
-use axum::{http::StatusCode, Json};
-use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
-
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
-pub struct HealthResponse {
-    /// Health status of the service
-    #[schema(example = "healthy")]
-    pub status: String,
+use axum::{extract::State, http::StatusCode, Json};
+use sea_orm::DatabaseConnection;
+use std::{
+    fmt::Display,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::config::SchemaCheckPolicy;
+use crate::dto::health::{ActiveModules, DependencyHealth, DetailedHealthResponse, HealthResponse};
+use crate::server::Shutdown;
+use crate::services::schema_version::{check_schema, SchemaState};
+use crate::services::valkey::{
+    provider_health::{any_provider_healthy, latest_probes, ProviderProbe},
+    ValkeyManager,
+};
+
+/// Dependencies checked by the readiness endpoint
+#[derive(Clone)]
+pub struct ReadinessState {
+    pub db: Arc<DatabaseConnection>,
+    /// LLM provider probes to check (set when chat is a critical dependency)
+    pub provider_probes: Option<ProviderProbeCheck>,
+    /// Whether to compare the applied migrations, and whether a difference
+    /// makes the instance unready
+    pub schema_check: SchemaCheckPolicy,
+    /// Valkey to ping (`None` when it is not configured)
+    pub valkey: Option<ValkeyManager>,
+    /// Unready once the server has started shutting down
+    pub shutdown: Shutdown,
+}
+
+/// Where to find the provider probe results
+#[derive(Clone)]
+pub struct ProviderProbeCheck {
+    pub valkey: ValkeyManager,
+    /// Provider keys being probed
+    pub providers: Vec<String>,
+    /// Whether chat is a critical dependency, so readiness requires a
+    /// healthy provider
+    pub critical: bool,
 }
 
+/// Longest a single dependency check of `/health/detailed` may take
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Health check endpoint
 ///
-/// Returns a simple health status to verify the server is running.
+/// Returns a simple health status to verify the server is running, along with
+/// the subsystems enabled on this deployment.
 #[utoipa::path(
     get,
     path = "/health",
@@ -21,25 +58,250 @@ pub struct HealthResponse {
     tag = "health"
 )]
 #[allow(clippy::unused_async)]
-pub async fn health_check() -> (StatusCode, Json<HealthResponse>) {
+pub async fn health_check(
+    State(modules): State<ActiveModules>,
+) -> (StatusCode, Json<HealthResponse>) {
     (
         StatusCode::OK,
         Json(HealthResponse {
             status: "healthy".to_string(),
+            modules: Some(modules),
+            providers: None,
+            schema: None,
         }),
     )
 }
 
+/// Readiness check endpoint
+///
+/// Verifies the database and Valkey (when configured) are reachable so
+/// orchestrators only route traffic to instances that can serve it: a new
+/// instance stays unready until both answer, and a stopping one is unready
+/// from the moment it receives `SIGTERM`. When chat is a critical dependency, also
+/// requires at least one LLM provider to pass its latest synthetic probe and
+/// lists the probe results. Unless `SCHEMA_VERSION_CHECK=off`, reports how
+/// the applied migrations compare to this binary's; with `refuse`, a
+/// difference (e.g. a newer release migrated the database mid-deploy) makes
+/// the instance unready. Served on the internal listener when one is
+/// configured, and also at `/ready`.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "Service is ready to serve traffic", body = HealthResponse),
+        (status = 503, description = "A required dependency is unavailable", body = HealthResponse)
+    ),
+    tag = "health"
+)]
+pub async fn readiness_check(
+    State(state): State<ReadinessState>,
+) -> (StatusCode, Json<HealthResponse>) {
+    if state.shutdown.is_shutting_down() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(HealthResponse {
+                status: "shutting_down".to_string(),
+                modules: None,
+                providers: None,
+                schema: None,
+            }),
+        );
+    }
+
+    let database_ok = match state.db.ping().await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("Readiness check failed: database unreachable: {}", e);
+            false
+        }
+    };
+
+    let valkey_ok = match &state.valkey {
+        Some(valkey) => match ping_valkey(valkey).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Readiness check failed: Valkey unreachable: {}", e);
+                false
+            }
+        },
+        None => true,
+    };
+
+    let probes = state
+        .provider_probes
+        .as_ref()
+        .filter(|check| check.critical)
+        .map(load_probes);
+    let providers_ok = probes.as_deref().map_or(true, any_provider_healthy);
+    if !providers_ok {
+        tracing::warn!("Readiness check failed: every LLM provider is failing its probe");
+    }
+
+    let schema = if database_ok && state.schema_check != SchemaCheckPolicy::Off {
+        match check_schema(&state.db).await {
+            Ok(status) => Some(status),
+            Err(e) => {
+                tracing::warn!("Readiness check: cannot read applied migrations: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let schema_ok = state.schema_check != SchemaCheckPolicy::Refuse
+        || schema
+            .as_ref()
+            .is_some_and(|schema| schema.state() == SchemaState::UpToDate);
+    if !schema_ok {
+        tracing::warn!("Readiness check failed: database schema does not match this binary");
+    }
+
+    let (status, label) = if database_ok && valkey_ok && providers_ok && schema_ok {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "unavailable")
+    };
+
+    (
+        status,
+        Json(HealthResponse {
+            status: label.to_string(),
+            modules: None,
+            providers: probes.map(|probes| probes.into_iter().map(Into::into).collect()),
+            schema: schema.map(Into::into),
+        }),
+    )
+}
+
+/// Detailed health check endpoint
+///
+/// Checks every dependency and reports its status and latency: a Postgres
+/// round trip, a Valkey `PING` (when configured), and the latest synthetic
+/// probe of each LLM provider (when probing is configured). The verdict is
+/// `unhealthy` (`503`) when the database is unreachable or, with chat as a
+/// critical dependency, every provider is failing; `degraded` when Valkey or
+/// some provider is failing; `healthy` otherwise. Each check gives up after
+/// two seconds. Served on the internal listener when one is configured.
+#[utoipa::path(
+    get,
+    path = "/health/detailed",
+    responses(
+        (status = 200, description = "Healthy or degraded", body = DetailedHealthResponse),
+        (status = 503, description = "Requests cannot be served", body = DetailedHealthResponse)
+    ),
+    tag = "health"
+)]
+pub async fn detailed_health_check(
+    State(state): State<ReadinessState>,
+) -> (StatusCode, Json<DetailedHealthResponse>) {
+    let (database, valkey) = tokio::join!(check(state.db.ping()), async {
+        match &state.valkey {
+            Some(valkey) => Some(check(ping_valkey(valkey)).await),
+            None => None,
+        }
+    });
+
+    // Probes are read from Valkey, so they are unknown while it is down
+    let probes = state.provider_probes.as_ref().map(load_probes);
+    let providers_critical = state
+        .provider_probes
+        .as_ref()
+        .is_some_and(|check| check.critical);
+
+    let verdict = verdict(
+        &database,
+        valkey.as_ref(),
+        probes.as_deref().unwrap_or_default(),
+        providers_critical,
+    );
+    let status = if verdict == "unhealthy" {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    (
+        status,
+        Json(DetailedHealthResponse {
+            status: verdict.to_string(),
+            database,
+            valkey,
+            providers: probes.map(|probes| probes.into_iter().map(Into::into).collect()),
+        }),
+    )
+}
+
+/// Time a dependency check, giving up after [`CHECK_TIMEOUT`]
+async fn check<E: Display>(ping: impl Future<Output = Result<(), E>>) -> DependencyHealth {
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, ping).await;
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    let error = match result {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("No answer within {}s", CHECK_TIMEOUT.as_secs())),
+    };
+    DependencyHealth {
+        healthy: error.is_none(),
+        latency_ms,
+        error,
+    }
+}
+
+/// Overall verdict of `/health/detailed`
+fn verdict(
+    database: &DependencyHealth,
+    valkey: Option<&DependencyHealth>,
+    probes: &[ProviderProbe],
+    providers_critical: bool,
+) -> &'static str {
+    if !database.healthy || (providers_critical && !any_provider_healthy(probes)) {
+        "unhealthy"
+    } else if valkey.is_some_and(|valkey| !valkey.healthy) || probes.iter().any(|p| !p.healthy) {
+        "degraded"
+    } else {
+        "healthy"
+    }
+}
+
+async fn ping_valkey(valkey: &ValkeyManager) -> anyhow::Result<()> {
+    let mut conn = valkey.get_async_connection().await?;
+    redis::cmd("PING").query_async::<String>(&mut conn).await?;
+    Ok(())
+}
+
+/// Load probe results; unknown (empty) if Valkey is unreachable
+fn load_probes(check: &ProviderProbeCheck) -> Vec<ProviderProbe> {
+    let result = check
+        .valkey
+        .get_connection()
+        .and_then(|mut conn| latest_probes(&mut conn, &check.providers));
+    match result {
+        Ok(probes) => probes,
+        Err(e) => {
+            tracing::warn!("Failed to load LLM provider probes: {}", e);
+            Vec::new()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const ALL_MODULES: ActiveModules = ActiveModules {
+        chat: true,
+        admin_api: true,
+        email: true,
+    };
+
     #[tokio::test]
     async fn test_health_check_returns_200_ok() {
         // Arrange: No setup needed for health check
 
         // Act: Call the health check handler
-        let (status, Json(response)) = health_check().await;
+        let (status, Json(response)) = health_check(State(ALL_MODULES)).await;
 
         // Assert: Status should be 200 OK
         assert_eq!(status, StatusCode::OK);
@@ -51,22 +313,41 @@ mod tests {
     #[tokio::test]
     async fn test_health_check_response_structure() {
         // Arrange & Act
-        let (_, Json(response)) = health_check().await;
+        let (_, Json(response)) = health_check(State(ALL_MODULES)).await;
 
         // Assert: Response should match expected structure
         let expected = HealthResponse {
             status: "healthy".to_string(),
+            modules: Some(ALL_MODULES),
+            providers: None,
+            schema: None,
         };
         assert_eq!(response, expected);
     }
 
+    #[tokio::test]
+    async fn test_health_check_reports_disabled_modules() {
+        let modules = ActiveModules {
+            chat: false,
+            admin_api: true,
+            email: false,
+        };
+
+        let (_, Json(response)) = health_check(State(modules)).await;
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["modules"]["chat"], false);
+        assert_eq!(json["modules"]["admin_api"], true);
+        assert_eq!(json["modules"]["email"], false);
+    }
+
     #[tokio::test]
     async fn test_health_check_is_fast() {
         // Arrange
         let start = std::time::Instant::now();
 
         // Act
-        let _ = health_check().await;
+        let _ = health_check(State(ALL_MODULES)).await;
 
         // Assert: Should execute in less than 10ms
         let duration = start.elapsed();
@@ -75,4 +356,137 @@ mod tests {
             "Health check took {duration:?}, expected < 10ms"
         );
     }
+
+    #[tokio::test]
+    async fn test_readiness_check_ready() {
+        use sea_orm::{DatabaseBackend, MockDatabase};
+
+        let db = Arc::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let (status, Json(response)) = readiness_check(State(ReadinessState {
+            db,
+            provider_probes: None,
+            schema_check: SchemaCheckPolicy::Off,
+            valkey: None,
+            shutdown: Shutdown::new(),
+        }))
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.status, "ready");
+    }
+
+    #[tokio::test]
+    async fn test_readiness_check_database_unavailable() {
+        let db = Arc::new(DatabaseConnection::Disconnected);
+
+        let (status, Json(response)) = readiness_check(State(ReadinessState {
+            db,
+            provider_probes: None,
+            schema_check: SchemaCheckPolicy::Off,
+            valkey: None,
+            shutdown: Shutdown::new(),
+        }))
+        .await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.status, "unavailable");
+    }
+
+    #[tokio::test]
+    async fn test_readiness_check_unready_once_shutting_down() {
+        use sea_orm::{DatabaseBackend, MockDatabase};
+
+        let db = Arc::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+        let shutdown = Shutdown::new();
+        shutdown.begin();
+
+        let (status, Json(response)) = readiness_check(State(ReadinessState {
+            db,
+            provider_probes: None,
+            schema_check: SchemaCheckPolicy::Off,
+            valkey: None,
+            shutdown,
+        }))
+        .await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.status, "shutting_down");
+    }
+
+    fn up() -> DependencyHealth {
+        DependencyHealth {
+            healthy: true,
+            latency_ms: 1,
+            error: None,
+        }
+    }
+
+    fn probe(healthy: bool) -> ProviderProbe {
+        ProviderProbe {
+            provider: "sambanova".to_string(),
+            model: "llama".to_string(),
+            healthy,
+            latency_ms: 100,
+            error: (!healthy).then(|| "timeout".to_string()),
+            checked_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detailed_health_check_healthy() {
+        use sea_orm::{DatabaseBackend, MockDatabase};
+
+        let db = Arc::new(MockDatabase::new(DatabaseBackend::Postgres).into_connection());
+
+        let (status, Json(response)) = detailed_health_check(State(ReadinessState {
+            db,
+            provider_probes: None,
+            schema_check: SchemaCheckPolicy::Off,
+            valkey: None,
+            shutdown: Shutdown::new(),
+        }))
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.status, "healthy");
+        assert!(response.database.healthy);
+        assert_eq!(response.valkey, None);
+    }
+
+    #[tokio::test]
+    async fn test_detailed_health_check_database_unavailable() {
+        let (status, Json(response)) = detailed_health_check(State(ReadinessState {
+            db: Arc::new(DatabaseConnection::Disconnected),
+            provider_probes: None,
+            schema_check: SchemaCheckPolicy::Off,
+            valkey: None,
+            shutdown: Shutdown::new(),
+        }))
+        .await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.status, "unhealthy");
+        assert!(!response.database.healthy);
+        assert!(response.database.error.is_some());
+    }
+
+    #[test]
+    fn test_verdict() {
+        let down = DependencyHealth {
+            healthy: false,
+            latency_ms: 2000,
+            error: Some("No answer within 2s".to_string()),
+        };
+
+        assert_eq!(verdict(&up(), Some(&up()), &[probe(true)], true), "healthy");
+        assert_eq!(verdict(&up(), Some(&down), &[], false), "degraded");
+        assert_eq!(
+            verdict(&up(), None, &[probe(true), probe(false)], true),
+            "degraded"
+        );
+        assert_eq!(verdict(&up(), None, &[probe(false)], false), "degraded");
+        assert_eq!(verdict(&up(), None, &[probe(false)], true), "unhealthy");
+        assert_eq!(verdict(&down, None, &[], false), "unhealthy");
+    }
 }
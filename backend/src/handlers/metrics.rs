@@ -0,0 +1,68 @@
+use axum::{extract::State, http::header, response::IntoResponse};
+use sea_orm::DatabaseConnection;
+use std::{fmt::Write, sync::Arc};
+
+use crate::application::chat::{event_subscribers::TokenMetrics, StreamMetrics};
+use crate::middleware::chat_rate_limit::RateLimitMetrics;
+use crate::middleware::latency_budget::LatencyBudgets;
+use crate::middleware::metrics::HttpMetrics;
+use crate::services::auth::LoginMetrics;
+
+/// Metrics rendered by the `/metrics` endpoint
+#[derive(Clone)]
+pub struct MetricsState {
+    pub http: Arc<HttpMetrics>,
+    pub latency: Arc<LatencyBudgets>,
+    pub streams: Arc<StreamMetrics>,
+    pub logins: Arc<LoginMetrics>,
+    pub rate_limits: Arc<RateLimitMetrics>,
+    pub tokens: Arc<TokenMetrics>,
+    /// Connection pool reported at scrape time
+    pub db: Arc<DatabaseConnection>,
+}
+
+/// Prometheus metrics endpoint
+///
+/// Served on the internal listener when one is configured.
+#[allow(clippy::unused_async)]
+pub async fn metrics(State(metrics): State<MetricsState>) -> impl IntoResponse {
+    let mut body = metrics.http.render();
+    body.push_str(&metrics.latency.render());
+    body.push_str(&metrics.streams.render());
+    body.push_str(&metrics.logins.render());
+    body.push_str(&metrics.rate_limits.render());
+    body.push_str(&metrics.tokens.render());
+    body.push_str(&render_db_pool(&metrics.db));
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+/// Open, idle and maximum connections of the `PostgreSQL` pool (nothing for
+/// other databases, e.g. in demo mode)
+fn render_db_pool(db: &DatabaseConnection) -> String {
+    let mut out = String::new();
+    if !matches!(db, DatabaseConnection::SqlxPostgresPoolConnection(_)) {
+        return out;
+    }
+    let pool = db.get_postgres_connection_pool();
+    let open = pool.size();
+    let idle = u32::try_from(pool.num_idle()).unwrap_or(open);
+
+    out.push_str("# HELP db_pool_connections Database pool connections by state.\n");
+    out.push_str("# TYPE db_pool_connections gauge\n");
+    let _ = writeln!(
+        out,
+        "db_pool_connections{{state=\"in_use\"}} {}",
+        open.saturating_sub(idle)
+    );
+    let _ = writeln!(out, "db_pool_connections{{state=\"idle\"}} {idle}");
+
+    out.push_str("# HELP db_pool_max_connections Most connections the database pool opens.\n");
+    out.push_str("# TYPE db_pool_max_connections gauge\n");
+    let _ = writeln!(
+        out,
+        "db_pool_max_connections {}",
+        pool.options().get_max_connections()
+    );
+
+    out
+}
@@ -1,4 +1,13 @@
 pub mod admin;
 pub mod auth;
+pub mod branding;
 pub mod chat;
+pub mod email;
+pub mod guest;
 pub mod health;
+pub mod metrics;
+pub mod notifications;
+pub mod oauth;
+pub mod openapi;
+pub mod preferences;
+pub mod tokenizer;
@@ -0,0 +1,49 @@
+//! User notification inbox endpoint
+
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    dto::notifications::{NotificationDto, NotificationListResponse},
+    middleware::auth::AuthUser,
+    services::valkey::{notifications, ValkeyManager},
+};
+
+/// List the current user's notifications, newest first
+///
+/// # Errors
+/// Returns HTTP error if:
+/// - Valkey is unreachable (500)
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications",
+    tag = "notifications",
+    responses(
+        (status = 200, description = "Notifications retrieved", body = NotificationListResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_notifications(
+    State(valkey): State<ValkeyManager>,
+    auth_user: AuthUser,
+) -> Result<Json<NotificationListResponse>, (StatusCode, String)> {
+    let notifications = async {
+        let mut conn = valkey.get_async_connection().await?;
+        notifications::list_notifications(&mut conn, auth_user.user_id).await
+    }
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to list notifications: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to load notifications".to_string(),
+        )
+    })?;
+
+    Ok(Json(NotificationListResponse {
+        notifications: notifications
+            .into_iter()
+            .map(NotificationDto::from)
+            .collect(),
+    }))
+}
@@ -0,0 +1,180 @@
+//! Social login endpoints
+//!
+//! Only mounted when at least one provider is configured (see
+//! [`OAuthConfig`](crate::config::OAuthConfig)). The browser opens the
+//! authorize endpoint, signs in at the provider and comes back to the
+//! frontend page at `{APP_PUBLIC_URL}/oauth/{provider}/callback`, which calls
+//! the callback endpoint with the `code` and `state` it received. The callback
+//! answers like password login.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect},
+    Json,
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+
+use super::auth::{complete_sign_in, AppState};
+use crate::{
+    dto::{
+        auth::{AuthResponse, OAuthCallbackQuery},
+        ErrorResponse,
+    },
+    middleware::{auth::DpopProof, client_ip::ClientIp},
+    services::{
+        audit::{self, AuditEntry, AuditEvent},
+        auth::AuthError,
+        oauth::{self, OAuthProvider, OAuthProviders},
+    },
+    utils::token::{constant_time_eq, generate_verification_token},
+};
+use std::sync::Arc;
+
+/// Cookie holding the state of an authorization in progress
+const STATE_COOKIE: &str = "oauth_state";
+
+/// Time the user has to sign in at the provider
+const STATE_MAX_AGE_MINUTES: i64 = 10;
+
+/// Application state for the social login endpoints
+#[derive(Clone)]
+pub struct OAuthState {
+    pub auth: AppState,
+    pub providers: OAuthProviders,
+}
+
+fn provider<'a>(
+    providers: &'a OAuthProviders,
+    name: &str,
+) -> Result<&'a Arc<dyn OAuthProvider>, AuthError> {
+    providers
+        .get(name)
+        .ok_or_else(|| AuthError::InvalidInput(format!("Unknown sign-in provider: {name}")))
+}
+
+/// GET /api/v1/auth/oauth/{provider}/authorize - Start signing in with a
+/// provider
+///
+/// Public route. Redirects to the provider and sets a short-lived cookie with
+/// the state the callback checks.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oauth/{provider}/authorize",
+    params(("provider" = String, Path, description = "`google` or `github`")),
+    responses(
+        (status = 303, description = "Redirect to the provider"),
+        (status = 400, description = "Provider not configured", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+#[allow(clippy::unused_async)]
+pub async fn authorize(
+    State(state): State<OAuthState>,
+    Path(name): Path<String>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthError> {
+    let provider = provider(&state.providers, &name)?;
+
+    let nonce = generate_verification_token();
+    let url = provider.authorize_url(&state.providers.redirect_uri(&name), &nonce);
+    let cookie = Cookie::build((STATE_COOKIE, format!("{name}:{nonce}")))
+        .http_only(true)
+        .secure(true)
+        // Lax, so the cookie survives the redirect back from the provider
+        .same_site(SameSite::Lax)
+        .path("/api/v1/auth/oauth")
+        .max_age(time::Duration::minutes(STATE_MAX_AGE_MINUTES))
+        .build();
+
+    Ok((jar.add(cookie), Redirect::to(&url)))
+}
+
+/// GET /api/v1/auth/oauth/{provider}/callback - Finish signing in with a
+/// provider
+///
+/// Public route. Links the provider account to the local account with the
+/// same verified email, or creates an account, then issues tokens as login
+/// does.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oauth/{provider}/callback",
+    params(
+        ("provider" = String, Path, description = "`google` or `github`"),
+        OAuthCallbackQuery
+    ),
+    responses(
+        (status = 200, description = "Signed in", body = AuthResponse),
+        (status = 400, description = "Unknown provider, invalid state or no verified email", body = ErrorResponse),
+        (status = 403, description = "No account for this identity and registration is closed", body = ErrorResponse),
+        (status = 409, description = "The email belongs to an account whose address is not verified", body = ErrorResponse),
+        (status = 502, description = "The provider rejected the code or could not be reached", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn callback(
+    State(state): State<OAuthState>,
+    Path(name): Path<String>,
+    Query(query): Query<OAuthCallbackQuery>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    dpop: DpopProof,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthError> {
+    let provider = provider(&state.providers, &name)?;
+    let key = dpop.verify(&state.auth.jwt_config)?;
+
+    // The state must be the one issued to this browser, for this provider
+    let expected = format!("{name}:{}", query.state);
+    let valid = jar
+        .get(STATE_COOKIE)
+        .is_some_and(|cookie| constant_time_eq(cookie.value().as_bytes(), expected.as_bytes()));
+    let jar = jar.remove(Cookie::build(STATE_COOKIE).path("/api/v1/auth/oauth"));
+    if !valid {
+        return Err(AuthError::InvalidInput("Invalid sign-in state".to_string()));
+    }
+
+    let identity = provider
+        .identify(&query.code, &state.providers.redirect_uri(&name))
+        .await
+        .map_err(|e| {
+            tracing::warn!(provider = %name, "OAuth sign-in failed: {}", e);
+            state.auth.login_metrics.record(provider.name(), false);
+            e
+        })?;
+    let (user, link) = oauth::sign_in(
+        state.auth.db.as_ref(),
+        provider.name(),
+        &identity,
+        state.auth.switches.registration_open(),
+        state.auth.sandbox.as_ref(),
+    )
+    .await
+    .map_err(|e| {
+        state.auth.login_metrics.record(provider.name(), false);
+        e
+    })?;
+    state.auth.login_metrics.record(provider.name(), true);
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.oauth_login",
+        user_id = %user.id,
+        provider = provider.name(),
+        link = link.as_str(),
+        ip = %client_ip,
+        "User logged in with a sign-in provider"
+    );
+    audit::record(
+        state.auth.db.as_ref(),
+        AuditEntry::new(AuditEvent::LoginSucceeded)
+            .actor(user.id)
+            .client(client_ip, &headers)
+            .details(serde_json::json!({ "method": provider.name(), "link": link.as_str() })),
+    )
+    .await;
+
+    let (jar, response) =
+        complete_sign_in(&state.auth, jar, &headers, client_ip, &user, key).await?;
+    Ok((StatusCode::OK, jar, Json(response)))
+}
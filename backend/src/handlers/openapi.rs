@@ -0,0 +1,24 @@
+//! Development helpers around the `OpenAPI` document
+
+use axum::{body::Bytes, extract::State, http::header, response::IntoResponse};
+
+/// GET /openapi/types.ts - TypeScript types of the API
+///
+/// Development-only: mounted when `OPENAPI_TYPES_ENABLED=true`. The module is
+/// generated at startup from the full document (see
+/// [`crate::openapi::typescript`]), so the frontend can fetch the types of
+/// the running backend instead of exporting the schema and running a
+/// separate codegen step.
+#[allow(clippy::unused_async)]
+pub async fn typescript_types(State(types): State<Bytes>) -> impl IntoResponse {
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "application/typescript; charset=utf-8",
+            ),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        types,
+    )
+}
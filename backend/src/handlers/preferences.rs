@@ -0,0 +1,73 @@
+//! User preference endpoints
+
+use axum::{extract::State, Json};
+
+use crate::{
+    dto::{
+        preferences::{UpdatePreferencesRequest, UserPreferences},
+        ErrorResponse,
+    },
+    handlers::auth::AppState,
+    middleware::auth::AuthUser,
+    services::{auth::AuthError, preferences},
+};
+
+/// GET /api/v1/auth/me/preferences - Get the current user's preferences
+///
+/// Users who never changed a preference get the defaults.
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me/preferences",
+    responses(
+        (status = 200, description = "User preferences", body = UserPreferences),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    tag = "Authentication"
+)]
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<UserPreferences>, AuthError> {
+    let preferences = preferences::load(state.db.as_ref(), auth_user.user_id)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    Ok(Json(preferences))
+}
+
+/// PATCH /api/v1/auth/me/preferences - Update some of the current user's preferences
+///
+/// Only the keys present in the body change. Unknown keys or values are
+/// rejected with 422; bodies larger than
+/// [`preferences::MAX_PREFERENCES_BYTES`] with 413.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/auth/me/preferences",
+    request_body = UpdatePreferencesRequest,
+    responses(
+        (status = 200, description = "Preferences updated", body = UserPreferences),
+        (status = 400, description = "Invalid preference value", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 413, description = "Request body too large"),
+        (status = 422, description = "Unknown preference key or value"),
+    ),
+    tag = "Authentication"
+)]
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(req): Json<UpdatePreferencesRequest>,
+) -> Result<Json<UserPreferences>, AuthError> {
+    let current = preferences::load(state.db.as_ref(), auth_user.user_id)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    let updated = preferences::apply_update(current, req);
+    preferences::validate(&updated).map_err(|e| AuthError::InvalidInput(e.to_string()))?;
+
+    preferences::save(state.db.as_ref(), auth_user.user_id, &updated)
+        .await
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+    Ok(Json(updated))
+}
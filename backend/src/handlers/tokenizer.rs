@@ -0,0 +1,74 @@
+//! Token counting endpoint for internal services
+//!
+//! Mounted on the signed internal routes, so sidecars (e.g. the RAG ingester)
+//! count tokens with the same tokenizers and cache as chat.
+
+use axum::{extract::State, Json};
+use std::sync::Arc;
+
+use crate::{
+    dto::tokenizer::{CountTokensRequest, CountTokensResponse},
+    services::{auth::AuthError, tokenizer::TokenizerService},
+};
+
+/// POST /api/v1/internal/tokens/count - Count the tokens of a text
+///
+/// # Errors
+/// Returns `InternalError` if the tokenizer cannot be loaded
+#[allow(clippy::unused_async)]
+pub async fn count_tokens(
+    State(tokenizers): State<Arc<TokenizerService>>,
+    Json(request): Json<CountTokensRequest>,
+) -> Result<Json<CountTokensResponse>, AuthError> {
+    let tokenizer = request
+        .model
+        .as_deref()
+        .map_or_else(
+            || tokenizers.default_tokenizer(),
+            |model| tokenizers.for_model(model),
+        )
+        .map_err(|e| {
+            tracing::error!("Failed to load tokenizer: {}", e);
+            AuthError::InternalError
+        })?;
+
+    Ok(Json(CountTokensResponse {
+        encoding: tokenizer.encoding().to_string(),
+        tokens: tokenizer.count_tokens(&request.text),
+        model: request.model,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_count_tokens() {
+        let tokenizers = Arc::new(TokenizerService::new());
+
+        let Json(response) = count_tokens(
+            State(Arc::clone(&tokenizers)),
+            Json(CountTokensRequest {
+                text: "hello world".to_string(),
+                model: Some("gpt-4o".to_string()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.encoding, "o200k_base");
+        assert_eq!(response.tokens, 2);
+
+        let Json(response) = count_tokens(
+            State(tokenizers),
+            Json(CountTokensRequest {
+                text: String::new(),
+                model: None,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.encoding, "cl100k_base");
+        assert_eq!(response.tokens, 0);
+    }
+}
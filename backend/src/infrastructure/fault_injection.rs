@@ -0,0 +1,266 @@
+//! Fault injection for resilience testing (development only)
+//!
+//! With `FAULT_INJECTION_ENABLED=true`, a share of calls to each dependency
+//! fails on purpose so retries, fail-open policies and error paths can be
+//! exercised without breaking the real dependency:
+//!
+//! - **database**: API requests fail as if their first query timed out, after
+//!   hanging for the configured delay
+//!   ([`crate::middleware::fault_injection::database_faults`])
+//! - **Valkey**: [`crate::services::valkey::ValkeyManager`] hands out no
+//!   connection, as if the server were unreachable
+//! - **LLM providers**: [`FaultyProvider`] answers with a 500, or delays each
+//!   chunk of the stream
+//!
+//! Every injected fault is logged with the `fault_injection` target and
+//! counted in [`FaultInjector::injected_total`].
+
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::config::FaultInjectionConfig;
+use crate::infrastructure::llm::{
+    ChatCompletionRequest, LlmProvider, LlmProviderError, LlmResult, StreamChunk,
+};
+
+/// A failure that can be injected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    DatabaseTimeout,
+    ValkeyUnavailable,
+    ProviderError,
+    SlowProviderStream,
+}
+
+impl Fault {
+    /// Name used in logs
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::DatabaseTimeout => "database_timeout",
+            Self::ValkeyUnavailable => "valkey_unavailable",
+            Self::ProviderError => "provider_error",
+            Self::SlowProviderStream => "slow_provider_stream",
+        }
+    }
+}
+
+/// Decides which calls fail, and counts the failures
+#[derive(Debug)]
+pub struct FaultInjector {
+    config: FaultInjectionConfig,
+    injected: [AtomicU64; 4],
+}
+
+impl FaultInjector {
+    #[must_use]
+    pub fn new(config: FaultInjectionConfig) -> Self {
+        Self {
+            config,
+            injected: Default::default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &FaultInjectionConfig {
+        &self.config
+    }
+
+    /// Whether this call should fail with `fault`, sampled at its percentage
+    pub fn inject(&self, fault: Fault) -> bool {
+        let percent = match fault {
+            Fault::DatabaseTimeout => self.config.database_timeout_percent,
+            Fault::ValkeyUnavailable => self.config.valkey_unavailable_percent,
+            Fault::ProviderError => self.config.provider_error_percent,
+            Fault::SlowProviderStream => self.config.provider_slow_percent,
+        };
+        let injected = match percent {
+            0 => false,
+            100.. => true,
+            _ => rand::random::<f64>() * 100.0 < f64::from(percent),
+        };
+        if injected {
+            self.injected[fault as usize].fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(target: "fault_injection", fault = fault.as_str(), "Injected fault");
+        }
+        injected
+    }
+
+    /// Faults of a kind injected so far
+    #[must_use]
+    pub fn injected_total(&self, fault: Fault) -> u64 {
+        self.injected[fault as usize].load(Ordering::Relaxed)
+    }
+}
+
+/// LLM provider that fails or slows down a share of its requests
+pub struct FaultyProvider {
+    inner: Arc<dyn LlmProvider>,
+    faults: Arc<FaultInjector>,
+}
+
+impl FaultyProvider {
+    #[must_use]
+    pub fn new(inner: Arc<dyn LlmProvider>, faults: Arc<FaultInjector>) -> Self {
+        Self { inner, faults }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FaultyProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn is_available(&self) -> bool {
+        self.inner.is_available()
+    }
+
+    async fn create_chat_completion_stream(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> LlmResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, LlmProviderError>> + Send>>> {
+        if self.faults.inject(Fault::ProviderError) {
+            return Err(LlmProviderError::ApiError(
+                "500 Internal Server Error (injected fault)".to_string(),
+            ));
+        }
+
+        let stream = self.inner.create_chat_completion_stream(request).await?;
+        if !self.faults.inject(Fault::SlowProviderStream) {
+            return Ok(stream);
+        }
+        let delay = self.faults.config().provider_chunk_delay;
+        Ok(Box::pin(stream.then(move |item| async move {
+            tokio::time::sleep(delay).await;
+            item
+        })))
+    }
+
+    fn max_context_tokens(&self, model: &str) -> Option<u32> {
+        self.inner.max_context_tokens(model)
+    }
+
+    fn max_output_tokens(&self, model: &str) -> Option<u32> {
+        self.inner.max_output_tokens(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::llm::{ChatMessage, ChatRole};
+    use std::time::{Duration, Instant};
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl LlmProvider for EchoProvider {
+        fn name(&self) -> &'static str {
+            "Echo"
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn create_chat_completion_stream(
+            &self,
+            request: ChatCompletionRequest,
+        ) -> LlmResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, LlmProviderError>> + Send>>>
+        {
+            let chunks = request.messages.into_iter().map(|message| {
+                Ok(StreamChunk {
+                    content: message.content,
+                    is_final: false,
+                    finish_reason: None,
+                    usage: None,
+                })
+            });
+            Ok(Box::pin(futures::stream::iter(chunks)))
+        }
+
+        fn max_context_tokens(&self, _model: &str) -> Option<u32> {
+            None
+        }
+
+        fn max_output_tokens(&self, _model: &str) -> Option<u32> {
+            None
+        }
+    }
+
+    fn provider(config: FaultInjectionConfig) -> (FaultyProvider, Arc<FaultInjector>) {
+        let faults = Arc::new(FaultInjector::new(config));
+        (
+            FaultyProvider::new(Arc::new(EchoProvider), Arc::clone(&faults)),
+            faults,
+        )
+    }
+
+    fn request() -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "echo".to_string(),
+            messages: ["a", "b"]
+                .map(|content| ChatMessage {
+                    role: ChatRole::User,
+                    content: content.to_string(),
+                })
+                .to_vec(),
+            max_tokens: 16,
+            temperature: None,
+            stream: true,
+        }
+    }
+
+    #[test]
+    fn test_percentages_bound_injection() {
+        let faults = FaultInjector::new(FaultInjectionConfig {
+            valkey_unavailable_percent: 100,
+            ..FaultInjectionConfig::default()
+        });
+
+        for _ in 0..10 {
+            assert!(faults.inject(Fault::ValkeyUnavailable));
+            assert!(!faults.inject(Fault::DatabaseTimeout));
+        }
+        assert_eq!(faults.injected_total(Fault::ValkeyUnavailable), 10);
+        assert_eq!(faults.injected_total(Fault::DatabaseTimeout), 0);
+    }
+
+    #[tokio::test]
+    async fn test_provider_error() {
+        let (provider, faults) = provider(FaultInjectionConfig {
+            provider_error_percent: 100,
+            ..FaultInjectionConfig::default()
+        });
+
+        let result = provider.create_chat_completion_stream(request()).await;
+        assert!(matches!(result, Err(LlmProviderError::ApiError(_))));
+        assert_eq!(faults.injected_total(Fault::ProviderError), 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_provider_stream() {
+        let (provider, _) = provider(FaultInjectionConfig {
+            provider_slow_percent: 100,
+            provider_chunk_delay: Duration::from_millis(20),
+            ..FaultInjectionConfig::default()
+        });
+
+        let started = Instant::now();
+        let content = provider.create_chat_completion(request()).await.unwrap();
+        assert_eq!(content, "ab");
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn test_no_faults_configured() {
+        let (provider, _) = provider(FaultInjectionConfig::default());
+
+        let content = provider.create_chat_completion(request()).await.unwrap();
+        assert_eq!(content, "ab");
+    }
+}
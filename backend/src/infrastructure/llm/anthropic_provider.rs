@@ -0,0 +1,475 @@
+//! Anthropic (Claude) LLM provider implementation
+//!
+//! Implements the `LlmProvider` trait against Anthropic's Messages API, which is
+//! not OpenAI-compatible: the system prompt is a separate `system` field, turns
+//! alternate between `user` and `assistant`, and the stream is a sequence of
+//! typed events (`content_block_delta`, `message_delta`, `message_stop`, ...).
+
+use super::provider::{
+    ChatCompletionRequest, ChatMessage as ProviderMessage, ChatRole, LlmProvider, LlmProviderError,
+    LlmResult, StreamChunk,
+};
+use async_trait::async_trait;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+
+use crate::infrastructure::llm::{ModelConfig, SharedModelRegistry};
+
+/// `anthropic-version` header sent when models.toml sets no `api_version`
+pub const DEFAULT_API_VERSION: &str = "2023-06-01";
+
+/// Anthropic provider using the Messages API
+pub struct AnthropicProvider {
+    api_base: String,
+    api_key: String,
+    api_version: String,
+    client: reqwest::Client,
+    model_registry: SharedModelRegistry,
+}
+
+/// Body of `POST /messages`
+#[derive(Debug, Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    stream: bool,
+}
+
+/// A turn of the conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Message {
+    role: &'static str,
+    content: String,
+}
+
+/// Events of the Messages API stream; those carrying no text or stop reason
+/// (`message_start`, `ping`, ...) are ignored
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    ContentBlockDelta {
+        delta: ContentDelta,
+    },
+    MessageDelta {
+        delta: MessageDelta,
+    },
+    MessageStop,
+    Error {
+        error: ApiError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentDelta {
+    TextDelta {
+        text: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageDelta {
+    stop_reason: Option<String>,
+}
+
+/// Error of an error response or `error` event
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    #[serde(rename = "type")]
+    kind: String,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: ApiError,
+}
+
+impl AnthropicProvider {
+    /// Create a new Anthropic provider
+    #[must_use]
+    pub fn new(api_base: String, api_key: String, model_registry: SharedModelRegistry) -> Self {
+        Self {
+            api_base,
+            api_key,
+            api_version: DEFAULT_API_VERSION.to_string(),
+            client: reqwest::Client::new(),
+            model_registry,
+        }
+    }
+
+    /// Send `api_version` as the `anthropic-version` header
+    #[must_use]
+    pub fn with_api_version(mut self, api_version: String) -> Self {
+        self.api_version = api_version;
+        self
+    }
+
+    /// Get model configuration from registry
+    fn get_model_config(&self, model_id: &str) -> LlmResult<ModelConfig> {
+        self.model_registry
+            .load()
+            .get_model(model_id)
+            .cloned()
+            .map_err(|e| LlmProviderError::ConfigError(e.to_string()))
+    }
+}
+
+/// Convert provider messages to the system prompt and turns of the Messages
+/// API
+///
+/// System messages are joined into the system prompt wherever they appear.
+/// Consecutive messages of the same role are merged, as the API only accepts
+/// alternating turns.
+fn convert_messages(messages: Vec<ProviderMessage>) -> LlmResult<(Option<String>, Vec<Message>)> {
+    let mut system: Vec<String> = Vec::new();
+    let mut turns: Vec<Message> = Vec::new();
+
+    for msg in messages {
+        let role = match msg.role {
+            ChatRole::System => {
+                system.push(msg.content);
+                continue;
+            }
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+        };
+        match turns.last_mut() {
+            Some(last) if last.role == role => {
+                last.content.push_str("\n\n");
+                last.content.push_str(&msg.content);
+            }
+            _ => turns.push(Message {
+                role,
+                content: msg.content,
+            }),
+        }
+    }
+
+    if turns.is_empty() {
+        return Err(LlmProviderError::InvalidRequest(
+            "Anthropic requires at least one user or assistant message".to_string(),
+        ));
+    }
+    let system = (!system.is_empty()).then(|| system.join("\n\n"));
+    Ok((system, turns))
+}
+
+/// Splits a server-sent event stream into the `data` of its events
+#[derive(Debug, Default)]
+struct SseDecoder {
+    /// Bytes after the last complete line
+    buffer: Vec<u8>,
+    /// Data lines of the event being read
+    data: String,
+}
+
+impl SseDecoder {
+    /// Feed `bytes` of the stream, returning the data of the events they
+    /// complete
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            // A complete line splits no character, so this is lossless
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(std::mem::take(&mut self.data));
+                }
+            } else if let Some(data) = line.strip_prefix("data:") {
+                if !self.data.is_empty() {
+                    self.data.push('\n');
+                }
+                self.data.push_str(data.strip_prefix(' ').unwrap_or(data));
+            }
+        }
+        events
+    }
+}
+
+/// Transform the events of a successful response into provider chunks
+fn chunk_stream(
+    response: reqwest::Response,
+) -> impl Stream<Item = Result<StreamChunk, LlmProviderError>> + Send {
+    async_stream::stream! {
+        let mut response = response;
+        let mut decoder = SseDecoder::default();
+        let mut chunk_count = 0;
+        let mut stop_reason = None;
+
+        loop {
+            let bytes = match response.chunk().await {
+                Ok(Some(bytes)) => bytes,
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("Anthropic: Stream error: {}", e);
+                    yield Err(LlmProviderError::StreamError(e.to_string()));
+                    return;
+                }
+            };
+
+            for data in decoder.push(&bytes) {
+                match serde_json::from_str::<StreamEvent>(&data) {
+                    Ok(StreamEvent::ContentBlockDelta {
+                        delta: ContentDelta::TextDelta { text },
+                    }) => {
+                        chunk_count += 1;
+                        tracing::debug!("Anthropic: Chunk #{}: {} bytes", chunk_count, text.len());
+
+                        yield Ok(StreamChunk {
+                            content: text,
+                            is_final: false,
+                            finish_reason: None,
+                            usage: None,
+                        });
+                    }
+                    Ok(StreamEvent::MessageDelta { delta }) => {
+                        stop_reason = delta.stop_reason.or(stop_reason);
+                    }
+                    Ok(StreamEvent::MessageStop) => {
+                        tracing::info!(
+                            "Anthropic: Stream finished: reason={:?}, chunks={}",
+                            stop_reason,
+                            chunk_count
+                        );
+
+                        yield Ok(StreamChunk {
+                            content: String::new(),
+                            is_final: true,
+                            finish_reason: stop_reason,
+                            usage: None,
+                        });
+                        return;
+                    }
+                    Ok(StreamEvent::Error { error }) => {
+                        tracing::error!("Anthropic: Stream error: {}: {}", error.kind, error.message);
+                        yield Err(LlmProviderError::StreamError(format!(
+                            "{}: {}",
+                            error.kind, error.message
+                        )));
+                        return;
+                    }
+                    Ok(StreamEvent::ContentBlockDelta { .. } | StreamEvent::Other) => {}
+                    Err(e) => {
+                        tracing::error!("Anthropic: Malformed event: {}", e);
+                        yield Err(LlmProviderError::StreamError(e.to_string()));
+                        return;
+                    }
+                }
+            }
+        }
+
+        tracing::warn!("Anthropic: Stream ended without message_stop");
+        yield Err(LlmProviderError::StreamError(
+            "Stream ended without message_stop".to_string(),
+        ));
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &'static str {
+        "Anthropic"
+    }
+
+    fn is_available(&self) -> bool {
+        !self.api_key.is_empty() && !self.api_base.is_empty()
+    }
+
+    async fn create_chat_completion_stream(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> LlmResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, LlmProviderError>> + Send>>> {
+        // Get model config to retrieve provider-specific model_id
+        let model_config = self.get_model_config(&request.model)?;
+
+        // Verify streaming is supported
+        if !model_config.supports_streaming {
+            return Err(LlmProviderError::InvalidRequest(format!(
+                "Model {} does not support streaming",
+                request.model
+            )));
+        }
+
+        let (system, messages) = convert_messages(request.messages)?;
+        let body = MessagesRequest {
+            model: &model_config.model_id,
+            max_tokens: request.max_tokens,
+            system,
+            messages,
+            temperature: request.temperature,
+            stream: true,
+        };
+
+        tracing::info!(
+            "Anthropic: Initiating stream request to {} with model {}",
+            self.api_base,
+            model_config.model_id
+        );
+
+        let response = self
+            .client
+            .post(format!("{}/messages", self.api_base.trim_end_matches('/')))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", &self.api_version)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("Anthropic: Failed to create stream: {}", e);
+                LlmProviderError::ApiError(e.to_string())
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<ErrorResponse>(&body)
+                .map_or(body, |e| format!("{}: {}", e.error.kind, e.error.message));
+            tracing::error!("Anthropic: Request failed with {}: {}", status, message);
+            return Err(LlmProviderError::ApiError(format!("{status}: {message}")));
+        }
+
+        tracing::info!("Anthropic: Stream created successfully");
+
+        Ok(Box::pin(chunk_stream(response)))
+    }
+
+    fn max_context_tokens(&self, model: &str) -> Option<u32> {
+        self.model_registry
+            .load()
+            .get_model(model)
+            .ok()
+            .map(|m| m.context_window)
+    }
+
+    fn max_output_tokens(&self, model: &str) -> Option<u32> {
+        self.model_registry
+            .load()
+            .get_model(model)
+            .ok()
+            .map(|m| m.max_output_tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::llm::replay::{assert_replay, registry, serve, Transcript};
+    use wiremock::{
+        matchers::{body_partial_json, header, path},
+        Mock,
+    };
+
+    fn message(role: ChatRole, content: &str) -> ProviderMessage {
+        ProviderMessage {
+            role,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_provider_unavailable() {
+        let provider = AnthropicProvider::new(String::new(), String::new(), registry().into());
+        assert_eq!(provider.name(), "Anthropic");
+        assert!(!provider.is_available());
+    }
+
+    #[test]
+    fn test_convert_messages() {
+        let (system, turns) = convert_messages(vec![
+            message(ChatRole::System, "Be brief."),
+            message(ChatRole::User, "Hi"),
+            message(ChatRole::User, "Are you there?"),
+            message(ChatRole::System, "Answer in English."),
+            message(ChatRole::Assistant, "Yes."),
+        ])
+        .unwrap();
+
+        assert_eq!(system.as_deref(), Some("Be brief.\n\nAnswer in English."));
+        assert_eq!(
+            turns,
+            [
+                Message {
+                    role: "user",
+                    content: "Hi\n\nAre you there?".to_string(),
+                },
+                Message {
+                    role: "assistant",
+                    content: "Yes.".to_string(),
+                },
+            ]
+        );
+
+        let result = convert_messages(vec![message(ChatRole::System, "Be brief.")]);
+        assert!(matches!(result, Err(LlmProviderError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_sse_decoder_joins_split_events() {
+        let mut decoder = SseDecoder::default();
+
+        assert!(decoder.push(b"event: ping\ndata: {\"type\"").is_empty());
+        assert_eq!(
+            decoder.push(b": \"ping\"}\r\n\r\nevent: message_stop\ndata: {}\n"),
+            ["{\"type\": \"ping\"}"]
+        );
+        assert_eq!(decoder.push(b"\n"), ["{}"]);
+    }
+
+    /// Replay `transcript` to a provider pointed at a mock Anthropic API
+    async fn replay(transcript: Transcript) {
+        let request = Mock::given(path("/v1/messages"))
+            .and(header("x-api-key", "test-key"))
+            .and(header("anthropic-version", DEFAULT_API_VERSION))
+            .and(body_partial_json(serde_json::json!({
+                "model": "claude-3-5-haiku-20241022",
+                "stream": true,
+            })));
+        let server = serve(request, transcript).await;
+        let provider = AnthropicProvider::new(
+            format!("{}/v1", server.uri()),
+            "test-key".to_string(),
+            registry().into(),
+        );
+
+        assert_replay(&provider, "claude-3-5-haiku", transcript).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_completed() {
+        replay(Transcript::stream("anthropic/completed.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_max_tokens() {
+        replay(Transcript::stream("anthropic/max_tokens.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_error_event() {
+        replay(Transcript::stream("anthropic/error_event.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_truncated() {
+        replay(Transcript::stream("anthropic/truncated.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_unauthorized() {
+        replay(Transcript::error("anthropic/unauthorized.json", 401)).await;
+    }
+}
@@ -4,14 +4,14 @@
 
 use super::provider::{
     ChatCompletionRequest, ChatMessage as ProviderMessage, ChatRole, LlmProvider,
-    LlmProviderError, LlmResult, StreamChunk,
+    LlmProviderError, LlmResult, StreamChunk, TokenUsage,
 };
 use async_openai::{
     config::AzureConfig,
     types::{
         ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
         ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-        CreateChatCompletionRequestArgs,
+        ChatCompletionStreamOptions, CreateChatCompletionRequestArgs,
     },
     Client,
 };
@@ -19,18 +19,23 @@ use async_trait::async_trait;
 use futures::{Stream, StreamExt};
 use std::pin::Pin;
 
-use crate::infrastructure::llm::{ModelConfig, ModelRegistry};
+use crate::infrastructure::llm::{ModelConfig, SharedModelRegistry};
 
 /// Azure AI provider using OpenAI-compatible API
 pub struct AzureAIProvider {
     endpoint: String,
     api_key: String,
-    model_registry: ModelRegistry,
+    model_registry: SharedModelRegistry,
 }
 
 impl AzureAIProvider {
     /// Create a new Azure AI provider
-    pub fn new(endpoint: String, api_key: String, model_registry: ModelRegistry) -> Self {
+    #[must_use]
+    pub const fn new(
+        endpoint: String,
+        api_key: String,
+        model_registry: SharedModelRegistry,
+    ) -> Self {
         Self {
             endpoint,
             api_key,
@@ -66,9 +71,11 @@ impl AzureAIProvider {
     }
 
     /// Get model configuration from registry
-    fn get_model_config(&self, model_id: &str) -> LlmResult<&ModelConfig> {
+    fn get_model_config(&self, model_id: &str) -> LlmResult<ModelConfig> {
         self.model_registry
+            .load()
             .get_model(model_id)
+            .cloned()
             .map_err(|e| LlmProviderError::ConfigError(e.to_string()))
     }
 
@@ -162,10 +169,17 @@ impl LlmProvider for AzureAIProvider {
 
         // Create streaming request
         // Note: AzureConfig already handles deployment_id, so we don't need to set model here
-        let openai_request = CreateChatCompletionRequestArgs::default()
-            .messages(openai_messages)
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.messages(openai_messages)
             .max_tokens(request.max_tokens)
             .stream(true)
+            .stream_options(ChatCompletionStreamOptions {
+                include_usage: true,
+            });
+        if let Some(temperature) = request.temperature {
+            args.temperature(temperature);
+        }
+        let openai_request = args
             .build()
             .map_err(|e| LlmProviderError::InvalidRequest(e.to_string()))?;
 
@@ -188,12 +202,25 @@ impl LlmProvider for AzureAIProvider {
         tracing::info!("Azure AI: Stream created successfully");
 
         // Transform API stream to provider stream
+        //
+        // With `include_usage`, the token counts come in a chunk without
+        // choices after the one with the finish reason, so the final chunk is
+        // sent once the API stream ends.
         let output_stream = async_stream::stream! {
             let mut chunk_count = 0;
+            let mut finish_reason = None;
+            let mut usage = None;
 
             while let Some(result) = api_stream.next().await {
                 match result {
                     Ok(response) => {
+                        if let Some(reported) = response.usage {
+                            usage = Some(TokenUsage {
+                                prompt_tokens: reported.prompt_tokens,
+                                completion_tokens: reported.completion_tokens,
+                            });
+                        }
+
                         for choice in response.choices {
                             // Handle content chunk
                             if let Some(content) = &choice.delta.content {
@@ -204,26 +231,21 @@ impl LlmProvider for AzureAIProvider {
                                     content: content.clone(),
                                     is_final: false,
                                     finish_reason: None,
+                                    usage: None,
                                 });
                             }
 
                             // Handle completion
                             if let Some(reason) = &choice.finish_reason {
-                                tracing::info!(
-                                    "Azure AI: Stream finished: reason={:?}, chunks={}",
-                                    reason,
-                                    chunk_count
-                                );
-
-                                yield Ok(StreamChunk {
-                                    content: String::new(),
-                                    is_final: true,
-                                    finish_reason: Some(format!("{:?}", reason)),
-                                });
-                                return;
+                                finish_reason = Some(format!("{reason:?}"));
                             }
                         }
                     }
+                    // The reply is complete; only the usage chunk is missing
+                    Err(e) if finish_reason.is_some() => {
+                        tracing::warn!("Azure AI: Stream error after finish_reason: {}", e);
+                        break;
+                    }
                     Err(e) => {
                         tracing::error!("Azure AI: Stream error: {}", e);
                         yield Err(LlmProviderError::StreamError(e.to_string()));
@@ -232,7 +254,24 @@ impl LlmProvider for AzureAIProvider {
                 }
             }
 
-            tracing::warn!("Azure AI: Stream ended without finish_reason");
+            if finish_reason.is_none() {
+                tracing::warn!("Azure AI: Stream ended without finish_reason");
+                return;
+            }
+
+            tracing::info!(
+                "Azure AI: Stream finished: reason={:?}, chunks={}, usage={:?}",
+                finish_reason,
+                chunk_count,
+                usage
+            );
+
+            yield Ok(StreamChunk {
+                content: String::new(),
+                is_final: true,
+                finish_reason,
+                usage,
+            });
         };
 
         Ok(Box::pin(output_stream))
@@ -240,6 +279,7 @@ impl LlmProvider for AzureAIProvider {
 
     fn max_context_tokens(&self, model: &str) -> Option<u32> {
         self.model_registry
+            .load()
             .get_model(model)
             .ok()
             .map(|m| m.context_window)
@@ -247,6 +287,7 @@ impl LlmProvider for AzureAIProvider {
 
     fn max_output_tokens(&self, model: &str) -> Option<u32> {
         self.model_registry
+            .load()
             .get_model(model)
             .ok()
             .map(|m| m.max_output_tokens)
@@ -256,6 +297,12 @@ impl LlmProvider for AzureAIProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infrastructure::llm::replay::{self, assert_replay, registry, serve, Transcript};
+    use crate::infrastructure::llm::ModelRegistry;
+    use wiremock::{
+        matchers::{body_partial_json, header, path, query_param},
+        Mock,
+    };
 
     #[test]
     fn test_provider_creation() {
@@ -268,7 +315,7 @@ mod tests {
         let provider = AzureAIProvider::new(
             "https://test.azure.com/models/chat/completions".to_string(),
             "test-key".to_string(),
-            registry,
+            registry.into(),
         );
 
         assert_eq!(provider.name(), "Azure AI");
@@ -283,7 +330,68 @@ mod tests {
             return;
         };
 
-        let provider = AzureAIProvider::new(String::new(), String::new(), registry);
+        let provider = AzureAIProvider::new(String::new(), String::new(), registry.into());
         assert!(!provider.is_available());
     }
+
+    /// Replay `transcript` to a provider pointed at a mock Azure AI endpoint
+    async fn replay(transcript: Transcript) {
+        let request = Mock::given(path("/openai/deployments/gpt-4o-mini/chat/completions"))
+            .and(query_param("api-version", "2024-05-01-preview"))
+            .and(header("api-key", "test-key"))
+            .and(body_partial_json(serde_json::json!({
+                "stream": true,
+                "stream_options": { "include_usage": true },
+            })));
+        let server = serve(request, transcript).await;
+        let provider = AzureAIProvider::new(
+            format!(
+                "{}/models/chat/completions?api-version=2024-05-01-preview",
+                server.uri()
+            ),
+            "test-key".to_string(),
+            registry().into(),
+        );
+
+        assert_replay(&provider, "gpt-4o-mini", transcript).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_completed() {
+        replay(Transcript::stream("azure/completed.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_usage() {
+        replay(Transcript::stream("azure/usage.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_content_filter() {
+        replay(Transcript::stream("azure/content_filter.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_truncated() {
+        replay(Transcript::stream("azure/truncated.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_rate_limited() {
+        replay(Transcript::error("azure/rate_limited.json", 429)).await;
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_without_api_version_is_rejected() {
+        let provider = AzureAIProvider::new(
+            "http://127.0.0.1:9/models/chat/completions".to_string(),
+            "test-key".to_string(),
+            registry().into(),
+        );
+
+        let result = provider
+            .create_chat_completion_stream(replay::request("gpt-4o-mini"))
+            .await;
+        assert!(matches!(result, Err(LlmProviderError::ConfigError(_))));
+    }
 }
@@ -1,20 +1,29 @@
 //! Provider factory for routing to appropriate LLM provider
 //!
 //! Creates and manages LLM provider instances based on model registry configuration.
+//!
+//! The model catalog can be reloaded at runtime: on Unix, `SIGHUP` re-reads
+//! models.toml and swaps the registry used by the factory and its providers
+//! (see [`ProviderFactory::reload`]). Providers themselves are created at
+//! startup, so changes to provider settings need a restart.
 
 use super::{
+    anthropic_provider::AnthropicProvider,
     azure_provider::AzureAIProvider,
-    model_registry::ModelRegistry,
+    model_registry::{ModelRegistry, RegistryDiff, SharedModelRegistry},
+    ollama_provider::{self, OllamaProvider},
     provider::{LlmProvider, LlmProviderError, LlmResult},
     sambanova_provider::SambaNovaProvider,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::infrastructure::fault_injection::{FaultInjector, FaultyProvider};
+
 /// Factory for creating and managing LLM providers
 pub struct ProviderFactory {
     providers: HashMap<String, Arc<dyn LlmProvider>>,
-    model_registry: ModelRegistry,
+    model_registry: SharedModelRegistry,
 }
 
 impl ProviderFactory {
@@ -23,10 +32,18 @@ impl ProviderFactory {
     /// # Errors
     /// Returns error if model registry cannot be loaded or providers cannot be initialized
     pub fn new() -> LlmResult<Self> {
-        // Load model registry
         let model_registry =
             ModelRegistry::load().map_err(|e| LlmProviderError::ConfigError(e.to_string()))?;
+        Self::from_registry(model_registry)
+    }
 
+    /// Create a provider factory for the providers enabled in `model_registry`
+    ///
+    /// # Errors
+    /// Returns error if providers cannot be initialized
+    pub fn from_registry(model_registry: ModelRegistry) -> LlmResult<Self> {
+        let shared_registry = SharedModelRegistry::new(model_registry);
+        let model_registry = shared_registry.load();
         let mut providers: HashMap<String, Arc<dyn LlmProvider>> = HashMap::new();
 
         // Initialize SambaNova provider if configured
@@ -41,7 +58,7 @@ impl ProviderFactory {
                     .clone()
                     .ok_or_else(|| LlmProviderError::ConfigError("SambaNova api_key missing".to_string()))?;
 
-                let provider = SambaNovaProvider::new(api_base, api_key, model_registry.clone());
+                let provider = SambaNovaProvider::new(api_base, api_key, shared_registry.clone());
                 providers.insert("sambanova".to_string(), Arc::new(provider));
                 tracing::info!("Initialized SambaNova provider");
             }
@@ -59,12 +76,53 @@ impl ProviderFactory {
                     .clone()
                     .ok_or_else(|| LlmProviderError::ConfigError("Azure api_key missing".to_string()))?;
 
-                let provider = AzureAIProvider::new(endpoint, api_key, model_registry.clone());
+                let provider = AzureAIProvider::new(endpoint, api_key, shared_registry.clone());
                 providers.insert("azure".to_string(), Arc::new(provider));
                 tracing::info!("Initialized Azure AI provider");
             }
         }
 
+        // Initialize Anthropic provider if configured
+        if let Ok(provider_config) = model_registry.get_provider("anthropic") {
+            if provider_config.enabled {
+                let api_base = provider_config.api_base.clone().ok_or_else(|| {
+                    LlmProviderError::ConfigError("Anthropic api_base missing".to_string())
+                })?;
+                let api_key = provider_config.api_key.clone().ok_or_else(|| {
+                    LlmProviderError::ConfigError("Anthropic api_key missing".to_string())
+                })?;
+
+                let mut provider =
+                    AnthropicProvider::new(api_base, api_key, shared_registry.clone());
+                if let Some(api_version) = provider_config.api_version.clone() {
+                    provider = provider.with_api_version(api_version);
+                }
+                providers.insert("anthropic".to_string(), Arc::new(provider));
+                tracing::info!("Initialized Anthropic provider");
+            }
+        }
+
+        // Initialize Ollama provider if configured; it needs no API key, and
+        // whether the server is running is checked in the background
+        if let Ok(provider_config) = model_registry.get_provider("ollama") {
+            if provider_config.enabled {
+                let api_base = provider_config
+                    .api_base
+                    .as_deref()
+                    .unwrap_or(ollama_provider::DEFAULT_API_BASE);
+
+                let provider = Arc::new(OllamaProvider::new(api_base, shared_registry.clone()));
+                if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                    let provider = Arc::clone(&provider);
+                    runtime.spawn(async move {
+                        provider.check_health().await;
+                    });
+                }
+                providers.insert("ollama".to_string(), provider);
+                tracing::info!("Initialized Ollama provider");
+            }
+        }
+
         if providers.is_empty() {
             return Err(LlmProviderError::ConfigError(
                 "No LLM providers configured".to_string(),
@@ -73,10 +131,20 @@ impl ProviderFactory {
 
         Ok(Self {
             providers,
-            model_registry,
+            model_registry: shared_registry,
         })
     }
 
+    /// Fail or slow down a share of the requests of every provider, as
+    /// configured in `faults`
+    #[must_use]
+    pub fn with_faults(mut self, faults: &Arc<FaultInjector>) -> Self {
+        for provider in self.providers.values_mut() {
+            *provider = Arc::new(FaultyProvider::new(Arc::clone(provider), Arc::clone(faults)));
+        }
+        self
+    }
+
     /// Get a provider by name
     pub fn get_provider(&self, name: &str) -> LlmResult<Arc<dyn LlmProvider>> {
         self.providers
@@ -88,8 +156,8 @@ impl ProviderFactory {
     /// Get the provider for a specific model ID
     pub fn get_provider_for_model(&self, model_id: &str) -> LlmResult<Arc<dyn LlmProvider>> {
         // Look up model in registry
-        let model = self
-            .model_registry
+        let model_registry = self.model_registry.load();
+        let model = model_registry
             .get_model(model_id)
             .map_err(|e| LlmProviderError::ConfigError(e.to_string()))?;
 
@@ -99,33 +167,137 @@ impl ProviderFactory {
 
     /// Get the default provider
     pub fn default_provider(&self) -> LlmResult<Arc<dyn LlmProvider>> {
-        let provider_name = self.model_registry.default_provider();
-        self.get_provider(provider_name)
+        self.get_provider(self.model_registry.load().default_provider())
     }
 
-    /// Get the model registry
-    pub fn model_registry(&self) -> &ModelRegistry {
-        &self.model_registry
+    /// Get the current model registry
+    ///
+    /// Hold on to the returned snapshot for the duration of an operation, so
+    /// a concurrent reload cannot change the models half-way.
+    #[must_use]
+    pub fn model_registry(&self) -> Arc<ModelRegistry> {
+        self.model_registry.load()
+    }
+
+    /// Re-read models.toml and replace the model registry
+    ///
+    /// # Errors
+    /// Returns error if models.toml cannot be loaded or fails validation; the
+    /// current registry stays in use
+    pub fn reload(&self) -> LlmResult<RegistryDiff> {
+        let registry =
+            ModelRegistry::load().map_err(|e| LlmProviderError::ConfigError(e.to_string()))?;
+        self.replace_registry(registry)
+    }
+
+    /// Replace the model registry if every enabled model can be served by
+    /// the providers initialized at startup
+    ///
+    /// # Errors
+    /// Returns error if the default model or an enabled model belongs to a
+    /// provider that is not initialized
+    pub fn replace_registry(&self, registry: ModelRegistry) -> LlmResult<RegistryDiff> {
+        let default_model = registry.default_model();
+        if !default_model.enabled {
+            return Err(LlmProviderError::ConfigError(format!(
+                "Default model '{}' is disabled",
+                default_model.id
+            )));
+        }
+        let mut unserved: Vec<&str> = registry
+            .enabled_models()
+            .into_iter()
+            .filter(|model| !self.providers.contains_key(&model.provider))
+            .map(|model| model.id.as_str())
+            .collect();
+        if !unserved.is_empty() {
+            unserved.sort_unstable();
+            return Err(LlmProviderError::ConfigError(format!(
+                "Models of providers not initialized at startup (restart to add providers): {}",
+                unserved.join(", ")
+            )));
+        }
+
+        let previous = self.model_registry.replace(registry);
+        Ok(previous.diff(&self.model_registry.load()))
     }
 
     /// List all available provider names
     pub fn available_providers(&self) -> Vec<String> {
         self.providers.keys().cloned().collect()
     }
-}
 
-// Implement Clone for ModelRegistry to support provider factory
-impl Clone for ModelRegistry {
-    fn clone(&self) -> Self {
-        // Load a fresh instance from file
-        // This is safe because ModelRegistry is immutable after loading
-        Self::load().expect("Failed to reload ModelRegistry")
+    /// Pick the model used to probe each provider, sorted by provider name
+    ///
+    /// Prefers the registry default model, then the first enabled model by
+    /// ID. Providers without an enabled model are skipped.
+    #[must_use]
+    pub fn probe_targets(&self) -> Vec<(String, Arc<dyn LlmProvider>, String)> {
+        let model_registry = self.model_registry.load();
+        let default_model = model_registry.default_model();
+        let mut targets: Vec<_> = self
+            .providers
+            .iter()
+            .filter_map(|(name, provider)| {
+                let model = if default_model.provider == *name {
+                    default_model.id.clone()
+                } else {
+                    model_registry
+                        .models_by_provider(name)
+                        .into_iter()
+                        .map(|model| model.id.clone())
+                        .min()?
+                };
+                Some((name.clone(), Arc::clone(provider), model))
+            })
+            .collect();
+        targets.sort_by(|a, b| a.0.cmp(&b.0));
+        targets
     }
 }
 
+/// Reload models.toml whenever the process receives `SIGHUP`, logging what
+/// changed. An invalid file is logged and the current models stay in use.
+///
+/// # Errors
+///
+/// Returns an error if the signal handler cannot be registered.
+#[cfg(unix)]
+pub fn spawn_sighup_reload(factory: Arc<ProviderFactory>) -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match factory.reload() {
+                Ok(diff) if diff.is_empty() => {
+                    tracing::info!("models.toml reloaded, no changes");
+                }
+                Ok(diff) => {
+                    tracing::info!(
+                        added = ?diff.added,
+                        removed = ?diff.removed,
+                        changed = ?diff.changed,
+                        default_model = ?diff.default_model,
+                        "models.toml reloaded"
+                    );
+                    if diff.providers_changed {
+                        tracing::warn!(
+                            "Provider settings in models.toml changed; restart to apply them"
+                        );
+                    }
+                }
+                Err(e) => tracing::error!("models.toml reload failed, keeping previous: {e}"),
+            }
+        }
+    });
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infrastructure::llm::replay;
 
     #[test]
     fn test_factory_creation() {
@@ -181,4 +353,73 @@ mod tests {
         let provider = factory.get_provider("nonexistent");
         assert!(provider.is_err());
     }
+
+    const EXTRA_MODEL: &str = r#"
+[[models]]
+id = "llama-long"
+name = "Llama long context"
+provider = "PROVIDER"
+model_id = "Meta-Llama-3.3-70B-Instruct"
+context_window = 131072
+max_output_tokens = 4096
+cost_per_million_input_tokens = 0.0
+cost_per_million_output_tokens = 0.0
+"#;
+
+    /// Fixture registry with an extra model served by `provider`
+    fn registry_with_extra_model(provider: &str) -> ModelRegistry {
+        let fixture =
+            std::fs::read_to_string(replay::fixtures_dir().join("models.toml")).unwrap();
+        ModelRegistry::parse(&(fixture + &EXTRA_MODEL.replace("PROVIDER", provider))).unwrap()
+    }
+
+    #[test]
+    fn test_replace_registry() {
+        let factory = ProviderFactory::from_registry(replay::registry()).unwrap();
+        let provider = factory.get_provider("sambanova").unwrap();
+
+        let diff = factory
+            .replace_registry(registry_with_extra_model("sambanova"))
+            .unwrap();
+        assert_eq!(diff.added, ["llama-long"]);
+        assert!(diff.removed.is_empty() && diff.changed.is_empty());
+        assert!(factory.model_registry().get_model("llama-long").is_ok());
+        assert_eq!(
+            factory.get_provider_for_model("llama-long").unwrap().name(),
+            "SambaNova"
+        );
+        // Providers created before the reload see the new models too
+        assert_eq!(provider.max_context_tokens("llama-long"), Some(131_072));
+    }
+
+    #[test]
+    fn test_anthropic_models_use_anthropic_provider() {
+        let factory = ProviderFactory::from_registry(replay::registry()).unwrap();
+
+        let provider = factory.get_provider_for_model("claude-3-5-haiku").unwrap();
+        assert_eq!(provider.name(), "Anthropic");
+        assert_eq!(
+            provider.max_context_tokens("claude-3-5-haiku"),
+            Some(200_000)
+        );
+    }
+
+    #[test]
+    fn test_local_models_use_ollama_provider() {
+        let factory = ProviderFactory::from_registry(replay::registry()).unwrap();
+
+        let provider = factory.get_provider_for_model("llama-3.2-local").unwrap();
+        assert_eq!(provider.name(), "Ollama");
+        assert_eq!(provider.max_output_tokens("llama-3.2-local"), Some(4096));
+    }
+
+    #[test]
+    fn test_replace_registry_rejects_uninitialized_provider() {
+        let factory = ProviderFactory::from_registry(replay::registry()).unwrap();
+
+        assert!(factory
+            .replace_registry(registry_with_extra_model("mistral"))
+            .is_err());
+        assert!(factory.model_registry().get_model("llama-long").is_err());
+    }
 }
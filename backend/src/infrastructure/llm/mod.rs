@@ -2,15 +2,20 @@
 //!
 //! Contains model registry and provider implementations for LLM services.
 
+pub mod anthropic_provider;
 pub mod azure_provider;
 pub mod factory;
 pub mod model_registry;
+pub mod ollama_provider;
+pub mod probe;
 pub mod provider;
+#[cfg(test)]
+pub(crate) mod replay;
 pub mod sambanova_provider;
 
 pub use factory::ProviderFactory;
-pub use model_registry::{ModelConfig, ModelRegistry, ProviderConfig};
+pub use model_registry::{ModelConfig, ModelRegistry, SharedModelRegistry};
 pub use provider::{
     ChatCompletionRequest, ChatMessage, ChatRole, LlmProvider, LlmProviderError, LlmResult,
-    StreamChunk,
+    StreamChunk, TokenUsage,
 };
@@ -1,13 +1,19 @@
 //! Model Registry for LLM configuration management
 //!
 //! Loads and manages model definitions from models.toml with environment variable substitution.
+//!
+//! The registry in use is held in a [`SharedModelRegistry`], so a reloaded
+//! models.toml can replace it without a restart (see
+//! [`super::ProviderFactory::reload`]).
 
+use arc_swap::ArcSwap;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -31,7 +37,7 @@ pub enum ModelRegistryError {
     ProviderNotFound(String),
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ProviderConfig {
     pub name: String,
     #[serde(default)]
@@ -46,7 +52,7 @@ pub struct ProviderConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ModelConfig {
     pub id: String,
     pub name: String,
@@ -88,6 +94,7 @@ struct ModelsToml {
     model_groups: HashMap<String, ModelGroup>,
 }
 
+#[derive(Debug, Clone)]
 pub struct ModelRegistry {
     default_provider: String,
     default_model_id: String,
@@ -96,17 +103,17 @@ pub struct ModelRegistry {
     model_groups: HashMap<String, ModelGroup>,
 }
 
+/// Where `load()` looks for models.toml: the current directory first, then
+/// the parent directory (for tests running in backend/)
+pub const SEARCH_PATHS: [&str; 2] = ["models.toml", "../models.toml"];
+
 impl ModelRegistry {
     /// Load model registry from models.toml
     pub fn load() -> Result<Self, ModelRegistryError> {
-        // Try loading from current directory first
-        if let Ok(registry) = Self::load_from_path("models.toml") {
-            return Ok(registry);
-        }
-
-        // Try loading from parent directory (for tests running in backend/)
-        if let Ok(registry) = Self::load_from_path("../models.toml") {
-            return Ok(registry);
+        for path in SEARCH_PATHS {
+            if let Ok(registry) = Self::load_from_path(path) {
+                return Ok(registry);
+            }
         }
 
         Err(ModelRegistryError::FileReadError(std::io::Error::new(
@@ -119,9 +126,13 @@ impl ModelRegistry {
     pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Self, ModelRegistryError> {
         // Read the TOML file
         let content = fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
 
+    /// Parse the contents of a models.toml
+    pub fn parse(content: &str) -> Result<Self, ModelRegistryError> {
         // Substitute environment variables
-        let substituted = Self::substitute_env_vars(&content)?;
+        let substituted = Self::substitute_env_vars(content)?;
 
         // Parse TOML
         let toml_config: ModelsToml = toml::from_str(&substituted)?;
@@ -149,9 +160,23 @@ impl ModelRegistry {
         })
     }
 
+    /// Names of the environment variables referenced as `${VAR_NAME}`, in
+    /// order of first use
+    #[must_use]
+    pub fn env_var_names(content: &str) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for cap in env_var_pattern().captures_iter(content) {
+            let name = &cap[1];
+            if !names.iter().any(|known| known == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
     /// Substitute ${VAR_NAME} patterns with environment variable values
     fn substitute_env_vars(content: &str) -> Result<String, ModelRegistryError> {
-        let re = Regex::new(r"\$\{([^}]+)\}").unwrap();
+        let re = env_var_pattern();
         let mut result = content.to_string();
 
         // Find all matches
@@ -205,6 +230,11 @@ impl ModelRegistry {
             .collect()
     }
 
+    /// Get all models, including disabled ones
+    pub fn models(&self) -> impl Iterator<Item = &ModelConfig> {
+        self.models.values()
+    }
+
     /// Get all enabled models
     pub fn enabled_models(&self) -> Vec<&ModelConfig> {
         self.models
@@ -235,15 +265,103 @@ impl ModelRegistry {
             .filter(|(_, p)| p.enabled)
             .collect()
     }
+
+    /// Compare with the registry `newer` replaces
+    #[must_use]
+    pub fn diff(&self, newer: &Self) -> RegistryDiff {
+        let ids = |registry: &Self| registry.models.keys().cloned().collect::<BTreeSet<_>>();
+        let (old_ids, new_ids) = (ids(self), ids(newer));
+
+        RegistryDiff {
+            added: new_ids.difference(&old_ids).cloned().collect(),
+            removed: old_ids.difference(&new_ids).cloned().collect(),
+            changed: old_ids
+                .intersection(&new_ids)
+                .filter(|id| self.models[*id] != newer.models[*id])
+                .cloned()
+                .collect(),
+            default_model: (self.default_model_id != newer.default_model_id).then(|| {
+                (
+                    self.default_model_id.clone(),
+                    newer.default_model_id.clone(),
+                )
+            }),
+            providers_changed: self.providers != newer.providers
+                || self.default_provider != newer.default_provider,
+        }
+    }
+}
+
+/// Differences between two registries, by model ID (sorted)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Models whose configuration differs (including `enabled`)
+    pub changed: Vec<String>,
+    /// Previous and new default model, when it changed
+    pub default_model: Option<(String, String)>,
+    /// Whether the provider settings differ
+    pub providers_changed: bool,
+}
+
+impl RegistryDiff {
+    /// Check whether the registries are the same
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.default_model.is_none()
+            && !self.providers_changed
+    }
+}
+
+/// Model registry shared by the provider factory and the providers, replaced
+/// as a whole on reload
+///
+/// Readers take a snapshot with [`Self::load`]; a snapshot stays consistent
+/// even if the registry is replaced while it is in use.
+#[derive(Clone)]
+pub struct SharedModelRegistry(Arc<ArcSwap<ModelRegistry>>);
+
+impl SharedModelRegistry {
+    #[must_use]
+    pub fn new(registry: ModelRegistry) -> Self {
+        Self(Arc::new(ArcSwap::from_pointee(registry)))
+    }
+
+    /// Current registry
+    #[must_use]
+    pub fn load(&self) -> Arc<ModelRegistry> {
+        self.0.load_full()
+    }
+
+    /// Replace the registry, returning the previous one
+    #[must_use]
+    pub fn replace(&self, registry: ModelRegistry) -> Arc<ModelRegistry> {
+        self.0.swap(Arc::new(registry))
+    }
+}
+
+impl From<ModelRegistry> for SharedModelRegistry {
+    fn from(registry: ModelRegistry) -> Self {
+        Self::new(registry)
+    }
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn env_var_pattern() -> Regex {
+    Regex::new(r"\$\{([^}]+)\}").unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infrastructure::llm::replay::registry;
 
     #[test]
     fn test_env_var_substitution() {
@@ -269,6 +387,13 @@ mod tests {
         assert!(matches!(result, Err(ModelRegistryError::EnvVarNotFound(_))));
     }
 
+    #[test]
+    fn test_env_var_names() {
+        let input = "key = \"${A_KEY}\"\nbase = \"${A_BASE}\"\nother = \"${A_KEY}\"";
+
+        assert_eq!(ModelRegistry::env_var_names(input), vec!["A_KEY", "A_BASE"]);
+    }
+
     #[test]
     fn test_load_registry() {
         // This test requires actual models.toml and environment variables
@@ -292,4 +417,42 @@ mod tests {
             assert!(!registry.enabled_models().is_empty());
         }
     }
+
+    #[test]
+    fn test_diff() {
+        let old = registry();
+        assert!(old.diff(&registry()).is_empty());
+
+        let mut new = registry();
+        new.models.remove("no-streaming");
+        new.models.get_mut("gpt-4o-mini").unwrap().enabled = false;
+        let mut added = new.models["llama-3.3-70b"].clone();
+        added.id = "llama-4".to_string();
+        new.models.insert(added.id.clone(), added);
+        new.default_model_id = "gpt-4o-mini".to_string();
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, ["llama-4"]);
+        assert_eq!(diff.removed, ["no-streaming"]);
+        assert_eq!(diff.changed, ["gpt-4o-mini"]);
+        assert_eq!(
+            diff.default_model,
+            Some(("llama-3.3-70b".to_string(), "gpt-4o-mini".to_string()))
+        );
+        assert!(!diff.providers_changed);
+    }
+
+    #[test]
+    fn test_shared_registry_replace_keeps_snapshots() {
+        let shared = SharedModelRegistry::new(registry());
+        let snapshot = shared.load();
+
+        let mut new = registry();
+        new.models.remove("no-streaming");
+        let previous = shared.replace(new);
+
+        assert!(previous.get_model("no-streaming").is_ok());
+        assert!(snapshot.get_model("no-streaming").is_ok());
+        assert!(shared.load().get_model("no-streaming").is_err());
+    }
 }
@@ -0,0 +1,430 @@
+//! Ollama LLM provider implementation
+//!
+//! Implements the `LlmProvider` trait against a local Ollama server
+//! (`POST /api/chat`), so the chat stack runs without cloud API keys. The
+//! stream is newline-delimited JSON: one object per piece of the reply, the
+//! last with `"done": true`.
+//!
+//! Ollama is often not running on a developer machine, so availability is
+//! tracked rather than assumed: [`OllamaProvider::check_health`] asks the
+//! server for its version, and every chat request updates the verdict too.
+
+use super::provider::{
+    ChatCompletionRequest, ChatMessage as ProviderMessage, ChatRole, LlmProvider, LlmProviderError,
+    LlmResult, StreamChunk,
+};
+use async_trait::async_trait;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::infrastructure::llm::{ModelConfig, SharedModelRegistry};
+
+/// Address Ollama listens on by default
+pub const DEFAULT_API_BASE: &str = "http://localhost:11434";
+
+/// How long a health check waits for the server
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Ollama provider using the native chat API
+pub struct OllamaProvider {
+    api_base: String,
+    client: reqwest::Client,
+    model_registry: SharedModelRegistry,
+    /// Whether the server answered the last health check or request
+    reachable: AtomicBool,
+}
+
+/// Body of `POST /api/chat`
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<Message>,
+    stream: bool,
+    options: Options,
+}
+
+/// A message of the conversation; Ollama takes system messages in line
+#[derive(Debug, Serialize)]
+struct Message {
+    role: &'static str,
+    content: String,
+}
+
+/// Sampling options
+#[derive(Debug, Serialize)]
+struct Options {
+    /// Most tokens to generate
+    num_predict: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+}
+
+/// One line of the stream, or the body of an error response
+#[derive(Debug, Deserialize)]
+struct ChatResponseLine {
+    #[serde(default)]
+    message: Option<ResponseMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    done_reason: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseMessage {
+    #[serde(default)]
+    content: String,
+}
+
+impl OllamaProvider {
+    /// Create a new Ollama provider for the server at `api_base`
+    ///
+    /// The provider reports itself unavailable until a health check or a
+    /// request reaches the server.
+    #[must_use]
+    pub fn new(api_base: &str, model_registry: SharedModelRegistry) -> Self {
+        Self {
+            api_base: api_base.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+            model_registry,
+            reachable: AtomicBool::new(false),
+        }
+    }
+
+    /// Ask the server for its version and record whether it answered
+    pub async fn check_health(&self) -> bool {
+        let result = self
+            .client
+            .get(format!("{}/api/version", self.api_base))
+            .timeout(HEALTH_CHECK_TIMEOUT)
+            .send()
+            .await;
+        let reachable = match result {
+            Ok(response) if response.status().is_success() => true,
+            Ok(response) => {
+                tracing::warn!(
+                    "Ollama: Health check at {} answered {}",
+                    self.api_base,
+                    response.status()
+                );
+                false
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Ollama: Server at {} is not reachable: {}",
+                    self.api_base,
+                    e
+                );
+                false
+            }
+        };
+        self.reachable.store(reachable, Ordering::Relaxed);
+        reachable
+    }
+
+    /// Get model configuration from registry
+    fn get_model_config(&self, model_id: &str) -> LlmResult<ModelConfig> {
+        self.model_registry
+            .load()
+            .get_model(model_id)
+            .cloned()
+            .map_err(|e| LlmProviderError::ConfigError(e.to_string()))
+    }
+}
+
+/// Convert provider messages to Ollama messages
+fn convert_messages(messages: Vec<ProviderMessage>) -> Vec<Message> {
+    messages
+        .into_iter()
+        .map(|msg| Message {
+            role: match msg.role {
+                ChatRole::System => "system",
+                ChatRole::User => "user",
+                ChatRole::Assistant => "assistant",
+            },
+            content: msg.content,
+        })
+        .collect()
+}
+
+/// Splits a newline-delimited JSON stream into its lines
+#[derive(Debug, Default)]
+struct LineDecoder {
+    /// Bytes after the last complete line
+    buffer: Vec<u8>,
+}
+
+impl LineDecoder {
+    /// Feed `bytes` of the stream, returning the non-empty lines they complete
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+        let mut lines = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            // A complete line splits no character, so this is lossless
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if !line.is_empty() {
+                lines.push(line.to_string());
+            }
+        }
+        lines
+    }
+}
+
+/// Transform the lines of a successful response into provider chunks
+fn chunk_stream(
+    response: reqwest::Response,
+) -> impl Stream<Item = Result<StreamChunk, LlmProviderError>> + Send {
+    async_stream::stream! {
+        let mut response = response;
+        let mut decoder = LineDecoder::default();
+        let mut chunk_count = 0;
+
+        loop {
+            let bytes = match response.chunk().await {
+                Ok(Some(bytes)) => bytes,
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("Ollama: Stream error: {}", e);
+                    yield Err(LlmProviderError::StreamError(e.to_string()));
+                    return;
+                }
+            };
+
+            for line in decoder.push(&bytes) {
+                let line = match serde_json::from_str::<ChatResponseLine>(&line) {
+                    Ok(line) => line,
+                    Err(e) => {
+                        tracing::error!("Ollama: Malformed line: {}", e);
+                        yield Err(LlmProviderError::StreamError(e.to_string()));
+                        return;
+                    }
+                };
+
+                if let Some(error) = line.error {
+                    tracing::error!("Ollama: Stream error: {}", error);
+                    yield Err(LlmProviderError::StreamError(error));
+                    return;
+                }
+
+                let content = line.message.map(|m| m.content).unwrap_or_default();
+                if !content.is_empty() {
+                    chunk_count += 1;
+                    tracing::debug!("Ollama: Chunk #{}: {} bytes", chunk_count, content.len());
+
+                    yield Ok(StreamChunk {
+                        content,
+                        is_final: false,
+                        finish_reason: None,
+                        usage: None,
+                    });
+                }
+
+                if line.done {
+                    tracing::info!(
+                        "Ollama: Stream finished: reason={:?}, chunks={}",
+                        line.done_reason,
+                        chunk_count
+                    );
+
+                    yield Ok(StreamChunk {
+                        content: String::new(),
+                        is_final: true,
+                        finish_reason: line.done_reason,
+                        usage: None,
+                    });
+                    return;
+                }
+            }
+        }
+
+        tracing::warn!("Ollama: Stream ended without a done line");
+        yield Err(LlmProviderError::StreamError(
+            "Stream ended without a done line".to_string(),
+        ));
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &'static str {
+        "Ollama"
+    }
+
+    fn is_available(&self) -> bool {
+        !self.api_base.is_empty() && self.reachable.load(Ordering::Relaxed)
+    }
+
+    async fn create_chat_completion_stream(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> LlmResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, LlmProviderError>> + Send>>> {
+        // Get model config to retrieve provider-specific model_id
+        let model_config = self.get_model_config(&request.model)?;
+
+        // Verify streaming is supported
+        if !model_config.supports_streaming {
+            return Err(LlmProviderError::InvalidRequest(format!(
+                "Model {} does not support streaming",
+                request.model
+            )));
+        }
+
+        let body = ChatRequest {
+            model: &model_config.model_id,
+            messages: convert_messages(request.messages),
+            stream: true,
+            options: Options {
+                num_predict: request.max_tokens,
+                temperature: request.temperature,
+            },
+        };
+
+        tracing::info!(
+            "Ollama: Initiating stream request to {} with model {}",
+            self.api_base,
+            model_config.model_id
+        );
+
+        let response = self
+            .client
+            .post(format!("{}/api/chat", self.api_base))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("Ollama: Failed to create stream: {}", e);
+                self.reachable.store(false, Ordering::Relaxed);
+                LlmProviderError::ApiError(e.to_string())
+            })?;
+        self.reachable.store(true, Ordering::Relaxed);
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<ChatResponseLine>(&body)
+                .ok()
+                .and_then(|line| line.error)
+                .unwrap_or(body);
+            tracing::error!("Ollama: Request failed with {}: {}", status, message);
+            return Err(LlmProviderError::ApiError(format!("{status}: {message}")));
+        }
+
+        tracing::info!("Ollama: Stream created successfully");
+
+        Ok(Box::pin(chunk_stream(response)))
+    }
+
+    fn max_context_tokens(&self, model: &str) -> Option<u32> {
+        self.model_registry
+            .load()
+            .get_model(model)
+            .ok()
+            .map(|m| m.context_window)
+    }
+
+    fn max_output_tokens(&self, model: &str) -> Option<u32> {
+        self.model_registry
+            .load()
+            .get_model(model)
+            .ok()
+            .map(|m| m.max_output_tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::llm::replay::{assert_replay, registry, serve, Transcript};
+    use wiremock::{
+        matchers::{body_partial_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[test]
+    fn test_provider_unavailable_until_checked() {
+        let provider = OllamaProvider::new(DEFAULT_API_BASE, registry().into());
+        assert_eq!(provider.name(), "Ollama");
+        assert!(!provider.is_available());
+    }
+
+    #[test]
+    fn test_line_decoder_joins_split_lines() {
+        let mut decoder = LineDecoder::default();
+
+        assert!(decoder.push(b"{\"done\":").is_empty());
+        assert_eq!(
+            decoder.push(b"false}\n\n{\"done\":true}\r\n{"),
+            ["{\"done\":false}", "{\"done\":true}"]
+        );
+        assert!(decoder.push(b"}").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_health() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/version"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"version":"0.4.0"}"#))
+            .mount(&server)
+            .await;
+        let provider = OllamaProvider::new(&server.uri(), registry().into());
+
+        assert!(provider.check_health().await);
+        assert!(provider.is_available());
+
+        server.reset().await;
+        Mock::given(method("GET"))
+            .and(path("/api/version"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+        assert!(!provider.check_health().await);
+        assert!(!provider.is_available());
+    }
+
+    /// Replay `transcript` to a provider pointed at a mock Ollama server
+    async fn replay(transcript: Transcript) {
+        let request = Mock::given(path("/api/chat")).and(body_partial_json(serde_json::json!({
+            "model": "llama3.2",
+            "stream": true,
+            "options": { "num_predict": 64 },
+        })));
+        let server = serve(request, transcript).await;
+        let provider = OllamaProvider::new(&server.uri(), registry().into());
+
+        assert_replay(&provider, "llama-3.2-local", transcript).await;
+        assert!(provider.is_available());
+    }
+
+    #[tokio::test]
+    async fn test_replay_completed() {
+        replay(Transcript::stream("ollama/completed.ndjson")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_length() {
+        replay(Transcript::stream("ollama/length.ndjson")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_error_line() {
+        replay(Transcript::stream("ollama/error_line.ndjson")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_truncated() {
+        replay(Transcript::stream("ollama/truncated.ndjson")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_model_not_found() {
+        replay(Transcript::error("ollama/model_not_found.json", 404)).await;
+    }
+}
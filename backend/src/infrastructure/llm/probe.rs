@@ -0,0 +1,168 @@
+//! Synthetic provider probes
+//!
+//! A probe sends a one-token completion to a provider and records whether it
+//! answered within the timeout. Run on a schedule, probes catch expired keys
+//! and provider outages before users do.
+
+use chrono::Utc;
+use futures::future::join_all;
+use std::time::{Duration, Instant};
+
+use super::{
+    factory::ProviderFactory,
+    provider::{ChatCompletionRequest, ChatMessage, ChatRole, LlmProvider},
+};
+use crate::services::valkey::provider_health::ProviderProbe;
+
+/// Prompt of the synthetic completion
+const PROBE_PROMPT: &str = "ping";
+
+/// Probe one provider with `model`
+pub async fn probe_provider(
+    provider: &dyn LlmProvider,
+    provider_key: &str,
+    model: &str,
+    timeout: Duration,
+) -> ProviderProbe {
+    let request = ChatCompletionRequest {
+        model: model.to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: PROBE_PROMPT.to_string(),
+        }],
+        max_tokens: 1,
+        temperature: None,
+        stream: false,
+    };
+
+    let started = Instant::now();
+    let result = tokio::time::timeout(timeout, provider.create_chat_completion(request)).await;
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    let error = match result {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("No response within {}s", timeout.as_secs_f32())),
+    };
+    if let Some(error) = &error {
+        tracing::warn!(
+            "LLM provider probe failed for {} ({}): {}",
+            provider_key,
+            model,
+            error
+        );
+    }
+
+    ProviderProbe {
+        provider: provider_key.to_string(),
+        model: model.to_string(),
+        healthy: error.is_none(),
+        latency_ms,
+        error,
+        checked_at: Utc::now(),
+    }
+}
+
+/// Probe every enabled provider concurrently
+pub async fn probe_all(factory: &ProviderFactory, timeout: Duration) -> Vec<ProviderProbe> {
+    let targets = factory.probe_targets();
+    join_all(
+        targets
+            .iter()
+            .map(|(name, provider, model)| probe_provider(provider.as_ref(), name, model, timeout)),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::llm::{LlmProviderError, LlmResult, StreamChunk};
+    use async_trait::async_trait;
+    use futures::Stream;
+    use std::pin::Pin;
+
+    /// Provider answering after `delay`, or failing
+    struct StubProvider {
+        delay: Duration,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl LlmProvider for StubProvider {
+        fn name(&self) -> &'static str {
+            "Stub"
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn create_chat_completion_stream(
+            &self,
+            _request: ChatCompletionRequest,
+        ) -> LlmResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, LlmProviderError>> + Send>>>
+        {
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err(LlmProviderError::ApiError("invalid api key".to_string()));
+            }
+            Ok(Box::pin(futures::stream::iter([Ok(StreamChunk {
+                content: "pong".to_string(),
+                is_final: true,
+                finish_reason: Some("stop".to_string()),
+                usage: None,
+            })])))
+        }
+
+        fn max_context_tokens(&self, _model: &str) -> Option<u32> {
+            None
+        }
+
+        fn max_output_tokens(&self, _model: &str) -> Option<u32> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_healthy_provider() {
+        let provider = StubProvider {
+            delay: Duration::ZERO,
+            fail: false,
+        };
+
+        let probe = probe_provider(&provider, "stub", "model-a", Duration::from_secs(1)).await;
+
+        assert!(probe.healthy);
+        assert_eq!(probe.provider, "stub");
+        assert_eq!(probe.model, "model-a");
+        assert_eq!(probe.error, None);
+    }
+
+    #[tokio::test]
+    async fn test_probe_failing_provider() {
+        let provider = StubProvider {
+            delay: Duration::ZERO,
+            fail: true,
+        };
+
+        let probe = probe_provider(&provider, "stub", "model-a", Duration::from_secs(1)).await;
+
+        assert!(!probe.healthy);
+        assert!(probe.error.unwrap().contains("invalid api key"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_times_out() {
+        let provider = StubProvider {
+            delay: Duration::from_secs(5),
+            fail: false,
+        };
+
+        let probe = probe_provider(&provider, "stub", "model-a", Duration::from_millis(20)).await;
+
+        assert!(!probe.healthy);
+        assert!(probe.latency_ms < 5000);
+        assert!(probe.error.unwrap().contains("No response"));
+    }
+}
@@ -3,9 +3,11 @@
 //! Defines the interface that all LLM providers must implement for streaming chat completions.
 
 use async_trait::async_trait;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use std::pin::Pin;
 
+use crate::domain::chat::repository::RepositoryError;
+
 /// Request for creating a chat completion
 #[derive(Debug, Clone)]
 pub struct ChatCompletionRequest {
@@ -15,6 +17,8 @@ pub struct ChatCompletionRequest {
     pub messages: Vec<ChatMessage>,
     /// Maximum tokens to generate
     pub max_tokens: u16,
+    /// Sampling temperature (`None` = provider default)
+    pub temperature: Option<f32>,
     /// Whether to stream the response
     pub stream: bool,
 }
@@ -43,6 +47,16 @@ pub struct StreamChunk {
     pub is_final: bool,
     /// Optional finish reason
     pub finish_reason: Option<String>,
+    /// Tokens billed for the request, on the final chunk of providers that
+    /// report them
+    pub usage: Option<TokenUsage>,
+}
+
+/// Token counts reported by the provider for a completion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
 }
 
 /// Error types for LLM provider operations
@@ -63,6 +77,19 @@ pub enum LlmProviderError {
 
 pub type LlmResult<T> = Result<T, LlmProviderError>;
 
+/// Provider failures as seen by the chat use cases
+///
+/// A request the provider rejected as invalid is the client's to fix; any
+/// other failure means the model cannot answer right now.
+impl From<LlmProviderError> for RepositoryError {
+    fn from(err: LlmProviderError) -> Self {
+        match err {
+            LlmProviderError::InvalidRequest(msg) => Self::ValidationError(msg),
+            err => Self::ProviderUnavailable(err.to_string()),
+        }
+    }
+}
+
 /// Trait that all LLM providers must implement
 #[async_trait]
 pub trait LlmProvider: Send + Sync {
@@ -80,6 +107,19 @@ pub trait LlmProvider: Send + Sync {
         request: ChatCompletionRequest,
     ) -> LlmResult<Pin<Box<dyn Stream<Item = Result<StreamChunk, LlmProviderError>> + Send>>>;
 
+    /// Create a chat completion and wait for the whole response
+    ///
+    /// Collects [`Self::create_chat_completion_stream`]; used where no
+    /// incremental output is needed (e.g. health probes).
+    async fn create_chat_completion(&self, request: ChatCompletionRequest) -> LlmResult<String> {
+        let mut stream = self.create_chat_completion_stream(request).await?;
+        let mut content = String::new();
+        while let Some(chunk) = stream.next().await {
+            content.push_str(&chunk?.content);
+        }
+        Ok(content)
+    }
+
     /// Get the maximum context window for a model
     fn max_context_tokens(&self, model: &str) -> Option<u32>;
 
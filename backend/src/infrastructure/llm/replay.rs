@@ -0,0 +1,159 @@
+//! Replay of recorded provider streams, for provider tests
+//!
+//! Fixtures live in `backend/tests/fixtures/llm/<provider>/`: a captured
+//! response body (`.sse` or `.ndjson` for a stream, `.json` for an error
+//! response) and a `.golden` file with what the provider is expected to make
+//! of it. The body is served by a [`MockServer`] that only answers requests of
+//! the shape the provider should send, so a wrong URL, model or credential
+//! shows up as a golden mismatch too.
+//!
+//! Run the tests with `UPDATE_GOLDEN=1` to rewrite the golden files after an
+//! intended change, then review the diff.
+
+use futures::StreamExt;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use wiremock::{MockBuilder, MockServer, ResponseTemplate};
+
+use super::provider::{LlmProvider, LlmProviderError, StreamChunk};
+use super::{ChatCompletionRequest, ChatMessage, ChatRole, ModelRegistry};
+
+/// Directory holding the recorded transcripts
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/llm")
+}
+
+/// Registry of the models the fixtures were recorded with
+pub fn registry() -> ModelRegistry {
+    ModelRegistry::load_from_path(fixtures_dir().join("models.toml"))
+        .expect("fixture models.toml should load")
+}
+
+/// A recorded response: `file` relative to [`fixtures_dir`], served with
+/// `status`
+#[derive(Debug, Clone, Copy)]
+pub struct Transcript {
+    pub file: &'static str,
+    pub status: u16,
+}
+
+impl Transcript {
+    /// A stream answered with 200
+    pub const fn stream(file: &'static str) -> Self {
+        Self { file, status: 200 }
+    }
+
+    /// An error response answered with `status`
+    pub const fn error(file: &'static str, status: u16) -> Self {
+        Self { file, status }
+    }
+
+    fn response(self) -> ResponseTemplate {
+        let path = fixtures_dir().join(self.file);
+        let body = std::fs::read(&path)
+            .unwrap_or_else(|e| panic!("cannot read fixture {}: {e}", path.display()));
+        let content_type = match Path::new(self.file).extension().and_then(|e| e.to_str()) {
+            Some("sse") => "text/event-stream",
+            Some("ndjson") => "application/x-ndjson",
+            _ => "application/json",
+        };
+        ResponseTemplate::new(self.status).set_body_raw(body, content_type)
+    }
+
+    fn golden_path(self) -> PathBuf {
+        fixtures_dir().join(self.file).with_extension("golden")
+    }
+}
+
+/// Start a server answering the POST requests matched by `request` with
+/// `transcript`
+pub async fn serve(request: MockBuilder, transcript: Transcript) -> MockServer {
+    let server = MockServer::start().await;
+    request
+        .and(wiremock::matchers::method("POST"))
+        .respond_with(transcript.response())
+        .mount(&server)
+        .await;
+    server
+}
+
+/// One-message request for `model`
+pub fn request(model: &str) -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: model.to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "Say hello".to_string(),
+        }],
+        max_tokens: 64,
+        temperature: None,
+        stream: true,
+    }
+}
+
+/// Stream `model` from `provider` and compare the result with the golden file
+/// of `transcript`
+pub async fn assert_replay(provider: &dyn LlmProvider, model: &str, transcript: Transcript) {
+    let rendered = match provider.create_chat_completion_stream(request(model)).await {
+        Ok(stream) => render(&stream.collect::<Vec<_>>().await),
+        Err(e) => format!("request {}\n", error_kind(&e)),
+    };
+    assert_golden(&transcript.golden_path(), &rendered);
+}
+
+/// Describe a stream, one item per line, then the assembled reply
+///
+/// Errors are reduced to their kind: their messages come from the HTTP
+/// client and change between its versions.
+pub fn render(items: &[Result<StreamChunk, LlmProviderError>]) -> String {
+    let mut out = String::new();
+    let mut content = String::new();
+    for item in items {
+        let _ = match item {
+            Ok(chunk) if chunk.is_final => match chunk.usage {
+                Some(usage) => writeln!(
+                    out,
+                    "final {:?} usage prompt={} completion={}",
+                    chunk.finish_reason, usage.prompt_tokens, usage.completion_tokens
+                ),
+                None => writeln!(out, "final {:?}", chunk.finish_reason),
+            },
+            Ok(chunk) => {
+                content.push_str(&chunk.content);
+                writeln!(out, "chunk {:?}", chunk.content)
+            }
+            Err(e) => writeln!(out, "error {}", error_kind(e)),
+        };
+    }
+    let _ = writeln!(out, "= {content:?}");
+    out
+}
+
+const fn error_kind(error: &LlmProviderError) -> &'static str {
+    match error {
+        LlmProviderError::ApiError(_) => "ApiError",
+        LlmProviderError::ConfigError(_) => "ConfigError",
+        LlmProviderError::StreamError(_) => "StreamError",
+        LlmProviderError::InvalidRequest(_) => "InvalidRequest",
+    }
+}
+
+fn assert_golden(path: &Path, actual: &str) {
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(path, actual)
+            .unwrap_or_else(|e| panic!("cannot write {}: {e}", path.display()));
+        return;
+    }
+    let expected = std::fs::read_to_string(path).unwrap_or_else(|e| {
+        panic!(
+            "cannot read {} ({e}); run with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        )
+    });
+    assert_eq!(
+        actual,
+        expected,
+        "replay differs from {} (run with UPDATE_GOLDEN=1 to accept)",
+        path.display()
+    );
+}
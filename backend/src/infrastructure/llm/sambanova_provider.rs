@@ -4,14 +4,14 @@
 
 use super::provider::{
     ChatCompletionRequest, ChatMessage as ProviderMessage, ChatRole, LlmProvider,
-    LlmProviderError, LlmResult, StreamChunk,
+    LlmProviderError, LlmResult, StreamChunk, TokenUsage,
 };
 use async_openai::{
     config::OpenAIConfig,
     types::{
         ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
         ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-        CreateChatCompletionRequestArgs,
+        ChatCompletionStreamOptions, CreateChatCompletionRequestArgs,
     },
     Client,
 };
@@ -19,18 +19,23 @@ use async_trait::async_trait;
 use futures::{Stream, StreamExt};
 use std::pin::Pin;
 
-use crate::infrastructure::llm::{ModelConfig, ModelRegistry};
+use crate::infrastructure::llm::{ModelConfig, SharedModelRegistry};
 
 /// SambaNova provider using OpenAI-compatible API
 pub struct SambaNovaProvider {
     api_base: String,
     api_key: String,
-    model_registry: ModelRegistry,
+    model_registry: SharedModelRegistry,
 }
 
 impl SambaNovaProvider {
     /// Create a new SambaNova provider
-    pub fn new(api_base: String, api_key: String, model_registry: ModelRegistry) -> Self {
+    #[must_use]
+    pub const fn new(
+        api_base: String,
+        api_key: String,
+        model_registry: SharedModelRegistry,
+    ) -> Self {
         Self {
             api_base,
             api_key,
@@ -66,9 +71,11 @@ impl SambaNovaProvider {
     }
 
     /// Get model configuration from registry
-    fn get_model_config(&self, model_id: &str) -> LlmResult<&ModelConfig> {
+    fn get_model_config(&self, model_id: &str) -> LlmResult<ModelConfig> {
         self.model_registry
+            .load()
             .get_model(model_id)
+            .cloned()
             .map_err(|e| LlmProviderError::ConfigError(e.to_string()))
     }
 }
@@ -109,11 +116,18 @@ impl LlmProvider for SambaNovaProvider {
         let client = Client::with_config(config);
 
         // Create streaming request using provider-specific model_id
-        let openai_request = CreateChatCompletionRequestArgs::default()
-            .model(&model_config.model_id) // Use provider-specific model_id
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.model(&model_config.model_id) // Use provider-specific model_id
             .messages(openai_messages)
             .max_tokens(request.max_tokens)
             .stream(true)
+            .stream_options(ChatCompletionStreamOptions {
+                include_usage: true,
+            });
+        if let Some(temperature) = request.temperature {
+            args.temperature(temperature);
+        }
+        let openai_request = args
             .build()
             .map_err(|e| LlmProviderError::InvalidRequest(e.to_string()))?;
 
@@ -136,12 +150,25 @@ impl LlmProvider for SambaNovaProvider {
         tracing::info!("SambaNova: Stream created successfully");
 
         // Transform API stream to provider stream
+        //
+        // With `include_usage`, the token counts come in a chunk without
+        // choices after the one with the finish reason, so the final chunk is
+        // sent once the API stream ends.
         let output_stream = async_stream::stream! {
             let mut chunk_count = 0;
+            let mut finish_reason = None;
+            let mut usage = None;
 
             while let Some(result) = api_stream.next().await {
                 match result {
                     Ok(response) => {
+                        if let Some(reported) = response.usage {
+                            usage = Some(TokenUsage {
+                                prompt_tokens: reported.prompt_tokens,
+                                completion_tokens: reported.completion_tokens,
+                            });
+                        }
+
                         for choice in response.choices {
                             // Handle content chunk
                             if let Some(content) = &choice.delta.content {
@@ -152,26 +179,21 @@ impl LlmProvider for SambaNovaProvider {
                                     content: content.clone(),
                                     is_final: false,
                                     finish_reason: None,
+                                    usage: None,
                                 });
                             }
 
                             // Handle completion
                             if let Some(reason) = &choice.finish_reason {
-                                tracing::info!(
-                                    "SambaNova: Stream finished: reason={:?}, chunks={}",
-                                    reason,
-                                    chunk_count
-                                );
-
-                                yield Ok(StreamChunk {
-                                    content: String::new(),
-                                    is_final: true,
-                                    finish_reason: Some(format!("{:?}", reason)),
-                                });
-                                return;
+                                finish_reason = Some(format!("{reason:?}"));
                             }
                         }
                     }
+                    // The reply is complete; only the usage chunk is missing
+                    Err(e) if finish_reason.is_some() => {
+                        tracing::warn!("SambaNova: Stream error after finish_reason: {}", e);
+                        break;
+                    }
                     Err(e) => {
                         tracing::error!("SambaNova: Stream error: {}", e);
                         yield Err(LlmProviderError::StreamError(e.to_string()));
@@ -180,7 +202,24 @@ impl LlmProvider for SambaNovaProvider {
                 }
             }
 
-            tracing::warn!("SambaNova: Stream ended without finish_reason");
+            if finish_reason.is_none() {
+                tracing::warn!("SambaNova: Stream ended without finish_reason");
+                return;
+            }
+
+            tracing::info!(
+                "SambaNova: Stream finished: reason={:?}, chunks={}, usage={:?}",
+                finish_reason,
+                chunk_count,
+                usage
+            );
+
+            yield Ok(StreamChunk {
+                content: String::new(),
+                is_final: true,
+                finish_reason,
+                usage,
+            });
         };
 
         Ok(Box::pin(output_stream))
@@ -188,6 +227,7 @@ impl LlmProvider for SambaNovaProvider {
 
     fn max_context_tokens(&self, model: &str) -> Option<u32> {
         self.model_registry
+            .load()
             .get_model(model)
             .ok()
             .map(|m| m.context_window)
@@ -195,6 +235,7 @@ impl LlmProvider for SambaNovaProvider {
 
     fn max_output_tokens(&self, model: &str) -> Option<u32> {
         self.model_registry
+            .load()
             .get_model(model)
             .ok()
             .map(|m| m.max_output_tokens)
@@ -204,6 +245,12 @@ impl LlmProvider for SambaNovaProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infrastructure::llm::replay::{self, assert_replay, registry, serve, Transcript};
+    use crate::infrastructure::llm::ModelRegistry;
+    use wiremock::{
+        matchers::{body_partial_json, header, path},
+        Mock,
+    };
 
     #[test]
     fn test_provider_creation() {
@@ -216,7 +263,7 @@ mod tests {
         let provider = SambaNovaProvider::new(
             "https://api.sambanova.ai/v1".to_string(),
             "test-key".to_string(),
-            registry,
+            registry.into(),
         );
 
         assert_eq!(provider.name(), "SambaNova");
@@ -231,7 +278,7 @@ mod tests {
             return;
         };
 
-        let provider = SambaNovaProvider::new(String::new(), String::new(), registry);
+        let provider = SambaNovaProvider::new(String::new(), String::new(), registry.into());
         assert!(!provider.is_available());
     }
 
@@ -246,7 +293,7 @@ mod tests {
         let provider = SambaNovaProvider::new(
             "https://api.sambanova.ai/v1".to_string(),
             "test-key".to_string(),
-            registry,
+            registry.into(),
         );
 
         // Test with default model
@@ -258,4 +305,72 @@ mod tests {
         assert!(output.is_some());
         assert!(output.unwrap() > 0);
     }
+
+    /// Replay `transcript` to a provider pointed at a mock server
+    async fn replay(transcript: Transcript) {
+        let request = Mock::given(path("/v1/chat/completions"))
+            .and(header("authorization", "Bearer test-key"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "Meta-Llama-3.3-70B-Instruct",
+                "stream": true,
+                "stream_options": { "include_usage": true },
+            })));
+        let server = serve(request, transcript).await;
+        let provider = SambaNovaProvider::new(
+            format!("{}/v1", server.uri()),
+            "test-key".to_string(),
+            registry().into(),
+        );
+
+        assert_replay(&provider, "llama-3.3-70b", transcript).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_completed() {
+        replay(Transcript::stream("sambanova/completed.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_length_limit() {
+        replay(Transcript::stream("sambanova/length.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_done_without_finish_reason() {
+        replay(Transcript::stream("sambanova/no_finish_reason.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_error_event() {
+        replay(Transcript::stream("sambanova/error_event.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_malformed_chunk() {
+        replay(Transcript::stream("sambanova/malformed.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_truncated() {
+        replay(Transcript::stream("sambanova/truncated.sse")).await;
+    }
+
+    #[tokio::test]
+    async fn test_replay_unauthorized() {
+        replay(Transcript::error("sambanova/unauthorized.json", 401)).await;
+    }
+
+    #[tokio::test]
+    async fn test_model_without_streaming_is_rejected() {
+        let provider = SambaNovaProvider::new(
+            "http://127.0.0.1:9/v1".to_string(),
+            "test-key".to_string(),
+            registry().into(),
+        );
+
+        let result = provider
+            .create_chat_completion_stream(replay::request("no-streaming"))
+            .await;
+        assert!(matches!(result, Err(LlmProviderError::InvalidRequest(_))));
+    }
 }
@@ -3,5 +3,9 @@
 //! Contains implementations of domain interfaces (repository traits)
 //! and external service integrations.
 
+pub mod fault_injection;
 pub mod llm;
+pub mod object_storage;
 pub mod persistence;
+pub mod session_lock;
+pub mod share_attempts;
@@ -0,0 +1,292 @@
+//! Object storage for large generated files
+//!
+//! Backup exports can be hundreds of megabytes, too much to rebuild or hold
+//! in memory for every download attempt. They are written once to an
+//! [`ObjectStorage`] under a key, along with their size and SHA-256
+//! checksum, and read back as a stream of chunks limited to a byte range, so
+//! an interrupted download resumes where it stopped.
+//!
+//! [`LocalObjectStorage`] keeps objects in a directory
+//! (`OBJECT_STORAGE_DIR`), each next to a `.meta.json` file holding its
+//! [`ObjectMetadata`].
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    io::{ErrorKind, SeekFrom},
+    ops::Range,
+    path::PathBuf,
+};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use uuid::Uuid;
+
+/// Size of the chunks objects are streamed in
+const READ_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Suffix of the metadata file stored next to each object
+const METADATA_SUFFIX: &str = ".meta.json";
+
+/// Errors from storing or reading objects
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Invalid object key '{0}'")]
+    InvalidKey(String),
+
+    #[error("Object '{0}' not found")]
+    NotFound(String),
+
+    #[error("Range {start}-{end} is outside the object")]
+    InvalidRange { start: u64, end: u64 },
+
+    #[error("Storage I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid object metadata: {0}")]
+    Metadata(#[from] serde_json::Error),
+}
+
+/// Result type for storage operations
+pub type StorageResult<T> = Result<T, StorageError>;
+
+/// Stream of an object's bytes
+pub type ByteStream = BoxStream<'static, std::io::Result<Bytes>>;
+
+/// What is known about a stored object without reading it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectMetadata {
+    /// Size in bytes
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the content
+    pub sha256: String,
+    /// File name offered to clients downloading the object
+    pub filename: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Store of write-once objects read back in byte ranges
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    /// Store `data` under `key`, replacing any object with that key
+    async fn put(&self, key: &str, filename: &str, data: Bytes) -> StorageResult<ObjectMetadata>;
+
+    /// Metadata of the object under `key`, `None` if there is none
+    async fn metadata(&self, key: &str) -> StorageResult<Option<ObjectMetadata>>;
+
+    /// Stream the bytes of `range` (end exclusive) of the object under `key`
+    async fn read(&self, key: &str, range: Range<u64>) -> StorageResult<ByteStream>;
+
+    /// Remove the object under `key`; `false` if there was none
+    async fn delete(&self, key: &str) -> StorageResult<bool>;
+}
+
+/// [`ObjectStorage`] in a local directory
+#[derive(Debug, Clone)]
+pub struct LocalObjectStorage {
+    root: PathBuf,
+}
+
+impl LocalObjectStorage {
+    /// Storage in `root`, created on the first write
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Path of the object under `key`
+    ///
+    /// Keys are single path segments of ASCII letters, digits, `-`, `_` and
+    /// `.`, so they cannot point outside the root.
+    fn object_path(&self, key: &str) -> StorageResult<PathBuf> {
+        let valid = !key.is_empty()
+            && !key.starts_with('.')
+            && !key.ends_with(METADATA_SUFFIX)
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(StorageError::InvalidKey(key.to_string()));
+        }
+        Ok(self.root.join(key))
+    }
+
+    fn metadata_path(&self, key: &str) -> StorageResult<PathBuf> {
+        self.object_path(key)
+            .map(|path| path.with_file_name(format!("{key}{METADATA_SUFFIX}")))
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for LocalObjectStorage {
+    async fn put(&self, key: &str, filename: &str, data: Bytes) -> StorageResult<ObjectMetadata> {
+        let path = self.object_path(key)?;
+        let metadata = ObjectMetadata {
+            size: data.len() as u64,
+            sha256: hex::encode(Sha256::digest(&data)),
+            filename: filename.to_string(),
+            created_at: Utc::now(),
+        };
+        tokio::fs::create_dir_all(&self.root).await?;
+
+        // Written aside and renamed, so readers never see a partial object;
+        // the metadata goes last and marks the object as complete
+        let temporary = self.root.join(format!(".{key}.{}", Uuid::new_v4()));
+        let mut file = tokio::fs::File::create(&temporary).await?;
+        file.write_all(&data).await?;
+        file.sync_all().await?;
+        drop(file);
+        tokio::fs::rename(&temporary, &path).await?;
+        tokio::fs::write(self.metadata_path(key)?, serde_json::to_vec(&metadata)?).await?;
+
+        Ok(metadata)
+    }
+
+    async fn metadata(&self, key: &str) -> StorageResult<Option<ObjectMetadata>> {
+        match tokio::fs::read(self.metadata_path(key)?).await {
+            Ok(json) => Ok(Some(serde_json::from_slice(&json)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn read(&self, key: &str, range: Range<u64>) -> StorageResult<ByteStream> {
+        let size = self
+            .metadata(key)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(key.to_string()))?
+            .size;
+        if range.start > range.end || range.end > size {
+            return Err(StorageError::InvalidRange {
+                start: range.start,
+                end: range.end,
+            });
+        }
+
+        let mut file = tokio::fs::File::open(self.object_path(key)?).await?;
+        file.seek(SeekFrom::Start(range.start)).await?;
+
+        // Chunks are read as the client consumes them, never the whole range
+        let remaining = range.end - range.start;
+        let stream =
+            futures::stream::try_unfold((file, remaining), |(mut file, remaining)| async move {
+                if remaining == 0 {
+                    return Ok(None);
+                }
+                let len = usize::try_from(remaining)
+                    .map_or(READ_CHUNK_BYTES, |r| r.min(READ_CHUNK_BYTES));
+                let mut buffer = vec![0; len];
+                let read = file.read(&mut buffer).await?;
+                if read == 0 {
+                    return Err(std::io::Error::from(ErrorKind::UnexpectedEof));
+                }
+                buffer.truncate(read);
+                Ok(Some((Bytes::from(buffer), (file, remaining - read as u64))))
+            });
+
+        Ok(stream.boxed())
+    }
+
+    async fn delete(&self, key: &str) -> StorageResult<bool> {
+        // Metadata first, so a half-deleted object already reads as missing
+        match tokio::fs::remove_file(self.metadata_path(key)?).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        }
+        match tokio::fs::remove_file(self.object_path(key)?).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(true),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storage() -> (LocalObjectStorage, PathBuf) {
+        let root = std::env::temp_dir().join(format!("cobalt-objects-{}", Uuid::new_v4()));
+        (LocalObjectStorage::new(&root), root)
+    }
+
+    async fn collect(stream: ByteStream) -> Vec<u8> {
+        let chunks: Vec<Bytes> = stream.map(Result::unwrap).collect().await;
+        chunks.concat()
+    }
+
+    #[tokio::test]
+    async fn test_put_and_read_ranges() {
+        let (storage, root) = storage();
+        let data: Vec<u8> = (0..200_000u32)
+            .map(|i| u8::try_from(i % 251).unwrap())
+            .collect();
+
+        let metadata = storage
+            .put("export-1.csbk", "backup.csbk", Bytes::from(data.clone()))
+            .await
+            .unwrap();
+        assert_eq!(metadata.size, data.len() as u64);
+        assert_eq!(metadata.sha256, hex::encode(Sha256::digest(&data)));
+        assert_eq!(
+            storage.metadata("export-1.csbk").await.unwrap(),
+            Some(metadata)
+        );
+
+        let full = storage
+            .read("export-1.csbk", 0..data.len() as u64)
+            .await
+            .unwrap();
+        assert_eq!(collect(full).await, data);
+
+        // A resumed download past the first chunk
+        let tail = storage
+            .read("export-1.csbk", 100_000..150_000)
+            .await
+            .unwrap();
+        assert_eq!(collect(tail).await, &data[100_000..150_000]);
+
+        let beyond = storage
+            .read("export-1.csbk", 0..data.len() as u64 + 1)
+            .await;
+        assert!(matches!(beyond, Err(StorageError::InvalidRange { .. })));
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let (storage, root) = storage();
+        storage
+            .put("export-2.csbk", "backup.csbk", Bytes::from_static(b"data"))
+            .await
+            .unwrap();
+
+        assert!(storage.delete("export-2.csbk").await.unwrap());
+        assert!(!storage.delete("export-2.csbk").await.unwrap());
+        assert_eq!(storage.metadata("export-2.csbk").await.unwrap(), None);
+        assert!(matches!(
+            storage.read("export-2.csbk", 0..0).await,
+            Err(StorageError::NotFound(_))
+        ));
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_keys_stay_inside_the_root() {
+        let (storage, _root) = storage();
+        for key in ["", "../etc/passwd", "a/b", ".hidden", "x.meta.json"] {
+            assert!(
+                matches!(
+                    storage.metadata(key).await,
+                    Err(StorageError::InvalidKey(_))
+                ),
+                "{key}"
+            );
+        }
+    }
+}
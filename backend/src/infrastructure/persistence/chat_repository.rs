@@ -1,26 +1,50 @@
 //! ChatRepository implementation using SeaORM
 //!
-//! Implements the domain ChatRepository trait for database persistence.
+//! Implements the domain `ChatRepository`, `MessageImportRepository`,
+//! `MessageDeletionRepository`, `ReadStateRepository`,
+//! `AnnotationRepository`, `ShareRepository`, `SuspensionRepository`,
+//! `ChatDefaultsRepository`, `UsageRepository`, `JobRepository` and
+//! `WebhookRepository` traits for database persistence.
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
-    QueryOrder, QuerySelect, Set,
+    sea_query::{Expr, OnConflict, SimpleExpr},
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseBackend, DatabaseConnection,
+    EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait, RelationTrait, Set, TransactionTrait,
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 
 use crate::{
     domain::chat::{
+        annotation::{
+            Annotation, AnnotationFilter, AnnotationKind, AnnotationRepository, MessageAnnotation,
+        },
+        deletion::MessageDeletionRepository,
         entity::{ChatMessage, ChatSession},
+        import::MessageImportRepository,
+        job::{ChatJob, ChatJobItem, JobItemStatus, JobRepository, JobStatus},
+        preferences::{ChatDefaults, ChatDefaultsRepository},
+        read_state::{ReadState, ReadStateRepository},
         repository::{ChatRepository, RepositoryError, RepositoryResult},
+        share::{ChatShare, ShareRepository},
+        suspension::SuspensionRepository,
+        usage::{DailyMessages, ModelTokens, SessionActivity, UsageRecord, UsageRepository},
         value_objects::MessageRole,
+        webhook::{ChatWebhook, WebhookDelivery, WebhookRepository},
     },
     models::{
-        chat_messages, chat_sessions,
-        prelude::{ChatMessages, ChatSessions},
+        chat_job_items, chat_jobs, chat_messages, chat_read_states, chat_sessions, chat_shares,
+        chat_usage, chat_webhooks, message_annotations,
+        prelude::{
+            ChatJobItems, ChatJobs, ChatMessages, ChatReadStates, ChatSessions, ChatShares,
+            ChatUsage, ChatWebhooks, MessageAnnotations, Users,
+        },
+        users,
     },
+    services::preferences,
 };
 
 /// SeaORM implementation of ChatRepository
@@ -44,12 +68,15 @@ impl SeaOrmChatRepository {
             created_at: model.created_at.with_timezone(&Utc),
             updated_at: model.updated_at.with_timezone(&Utc),
             deleted_at: model.deleted_at.map(|dt| dt.with_timezone(&Utc)),
+            version: model.version,
         }
     }
 
     /// Convert SeaORM model to domain entity
     fn model_to_message(model: chat_messages::Model) -> RepositoryResult<ChatMessage> {
-        let role = MessageRole::from_str(&model.role)
+        let role = model
+            .role
+            .parse::<MessageRole>()
             .map_err(|e| RepositoryError::ValidationError(e))?;
 
         Ok(ChatMessage {
@@ -59,23 +86,119 @@ impl SeaOrmChatRepository {
             content: model.content,
             token_count: model.token_count,
             created_at: model.created_at.with_timezone(&Utc),
+            redacted_at: model.redacted_at.map(|dt| dt.with_timezone(&Utc)),
         })
     }
-}
 
-#[async_trait]
-impl ChatRepository for SeaOrmChatRepository {
-    async fn create_session(&self, session: &ChatSession) -> RepositoryResult<()> {
-        let active_model = chat_sessions::ActiveModel {
+    /// Convert domain session to a `SeaORM` active model for insertion
+    fn session_to_active_model(session: &ChatSession) -> chat_sessions::ActiveModel {
+        chat_sessions::ActiveModel {
             id: Set(session.id),
             user_id: Set(session.user_id),
             title: Set(session.title.clone()),
             created_at: Set(session.created_at.into()),
             updated_at: Set(session.updated_at.into()),
             deleted_at: Set(session.deleted_at.map(Into::into)),
-        };
+            archived_at: Set(None),
+            version: Set(session.version),
+        }
+    }
 
-        active_model
+    /// Convert domain message to a `SeaORM` active model for insertion
+    fn message_to_active_model(message: &ChatMessage) -> chat_messages::ActiveModel {
+        chat_messages::ActiveModel {
+            id: Set(message.id),
+            session_id: Set(message.session_id),
+            role: Set(message.role.as_str().to_string()),
+            content: Set(message.content.clone()),
+            token_count: Set(message.token_count),
+            created_at: Set(message.created_at.into()),
+            redacted_at: Set(message.redacted_at.map(Into::into)),
+        }
+    }
+
+    /// Convert `SeaORM` model to domain read state
+    fn model_to_read_state(model: &chat_read_states::Model) -> ReadState {
+        ReadState {
+            user_id: model.user_id,
+            session_id: model.session_id,
+            last_read_message_id: model.last_read_message_id,
+            last_read_at: model.last_read_at.with_timezone(&Utc),
+        }
+    }
+
+    /// Convert `SeaORM` model to domain annotation
+    fn model_to_annotation(model: &message_annotations::Model) -> RepositoryResult<MessageAnnotation> {
+        let kind = AnnotationKind::parse(&model.kind).map_err(RepositoryError::ValidationError)?;
+        let value = Some(model.value.as_str()).filter(|value| !value.is_empty());
+
+        Ok(MessageAnnotation {
+            id: model.id,
+            message_id: model.message_id,
+            session_id: model.session_id,
+            user_id: model.user_id,
+            annotation: Annotation::new(kind, value).map_err(RepositoryError::ValidationError)?,
+            created_at: model.created_at.with_timezone(&Utc),
+        })
+    }
+
+    /// Find the user's annotation with the same kind and value on a message
+    async fn find_identical_annotation(
+        &self,
+        annotation: &MessageAnnotation,
+    ) -> RepositoryResult<Option<MessageAnnotation>> {
+        MessageAnnotations::find()
+            .filter(message_annotations::Column::MessageId.eq(annotation.message_id))
+            .filter(message_annotations::Column::UserId.eq(annotation.user_id))
+            .filter(message_annotations::Column::Kind.eq(annotation.annotation.kind().as_str()))
+            .filter(
+                message_annotations::Column::Value
+                    .eq(annotation.annotation.value().unwrap_or_default()),
+            )
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .map(|model| Self::model_to_annotation(&model))
+            .transpose()
+    }
+
+    /// Convert `SeaORM` model to domain share
+    fn model_to_share(model: chat_shares::Model) -> ChatShare {
+        ChatShare {
+            id: model.id,
+            session_id: model.session_id,
+            user_id: model.user_id,
+            slug: model.slug,
+            password_hash: model.password_hash,
+            expires_at: model.expires_at.map(|dt| dt.with_timezone(&Utc)),
+            revoked_at: model.revoked_at.map(|dt| dt.with_timezone(&Utc)),
+            suspended_at: model.suspended_at.map(|dt| dt.with_timezone(&Utc)),
+            view_count: u64::try_from(model.view_count).unwrap_or(0),
+            created_at: model.created_at.with_timezone(&Utc),
+        }
+    }
+
+    /// Convert `SeaORM` model to domain webhook
+    fn model_to_webhook(model: chat_webhooks::Model) -> ChatWebhook {
+        ChatWebhook {
+            id: model.id,
+            user_id: model.user_id,
+            url: model.url,
+            secret: model.secret,
+            created_at: model.created_at.with_timezone(&Utc),
+            last_delivery_at: model.last_delivery_at.map(|dt| dt.with_timezone(&Utc)),
+            last_status: model
+                .last_status
+                .and_then(|status| u16::try_from(status).ok()),
+            last_error: model.last_error,
+        }
+    }
+}
+
+#[async_trait]
+impl ChatRepository for SeaOrmChatRepository {
+    async fn create_session(&self, session: &ChatSession) -> RepositoryResult<()> {
+        Self::session_to_active_model(session)
             .insert(self.db.as_ref())
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
@@ -98,10 +221,11 @@ impl ChatRepository for SeaOrmChatRepository {
         page: u64,
         per_page: u64,
     ) -> RepositoryResult<(Vec<ChatSession>, u64)> {
-        // Filter out deleted sessions
+        // Filter out deleted and archived sessions
         let query = ChatSessions::find()
             .filter(chat_sessions::Column::UserId.eq(user_id))
             .filter(chat_sessions::Column::DeletedAt.is_null())
+            .filter(chat_sessions::Column::ArchivedAt.is_null())
             .order_by_desc(chat_sessions::Column::CreatedAt);
 
         // Get total count
@@ -124,53 +248,57 @@ impl ChatRepository for SeaOrmChatRepository {
     }
 
     async fn update_session(&self, session: &ChatSession) -> RepositoryResult<()> {
-        let active_model = chat_sessions::ActiveModel {
-            id: Set(session.id),
-            user_id: Set(session.user_id),
-            title: Set(session.title.clone()),
-            created_at: Set(session.created_at.into()),
-            updated_at: Set(Utc::now().into()),
-            deleted_at: Set(session.deleted_at.map(Into::into)),
-        };
-
-        active_model
-            .update(self.db.as_ref())
+        // Compare-and-set on the version, so a concurrent update is not
+        // silently overwritten
+        let result = ChatSessions::update_many()
+            .col_expr(
+                chat_sessions::Column::Title,
+                Expr::value(session.title.clone()),
+            )
+            .col_expr(chat_sessions::Column::UpdatedAt, Expr::value(Utc::now()))
+            .col_expr(
+                chat_sessions::Column::Version,
+                Expr::col(chat_sessions::Column::Version).add(1),
+            )
+            .filter(chat_sessions::Column::Id.eq(session.id))
+            .filter(chat_sessions::Column::Version.eq(session.version))
+            .filter(chat_sessions::Column::DeletedAt.is_null())
+            .exec(self.db.as_ref())
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        Ok(())
+        if result.rows_affected > 0 {
+            return Ok(());
+        }
+
+        // Nothing matched: either the session is gone or it moved on
+        match self.find_session_by_id(session.id).await? {
+            Some(current) if !current.is_deleted() => {
+                Err(RepositoryError::SessionConflict(session.id))
+            }
+            _ => Err(RepositoryError::SessionNotFound(session.id)),
+        }
     }
 
-    async fn delete_session(&self, id: Uuid) -> RepositoryResult<()> {
+    async fn delete_session(&self, id: Uuid, user_id: Uuid) -> RepositoryResult<()> {
         // Soft delete: set deleted_at timestamp
-        let session = ChatSessions::find_by_id(id)
-            .one(self.db.as_ref())
-            .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
-            .ok_or(RepositoryError::SessionNotFound(id))?;
-
-        let mut active_model: chat_sessions::ActiveModel = session.into();
-        active_model.deleted_at = Set(Some(Utc::now().into()));
-
-        active_model
-            .update(self.db.as_ref())
+        let result = ChatSessions::update_many()
+            .col_expr(chat_sessions::Column::DeletedAt, Expr::value(Utc::now()))
+            .filter(chat_sessions::Column::Id.eq(id))
+            .filter(chat_sessions::Column::UserId.eq(user_id))
+            .filter(chat_sessions::Column::DeletedAt.is_null())
+            .exec(self.db.as_ref())
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::SessionNotFound(id));
+        }
         Ok(())
     }
 
     async fn save_message(&self, message: &ChatMessage) -> RepositoryResult<()> {
-        let active_model = chat_messages::ActiveModel {
-            id: Set(message.id),
-            session_id: Set(message.session_id),
-            role: Set(message.role.as_str().to_string()),
-            content: Set(message.content.clone()),
-            token_count: Set(message.token_count),
-            created_at: Set(message.created_at.into()),
-        };
-
-        active_model
+        Self::message_to_active_model(message)
             .insert(self.db.as_ref())
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
@@ -210,6 +338,7 @@ impl ChatRepository for SeaOrmChatRepository {
         // Get last N messages in descending order, then reverse to chronological
         let models = ChatMessages::find()
             .filter(chat_messages::Column::SessionId.eq(session_id))
+            .filter(chat_messages::Column::RedactedAt.is_null())
             .order_by_desc(chat_messages::Column::CreatedAt)
             .limit(limit)
             .all(self.db.as_ref())
@@ -228,66 +357,1853 @@ impl ChatRepository for SeaOrmChatRepository {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::domain::chat::value_objects::MessageRole;
+#[async_trait]
+impl MessageImportRepository for SeaOrmChatRepository {
+    async fn save_messages(&self, messages: &[ChatMessage]) -> RepositoryResult<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
 
-    #[test]
-    fn test_model_to_session() {
-        let model = chat_sessions::Model {
-            id: Uuid::new_v4(),
-            user_id: Uuid::new_v4(),
-            title: "Test Session".to_string(),
-            created_at: Utc::now().into(),
-            updated_at: Utc::now().into(),
-            deleted_at: None,
-        };
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        let session = SeaOrmChatRepository::model_to_session(model.clone());
+        ChatMessages::insert_many(messages.iter().map(Self::message_to_active_model))
+            .exec_without_returning(&txn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        assert_eq!(session.id, model.id);
-        assert_eq!(session.user_id, model.user_id);
-        assert_eq!(session.title, model.title);
+        txn.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
     }
 
-    #[test]
-    fn test_model_to_message() {
-        let model = chat_messages::Model {
-            id: Uuid::new_v4(),
-            session_id: Uuid::new_v4(),
-            role: "user".to_string(),
-            content: "Hello".to_string(),
-            token_count: Some(5),
-            created_at: Utc::now().into(),
+    async fn create_session_with_messages(
+        &self,
+        session: &ChatSession,
+        messages: &[ChatMessage],
+    ) -> RepositoryResult<()> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Self::session_to_active_model(session)
+            .insert(&txn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if !messages.is_empty() {
+            ChatMessages::insert_many(messages.iter().map(Self::message_to_active_model))
+                .exec_without_returning(&txn)
+                .await
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageDeletionRepository for SeaOrmChatRepository {
+    async fn find_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> RepositoryResult<Option<ChatMessage>> {
+        ChatMessages::find_by_id(message_id)
+            .filter(chat_messages::Column::SessionId.eq(session_id))
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .map(Self::model_to_message)
+            .transpose()
+    }
+
+    async fn delete_message(&self, message: &ChatMessage) -> RepositoryResult<()> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        // Read markers on the message move to the one before it; the read
+        // time is kept so no later message turns unread
+        let previous = ChatMessages::find()
+            .filter(chat_messages::Column::SessionId.eq(message.session_id))
+            .filter(chat_messages::Column::Id.ne(message.id))
+            .filter(chat_messages::Column::CreatedAt.lte(message.created_at))
+            .order_by_desc(chat_messages::Column::CreatedAt)
+            .one(&txn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        let markers = chat_read_states::Column::LastReadMessageId.eq(message.id);
+        match previous {
+            Some(previous) => ChatReadStates::update_many()
+                .col_expr(
+                    chat_read_states::Column::LastReadMessageId,
+                    Expr::value(previous.id),
+                )
+                .filter(markers)
+                .exec(&txn)
+                .await
+                .map(drop),
+            None => ChatReadStates::delete_many()
+                .filter(markers)
+                .exec(&txn)
+                .await
+                .map(drop),
+        }
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        MessageAnnotations::delete_many()
+            .filter(message_annotations::Column::MessageId.eq(message.id))
+            .exec(&txn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let result = ChatMessages::delete_by_id(message.id)
+            .exec(&txn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::MessageNotFound(message.id));
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn redact_message(&self, message: &ChatMessage) -> RepositoryResult<()> {
+        let result = ChatMessages::update_many()
+            .col_expr(
+                chat_messages::Column::Content,
+                Expr::value(message.content.clone()),
+            )
+            .col_expr(
+                chat_messages::Column::RedactedAt,
+                Expr::value(message.redacted_at.map(DateTime::<FixedOffset>::from)),
+            )
+            .filter(chat_messages::Column::Id.eq(message.id))
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::MessageNotFound(message.id));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReadStateRepository for SeaOrmChatRepository {
+    async fn find_read_state(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> RepositoryResult<Option<ReadState>> {
+        let model = ChatReadStates::find_by_id((user_id, session_id))
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(model.as_ref().map(Self::model_to_read_state))
+    }
+
+    async fn mark_read(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> RepositoryResult<()> {
+        let message = ChatMessages::find_by_id(message_id)
+            .filter(chat_messages::Column::SessionId.eq(session_id))
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .ok_or(RepositoryError::MessageNotFound(message_id))?;
+
+        let active_model = chat_read_states::ActiveModel {
+            user_id: Set(user_id),
+            session_id: Set(session_id),
+            last_read_message_id: Set(message.id),
+            last_read_at: Set(message.created_at),
+            updated_at: Set(Utc::now().into()),
         };
 
-        let message = SeaOrmChatRepository::model_to_message(model.clone()).unwrap();
+        // Upsert, but never move the read position backwards
+        ChatReadStates::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([
+                    chat_read_states::Column::UserId,
+                    chat_read_states::Column::SessionId,
+                ])
+                .update_columns([
+                    chat_read_states::Column::LastReadMessageId,
+                    chat_read_states::Column::LastReadAt,
+                    chat_read_states::Column::UpdatedAt,
+                ])
+                .action_and_where(
+                    Expr::col((ChatReadStates, chat_read_states::Column::LastReadAt))
+                        .lt(message.created_at),
+                )
+                .to_owned(),
+            )
+            .exec_without_returning(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        assert_eq!(message.id, model.id);
-        assert_eq!(message.session_id, model.session_id);
-        assert_eq!(message.role, MessageRole::User);
-        assert_eq!(message.content, model.content);
-        assert_eq!(message.token_count, model.token_count);
+        Ok(())
     }
 
-    #[test]
-    fn test_model_to_message_invalid_role() {
-        let model = chat_messages::Model {
-            id: Uuid::new_v4(),
-            session_id: Uuid::new_v4(),
-            role: "invalid".to_string(),
-            content: "Hello".to_string(),
-            token_count: None,
-            created_at: Utc::now().into(),
+    async fn count_unread(
+        &self,
+        user_id: Uuid,
+        session_ids: &[Uuid],
+    ) -> RepositoryResult<HashMap<Uuid, u64>> {
+        if session_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let read_at: HashMap<Uuid, _> = ChatReadStates::find()
+            .filter(chat_read_states::Column::UserId.eq(user_id))
+            .filter(chat_read_states::Column::SessionId.is_in(session_ids.iter().copied()))
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .map(|state| (state.session_id, state.last_read_at))
+            .collect();
+
+        // One condition per session: everything after its read position
+        let unread = session_ids.iter().fold(Condition::any(), |condition, id| {
+            let mut in_session = Condition::all().add(chat_messages::Column::SessionId.eq(*id));
+            if let Some(last_read_at) = read_at.get(id) {
+                in_session = in_session.add(chat_messages::Column::CreatedAt.gt(*last_read_at));
+            }
+            condition.add(in_session)
+        });
+
+        let counts: Vec<(Uuid, i64)> = ChatMessages::find()
+            .select_only()
+            .column(chat_messages::Column::SessionId)
+            .column_as(Expr::col(chat_messages::Column::Id).count(), "unread")
+            .filter(unread)
+            .group_by(chat_messages::Column::SessionId)
+            .into_tuple()
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(counts
+            .into_iter()
+            .map(|(session_id, count)| (session_id, u64::try_from(count).unwrap_or(0)))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl AnnotationRepository for SeaOrmChatRepository {
+    async fn add_annotation(
+        &self,
+        annotation: &MessageAnnotation,
+    ) -> RepositoryResult<MessageAnnotation> {
+        ChatMessages::find_by_id(annotation.message_id)
+            .filter(chat_messages::Column::SessionId.eq(annotation.session_id))
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .ok_or(RepositoryError::MessageNotFound(annotation.message_id))?;
+
+        if let Some(existing) = self.find_identical_annotation(annotation).await? {
+            return Ok(existing);
+        }
+
+        let active_model = message_annotations::ActiveModel {
+            id: Set(annotation.id),
+            message_id: Set(annotation.message_id),
+            session_id: Set(annotation.session_id),
+            user_id: Set(annotation.user_id),
+            kind: Set(annotation.annotation.kind().as_str().to_string()),
+            value: Set(annotation.annotation.value().unwrap_or_default().to_string()),
+            created_at: Set(annotation.created_at.into()),
         };
 
-        let result = SeaOrmChatRepository::model_to_message(model);
+        if let Err(e) = active_model.insert(self.db.as_ref()).await {
+            // A concurrent request added the same annotation first
+            return self
+                .find_identical_annotation(annotation)
+                .await?
+                .ok_or_else(|| RepositoryError::DatabaseError(e.to_string()));
+        }
 
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            RepositoryError::ValidationError(_)
-        ));
+        Ok(annotation.clone())
+    }
+
+    async fn remove_annotation(
+        &self,
+        user_id: Uuid,
+        message_id: Uuid,
+        annotation_id: Uuid,
+    ) -> RepositoryResult<()> {
+        let result = MessageAnnotations::delete_many()
+            .filter(message_annotations::Column::Id.eq(annotation_id))
+            .filter(message_annotations::Column::MessageId.eq(message_id))
+            .filter(message_annotations::Column::UserId.eq(user_id))
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::AnnotationNotFound(annotation_id));
+        }
+
+        Ok(())
+    }
+
+    async fn find_annotations_by_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> RepositoryResult<Vec<MessageAnnotation>> {
+        MessageAnnotations::find()
+            .filter(message_annotations::Column::SessionId.eq(session_id))
+            .filter(message_annotations::Column::UserId.eq(user_id))
+            .order_by_asc(message_annotations::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .iter()
+            .map(Self::model_to_annotation)
+            .collect()
+    }
+
+    async fn find_annotated_messages(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        filter: &AnnotationFilter,
+        limit: Option<u64>,
+    ) -> RepositoryResult<Vec<ChatMessage>> {
+        let mut annotated = MessageAnnotations::find()
+            .select_only()
+            .column(message_annotations::Column::MessageId)
+            .filter(message_annotations::Column::SessionId.eq(session_id))
+            .filter(message_annotations::Column::UserId.eq(user_id))
+            .filter(message_annotations::Column::Kind.eq(filter.kind.as_str()));
+        if let Some(value) = &filter.value {
+            annotated = annotated.filter(message_annotations::Column::Value.eq(value.as_str()));
+        }
+
+        let mut query = ChatMessages::find()
+            .filter(chat_messages::Column::SessionId.eq(session_id))
+            .filter(chat_messages::Column::Id.in_subquery(annotated.into_query()))
+            .order_by_asc(chat_messages::Column::CreatedAt);
+        if let Some(limit_value) = limit {
+            query = query.limit(limit_value);
+        }
+
+        query
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .map(Self::model_to_message)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ShareRepository for SeaOrmChatRepository {
+    async fn create_share(&self, share: &ChatShare) -> RepositoryResult<()> {
+        let active_model = chat_shares::ActiveModel {
+            id: Set(share.id),
+            session_id: Set(share.session_id),
+            user_id: Set(share.user_id),
+            slug: Set(share.slug.clone()),
+            password_hash: Set(share.password_hash.clone()),
+            expires_at: Set(share.expires_at.map(Into::into)),
+            revoked_at: Set(share.revoked_at.map(Into::into)),
+            suspended_at: Set(share.suspended_at.map(Into::into)),
+            view_count: Set(i64::try_from(share.view_count).unwrap_or(i64::MAX)),
+            created_at: Set(share.created_at.into()),
+        };
+
+        active_model
+            .insert(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_share_by_slug(&self, slug: &str) -> RepositoryResult<Option<ChatShare>> {
+        let model = ChatShares::find()
+            .filter(chat_shares::Column::Slug.eq(slug))
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(model.map(Self::model_to_share))
+    }
+
+    async fn find_shares_by_session(&self, session_id: Uuid) -> RepositoryResult<Vec<ChatShare>> {
+        let models = ChatShares::find()
+            .filter(chat_shares::Column::SessionId.eq(session_id))
+            .order_by_desc(chat_shares::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(models.into_iter().map(Self::model_to_share).collect())
+    }
+
+    async fn revoke_share(&self, session_id: Uuid, share_id: Uuid) -> RepositoryResult<()> {
+        let result = ChatShares::update_many()
+            .col_expr(chat_shares::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(chat_shares::Column::Id.eq(share_id))
+            .filter(chat_shares::Column::SessionId.eq(session_id))
+            .filter(chat_shares::Column::RevokedAt.is_null())
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            // Revoking twice is fine; only an unknown share is an error
+            let exists = ChatShares::find_by_id(share_id)
+                .filter(chat_shares::Column::SessionId.eq(session_id))
+                .one(self.db.as_ref())
+                .await
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+                .is_some();
+            if !exists {
+                return Err(RepositoryError::ShareNotFound);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn increment_share_views(&self, share_id: Uuid) -> RepositoryResult<()> {
+        ChatShares::update_many()
+            .col_expr(
+                chat_shares::Column::ViewCount,
+                Expr::col(chat_shares::Column::ViewCount).add(1),
+            )
+            .filter(chat_shares::Column::Id.eq(share_id))
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for SeaOrmChatRepository {
+    async fn create_webhook(&self, webhook: &ChatWebhook) -> RepositoryResult<()> {
+        let active_model = chat_webhooks::ActiveModel {
+            id: Set(webhook.id),
+            user_id: Set(webhook.user_id),
+            url: Set(webhook.url.clone()),
+            secret: Set(webhook.secret.clone()),
+            created_at: Set(webhook.created_at.into()),
+            last_delivery_at: Set(webhook.last_delivery_at.map(Into::into)),
+            last_status: Set(webhook.last_status.map(i32::from)),
+            last_error: Set(webhook.last_error.clone()),
+        };
+
+        active_model
+            .insert(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_webhooks_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<ChatWebhook>> {
+        let models = ChatWebhooks::find()
+            .filter(chat_webhooks::Column::UserId.eq(user_id))
+            .order_by_asc(chat_webhooks::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(models.into_iter().map(Self::model_to_webhook).collect())
+    }
+
+    async fn find_webhook(
+        &self,
+        user_id: Uuid,
+        webhook_id: Uuid,
+    ) -> RepositoryResult<Option<ChatWebhook>> {
+        let model = ChatWebhooks::find_by_id(webhook_id)
+            .filter(chat_webhooks::Column::UserId.eq(user_id))
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(model.map(Self::model_to_webhook))
+    }
+
+    async fn delete_webhook(&self, user_id: Uuid, webhook_id: Uuid) -> RepositoryResult<()> {
+        let result = ChatWebhooks::delete_many()
+            .filter(chat_webhooks::Column::Id.eq(webhook_id))
+            .filter(chat_webhooks::Column::UserId.eq(user_id))
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::WebhookNotFound(webhook_id));
+        }
+        Ok(())
+    }
+
+    async fn record_delivery(
+        &self,
+        webhook_id: Uuid,
+        delivery: &WebhookDelivery,
+    ) -> RepositoryResult<()> {
+        // A webhook deleted while a delivery was retried updates nothing
+        ChatWebhooks::update_many()
+            .col_expr(
+                chat_webhooks::Column::LastDeliveryAt,
+                Expr::value(delivery.attempted_at),
+            )
+            .col_expr(
+                chat_webhooks::Column::LastStatus,
+                Expr::value(delivery.status.map(i32::from)),
+            )
+            .col_expr(
+                chat_webhooks::Column::LastError,
+                Expr::value(delivery.error.clone()),
+            )
+            .filter(chat_webhooks::Column::Id.eq(webhook_id))
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChatDefaultsRepository for SeaOrmChatRepository {
+    async fn chat_defaults(&self, user_id: Uuid) -> RepositoryResult<ChatDefaults> {
+        let preferences = preferences::load(self.db.as_ref(), user_id)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(ChatDefaults {
+            model: preferences.default_model,
+            temperature: preferences.temperature,
+            persona: preferences.persona,
+        })
+    }
+}
+
+#[async_trait]
+impl SuspensionRepository for SeaOrmChatRepository {
+    async fn is_account_disabled(&self, user_id: Uuid) -> RepositoryResult<bool> {
+        let user = Users::find_by_id(user_id)
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(user.is_some_and(|user| user.disabled_at.is_some()))
+    }
+
+    async fn suspend_shares(&self, user_id: Uuid) -> RepositoryResult<u64> {
+        let result = ChatShares::update_many()
+            .col_expr(chat_shares::Column::SuspendedAt, Expr::value(Utc::now()))
+            .filter(chat_shares::Column::UserId.eq(user_id))
+            .filter(chat_shares::Column::SuspendedAt.is_null())
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn restore_shares(&self, user_id: Uuid) -> RepositoryResult<u64> {
+        let result = ChatShares::update_many()
+            .col_expr(
+                chat_shares::Column::SuspendedAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .filter(chat_shares::Column::UserId.eq(user_id))
+            .filter(chat_shares::Column::SuspendedAt.is_not_null())
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn archive_sessions_disabled_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> RepositoryResult<u64> {
+        let disabled_users = Users::find()
+            .select_only()
+            .column(users::Column::Id)
+            .filter(users::Column::DisabledAt.lt(cutoff))
+            .into_query();
+
+        let result = ChatSessions::update_many()
+            .col_expr(chat_sessions::Column::ArchivedAt, Expr::value(Utc::now()))
+            .filter(chat_sessions::Column::UserId.in_subquery(disabled_users))
+            .filter(chat_sessions::Column::DeletedAt.is_null())
+            .filter(chat_sessions::Column::ArchivedAt.is_null())
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn unarchive_sessions(&self, user_id: Uuid) -> RepositoryResult<u64> {
+        let result = ChatSessions::update_many()
+            .col_expr(
+                chat_sessions::Column::ArchivedAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .filter(chat_sessions::Column::UserId.eq(user_id))
+            .filter(chat_sessions::Column::ArchivedAt.is_not_null())
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+impl SeaOrmChatRepository {
+    /// UTC calendar day of a usage row
+    fn usage_day(&self) -> SimpleExpr {
+        match self.db.get_database_backend() {
+            DatabaseBackend::Postgres => Expr::cust("DATE(created_at AT TIME ZONE 'UTC')"),
+            // SQLite stores timestamps as text with an offset and converts to UTC
+            _ => Expr::cust("DATE(created_at)"),
+        }
+    }
+
+    /// A user's usage rows since a point in time
+    fn usage_since(user_id: Uuid, since: DateTime<Utc>) -> Condition {
+        Condition::all()
+            .add(chat_usage::Column::UserId.eq(user_id))
+            .add(chat_usage::Column::CreatedAt.gte(since))
+    }
+}
+
+#[async_trait]
+impl UsageRepository for SeaOrmChatRepository {
+    async fn record_usage(&self, record: &UsageRecord) -> RepositoryResult<()> {
+        let active_model = chat_usage::ActiveModel {
+            id: Set(record.id),
+            user_id: Set(record.user_id),
+            session_id: Set(record.session_id),
+            model: Set(record.model.clone()),
+            prompt_tokens: Set(record.prompt_tokens),
+            completion_tokens: Set(record.completion_tokens),
+            latency_ms: Set(record.latency_ms),
+            created_at: Set(record.created_at.into()),
+        };
+
+        ChatUsage::insert(active_model)
+            .exec_without_returning(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn daily_messages(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<DailyMessages>> {
+        let rows: Vec<(NaiveDate, i64)> = ChatUsage::find()
+            .select_only()
+            .column_as(self.usage_day(), "day")
+            .column_as(Expr::col(chat_usage::Column::Id).count(), "messages")
+            .filter(Self::usage_since(user_id, since))
+            .group_by(self.usage_day())
+            .order_by_asc(self.usage_day())
+            .into_tuple()
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(date, messages)| DailyMessages {
+                date,
+                messages: u64::try_from(messages).unwrap_or(0),
+            })
+            .collect())
+    }
+
+    async fn tokens_by_model(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<Vec<ModelTokens>> {
+        let prompt_tokens = Expr::col(chat_usage::Column::PromptTokens).sum();
+        let completion_tokens = Expr::col(chat_usage::Column::CompletionTokens).sum();
+
+        let rows: Vec<(String, i64, i64)> = ChatUsage::find()
+            .select_only()
+            .column(chat_usage::Column::Model)
+            .column_as(prompt_tokens.clone(), "prompt_tokens")
+            .column_as(completion_tokens.clone(), "completion_tokens")
+            .filter(Self::usage_since(user_id, since))
+            .group_by(chat_usage::Column::Model)
+            .order_by_desc(prompt_tokens.add(completion_tokens))
+            .into_tuple()
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(model, prompt_tokens, completion_tokens)| ModelTokens {
+                model,
+                prompt_tokens: u64::try_from(prompt_tokens).unwrap_or(0),
+                completion_tokens: u64::try_from(completion_tokens).unwrap_or(0),
+            })
+            .collect())
+    }
+
+    async fn most_active_sessions(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: u64,
+    ) -> RepositoryResult<Vec<SessionActivity>> {
+        let messages = Expr::col((ChatUsage, chat_usage::Column::Id)).count();
+
+        let rows: Vec<(Uuid, String, i64)> = ChatUsage::find()
+            .select_only()
+            .column(chat_usage::Column::SessionId)
+            .column(chat_sessions::Column::Title)
+            .column_as(messages.clone(), "messages")
+            .join(JoinType::InnerJoin, chat_usage::Relation::ChatSessions.def())
+            .filter(Self::usage_since(user_id, since))
+            .filter(chat_sessions::Column::DeletedAt.is_null())
+            .group_by(chat_usage::Column::SessionId)
+            .group_by(chat_sessions::Column::Title)
+            .order_by_desc(messages)
+            .limit(limit)
+            .into_tuple()
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(session_id, title, messages)| SessionActivity {
+                session_id,
+                title,
+                messages: u64::try_from(messages).unwrap_or(0),
+            })
+            .collect())
+    }
+
+    async fn average_latency_ms(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<Option<f64>> {
+        // Sum and count rather than AVG, which is NUMERIC on PostgreSQL
+        let totals: Option<(Option<i64>, i64)> = ChatUsage::find()
+            .select_only()
+            .column_as(Expr::col(chat_usage::Column::LatencyMs).sum(), "latency_ms")
+            .column_as(Expr::col(chat_usage::Column::Id).count(), "replies")
+            .filter(Self::usage_since(user_id, since))
+            .into_tuple()
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        #[allow(clippy::cast_precision_loss)]
+        Ok(match totals {
+            Some((Some(total), replies)) if replies > 0 => Some(total as f64 / replies as f64),
+            _ => None,
+        })
+    }
+}
+
+impl SeaOrmChatRepository {
+    /// Convert `SeaORM` model to domain job
+    fn model_to_job(model: chat_jobs::Model) -> RepositoryResult<ChatJob> {
+        Ok(ChatJob {
+            id: model.id,
+            user_id: model.user_id,
+            model: model.model,
+            status: JobStatus::parse(&model.status).map_err(RepositoryError::ValidationError)?,
+            total_items: u32::try_from(model.total_items).unwrap_or(0),
+            completed_items: u32::try_from(model.completed_items).unwrap_or(0),
+            failed_items: u32::try_from(model.failed_items).unwrap_or(0),
+            prompt_tokens: u64::try_from(model.prompt_tokens).unwrap_or(0),
+            completion_tokens: u64::try_from(model.completion_tokens).unwrap_or(0),
+            created_at: model.created_at.with_timezone(&Utc),
+            started_at: model.started_at.map(|dt| dt.with_timezone(&Utc)),
+            finished_at: model.finished_at.map(|dt| dt.with_timezone(&Utc)),
+        })
+    }
+
+    /// Convert `SeaORM` model to domain job item
+    fn model_to_job_item(model: chat_job_items::Model) -> RepositoryResult<ChatJobItem> {
+        Ok(ChatJobItem {
+            id: model.id,
+            job_id: model.job_id,
+            position: u32::try_from(model.position).unwrap_or(0),
+            prompt: model.prompt,
+            status: JobItemStatus::parse(&model.status)
+                .map_err(RepositoryError::ValidationError)?,
+            output: model.output,
+            error: model.error,
+            prompt_tokens: model.prompt_tokens.map(|t| u32::try_from(t).unwrap_or(0)),
+            completion_tokens: model.completion_tokens.map(|t| u32::try_from(t).unwrap_or(0)),
+            finished_at: model.finished_at.map(|dt| dt.with_timezone(&Utc)),
+        })
+    }
+
+    /// Convert domain job item to a `SeaORM` active model for insertion
+    fn job_item_to_active_model(item: &ChatJobItem) -> chat_job_items::ActiveModel {
+        chat_job_items::ActiveModel {
+            id: Set(item.id),
+            job_id: Set(item.job_id),
+            position: Set(i32::try_from(item.position).unwrap_or(i32::MAX)),
+            prompt: Set(item.prompt.clone()),
+            status: Set(item.status.as_str().to_string()),
+            output: Set(item.output.clone()),
+            error: Set(item.error.clone()),
+            prompt_tokens: Set(item.prompt_tokens.map(to_i32)),
+            completion_tokens: Set(item.completion_tokens.map(to_i32)),
+            finished_at: Set(item.finished_at.map(Into::into)),
+        }
+    }
+}
+
+fn to_i32(count: u32) -> i32 {
+    i32::try_from(count).unwrap_or(i32::MAX)
+}
+
+#[async_trait]
+impl JobRepository for SeaOrmChatRepository {
+    async fn create_job(&self, job: &ChatJob, items: &[ChatJobItem]) -> RepositoryResult<()> {
+        let active_model = chat_jobs::ActiveModel {
+            id: Set(job.id),
+            user_id: Set(job.user_id),
+            model: Set(job.model.clone()),
+            status: Set(job.status.as_str().to_string()),
+            total_items: Set(to_i32(job.total_items)),
+            completed_items: Set(to_i32(job.completed_items)),
+            failed_items: Set(to_i32(job.failed_items)),
+            prompt_tokens: Set(i64::try_from(job.prompt_tokens).unwrap_or(i64::MAX)),
+            completion_tokens: Set(i64::try_from(job.completion_tokens).unwrap_or(i64::MAX)),
+            created_at: Set(job.created_at.into()),
+            started_at: Set(job.started_at.map(Into::into)),
+            finished_at: Set(job.finished_at.map(Into::into)),
+        };
+
+        // The job only becomes visible to workers with all of its items
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        ChatJobs::insert(active_model)
+            .exec_without_returning(&txn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        ChatJobItems::insert_many(items.iter().map(Self::job_item_to_active_model))
+            .exec_without_returning(&txn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        txn.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_job(&self, id: Uuid) -> RepositoryResult<Option<ChatJob>> {
+        ChatJobs::find_by_id(id)
+            .one(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .map(Self::model_to_job)
+            .transpose()
+    }
+
+    async fn count_active_jobs(&self, user_id: Uuid) -> RepositoryResult<u64> {
+        ChatJobs::find()
+            .filter(chat_jobs::Column::UserId.eq(user_id))
+            .filter(chat_jobs::Column::Status.is_in([
+                JobStatus::Queued.as_str(),
+                JobStatus::Running.as_str(),
+            ]))
+            .count(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+
+    async fn find_queued_jobs(&self, limit: u64) -> RepositoryResult<Vec<Uuid>> {
+        ChatJobs::find()
+            .select_only()
+            .column(chat_jobs::Column::Id)
+            .filter(chat_jobs::Column::Status.eq(JobStatus::Queued.as_str()))
+            .order_by_asc(chat_jobs::Column::CreatedAt)
+            .limit(limit)
+            .into_tuple()
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+
+    async fn claim_job(&self, id: Uuid) -> RepositoryResult<bool> {
+        let result = ChatJobs::update_many()
+            .col_expr(
+                chat_jobs::Column::Status,
+                Expr::value(JobStatus::Running.as_str()),
+            )
+            .col_expr(chat_jobs::Column::StartedAt, Expr::value(Utc::now()))
+            .filter(chat_jobs::Column::Id.eq(id))
+            .filter(chat_jobs::Column::Status.eq(JobStatus::Queued.as_str()))
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn requeue_running_jobs(&self) -> RepositoryResult<u64> {
+        let result = ChatJobs::update_many()
+            .col_expr(
+                chat_jobs::Column::Status,
+                Expr::value(JobStatus::Queued.as_str()),
+            )
+            .filter(chat_jobs::Column::Status.eq(JobStatus::Running.as_str()))
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn find_pending_items(&self, job_id: Uuid) -> RepositoryResult<Vec<ChatJobItem>> {
+        ChatJobItems::find()
+            .filter(chat_job_items::Column::JobId.eq(job_id))
+            .filter(chat_job_items::Column::Status.eq(JobItemStatus::Pending.as_str()))
+            .order_by_asc(chat_job_items::Column::Position)
+            .all(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .map(Self::model_to_job_item)
+            .collect()
+    }
+
+    async fn finish_item(&self, item: &ChatJobItem) -> RepositoryResult<()> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let result = chat_job_items::ActiveModel {
+            status: Set(item.status.as_str().to_string()),
+            output: Set(item.output.clone()),
+            error: Set(item.error.clone()),
+            prompt_tokens: Set(item.prompt_tokens.map(to_i32)),
+            completion_tokens: Set(item.completion_tokens.map(to_i32)),
+            finished_at: Set(item.finished_at.map(Into::into)),
+            ..Default::default()
+        };
+        let updated = ChatJobItems::update_many()
+            .set(result)
+            .filter(chat_job_items::Column::Id.eq(item.id))
+            .filter(chat_job_items::Column::Status.eq(JobItemStatus::Pending.as_str()))
+            .exec(&txn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        // Count each item once, even if two workers raced on it
+        if updated.rows_affected == 1 {
+            let counter = match item.status {
+                JobItemStatus::Failed => chat_jobs::Column::FailedItems,
+                _ => chat_jobs::Column::CompletedItems,
+            };
+            ChatJobs::update_many()
+                .col_expr(counter, Expr::col(counter).add(1))
+                .col_expr(
+                    chat_jobs::Column::PromptTokens,
+                    Expr::col(chat_jobs::Column::PromptTokens)
+                        .add(i64::from(item.prompt_tokens.unwrap_or(0))),
+                )
+                .col_expr(
+                    chat_jobs::Column::CompletionTokens,
+                    Expr::col(chat_jobs::Column::CompletionTokens)
+                        .add(i64::from(item.completion_tokens.unwrap_or(0))),
+                )
+                .filter(chat_jobs::Column::Id.eq(item.job_id))
+                .exec(&txn)
+                .await
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn finish_job(&self, id: Uuid, status: JobStatus) -> RepositoryResult<()> {
+        ChatJobs::update_many()
+            .col_expr(chat_jobs::Column::Status, Expr::value(status.as_str()))
+            .col_expr(chat_jobs::Column::FinishedAt, Expr::value(Utc::now()))
+            .filter(chat_jobs::Column::Id.eq(id))
+            .exec(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_job_items(
+        &self,
+        job_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> RepositoryResult<(Vec<ChatJobItem>, u64)> {
+        let query = ChatJobItems::find()
+            .filter(chat_job_items::Column::JobId.eq(job_id))
+            .order_by_asc(chat_job_items::Column::Position);
+
+        let total = query
+            .clone()
+            .count(self.db.as_ref())
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let items = query
+            .paginate(self.db.as_ref(), per_page)
+            .fetch_page(page)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .map(Self::model_to_job_item)
+            .collect::<RepositoryResult<_>>()?;
+
+        Ok((items, total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::chat::value_objects::MessageRole;
+
+    #[test]
+    fn test_model_to_session() {
+        let model = chat_sessions::Model {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            title: "Test Session".to_string(),
+            created_at: Utc::now().into(),
+            updated_at: Utc::now().into(),
+            deleted_at: None,
+            archived_at: None,
+            version: 3,
+        };
+
+        let session = SeaOrmChatRepository::model_to_session(model.clone());
+
+        assert_eq!(session.id, model.id);
+        assert_eq!(session.user_id, model.user_id);
+        assert_eq!(session.title, model.title);
+        assert_eq!(session.version, 3);
+    }
+
+    #[test]
+    fn test_model_to_message() {
+        let model = chat_messages::Model {
+            id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            role: "user".to_string(),
+            content: "Hello".to_string(),
+            token_count: Some(5),
+            created_at: Utc::now().into(),
+            redacted_at: None,
+        };
+
+        let message = SeaOrmChatRepository::model_to_message(model.clone()).unwrap();
+
+        assert_eq!(message.id, model.id);
+        assert_eq!(message.session_id, model.session_id);
+        assert_eq!(message.role, MessageRole::User);
+        assert_eq!(message.content, model.content);
+        assert_eq!(message.token_count, model.token_count);
+    }
+
+    #[test]
+    fn test_model_to_message_invalid_role() {
+        let model = chat_messages::Model {
+            id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            role: "invalid".to_string(),
+            content: "Hello".to_string(),
+            token_count: None,
+            created_at: Utc::now().into(),
+            redacted_at: None,
+        };
+
+        let result = SeaOrmChatRepository::model_to_message(model);
+
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            RepositoryError::ValidationError(_)
+        ));
+    }
+
+    #[test]
+    fn test_model_to_read_state() {
+        let model = chat_read_states::Model {
+            user_id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            last_read_message_id: Uuid::new_v4(),
+            last_read_at: Utc::now().into(),
+            updated_at: Utc::now().into(),
+        };
+
+        let state = SeaOrmChatRepository::model_to_read_state(&model);
+
+        assert_eq!(state.user_id, model.user_id);
+        assert_eq!(state.session_id, model.session_id);
+        assert_eq!(state.last_read_message_id, model.last_read_message_id);
+    }
+
+    #[tokio::test]
+    async fn test_mark_read_rejects_message_from_other_session() {
+        use sea_orm::{DatabaseBackend, MockDatabase};
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<chat_messages::Model>::new()])
+            .into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+        let message_id = Uuid::new_v4();
+
+        let result = repository
+            .mark_read(Uuid::new_v4(), Uuid::new_v4(), message_id)
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::MessageNotFound(id)) if id == message_id));
+    }
+
+    #[tokio::test]
+    async fn test_count_unread() {
+        use sea_orm::{DatabaseBackend, MockDatabase, Value};
+        use std::collections::BTreeMap;
+
+        let session_id = Uuid::new_v4();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<chat_read_states::Model>::new()])
+            .append_query_results([[BTreeMap::from([
+                ("session_id", Value::from(session_id)),
+                ("unread", Value::from(3_i64)),
+            ])]])
+            .into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+
+        let counts = repository
+            .count_unread(Uuid::new_v4(), &[session_id, Uuid::new_v4()])
+            .await
+            .unwrap();
+
+        assert_eq!(counts.get(&session_id), Some(&3));
+        assert_eq!(counts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_count_unread_no_sessions() {
+        use sea_orm::{DatabaseBackend, MockDatabase};
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+
+        let counts = repository.count_unread(Uuid::new_v4(), &[]).await.unwrap();
+        assert!(counts.is_empty());
+    }
+
+    #[test]
+    fn test_model_to_share() {
+        let model = chat_shares::Model {
+            id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            slug: "slug".to_string(),
+            password_hash: None,
+            expires_at: Some(Utc::now().into()),
+            revoked_at: None,
+            suspended_at: None,
+            view_count: 7,
+            created_at: Utc::now().into(),
+        };
+
+        let share = SeaOrmChatRepository::model_to_share(model.clone());
+
+        assert_eq!(share.id, model.id);
+        assert_eq!(share.slug, model.slug);
+        assert_eq!(share.view_count, 7);
+        assert!(share.expires_at.is_some());
+        assert!(share.revoked_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_unknown_share() {
+        use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .append_query_results([Vec::<chat_shares::Model>::new()])
+            .into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+
+        let result = repository
+            .revoke_share(Uuid::new_v4(), Uuid::new_v4())
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::ShareNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_create_session_with_messages_fails_as_a_whole() {
+        use sea_orm::{DatabaseBackend, MockDatabase};
+
+        let session = ChatSession::new(Uuid::new_v4(), "Seeded".to_string()).unwrap();
+        let message =
+            ChatMessage::new(session.id, MessageRole::User, "Hello".to_string()).unwrap();
+        // The session insert succeeds; the message insert has no result and fails
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![chat_sessions::Model {
+                id: session.id,
+                user_id: session.user_id,
+                title: session.title.clone(),
+                created_at: session.created_at.into(),
+                updated_at: session.updated_at.into(),
+                deleted_at: None,
+                archived_at: None,
+                version: session.version,
+            }]])
+            .into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+
+        let result = repository
+            .create_session_with_messages(&session, &[message])
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::DatabaseError(_))));
+    }
+
+    #[test]
+    fn test_model_to_annotation() {
+        let model = message_annotations::Model {
+            id: Uuid::new_v4(),
+            message_id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            kind: "bookmark".to_string(),
+            value: String::new(),
+            created_at: Utc::now().into(),
+        };
+
+        let annotation = SeaOrmChatRepository::model_to_annotation(&model).unwrap();
+        assert_eq!(annotation.annotation, Annotation::Bookmark);
+        assert_eq!(annotation.message_id, model.message_id);
+
+        let label = SeaOrmChatRepository::model_to_annotation(&message_annotations::Model {
+            kind: "label".to_string(),
+            value: "todo".to_string(),
+            ..model
+        })
+        .unwrap();
+        assert_eq!(label.annotation, Annotation::Label("todo".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_add_annotation_rejects_message_from_other_session() {
+        use sea_orm::{DatabaseBackend, MockDatabase};
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<chat_messages::Model>::new()])
+            .into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+        let annotation = MessageAnnotation::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Annotation::Bookmark,
+        );
+
+        let result = repository.add_annotation(&annotation).await;
+
+        assert!(
+            matches!(result, Err(RepositoryError::MessageNotFound(id)) if id == annotation.message_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_unknown_annotation() {
+        use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+        let annotation_id = Uuid::new_v4();
+
+        let result = repository
+            .remove_annotation(Uuid::new_v4(), Uuid::new_v4(), annotation_id)
+            .await;
+
+        assert!(
+            matches!(result, Err(RepositoryError::AnnotationNotFound(id)) if id == annotation_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_daily_messages() {
+        use sea_orm::{DatabaseBackend, MockDatabase, Value};
+        use std::collections::BTreeMap;
+
+        let day = NaiveDate::from_ymd_opt(2025, 2, 3).unwrap();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[BTreeMap::from([
+                ("day", Value::from(day)),
+                ("messages", Value::from(4_i64)),
+            ])]])
+            .into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+
+        let days = repository
+            .daily_messages(Uuid::new_v4(), Utc::now())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            days,
+            vec![DailyMessages {
+                date: day,
+                messages: 4
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_average_latency() {
+        use sea_orm::{DatabaseBackend, MockDatabase, Value};
+        use std::collections::BTreeMap;
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[BTreeMap::from([
+                ("replies", Value::from(4_i64)),
+                ("latency_ms", Value::from(Some(1000_i64))),
+            ])]])
+            .append_query_results([[BTreeMap::from([
+                ("replies", Value::from(0_i64)),
+                ("latency_ms", Value::from(None::<i64>)),
+            ])]])
+            .into_connection();
+        let repository = SeaOrmChatRepository::new(Arc::new(db));
+
+        let average = repository
+            .average_latency_ms(Uuid::new_v4(), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(average, Some(250.0));
+
+        let none = repository
+            .average_latency_ms(Uuid::new_v4(), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(none, None);
+    }
+
+    #[cfg(feature = "demo")]
+    mod jobs {
+        use super::*;
+        use crate::domain::chat::job::JobItemStatus;
+
+        #[tokio::test]
+        async fn test_job_queue() {
+            let db = crate::demo::open().await.unwrap();
+            crate::demo::seed(&db).await.unwrap();
+            let user_id = Users::find().one(db.as_ref()).await.unwrap().unwrap().id;
+            let repository = SeaOrmChatRepository::new(db);
+
+            let (job, items) =
+                ChatJob::new(user_id, "llama", vec!["one".into(), "two".into()]).unwrap();
+            repository.create_job(&job, &items).await.unwrap();
+            assert_eq!(repository.count_active_jobs(user_id).await.unwrap(), 1);
+            assert_eq!(repository.find_queued_jobs(10).await.unwrap(), vec![job.id]);
+
+            // Only one worker gets the job
+            assert!(repository.claim_job(job.id).await.unwrap());
+            assert!(!repository.claim_job(job.id).await.unwrap());
+            assert!(repository.find_queued_jobs(10).await.unwrap().is_empty());
+
+            let mut first = items[0].clone();
+            first.complete("ONE".to_string(), 1, 2);
+            repository.finish_item(&first).await.unwrap();
+            // A repeated result is not counted twice
+            repository.finish_item(&first).await.unwrap();
+
+            let stored = repository.find_job(job.id).await.unwrap().unwrap();
+            assert_eq!(stored.status, JobStatus::Running);
+            assert_eq!((stored.completed_items, stored.failed_items), (1, 0));
+            assert_eq!((stored.prompt_tokens, stored.completion_tokens), (1, 2));
+
+            let pending = repository.find_pending_items(job.id).await.unwrap();
+            assert_eq!(pending.len(), 1);
+            assert_eq!(pending[0].prompt, "two");
+
+            // An interrupted job goes back to the queue with its results kept
+            assert_eq!(repository.requeue_running_jobs().await.unwrap(), 1);
+            assert_eq!(repository.find_queued_jobs(10).await.unwrap(), vec![job.id]);
+
+            repository
+                .finish_job(job.id, JobStatus::Completed)
+                .await
+                .unwrap();
+            assert_eq!(repository.count_active_jobs(user_id).await.unwrap(), 0);
+
+            let (page, total) = repository.find_job_items(job.id, 0, 1).await.unwrap();
+            assert_eq!(total, 2);
+            assert_eq!(page[0].status, JobItemStatus::Completed);
+            assert_eq!(page[0].output.as_deref(), Some("ONE"));
+            let (page, _) = repository.find_job_items(job.id, 1, 1).await.unwrap();
+            assert_eq!(page[0].status, JobItemStatus::Pending);
+        }
+    }
+
+    #[cfg(feature = "demo")]
+    mod message_deletion {
+        use super::*;
+        use crate::domain::chat::entity::REDACTED_CONTENT;
+
+        /// A session of the first demo user with three messages, a second apart
+        async fn conversation() -> (SeaOrmChatRepository, ChatSession, Vec<ChatMessage>) {
+            let db = crate::demo::open().await.unwrap();
+            crate::demo::seed(&db).await.unwrap();
+            let user_id = Users::find().one(db.as_ref()).await.unwrap().unwrap().id;
+            let repository = SeaOrmChatRepository::new(db);
+
+            let session = ChatSession::new(user_id, "Deletion".to_string()).unwrap();
+            repository.create_session(&session).await.unwrap();
+            let start = Utc::now() - chrono::Duration::minutes(1);
+            let mut messages = Vec::new();
+            for (i, content) in ["one", "two", "three"].into_iter().enumerate() {
+                let mut message =
+                    ChatMessage::new(session.id, MessageRole::User, content.to_string()).unwrap();
+                message.created_at = start + chrono::Duration::seconds(i64::try_from(i).unwrap());
+                repository.save_message(&message).await.unwrap();
+                messages.push(message);
+            }
+            (repository, session, messages)
+        }
+
+        #[tokio::test]
+        async fn test_delete_message_moves_read_marker_back() {
+            let (repository, session, messages) = conversation().await;
+            repository
+                .mark_read(session.user_id, session.id, messages[1].id)
+                .await
+                .unwrap();
+            repository
+                .add_annotation(&MessageAnnotation::new(
+                    session.id,
+                    messages[1].id,
+                    session.user_id,
+                    Annotation::new(AnnotationKind::Bookmark, None).unwrap(),
+                ))
+                .await
+                .unwrap();
+
+            repository.delete_message(&messages[1]).await.unwrap();
+
+            assert!(repository
+                .find_message(session.id, messages[1].id)
+                .await
+                .unwrap()
+                .is_none());
+            let state = repository
+                .find_read_state(session.user_id, session.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(state.last_read_message_id, messages[0].id);
+            assert!(repository
+                .find_annotations_by_session(session.user_id, session.id)
+                .await
+                .unwrap()
+                .is_empty());
+            let unread = repository
+                .count_unread(session.user_id, &[session.id])
+                .await
+                .unwrap();
+            assert_eq!(unread.get(&session.id), Some(&1));
+
+            // Without an earlier message there is nothing left to have read
+            repository
+                .mark_read(session.user_id, session.id, messages[0].id)
+                .await
+                .unwrap();
+            repository.delete_message(&messages[0]).await.unwrap();
+            assert!(repository
+                .find_read_state(session.user_id, session.id)
+                .await
+                .unwrap()
+                .is_none());
+        }
+
+        #[tokio::test]
+        async fn test_redacted_message_is_left_out_of_context() {
+            let (repository, session, mut messages) = conversation().await;
+
+            messages[2].redact();
+            repository.redact_message(&messages[2]).await.unwrap();
+
+            let context = repository.find_recent_messages(session.id, 10).await.unwrap();
+            assert_eq!(
+                context.iter().map(|m| m.id).collect::<Vec<_>>(),
+                vec![messages[0].id, messages[1].id]
+            );
+
+            let history = repository
+                .find_messages_by_session(session.id, None)
+                .await
+                .unwrap();
+            assert_eq!(history.len(), 3);
+            assert!(history[2].is_redacted());
+            assert_eq!(history[2].content, REDACTED_CONTENT);
+        }
+    }
+
+    /// Known IDOR patterns against the real queries: one user passing the
+    /// ids of another user's data, directly or mixed with ids of their own
+    #[cfg(feature = "demo")]
+    mod isolation {
+        use super::*;
+        use crate::application::chat::{
+            delete_message::DeleteMessageRequest, delete_session::DeleteSessionRequest,
+            get_session_history::GetSessionHistoryRequest,
+            list_user_sessions::ListUserSessionsRequest, message_annotations::AddAnnotationRequest,
+            rename_session::RenameSessionRequest, session_read_state::MarkSessionReadRequest,
+            share_session::CreateShareRequest, DeleteMessageUseCase, DeleteSessionUseCase,
+            GetSessionHistoryUseCase, ListUserSessionsUseCase, MessageAnnotationsUseCase,
+            RenameSessionUseCase, SessionReadStateUseCase, ShareSessionUseCase,
+        };
+        use crate::domain::chat::{deletion::MessageDeletionPolicy, share::ShareSigner};
+        use crate::infrastructure::share_attempts::InMemorySharePasswordAttempts;
+        use crate::models::sea_orm_active_enums::UserRole;
+        use crate::services::user_admin::{self, NewUser};
+
+        struct Fixture {
+            repository: Arc<SeaOrmChatRepository>,
+            /// Session of the victim, with one message
+            session: ChatSession,
+            message: ChatMessage,
+            /// Another user, with a session of their own
+            attacker: Uuid,
+            attacker_session: ChatSession,
+        }
+
+        async fn fixture() -> Fixture {
+            let db = crate::demo::open().await.unwrap();
+            crate::demo::seed(&db).await.unwrap();
+            let victim = Users::find().one(db.as_ref()).await.unwrap().unwrap().id;
+            let (attacker, _) = user_admin::create_user(
+                db.as_ref(),
+                NewUser {
+                    username: "mallory".to_string(),
+                    email: "mallory@example.com".to_string(),
+                    role: UserRole::User,
+                    email_verified: true,
+                },
+            )
+            .await
+            .unwrap();
+            let repository = Arc::new(SeaOrmChatRepository::new(db));
+
+            let session = ChatSession::new(victim, "Private".to_string()).unwrap();
+            repository.create_session(&session).await.unwrap();
+            let message =
+                ChatMessage::new(session.id, MessageRole::User, "secret".to_string()).unwrap();
+            repository.save_message(&message).await.unwrap();
+            let attacker_session = ChatSession::new(attacker.id, "Mine".to_string()).unwrap();
+            repository.create_session(&attacker_session).await.unwrap();
+
+            Fixture {
+                repository,
+                session,
+                message,
+                attacker: attacker.id,
+                attacker_session,
+            }
+        }
+
+        /// Denied as another user's session, without revealing anything
+        fn assert_denied<T: std::fmt::Debug>(result: RepositoryResult<T>) {
+            assert!(
+                matches!(result, Err(RepositoryError::ValidationError(_))),
+                "expected denial, got {result:?}"
+            );
+        }
+
+        /// The victim's session and message are as they were
+        async fn assert_untouched(f: &Fixture) {
+            let session = f
+                .repository
+                .find_active_session_for_user(f.session.id, f.session.user_id)
+                .await
+                .unwrap();
+            assert_eq!(session.title, f.session.title);
+            assert_eq!(session.version, f.session.version);
+            let message = f
+                .repository
+                .find_message(f.session.id, f.message.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(message.content, f.message.content);
+        }
+
+        #[tokio::test]
+        async fn test_foreign_session_ids_are_denied() {
+            let f = fixture().await;
+            let repo = Arc::clone(&f.repository);
+
+            assert_denied(
+                GetSessionHistoryUseCase::new(repo.clone(), repo.clone())
+                    .execute(GetSessionHistoryRequest {
+                        session_id: f.session.id,
+                        user_id: f.attacker,
+                        limit: None,
+                        annotation: None,
+                    })
+                    .await,
+            );
+            assert_denied(
+                RenameSessionUseCase::new(repo.clone())
+                    .execute(RenameSessionRequest {
+                        session_id: f.session.id,
+                        user_id: f.attacker,
+                        title: "Taken".to_string(),
+                        version: f.session.version,
+                    })
+                    .await,
+            );
+            assert_denied(
+                DeleteSessionUseCase::new(repo.clone())
+                    .execute(DeleteSessionRequest {
+                        session_id: f.session.id,
+                        user_id: f.attacker,
+                    })
+                    .await,
+            );
+            assert_denied(
+                DeleteMessageUseCase::new(repo.clone(), repo.clone(), MessageDeletionPolicy::Hard)
+                    .execute(DeleteMessageRequest {
+                        session: f.session.id,
+                        message: f.message.id,
+                        user: f.attacker,
+                    })
+                    .await,
+            );
+
+            let annotations = MessageAnnotationsUseCase::new(repo.clone(), repo.clone());
+            assert_denied(
+                annotations
+                    .add(AddAnnotationRequest {
+                        session_id: f.session.id,
+                        message_id: f.message.id,
+                        user_id: f.attacker,
+                        kind: AnnotationKind::Bookmark,
+                        value: None,
+                    })
+                    .await,
+            );
+            assert_denied(annotations.list(f.session.id, f.attacker, None).await);
+
+            let read_state = SessionReadStateUseCase::new(repo.clone(), repo.clone());
+            assert_denied(read_state.get(f.session.id, f.attacker).await);
+            assert_denied(
+                read_state
+                    .mark_read(MarkSessionReadRequest {
+                        session_id: f.session.id,
+                        user_id: f.attacker,
+                        message_id: None,
+                    })
+                    .await,
+            );
+
+            let shares = ShareSessionUseCase::new(
+                repo.clone(),
+                repo.clone(),
+                ShareSigner::new("s"),
+                Arc::new(InMemorySharePasswordAttempts::new()),
+            );
+            assert_denied(
+                shares
+                    .create(CreateShareRequest {
+                        session_id: f.session.id,
+                        user_id: f.attacker,
+                        expires_in: None,
+                        password: None,
+                    })
+                    .await,
+            );
+            assert_denied(shares.list(f.session.id, f.attacker).await);
+
+            assert_untouched(&f).await;
+        }
+
+        #[tokio::test]
+        async fn test_foreign_child_ids_under_own_session_are_not_found() {
+            let f = fixture().await;
+            let repo = Arc::clone(&f.repository);
+            let own = f.attacker_session.id;
+
+            // The victim's message addressed through the attacker's session
+            let result =
+                DeleteMessageUseCase::new(repo.clone(), repo.clone(), MessageDeletionPolicy::Hard)
+                    .execute(DeleteMessageRequest {
+                        session: own,
+                        message: f.message.id,
+                        user: f.attacker,
+                    })
+                    .await;
+            assert!(matches!(result, Err(RepositoryError::MessageNotFound(_))));
+
+            let result = MessageAnnotationsUseCase::new(repo.clone(), repo.clone())
+                .add(AddAnnotationRequest {
+                    session_id: own,
+                    message_id: f.message.id,
+                    user_id: f.attacker,
+                    kind: AnnotationKind::Bookmark,
+                    value: None,
+                })
+                .await;
+            assert!(matches!(result, Err(RepositoryError::MessageNotFound(_))));
+
+            let result = SessionReadStateUseCase::new(repo.clone(), repo.clone())
+                .mark_read(MarkSessionReadRequest {
+                    session_id: own,
+                    user_id: f.attacker,
+                    message_id: Some(f.message.id),
+                })
+                .await;
+            assert!(matches!(result, Err(RepositoryError::MessageNotFound(_))));
+
+            // The victim's share revoked through the attacker's session
+            let shares = ShareSessionUseCase::new(
+                repo.clone(),
+                repo.clone(),
+                ShareSigner::new("s"),
+                Arc::new(InMemorySharePasswordAttempts::new()),
+            );
+            let share = shares
+                .create(CreateShareRequest {
+                    session_id: f.session.id,
+                    user_id: f.session.user_id,
+                    expires_in: None,
+                    password: None,
+                })
+                .await
+                .unwrap();
+            let result = shares.revoke(own, share.id, f.attacker).await;
+            assert!(matches!(result, Err(RepositoryError::ShareNotFound)));
+            let stored = shares.list(f.session.id, f.session.user_id).await.unwrap();
+            assert_eq!(stored[0].revoked_at, None);
+
+            assert_untouched(&f).await;
+        }
+
+        #[tokio::test]
+        async fn test_repository_writes_are_scoped_to_the_owner() {
+            let f = fixture().await;
+
+            let result = f.repository.delete_session(f.session.id, f.attacker).await;
+            assert!(matches!(result, Err(RepositoryError::SessionNotFound(_))));
+            assert_untouched(&f).await;
+
+            let (job, items) =
+                ChatJob::new(f.session.user_id, "llama", vec!["one".into()]).unwrap();
+            f.repository.create_job(&job, &items).await.unwrap();
+            let result = f.repository.find_job_for_user(job.id, f.attacker).await;
+            assert!(matches!(result, Err(RepositoryError::JobNotFound(_))));
+
+            let webhook = ChatWebhook::new(f.session.user_id, "https://example.com/hook".into());
+            f.repository.create_webhook(&webhook).await.unwrap();
+            let result = f.repository.delete_webhook(f.attacker, webhook.id).await;
+            assert!(matches!(result, Err(RepositoryError::WebhookNotFound(_))));
+            assert!(f
+                .repository
+                .find_webhooks_by_user(f.attacker)
+                .await
+                .unwrap()
+                .is_empty());
+            assert_eq!(
+                f.repository
+                    .find_webhooks_by_user(f.session.user_id)
+                    .await
+                    .unwrap()
+                    .len(),
+                1
+            );
+        }
+
+        #[tokio::test]
+        async fn test_session_list_only_shows_own_sessions() {
+            let f = fixture().await;
+
+            let listed = ListUserSessionsUseCase::new(f.repository.clone())
+                .execute(ListUserSessionsRequest {
+                    user_id: f.attacker,
+                    page: 0,
+                    per_page: 100,
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(listed.total, 1);
+            assert_eq!(listed.sessions[0].id, f.attacker_session.id);
+        }
     }
 }
@@ -0,0 +1,198 @@
+//! Session lock implementations
+//!
+//! - [`ValkeySessionLock`]: shared across all backend instances; used in production
+//! - [`InMemorySessionLock`]: single-process, for tests and local tooling
+//!
+//! The Valkey lock is a `SET NX PX` key holding a random token, released
+//! only if the token still matches so an expired lock taken over by another
+//! request is never deleted. The TTL bounds how long a crashed instance can
+//! block a session.
+
+use async_trait::async_trait;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use uuid::Uuid;
+
+use crate::{
+    domain::chat::{
+        lock::{SessionLock, SessionLockGuard},
+        repository::{RepositoryError, RepositoryResult},
+    },
+    services::valkey::ValkeyManager,
+};
+
+/// Deletes the lock key only if it still holds our token
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+else
+    return 0
+end
+"#;
+
+/// Valkey-backed session lock shared by all instances
+#[derive(Clone)]
+pub struct ValkeySessionLock {
+    valkey: ValkeyManager,
+    ttl: Duration,
+}
+
+impl ValkeySessionLock {
+    /// Create a lock whose keys expire after `ttl` if never released
+    #[must_use]
+    pub const fn new(valkey: ValkeyManager, ttl: Duration) -> Self {
+        Self { valkey, ttl }
+    }
+
+    fn key(session_id: Uuid) -> String {
+        format!("lock:chat:session:{session_id}")
+    }
+}
+
+async fn release_valkey_lock(valkey: ValkeyManager, key: String, token: String) {
+    let result = match valkey.get_async_connection().await {
+        Ok(mut conn) => redis::Script::new(RELEASE_SCRIPT)
+            .key(&key)
+            .arg(token)
+            .invoke_async::<i64>(&mut conn)
+            .await
+            .map_err(Into::into),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to release {}: {} (expires with its TTL)", key, e);
+    }
+}
+
+#[async_trait]
+impl SessionLock for ValkeySessionLock {
+    async fn try_acquire(&self, session_id: Uuid) -> RepositoryResult<Option<SessionLockGuard>> {
+        let key = Self::key(session_id);
+        let token = Uuid::new_v4().to_string();
+        let ttl_ms = u64::try_from(self.ttl.as_millis()).unwrap_or(u64::MAX);
+
+        let mut conn = self
+            .valkey
+            .get_async_connection()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if acquired.is_none() {
+            return Ok(None);
+        }
+
+        let valkey = self.valkey.clone();
+        Ok(Some(SessionLockGuard::new(move || {
+            // Guards are dropped synchronously; release on a task of the runtime
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                handle.spawn(release_valkey_lock(valkey, key, token));
+            } else {
+                tracing::warn!("No runtime to release {} (expires with its TTL)", key);
+            }
+        })))
+    }
+}
+
+/// Process-local session lock
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySessionLock {
+    held: Arc<Mutex<HashSet<Uuid>>>,
+}
+
+impl InMemorySessionLock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionLock for InMemorySessionLock {
+    async fn try_acquire(&self, session_id: Uuid) -> RepositoryResult<Option<SessionLockGuard>> {
+        if !self.held.lock().unwrap().insert(session_id) {
+            return Ok(None);
+        }
+
+        let held = Arc::clone(&self.held);
+        Ok(Some(SessionLockGuard::new(move || {
+            held.lock().unwrap().remove(&session_id);
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::chat::lock::LockPolicy;
+
+    #[tokio::test]
+    async fn test_second_acquire_fails_until_release() {
+        let lock = InMemorySessionLock::new();
+        let session_id = Uuid::new_v4();
+
+        let guard = lock.try_acquire(session_id).await.unwrap();
+        assert!(guard.is_some());
+        assert!(lock.try_acquire(session_id).await.unwrap().is_none());
+
+        // Other sessions are independent
+        assert!(lock.try_acquire(Uuid::new_v4()).await.unwrap().is_some());
+
+        drop(guard);
+        assert!(lock.try_acquire(session_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_reject_policy_returns_generation_in_progress() {
+        let lock = InMemorySessionLock::new();
+        let session_id = Uuid::new_v4();
+        let _guard = lock.acquire(session_id, LockPolicy::Reject).await.unwrap();
+
+        let err = lock
+            .acquire(session_id, LockPolicy::Reject)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RepositoryError::GenerationInProgress(id) if id == session_id));
+    }
+
+    #[tokio::test]
+    async fn test_wait_policy_acquires_after_release() {
+        let lock = InMemorySessionLock::new();
+        let session_id = Uuid::new_v4();
+        let guard = lock.acquire(session_id, LockPolicy::Reject).await.unwrap();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            drop(guard);
+        });
+
+        let result = lock
+            .acquire(session_id, LockPolicy::Wait(Duration::from_secs(2)))
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_wait_policy_gives_up() {
+        let lock = InMemorySessionLock::new();
+        let session_id = Uuid::new_v4();
+        let _guard = lock.acquire(session_id, LockPolicy::Reject).await.unwrap();
+
+        let err = lock
+            .acquire(session_id, LockPolicy::Wait(Duration::from_millis(250)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RepositoryError::GenerationInProgress(_)));
+    }
+}
@@ -0,0 +1,152 @@
+//! Share password attempt counters
+//!
+//! - [`ValkeySharePasswordAttempts`]: shared across all backend instances; used in production
+//! - [`InMemorySharePasswordAttempts`]: single-process, for demo mode and tests
+//!
+//! Each slug and viewer pair has a counter that expires
+//! [`SHARE_PASSWORD_LOCKOUT`] after the first failure, so a locked out viewer
+//! can try again once it is gone.
+
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use crate::{
+    domain::chat::{
+        repository::{RepositoryError, RepositoryResult},
+        share::{SharePasswordAttempts, SHARE_PASSWORD_LOCKOUT},
+    },
+    services::valkey::ValkeyManager,
+};
+
+/// Valkey-backed counters shared by all instances
+#[derive(Clone)]
+pub struct ValkeySharePasswordAttempts {
+    valkey: ValkeyManager,
+}
+
+impl ValkeySharePasswordAttempts {
+    #[must_use]
+    pub const fn new(valkey: ValkeyManager) -> Self {
+        Self { valkey }
+    }
+
+    fn key(slug: &str, viewer: &str) -> String {
+        format!("ratelimit:share_password:{slug}:{viewer}")
+    }
+}
+
+#[async_trait]
+impl SharePasswordAttempts for ValkeySharePasswordAttempts {
+    async fn failures(&self, slug: &str, viewer: &str) -> RepositoryResult<u32> {
+        let mut conn = self
+            .valkey
+            .get_async_connection()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        let count: Option<u32> = conn
+            .get(Self::key(slug, viewer))
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        Ok(count.unwrap_or(0))
+    }
+
+    async fn record_failure(&self, slug: &str, viewer: &str) -> RepositoryResult<()> {
+        let key = Self::key(slug, viewer);
+        let mut conn = self
+            .valkey
+            .get_async_connection()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        // The window starts at the first failure and is not extended by later ones
+        redis::pipe()
+            .atomic()
+            .incr(&key, 1)
+            .ignore()
+            .cmd("EXPIRE")
+            .arg(&key)
+            .arg(SHARE_PASSWORD_LOCKOUT.as_secs())
+            .arg("NX")
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+}
+
+/// Process-local counters
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySharePasswordAttempts {
+    /// Failures and the time of the first, by slug and viewer
+    failures: Arc<Mutex<HashMap<(String, String), (u32, Instant)>>>,
+}
+
+impl InMemorySharePasswordAttempts {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SharePasswordAttempts for InMemorySharePasswordAttempts {
+    async fn failures(&self, slug: &str, viewer: &str) -> RepositoryResult<u32> {
+        Ok(self
+            .failures
+            .lock()
+            .unwrap()
+            .get(&(slug.to_string(), viewer.to_string()))
+            .filter(|(_, first)| first.elapsed() < SHARE_PASSWORD_LOCKOUT)
+            .map_or(0, |(count, _)| *count))
+    }
+
+    async fn record_failure(&self, slug: &str, viewer: &str) -> RepositoryResult<()> {
+        let mut failures = self.failures.lock().unwrap();
+        failures.retain(|_, (_, first)| first.elapsed() < SHARE_PASSWORD_LOCKOUT);
+        failures
+            .entry((slug.to_string(), viewer.to_string()))
+            .or_insert_with(|| (0, Instant::now()))
+            .0 += 1;
+        drop(failures);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_failures_are_counted_per_slug_and_viewer() {
+        let attempts = InMemorySharePasswordAttempts::new();
+
+        attempts
+            .record_failure("slug", "198.51.100.7")
+            .await
+            .unwrap();
+        attempts
+            .record_failure("slug", "198.51.100.7")
+            .await
+            .unwrap();
+        attempts
+            .record_failure("other", "198.51.100.7")
+            .await
+            .unwrap();
+
+        assert_eq!(attempts.failures("slug", "198.51.100.7").await.unwrap(), 2);
+        assert_eq!(attempts.failures("other", "198.51.100.7").await.unwrap(), 1);
+        assert_eq!(attempts.failures("slug", "203.0.113.9").await.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_valkey_key_format() {
+        assert_eq!(
+            ValkeySharePasswordAttempts::key("abc", "198.51.100.7"),
+            "ratelimit:share_password:abc:198.51.100.7"
+        );
+    }
+}
@@ -8,13 +8,19 @@
 //!
 //! The codebase is organized into the following layers:
 //!
+//! - **App**: Wiring of services, background jobs and routers for the server
 //! - **Handlers**: HTTP request/response handling and routing
+//! - **DTOs**: Shared request/response types used by handlers and `OpenAPI`
 //! - **Services**: Business logic and domain operations
 //! - **Models**: Database entities and domain models (`SeaORM`)
 //! - **Middleware**: Cross-cutting concerns (authentication, authorization)
 //! - **Config**: Application configuration management
 //! - **Utils**: Shared utilities and helpers
 //!
+//! The chat domain, the typed ids and the token primitives live in the
+//! `cobalt-stack-domain` crate and are re-exported as [`domain`] and
+//! [`utils::token`], so the import paths do not depend on the crate layout.
+//!
 //! # Key Features
 //!
 //! - JWT-based authentication with access and refresh tokens
@@ -48,13 +54,18 @@
 //! - Token rotation and revocation
 //! - Rate limiting on authentication endpoints
 
+pub mod app;
 pub mod application;
 pub mod config;
+#[cfg(feature = "demo")]
+pub mod demo;
 pub mod domain;
+pub mod dto;
 pub mod handlers;
 pub mod infrastructure;
 pub mod middleware;
 pub mod models;
 pub mod openapi;
+pub mod server;
 pub mod services;
 pub mod utils;
@@ -30,6 +30,9 @@
 //!
 //! # Start server
 //! cargo run
+//!
+//! # Or try it without Postgres/Valkey: in-memory, seeded, lost on exit
+//! cargo run --features demo -- --demo
 //! ```
 //!
 //! # Environment Variables
@@ -40,37 +43,252 @@
 //! - `JWT_SECRET` - Secret key for JWT signing
 //! - `JWT_ACCESS_EXPIRY_MINUTES` - Access token lifetime (default: 30)
 //! - `JWT_REFRESH_EXPIRY_DAYS` - Refresh token lifetime (default: 7)
+//! - `JWT_LEEWAY_SECS` / `JWT_REQUIRE_NBF` / `JWT_MAX_TOKEN_AGE_SECS` - Access token
+//!   clock leeway, required `nbf` claim and maximum age regardless of expiry
+//!   (defaults: 60 / false / unset); `JWT_STRICT=true` forces zero leeway and `nbf`
 //! - `PORT` - Server port (default: 3000)
+//! - `SERVER_UNIX_SOCKET_PATH` / `SERVER_UNIX_SOCKET_MODE` - Listen on a Unix socket
+//!   instead of TCP (mode default: 660); a systemd-activated socket (`LISTEN_FDS`) takes
+//!   precedence over both
+//! - `SERVER_*` - Connection tuning (keep-alive, HTTP/2, `TCP_NODELAY`, max connections),
+//!   see [`config::ServerConfig`]
+//! - `SERVER_SHUTDOWN_TIMEOUT_SECS` - Time open connections get to finish after `SIGTERM`
+//!   or `SIGINT` (default: 30); chat reply streams and WebSocket connections are ended at once
+//! - `SERVER_SHUTDOWN_PREDRAIN_SECS` - Time the server keeps accepting connections after
+//!   `SIGTERM` while `/ready` already fails, before draining (default: 0)
+//! - `TLS_CERT_PATH` / `TLS_KEY_PATH` - Enable built-in TLS (reloaded on `SIGHUP`;
+//!   with chat enabled, `SIGHUP` also reloads the models in `models.toml`)
+//! - `TLS_REDIRECT_HTTP_PORT` - Optional plain-HTTP port redirecting to HTTPS
+//! - `REQUEST_TIMEOUT_SECS` / `REQUEST_TIMEOUT_AUTH_SECS` / `REQUEST_TIMEOUT_CHAT_SECS` -
+//!   Request deadlines (defaults: 30 / 10 / 300), see [`config::RequestTimeoutConfig`]
+//! - `HTTP_CACHE_MODELS_MAX_AGE_SECS` / `HTTP_CACHE_BRANDING_MAX_AGE_SECS` - Client cache
+//!   lifetime of the model list and branding (defaults: 300 / 60); responses carry an
+//!   `ETag` and answer `If-None-Match` with `304`, see [`config::cache::HttpCacheConfig`]
+//! - `REQUEST_TIMEOUT_STATUS` - Status returned on timeout, `408` or `504` (default: 504)
+//! - `LATENCY_BUDGET_MS` / `LATENCY_BUDGETS` - Default latency budget (default: 1000) and
+//!   `prefix=ms` overrides (default: `/api/v1/chat=5000`); slower requests are logged with
+//!   their database, Valkey and provider time, see [`config::LatencyBudgetConfig`]
+//! - `SCHEMA_VERSION_CHECK` - `off`, `warn` or `refuse` (default: warn); compares the
+//!   applied migrations with this binary's at startup and in `/health/ready`, and with
+//!   `refuse` does not start (and is not ready) while they differ
+//! - `OPENAPI_TYPES_ENABLED` - Serve the TypeScript types of the API at `/openapi/types.ts`
+//!   for the frontend (development only, default: false)
+//! - `FAULT_INJECTION_ENABLED` - Fail a share of database, Valkey and LLM provider calls on
+//!   purpose to exercise error handling (development only, default: false), see
+//!   [`config::FaultInjectionConfig`]
+//! - `API_JSON_CASE` - `snake_case` or `camelCase` (default: `snake_case`); `camelCase`
+//!   also serves every `/api/v1` endpoint under `/api/v2` with `camelCase` JSON bodies
+//! - `REFRESH_TOKEN_STORE` - `database` or `valkey` (default: database); with `valkey`
+//!   refresh tokens live only in Valkey, see [`config::TokenStoreBackend`]
+//! - `USER_CACHE_TTL_SECS` - How long authenticated users' rows are shared through
+//!   Valkey (default: 5, 0 turns it off), see [`services::valkey::user_cache`]
+//! - `REFRESH_TOKEN_BINDING_ENABLED` - Bind refresh tokens to a device cookie and the
+//!   `User-Agent` (default: false); a token refreshed from another client signs out
+//!   its session, see [`services::auth::token_binding`]
+//! - `TRUSTED_DEVICES_ENABLED` - Email a confirmation link on sign-in from a new device
+//!   and issue refresh tokens only to confirmed devices (default: false, needs email);
+//!   `TRUSTED_DEVICE_CONFIRMATION_TTL_MINUTES` sets the link lifetime (default: 60), see
+//!   [`services::auth::trusted_devices`]
+//! - `PASSWORD_MAX_AGE_DAYS` / `PASSWORD_EXPIRY_GRACE_DAYS` - Password maximum age and
+//!   grace window after it (defaults: unset, passwords never expire / 7); past the grace
+//!   window access tokens only allow a password change, see
+//!   [`services::auth::password_expiry`]
+//! - `FEATURE_CHAT_ENABLED` / `FEATURE_ADMIN_API_ENABLED` / `FEATURE_EMAIL_ENABLED` -
+//!   Subsystem toggles (defaults: false / true / true); disabled subsystems are not
+//!   initialized or routed, and `/health` lists what is active
+//! - `FEATURE_METRICS_ENABLED` - Serve `/metrics` and record request metrics (default: true)
+//! - `CHAT_QUOTA_WARNING_THRESHOLDS` - Daily quota percentages that add
+//!   `X-Quota-Warning` to chat responses and notify the user (default: 80)
+//! - `CHAT_MESSAGE_DELETION` - `redact` (keep a placeholder) or `hard` when a
+//!   user deletes one message (default: redact)
+//! - `CHAT_SESSION_LOCK_POLICY` - `reject` (409) or `wait` when a session is
+//!   already generating a response (default: reject)
+//! - `CHAT_SESSION_LOCK_WAIT_SECS` / `CHAT_SESSION_LOCK_TTL_SECS` - Wait limit
+//!   and lock expiry (defaults: 30 / 300)
+//! - `CHAT_SHARE_SECRET` - Key signing public share link slugs (required with chat)
+//! - `EMAIL_DIGEST_PERIOD_DAYS` / `EMAIL_DIGEST_CHECK_INTERVAL_SECS` - Weekly digest
+//!   period and how often due digests are looked for (defaults: 7 / 3600)
+//! - `EMAIL_BOUNCE_WEBHOOK_SECRET` - Secret of the bounce/complaint webhook, mounted
+//!   only when set; `EMAIL_SUPPRESSION_REFRESH_SECS` - How often the suppressed
+//!   addresses are reloaded (default: 60)
+//! - `ADMIN_STATS_REPORT_ENABLED` - Email admins a periodic stats report (default: false);
+//!   `ADMIN_STATS_REPORT_PERIOD_DAYS` / `ADMIN_STATS_REPORT_CHECK_INTERVAL_SECS` set the
+//!   period and how often a due report is looked for (defaults: 7 / 3600)
+//! - `APP_PUBLIC_URL` - Base URL for links in emails (default: `http://localhost:2727`)
+//! - `EMAIL_VERIFICATION_URL` - Verification link, `{token}` replaced (default:
+//!   `{APP_PUBLIC_URL}/verify-email?token={token}`)
+//! - `EMAIL_VERIFICATION_CLIENT_URLS` - `client=template` links for clients naming
+//!   themselves at registration, e.g. `ios=myapp://verify?token={token}`
+//! - `BRANDING_PRODUCT_NAME` / `BRANDING_LOGO_URL` / `BRANDING_SUPPORT_EMAIL` /
+//!   `BRANDING_PRIMARY_COLOR` / `BRANDING_ACCENT_COLOR` - Default white-label branding,
+//!   see [`config::BrandingConfig`]
+//! - `CHAT_PROVIDER_PROBE_INTERVAL_SECS` / `CHAT_PROVIDER_PROBE_TIMEOUT_SECS` - Send a
+//!   one-token completion to each LLM provider on this interval (default: 0, disabled)
+//!   and record success and latency in Valkey (timeout default: 10)
+//! - `CHAT_ARCHIVE_DISABLED_AFTER_DAYS` - Archive the chat sessions of users disabled for
+//!   this many days; enabling the user restores them (default: 0, never)
+//! - `CHAT_CRITICAL_DEPENDENCY` - Fail `/health/ready` (and report `/health/detailed` as
+//!   unhealthy) when every probed provider is down (default: false)
+//! - `CHAT_JOB_WORKERS` - Batch job workers on this instance (default: 2); set 0 on all
+//!   instances but one, since workers requeue jobs left running when they start
+//! - `CHAT_JOB_PROVIDER_CONCURRENCY` - Batch job requests in flight per LLM provider
+//!   (default: 4)
+//! - `CHAT_JOB_MAX_PROMPTS` / `CHAT_JOB_MAX_ACTIVE` - Prompts per batch job and queued or
+//!   running jobs per user (defaults: 100 / 1)
+//! - `INTERNAL_LISTEN_ADDR` - Optional internal listener (e.g. `127.0.0.1:9090`) that
+//!   takes over the operational endpoints below, removing them from the public listener
+//! - `TRUSTED_PROXIES` - Comma-separated CIDRs of reverse proxies whose client IP header
+//!   is believed (default: none)
+//! - `TRUSTED_PROXY_HEADER` - The header those proxies set: `x-forwarded-for`, `forwarded`
+//!   or `cf-connecting-ip` (default: `x-forwarded-for`); the others are ignored
+//! - `INTERNAL_SIGNING_KEYS` - Comma-separated `key_id:secret` pairs (secrets of at least
+//!   32 bytes) enabling the HMAC-signed internal routes below for sidecar services
+//! - `INTERNAL_SIGNATURE_WINDOW_SECS` - Accepted signature timestamp skew (default: 300)
+//! - `ACCESS_LOG_ENABLED` - Record API requests (route, user, IP, status, latency) for
+//!   compliance trails (default: false)
+//! - `ACCESS_LOG_SINK` - `database` (`access_logs` table) or `log` (`access_log` events for
+//!   an external store) (default: database)
+//! - `ACCESS_LOG_SAMPLE_RATE` - Fraction of successful requests recorded; errors are
+//!   always recorded (default: 1.0)
+//! - `ACCESS_LOG_EXCLUDE_PATHS` - Path prefixes never recorded (default: `/health,/ready,/metrics`)
+//! - `ACCESS_LOG_RETENTION_DAYS` / `ACCESS_LOG_PURGE_INTERVAL_SECS` - Delete database
+//!   records older than this, 0 keeps them (default: 90), checked this often (default: 3600)
 //!
 //! # API Endpoints
 //!
+//! JSON fields are `snake_case`. With `API_JSON_CASE=camelCase` each `/api/v1` endpoint
+//! below is also served under `/api/v2` with `camelCase` JSON request and response bodies
+//! (query parameters and event streams are unchanged).
+//!
 //! ## Public Endpoints
 //!
 //! - `GET /health` - Health check
+//! - `GET /api/v1/branding` - Product name, logo, support email and theme colors
 //! - `POST /api/v1/auth/register` - User registration
 //! - `POST /api/v1/auth/login` - User login
 //! - `POST /api/v1/auth/refresh` - Refresh access token
 //! - `POST /api/v1/auth/verify-email` - Verify email address
+//! - `GET /api/v1/chat/shared/:slug` - Shared conversation (when chat is enabled)
+//! - `GET|POST /api/v1/email/unsubscribe` - Digest unsubscribe confirmation page and
+//!   one-click unsubscribe (when email is enabled)
+//! - `POST /api/v1/email/bounces?token=` - Bounce and complaint reports from the mail provider
+//!   (when `EMAIL_BOUNCE_WEBHOOK_SECRET` is set)
+//!
+//! ## Operational Endpoints (internal listener when configured)
+//!
+//! - `GET /health/ready` (also `GET /ready`) - Readiness check (database and Valkey
+//!   reachable; LLM provider probes when chat is critical; applied migrations against this
+//!   binary's); `503` once shutdown has begun
+//! - `GET /health/detailed` - Status and latency of Postgres, Valkey and the probed LLM
+//!   providers, with a `healthy`/`degraded`/`unhealthy` verdict (`503` when unhealthy)
+//! - `GET /metrics` - Prometheus metrics (requests and durations by route, latency budget
+//!   overruns, sign-ins, chat rate limit rejections, chat reply streams, LLM tokens, database
+//!   pool); not mounted with `FEATURE_METRICS_ENABLED=false`
+//! - `/api/v1/admin/*` - Admin endpoints below
 //!
 //! ## Protected Endpoints (Requires JWT)
 //!
-//! - `GET /api/v1/auth/me` - Get current user info
+//! - `GET /api/v1/auth/me` - Get current user info (preferences, permissions, quota)
+//! - `PATCH /api/v1/auth/me` - Change username or email (a new email is verified again)
+//! - `GET|PATCH /api/v1/auth/me/preferences` - Theme, default model, language and
+//!   notification preferences
 //! - `POST /api/v1/auth/logout` - Logout user
+//! - `POST /api/v1/auth/logout-all` - Sign out every session of the user
+//! - `GET /api/v1/auth/sessions` - Active sessions with device labels, IP and last use
+//! - `DELETE /api/v1/auth/sessions/:id` - Sign out one session
+//! - `POST /api/v1/auth/change-password` - Change password and sign out other sessions;
+//!   the only endpoint open to users whose password has expired
 //! - `POST /api/v1/auth/send-verification` - Resend verification email
+//! - `GET /api/v1/notifications` - Notification inbox (when chat is enabled)
+//! - `POST /api/v1/chat/sessions/:id/messages/stream` - Send a message and stream the reply
+//!   as `chunk`, `done` and `error` SSE events (when chat is enabled)
+//! - `GET /api/v1/chat/ws` - WebSocket to send messages to several sessions, receive
+//!   their replies, typing indicators and cancel generation (when chat is enabled)
+//! - `POST /api/v1/chat/sessions/:id/generations` - Send a message without streaming;
+//!   `GET /api/v1/chat/generations/:id` long-polls the response (when chat is enabled)
+//! - `PATCH /api/v1/chat/sessions/:id` - Rename a session; 409 if it changed since the
+//!   `version` the client read (when chat is enabled)
+//! - `POST /api/v1/chat/sessions/:id/messages/bulk` - Append messages without generating a
+//!   reply (when chat is enabled)
+//! - `POST /api/v1/chat/sessions/:id/messages/:message_id/annotations` - Bookmark, label
+//!   or react to a message; `DELETE .../annotations/:annotation_id` removes one and
+//!   `GET /api/v1/chat/sessions/:id/annotations` lists them (when chat is enabled)
+//! - `GET /api/v1/chat/analytics` - Own messages per day, tokens per model, most active
+//!   sessions and reply latency (when chat is enabled)
+//! - `GET|POST /api/v1/chat/webhooks` - Webhooks posted a signed payload when a reply
+//!   completes; `DELETE /api/v1/chat/webhooks/:id` removes one and
+//!   `POST /api/v1/chat/webhooks/:id/test` sends a test delivery (when chat is enabled)
+//! - `GET|PUT /api/v1/email/digest` - Weekly digest preference (when email is enabled)
 //!
 //! ## Admin Endpoints (Requires Admin Role)
 //!
 //! - `GET /api/v1/admin/users` - List all users
+//! - `POST /api/v1/admin/users` - Create a user with a temporary password
 //! - `GET /api/v1/admin/users/:id` - Get user details
+//! - `DELETE /api/v1/admin/users/:id` - Delete a user and all their data (audited)
 //! - `PATCH /api/v1/admin/users/:id/disable` - Disable user account
 //! - `PATCH /api/v1/admin/users/:id/enable` - Enable user account
-//! - `GET /api/v1/admin/stats` - System statistics
+//! - `PATCH /api/v1/admin/users/:id/require-password-change` - Force a password change
+//! - `PATCH /api/v1/admin/users/:id/role` - Promote to or demote from admin (audited)
+//! - `GET|PUT /api/v1/admin/users/:id/roles` - Custom roles and permissions of a user
+//! - `GET|POST /api/v1/admin/roles` - List or create custom roles; `DELETE /api/v1/admin/roles/:id`
+//! - `GET /api/v1/admin/stats` - System statistics (user counts refreshed every minute)
+//! - `GET /api/v1/admin/stats/export` - Activity statistics for a period as CSV
+//! - `POST /api/v1/admin/debug-token` - Mint short-lived scoped test token (dev only)
+//! - `GET /api/v1/admin/email-verifications` - Unverified users and their verification emails
+//! - `POST /api/v1/admin/email-verifications/:id/resend` - Resend the verification email
+//! - `POST /api/v1/admin/email-verifications/:id/verify` - Force-verify an email (audited)
+//! - `GET /api/v1/admin/email-suppressions` - Addresses suppressed after bounces and complaints
+//! - `DELETE /api/v1/admin/email-suppressions/:id` - Lift a suppression (audited)
+//! - `GET /api/v1/admin/email-log` - Search the history of outbound emails
+//! - `POST /api/v1/admin/backup` - Download an encrypted backup of users and chat data
+//! - `POST /api/v1/admin/backup/exports` - Store an encrypted backup for resumable download;
+//!   `GET|DELETE /api/v1/admin/backup/exports/:id` downloads (with `Range`) or deletes it
+//! - `POST /api/v1/admin/backup/restore` - Restore a backup into a fresh instance
+//! - `GET /api/v1/admin/system/doctor` - Configuration checks with fixes
+//! - `GET /api/v1/admin/system/info` - Version and effective configuration (secrets masked)
+//! - `GET|PUT /api/v1/admin/system/switches` - Close registration or make the API read-only
+//! - `GET /api/v1/admin/access-logs` - Recorded API requests (with `ACCESS_LOG_SINK=database`)
+//! - `GET /api/v1/admin/audit-logs` - Sign-ins, password changes and admin actions
+//! - `PUT|DELETE /api/v1/admin/branding` - Override or reset the branding at runtime
+//!
+//! ## Signed Internal Endpoints (when `INTERNAL_SIGNING_KEYS` is set)
+//!
+//! Authenticated by `X-Signature-Key-Id` / `X-Signature-Timestamp` / `X-Signature`
+//! headers instead of a JWT, see [`middleware::request_signing`]:
+//!
+//! - `GET /api/v1/internal/users` - List users
+//! - `GET /api/v1/internal/users/:id` - User details
+//! - `GET /api/v1/internal/stats` - User statistics
+//! - `POST /api/v1/internal/tokens/count` - Count tokens of a text for a model
 //!
 //! # Documentation
 //!
 //! Interactive API documentation available at:
 //! - Swagger UI: <http://localhost:3000/swagger-ui>
-//! - `OpenAPI` JSON: <http://localhost:3000/openapi.json>
+//! - `OpenAPI` JSON per audience: <http://localhost:3000/openapi/public.json>,
+//!   `/openapi/authenticated.json` and `/openapi/admin.json`, plus
+//!   `/openapi/v2/<audience>.json` with `API_JSON_CASE=camelCase`
+//! - TypeScript types (with `OPENAPI_TYPES_ENABLED=true`): <http://localhost:3000/openapi/types.ts>
+//!
+//! The schema file for frontend type generation is exported on demand with
+//! `cobalt-stack-backend export-openapi --out <path>`; the server itself never
+//! writes it.
+//!
+//! # Backup and Restore
+//!
+//! Besides the admin endpoints, `cobalt-stack-backend backup --out <path>` and
+//! `cobalt-stack-backend restore --in <path>` back up or restore the database
+//! at `DATABASE_URL`, with the archive passphrase taken from `BACKUP_PASSPHRASE`.
+//! The CLI restore needs an instance without any users (migrated, not seeded).
+//!
+//! # Configuration Check
+//!
+//! `cobalt-stack-backend doctor` checks database and Valkey reachability, that
+//! the applied migrations match this binary, the `JWT_SECRET` strength, that every `${VAR}` in `models.toml` is set, CORS
+//! origins and whether the `Secure` refresh cookie survives `APP_PUBLIC_URL`'s
+//! scheme. It prints a fix for each problem and exits non-zero on errors; the
+//! same report is served at `GET /api/v1/admin/system/doctor`.
 //!
 //! # Architecture
 //!
@@ -86,37 +304,33 @@
 //! └─────────────┘
 //! ```
 
+mod app;
 mod application;
 mod config;
+#[cfg(feature = "demo")]
+mod demo;
 mod domain;
+mod dto;
 mod handlers;
 mod infrastructure;
 mod middleware;
 mod models;
 mod openapi;
+mod server;
 mod services;
 mod utils;
 
-use axum::{
-    http::{header, HeaderValue, Method},
-    middleware as axum_middleware,
-    routing::{get, patch, post},
-    Router,
-};
-use sea_orm::Database;
-use std::{net::SocketAddr, sync::Arc};
-use tower_http::cors::CorsLayer;
+use axum::middleware as axum_middleware;
+use sea_orm::{Database, DatabaseConnection};
+use std::sync::Arc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
-
-/// API version prefix for all routes
-const API_PREFIX: &str = "/api/v1";
 
 /// Application entry point.
 ///
 /// Initializes logging, database connection, and starts the Axum HTTP server.
-/// Loads configuration from environment variables and `.env` file.
+/// Loads configuration from environment variables and `.env` file. With the
+/// `export-openapi` subcommand it only writes the `OpenAPI` schema and exits;
+/// with `--demo` it runs on an in-memory database without Valkey.
 ///
 /// # Errors
 ///
@@ -125,7 +339,33 @@ const API_PREFIX: &str = "/api/v1";
 /// - Database connection fails
 /// - Server fails to bind to port
 #[tokio::main]
+#[allow(clippy::too_many_lines)]
 async fn main() -> anyhow::Result<()> {
+    // `export-openapi [--out <path>] [--audience <name>] [--json-case <case>]` writes the
+    // schema and exits without touching the database or the environment
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("export-openapi") => {
+            let export = openapi::parse_export_args(args).map_err(anyhow::Error::msg)?;
+            openapi::write_openapi_schema(&export.out, export.audience, export.json_case)?;
+            println!(
+                "OpenAPI schema ({}) written to {}",
+                export.audience,
+                export.out.display()
+            );
+            return Ok(());
+        }
+        // `backup --out <path>` / `restore --in <path>` run against `DATABASE_URL`
+        Some(command @ ("backup" | "restore")) => {
+            let command =
+                services::backup::parse_command(command, args).map_err(anyhow::Error::msg)?;
+            return run_backup_command(command).await;
+        }
+        // `doctor` checks the configuration and exits non-zero on errors
+        Some("doctor") => return run_doctor_command().await,
+        _ => {}
+    }
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
@@ -138,271 +378,495 @@ async fn main() -> anyhow::Result<()> {
     // Load environment variables
     dotenvy::dotenv().ok();
 
-    // Generate OpenAPI schema for frontend
-    if let Err(e) = openapi::write_openapi_schema() {
-        tracing::warn!("Failed to write OpenAPI schema: {}", e);
-    } else {
-        tracing::info!("OpenAPI schema generated at openapi/schema.json");
-    }
-
-    // Initialize database connection
-    let database_url = std::env::var("DATABASE_URL")?;
-    let db = Arc::new(Database::connect(&database_url).await?);
+    // Initialize database connection (in-memory SQLite in demo mode)
+    let demo_mode = std::env::args().skip(1).any(|arg| arg == "--demo");
+    let db = connect_database(demo_mode).await?;
     tracing::info!("Database connected");
 
     // Initialize JWT config
     let jwt_config = services::auth::JwtConfig::from_env();
 
-    // Initialize chat config (if enabled)
-    let chat_config = config::ChatConfig::from_env();
+    // Load application configuration (listeners, timeouts, enabled subsystems)
+    let app_config = config::AppConfig::from_env();
+    let server_config = &app_config.server;
+    let modules = app::routes::active_modules(&app_config);
+    tracing::info!(
+        chat = modules.chat,
+        admin_api = modules.admin_api,
+        email = modules.email,
+        "Enabled subsystems"
+    );
+
+    // Compare the applied migrations with this binary's
+    check_schema_version(&db, app_config.schema_check).await?;
+
+    // Create the first admin of a new deployment (if configured)
+    if let Some(admin_config) = &app_config.bootstrap_admin {
+        bootstrap_admin(&db, admin_config).await?;
+    }
+
+    // Write the permission catalog custom roles are made of
+    services::rbac::sync_permissions(&db).await?;
+
+    // Initialize chat config (only read when chat is enabled)
+    let chat_config = app_config.enable_chat.then(config::ChatConfig::from_env);
+
+    // Log the settings in effect once; admins can read them later
+    let effective_config = Arc::new(services::effective_config::EffectiveConfig::collect(
+        &app_config,
+        &jwt_config,
+        chat_config.as_ref(),
+    ));
+    tracing::info!("Effective configuration:\n{}", effective_config);
+
+    // Fail a share of dependency calls on purpose (if enabled)
+    let faults = app_config.fault_injection.clone().map(|fault_config| {
+        tracing::warn!(
+            config = ?fault_config,
+            "Fault injection enabled - development only, requests will fail on purpose"
+        );
+        Arc::new(infrastructure::fault_injection::FaultInjector::new(
+            fault_config,
+        ))
+    });
 
-    // Initialize Valkey/Redis connection (if chat enabled)
-    let valkey_manager = if chat_config.enabled {
-        let valkey_url = std::env::var("VALKEY_URL")
-            .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
-        let manager = services::valkey::ValkeyManager::new(&valkey_url)?;
-        tracing::info!("Valkey connected for chat rate limiting");
-        Some(manager)
+    // Initialize Valkey/Redis connection (if chat enabled or it stores refresh tokens)
+    let tokens_in_valkey = app_config.token_store == config::TokenStoreBackend::Valkey;
+    let valkey_manager = if (chat_config.is_some() || tokens_in_valkey) && !demo_mode {
+        Some(app::state::valkey_manager(faults.as_ref())?)
     } else {
         None
     };
 
+    // Select the refresh token store
+    let token_store = app::state::token_store(&db, valkey_manager.as_ref(), tokens_in_valkey);
+
+    // Access tokens revoked by logout, shared by all instances through Valkey
+    if valkey_manager.is_none() && !demo_mode {
+        tracing::warn!("Valkey not connected - logout revokes access tokens on this instance only");
+    }
+    let blacklist: Arc<dyn services::auth::TokenBlacklist> = valkey_manager.clone().map_or_else(
+        || Arc::new(services::auth::InMemoryBlacklist::new()) as _,
+        |valkey| Arc::new(services::valkey::blacklist::ValkeyBlacklist::new(valkey)) as _,
+    );
+
+    // Rows of authenticated users, shared across requests through Valkey
+    let mut users = middleware::current_user::UserLoader::new(Arc::clone(&db));
+    if let (Some(valkey), Some(ttl)) = (
+        &valkey_manager,
+        services::valkey::user_cache::ttl_from_env(),
+    ) {
+        users = users.with_cache(valkey.clone(), ttl);
+    }
+
+    // Access tokens issued before a user's cutoff (e.g. a password change)
+    // are rejected; the loader reads it from the cached row
+    let jwt_config = services::auth::JwtConfig {
+        not_before: Some(Arc::new(users.clone())),
+        blacklist: Some(blacklist),
+        ..jwt_config
+    };
+
+    // Initialize email delivery (if enabled), skipping suppressed addresses
+    // and recording every send in the email log
+    let email_suppressions = app_config.email_bounce.as_ref().map(|bounce_config| {
+        let list = Arc::new(services::email::suppression::SuppressionList::new());
+        app::jobs::spawn_suppression_refresh(&db, &list, bounce_config.refresh_interval);
+        list
+    });
+    let email_sender = email_suppressions
+        .as_ref()
+        .map(|list| app::state::email_sender(&db, list));
+
+    // Load the registration and read-only switches, and pick up changes made
+    // through other instances
+    let switches = Arc::new(services::runtime_switches::RuntimeSwitches::new(
+        Arc::clone(&db),
+    ));
+    app::jobs::spawn_switches_refresh(&switches);
+
+    if app_config.enable_admin_api {
+        app::jobs::spawn_admin_stats_refresh(&db);
+    }
+
+    // Sign-in counters, exposed at /metrics
+    let login_metrics = Arc::new(services::auth::LoginMetrics::new());
+
     // Create application state
     let state = handlers::auth::AppState {
         db: Arc::clone(&db),
+        users,
         jwt_config: jwt_config.clone(),
+        token_store,
+        email_sender,
+        email_suppressions,
+        modules,
+        bind_refresh_tokens: app_config.bind_refresh_tokens,
+        chat_quota: valkey_manager
+            .clone()
+            .zip(chat_config.as_ref())
+            .map(|(valkey, chat)| application::account::ChatQuotaSource {
+                valkey,
+                daily_limit: chat.daily_message_quota,
+            }),
+        password_expiry: services::auth::PasswordExpiryPolicy::from_env(),
+        trusted_devices: app::state::trusted_device_policy(app_config.enable_email),
+        sandbox: app_config.sandbox.clone(),
+        switches,
+        verification_links: Arc::new(config::VerificationLinkConfig::from_env()),
+        login_metrics: Arc::clone(&login_metrics),
     };
 
+    if let Some(sandbox) = &app_config.sandbox {
+        tracing::warn!(
+            retention_days = sandbox.retention.as_secs() / 86_400,
+            "Sandbox mode: new accounts are deleted after the retention period"
+        );
+        app::jobs::spawn_sandbox_purge(&db);
+    }
+
+    // Schedule weekly activity digests (if email enabled)
+    if let Some(email_sender) = state.email_sender.clone() {
+        app::jobs::spawn_email_digests(
+            &db,
+            valkey_manager.clone(),
+            email_sender,
+            config::DigestConfig::from_env(),
+        );
+    }
+
     // Initialize provider factory for LLM models (if chat enabled)
-    let provider_factory = if chat_config.enabled {
-        match infrastructure::llm::ProviderFactory::new() {
-            Ok(factory) => {
-                tracing::info!("LLM Provider Factory initialized successfully");
-                Some(Arc::new(factory))
-            }
-            Err(e) => {
-                tracing::error!("Failed to initialize Provider Factory: {}", e);
-                anyhow::bail!("Provider Factory initialization failed: {}", e);
-            }
-        }
+    let provider_factory = if chat_config.is_some() {
+        Some(app::state::provider_factory(faults.as_ref())?)
     } else {
         None
     };
 
+    // Model prices for LLM cost in admin stats (none configured without chat)
+    let model_pricing = Arc::new(
+        provider_factory
+            .as_ref()
+            .map(|factory| {
+                services::stats_report::ModelPricing::from_registry(&factory.model_registry())
+            })
+            .unwrap_or_default(),
+    );
+
+    // Email administrators a periodic stats report (if email enabled and configured)
+    if let (Some(email_sender), Some(report_config)) = (
+        state.email_sender.clone(),
+        config::StatsReportConfig::from_env(),
+    ) {
+        app::jobs::spawn_stats_report(&db, email_sender, report_config, &model_pricing);
+    }
+
+    // Drain and stop on SIGTERM/SIGINT; the instance is unready from then on
+    let shutdown = server::Shutdown::new();
+    tokio::spawn(server::shutdown::on_signal(shutdown.clone()));
+
+    // Probe LLM providers on a schedule (if configured); `/health/detailed`
+    // reports the results, and readiness requires a healthy provider when
+    // chat is a critical dependency
+    let provider_probes = match (&chat_config, &provider_factory, &valkey_manager) {
+        (Some(chat_config), Some(factory), Some(valkey)) => {
+            app::state::provider_probes(chat_config, factory, valkey)
+        }
+        _ => None,
+    };
+    let readiness = handlers::health::ReadinessState {
+        db: Arc::clone(&db),
+        provider_probes,
+        schema_check: app_config.schema_check,
+        valkey: valkey_manager.clone(),
+        shutdown: shutdown.clone(),
+    };
+
+    // Per-model tokenizers shared by chat and the internal token counting route
+    let tokenizers = Arc::new(services::tokenizer::TokenizerService::new());
+
+    // Chat stream outcomes, durations and token usage, also exposed at /metrics
+    let stream_metrics = Arc::new(application::chat::StreamMetrics::new());
+    let token_metrics = Arc::new(application::chat::event_subscribers::TokenMetrics::new());
+
     // Create chat state (if enabled)
-    let chat_state = if chat_config.enabled {
-        let chat_repository = infrastructure::persistence::SeaOrmChatRepository::new(Arc::clone(&db));
-        Some(handlers::chat::ChatState {
-            repository: Arc::new(chat_repository),
-            llm_config: chat_config.llm.clone(),
-            provider_factory: provider_factory.expect("Provider factory should be initialized when chat is enabled"),
-        })
+    let chat_state = chat_config.as_ref().map(|chat_config| {
+        let deps = app::state::ChatDeps {
+            provider_factory: provider_factory
+                .expect("Provider factory should be initialized when chat is enabled"),
+            valkey: valkey_manager.clone(),
+            tokenizers: Arc::clone(&tokenizers),
+            stream_metrics: Arc::clone(&stream_metrics),
+            token_metrics: Arc::clone(&token_metrics),
+            model_pricing: Arc::clone(&model_pricing),
+            shutdown: shutdown.clone(),
+        };
+        app::state::chat_state(chat_config, &app_config, &db, deps)
+    });
+
+    // Delete guests nobody claimed (guest mode needs chat)
+    if app_config.guest.is_some() {
+        if chat_state.is_some() {
+            app::jobs::spawn_guest_purge(&db);
+        } else {
+            tracing::warn!("GUEST_MODE_ENABLED is ignored because chat is disabled");
+        }
+    }
+
+    // Restrict the chat data of disabled accounts, and archive the sessions of
+    // users who stay disabled (if configured)
+    let mut account_hooks: Vec<Arc<dyn application::account::AccountLifecycleHook>> = Vec::new();
+    if let (Some(chat_state), Some(chat_config)) = (&chat_state, &chat_config) {
+        let policy = Arc::new(application::chat::AccountSuspensionPolicy::new(
+            Arc::clone(&chat_state.repository) as Arc<_>,
+            chat_config.archive_disabled_after,
+        ));
+        if chat_config.archive_disabled_after.is_some() {
+            app::jobs::spawn_chat_archival(&policy);
+        }
+        account_hooks.push(policy);
+    }
+
+    // Create rate limit state (if chat enabled)
+    let rate_limit_metrics = Arc::new(middleware::chat_rate_limit::RateLimitMetrics::new());
+    let rate_limit_state = match (valkey_manager, &chat_config) {
+        (Some(valkey), Some(chat_config)) => Some(app::state::rate_limit_state(
+            valkey,
+            chat_config,
+            &app_config,
+            &db,
+            Arc::clone(&rate_limit_metrics),
+        )),
+        _ => None,
+    };
+
+    // Request counters and latency budget overruns exposed at /metrics
+    let metrics = handlers::metrics::MetricsState {
+        http: Arc::new(middleware::metrics::HttpMetrics::new()),
+        latency: Arc::new(middleware::latency_budget::LatencyBudgets::new(
+            app_config.latency_budgets.clone(),
+        )),
+        streams: stream_metrics,
+        logins: login_metrics,
+        rate_limits: rate_limit_metrics,
+        tokens: token_metrics,
+        db: Arc::clone(&db),
+    };
+
+    // Operational endpoints go to the internal listener when one is configured
+    let ops_routes = app::routes::create_ops_routes(
+        &state,
+        &jwt_config,
+        metrics.clone(),
+        readiness,
+        app::routes::AdminDeps {
+            tokenizers,
+            account_hooks,
+            model_pricing,
+            effective_config,
+        },
+        &app_config,
+    );
+    let (public_ops_routes, internal_ops_routes) = if app_config.internal_listener.is_some() {
+        (None, Some(ops_routes))
     } else {
-        None
+        (Some(ops_routes), None)
     };
 
-    // Create rate limit state (if chat enabled)
-    let rate_limit_state = valkey_manager.map(|manager| {
-        middleware::chat_rate_limit::ChatRateLimitState {
-            valkey: manager,
-            config: services::valkey::chat_rate_limit::ChatRateLimitConfig {
-                rate_limit_per_minute: chat_config.rate_limit_per_minute,
-                daily_message_quota: chat_config.daily_message_quota,
-            },
-        }
+    // Record API requests for compliance trails (if enabled)
+    let access_log = app_config.access_log.clone().map(|access_log_config| {
+        app::routes::access_log_state(access_log_config, &db, &jwt_config, &app_config)
     });
 
     // Build application router with state
-    let app = create_app(state, jwt_config, chat_state, rate_limit_state);
+    let app = app::routes::create_app(
+        state,
+        jwt_config,
+        chat_state,
+        rate_limit_state,
+        metrics,
+        public_ops_routes,
+        &app_config,
+    );
+    // Serve the API under /api/v2 with camelCase JSON bodies as well (if enabled)
+    let app = if app_config.json_case == config::JsonCase::Camel {
+        middleware::json_case::with_camel_case_api(app)
+    } else {
+        app
+    };
+    let app = match faults {
+        Some(faults) => app.layer(axum_middleware::from_fn_with_state(
+            faults,
+            middleware::fault_injection::database_faults,
+        )),
+        None => app,
+    };
+    let app = app::routes::with_access_log(app, access_log.as_ref());
+
+    // Bind the listener: systemd-activated socket, Unix socket, or TCP port
+    let listener = server::Listener::bind(server_config).await?;
+    tracing::info!("Starting server on {}", listener);
+
+    if let (Some(internal), Some(ops_routes)) = (&app_config.internal_listener, internal_ops_routes)
+    {
+        app::listeners::spawn_internal_listener(
+            internal,
+            ops_routes,
+            &app_config,
+            access_log.as_ref(),
+            shutdown.clone(),
+        )
+        .await?;
+    }
+    app::listeners::spawn_https_redirect(server_config);
 
-    // Get port from environment or use default
-    let port = std::env::var("PORT")
-        .ok()
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(3000);
+    // Start server; returns once shutdown has drained the connections
+    server::serve_with_shutdown(listener, app, server_config, shutdown).await?;
+    tracing::info!("Server stopped");
+    Ok(())
+}
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    tracing::info!("Starting server on {}", addr);
+/// Connect to `DATABASE_URL`, or to a seeded in-memory database in demo mode.
+async fn connect_database(demo_mode: bool) -> anyhow::Result<Arc<DatabaseConnection>> {
+    if demo_mode {
+        #[cfg(feature = "demo")]
+        {
+            let db = demo::connect().await?;
+            tracing::warn!(
+                username = demo::DEMO_ADMIN_USERNAME,
+                password = demo::DEMO_ADMIN_PASSWORD,
+                "Demo mode: in-memory database, all data is lost on exit"
+            );
+            return Ok(db);
+        }
+        #[cfg(not(feature = "demo"))]
+        anyhow::bail!("--demo requires a build with `--features demo`");
+    }
 
-    // Start server
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
-    Ok(())
+    let database_url = std::env::var("DATABASE_URL")?;
+    let mut db = Database::connect(&database_url).await?;
+    // Query time counts towards the latency budget of the request
+    db.set_metric_callback(|info| {
+        use services::request_timing::{record, Dependency};
+        record(Dependency::Database, info.elapsed);
+    });
+    Ok(Arc::new(db))
 }
 
-/// Create the Axum router with all routes, middleware, and state.
-///
-/// Configures the complete application including:
-/// - Public routes (register, login, refresh)
-/// - Protected routes (profile, logout)
-/// - Admin routes (user management)
-/// - CORS middleware
-/// - Swagger UI documentation
-///
-/// # Arguments
-///
-/// * `state` - Application state with database connection and JWT config
-/// * `jwt_config` - JWT configuration for authentication middleware
-///
-/// # Returns
-///
-/// Fully configured Axum [`Router`] ready to serve HTTP requests.
-///
-/// # CORS Configuration
-///
-/// Allows requests from origins ending with `:2727` (frontend port) for development.
-/// In production, configure specific allowed origins via environment variables.
-#[allow(clippy::too_many_lines)]
-fn create_app(
-    state: handlers::auth::AppState,
-    jwt_config: services::auth::JwtConfig,
-    chat_state: Option<handlers::chat::ChatState>,
-    rate_limit_state: Option<middleware::chat_rate_limit::ChatRateLimitState>,
-) -> Router {
-    // Configure CORS with credentials support
-
-    // Get allowed origins from environment variable
-    let allowed_origins = std::env::var("CORS_ORIGINS")
-        .unwrap_or_else(|_| "http://localhost:2727,http://localhost:3001".to_string());
-
-    let origins: Vec<HeaderValue> = allowed_origins
-        .split(',')
-        .filter_map(|origin| origin.trim().parse().ok())
-        .collect();
-
-    tracing::info!("CORS allowed origins: {:?}", origins);
-
-    let cors = CorsLayer::new()
-        .allow_origin(origins)
-        .allow_methods(vec![
-            Method::GET,
-            Method::POST,
-            Method::PUT,
-            Method::PATCH,
-            Method::DELETE,
-            Method::OPTIONS,
-        ])
-        .allow_headers(vec![
-            header::AUTHORIZATION,
-            header::CONTENT_TYPE,
-            header::ACCEPT,
-            header::COOKIE,
-        ])
-        .allow_credentials(true);
-
-    // Auth routes (public)
-    let auth_public_routes = Router::new()
-        .route(
-            &format!("{API_PREFIX}/auth/register"),
-            post(handlers::auth::register),
-        )
-        .route(
-            &format!("{API_PREFIX}/auth/login"),
-            post(handlers::auth::login),
-        )
-        .route(
-            &format!("{API_PREFIX}/auth/refresh"),
-            post(handlers::auth::refresh_token),
-        )
-        .route(
-            &format!("{API_PREFIX}/auth/verify-email"),
-            post(handlers::auth::verify_email),
-        )
-        .with_state(state.clone());
+/// Log how the applied migrations compare to this binary's; with
+/// `SCHEMA_VERSION_CHECK=refuse`, fail startup unless they match.
+async fn check_schema_version(
+    db: &DatabaseConnection,
+    policy: config::SchemaCheckPolicy,
+) -> anyhow::Result<()> {
+    use services::schema_version::{check_schema, SchemaState};
 
-    // Auth routes (protected)
-    let auth_protected_routes = Router::new()
-        .route(
-            &format!("{API_PREFIX}/auth/me"),
-            get(handlers::auth::get_current_user),
-        )
-        .route(
-            &format!("{API_PREFIX}/auth/logout"),
-            post(handlers::auth::logout),
-        )
-        .route(
-            &format!("{API_PREFIX}/auth/send-verification"),
-            post(handlers::auth::send_verification_email),
-        )
-        .layer(axum_middleware::from_fn_with_state(
-            jwt_config.clone(),
-            middleware::auth::auth_middleware,
-        ))
-        .with_state(state.clone());
+    if policy == config::SchemaCheckPolicy::Off {
+        return Ok(());
+    }
+    let refuse = policy == config::SchemaCheckPolicy::Refuse;
+    match check_schema(db).await {
+        Ok(status) => {
+            status.log_diagnostics();
+            if refuse && status.state() != SchemaState::UpToDate {
+                anyhow::bail!(
+                    "Database schema does not match this binary ({}); refusing to start \
+                     (SCHEMA_VERSION_CHECK=refuse)",
+                    status.state()
+                );
+            }
+        }
+        Err(e) if refuse => anyhow::bail!("Cannot read applied migrations: {e}"),
+        Err(e) => tracing::warn!("Cannot read applied migrations: {}", e),
+    }
+    Ok(())
+}
 
-    // Admin routes (protected - requires admin role)
-    let admin_state = handlers::admin::AdminState {
-        db: state.db.clone(),
-    };
+/// Create or repair the admin account configured by `BOOTSTRAP_ADMIN_*`,
+/// unless an enabled admin exists; a failure stops startup.
+async fn bootstrap_admin(
+    db: &DatabaseConnection,
+    config: &config::BootstrapAdminConfig,
+) -> anyhow::Result<()> {
+    use services::auth::bootstrap::{self, BootstrapOutcome};
+
+    match bootstrap::bootstrap_admin(db, config).await? {
+        BootstrapOutcome::AdminExists => {
+            tracing::info!("Admin account exists - BOOTSTRAP_ADMIN_* ignored");
+        }
+        BootstrapOutcome::Created(user_id) => tracing::info!(
+            target: "audit",
+            action = "admin.bootstrapped",
+            user_id = %user_id,
+            email = %config.email,
+            "Created admin account from BOOTSTRAP_ADMIN_* - its password must be changed at \
+             first login"
+        ),
+        BootstrapOutcome::Repaired(user_id) => tracing::info!(
+            target: "audit",
+            action = "admin.bootstrap_repaired",
+            user_id = %user_id,
+            email = %config.email,
+            "No enabled admin - made the BOOTSTRAP_ADMIN_EMAIL account an admin again; its \
+             password must be changed at first login"
+        ),
+    }
+    Ok(())
+}
 
-    let admin_routes = Router::new()
-        .route(
-            &format!("{API_PREFIX}/admin/users"),
-            get(handlers::admin::list_users),
-        )
-        .route(
-            &format!("{API_PREFIX}/admin/users/:id"),
-            get(handlers::admin::get_user),
-        )
-        .route(
-            &format!("{API_PREFIX}/admin/users/:id/disable"),
-            patch(handlers::admin::disable_user),
-        )
-        .route(
-            &format!("{API_PREFIX}/admin/users/:id/enable"),
-            patch(handlers::admin::enable_user),
-        )
-        .route(
-            &format!("{API_PREFIX}/admin/stats"),
-            get(handlers::admin::get_stats),
-        )
-        .layer(axum_middleware::from_fn_with_state(
-            state.db,
-            middleware::admin::admin_middleware,
-        ))
-        .layer(axum_middleware::from_fn_with_state(
-            jwt_config.clone(),
-            middleware::auth::auth_middleware,
-        ))
-        .with_state(admin_state);
-
-    // Chat routes (protected - if feature enabled)
-    let mut app = Router::new()
-        .route("/health", get(handlers::health::health_check))
-        .merge(auth_public_routes)
-        .merge(auth_protected_routes)
-        .merge(admin_routes);
-
-    // Add chat routes if feature is enabled
-    if let (Some(chat_state), Some(rate_limit_state)) = (chat_state, rate_limit_state) {
-        tracing::info!("Chat feature enabled - mounting chat routes with rate limiting");
-
-        // Public chat routes (no auth required)
-        let chat_public_routes = handlers::chat::public_routes(chat_state.clone());
-
-        // Protected chat routes with rate limiting and auth
-        let chat_protected_routes = handlers::chat::routes_v2(chat_state)
-            .layer(axum_middleware::from_fn_with_state(
-                rate_limit_state,
-                middleware::chat_rate_limit::chat_rate_limit_middleware,
-            ))
-            .layer(axum_middleware::from_fn_with_state(
-                jwt_config.clone(),
-                middleware::auth::auth_middleware,
-            ));
-
-        // Merge both public and protected routes under /api/v1/chat
-        app = app
-            .nest(&format!("{API_PREFIX}/chat"), chat_public_routes)
-            .nest(&format!("{API_PREFIX}/chat"), chat_protected_routes);
-    } else {
-        tracing::info!("Chat feature disabled");
+/// Run the `backup` / `restore` subcommands; the passphrase comes from
+/// `BACKUP_PASSPHRASE` so it stays out of the process list.
+async fn run_backup_command(command: services::backup::BackupCommand) -> anyhow::Result<()> {
+    use services::backup::{self, BackupCommand};
+
+    dotenvy::dotenv().ok();
+    let passphrase = std::env::var(backup::PASSPHRASE_ENV)
+        .map_err(|_| anyhow::anyhow!("{} must be set", backup::PASSPHRASE_ENV))?;
+    let db = connect_database(false).await?;
+
+    match command {
+        BackupCommand::Backup { out } => {
+            let snapshot = backup::export(&db).await?;
+            std::fs::write(&out, backup::encode(&snapshot, &passphrase)?)?;
+            println!(
+                "Backup of {} users and {} chat sessions written to {}",
+                snapshot.users.len(),
+                snapshot.chat_sessions.len(),
+                out.display()
+            );
+        }
+        BackupCommand::Restore { input } => {
+            let snapshot = backup::decode(&std::fs::read(&input)?, &passphrase)?;
+            let summary = backup::restore(&db, &snapshot, None).await?;
+            println!(
+                "Restored {} users, {} chat sessions and {} messages from {}",
+                summary.users,
+                summary.chat_sessions,
+                summary.chat_messages,
+                input.display()
+            );
+        }
     }
 
-    // Build main router
-    app.merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", openapi::ApiDoc::openapi()))
-        .layer(cors)
-        .layer(tower_http::trace::TraceLayer::new_for_http())
+    Ok(())
+}
+
+/// Run the `doctor` subcommand: print every configuration finding and fail
+/// if any check has severity `error`.
+async fn run_doctor_command() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+    // Config parsers panic on invalid values; the report shows the message instead
+    std::panic::set_hook(Box::new(|_| {}));
+    let report = services::doctor::diagnose(None).await;
+    drop(std::panic::take_hook());
+
+    println!("{report}");
+    if report.has_errors() {
+        anyhow::bail!(
+            "configuration has {} errors",
+            report.count(services::doctor::Severity::Error)
+        );
+    }
+    Ok(())
 }
 
 // TODO: Add integration tests later
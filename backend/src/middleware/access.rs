@@ -0,0 +1,500 @@
+//! Route access declarations
+//!
+//! Every route mounted through [`SecuredRouter`] states the credentials it
+//! requires once, in [`ROUTES`]. The same declaration decides which
+//! middleware guards the route ([`SecuredRouter::guard`]) and which security
+//! requirement its `OpenAPI` operation carries (applied by the `ApiDoc`
+//! security modifier), so a route cannot be documented as protected while
+//! mounted without the middleware, or the other way around.
+//!
+//! A route whose path has no declaration makes [`SecuredRouter::route`] panic
+//! while the router is built, at startup.
+//!
+//! Guest tokens (see [`crate::services::auth::guest`]) are only accepted on
+//! user routes declared with [`RouteAccess::allow_guests`].
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use axum::{routing::get, Router};
+//! use cobalt_stack_backend::middleware::access::{AccessGuards, SecuredRouter};
+//!
+//! # fn example(guards: &AccessGuards) -> Router {
+//! SecuredRouter::new()
+//!     .route("/health", get(|| async { "ok" })) // declared public
+//!     .route("/api/v1/auth/me", get(|| async { "me" })) // declared user
+//!     .guard(guards)
+//! # }
+//! ```
+
+use axum::{
+    extract::Request,
+    middleware::from_fn_with_state,
+    response::IntoResponse,
+    routing::{MethodRouter, Route},
+    Router,
+};
+use std::convert::Infallible;
+use tower::{Layer, Service};
+
+use super::{
+    admin::admin_middleware,
+    auth::{auth_middleware, guest_auth_middleware},
+    current_user::UserLoader,
+};
+use crate::services::auth::JwtConfig;
+
+/// Credentials a route requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Access {
+    /// No access token
+    Public,
+    /// Access token of an active user ([`auth_middleware`])
+    User,
+    /// Access token of a user holding `admin:access` ([`auth_middleware`] and
+    /// [`admin_middleware`]); routes check their own permission on top
+    Admin,
+}
+
+/// Access declaration of one route path
+///
+/// `path` uses the axum syntax of the router (`/sessions/:id`). The
+/// declaration covers every method mounted on the path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteAccess {
+    pub path: &'static str,
+    pub access: Access,
+    /// The handler also reads the `refresh_token` cookie
+    pub refresh_cookie: bool,
+    /// Guest tokens are accepted as well (user routes only)
+    pub guests: bool,
+}
+
+impl RouteAccess {
+    #[must_use]
+    pub const fn public(path: &'static str) -> Self {
+        Self {
+            path,
+            access: Access::Public,
+            refresh_cookie: false,
+            guests: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn user(path: &'static str) -> Self {
+        Self {
+            path,
+            access: Access::User,
+            refresh_cookie: false,
+            guests: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn admin(path: &'static str) -> Self {
+        Self {
+            path,
+            access: Access::Admin,
+            refresh_cookie: false,
+            guests: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_refresh_cookie(mut self) -> Self {
+        self.refresh_cookie = true;
+        self
+    }
+
+    #[must_use]
+    pub const fn allow_guests(mut self) -> Self {
+        self.guests = true;
+        self
+    }
+
+    /// Names of the `OpenAPI` security schemes required together, empty for
+    /// a public route
+    #[must_use]
+    pub fn security_schemes(&self) -> Vec<&'static str> {
+        let mut schemes = Vec::new();
+        if self.access >= Access::User {
+            schemes.push(crate::openapi::BEARER_AUTH);
+        }
+        if self.refresh_cookie {
+            schemes.push(crate::openapi::REFRESH_COOKIE);
+        }
+        schemes
+    }
+}
+
+/// Access of every route mounted through [`SecuredRouter`]
+///
+/// The signed internal routes are authenticated by their own middleware and
+/// the Swagger UI serves static documents, so neither is listed.
+pub const ROUTES: &[RouteAccess] = &[
+    // Health, branding and operational routes
+    RouteAccess::public("/health"),
+    RouteAccess::public("/health/ready"),
+    RouteAccess::public("/health/detailed"),
+    RouteAccess::public("/ready"),
+    RouteAccess::public("/metrics"),
+    RouteAccess::public("/openapi/types.ts"),
+    RouteAccess::public("/api/v1/branding"),
+    // Authentication
+    RouteAccess::public("/api/v1/auth/register"),
+    RouteAccess::public("/api/v1/auth/login"),
+    RouteAccess::public("/api/v1/auth/refresh").with_refresh_cookie(),
+    RouteAccess::public("/api/v1/auth/verify-email"),
+    RouteAccess::public("/api/v1/auth/devices/confirm"),
+    RouteAccess::user("/api/v1/auth/me"),
+    RouteAccess::user("/api/v1/auth/me/preferences"),
+    RouteAccess::user("/api/v1/auth/logout").with_refresh_cookie(),
+    RouteAccess::user("/api/v1/auth/logout-all"),
+    RouteAccess::user("/api/v1/auth/sessions"),
+    RouteAccess::user("/api/v1/auth/sessions/:id"),
+    RouteAccess::user(super::auth::PASSWORD_CHANGE_PATH),
+    RouteAccess::user("/api/v1/auth/send-verification"),
+    RouteAccess::user("/api/v1/auth/devices"),
+    RouteAccess::user("/api/v1/auth/devices/:id"),
+    RouteAccess::public("/api/v1/auth/guest"),
+    RouteAccess::user("/api/v1/auth/guest/claim"),
+    RouteAccess::public("/api/v1/auth/oauth/:provider/authorize"),
+    RouteAccess::public("/api/v1/auth/oauth/:provider/callback"),
+    // Email (unsubscribe is authorized by the token of the link)
+    RouteAccess::public("/api/v1/email/unsubscribe"),
+    RouteAccess::public("/api/v1/email/bounces"),
+    RouteAccess::user("/api/v1/email/digest"),
+    RouteAccess::user("/api/v1/notifications"),
+    // Chat
+    RouteAccess::public("/api/v1/chat/models"),
+    RouteAccess::public("/api/v1/chat/shared/:slug"),
+    RouteAccess::user("/api/v1/chat/sessions").allow_guests(),
+    RouteAccess::user("/api/v1/chat/sessions/:id").allow_guests(),
+    RouteAccess::user("/api/v1/chat/sessions/:id/messages").allow_guests(),
+    RouteAccess::user("/api/v1/chat/sessions/:id/messages/bulk"),
+    RouteAccess::user("/api/v1/chat/sessions/:id/messages/stream").allow_guests(),
+    RouteAccess::user("/api/v1/chat/sessions/:id/messages/:message_id"),
+    RouteAccess::user("/api/v1/chat/sessions/:id/messages/:message_id/annotations"),
+    RouteAccess::user("/api/v1/chat/sessions/:id/messages/:message_id/annotations/:annotation_id"),
+    RouteAccess::user("/api/v1/chat/sessions/:id/annotations"),
+    RouteAccess::user("/api/v1/chat/sessions/:id/generations").allow_guests(),
+    RouteAccess::user("/api/v1/chat/sessions/:id/read"),
+    RouteAccess::user("/api/v1/chat/sessions/:id/share"),
+    RouteAccess::user("/api/v1/chat/sessions/:id/share/:share_id"),
+    RouteAccess::user("/api/v1/chat/generations/:id").allow_guests(),
+    RouteAccess::user("/api/v1/chat/ws").allow_guests(),
+    RouteAccess::user("/api/v1/chat/analytics"),
+    RouteAccess::user("/api/v1/chat/jobs"),
+    RouteAccess::user("/api/v1/chat/jobs/estimate"),
+    RouteAccess::user("/api/v1/chat/jobs/:id"),
+    RouteAccess::user("/api/v1/chat/jobs/:id/results"),
+    RouteAccess::user("/api/v1/chat/webhooks"),
+    RouteAccess::user("/api/v1/chat/webhooks/:id"),
+    RouteAccess::user("/api/v1/chat/webhooks/:id/test"),
+    // Admin
+    RouteAccess::admin("/api/v1/admin/users"),
+    RouteAccess::admin("/api/v1/admin/users/:id"),
+    RouteAccess::admin("/api/v1/admin/users/:id/disable"),
+    RouteAccess::admin("/api/v1/admin/users/:id/enable"),
+    RouteAccess::admin("/api/v1/admin/users/:id/require-password-change"),
+    RouteAccess::admin("/api/v1/admin/users/:id/role"),
+    RouteAccess::admin("/api/v1/admin/users/:id/roles"),
+    RouteAccess::admin("/api/v1/admin/roles"),
+    RouteAccess::admin("/api/v1/admin/roles/:id"),
+    RouteAccess::admin("/api/v1/admin/stats"),
+    RouteAccess::admin("/api/v1/admin/stats/export"),
+    RouteAccess::admin("/api/v1/admin/debug-token"),
+    RouteAccess::admin("/api/v1/admin/email-verifications"),
+    RouteAccess::admin("/api/v1/admin/email-verifications/:id/resend"),
+    RouteAccess::admin("/api/v1/admin/email-verifications/:id/verify"),
+    RouteAccess::admin("/api/v1/admin/email-suppressions"),
+    RouteAccess::admin("/api/v1/admin/email-suppressions/:id"),
+    RouteAccess::admin("/api/v1/admin/email-log"),
+    RouteAccess::admin("/api/v1/admin/backup"),
+    RouteAccess::admin("/api/v1/admin/backup/restore"),
+    RouteAccess::admin("/api/v1/admin/backup/exports"),
+    RouteAccess::admin("/api/v1/admin/backup/exports/:id"),
+    RouteAccess::admin("/api/v1/admin/system/doctor"),
+    RouteAccess::admin("/api/v1/admin/system/info"),
+    RouteAccess::admin("/api/v1/admin/system/switches"),
+    RouteAccess::admin("/api/v1/admin/access-logs"),
+    RouteAccess::admin("/api/v1/admin/audit-logs"),
+    RouteAccess::admin("/api/v1/admin/branding"),
+];
+
+/// Declaration of the route at `path` (axum syntax)
+#[must_use]
+pub fn declared(path: &str) -> Option<&'static RouteAccess> {
+    ROUTES.iter().find(|route| route.path == path)
+}
+
+/// Declaration of the `OpenAPI` operation path `path` (`/sessions/{id}`)
+#[must_use]
+pub fn declared_operation(path: &str) -> Option<&'static RouteAccess> {
+    ROUTES.iter().find(|route| openapi_path(route.path) == path)
+}
+
+/// `/sessions/:id` as `/sessions/{id}`
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            segment
+                .strip_prefix(':')
+                .map_or_else(|| segment.to_string(), |name| format!("{{{name}}}"))
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// State of the middleware guarding declared routes
+#[derive(Clone)]
+pub struct AccessGuards {
+    pub jwt_config: JwtConfig,
+    /// Where [`admin_middleware`] looks up the role of the user
+    pub users: UserLoader,
+}
+
+/// Router that mounts each route behind the middleware of its declared
+/// [`Access`]
+///
+/// Routes are collected per access level (user routes open to guests apart);
+/// [`guard`](Self::guard) layers each level with its middleware and merges them into one [`Router`]. Layers added
+/// with [`layer`](Self::layer) run inside the guards, so they can rely on the
+/// authenticated user.
+#[must_use]
+pub struct SecuredRouter<S = ()> {
+    prefix: String,
+    public: Router<S>,
+    user: Router<S>,
+    guest: Router<S>,
+    admin: Router<S>,
+}
+
+impl<S> Default for SecuredRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> SecuredRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        Self::nested("")
+    }
+
+    /// Router that will be nested at `prefix`; declarations are looked up by
+    /// the full path
+    pub fn nested(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            public: Router::new(),
+            user: Router::new(),
+            guest: Router::new(),
+            admin: Router::new(),
+        }
+    }
+
+    /// Add a route, behind the middleware its [`ROUTES`] declaration requires
+    ///
+    /// # Panics
+    /// Panics if the path has no declaration
+    pub fn route(mut self, path: &str, method_router: MethodRouter<S>) -> Self {
+        let full_path = format!("{}{path}", self.prefix);
+        let declaration = declared(&full_path)
+            .unwrap_or_else(|| panic!("route {full_path} has no access declaration in ROUTES"));
+        match declaration.access {
+            Access::Public => self.public = self.public.route(path, method_router),
+            Access::User if declaration.guests => {
+                self.guest = self.guest.route(path, method_router);
+            }
+            Access::User => self.user = self.user.route(path, method_router),
+            Access::Admin => self.admin = self.admin.route(path, method_router),
+        }
+        self
+    }
+
+    /// Merge the routes of `other`, which must share the prefix
+    pub fn merge(self, other: Self) -> Self {
+        debug_assert_eq!(self.prefix, other.prefix, "merged routers differ in prefix");
+        Self {
+            prefix: self.prefix,
+            public: self.public.merge(other.public),
+            user: self.user.merge(other.user),
+            guest: self.guest.merge(other.guest),
+            admin: self.admin.merge(other.admin),
+        }
+    }
+
+    /// Provide the state of the routes added so far (see [`Router::with_state`])
+    pub fn with_state<S2>(self, state: S) -> SecuredRouter<S2> {
+        SecuredRouter {
+            prefix: self.prefix,
+            public: self.public.with_state(state.clone()),
+            user: self.user.with_state(state.clone()),
+            guest: self.guest.with_state(state.clone()),
+            admin: self.admin.with_state(state),
+        }
+    }
+
+    /// Apply `layer` to every route added so far, inside the access guards
+    pub fn layer<L>(self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        Self {
+            prefix: self.prefix,
+            public: self.public.layer(layer.clone()),
+            user: self.user.layer(layer.clone()),
+            guest: self.guest.layer(layer.clone()),
+            admin: self.admin.layer(layer),
+        }
+    }
+
+    /// Layer each access level with its middleware and merge the routes
+    pub fn guard(self, guards: &AccessGuards) -> Router<S> {
+        let auth = from_fn_with_state(guards.jwt_config.clone(), auth_middleware);
+        let user = self.user.layer(auth.clone());
+        let guest = self.guest.layer(from_fn_with_state(
+            guards.jwt_config.clone(),
+            guest_auth_middleware,
+        ));
+        let admin = self
+            .admin
+            .layer(from_fn_with_state(guards.users.clone(), admin_middleware))
+            .layer(auth);
+        self.public.merge(user).merge(guest).merge(admin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::auth::jwt::TokenValidation;
+    use axum::{body::Body, http::StatusCode, routing::get};
+    use sea_orm::DatabaseConnection;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn guards() -> AccessGuards {
+        AccessGuards {
+            jwt_config: JwtConfig {
+                secret: "test_secret_key_for_access".to_string(),
+                access_token_expiry_minutes: 30,
+                refresh_token_expiry_days: 7,
+                validation: TokenValidation::default(),
+                not_before: None,
+                blacklist: None,
+                dpop: None,
+            },
+            users: UserLoader::new(Arc::new(DatabaseConnection::Disconnected)),
+        }
+    }
+
+    async fn status(app: &Router, path: &str) -> StatusCode {
+        let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_routes_are_guarded_by_their_declaration() {
+        let app = SecuredRouter::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/api/v1/auth/me", get(|| async { "me" }))
+            .route("/api/v1/admin/stats", get(|| async { "stats" }))
+            .guard(&guards());
+
+        assert_eq!(status(&app, "/health").await, StatusCode::OK);
+        assert_eq!(
+            status(&app, "/api/v1/auth/me").await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&app, "/api/v1/admin/stats").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nested_routes_use_the_full_path() {
+        let chat = SecuredRouter::nested("/api/v1/chat")
+            .route("/models", get(|| async { "models" }))
+            .route("/sessions", get(|| async { "sessions" }))
+            .guard(&guards());
+        let app = Router::new().nest("/api/v1/chat", chat);
+
+        assert_eq!(status(&app, "/api/v1/chat/models").await, StatusCode::OK);
+        assert_eq!(
+            status(&app, "/api/v1/chat/sessions").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guest_tokens_only_reach_routes_open_to_guests() {
+        let guards = guards();
+        let chat = SecuredRouter::nested("/api/v1/chat")
+            .route("/sessions", get(|| async { "sessions" }))
+            .route("/analytics", get(|| async { "analytics" }))
+            .guard(&guards);
+        let app = Router::new().nest("/api/v1/chat", chat);
+        let token = crate::services::auth::create_guest_access_token(
+            uuid::Uuid::new_v4(),
+            "guest_1a2b3c".to_string(),
+            chrono::Duration::minutes(10),
+            &guards.jwt_config,
+        )
+        .unwrap();
+        let call = |path: &str| {
+            Request::builder()
+                .uri(path)
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(call("/api/v1/chat/sessions")).await;
+        assert_eq!(response.unwrap().status(), StatusCode::OK);
+        let response = app.oneshot(call("/api/v1/chat/analytics")).await;
+        assert_eq!(response.unwrap().status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    #[should_panic(expected = "no access declaration")]
+    fn test_undeclared_route_panics() {
+        let _ = SecuredRouter::<()>::new().route("/api/v1/secret", get(|| async { "" }));
+    }
+
+    #[test]
+    fn test_declarations_are_unique() {
+        let mut paths = HashSet::new();
+        for route in ROUTES {
+            assert!(paths.insert(route.path), "{} declared twice", route.path);
+        }
+    }
+
+    #[test]
+    fn test_security_schemes() {
+        assert!(RouteAccess::public("/").security_schemes().is_empty());
+        assert_eq!(
+            declared("/api/v1/auth/logout").unwrap().security_schemes(),
+            ["bearer_auth", "refresh_cookie"]
+        );
+        assert_eq!(
+            declared_operation("/api/v1/chat/sessions/{id}/share/{share_id}")
+                .unwrap()
+                .security_schemes(),
+            ["bearer_auth"]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Access log middleware.
+//!
+//! [`record_access`] times every request on the router it wraps and hands
+//! an [`AccessRecord`] to the [`AccessLogger`], which applies the path
+//! exclusions and sampling of [`crate::config::AccessLogConfig`].
+//!
+//! The user is taken from a valid bearer token, whether or not the route
+//! requires one; requests with a missing, expired or forged token are
+//! recorded without a user. The layer sits outside the trusted proxy
+//! extension, so it adds its own copy of [`TrustedProxyConfig`] to the
+//! request before resolving the [`ClientIp`].
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use std::{sync::Arc, time::Instant};
+
+use crate::config::TrustedProxyConfig;
+use crate::middleware::{auth::extract_token_from_header, client_ip::ClientIp};
+use crate::services::access_log::{AccessLogger, AccessRecord};
+use crate::services::auth::{decode_access_token, JwtConfig};
+
+/// State of [`record_access`]
+#[derive(Clone)]
+pub struct AccessLogState {
+    pub logger: AccessLogger,
+    pub jwt_config: JwtConfig,
+    pub trusted_proxies: Arc<TrustedProxyConfig>,
+}
+
+/// Axum middleware that records requests in the access log
+pub async fn record_access(
+    State(state): State<AccessLogState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if !state.logger.covers(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let created_at = Utc::now();
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    request
+        .extensions_mut()
+        .insert(Arc::clone(&state.trusted_proxies));
+    let ClientIp(ip) = ClientIp::from_parts(request.extensions(), request.headers());
+    // Attribution only: skip the per-user cutoff lookup, a token revoked by a
+    // password change still names its user
+    let user_id = extract_token_from_header(request.headers())
+        .ok()
+        .and_then(|token| decode_access_token(&token, &state.jwt_config).ok())
+        .map(|claims| claims.sub);
+
+    let response = next.run(request).await;
+
+    let status = response.status().as_u16();
+    if state.logger.is_sampled(status) {
+        state.logger.record(AccessRecord {
+            created_at,
+            method,
+            path,
+            status,
+            latency: started.elapsed(),
+            user_id,
+            ip,
+        });
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AccessLogConfig, AccessLogSinkKind};
+    use crate::services::access_log::AccessLogSink;
+    use crate::services::auth::{create_access_token, jwt::TokenValidation};
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use std::{sync::Mutex, time::Duration};
+    use tower::ServiceExt;
+
+    #[derive(Default)]
+    struct CollectingSink {
+        records: Mutex<Vec<AccessRecord>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AccessLogSink for CollectingSink {
+        async fn write(&self, records: &[AccessRecord]) -> anyhow::Result<()> {
+            self.records.lock().unwrap().extend_from_slice(records);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_access_middleware() {
+        let sink = Arc::new(CollectingSink::default());
+        let config = AccessLogConfig {
+            sink: AccessLogSinkKind::Log,
+            sample_rate: 1.0,
+            exclude_paths: vec!["/health".to_string()],
+            retention: None,
+            purge_interval: Duration::from_secs(3600),
+        };
+        let jwt_config = JwtConfig {
+            secret: "test_secret_key_for_access_log".to_string(),
+            access_token_expiry_minutes: 30,
+            refresh_token_expiry_days: 7,
+            validation: TokenValidation::default(),
+            not_before: None,
+            blacklist: None,
+            dpop: None,
+        };
+        let user_id = uuid::Uuid::new_v4();
+        let token = create_access_token(user_id, "alice".to_string(), &jwt_config).unwrap();
+        let state = AccessLogState {
+            logger: AccessLogger::spawn(config, Arc::clone(&sink) as Arc<_>),
+            jwt_config,
+            trusted_proxies: Arc::new(TrustedProxyConfig::default()),
+        };
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/ok", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(state, record_access));
+
+        for uri in ["/health", "/ok?secret=1", "/missing"] {
+            let request = Request::get(uri)
+                .header("authorization", format!("Bearer {token}"))
+                .header("x-forwarded-for", "203.0.113.7")
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+        app.oneshot(
+            Request::get("/ok")
+                .header("authorization", "Bearer forged")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let records = sink.records.lock().unwrap().clone();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].path, "/ok");
+        assert_eq!(records[0].status, StatusCode::OK.as_u16());
+        assert_eq!(records[0].user_id, Some(user_id));
+        // No peer address (like the Unix socket): the forwarded client is recorded
+        assert_eq!(records[0].ip, Some([203, 0, 113, 7].into()));
+        assert_eq!(records[1].status, StatusCode::NOT_FOUND.as_u16());
+        assert_eq!(records[2].user_id, None);
+    }
+}
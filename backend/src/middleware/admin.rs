@@ -1,13 +1,16 @@
 //! Role-based authorization middleware for admin access control.
 //!
 //! This module provides middleware that restricts access to admin-only endpoints
-//! by verifying the authenticated user has the admin role. It must be used in
-//! combination with the auth middleware.
+//! by verifying the authenticated user holds the `admin:access` permission:
+//! admins, and users assigned a custom role granting it (see
+//! [`crate::services::rbac`]). It must be used in combination with the auth
+//! middleware. Routes check the permission of their operation on top, with
+//! [`crate::middleware::permission::require_permission`].
 //!
 //! # Security
 //!
 //! - Requires prior authentication via [`crate::middleware::auth::auth_middleware`]
-//! - Verifies user has [`UserRole::Admin`] role from database
+//! - Verifies user holds `admin:access` (admin role or a custom role) from database
 //! - Checks user account is not disabled
 //! - Returns 401/403 for unauthorized access attempts
 //!
@@ -17,7 +20,9 @@
 //!
 //! ```no_run
 //! use axum::{Router, routing::get, middleware};
-//! use cobalt_stack_backend::middleware::{auth::auth_middleware, admin::admin_middleware};
+//! use cobalt_stack_backend::middleware::{
+//!     admin::admin_middleware, auth::auth_middleware, current_user::UserLoader,
+//! };
 //! use cobalt_stack_backend::services::auth::JwtConfig;
 //! use sea_orm::DatabaseConnection;
 //! use std::sync::Arc;
@@ -28,7 +33,7 @@
 //! let admin_routes = Router::new()
 //!     .route("/admin/users", get(list_users))
 //!     // Admin middleware first (inner layer)
-//!     .layer(middleware::from_fn_with_state(db, admin_middleware))
+//!     .layer(middleware::from_fn_with_state(UserLoader::new(db), admin_middleware))
 //!     // Auth middleware second (outer layer)
 //!     .layer(middleware::from_fn_with_state(jwt_config, auth_middleware));
 //! # }
@@ -39,38 +44,40 @@
 //!
 //! - **401 Unauthorized**: `AuthUser` not found in extensions (`auth_middleware` not run first)
 //! - **401 Unauthorized**: User not found in database (token valid but user deleted)
-//! - **403 Forbidden**: User exists but doesn't have admin role
+//! - **403 Forbidden**: User exists but doesn't hold `admin:access`
 //! - **403 Forbidden**: User is an admin but account is disabled
 //! - **500 Internal Server Error**: Database connection/query failure
 
 use crate::middleware::auth::AuthUser;
-use crate::models::{prelude::*, sea_orm_active_enums::UserRole};
+use crate::middleware::current_user::UserLoader;
+use crate::services::rbac::{self, PermissionChecker};
 use axum::{
     extract::{Request, State},
     http::StatusCode,
     middleware::Next,
     response::Response,
 };
-use sea_orm::{DatabaseConnection, EntityTrait};
 use std::sync::Arc;
 
 /// Axum middleware that enforces admin role requirement.
 ///
 /// This middleware verifies that the authenticated user (from `auth_middleware`)
-/// has admin privileges by checking their role in the database. Only users with
-/// [`UserRole::Admin`] and non-disabled accounts can access protected routes.
+/// has admin privileges by checking their permissions in the database. Only
+/// non-disabled accounts holding `admin:access` can access protected routes.
 ///
 /// # Execution Flow
 ///
 /// 1. Extract [`AuthUser`] from request extensions (injected by `auth_middleware`)
-/// 2. Query database to fetch full user record
-/// 3. Verify user has [`UserRole::Admin`] role
-/// 4. Verify user account is not disabled (`disabled_at` is NULL)
-/// 5. Pass request to next middleware/handler
+/// 2. Load the user record (kept for the handler, see [`UserLoader`])
+/// 3. Resolve the user's permissions: none if the account is disabled or a
+///    debug token scope (if present) is below the user's role
+/// 4. Verify the permissions include `admin:access`
+/// 5. Store the permissions in the request extensions and pass the request
+///    to the next middleware/handler
 ///
 /// # Arguments
 ///
-/// * `db` - Database connection for user role verification
+/// * `users` - Loader of the user record for role verification
 /// * `req` - Incoming HTTP request with `AuthUser` in extensions
 /// * `next` - Next middleware/handler in chain
 ///
@@ -78,14 +85,16 @@ use std::sync::Arc;
 ///
 /// - `Ok(Response)` - User is admin and not disabled, request processed
 /// - `Err(StatusCode::UNAUTHORIZED)` - `AuthUser` missing or user not found
-/// - `Err(StatusCode::FORBIDDEN)` - User is not admin or account disabled
+/// - `Err(StatusCode::FORBIDDEN)` - No `admin:access`, account disabled, or token scoped below admin
 /// - `Err(StatusCode::INTERNAL_SERVER_ERROR)` - Database error
 ///
 /// # Examples
 ///
 /// ```no_run
 /// use axum::{Router, routing::patch, middleware};
-/// use cobalt_stack_backend::middleware::{auth::auth_middleware, admin::admin_middleware};
+/// use cobalt_stack_backend::middleware::{
+///     admin::admin_middleware, auth::auth_middleware, current_user::UserLoader,
+/// };
 /// use cobalt_stack_backend::services::auth::JwtConfig;
 /// use sea_orm::DatabaseConnection;
 /// use std::sync::Arc;
@@ -96,7 +105,7 @@ use std::sync::Arc;
 /// // Admin-only endpoint for disabling users
 /// let admin_routes = Router::new()
 ///     .route("/admin/users/:id/disable", patch(disable_user))
-///     .layer(middleware::from_fn_with_state(db, admin_middleware))
+///     .layer(middleware::from_fn_with_state(UserLoader::new(db), admin_middleware))
 ///     .layer(middleware::from_fn_with_state(jwt_config, auth_middleware));
 /// # }
 /// # async fn disable_user() -> &'static str { "Disabled" }
@@ -107,10 +116,11 @@ use std::sync::Arc;
 /// - Always check role from database, never trust client-provided role claims
 /// - Disabled admin accounts cannot access admin endpoints
 /// - Database connection errors fail secure (return 500, block access)
-/// - This middleware performs a database query on each request (consider caching for high traffic)
+/// - The user record is read once per request, or from Valkey for a few
+///   seconds after an earlier request; role changes and disabling invalidate it
 pub async fn admin_middleware(
-    State(db): State<Arc<DatabaseConnection>>,
-    req: Request,
+    State(users): State<UserLoader>,
+    mut req: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
     // Extract AuthUser from request extensions (injected by auth_middleware)
@@ -120,30 +130,32 @@ pub async fn admin_middleware(
         .ok_or(StatusCode::UNAUTHORIZED)?
         .clone();
 
-    // Fetch user from database to check role
-    let user = Users::find_by_id(auth_user.user_id)
-        .one(db.as_ref())
+    // Load the user to check role
+    let user = users
+        .load(req.extensions_mut(), auth_user.user_id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
-    // Check if user has admin role
-    if user.role != UserRole::Admin {
-        return Err(StatusCode::FORBIDDEN);
-    }
-
-    // Check if user account is disabled
-    if user.disabled_at.is_some() {
+    // Admins and holders of a custom role granting admin access; disabled
+    // accounts and debug tokens scoped to a lower role hold no permissions
+    let granted = PermissionChecker::new(Arc::clone(users.db()))
+        .granted(&user, auth_user.scope.as_ref())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !granted.contains(rbac::ADMIN_ACCESS) {
         return Err(StatusCode::FORBIDDEN);
     }
 
-    // User is admin and not disabled, continue
+    // Reused by the permission checks of the individual routes
+    req.extensions_mut().insert(granted);
     Ok(next.run(req).await)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::sea_orm_active_enums::UserRole;
 
     // RED PHASE - Write failing tests first
 
@@ -7,8 +7,14 @@
 //! # Security
 //!
 //! - Validates JWT signature and expiration
-//! - Requires "Bearer \<token\>" format in Authorization header
+//! - Requires "Bearer \<token\>" format in Authorization header, or
+//!   "`DPoP` \<token\>" with a `DPoP` proof for tokens bound to a client key
+//!   (see [`crate::services::auth::dpop`])
 //! - Returns 401 Unauthorized for missing/invalid tokens
+//! - Returns 403 Forbidden for tokens of users whose password has expired,
+//!   except on [`PASSWORD_CHANGE_PATH`]
+//! - Returns 403 Forbidden for guest tokens, except on routes layered with
+//!   [`guest_auth_middleware`]
 //! - Injects [`AuthUser`] into request extensions for handler access
 //!
 //! # Usage
@@ -46,15 +52,21 @@
 //! }
 //! ```
 
-use crate::services::auth::{verify_access_token, AuthError, JwtConfig};
+use crate::services::auth::{
+    dpop::DPOP_HEADER, jwt::AccessTokenClaims, verify_access_token, AuthError, JwtConfig,
+    TokenScope,
+};
 use axum::{
-    extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    extract::{OriginalUri, Request, State},
+    http::{Extensions, HeaderMap, StatusCode, Uri},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use uuid::Uuid;
 
+/// Only route accepting access tokens flagged `password_expired`
+pub const PASSWORD_CHANGE_PATH: &str = "/api/v1/auth/change-password";
+
 /// Authenticated user information extracted from JWT token.
 ///
 /// This struct is injected into request extensions by [`auth_middleware`]
@@ -64,6 +76,9 @@ use uuid::Uuid;
 ///
 /// - `user_id`: Unique identifier of the authenticated user
 /// - `username`: Username of the authenticated user
+/// - `scope`: Restriction carried by admin debug tokens, `None` for regular tokens
+/// - `guest`: Anonymous guest-mode user (only on routes open to guests)
+/// - `key_thumbprint`: Client key the token is bound to, `None` for bearer tokens
 ///
 /// # Examples
 ///
@@ -82,6 +97,12 @@ pub struct AuthUser {
     pub user_id: Uuid,
     /// Username of the authenticated user.
     pub username: String,
+    /// Scope restriction from a debug token, if any.
+    pub scope: Option<TokenScope>,
+    /// Anonymous guest-mode user, see [`crate::services::auth::guest`].
+    pub guest: bool,
+    /// Thumbprint of the `DPoP` key the token is bound to, if any.
+    pub key_thumbprint: Option<String>,
 }
 
 // Implement FromRequestParts to allow AuthUser to be used as an axum extractor
@@ -104,10 +125,88 @@ where
     }
 }
 
+/// `DPoP` proof sent with a request that issues tokens
+///
+/// Extracted as is; [`DpopProof::verify`] checks it against the method and
+/// path of the request (see [`proof_path`]).
+#[derive(Debug, Clone)]
+pub struct DpopProof {
+    proof: Option<String>,
+    method: String,
+    path: String,
+}
+
+#[axum::async_trait]
+impl<S> axum::extract::FromRequestParts<S> for DpopProof
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let mut proofs = parts.headers.get_all(DPOP_HEADER).iter();
+        let proof = match (proofs.next(), proofs.next()) {
+            (None, _) => None,
+            (Some(proof), None) => Some(
+                proof
+                    .to_str()
+                    .map_err(|_| AuthError::InvalidDpopProof)?
+                    .to_string(),
+            ),
+            (Some(_), Some(_)) => return Err(AuthError::InvalidDpopProof),
+        };
+        Ok(Self {
+            proof,
+            method: parts.method.to_string(),
+            path: proof_path(&parts.uri, &parts.extensions).to_string(),
+        })
+    }
+}
+
+/// Path a `DPoP` proof's `htu` must match
+///
+/// The path the client requested: nested routers (`/api/v1/chat`) strip
+/// their prefix from the request URI and `/api/v2` requests are forwarded
+/// as `/api/v1` ones, so the path is taken from [`OriginalUri`] when set.
+fn proof_path<'a>(uri: &'a Uri, extensions: &'a Extensions) -> &'a str {
+    extensions
+        .get::<OriginalUri>()
+        .map_or_else(|| uri.path(), |original| original.path())
+}
+
+impl DpopProof {
+    /// Thumbprint of the key the proof is signed with, `None` if the client
+    /// sent no proof or `DPoP` is disabled (the client gets bearer tokens)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuthError::InvalidDpopProof`] if the proof does not check out
+    pub fn verify(&self, jwt_config: &JwtConfig) -> Result<Option<String>, AuthError> {
+        match (&jwt_config.dpop, &self.proof) {
+            (Some(verifier), Some(proof)) => verifier
+                .verify(proof, &self.method, &self.path, None)
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Scheme of the `Authorization` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenScheme {
+    /// `Bearer <token>`
+    Bearer,
+    /// `DPoP <token>`, sent with a proof in the `DPoP` header
+    Dpop,
+}
+
 /// Extract JWT token from Authorization header.
 ///
 /// Parses the Authorization header and extracts the JWT token.
-/// Expected header format: `Authorization: Bearer <token>`
+/// Expected header format: `Authorization: Bearer <token>` (or `DPoP <token>`)
 ///
 /// # Arguments
 ///
@@ -138,26 +237,77 @@ where
 /// Returns [`AuthError::InvalidToken`] if:
 /// - Authorization header is missing
 /// - Header value is not valid UTF-8
-/// - Header doesn't start with "Bearer "
-/// - Token portion is empty after "Bearer " prefix
-fn extract_token_from_header(headers: &HeaderMap) -> Result<String, AuthError> {
+/// - Header doesn't start with "Bearer " or "`DPoP` "
+/// - Token portion is empty after the prefix
+pub fn extract_token_from_header(headers: &HeaderMap) -> Result<String, AuthError> {
+    extract_authorization(headers).map(|(_, token)| token)
+}
+
+/// Scheme and token of the Authorization header
+///
+/// # Errors
+///
+/// Same as [`extract_token_from_header`]
+pub fn extract_authorization(headers: &HeaderMap) -> Result<(TokenScheme, String), AuthError> {
     let auth_header = headers
         .get("authorization")
         .ok_or(AuthError::InvalidToken)?
         .to_str()
         .map_err(|_| AuthError::InvalidToken)?;
 
-    if !auth_header.starts_with("Bearer ") {
+    let (scheme, token) = if let Some(token) = auth_header.strip_prefix("Bearer ") {
+        (TokenScheme::Bearer, token)
+    } else if let Some(token) = auth_header.strip_prefix("DPoP ") {
+        (TokenScheme::Dpop, token)
+    } else {
         return Err(AuthError::InvalidToken);
-    }
-
-    let token = auth_header.trim_start_matches("Bearer ").to_string();
+    };
 
     if token.is_empty() {
         return Err(AuthError::InvalidToken);
     }
 
-    Ok(token)
+    Ok((scheme, token.to_string()))
+}
+
+/// Check the `DPoP` proof of a request presenting `token`
+///
+/// Bound tokens need the `DPoP` scheme and a proof signed by their key;
+/// bearer tokens must not use the `DPoP` scheme. Returns the thumbprint of
+/// the key of a bound token.
+fn check_key_binding(
+    jwt_config: &JwtConfig,
+    req: &Request,
+    scheme: TokenScheme,
+    token: &str,
+    claims: &AccessTokenClaims,
+) -> Result<Option<String>, AuthError> {
+    let Some(cnf) = &claims.cnf else {
+        return match scheme {
+            TokenScheme::Bearer => Ok(None),
+            TokenScheme::Dpop => Err(AuthError::InvalidToken),
+        };
+    };
+    // Tokens stay bound if DPoP has been turned off since they were issued
+    let verifier = jwt_config
+        .dpop
+        .as_ref()
+        .ok_or(AuthError::InvalidDpopProof)?;
+    if scheme != TokenScheme::Dpop {
+        return Err(AuthError::InvalidDpopProof);
+    }
+
+    let mut proofs = req.headers().get_all(DPOP_HEADER).iter();
+    let (Some(proof), None) = (proofs.next(), proofs.next()) else {
+        return Err(AuthError::InvalidDpopProof);
+    };
+    let proof = proof.to_str().map_err(|_| AuthError::InvalidDpopProof)?;
+    let path = proof_path(req.uri(), req.extensions());
+    let jkt = verifier.verify(proof, req.method().as_str(), path, Some(token))?;
+    if jkt != cnf.jkt {
+        return Err(AuthError::InvalidDpopProof);
+    }
+    Ok(Some(jkt))
 }
 
 /// Axum middleware that validates JWT tokens and injects authenticated user.
@@ -169,10 +319,13 @@ fn extract_token_from_header(headers: &HeaderMap) -> Result<String, AuthError> {
 /// # Flow
 ///
 /// 1. Extract token from `Authorization: Bearer <token>` header
-/// 2. Verify token signature and validate expiration
-/// 3. Extract user claims (`user_id`, username) from token
-/// 4. Create [`AuthUser`] and inject into request extensions
-/// 5. Pass request to next middleware/handler
+/// 2. Verify token signature and validate expiration, and the `DPoP` proof
+///    of tokens bound to a client key
+/// 3. Reject tokens flagged `password_expired` outside [`PASSWORD_CHANGE_PATH`],
+///    and guest tokens
+/// 4. Extract user claims (`user_id`, username) from token
+/// 5. Create [`AuthUser`] and inject into request extensions
+/// 6. Pass request to next middleware/handler
 ///
 /// # Arguments
 ///
@@ -184,6 +337,9 @@ fn extract_token_from_header(headers: &HeaderMap) -> Result<String, AuthError> {
 ///
 /// - `Ok(Response)` - Request processed successfully by downstream handler
 /// - `Err(StatusCode::UNAUTHORIZED)` - Token missing, invalid, or expired
+/// - `Ok(Response)` with 403 Forbidden - Password expired (see
+///   [`AuthError::PasswordExpired`])
+/// - `Err(StatusCode::FORBIDDEN)` - Guest token
 ///
 /// # Examples
 ///
@@ -208,24 +364,70 @@ fn extract_token_from_header(headers: &HeaderMap) -> Result<String, AuthError> {
 /// # Security Notes
 ///
 /// - Always use HTTPS in production to protect tokens in transit
-/// - Token validation includes signature check and expiration check
+/// - Token validation includes signature, expiration and configured claim
+///   checks, and the user's not-before cutoff when one is configured
 /// - Invalid tokens return 401 Unauthorized without detailed error messages
 /// - This middleware should be applied to all protected routes
 pub async fn auth_middleware(
     State(jwt_config): State<JwtConfig>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    authenticate(&jwt_config, req, next, false).await
+}
+
+/// [`auth_middleware`] that also accepts guest tokens
+///
+/// Layered on the routes declared open to guests (see
+/// [`RouteAccess::allow_guests`](super::access::RouteAccess::allow_guests)).
+///
+/// # Errors
+///
+/// Same as [`auth_middleware`], without the guest token rejection
+pub async fn guest_auth_middleware(
+    State(jwt_config): State<JwtConfig>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    authenticate(&jwt_config, req, next, true).await
+}
+
+async fn authenticate(
+    jwt_config: &JwtConfig,
     mut req: Request,
     next: Next,
+    allow_guests: bool,
 ) -> Result<Response, StatusCode> {
     // Extract token from header
-    let token = extract_token_from_header(req.headers()).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let (scheme, token) =
+        extract_authorization(req.headers()).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
     // Verify token
-    let claims = verify_access_token(&token, &jwt_config).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let claims = verify_access_token(&token, jwt_config)
+        .await
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    // Bound tokens are only accepted from the holder of their key
+    let key_thumbprint = check_key_binding(jwt_config, &req, scheme, &token, &claims)
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    // Expired passwords may only be changed
+    if claims.password_expired && req.uri().path() != PASSWORD_CHANGE_PATH {
+        return Ok(AuthError::PasswordExpired.into_response());
+    }
+
+    // Guests may only use the routes open to them
+    if claims.guest && !allow_guests {
+        return Err(StatusCode::FORBIDDEN);
+    }
 
     // Create AuthUser from claims
     let auth_user = AuthUser {
         user_id: claims.sub,
         username: claims.username,
+        scope: claims.scope,
+        guest: claims.guest,
+        key_thumbprint,
     };
 
     // Inject user into request extensions
@@ -238,13 +440,29 @@ pub async fn auth_middleware(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::services::auth::create_access_token;
+    use crate::services::auth::{
+        create_access_token, create_bound_access_token, create_password_change_token,
+        dpop::tests::{key_thumbprint, proof, verifier},
+        jwt::TokenValidation,
+    };
+    use axum::{
+        body::Body,
+        middleware,
+        routing::{get, post},
+        Router,
+    };
+    use std::sync::Arc;
+    use tower::ServiceExt;
 
     fn test_jwt_config() -> JwtConfig {
         JwtConfig {
             secret: "test_secret_key_for_middleware".to_string(),
             access_token_expiry_minutes: 30,
             refresh_token_expiry_days: 7,
+            validation: TokenValidation::default(),
+            not_before: None,
+            blacklist: None,
+            dpop: None,
         }
     }
 
@@ -303,7 +521,7 @@ mod tests {
         let token = create_access_token(user_id, username.clone(), &config).unwrap();
 
         // Verify it
-        let result = verify_access_token(&token, &config);
+        let result = verify_access_token(&token, &config).await;
         assert!(result.is_ok());
 
         let claims = result.unwrap();
@@ -314,7 +532,7 @@ mod tests {
     #[tokio::test]
     async fn test_verify_invalid_token() {
         let config = test_jwt_config();
-        let result = verify_access_token("invalid.token.format", &config);
+        let result = verify_access_token("invalid.token.format", &config).await;
         assert!(result.is_err());
     }
 
@@ -332,7 +550,132 @@ mod tests {
             ..config
         };
 
-        let result = verify_access_token(&token, &wrong_config);
+        let result = verify_access_token(&token, &wrong_config).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_expired_password_token_only_changes_password() {
+        let config = test_jwt_config();
+        let app = Router::new()
+            .route("/api/v1/auth/me", get(|| async { "me" }))
+            .route(PASSWORD_CHANGE_PATH, post(|| async { "changed" }))
+            .layer(middleware::from_fn_with_state(
+                config.clone(),
+                auth_middleware,
+            ));
+        let user_id = Uuid::new_v4();
+        let call = |method: &str, path: &str, token: &str| {
+            Request::builder()
+                .method(method)
+                .uri(path)
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let token = create_password_change_token(user_id, "alice".to_string(), &config).unwrap();
+        let response = app
+            .clone()
+            .oneshot(call("GET", "/api/v1/auth/me", &token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = app
+            .clone()
+            .oneshot(call("POST", PASSWORD_CHANGE_PATH, &token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let token = create_access_token(user_id, "alice".to_string(), &config).unwrap();
+        let response = app
+            .oneshot(call("GET", "/api/v1/auth/me", &token))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_bound_token_needs_proof() {
+        let config = test_jwt_config();
+        let app = Router::new()
+            .route("/api/v1/auth/me", get(|| async { "me" }))
+            .layer(middleware::from_fn_with_state(
+                config.clone(),
+                auth_middleware,
+            ));
+        let call = |authorization: String| {
+            Request::builder()
+                .uri("/api/v1/auth/me")
+                .header("authorization", authorization)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let user_id = Uuid::new_v4();
+
+        let bound = create_bound_access_token(
+            user_id,
+            "alice".to_string(),
+            false,
+            "thumbprint".to_string(),
+            &config,
+        )
+        .unwrap();
+        for authorization in [format!("Bearer {bound}"), format!("DPoP {bound}")] {
+            let response = app.clone().oneshot(call(authorization)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // Bearer tokens are not presented as bound ones
+        let bearer = create_access_token(user_id, "alice".to_string(), &config).unwrap();
+        let response = app.oneshot(call(format!("DPoP {bearer}"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_bound_token_on_nested_route() {
+        let config = JwtConfig {
+            dpop: Some(Arc::new(verifier())),
+            ..test_jwt_config()
+        };
+        let chat = Router::new()
+            .route("/sessions", get(|| async { "sessions" }))
+            .layer(middleware::from_fn_with_state(
+                config.clone(),
+                auth_middleware,
+            ));
+        let app = Router::new().nest("/api/v1/chat", chat);
+        let token = create_bound_access_token(
+            Uuid::new_v4(),
+            "alice".to_string(),
+            false,
+            key_thumbprint(),
+            &config,
+        )
+        .unwrap();
+        let call = |htu: &str| {
+            Request::builder()
+                .uri("/api/v1/chat/sessions")
+                .header("authorization", format!("DPoP {token}"))
+                .header(DPOP_HEADER, proof("GET", htu, Some(&token)))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // The proof names the path the client requested
+        let response = app
+            .clone()
+            .oneshot(call("https://api.example.com/api/v1/chat/sessions"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // not the one left after the nested router strips its prefix
+        let response = app
+            .oneshot(call("https://api.example.com/sessions"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
 }
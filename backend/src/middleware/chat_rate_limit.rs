@@ -1,28 +1,127 @@
 //! Chat rate limiting middleware
 //!
-//! Enforces per-minute and daily rate limits on chat message endpoints.
+//! Enforces per-minute and daily rate limits on chat message endpoints, and
+//! warns users approaching their daily quota before requests start failing.
+//! In sandbox mode, sandbox accounts are held to the sandbox limits instead.
+//! Rejections are counted in [`RateLimitMetrics`] (rendered at `/metrics`).
 
 use axum::{
     extract::{Request, State},
-    http::{header, HeaderMap, StatusCode},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
     response::Response,
     Json,
 };
+use sea_orm::DatabaseConnection;
 use serde_json::json;
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use uuid::Uuid;
 
 use crate::{
     middleware::auth::AuthUser,
-    services::valkey::{chat_rate_limit, ValkeyManager},
+    services::sandbox,
+    services::valkey::{
+        chat_rate_limit::{self, ChatRateLimitConfig, LimitType},
+        notifications::{self, Notification},
+        AsyncConnection, ValkeyManager,
+    },
 };
 
+/// Header set on chat responses once daily usage reaches a warning threshold
+pub const QUOTA_WARNING_HEADER: &str = "x-quota-warning";
+
 /// Rate limit state shared across middleware
 #[derive(Clone)]
 pub struct ChatRateLimitState {
     /// Valkey connection manager
     pub valkey: ValkeyManager,
     /// Rate limit configuration
-    pub config: chat_rate_limit::ChatRateLimitConfig,
+    pub config: ChatRateLimitConfig,
+    /// Limits of sandbox accounts (`None` unless sandbox mode is on)
+    pub sandbox: Option<SandboxRateLimit>,
+    /// Rejected requests, by limit
+    pub metrics: Arc<RateLimitMetrics>,
+}
+
+/// Limits in the order of [`RateLimitMetrics`] counters
+const LIMIT_TYPES: [LimitType; 2] = [LimitType::PerMinute, LimitType::Daily];
+
+/// Chat requests rejected by the rate limits, in the Prometheus text format
+#[derive(Debug, Default)]
+pub struct RateLimitMetrics {
+    rejections: [AtomicU64; 2],
+}
+
+impl RateLimitMetrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a request rejected by `limit`
+    pub fn record(&self, limit: LimitType) {
+        self.rejections[limit as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Requests rejected by `limit`
+    #[must_use]
+    pub fn rejections_total(&self, limit: LimitType) -> u64 {
+        self.rejections[limit as usize].load(Ordering::Relaxed)
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str(
+            "# HELP chat_rate_limit_rejections_total Chat messages rejected by a rate limit.\n",
+        );
+        out.push_str("# TYPE chat_rate_limit_rejections_total counter\n");
+        for limit in LIMIT_TYPES {
+            let _ = writeln!(
+                out,
+                "chat_rate_limit_rejections_total{{limit=\"{}\"}} {}",
+                limit.as_str(),
+                self.rejections_total(limit)
+            );
+        }
+
+        out
+    }
+}
+
+/// Chat limits of the accounts created in sandbox mode
+#[derive(Clone)]
+pub struct SandboxRateLimit {
+    /// Where sandbox accounts are recorded
+    pub db: Arc<DatabaseConnection>,
+    pub config: ChatRateLimitConfig,
+}
+
+impl ChatRateLimitState {
+    /// Limits that apply to `user_id`
+    ///
+    /// If the account cannot be looked up, the sandbox limits apply.
+    pub async fn config_for(&self, user_id: Uuid) -> &ChatRateLimitConfig {
+        let Some(sandbox) = &self.sandbox else {
+            return &self.config;
+        };
+        match sandbox::expires_at(sandbox.db.as_ref(), user_id).await {
+            Ok(Some(_)) => &sandbox.config,
+            Ok(None) => &self.config,
+            Err(e) => {
+                tracing::warn!("Failed to look up sandbox account {}: {}", user_id, e);
+                &sandbox.config
+            }
+        }
+    }
 }
 
 /// Chat rate limiting middleware
@@ -39,6 +138,11 @@ pub struct ChatRateLimitState {
 /// - `X-RateLimit-Remaining-Daily`: Remaining today
 /// - `X-RateLimit-Reset-Daily`: Unix timestamp when quota resets
 /// - `Retry-After`: Seconds until retry (if rate limited)
+/// - `X-Quota-Warning`: `daily; threshold=80; used=80; limit=100` once daily
+///   usage reaches a configured threshold (see [`quota_warning_header`])
+///
+/// The first time a threshold is reached in a daily window, a `quota_warning`
+/// notification is also added to the user's inbox.
 pub async fn chat_rate_limit_middleware(
     State(state): State<ChatRateLimitState>,
     mut req: Request,
@@ -59,8 +163,10 @@ pub async fn chat_rate_limit_middleware(
             )
         })?;
 
+    let config = state.config_for(auth_user.user_id).await;
+
     // Get Redis connection
-    let mut conn = state.valkey.get_connection().map_err(|e| {
+    let mut conn = state.valkey.get_async_connection().await.map_err(|e| {
         tracing::error!("Failed to connect to Redis: {}", e);
         (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -72,7 +178,8 @@ pub async fn chat_rate_limit_middleware(
     })?;
 
     // Check rate limits
-    let result = chat_rate_limit::check_chat_rate_limit(&mut conn, auth_user.user_id, &state.config)
+    let result = chat_rate_limit::check_chat_rate_limit(&mut conn, auth_user.user_id, config)
+        .await
         .map_err(|e| {
             tracing::error!("Rate limit check failed: {}", e);
             (
@@ -87,6 +194,7 @@ pub async fn chat_rate_limit_middleware(
     // If rate limited, return 429
     if result.exceeded {
         let limit_type = result.limit_type.unwrap();
+        state.metrics.record(limit_type);
         let retry_after = result.retry_after.unwrap_or(60);
 
         let mut headers = HeaderMap::new();
@@ -111,6 +219,7 @@ pub async fn chat_rate_limit_middleware(
 
     // Get current usage for response headers
     let (minute_count, daily_count) = chat_rate_limit::get_chat_usage(&mut conn, auth_user.user_id)
+        .await
         .map_err(|e| {
             tracing::error!("Failed to get usage stats: {}", e);
             // Continue without headers on error
@@ -120,14 +229,75 @@ pub async fn chat_rate_limit_middleware(
 
     // Store usage info in request extensions for response headers
     req.extensions_mut().insert(RateLimitInfo {
-        minute_limit: state.config.rate_limit_per_minute,
-        minute_remaining: state.config.rate_limit_per_minute.saturating_sub(minute_count),
-        daily_limit: state.config.daily_message_quota,
-        daily_remaining: state.config.daily_message_quota.saturating_sub(daily_count),
+        minute_limit: config.rate_limit_per_minute,
+        minute_remaining: config.rate_limit_per_minute.saturating_sub(minute_count),
+        daily_limit: config.daily_message_quota,
+        daily_remaining: config.daily_message_quota.saturating_sub(daily_count),
     });
 
+    // Soft warning before the hard daily limit
+    let quota_warning = chat_rate_limit::quota_warning_threshold(
+        daily_count,
+        config.daily_message_quota,
+        &config.quota_warning_thresholds,
+    );
+    if let Some(threshold) = quota_warning {
+        notify_quota_warning(
+            &mut conn,
+            auth_user.user_id,
+            threshold,
+            daily_count,
+            config.daily_message_quota,
+        )
+        .await;
+    }
+    drop(conn);
+
     // Continue to handler
-    Ok(next.run(req).await)
+    let mut response = next.run(req).await;
+    if let Some(threshold) = quota_warning {
+        response.headers_mut().insert(
+            QUOTA_WARNING_HEADER,
+            quota_warning_header(threshold, daily_count, config.daily_message_quota),
+        );
+    }
+    Ok(response)
+}
+
+/// Build the `X-Quota-Warning` value for the daily message quota
+#[must_use]
+pub fn quota_warning_header(threshold: u8, used: u64, limit: u64) -> HeaderValue {
+    HeaderValue::from_str(&format!(
+        "daily; threshold={threshold}; used={used}; limit={limit}"
+    ))
+    .expect("quota warning header is ASCII")
+}
+
+/// Notify the user once per threshold per daily window; failures are only logged
+async fn notify_quota_warning(
+    conn: &mut AsyncConnection,
+    user_id: uuid::Uuid,
+    threshold: u8,
+    used: u64,
+    limit: u64,
+) {
+    let result = async {
+        if chat_rate_limit::mark_quota_warning(conn, user_id, threshold).await? {
+            let notification = Notification::new(
+                "quota_warning",
+                format!(
+                    "You have used {used} of {limit} daily chat messages ({threshold}% of your quota)."
+                ),
+            );
+            notifications::push_notification(conn, user_id, &notification).await?;
+        }
+        anyhow::Ok(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!("Failed to record quota warning for {}: {}", user_id, e);
+    }
 }
 
 /// Rate limit information to add to response headers
@@ -172,3 +342,26 @@ pub fn add_rate_limit_headers(headers: &mut HeaderMap, info: &RateLimitInfo) {
         daily_reset.to_string().parse().unwrap(),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_warning_header_format() {
+        let value = quota_warning_header(80, 82, 100);
+        assert_eq!(value, "daily; threshold=80; used=82; limit=100");
+    }
+
+    #[test]
+    fn test_rate_limit_metrics_by_limit() {
+        let metrics = RateLimitMetrics::new();
+        metrics.record(LimitType::Daily);
+        metrics.record(LimitType::Daily);
+
+        assert_eq!(metrics.rejections_total(LimitType::Daily), 2);
+        let output = metrics.render();
+        assert!(output.contains("chat_rate_limit_rejections_total{limit=\"per_minute\"} 0"));
+        assert!(output.contains("chat_rate_limit_rejections_total{limit=\"daily\"} 2"));
+    }
+}
@@ -0,0 +1,294 @@
+//! Client IP resolution behind reverse proxies.
+//!
+//! [`ClientIp`] is the address to record in logs and rate limit keys. It is
+//! the connecting peer, unless the peer is a trusted proxy (see
+//! [`TrustedProxyConfig`]), in which case the one header the proxies are
+//! configured to set is read ([`ForwardedHeader`]):
+//!
+//! - `X-Forwarded-For` (default)
+//! - `Forwarded` (RFC 7239, `for=` parameters)
+//! - `CF-Connecting-IP`
+//!
+//! The other headers are ignored: a proxy that only appends to
+//! `X-Forwarded-For` passes a client's own `Forwarded` header through
+//! untouched. The `Forwarded`/`X-Forwarded-For` chain is walked from the
+//! right, skipping trusted proxies, so a client cannot pick its address by
+//! prepending entries. Peers on the Unix socket are local processes
+//! (normally the reverse proxy) and are treated as trusted.
+//!
+//! The peer address comes from the `ConnectInfo<SocketAddr>` extension set by
+//! [`crate::server::serve`], and the trusted proxies from an
+//! `Extension<Arc<TrustedProxyConfig>>` layer on the router.
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, Extensions, HeaderMap},
+};
+use std::convert::Infallible;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use crate::config::{ForwardedHeader, TrustedProxyConfig};
+
+const FORWARDED_HEADER: &str = "forwarded";
+const X_FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+const CF_CONNECTING_IP_HEADER: &str = "cf-connecting-ip";
+
+/// Resolved client address (`None` when it cannot be determined, e.g. a
+/// Unix socket peer that sent no forwarding headers)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    /// Resolve the client address of a request
+    #[must_use]
+    pub fn from_parts(extensions: &Extensions, headers: &HeaderMap) -> Self {
+        let peer = extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let ip = extensions.get::<Arc<TrustedProxyConfig>>().map_or_else(
+            || resolve(peer, headers, &TrustedProxyConfig::default()),
+            |trusted| resolve(peer, headers, trusted),
+        );
+        Self(ip)
+    }
+}
+
+impl fmt::Display for ClientIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(ip) => write!(f, "{ip}"),
+            None => f.write_str("unknown"),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(&parts.extensions, &parts.headers))
+    }
+}
+
+/// Resolve the client address from the peer address and forwarding headers
+///
+/// `peer` is `None` for Unix socket connections.
+#[must_use]
+pub fn resolve(
+    peer: Option<IpAddr>,
+    headers: &HeaderMap,
+    trusted: &TrustedProxyConfig,
+) -> Option<IpAddr> {
+    let peer = peer.map(|peer| peer.to_canonical());
+    if let Some(peer) = peer.filter(|peer| !trusted.is_trusted(*peer)) {
+        return Some(peer);
+    }
+
+    let forwarded = match trusted.header {
+        ForwardedHeader::XForwardedFor => {
+            walk_chain(&header_values(headers, X_FORWARDED_FOR_HEADER), trusted)
+        }
+        ForwardedHeader::Forwarded => walk_chain(&forwarded_chain(headers), trusted),
+        ForwardedHeader::CfConnectingIp => headers
+            .get(CF_CONNECTING_IP_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_node),
+    };
+    forwarded.or(peer)
+}
+
+/// Pick the rightmost untrusted hop, or the leftmost one if all are trusted
+///
+/// Stops at the first entry that is not an address (`unknown`, obfuscated
+/// identifiers), since nothing left of it can be attributed.
+fn walk_chain(chain: &[&str], trusted: &TrustedProxyConfig) -> Option<IpAddr> {
+    let mut nearest = None;
+    for entry in chain.iter().rev() {
+        let Some(ip) = parse_node(entry) else {
+            break;
+        };
+        if !trusted.is_trusted(ip) {
+            return Some(ip);
+        }
+        nearest = Some(ip);
+    }
+    nearest
+}
+
+/// `for=` values of all `Forwarded` elements, in order
+fn forwarded_chain(headers: &HeaderMap) -> Vec<&str> {
+    header_values(headers, FORWARDED_HEADER)
+        .into_iter()
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim().eq_ignore_ascii_case("for").then_some(value)
+            })
+        })
+        .collect()
+}
+
+/// Comma-separated entries across all values of a header, in order
+fn header_values<'a>(headers: &'a HeaderMap, name: &str) -> Vec<&'a str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+/// Parse a node as sent by proxies: `ip`, `ip:port`, `[ipv6]` or
+/// `[ipv6]:port`, optionally quoted
+fn parse_node(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+    let ip = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .map_or_else(
+            || {
+                value
+                    .parse::<IpAddr>()
+                    .ok()
+                    .or_else(|| value.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+            },
+            |bracketed| bracketed.parse().ok(),
+        );
+    ip.map(|ip: IpAddr| ip.to_canonical())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn trusted() -> TrustedProxyConfig {
+        trusted_setting(ForwardedHeader::XForwardedFor)
+    }
+
+    fn trusted_setting(header: ForwardedHeader) -> TrustedProxyConfig {
+        TrustedProxyConfig {
+            networks: vec!["10.0.0.0/8".parse().unwrap()],
+            header,
+        }
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_untrusted_peer_headers_are_ignored() {
+        let headers = headers(&[
+            ("x-forwarded-for", "198.51.100.1"),
+            ("cf-connecting-ip", "198.51.100.2"),
+        ]);
+        assert_eq!(
+            resolve(Some(ip("203.0.113.7")), &headers, &trusted()),
+            Some(ip("203.0.113.7"))
+        );
+    }
+
+    #[test]
+    fn test_x_forwarded_for_walks_past_trusted_hops() {
+        // The client prepended a fake address; the last untrusted hop wins
+        let headers = headers(&[
+            ("x-forwarded-for", "1.1.1.1, 203.0.113.7"),
+            ("x-forwarded-for", "10.0.0.2"),
+        ]);
+        assert_eq!(
+            resolve(Some(ip("10.0.0.1")), &headers, &trusted()),
+            Some(ip("203.0.113.7"))
+        );
+
+        let all_trusted = self::headers(&[("x-forwarded-for", "10.0.0.3, 10.0.0.2")]);
+        assert_eq!(
+            resolve(Some(ip("10.0.0.1")), &all_trusted, &trusted()),
+            Some(ip("10.0.0.3"))
+        );
+    }
+
+    #[test]
+    fn test_only_the_configured_header_is_read() {
+        let headers = headers(&[
+            (
+                "forwarded",
+                "for=\"[2001:db8::17]:4711\";proto=https, For=10.0.0.2",
+            ),
+            ("x-forwarded-for", "198.51.100.1"),
+            ("cf-connecting-ip", "198.51.100.2"),
+        ]);
+        let peer = Some(ip("10.0.0.1"));
+
+        // A client's own Forwarded header, passed through by a proxy that
+        // only appends to X-Forwarded-For
+        assert_eq!(
+            resolve(peer, &headers, &trusted()),
+            Some(ip("198.51.100.1"))
+        );
+        assert_eq!(
+            resolve(peer, &headers, &trusted_setting(ForwardedHeader::Forwarded)),
+            Some(ip("2001:db8::17"))
+        );
+        assert_eq!(
+            resolve(
+                peer,
+                &headers,
+                &trusted_setting(ForwardedHeader::CfConnectingIp)
+            ),
+            Some(ip("198.51.100.2"))
+        );
+
+        // The configured header missing: the peer, not another header
+        let spoofed = self::headers(&[("forwarded", "for=1.2.3.4")]);
+        assert_eq!(resolve(peer, &spoofed, &trusted()), peer);
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        // Trusted peer without headers, and a garbage chain
+        assert_eq!(
+            resolve(Some(ip("10.0.0.1")), &HeaderMap::new(), &trusted()),
+            Some(ip("10.0.0.1"))
+        );
+        let garbage = headers(&[("x-forwarded-for", "unknown")]);
+        assert_eq!(
+            resolve(Some(ip("10.0.0.1")), &garbage, &trusted()),
+            Some(ip("10.0.0.1"))
+        );
+
+        // Unix socket peers trust the headers but have no address of their own
+        let forwarded = headers(&[("x-forwarded-for", "203.0.113.7:5000")]);
+        assert_eq!(
+            resolve(None, &forwarded, &trusted()),
+            Some(ip("203.0.113.7"))
+        );
+        assert_eq!(resolve(None, &HeaderMap::new(), &trusted()), None);
+    }
+
+    #[test]
+    fn test_ipv4_mapped_peer_is_canonical() {
+        assert_eq!(
+            resolve(
+                Some(ip("::ffff:203.0.113.7")),
+                &HeaderMap::new(),
+                &trusted()
+            ),
+            Some(ip("203.0.113.7"))
+        );
+    }
+}
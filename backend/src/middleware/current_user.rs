@@ -0,0 +1,290 @@
+//! The authenticated user's `users` row, read at most once per request.
+//!
+//! [`auth_middleware`](super::auth::auth_middleware) only validates the
+//! token. Whatever needs the caller's row next (the admin middleware checking
+//! the role, a handler) loads it through [`UserLoader`], which keeps it in the
+//! request extensions for everything running after it. Handlers take it with
+//! the [`CurrentUser`] extractor; routes that do not need the row don't pay
+//! for it.
+//!
+//! When Valkey is connected, rows are also shared across requests for a few
+//! seconds (see [`crate::services::valkey::user_cache`]). Writers changing a
+//! user call [`UserLoader::invalidate`].
+//!
+//! Loaded rows never carry the password hash; code checking a password reads
+//! the row itself.
+//!
+//! The loader is also the [`NotBeforeStore`] of the access token cutoff,
+//! which it reads from the cached row.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use cobalt_stack_backend::middleware::current_user::CurrentUser;
+//!
+//! async fn handler(CurrentUser(user): CurrentUser) -> String {
+//!     format!("{} <{}>", user.username, user.email)
+//! }
+//! ```
+
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, Extensions},
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::middleware::auth::AuthUser;
+use crate::models::{prelude::*, users};
+use crate::services::auth::{self, not_before, AuthError, NotBeforeStore};
+use crate::services::valkey::{user_cache, ValkeyManager};
+
+/// Row loaded earlier in the request
+#[derive(Clone)]
+struct LoadedUser(Arc<users::Model>);
+
+/// Loads users' rows through the request and Valkey caches
+#[derive(Clone)]
+pub struct UserLoader {
+    db: Arc<DatabaseConnection>,
+    /// Valkey and the expiry of its entries (`None` without Valkey or with
+    /// `USER_CACHE_TTL_SECS=0`)
+    cache: Option<(ValkeyManager, Duration)>,
+}
+
+impl UserLoader {
+    /// Loader reading the database once per request
+    #[must_use]
+    pub const fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db, cache: None }
+    }
+
+    /// Share rows across requests through Valkey for `ttl`
+    #[must_use]
+    pub fn with_cache(mut self, valkey: ValkeyManager, ttl: Duration) -> Self {
+        self.cache = Some((valkey, ttl));
+        self
+    }
+
+    /// Database the rows are read from
+    #[must_use]
+    pub const fn db(&self) -> &Arc<DatabaseConnection> {
+        &self.db
+    }
+
+    /// Row of `user_id`, `None` if there is no such user
+    ///
+    /// Reuses the row loaded earlier in the request, then the Valkey entry;
+    /// Valkey failures fall back to the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on database failure
+    pub async fn load(
+        &self,
+        extensions: &mut Extensions,
+        user_id: Uuid,
+    ) -> Result<Option<Arc<users::Model>>, DbErr> {
+        if let Some(LoadedUser(user)) = extensions.get::<LoadedUser>() {
+            if user.id == user_id {
+                return Ok(Some(Arc::clone(user)));
+            }
+        }
+
+        let user = if let Some(user) = self.cached(user_id).await {
+            user
+        } else {
+            let Some(user) = Users::find_by_id(user_id).one(self.db.as_ref()).await? else {
+                return Ok(None);
+            };
+            let user = users::Model {
+                password_hash: None,
+                ..user
+            };
+            self.store(&user).await;
+            user
+        };
+
+        let user = Arc::new(user);
+        extensions.insert(LoadedUser(Arc::clone(&user)));
+        Ok(Some(user))
+    }
+
+    /// Drop the Valkey entry of `user_id` after changing the user
+    ///
+    /// Failures are logged; the entry then expires on its own.
+    pub async fn invalidate(&self, user_id: Uuid) {
+        let Some((valkey, _)) = &self.cache else {
+            return;
+        };
+        let result = async {
+            let mut conn = valkey.get_async_connection().await?;
+            user_cache::invalidate(&mut conn, user_id).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(%user_id, "Failed to invalidate cached user: {}", e);
+        }
+    }
+
+    async fn cached(&self, user_id: Uuid) -> Option<users::Model> {
+        let (valkey, _) = self.cache.as_ref()?;
+        let result = async {
+            let mut conn = valkey.get_async_connection().await?;
+            user_cache::get(&mut conn, user_id).await
+        }
+        .await;
+        result.unwrap_or_else(|e| {
+            tracing::warn!(%user_id, "Failed to read cached user: {}", e);
+            None
+        })
+    }
+
+    async fn store(&self, user: &users::Model) {
+        let Some((valkey, ttl)) = &self.cache else {
+            return;
+        };
+        let result = async {
+            let mut conn = valkey.get_async_connection().await?;
+            user_cache::set(&mut conn, user, *ttl).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(user_id = %user.id, "Failed to cache user: {}", e);
+        }
+    }
+}
+
+/// Cutoffs read from the users' rows, through the Valkey cache when it is on
+///
+/// Token verification has no request to keep the row in, so without Valkey
+/// this is one query per check.
+#[async_trait]
+impl NotBeforeStore for UserLoader {
+    async fn not_before(&self, user_id: Uuid) -> auth::Result<Option<DateTime<Utc>>> {
+        let user = self.load(&mut Extensions::new(), user_id).await?;
+        Ok(user
+            .and_then(|user| user.tokens_not_before)
+            .map(|at| at.with_timezone(&Utc)))
+    }
+
+    async fn bump(&self, user_id: Uuid) -> auth::Result<()> {
+        not_before::bump(&self.db, user_id).await?;
+        // Otherwise other instances keep accepting old tokens until the entry expires
+        self.invalidate(user_id).await;
+        Ok(())
+    }
+}
+
+/// Row of the authenticated user, without the password hash
+///
+/// Requires [`auth_middleware`](super::auth::auth_middleware) and a state
+/// providing a [`UserLoader`]. Rejects with 401 without an authenticated
+/// user and 404 if the user no longer exists.
+#[derive(Debug, Clone)]
+pub struct CurrentUser(pub Arc<users::Model>);
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    UserLoader: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user_id = parts
+            .extensions
+            .get::<AuthUser>()
+            .ok_or(AuthError::InvalidToken)?
+            .user_id;
+        UserLoader::from_ref(state)
+            .load(&mut parts.extensions, user_id)
+            .await?
+            .map(Self)
+            .ok_or(AuthError::UserNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::sea_orm_active_enums::UserRole;
+    use chrono::SubsecRound;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    fn user() -> users::Model {
+        let now = Utc::now();
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password_hash: Some("hash".to_string()),
+            email_verified: true,
+            created_at: now.into(),
+            updated_at: now.into(),
+            role: UserRole::User,
+            disabled_at: None,
+            last_login_at: None,
+            tokens_not_before: None,
+            password_changed_at: None,
+            password_rotation_required: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_row_is_read_once_per_request() {
+        let alice = user();
+        let db = Arc::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([[alice.clone()]])
+                .into_connection(),
+        );
+        let loader = UserLoader::new(Arc::clone(&db));
+        let mut extensions = Extensions::new();
+
+        let first = loader.load(&mut extensions, alice.id).await.unwrap();
+        let second = loader.load(&mut extensions, alice.id).await.unwrap();
+
+        assert_eq!(first.unwrap().username, "alice");
+        let second = second.unwrap();
+        assert_eq!(second.id, alice.id);
+        assert_eq!(second.password_hash, None);
+        drop(loader);
+        let db = Arc::try_unwrap(db).unwrap();
+        assert_eq!(db.into_transaction_log().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_not_before_is_read_from_the_row() {
+        let cutoff = Utc::now().trunc_subsecs(0);
+        let alice = users::Model {
+            tokens_not_before: Some(cutoff.into()),
+            ..user()
+        };
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[alice.clone()], [user()]])
+            .into_connection();
+        let loader = UserLoader::new(Arc::new(db));
+
+        assert_eq!(loader.not_before(alice.id).await.unwrap(), Some(cutoff));
+        assert_eq!(loader.not_before(Uuid::new_v4()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_missing_user_is_not_cached() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<users::Model>::new()])
+            .into_connection();
+        let loader = UserLoader::new(Arc::new(db));
+        let mut extensions = Extensions::new();
+
+        let user = loader.load(&mut extensions, Uuid::new_v4()).await.unwrap();
+
+        assert!(user.is_none());
+        assert!(extensions.get::<LoadedUser>().is_none());
+    }
+}
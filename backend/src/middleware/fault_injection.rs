@@ -0,0 +1,77 @@
+//! Simulated database timeouts (development only)
+//!
+//! The database connection cannot fail on demand, so the fault is injected
+//! around the request instead: a sampled request hangs for
+//! `FAULT_DATABASE_TIMEOUT_DELAY_MS`, then fails the way a request whose query
+//! timed out does. Health checks are never failed, so orchestrators keep the
+//! instance running while faults are injected.
+//!
+//! See [`crate::infrastructure::fault_injection`] for the other dependencies.
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::dto::ErrorResponse;
+use crate::infrastructure::fault_injection::{Fault, FaultInjector};
+
+/// Fail a share of requests as if the database timed out
+pub async fn database_faults(
+    State(faults): State<Arc<FaultInjector>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().path().starts_with("/health") || !faults.inject(Fault::DatabaseTimeout) {
+        return next.run(request).await;
+    }
+
+    tokio::time::sleep(faults.config().database_timeout_delay).await;
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ErrorResponse {
+            error: "Database query timed out (injected fault)".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FaultInjectionConfig;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    fn app(database_timeout_percent: u8) -> Router {
+        let faults = Arc::new(FaultInjector::new(FaultInjectionConfig {
+            database_timeout_percent,
+            database_timeout_delay: Duration::from_millis(1),
+            ..FaultInjectionConfig::default()
+        }));
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/api/v1/users/me", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(faults, database_faults))
+    }
+
+    async fn status(app: Router, path: &str) -> StatusCode {
+        let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_database_faults() {
+        assert_eq!(
+            status(app(100), "/api/v1/users/me").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(status(app(100), "/health").await, StatusCode::OK);
+        assert_eq!(status(app(0), "/api/v1/users/me").await, StatusCode::OK);
+    }
+}
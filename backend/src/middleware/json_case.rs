@@ -0,0 +1,178 @@
+//! The API under `/api/v2` with `camelCase` JSON bodies.
+//!
+//! DTOs serialize in `snake_case`, which `/api/v1` keeps serving to existing
+//! clients. With `API_JSON_CASE=camelCase`, [`with_camel_case_api`] also
+//! mounts every `/api/v1` route under [`CAMEL_CASE_PREFIX`]: a request there
+//! has the field names of its JSON body renamed to `snake_case`, runs through
+//! the `/api/v1` router (middleware included), and has the field names of a
+//! JSON response renamed to `camelCase`.
+//!
+//! Only JSON bodies are renamed: query parameters and streamed
+//! (`text/event-stream`) responses keep their `snake_case` names.
+
+use std::convert::Infallible;
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::Request,
+    http::{header, uri::PathAndQuery, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use tower::{service_fn, ServiceExt};
+
+use crate::dto::ErrorResponse;
+use crate::utils::json_case::{rename_keys, to_camel_case, to_snake_case};
+
+/// Prefix of the `snake_case` API the `camelCase` one forwards to
+pub const SNAKE_CASE_PREFIX: &str = "/api/v1";
+
+/// Prefix of the `camelCase` API
+pub const CAMEL_CASE_PREFIX: &str = "/api/v2";
+
+/// Largest JSON request body renamed, the default body limit of the handlers
+pub const MAX_JSON_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Mount the `/api/v1` routes of `app` under `/api/v2` as well, with
+/// `camelCase` JSON bodies
+pub fn with_camel_case_api(app: Router) -> Router {
+    let api = app.clone();
+    app.nest_service(
+        CAMEL_CASE_PREFIX,
+        service_fn(move |req: Request| {
+            let api = api.clone();
+            async move { Ok::<_, Infallible>(forward(api, req).await) }
+        }),
+    )
+}
+
+/// Run a `/api/v2` request (prefix already stripped) through the `snake_case` API
+async fn forward(api: Router, req: Request) -> Response {
+    let (mut parts, body) = req.into_parts();
+
+    let path = parts.uri.path_and_query().map_or("/", PathAndQuery::as_str);
+    let mut uri = parts.uri.clone().into_parts();
+    uri.path_and_query = format!("{SNAKE_CASE_PREFIX}{path}").parse().ok();
+    let Ok(uri) = Uri::from_parts(uri) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    parts.uri = uri;
+
+    let body = if is_json(&parts.headers) {
+        let Ok(bytes) = to_bytes(body, MAX_JSON_BODY_BYTES).await else {
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(ErrorResponse {
+                    error: "Request body too large".to_string(),
+                }),
+            )
+                .into_response();
+        };
+        parts.headers.remove(header::CONTENT_LENGTH);
+        Body::from(rename_json(bytes, to_snake_case))
+    } else {
+        body
+    };
+
+    let response = match api.oneshot(Request::from_parts(parts, body)).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+    if !is_json(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(rename_json(bytes, to_camel_case)))
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+/// `bytes` with its field names renamed, or as-is if it is not valid JSON
+/// (the handler reports the error)
+fn rename_json(bytes: Bytes, rename: fn(&str) -> String) -> Bytes {
+    match serde_json::from_slice(&bytes) {
+        Ok(value) => serde_json::to_vec(&rename_keys(value, rename)).map_or(bytes, Bytes::from),
+        Err(_) => bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::{get, post};
+    use serde_json::{json, Value};
+
+    #[derive(serde::Deserialize, serde::Serialize)]
+    struct Login {
+        username_or_email: String,
+    }
+
+    fn app() -> Router {
+        with_camel_case_api(
+            Router::new()
+                .route(
+                    "/api/v1/echo",
+                    post(|Json(login): Json<Login>| async move { Json(login) }),
+                )
+                .route("/api/v1/text", get(|| async { "expires_in" })),
+        )
+    }
+
+    async fn call(app: Router, request: Request) -> (StatusCode, Bytes) {
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        (
+            status,
+            to_bytes(response.into_body(), usize::MAX).await.unwrap(),
+        )
+    }
+
+    fn post_json(path: &str, body: &Value) -> Request {
+        Request::post(path)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_camel_case_api_renames_json_fields() {
+        let (status, body) = call(
+            app(),
+            post_json("/api/v2/echo", &json!({ "usernameOrEmail": "alice" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body, json!({ "usernameOrEmail": "alice" }));
+    }
+
+    #[tokio::test]
+    async fn test_snake_case_api_is_unchanged() {
+        let (status, body) = call(
+            app(),
+            post_json("/api/v1/echo", &json!({ "username_or_email": "alice" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body, json!({ "username_or_email": "alice" }));
+    }
+
+    #[tokio::test]
+    async fn test_non_json_bodies_pass_through() {
+        let request = Request::get("/api/v2/text").body(Body::empty()).unwrap();
+        let (status, body) = call(app(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(&body[..], b"expires_in");
+    }
+}
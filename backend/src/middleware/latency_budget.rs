@@ -0,0 +1,189 @@
+//! Latency budget middleware with slow-request warnings.
+//!
+//! [`check_latency_budget`] times every request on the router it wraps
+//! against the budget of its route ([`LatencyBudgetConfig`]). A request over
+//! budget is logged as a warning with the time it spent in the database,
+//! Valkey and the LLM provider (see [`crate::services::request_timing`]),
+//! and counted in `http_latency_budget_exceeded_total`, labelled with the
+//! matched route prefix, so an alert can fire on the rate before users
+//! complain.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use axum::{middleware, routing::get, Router};
+//! use cobalt_stack_backend::config::LatencyBudgetConfig;
+//! use cobalt_stack_backend::middleware::latency_budget::{check_latency_budget, LatencyBudgets};
+//! use std::sync::Arc;
+//!
+//! let budgets = Arc::new(LatencyBudgets::new(LatencyBudgetConfig::from_env()));
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "ok" }))
+//!     .layer(middleware::from_fn_with_state(budgets, check_latency_budget));
+//! ```
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use crate::config::LatencyBudgetConfig;
+use crate::services::request_timing;
+
+/// `route` label of requests on the default budget
+const DEFAULT_ROUTE: &str = "default";
+
+/// Configured budgets and the count of requests over each
+#[derive(Debug)]
+pub struct LatencyBudgets {
+    config: LatencyBudgetConfig,
+    /// One counter per entry of `config.routes`, then the default budget
+    exceeded: Vec<AtomicU64>,
+}
+
+impl LatencyBudgets {
+    #[must_use]
+    pub fn new(config: LatencyBudgetConfig) -> Self {
+        let exceeded = (0..=config.routes.len())
+            .map(|_| AtomicU64::new(0))
+            .collect();
+        Self { config, exceeded }
+    }
+
+    /// Requests over budget for a route prefix (`None` = default budget)
+    #[must_use]
+    pub fn exceeded_total(&self, prefix: Option<&str>) -> u64 {
+        self.exceeded[self.index(prefix)].load(Ordering::Relaxed)
+    }
+
+    fn index(&self, prefix: Option<&str>) -> usize {
+        prefix
+            .and_then(|prefix| {
+                self.config
+                    .routes
+                    .iter()
+                    .position(|route| route.prefix == prefix)
+            })
+            .unwrap_or(self.config.routes.len())
+    }
+
+    /// Render the counters in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str(
+            "# HELP http_latency_budget_exceeded_total Requests slower than the latency budget of their route.\n",
+        );
+        out.push_str("# TYPE http_latency_budget_exceeded_total counter\n");
+        let labels = self
+            .config
+            .routes
+            .iter()
+            .map(|route| route.prefix.as_str())
+            .chain([DEFAULT_ROUTE]);
+        for (route, counter) in labels.zip(&self.exceeded) {
+            let _ = writeln!(
+                out,
+                "http_latency_budget_exceeded_total{{route=\"{route}\"}} {}",
+                counter.load(Ordering::Relaxed)
+            );
+        }
+
+        out
+    }
+}
+
+/// Axum middleware that reports requests slower than their budget
+pub async fn check_latency_budget(
+    State(budgets): State<Arc<LatencyBudgets>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let started = Instant::now();
+
+    let (response, timings) = request_timing::scope(next.run(request)).await;
+
+    let elapsed = started.elapsed();
+    let (prefix, budget) = budgets.config.budget_for(&path);
+    if elapsed > budget {
+        budgets.exceeded[budgets.index(prefix)].fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            %method,
+            path,
+            route = prefix.unwrap_or(DEFAULT_ROUTE),
+            status = response.status().as_u16(),
+            elapsed_ms = elapsed.as_millis(),
+            budget_ms = budget.as_millis(),
+            db_ms = timings.database.total.as_millis(),
+            db_queries = timings.database.calls,
+            valkey_ms = timings.valkey.total.as_millis(),
+            valkey_commands = timings.valkey.calls,
+            provider_ms = timings.provider.total.as_millis(),
+            "Request exceeded its latency budget"
+        );
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::latency_budget::RouteBudget;
+    use crate::services::request_timing::Dependency;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_counts_requests_over_budget() {
+        let budgets = Arc::new(LatencyBudgets::new(LatencyBudgetConfig {
+            default: Duration::from_millis(20),
+            routes: vec![RouteBudget {
+                prefix: "/chat".to_string(),
+                budget: Duration::from_secs(5),
+            }],
+        }));
+        let slow = || async {
+            request_timing::timed(Dependency::Provider, async {
+                tokio::time::sleep(Duration::from_millis(40)).await;
+            })
+            .await;
+            "ok"
+        };
+        let app = Router::new()
+            .route("/fast", get(|| async { "ok" }))
+            .route("/slow", get(slow))
+            .route("/chat/slow", get(slow))
+            .layer(middleware::from_fn_with_state(
+                Arc::clone(&budgets),
+                check_latency_budget,
+            ));
+
+        for uri in ["/fast", "/slow", "/chat/slow"] {
+            app.clone()
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(budgets.exceeded_total(None), 1);
+        assert_eq!(budgets.exceeded_total(Some("/chat")), 0);
+
+        let output = budgets.render();
+        assert!(output.contains("# TYPE http_latency_budget_exceeded_total counter"));
+        assert!(output.contains("http_latency_budget_exceeded_total{route=\"default\"} 1"));
+        assert!(output.contains("http_latency_budget_exceeded_total{route=\"/chat\"} 0"));
+    }
+}
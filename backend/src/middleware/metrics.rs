@@ -0,0 +1,355 @@
+//! Request counting middleware with Prometheus text exposition.
+//!
+//! [`HttpMetrics`] keeps lock-free counters of handled requests (by status
+//! class) and in-flight requests, plus request counts and durations per
+//! route. [`track_metrics`] updates them for every request on the router it
+//! wraps; [`HttpMetrics::render`] produces the Prometheus text format served
+//! at `/metrics`.
+//!
+//! Routes are labelled with their template (`/api/v1/chat/sessions/:id`),
+//! so the number of series stays bounded; requests no route matched share
+//! the `unmatched` label.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use axum::{middleware, routing::get, Router};
+//! use cobalt_stack_backend::middleware::metrics::{track_metrics, HttpMetrics};
+//! use std::sync::Arc;
+//!
+//! let metrics = Arc::new(HttpMetrics::new());
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "ok" }))
+//!     .layer(middleware::from_fn_with_state(metrics, track_metrics));
+//! ```
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+/// Status classes reported as the `status` label
+const STATUS_CLASSES: [&str; 5] = ["1xx", "2xx", "3xx", "4xx", "5xx"];
+
+/// Upper bounds of the request duration histogram buckets, in seconds
+const DURATION_BUCKETS_SECS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Route label of requests no route matched
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Requests of one route and method
+#[derive(Debug, Default)]
+struct RouteMetrics {
+    /// Requests by status code
+    requests: BTreeMap<u16, u64>,
+    duration_buckets: [u64; DURATION_BUCKETS_SECS.len()],
+    duration_count: u64,
+    duration_sum_secs: f64,
+}
+
+/// Counters for requests handled by the public listener
+#[derive(Debug)]
+pub struct HttpMetrics {
+    requests_total: [AtomicU64; 5],
+    in_flight: AtomicI64,
+    started_at: Instant,
+    /// By method and route template
+    routes: Mutex<BTreeMap<(String, String), RouteMetrics>>,
+}
+
+impl Default for HttpMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpMetrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            requests_total: Default::default(),
+            in_flight: AtomicI64::new(0),
+            started_at: Instant::now(),
+            routes: Mutex::default(),
+        }
+    }
+
+    /// Record a completed request with the given status code
+    pub fn record(&self, status: u16) {
+        let class = usize::from(status / 100).clamp(1, 5) - 1;
+        self.requests_total[class].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a completed request to `route` (a route template, or
+    /// [`UNMATCHED_ROUTE`]) that took `duration` until the response started
+    pub fn record_route(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        let mut routes = self.routes.lock().unwrap_or_else(PoisonError::into_inner);
+        let metrics = routes
+            .entry((method.to_string(), route.to_string()))
+            .or_default();
+        *metrics.requests.entry(status).or_default() += 1;
+
+        let secs = duration.as_secs_f64();
+        for (bound, bucket) in DURATION_BUCKETS_SECS
+            .iter()
+            .zip(&mut metrics.duration_buckets)
+        {
+            if secs <= *bound {
+                *bucket += 1;
+            }
+        }
+        metrics.duration_count += 1;
+        metrics.duration_sum_secs += secs;
+        drop(routes);
+    }
+
+    /// Requests recorded for a route and method with a status code
+    #[must_use]
+    pub fn route_requests_total(&self, method: &str, route: &str, status: u16) -> u64 {
+        let routes = self.routes.lock().unwrap_or_else(PoisonError::into_inner);
+        routes
+            .get(&(method.to_string(), route.to_string()))
+            .and_then(|metrics| metrics.requests.get(&status).copied())
+            .unwrap_or(0)
+    }
+
+    /// Total requests recorded for a status class (`1`..=`5`)
+    #[must_use]
+    pub fn requests_total(&self, class: usize) -> u64 {
+        self.requests_total[class.clamp(1, 5) - 1].load(Ordering::Relaxed)
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP http_requests_total Total HTTP requests handled.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        for (class, counter) in STATUS_CLASSES.iter().zip(&self.requests_total) {
+            let _ = writeln!(
+                out,
+                "http_requests_total{{status=\"{class}\"}} {}",
+                counter.load(Ordering::Relaxed)
+            );
+        }
+
+        out.push_str("# HELP http_requests_in_flight HTTP requests currently being handled.\n");
+        out.push_str("# TYPE http_requests_in_flight gauge\n");
+        let _ = writeln!(
+            out,
+            "http_requests_in_flight {}",
+            self.in_flight.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP process_uptime_seconds Seconds since the server started.\n");
+        out.push_str("# TYPE process_uptime_seconds gauge\n");
+        let _ = writeln!(
+            out,
+            "process_uptime_seconds {}",
+            self.started_at.elapsed().as_secs()
+        );
+
+        self.render_routes(&mut out);
+        out
+    }
+
+    fn render_routes(&self, out: &mut String) {
+        let routes = self.routes.lock().unwrap_or_else(PoisonError::into_inner);
+
+        out.push_str(
+            "# HELP http_route_requests_total HTTP requests handled, by route, method and status code.\n",
+        );
+        out.push_str("# TYPE http_route_requests_total counter\n");
+        for ((method, route), metrics) in &*routes {
+            for (status, count) in &metrics.requests {
+                let _ = writeln!(
+                    out,
+                    "http_route_requests_total{{method=\"{method}\",route=\"{route}\",status=\"{status}\"}} {count}"
+                );
+            }
+        }
+
+        out.push_str(
+            "# HELP http_request_duration_seconds Time until the response started, by route and method.\n",
+        );
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        for ((method, route), metrics) in &*routes {
+            let labels = format!("method=\"{method}\",route=\"{route}\"");
+            for (bound, bucket) in DURATION_BUCKETS_SECS.iter().zip(&metrics.duration_buckets) {
+                let _ = writeln!(
+                    out,
+                    "http_request_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {bucket}"
+                );
+            }
+            let count = metrics.duration_count;
+            let _ = writeln!(
+                out,
+                "http_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {count}"
+            );
+            let _ = writeln!(
+                out,
+                "http_request_duration_seconds_sum{{{labels}}} {}",
+                metrics.duration_sum_secs
+            );
+            let _ = writeln!(
+                out,
+                "http_request_duration_seconds_count{{{labels}}} {count}"
+            );
+        }
+    }
+}
+
+/// Decrements the in-flight gauge even if the request future is dropped
+struct InFlightGuard<'a>(&'a AtomicI64);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Method label; methods outside the standard ones share `OTHER`, so
+/// clients cannot add series at will
+const fn method_label(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::HEAD => "HEAD",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::PATCH => "PATCH",
+        Method::DELETE => "DELETE",
+        Method::OPTIONS => "OPTIONS",
+        _ => "OTHER",
+    }
+}
+
+/// Axum middleware that records every request in [`HttpMetrics`].
+///
+/// Must be added with `Router::layer` so it runs after routing, when the
+/// route template is known.
+pub async fn track_metrics(
+    State(metrics): State<Arc<HttpMetrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    metrics.in_flight.fetch_add(1, Ordering::Relaxed);
+    let _guard = InFlightGuard(&metrics.in_flight);
+
+    let method = method_label(request.method());
+    let route = request.extensions().get::<MatchedPath>().map_or_else(
+        || UNMATCHED_ROUTE.to_string(),
+        |path| path.as_str().to_string(),
+    );
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+    let status = response.status().as_u16();
+    metrics.record(status);
+    metrics.record_route(method, &route, status, started.elapsed());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_record_groups_by_status_class() {
+        let metrics = HttpMetrics::new();
+        metrics.record(200);
+        metrics.record(204);
+        metrics.record(404);
+        metrics.record(503);
+
+        assert_eq!(metrics.requests_total(2), 2);
+        assert_eq!(metrics.requests_total(4), 1);
+        assert_eq!(metrics.requests_total(5), 1);
+        assert_eq!(metrics.requests_total(3), 0);
+    }
+
+    #[test]
+    fn test_render_prometheus_format() {
+        let metrics = HttpMetrics::new();
+        metrics.record(200);
+
+        let output = metrics.render();
+        assert!(output.contains("# TYPE http_requests_total counter"));
+        assert!(output.contains("http_requests_total{status=\"2xx\"} 1"));
+        assert!(output.contains("http_requests_total{status=\"5xx\"} 0"));
+        assert!(output.contains("http_requests_in_flight 0"));
+        assert!(output.contains("process_uptime_seconds "));
+    }
+
+    #[tokio::test]
+    async fn test_track_metrics_middleware() {
+        let metrics = Arc::new(HttpMetrics::new());
+        let app = Router::new().route("/ok", get(|| async { "ok" })).layer(
+            middleware::from_fn_with_state(Arc::clone(&metrics), track_metrics),
+        );
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/ok").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .oneshot(Request::get("/missing").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        assert_eq!(metrics.requests_total(2), 1);
+        assert_eq!(metrics.requests_total(4), 1);
+        assert_eq!(metrics.in_flight.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_track_metrics_labels_route_templates() {
+        let metrics = Arc::new(HttpMetrics::new());
+        let app = Router::new()
+            .route("/users/:id", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::clone(&metrics),
+                track_metrics,
+            ));
+
+        for path in ["/users/1", "/users/2", "/missing"] {
+            app.clone()
+                .oneshot(Request::get(path).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(metrics.route_requests_total("GET", "/users/:id", 200), 2);
+        assert_eq!(metrics.route_requests_total("GET", UNMATCHED_ROUTE, 404), 1);
+
+        let output = metrics.render();
+        assert!(output.contains(
+            "http_route_requests_total{method=\"GET\",route=\"/users/:id\",status=\"200\"} 2"
+        ));
+        assert!(output.contains(
+            "http_request_duration_seconds_count{method=\"GET\",route=\"/users/:id\"} 2"
+        ));
+        assert!(output.contains(
+            "http_request_duration_seconds_bucket{method=\"GET\",route=\"/users/:id\",le=\"+Inf\"} 2"
+        ));
+    }
+}
@@ -5,22 +5,40 @@
 //!
 //! # Modules
 //!
+//! - **access**: Route access declarations that wire auth middleware and `OpenAPI` security
+//! - **`access_log`**: Records API requests for compliance trails
 //! - **auth**: JWT authentication middleware that validates tokens
 //! - **admin**: Role-based authorization middleware for admin-only endpoints
+//! - **permission**: Per-route permission checks for custom roles
+//! - **`client_ip`**: Client IP extractor honoring trusted proxy headers
+//! - **`current_user`**: The authenticated user's row, read at most once per request
+//! - **`fault_injection`**: Simulated database timeouts (development only)
+//! - **`json_case`**: The API under `/api/v2` with `camelCase` JSON bodies
+//! - **`latency_budget`**: Warnings and counters for requests slower than their route's budget
 //! - **chat_rate_limit**: Rate limiting middleware for chat endpoints
+//! - **metrics**: Request counters exposed in Prometheus format
+//! - **`read_only`**: Rejects mutating requests while an admin has the API read-only
+//! - **`request_signing`**: HMAC signature checks for internal service calls
+//! - **timeout**: Per-route-group request deadlines with JSON error bodies
 //!
 //! # Middleware Chain
 //!
 //! Middleware is applied in layers with specific ordering requirements:
 //!
 //! 1. **`auth_middleware`** - First layer: validates JWT token, injects `AuthUser`
-//! 2. **`admin_middleware`** - Second layer: checks admin role (requires `auth_middleware`)
+//! 2. **`admin_middleware`** - Second layer: checks admin access (requires `auth_middleware`)
+//! 3. **`require_permission`** - Per route: checks the permission of the operation
+//!
+//! Application routes are mounted through [`access::SecuredRouter`], which
+//! applies this chain according to the access declared for each route.
 //!
 //! # Usage Example
 //!
 //! ```no_run
 //! use axum::{Router, routing::get, middleware};
-//! use cobalt_stack_backend::middleware::{auth::auth_middleware, admin::admin_middleware};
+//! use cobalt_stack_backend::middleware::{
+//!     admin::admin_middleware, auth::auth_middleware, current_user::UserLoader,
+//! };
 //! use cobalt_stack_backend::services::auth::JwtConfig;
 //! use sea_orm::DatabaseConnection;
 //! use std::sync::Arc;
@@ -39,13 +57,25 @@
 //! // Admin routes (admin users only)
 //! let admin_routes = Router::new()
 //!     .route("/admin/users", get(list_users))
-//!     .layer(middleware::from_fn_with_state(db, admin_middleware))
+//!     .layer(middleware::from_fn_with_state(UserLoader::new(db), admin_middleware))
 //!     .layer(middleware::from_fn_with_state(jwt_config, auth_middleware));
 //! # }
 //! # async fn get_profile() -> &'static str { "Profile" }
 //! # async fn list_users() -> &'static str { "Users" }
 //! ```
 
+pub mod access;
+pub mod access_log;
 pub mod admin;
 pub mod auth;
 pub mod chat_rate_limit;
+pub mod client_ip;
+pub mod current_user;
+pub mod fault_injection;
+pub mod json_case;
+pub mod latency_budget;
+pub mod metrics;
+pub mod permission;
+pub mod read_only;
+pub mod request_signing;
+pub mod timeout;
@@ -0,0 +1,87 @@
+//! Permission checks for individual routes.
+//!
+//! [`require_permission`] rejects requests of users who do not hold a
+//! permission (see [`crate::services::rbac`]) with `403 Forbidden`. Like
+//! [`admin_middleware`](super::admin::admin_middleware) it must run after
+//! `auth_middleware`; on admin routes it reuses the permissions the admin
+//! middleware already resolved.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use axum::{middleware::from_fn_with_state, routing::patch, Router};
+//! use cobalt_stack_backend::middleware::permission::{require_permission, RequiredPermission};
+//! use cobalt_stack_backend::services::rbac::{PermissionChecker, USERS_WRITE};
+//!
+//! # fn example(checker: PermissionChecker) -> Router {
+//! Router::new().route(
+//!     "/admin/users/:id/disable",
+//!     patch(|| async { "disabled" }).layer(from_fn_with_state(
+//!         RequiredPermission::new(checker, USERS_WRITE),
+//!         require_permission,
+//!     )),
+//! )
+//! # }
+//! ```
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::middleware::auth::AuthUser;
+use crate::services::rbac::{GrantedPermissions, PermissionChecker};
+
+/// Permission a route requires, with the checker resolving it
+#[derive(Clone)]
+pub struct RequiredPermission {
+    checker: PermissionChecker,
+    permission: &'static str,
+}
+
+impl RequiredPermission {
+    #[must_use]
+    pub const fn new(checker: PermissionChecker, permission: &'static str) -> Self {
+        Self {
+            checker,
+            permission,
+        }
+    }
+}
+
+/// Axum middleware that lets only users holding a permission through
+///
+/// # Returns
+///
+/// - `Err(StatusCode::UNAUTHORIZED)` - `AuthUser` missing or user not found
+/// - `Err(StatusCode::FORBIDDEN)` - The user does not hold the permission
+/// - `Err(StatusCode::INTERNAL_SERVER_ERROR)` - Database error
+pub async fn require_permission(
+    State(required): State<RequiredPermission>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let granted = if let Some(granted) = req.extensions().get::<GrantedPermissions>() {
+        granted.clone()
+    } else {
+        let auth_user = req
+            .extensions()
+            .get::<AuthUser>()
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        let granted = required
+            .checker
+            .granted_to(auth_user.user_id, auth_user.scope.as_ref())
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        req.extensions_mut().insert(granted.clone());
+        granted
+    };
+
+    if !granted.contains(required.permission) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(next.run(req).await)
+}
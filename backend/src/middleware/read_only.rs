@@ -0,0 +1,165 @@
+//! Read-only mode
+//!
+//! While an admin has the API in read-only mode (see
+//! [`crate::services::runtime_switches`]), mutating requests get a 503 with
+//! the admin's reason instead of reaching their handler. Reads still work,
+//! and so do the admin API (to turn the mode off again) and signing in and
+//! out. Chat WebSocket connections send messages, so new ones are refused
+//! too; connections opened before the switch stay up.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::services::runtime_switches::{RuntimeSwitches, REFRESH_INTERVAL};
+
+/// Paths that accept writes in read-only mode
+const EXEMPT_PREFIXES: &[&str] = &[
+    "/api/v1/admin/",
+    "/api/v1/auth/login",
+    "/api/v1/auth/refresh",
+    "/api/v1/auth/logout",
+];
+
+/// Reject mutating requests while the API is read-only
+pub async fn reject_writes(
+    State(switches): State<Arc<RuntimeSwitches>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let switches = switches.current();
+    if !switches.read_only || !is_write(&request) {
+        return next.run(request).await;
+    }
+
+    let reason = switches
+        .read_only_reason
+        .unwrap_or_else(|| "Maintenance in progress".to_string());
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, REFRESH_INTERVAL.as_secs().to_string())],
+        Json(json!({
+            "error": "The API is read-only",
+            "reason": reason,
+        })),
+    )
+        .into_response()
+}
+
+fn is_write(request: &Request) -> bool {
+    let path = request.uri().path();
+    if EXEMPT_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return false;
+    }
+
+    let safe = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    !safe || request.headers().contains_key(header::UPGRADE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::runtime_switches::Switches;
+    use axum::{
+        body::Body,
+        middleware,
+        routing::{get, post, put},
+        Router,
+    };
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn app(read_only: bool) -> Router {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+        let switches = Arc::new(RuntimeSwitches::new(Arc::new(db)));
+        let state = Switches {
+            read_only,
+            read_only_reason: Some("Database upgrade".to_string()),
+            ..Switches::default()
+        };
+        switches.update(state, Uuid::new_v4()).await.unwrap();
+
+        Router::new()
+            .route(
+                "/api/v1/users/me",
+                get(|| async { "ok" }).patch(|| async { "ok" }),
+            )
+            .route("/api/v1/auth/login", post(|| async { "ok" }))
+            .route("/api/v1/admin/system/switches", put(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(switches, reject_writes))
+    }
+
+    async fn status(app: Router, method: Method, path: &str) -> StatusCode {
+        let request = Request::builder()
+            .method(method)
+            .uri(path)
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_read_only_rejects_writes() {
+        let app = app(true).await;
+
+        assert_eq!(
+            status(app.clone(), Method::PATCH, "/api/v1/users/me").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            status(app.clone(), Method::GET, "/api/v1/users/me").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app.clone(), Method::POST, "/api/v1/auth/login").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app, Method::PUT, "/api/v1/admin/system/switches").await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_only_response_carries_reason() {
+        let request = Request::builder()
+            .method(Method::PATCH)
+            .uri("/api/v1/users/me")
+            .body(Body::empty())
+            .unwrap();
+        let response = app(true).await.oneshot(request).await.unwrap();
+
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["reason"], "Database upgrade");
+    }
+
+    #[tokio::test]
+    async fn test_writable_api_accepts_writes() {
+        assert_eq!(
+            status(app(false).await, Method::PATCH, "/api/v1/users/me").await,
+            StatusCode::OK
+        );
+    }
+}
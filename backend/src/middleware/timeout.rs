@@ -0,0 +1,135 @@
+//! Request timeout middleware with the unified JSON error body.
+//!
+//! Each route group gets its own [`TimeoutLayer`] built by [`request_timeout`]
+//! with a deadline from [`RequestTimeoutConfig`], so long chat completions are
+//! not cut off by the short deadline used for auth. Layers must not be nested:
+//! the outermost (shortest) deadline would always win.
+//!
+//! `TimeoutLayer` answers with an empty body; [`timeout_error_body`] rewrites
+//! those responses into [`ErrorResponse`] so clients can parse every error the
+//! same way.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use axum::{middleware, routing::get, Router};
+//! use cobalt_stack_backend::config::RequestTimeoutConfig;
+//! use cobalt_stack_backend::middleware::timeout::{request_timeout, timeout_error_body};
+//!
+//! let config = RequestTimeoutConfig::from_env();
+//! let app: Router = Router::new()
+//!     .route("/slow", get(|| async { "ok" }))
+//!     .layer(request_timeout(&config, config.default))
+//!     .layer(middleware::map_response(timeout_error_body));
+//! ```
+
+use crate::config::RequestTimeoutConfig;
+use crate::dto::ErrorResponse;
+use axum::{
+    body::HttpBody,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::time::Duration;
+use tower_http::timeout::TimeoutLayer;
+
+/// Build a timeout layer for one route group.
+#[must_use]
+pub fn request_timeout(config: &RequestTimeoutConfig, timeout: Duration) -> TimeoutLayer {
+    TimeoutLayer::with_status_code(config.status, timeout)
+}
+
+/// Replace the empty body of timeout responses with [`ErrorResponse`].
+///
+/// Handlers that return 408/504 with their own body are left untouched.
+#[allow(clippy::unused_async)]
+pub async fn timeout_error_body(response: Response) -> Response {
+    let status = response.status();
+    let is_timeout = status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::GATEWAY_TIMEOUT;
+
+    if is_timeout && response.body().size_hint().exact() == Some(0) {
+        return (
+            status,
+            Json(ErrorResponse {
+                error: "Request timed out".to_string(),
+            }),
+        )
+            .into_response();
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+        middleware,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    fn app(status: StatusCode) -> Router {
+        let config = RequestTimeoutConfig {
+            status,
+            ..RequestTimeoutConfig::default()
+        };
+        Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(request_timeout(&config, Duration::from_millis(20)))
+            .layer(middleware::map_response(timeout_error_body))
+    }
+
+    async fn get_path(app: Router, path: &str) -> (StatusCode, String) {
+        let response = app
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_timeout_returns_unified_error_body() {
+        let (status, body) = get_path(app(StatusCode::GATEWAY_TIMEOUT), "/slow").await;
+
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        let error: ErrorResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(error.error, "Request timed out");
+    }
+
+    #[tokio::test]
+    async fn test_timeout_status_is_configurable() {
+        let (status, _) = get_path(app(StatusCode::REQUEST_TIMEOUT), "/slow").await;
+        assert_eq!(status, StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_fast_request_unaffected() {
+        let (status, body) = get_path(app(StatusCode::GATEWAY_TIMEOUT), "/fast").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "done");
+    }
+
+    #[tokio::test]
+    async fn test_handler_timeout_body_preserved() {
+        let response = (StatusCode::GATEWAY_TIMEOUT, "upstream slow").into_response();
+
+        let response = timeout_error_body(response).await;
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"upstream slow");
+    }
+}
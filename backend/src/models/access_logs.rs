@@ -0,0 +1,52 @@
+//! Persisted API access records.
+//!
+//! This module defines the `AccessLogs` entity, one row per API request
+//! recorded by the access log middleware (see
+//! [`crate::services::access_log`]) when access log persistence is enabled
+//! with the database sink. Rows are deleted after the retention period.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `access_logs`
+//! - **Primary Key**: `id` (UUID)
+//! - **Indexes**: `created_at`, `(user_id, created_at)`
+//! - **Foreign Keys**: none; records outlive the users they mention
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Access log entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "access_logs")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// HTTP method.
+    pub method: String,
+
+    /// Request path, without the query string.
+    pub path: String,
+
+    /// Response status code.
+    pub status: i16,
+
+    /// Milliseconds until the response headers were sent.
+    pub latency_ms: i32,
+
+    /// Authenticated user (`None` for anonymous or invalid credentials).
+    pub user_id: Option<Uuid>,
+
+    /// Client address, resolved through trusted proxies.
+    pub ip: Option<String>,
+
+    /// When the request arrived.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Access log records have no relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,44 @@
+//! Precomputed admin statistics.
+//!
+//! This module defines the `AdminStats` entity which stores the user counts
+//! shown on the admin dashboard. A scheduled job recounts them, so
+//! `/admin/stats` reads one row instead of counting users on every view.
+//! The table holds at most one row (`id = 1`), created by the first refresh.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `admin_stats`
+//! - **Primary Key**: `id` (always `1`)
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Admin statistics entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "admin_stats")]
+pub struct Model {
+    /// Row identifier (always `1`).
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i32,
+
+    /// Number of user accounts.
+    pub total_users: i64,
+
+    /// Users with a verified email.
+    pub verified_users: i64,
+
+    /// Users with the admin role.
+    pub admin_users: i64,
+
+    /// Disabled user accounts.
+    pub disabled_users: i64,
+
+    /// When the counts were taken.
+    pub refreshed_at: DateTimeWithTimeZone,
+}
+
+/// Admin statistics have no relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
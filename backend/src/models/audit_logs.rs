@@ -0,0 +1,54 @@
+//! Audit trail of security-sensitive events.
+//!
+//! This module defines the `AuditLogs` entity, one row per sign-in,
+//! sign-out, password change or admin change to an account, written by
+//! [`crate::services::audit`].
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `audit_logs`
+//! - **Primary Key**: `id` (UUID)
+//! - **Indexes**: `created_at`, `(event, created_at)`, `(actor_id, created_at)`,
+//!   `(target_id, created_at)`
+//! - **Foreign Keys**: none; entries outlive the users they mention
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Audit log entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_logs")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Event name, e.g. `auth.login` (see [`crate::services::audit::AuditEvent`]).
+    pub event: String,
+
+    /// User who did it (`None` when unknown, e.g. a failed login).
+    pub actor_id: Option<Uuid>,
+
+    /// User it was done to, if not the actor.
+    pub target_id: Option<Uuid>,
+
+    /// Client address, resolved through trusted proxies.
+    pub ip: Option<String>,
+
+    /// Client User-Agent, truncated.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub user_agent: Option<String>,
+
+    /// Event-specific fields.
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub details: Option<Json>,
+
+    /// When the event happened.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Audit log entries have no relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,70 @@
+//! Runtime branding overrides.
+//!
+//! This module defines the `BrandingSettings` entity which stores the
+//! branding an admin changed at runtime. The table holds at most one row
+//! (`id = 1`); `NULL` columns fall back to the `BRANDING_*` environment
+//! defaults.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `branding_settings`
+//! - **Primary Key**: `id` (always `1`)
+//! - **Foreign Key**: `updated_by` → `users.id` (SET NULL)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `Users`: Admin who last changed the branding
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Branding settings entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "branding_settings")]
+pub struct Model {
+    /// Row identifier (always `1`).
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i32,
+
+    /// Product name shown in the UI and emails.
+    pub product_name: Option<String>,
+
+    /// Absolute or site-relative logo URL.
+    pub logo_url: Option<String>,
+
+    /// Address users are told to contact for help.
+    pub support_email: Option<String>,
+
+    /// Primary theme color (`#rrggbb`).
+    pub primary_color: Option<String>,
+
+    /// Accent theme color (`#rrggbb`).
+    pub accent_color: Option<String>,
+
+    /// Admin who last changed the branding.
+    pub updated_by: Option<Uuid>,
+
+    /// Timestamp of the last change.
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `BrandingSettings` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Settings were last changed by a user.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UpdatedBy",
+        to = "super::users::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,79 @@
+//! Prompts of batch completion jobs and their results.
+//!
+//! This module defines the `ChatJobItems` entity, one row per prompt of a
+//! [`super::chat_jobs`] job. A worker fills in the output (or the error) and
+//! the token counts once the prompt has been answered.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `chat_job_items`
+//! - **Primary Key**: `id` (UUID)
+//! - **Unique**: (`job_id`, `position`)
+//! - **Foreign Keys**: `job_id` → `chat_jobs.id` (CASCADE)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `ChatJobs`: Job the prompt is part of
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Chat job item entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_job_items")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Job the prompt is part of.
+    pub job_id: Uuid,
+
+    /// Index of the prompt in the submitted list (0-based).
+    pub position: i32,
+
+    /// Prompt text, sent as a single user message.
+    #[sea_orm(column_type = "Text")]
+    pub prompt: String,
+
+    /// `pending`, `completed` or `failed`.
+    pub status: String,
+
+    /// Model reply once completed.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub output: Option<String>,
+
+    /// Provider error once failed.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error: Option<String>,
+
+    /// Tokens of the prompt.
+    pub prompt_tokens: Option<i32>,
+
+    /// Tokens of the reply.
+    pub completion_tokens: Option<i32>,
+
+    /// Timestamp when the prompt was answered or failed.
+    pub finished_at: Option<DateTimeWithTimeZone>,
+}
+
+/// Entity relations for the `ChatJobItems` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Item belongs to a job.
+    #[sea_orm(
+        belongs_to = "super::chat_jobs::Entity",
+        from = "Column::JobId",
+        to = "super::chat_jobs::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ChatJobs,
+}
+
+impl Related<super::chat_jobs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChatJobs.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
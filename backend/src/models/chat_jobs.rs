@@ -0,0 +1,79 @@
+//! Batch completion jobs.
+//!
+//! This module defines the `ChatJobs` entity: a batch of prompts a user
+//! submitted for one model, processed by background workers (see
+//! [`crate::application::chat::batch_jobs`]). Progress counters and token
+//! totals are updated as items finish, so polling a job needs no aggregate.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `chat_jobs`
+//! - **Primary Key**: `id` (UUID)
+//! - **Index**: `(status, created_at)`
+//! - **Foreign Keys**: `user_id` → `users.id` (CASCADE)
+//!
+//! # Relations
+//!
+//! - `has_many` `ChatJobItems`: Prompts of the job
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Chat job entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_jobs")]
+#[allow(clippy::struct_field_names)] // `model` is the column name
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// User who submitted the job.
+    pub user_id: Uuid,
+
+    /// Model every prompt is sent to.
+    pub model: String,
+
+    /// `queued`, `running`, `completed` or `failed`.
+    pub status: String,
+
+    /// Number of prompts.
+    pub total_items: i32,
+
+    /// Prompts answered so far.
+    pub completed_items: i32,
+
+    /// Prompts that failed so far.
+    pub failed_items: i32,
+
+    /// Prompt tokens of the answered prompts.
+    pub prompt_tokens: i64,
+
+    /// Completion tokens of the answered prompts.
+    pub completion_tokens: i64,
+
+    /// Timestamp when the job was submitted.
+    pub created_at: DateTimeWithTimeZone,
+
+    /// Timestamp when a worker picked the job up.
+    pub started_at: Option<DateTimeWithTimeZone>,
+
+    /// Timestamp when the last prompt finished.
+    pub finished_at: Option<DateTimeWithTimeZone>,
+}
+
+/// Entity relations for the `ChatJobs` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Job has many items.
+    #[sea_orm(has_many = "super::chat_job_items::Entity")]
+    ChatJobItems,
+}
+
+impl Related<super::chat_job_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChatJobItems.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -19,6 +19,11 @@
 //! - `user`: Message from the human user
 //! - `assistant`: Response from the AI assistant
 //! - `system`: System message for behavior control
+//!
+//! # Redaction
+//!
+//! A message deleted under the `redact` policy keeps its row with the content
+//! replaced and `redacted_at` set; it is no longer sent to the LLM.
 
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -52,6 +57,10 @@ pub struct Model {
 
     /// Timestamp when the message was created.
     pub created_at: DateTimeWithTimeZone,
+
+    /// Timestamp when the message content was redacted.
+    /// If set, the content is a placeholder.
+    pub redacted_at: Option<DateTimeWithTimeZone>,
 }
 
 /// Entity relations for the ChatMessage model.
@@ -0,0 +1,80 @@
+//! Per-user read position in a chat session.
+//!
+//! This module defines the `ChatReadState` entity which records the last
+//! message a user has read in a session, used to compute unread counts.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `chat_read_states`
+//! - **Primary Key**: (`user_id`, `session_id`)
+//! - **Foreign Keys**: `user_id` → `users.id`, `session_id` → `chat_sessions.id`,
+//!   `last_read_message_id` → `chat_messages.id` (all CASCADE)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `ChatSessions`: Session being read
+//! - `belongs_to` `ChatMessages`: Last message read
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Chat read state entity.
+///
+/// Messages created after `last_read_at` are unread for this user.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_read_states")]
+pub struct Model {
+    /// Reader.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+
+    /// Session being read.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub session_id: Uuid,
+
+    /// Most recent message the user has read.
+    pub last_read_message_id: Uuid,
+
+    /// Creation time of `last_read_message_id`.
+    /// Denormalized so unread counts need no join.
+    pub last_read_at: DateTimeWithTimeZone,
+
+    /// Timestamp when the read position last moved.
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `ChatReadState` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Read state belongs to a session.
+    #[sea_orm(
+        belongs_to = "super::chat_sessions::Entity",
+        from = "Column::SessionId",
+        to = "super::chat_sessions::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ChatSessions,
+
+    /// Read state points at a message.
+    #[sea_orm(
+        belongs_to = "super::chat_messages::Entity",
+        from = "Column::LastReadMessageId",
+        to = "super::chat_messages::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ChatMessages,
+}
+
+impl Related<super::chat_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChatSessions.def()
+    }
+}
+
+impl Related<super::chat_messages::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChatMessages.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -18,6 +18,17 @@
 //!
 //! Sessions use soft delete pattern with `deleted_at` timestamp.
 //! Deleted sessions are filtered out in queries but remain in database.
+//!
+//! # Archival
+//!
+//! Sessions of a user who stays disabled can be archived (`archived_at`).
+//! Archived sessions are hidden from the owner's list until the account is
+//! enabled again, which clears the timestamp.
+//!
+//! # Optimistic Locking
+//!
+//! `version` is incremented by every update. Updates name the version they
+//! were based on and are rejected if the row has moved on since.
 
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -49,6 +60,14 @@ pub struct Model {
     /// Timestamp when the session was soft deleted.
     /// If set, session is considered deleted.
     pub deleted_at: Option<DateTimeWithTimeZone>,
+    /// Timestamp when the session was archived because its owner's account
+    /// stayed disabled.
+    pub archived_at: Option<DateTimeWithTimeZone>,
+
+    /// Number of updates so far, for optimistic locking.
+    /// Missing from backups taken before the column existed.
+    #[serde(default)]
+    pub version: i32,
 }
 
 /// Entity relations for the ChatSession model.
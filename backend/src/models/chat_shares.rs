@@ -0,0 +1,80 @@
+//! Public read-only share links for chat sessions.
+//!
+//! This module defines the `ChatShare` entity which records a link that lets
+//! anyone holding its slug view a session without authenticating.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `chat_shares`
+//! - **Primary Key**: `id` (UUID)
+//! - **Unique**: `slug`
+//! - **Foreign Keys**: `session_id` → `chat_sessions.id`, `user_id` → `users.id`
+//!   (both CASCADE)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `ChatSessions`: Shared session
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Chat share entity.
+///
+/// A share is viewable while it is neither revoked, suspended nor past
+/// `expires_at`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_shares")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Shared session.
+    pub session_id: Uuid,
+
+    /// Session owner who created the share.
+    pub user_id: Uuid,
+
+    /// Signed public identifier used in the share URL.
+    #[sea_orm(unique)]
+    pub slug: String,
+
+    /// Argon2 hash of the optional viewing password.
+    pub password_hash: Option<String>,
+
+    /// Time after which the share stops working (never if `None`).
+    pub expires_at: Option<DateTimeWithTimeZone>,
+
+    /// Time the owner revoked the share.
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+    /// Time the share was suspended because the owner's account was disabled.
+    /// Cleared when the account is enabled again.
+    pub suspended_at: Option<DateTimeWithTimeZone>,
+
+    /// Number of successful public views.
+    pub view_count: i64,
+
+    /// Timestamp when the share was created.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `ChatShare` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Share belongs to a session.
+    #[sea_orm(
+        belongs_to = "super::chat_sessions::Entity",
+        from = "Column::SessionId",
+        to = "super::chat_sessions::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ChatSessions,
+}
+
+impl Related<super::chat_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChatSessions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
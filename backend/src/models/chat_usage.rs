@@ -0,0 +1,72 @@
+//! Per-reply chat usage records.
+//!
+//! This module defines the `ChatUsage` entity, one row per assistant reply,
+//! which feeds the user's chat analytics (messages per day, tokens per model,
+//! most active sessions, response latency).
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `chat_usage`
+//! - **Primary Key**: `id` (UUID)
+//! - **Index**: `(user_id, created_at)`
+//! - **Foreign Keys**: `user_id` → `users.id`, `session_id` → `chat_sessions.id`
+//!   (both CASCADE)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `ChatSessions`: Session the reply belongs to
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Chat usage entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_usage")]
+#[allow(clippy::struct_field_names)] // `model` is the column name
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// User who sent the message.
+    pub user_id: Uuid,
+
+    /// Session the reply belongs to.
+    pub session_id: Uuid,
+
+    /// Model that generated the reply.
+    pub model: String,
+
+    /// Tokens of the context sent to the model (messages with a known count).
+    pub prompt_tokens: i32,
+
+    /// Tokens of the reply.
+    pub completion_tokens: i32,
+
+    /// Milliseconds from the request to the first reply chunk.
+    pub latency_ms: i32,
+
+    /// Timestamp of the reply.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `ChatUsage` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Usage belongs to a session.
+    #[sea_orm(
+        belongs_to = "super::chat_sessions::Entity",
+        from = "Column::SessionId",
+        to = "super::chat_sessions::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ChatSessions,
+}
+
+impl Related<super::chat_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChatSessions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
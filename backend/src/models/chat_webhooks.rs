@@ -0,0 +1,68 @@
+//! Completion webhooks of chat users.
+//!
+//! This module defines the `ChatWebhook` entity which records a URL called
+//! with a signed payload whenever one of the user's replies completes.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `chat_webhooks`
+//! - **Primary Key**: `id` (UUID)
+//! - **Foreign Keys**: `user_id` → `users.id` (CASCADE)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `Users`: Owner of the webhook
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Chat webhook entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_webhooks")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// User whose replies trigger the webhook.
+    pub user_id: Uuid,
+
+    /// URL the payloads are posted to.
+    pub url: String,
+
+    /// Key of the HMAC-SHA256 signature of each delivery.
+    pub secret: String,
+
+    /// Timestamp when the webhook was created.
+    pub created_at: DateTimeWithTimeZone,
+
+    /// Time of the last delivery attempt.
+    pub last_delivery_at: Option<DateTimeWithTimeZone>,
+
+    /// HTTP status of the last attempt (`None` without a response).
+    pub last_status: Option<i32>,
+
+    /// Why the last attempt failed, if it did.
+    pub last_error: Option<String>,
+}
+
+/// Entity relations for the `ChatWebhook` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Webhook belongs to a user.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,68 @@
+//! Weekly email digest opt-in per user.
+//!
+//! This module defines the `EmailDigestSubscription` entity which stores
+//! whether a user receives the activity digest and the token used by the
+//! one-click unsubscribe link.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `email_digest_subscriptions`
+//! - **Primary Key**: `user_id`
+//! - **Unique**: `unsubscribe_token`
+//! - **Foreign Key**: `user_id` → `users.id` (CASCADE)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `Users`: Subscriber
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Email digest subscription entity.
+///
+/// Rows are kept after unsubscribing (`enabled = false`) so the token in
+/// previously sent emails keeps working.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "email_digest_subscriptions")]
+pub struct Model {
+    /// Subscriber.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+
+    /// Whether digests are sent.
+    pub enabled: bool,
+
+    /// Random token embedded in unsubscribe links.
+    #[sea_orm(unique)]
+    pub unsubscribe_token: String,
+
+    /// Time the last digest was sent (or skipped for lack of activity).
+    pub last_sent_at: Option<DateTimeWithTimeZone>,
+
+    /// Timestamp when the subscription was created.
+    pub created_at: DateTimeWithTimeZone,
+
+    /// Timestamp when the preference last changed.
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `EmailDigestSubscription` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Subscription belongs to a user.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,52 @@
+//! Outbound email history.
+//!
+//! This module defines the `EmailLog` entity: one row per email handed to
+//! the mail provider, written by the delivery log worker (see
+//! [`crate::services::email::delivery_log`]).
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `email_log`
+//! - **Primary Key**: `id` (UUID)
+//! - **Indexes**: `(recipient, created_at)`, `message_id`
+//! - **Foreign Keys**: none; the history of an address outlives the account
+//!   that used it
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Email log entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "email_log")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Kind of email: `verification`, `transactional` or `bulk`.
+    pub kind: String,
+
+    /// Recipient address, lowercase.
+    pub recipient: String,
+
+    /// Template the email was rendered from, if any.
+    pub template: Option<String>,
+
+    /// Message id assigned by the provider, if it reports one.
+    pub message_id: Option<String>,
+
+    /// Delivery outcome: `sent` or `failed`.
+    pub status: String,
+
+    /// Why delivery failed.
+    pub error: Option<String>,
+
+    /// When the email was handed to the provider.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Email log entries have no relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,51 @@
+//! Suppressed email addresses.
+//!
+//! This module defines the `EmailSuppressions` entity: addresses the mail
+//! provider reported as permanently bouncing, or whose recipient marked a
+//! message as spam. Nothing is sent to them until an admin lifts the
+//! suppression (see [`crate::services::email::suppression`]).
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `email_suppressions`
+//! - **Primary Key**: `id` (UUID)
+//! - **Unique Constraints**: `email`
+//! - **Foreign Keys**: none; a suppression outlives the account that used
+//!   the address
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Email suppression entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "email_suppressions")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Suppressed address, lowercase.
+    #[sea_orm(unique)]
+    pub email: String,
+
+    /// Why the address is suppressed: `bounce` or `complaint`.
+    pub reason: String,
+
+    /// Diagnostic reported by the provider (bounce code, feedback type).
+    pub detail: Option<String>,
+
+    /// Reports received for the address.
+    pub reports: i32,
+
+    /// When the latest report arrived.
+    pub last_reported_at: DateTimeWithTimeZone,
+
+    /// When the address was first suppressed.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Email suppressions have no relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -76,6 +76,10 @@ pub struct Model {
 
     /// When the verification token was created.
     pub created_at: DateTimeWithTimeZone,
+
+    /// Client the verification link was built for (e.g. `ios`).
+    /// `None` for the web link; resends build the same kind of link.
+    pub client: Option<String>,
 }
 
 /// Entity relations for the `EmailVerification` model.
@@ -0,0 +1,67 @@
+//! Guest account entity for anonymous chat access.
+//!
+//! This module defines the `GuestAccount` entity. In guest mode, a visitor
+//! receives a short-lived access token for a new user without password or
+//! real email address; the record marks that user as a guest and holds its
+//! limits (see [`crate::services::auth::guest`]).
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `guest_accounts`
+//! - **Primary Key**: `user_id` (UUID)
+//! - **Foreign Key**: `user_id` → `users.id` (CASCADE on delete)
+//!
+//! # Lifecycle
+//!
+//! 1. A visitor requests a guest token: the user and this record are created
+//! 2. The guest chats until `messages_sent` reaches the limit
+//! 3. After registering, the visitor claims the guest: its chat sessions move
+//!    to the new account and the guest user is deleted
+//! 4. Guests not claimed by `expires_at` are deleted with their sessions
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Guest account entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "guest_accounts")]
+pub struct Model {
+    /// The guest user.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+
+    /// Client IP address the guest was created from.
+    pub ip: Option<String>,
+
+    /// Chat messages sent so far.
+    pub messages_sent: i32,
+
+    /// When the guest token expires; the guest is deleted after this.
+    pub expires_at: DateTimeWithTimeZone,
+
+    /// When the guest was created.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `GuestAccount` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// `GuestAccount` belongs to a User.
+    /// Cascades on delete: deleting the user removes the record.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
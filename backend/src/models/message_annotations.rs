@@ -0,0 +1,84 @@
+//! User annotations on chat messages.
+//!
+//! This module defines the `MessageAnnotations` entity: bookmarks, labels
+//! and emoji reactions a user attaches to messages in their sessions.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `message_annotations`
+//! - **Primary Key**: `id` (UUID)
+//! - **Unique**: (`message_id`, `user_id`, `kind`, `value`)
+//! - **Foreign Keys**: `message_id` → `chat_messages.id`, `session_id` →
+//!   `chat_sessions.id`, `user_id` → `users.id` (all CASCADE)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `ChatMessages`: Annotated message
+//! - `belongs_to` `ChatSessions`: Session of the message
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Message annotation entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "message_annotations")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Annotated message.
+    pub message_id: Uuid,
+
+    /// Session of the message.
+    /// Denormalized so a session's annotations need no join.
+    pub session_id: Uuid,
+
+    /// User who added the annotation.
+    pub user_id: Uuid,
+
+    /// `bookmark`, `label` or `reaction`.
+    pub kind: String,
+
+    /// Label text or emoji; empty for bookmarks.
+    pub value: String,
+
+    /// Timestamp when the annotation was added.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `MessageAnnotations` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Annotation belongs to a message.
+    #[sea_orm(
+        belongs_to = "super::chat_messages::Entity",
+        from = "Column::MessageId",
+        to = "super::chat_messages::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ChatMessages,
+
+    /// Annotation belongs to a session.
+    #[sea_orm(
+        belongs_to = "super::chat_sessions::Entity",
+        from = "Column::SessionId",
+        to = "super::chat_sessions::Column::Id",
+        on_delete = "Cascade"
+    )]
+    ChatSessions,
+}
+
+impl Related<super::chat_messages::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChatMessages.def()
+    }
+}
+
+impl Related<super::chat_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChatSessions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -17,7 +17,15 @@
 //! - **users**: User accounts with authentication credentials
 //! - **`refresh_tokens`**: JWT refresh tokens for token rotation
 //! - **`email_verifications`**: Email verification tokens and status
+//! - **`email_log`**: Outbound emails and their delivery outcome
+//! - **`email_suppressions`**: Addresses never emailed again after bounces or complaints
+//! - **`guest_accounts`**: Anonymous accounts of the guest mode
+//! - **`sandbox_accounts`**: Accounts of the sandbox mode, deleted once expired
 //! - **`o_auth_accounts`**: OAuth provider account linkages
+//! - **`trusted_devices`**: Devices confirmed by email for long-lived sessions
+//! - **`admin_stats`**: User counts of the admin dashboard, refreshed by a scheduled job
+//! - **`roles`**, **`permissions`**, **`role_permissions`**, **`user_roles`**: Custom roles,
+//!   the permissions they grant and the users holding them
 //!
 //! # Entity Relations
 //!
@@ -25,6 +33,10 @@
 //! Users (1) ──< (N) RefreshTokens
 //!       (1) ──< (N) EmailVerifications
 //!       (1) ──< (N) OAuthAccounts
+//!       (1) ──< (N) TrustedDevices
+//!       (1) ──  (1) GuestAccounts
+//!       (1) ──  (1) SandboxAccounts
+//!       (1) ──< (N) UserRoles >── (1) Roles (1) ──< (N) RolePermissions
 //! ```
 //!
 //! # Examples
@@ -54,10 +66,34 @@
 
 pub mod prelude;
 
+pub mod access_logs;
+pub mod admin_stats;
+pub mod audit_logs;
+pub mod branding_settings;
+pub mod chat_job_items;
+pub mod chat_jobs;
 pub mod chat_messages;
+pub mod chat_read_states;
 pub mod chat_sessions;
+pub mod chat_shares;
+pub mod chat_usage;
+pub mod chat_webhooks;
+pub mod email_digest_subscriptions;
+pub mod email_log;
+pub mod email_suppressions;
 pub mod email_verifications;
+pub mod guest_accounts;
+pub mod message_annotations;
 pub mod o_auth_accounts;
+pub mod permissions;
 pub mod refresh_tokens;
+pub mod role_permissions;
+pub mod roles;
+pub mod runtime_switches;
+pub mod sandbox_accounts;
+pub mod scheduled_reports;
 pub mod sea_orm_active_enums;
+pub mod trusted_devices;
+pub mod user_preferences;
+pub mod user_roles;
 pub mod users;
@@ -0,0 +1,41 @@
+//! Permission entity for fine-grained authorization.
+//!
+//! This module defines the `Permission` entity: the catalog of permissions
+//! known to the backend ([`crate::services::rbac::PERMISSIONS`]), written at
+//! startup so roles can reference them.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `permissions`
+//! - **Primary Key**: `name` (e.g. `users:write`)
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Permission entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "permissions")]
+pub struct Model {
+    /// Permission name, `resource:action`.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+
+    /// What the permission allows.
+    pub description: String,
+}
+
+/// Entity relations for the `Permission` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Roles granting the permission.
+    #[sea_orm(has_many = "super::role_permissions::Entity")]
+    RolePermissions,
+}
+
+impl Related<super::role_permissions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RolePermissions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
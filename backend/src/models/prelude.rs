@@ -17,7 +17,32 @@
 //! # }
 //! ```
 
+pub use super::access_logs::Entity as AccessLogs;
+pub use super::admin_stats::Entity as AdminStats;
+pub use super::audit_logs::Entity as AuditLogs;
+pub use super::branding_settings::Entity as BrandingSettings;
+pub use super::chat_job_items::Entity as ChatJobItems;
+pub use super::chat_jobs::Entity as ChatJobs;
 pub use super::chat_messages::Entity as ChatMessages;
+pub use super::chat_read_states::Entity as ChatReadStates;
 pub use super::chat_sessions::Entity as ChatSessions;
+pub use super::chat_shares::Entity as ChatShares;
+pub use super::chat_usage::Entity as ChatUsage;
+pub use super::chat_webhooks::Entity as ChatWebhooks;
+pub use super::email_digest_subscriptions::Entity as EmailDigestSubscriptions;
+pub use super::email_log::Entity as EmailLog;
+pub use super::email_suppressions::Entity as EmailSuppressions;
+pub use super::guest_accounts::Entity as GuestAccounts;
+pub use super::message_annotations::Entity as MessageAnnotations;
+pub use super::o_auth_accounts::Entity as OAuthAccounts;
+pub use super::permissions::Entity as Permissions;
 pub use super::refresh_tokens::Entity as RefreshTokens;
+pub use super::role_permissions::Entity as RolePermissions;
+pub use super::roles::Entity as Roles;
+pub use super::runtime_switches::Entity as RuntimeSwitches;
+pub use super::sandbox_accounts::Entity as SandboxAccounts;
+pub use super::scheduled_reports::Entity as ScheduledReports;
+pub use super::trusted_devices::Entity as TrustedDevices;
+pub use super::user_preferences::Entity as UserPreferences;
+pub use super::user_roles::Entity as UserRoles;
 pub use super::users::Entity as Users;
@@ -10,6 +10,7 @@
 //! - **Primary Key**: `id` (UUID, matches JWT jti claim)
 //! - **Unique Constraints**: `token_hash`
 //! - **Foreign Key**: `user_id` → `users.id` (CASCADE on delete)
+//! - **Index**: `family_id`
 //!
 //! # Security
 //!
@@ -18,23 +19,30 @@
 //! - Tokens can be revoked individually via `revoked_at`
 //! - Expired tokens are cleaned up periodically
 //!
+//! The id columns are plain UUIDs, since the domain's typed ids carry no
+//! `SeaORM` integration; [`Model::jti`], [`Model::owner`] and
+//! [`Model::family`] read them as typed ids.
+//!
 //! # Token Rotation
 //!
 //! 1. User requests token refresh with old token
 //! 2. Old token is validated and revoked
-//! 3. New token pair is generated and stored
+//! 3. New token pair is generated and stored in the old token's family
 //! 4. Old token becomes unusable immediately
 //!
+//! Presenting a rotated token again revokes its whole family.
+//!
 //! # Examples
 //!
 //! ```no_run
-//! use cobalt_stack::models::{refresh_tokens, prelude::*};
+//! use cobalt_stack_backend::domain::ids::UserId;
+//! use cobalt_stack_backend::models::{refresh_tokens, prelude::*};
 //! use sea_orm::*;
 //!
-//! # async fn example(db: &DatabaseConnection, user_id: uuid::Uuid) -> Result<(), DbErr> {
+//! # async fn example(db: &DatabaseConnection, user_id: UserId) -> Result<(), DbErr> {
 //! // Find all active tokens for a user
 //! let tokens = RefreshTokens::find()
-//!     .filter(refresh_tokens::Column::UserId.eq(user_id))
+//!     .filter(refresh_tokens::Column::UserId.eq(user_id.into_uuid()))
 //!     .filter(refresh_tokens::Column::RevokedAt.is_null())
 //!     .all(db)
 //!     .await?;
@@ -42,6 +50,7 @@
 //! # }
 //! ```
 
+use crate::domain::ids::{TokenId, UserId};
 use sea_orm::entity::prelude::*;
 
 /// Refresh token entity for JWT token rotation.
@@ -59,6 +68,11 @@ pub struct Model {
     /// Foreign key to the user who owns this token.
     pub user_id: Uuid,
 
+    /// Token family: the jti of the token the session signed in with.
+    /// Carried over when the token is rotated, so a reused token can
+    /// revoke every token descended from the same sign-in.
+    pub family_id: Uuid,
+
     /// SHA-256 hash of the refresh token.
     /// Tokens are never stored in plaintext for security.
     #[sea_orm(unique)]
@@ -72,8 +86,24 @@ pub struct Model {
     /// If set, token is no longer valid regardless of expiry.
     pub revoked_at: Option<DateTimeWithTimeZone>,
 
-    /// When the token was created.
+    /// When the session signed in. Carried over when the token is rotated,
+    /// so it is the sign-in time of the whole chain of tokens.
     pub created_at: DateTimeWithTimeZone,
+
+    /// Raw `User-Agent` header of the client the token was issued to.
+    /// Parsed into a device label for the sessions list.
+    pub user_agent: Option<String>,
+
+    /// Client fingerprint the token is bound to (hex SHA-256).
+    /// `None` when issued without `REFRESH_TOKEN_BINDING_ENABLED`.
+    pub fingerprint: Option<String>,
+
+    /// IP address of the client the token was issued to, if known.
+    pub ip: Option<String>,
+
+    /// When the token was issued: the sign-in or the latest refresh.
+    /// `None` for tokens issued before the column existed.
+    pub last_used_at: Option<DateTimeWithTimeZone>,
 }
 
 /// Entity relations for the `RefreshToken` model.
@@ -98,3 +128,23 @@ impl Related<super::users::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// The token's id (`jti` claim)
+    #[must_use]
+    pub const fn jti(&self) -> TokenId {
+        TokenId::from_uuid(self.id)
+    }
+
+    /// The user the token was issued to
+    #[must_use]
+    pub const fn owner(&self) -> UserId {
+        UserId::from_uuid(self.user_id)
+    }
+
+    /// The token's family
+    #[must_use]
+    pub const fn family(&self) -> TokenId {
+        TokenId::from_uuid(self.family_id)
+    }
+}
@@ -0,0 +1,62 @@
+//! Role permission entity linking roles to the permissions they grant.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `role_permissions`
+//! - **Primary Key**: (`role_id`, `permission`)
+//! - **Foreign Keys**: `role_id` → `roles.id`, `permission` → `permissions.name`
+//!   (both CASCADE on delete)
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Role permission entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "role_permissions")]
+pub struct Model {
+    /// The granting role.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub role_id: Uuid,
+
+    /// The granted permission.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub permission: String,
+}
+
+/// Entity relations for the `RolePermission` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// `RolePermission` belongs to a Role.
+    #[sea_orm(
+        belongs_to = "super::roles::Entity",
+        from = "Column::RoleId",
+        to = "super::roles::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Roles,
+
+    /// `RolePermission` belongs to a Permission.
+    #[sea_orm(
+        belongs_to = "super::permissions::Entity",
+        from = "Column::Permission",
+        to = "super::permissions::Column::Name",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Permissions,
+}
+
+impl Related<super::roles::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Roles.def()
+    }
+}
+
+impl Related<super::permissions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Permissions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
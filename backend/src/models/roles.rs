@@ -0,0 +1,63 @@
+//! Role entity for fine-grained authorization.
+//!
+//! This module defines the `Role` entity: a named set of permissions
+//! created by an admin and assigned to users on top of their base role
+//! (`users.role`). See [`crate::services::rbac`].
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `roles`
+//! - **Primary Key**: `id` (UUID)
+//! - **Unique**: `name`
+//! - **Foreign Key**: `created_by` → `users.id` (SET NULL on delete)
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Role entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "roles")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Unique role name (lowercase letters, digits, `-` and `_`).
+    #[sea_orm(unique)]
+    pub name: String,
+
+    /// What the role is for.
+    pub description: Option<String>,
+
+    /// Admin who created the role.
+    pub created_by: Option<Uuid>,
+
+    /// When the role was created.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `Role` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Permissions granted by the role.
+    #[sea_orm(has_many = "super::role_permissions::Entity")]
+    RolePermissions,
+
+    /// Assignments of the role to users.
+    #[sea_orm(has_many = "super::user_roles::Entity")]
+    UserRoles,
+}
+
+impl Related<super::role_permissions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RolePermissions.def()
+    }
+}
+
+impl Related<super::user_roles::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserRoles.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,64 @@
+//! Runtime switches.
+//!
+//! This module defines the `RuntimeSwitches` entity which stores the
+//! switches an admin flips without a restart: whether new accounts can be
+//! registered and whether the API is read-only. The table holds at most one
+//! row (`id = 1`); without it registration is open and writes are accepted.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `runtime_switches`
+//! - **Primary Key**: `id` (always `1`)
+//! - **Foreign Key**: `updated_by` → `users.id` (SET NULL)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `Users`: Admin who last flipped a switch
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Runtime switches entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "runtime_switches")]
+pub struct Model {
+    /// Row identifier (always `1`).
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i32,
+
+    /// Whether new accounts can be registered.
+    pub registration_open: bool,
+
+    /// Whether mutating requests are rejected.
+    pub read_only: bool,
+
+    /// Reason returned with rejected requests while read-only.
+    pub read_only_reason: Option<String>,
+
+    /// Admin who last flipped a switch.
+    pub updated_by: Option<Uuid>,
+
+    /// Timestamp of the last change.
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `RuntimeSwitches` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Switches were last changed by a user.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UpdatedBy",
+        to = "super::users::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,59 @@
+//! Sandbox account entity for self-cleaning demo deployments.
+//!
+//! This module defines the `SandboxAccount` entity. In sandbox mode, every
+//! account created by sign-up or social login gets a record here; the record
+//! marks the user as a sandbox account with the sandbox chat limits and
+//! holds when it expires (see [`crate::services::sandbox`]).
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `sandbox_accounts`
+//! - **Primary Key**: `user_id` (UUID)
+//! - **Foreign Key**: `user_id` → `users.id` (CASCADE on delete)
+//!
+//! # Lifecycle
+//!
+//! 1. An account is created while sandbox mode is on: so is this record
+//! 2. The account is used normally, under the sandbox chat limits
+//! 3. After `expires_at`, the user is deleted with everything it owns
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Sandbox account entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "sandbox_accounts")]
+pub struct Model {
+    /// The sandbox user.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+
+    /// When the account is deleted.
+    pub expires_at: DateTimeWithTimeZone,
+
+    /// When the account was created.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `SandboxAccount` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// `SandboxAccount` belongs to a User.
+    /// Cascades on delete: deleting the user removes the record.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,32 @@
+//! Delivery state of periodic reports.
+//!
+//! This module defines the `ScheduledReports` entity, one row per report the
+//! scheduler sends (see [`crate::services::stats_report`]). Instances claim a
+//! due report by moving `last_sent_at` with a conditional update, so each
+//! report goes out once per period however many instances run.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `scheduled_reports`
+//! - **Primary Key**: `name`
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Scheduled report entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "scheduled_reports")]
+pub struct Model {
+    /// Report identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+
+    /// End of the last period reported (`None` before the first report).
+    pub last_sent_at: Option<DateTimeWithTimeZone>,
+}
+
+/// Scheduled reports have no relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,100 @@
+//! Trusted device entity for email-confirmed sign-in devices.
+//!
+//! This module defines the `TrustedDevice` entity. With trusted devices
+//! enabled, a browser is identified by its device cookie (see
+//! [`crate::services::auth::token_binding`]); signing in from a device
+//! without a confirmed record issues no refresh token until the account
+//! owner confirms the device through an emailed link.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `trusted_devices`
+//! - **Primary Key**: `id` (UUID)
+//! - **Unique Constraints**: `(user_id, device_hash)`, `confirmation_token_hash`
+//! - **Foreign Key**: `user_id` → `users.id` (CASCADE on delete)
+//!
+//! # Confirmation Flow
+//!
+//! 1. User signs in from a device without a record: a pending record is
+//!    created with a hashed confirmation token
+//! 2. Email is sent with a confirmation link containing the token
+//! 3. User opens the link: `confirmed_at` is set and the token is cleared
+//! 4. Later sign-ins from the device receive a refresh token
+//!
+//! Deleting a record revokes the trust: refreshes from the device are
+//! rejected and the next sign-in needs a new confirmation.
+//!
+//! # Security
+//!
+//! - Device cookies and confirmation tokens are stored as SHA-256 hashes
+//! - Confirmation tokens expire, and are one-time use
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Trusted device entity.
+///
+/// A device is trusted once `confirmed_at` is set; until then the record
+/// only holds the pending confirmation.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "trusted_devices")]
+pub struct Model {
+    /// Unique identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+
+    /// Owner of the device.
+    pub user_id: Uuid,
+
+    /// SHA-256 hash of the device cookie.
+    pub device_hash: String,
+
+    /// `User-Agent` of the sign-in that created the record.
+    pub user_agent: Option<String>,
+
+    /// Client IP address of that sign-in.
+    pub ip: Option<String>,
+
+    /// SHA-256 hash of the pending confirmation token.
+    /// Cleared once the device is confirmed.
+    #[sea_orm(unique)]
+    pub confirmation_token_hash: Option<String>,
+
+    /// When the pending confirmation token expires.
+    pub confirmation_expires_at: Option<DateTimeWithTimeZone>,
+
+    /// When the last confirmation email was sent.
+    pub confirmation_sent_at: Option<DateTimeWithTimeZone>,
+
+    /// When the owner confirmed the device (`None` = not trusted yet).
+    pub confirmed_at: Option<DateTimeWithTimeZone>,
+
+    /// Last sign-in or refresh from the device once trusted.
+    pub last_used_at: Option<DateTimeWithTimeZone>,
+
+    /// When the record was created.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `TrustedDevice` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// `TrustedDevice` belongs to a User.
+    /// Cascades on delete: deleting user removes their devices.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,56 @@
+//! Per-user application preferences.
+//!
+//! This module defines the `UserPreferences` entity which stores a user's
+//! preferences (theme, default model, language, notification settings) as a
+//! single JSONB document. The document shape is defined by
+//! [`crate::dto::preferences::UserPreferences`].
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `user_preferences`
+//! - **Primary Key**: `user_id`
+//! - **Foreign Key**: `user_id` → `users.id` (CASCADE)
+//!
+//! # Relations
+//!
+//! - `belongs_to` `Users`: Owner of the preferences
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// User preferences entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_preferences")]
+pub struct Model {
+    /// Owner of the preferences.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+
+    /// Preferences document (missing keys take their default).
+    #[sea_orm(column_type = "JsonBinary")]
+    pub preferences: Json,
+
+    /// Timestamp when the preferences last changed.
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `UserPreferences` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Preferences belong to a user.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,65 @@
+//! User role entity assigning custom roles to users.
+//!
+//! # Database Mapping
+//!
+//! - **Table**: `user_roles`
+//! - **Primary Key**: (`user_id`, `role_id`)
+//! - **Foreign Keys**: `user_id` → `users.id`, `role_id` → `roles.id`
+//!   (both CASCADE on delete)
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// User role entity.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_roles")]
+pub struct Model {
+    /// The user holding the role.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+
+    /// The assigned role.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub role_id: Uuid,
+
+    /// When the role was assigned.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Entity relations for the `UserRole` model.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// `UserRole` belongs to a User.
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+
+    /// `UserRole` belongs to a Role.
+    #[sea_orm(
+        belongs_to = "super::roles::Entity",
+        from = "Column::RoleId",
+        to = "super::roles::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Roles,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl Related<super::roles::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Roles.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
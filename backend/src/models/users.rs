@@ -22,6 +22,7 @@
 //! - `has_many` `RefreshTokens`: User's active refresh tokens
 //! - `has_many` `EmailVerifications`: Email verification history
 //! - `has_many` `OAuthAccounts`: Linked OAuth provider accounts
+//! - `has_many` `TrustedDevices`: Devices confirmed for long-lived sessions
 //!
 //! # Examples
 //!
@@ -95,6 +96,19 @@ pub struct Model {
     /// Timestamp of the user's last successful login.
     /// Updated on each successful authentication.
     pub last_login_at: Option<DateTimeWithTimeZone>,
+
+    /// Access tokens issued before this time are rejected.
+    /// Bumped on password change to sign out every device.
+    pub tokens_not_before: Option<DateTimeWithTimeZone>,
+
+    /// Timestamp of the user's last password change.
+    /// `None` until the first change; the password age then counts from
+    /// `created_at`.
+    pub password_changed_at: Option<DateTimeWithTimeZone>,
+
+    /// Whether an admin requires the user to change their password.
+    /// Cleared by a password change.
+    pub password_rotation_required: bool,
 }
 
 /// Entity relations for the User model.
@@ -117,6 +131,11 @@ pub enum Relation {
     /// Cascades on delete: deleting user removes all tokens.
     #[sea_orm(has_many = "super::refresh_tokens::Entity")]
     RefreshTokens,
+
+    /// User has many trusted devices.
+    /// Cascades on delete: deleting user removes their devices.
+    #[sea_orm(has_many = "super::trusted_devices::Entity")]
+    TrustedDevices,
 }
 
 impl Related<super::email_verifications::Entity> for Entity {
@@ -137,4 +156,10 @@ impl Related<super::refresh_tokens::Entity> for Entity {
     }
 }
 
+impl Related<super::trusted_devices::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TrustedDevices.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,353 @@
+//! Per-audience views of the `OpenAPI` specification.
+//!
+//! The full [`ApiDoc`](super::ApiDoc) documents every endpoint, admin and
+//! operational ones included. [`Audience::document`] narrows it to what one
+//! kind of client may call, and drops the schemas and tags only the removed
+//! operations used, so the spec handed to the frontend does not describe the
+//! admin API. Schemas registered without being referenced by an operation
+//! are kept in every document.
+//!
+//! Security schemes are pruned the same way, so the Swagger UI "Authorize"
+//! dialog of each document offers only the credentials its audience uses:
+//! the public document asks for the refresh cookie alone, the others add the
+//! bearer token.
+//!
+//! | Audience        | Operations                                         |
+//! |-----------------|----------------------------------------------------|
+//! | `public`        | No bearer token required (refresh cookie at most)  |
+//! | `authenticated` | Public plus bearer-token user endpoints            |
+//! | `admin`         | Everything, including admin and operational        |
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use utoipa::openapi::{
+    path::{Operation, PathItem},
+    OpenApi as Spec,
+};
+use utoipa::OpenApi;
+
+use super::{ApiDoc, REFRESH_COOKIE};
+use crate::middleware::access::{declared_operation, Access};
+
+/// Paths only operators use, besides the routes declared [`Access::Admin`]
+///
+/// `/health/ready` is served on the internal listener when one is configured;
+/// the bounce webhook is called by the mail provider, set up by an operator.
+const ADMIN_PATH_PREFIXES: &[&str] = &["/health/ready", "/api/v1/email/bounces"];
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// Who an `OpenAPI` document is written for
+///
+/// Ordered by access: each audience sees the operations of the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Audience {
+    Public,
+    Authenticated,
+    Admin,
+}
+
+impl Audience {
+    pub const ALL: [Self; 3] = [Self::Public, Self::Authenticated, Self::Admin];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Authenticated => "authenticated",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Where the document is served, e.g. `/openapi/public.json`
+    #[must_use]
+    pub const fn spec_path(self) -> &'static str {
+        match self {
+            Self::Public => "/openapi/public.json",
+            Self::Authenticated => "/openapi/authenticated.json",
+            Self::Admin => "/openapi/admin.json",
+        }
+    }
+
+    /// Audience needed to call `operation` on `path`
+    fn of_operation(path: &str, operation: &Operation) -> Self {
+        if ADMIN_PATH_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+            || declared_operation(path).is_some_and(|route| route.access == Access::Admin)
+        {
+            Self::Admin
+        } else if security_schemes(operation)
+            .iter()
+            .any(|scheme| scheme != REFRESH_COOKIE)
+        {
+            Self::Authenticated
+        } else {
+            Self::Public
+        }
+    }
+
+    /// The `OpenAPI` document of this audience
+    #[must_use]
+    pub fn document(self) -> Spec {
+        let mut spec = ApiDoc::openapi();
+        let referenced = reachable_schemas(&spec);
+        let required = required_schemes(&spec);
+
+        spec.paths.paths.retain(|path, item| {
+            for operation in operations_mut(item) {
+                if operation
+                    .as_ref()
+                    .is_some_and(|operation| Self::of_operation(path, operation) > self)
+                {
+                    *operation = None;
+                }
+            }
+            operations(item).iter().any(|operation| operation.is_some())
+        });
+
+        // Schemas registered for documentation only (no operation refers to
+        // them) stay in every document
+        let reachable = reachable_schemas(&spec);
+        let still_required = required_schemes(&spec);
+        if let Some(components) = spec.components.as_mut() {
+            components
+                .schemas
+                .retain(|name, _| reachable.contains(name) || !referenced.contains(name));
+            components
+                .security_schemes
+                .retain(|name, _| still_required.contains(name) || !required.contains(name));
+        }
+        prune_tags(&mut spec);
+        spec
+    }
+}
+
+impl fmt::Display for Audience {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Audience {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|audience| audience.as_str() == s)
+            .ok_or_else(|| {
+                format!("unknown audience: {s} (expected public, authenticated or admin)")
+            })
+    }
+}
+
+/// Operation slots of a path, one per HTTP method
+const fn operations(item: &PathItem) -> [&Option<Operation>; 8] {
+    [
+        &item.get,
+        &item.put,
+        &item.post,
+        &item.delete,
+        &item.options,
+        &item.head,
+        &item.patch,
+        &item.trace,
+    ]
+}
+
+pub(super) fn operations_mut(item: &mut PathItem) -> [&mut Option<Operation>; 8] {
+    [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.options,
+        &mut item.head,
+        &mut item.patch,
+        &mut item.trace,
+    ]
+}
+
+/// Names of the security schemes `operation` accepts
+fn security_schemes(operation: &Operation) -> BTreeSet<String> {
+    operation
+        .security
+        .iter()
+        .flatten()
+        .filter_map(|requirement| match serde_json::to_value(requirement) {
+            Ok(serde_json::Value::Object(schemes)) => {
+                Some(schemes.into_iter().map(|(name, _)| name))
+            }
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// Security schemes the operations of `spec` accept
+fn required_schemes(spec: &Spec) -> BTreeSet<String> {
+    spec.paths
+        .paths
+        .values()
+        .flat_map(operations)
+        .flatten()
+        .flat_map(security_schemes)
+        .collect()
+}
+
+/// Component schemas the operations of `spec` refer to, directly or nested
+fn reachable_schemas(spec: &Spec) -> BTreeSet<String> {
+    let Some(components) = spec.components.as_ref() else {
+        return BTreeSet::new();
+    };
+
+    let mut reachable = BTreeSet::new();
+    let mut pending = schema_refs(&serde_json::to_value(&spec.paths).unwrap_or_default());
+    while let Some(name) = pending.pop_first() {
+        if let Some(schema) = components.schemas.get(&name) {
+            let nested = schema_refs(&serde_json::to_value(schema).unwrap_or_default());
+            pending.extend(
+                nested
+                    .into_iter()
+                    .filter(|nested| !reachable.contains(nested)),
+            );
+        }
+        reachable.insert(name);
+    }
+    reachable
+}
+
+/// Remove tags no remaining operation carries
+fn prune_tags(spec: &mut Spec) {
+    let used: BTreeSet<String> = spec
+        .paths
+        .paths
+        .values()
+        .flat_map(operations)
+        .flatten()
+        .flat_map(|operation| operation.tags.iter().flatten().cloned())
+        .collect();
+
+    if let Some(tags) = spec.tags.as_mut() {
+        tags.retain(|tag| used.contains(&tag.name));
+    }
+}
+
+/// Names of the component schemas referenced anywhere in `value`
+fn schema_refs(value: &serde_json::Value) -> BTreeSet<String> {
+    fn collect(value: &serde_json::Value, refs: &mut BTreeSet<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    match value {
+                        serde_json::Value::String(target) if key == "$ref" => {
+                            if let Some(name) = target.strip_prefix(SCHEMA_REF_PREFIX) {
+                                refs.insert(name.to_string());
+                            }
+                        }
+                        _ => collect(value, refs),
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    collect(value, refs);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut refs = BTreeSet::new();
+    collect(value, &mut refs);
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(spec: &Spec) -> Vec<&str> {
+        spec.paths.paths.keys().map(String::as_str).collect()
+    }
+
+    fn schemas(spec: &Spec) -> Vec<&str> {
+        spec.components
+            .as_ref()
+            .map(|components| components.schemas.keys().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_public_document_has_no_protected_operations() {
+        let spec = Audience::Public.document();
+        let paths = paths(&spec);
+
+        assert!(paths.contains(&"/api/v1/auth/login"));
+        assert!(paths.contains(&"/api/v1/branding"));
+        assert!(!paths.contains(&"/api/v1/auth/me"));
+        assert!(!paths.iter().any(|path| path.starts_with("/api/v1/admin")));
+        assert!(!paths.contains(&"/health/ready"));
+        assert!(!schemas(&spec).contains(&"UserResponse"));
+        assert!(schemas(&spec).contains(&"LoginRequest"));
+    }
+
+    #[test]
+    fn test_authenticated_document_has_no_admin_operations() {
+        let spec = Audience::Authenticated.document();
+        let paths = paths(&spec);
+
+        assert!(paths.contains(&"/api/v1/auth/login"));
+        assert!(paths.contains(&"/api/v1/auth/me"));
+        assert!(!paths.iter().any(|path| path.starts_with("/api/v1/admin")));
+        assert!(!schemas(&spec).contains(&"AdminStatsResponse"));
+        assert!(!spec.tags.iter().flatten().any(|tag| tag.name == "Admin"));
+    }
+
+    fn security_schemes(spec: &Spec) -> Vec<&str> {
+        spec.components
+            .as_ref()
+            .map(|components| {
+                components
+                    .security_schemes
+                    .keys()
+                    .map(String::as_str)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_documents_offer_the_security_schemes_of_their_audience() {
+        let public = Audience::Public.document();
+        assert!(public.paths.paths.contains_key("/api/v1/auth/refresh"));
+        assert_eq!(security_schemes(&public), ["api_key", "refresh_cookie"]);
+
+        let authenticated = Audience::Authenticated.document();
+        assert_eq!(
+            security_schemes(&authenticated),
+            ["api_key", "bearer_auth", "refresh_cookie"]
+        );
+    }
+
+    #[test]
+    fn test_admin_document_is_the_full_spec() {
+        let full = ApiDoc::openapi();
+        let admin = Audience::Admin.document();
+
+        assert_eq!(paths(&admin), paths(&full));
+        assert_eq!(schemas(&admin), schemas(&full));
+        assert_eq!(security_schemes(&admin), security_schemes(&full));
+    }
+
+    #[test]
+    fn test_audience_from_str() {
+        for audience in Audience::ALL {
+            assert_eq!(audience.as_str().parse(), Ok(audience));
+        }
+        assert!("root".parse::<Audience>().is_err());
+    }
+}
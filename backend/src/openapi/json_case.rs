@@ -0,0 +1,147 @@
+//! `OpenAPI` documents of the `camelCase` API.
+//!
+//! With `API_JSON_CASE=camelCase` the API is also served under `/api/v2`
+//! with `camelCase` JSON bodies (see [`crate::middleware::json_case`]).
+//! [`camel_case`] turns a document of the `snake_case` API into the one
+//! describing it: paths move to `/api/v2` and schema properties are renamed.
+//! Query and path parameters keep their names, as they do on the wire.
+
+use serde_json::Value;
+use utoipa::openapi::OpenApi as Spec;
+
+use super::Audience;
+use crate::middleware::json_case::{CAMEL_CASE_PREFIX, SNAKE_CASE_PREFIX};
+use crate::utils::json_case::to_camel_case;
+
+/// Name of the `camelCase` document of `audience` in the Swagger UI
+#[must_use]
+pub const fn spec_name(audience: Audience) -> &'static str {
+    match audience {
+        Audience::Public => "public (v2, camelCase)",
+        Audience::Authenticated => "authenticated (v2, camelCase)",
+        Audience::Admin => "admin (v2, camelCase)",
+    }
+}
+
+/// Where the `camelCase` document of `audience` is served
+#[must_use]
+pub const fn spec_path(audience: Audience) -> &'static str {
+    match audience {
+        Audience::Public => "/openapi/v2/public.json",
+        Audience::Authenticated => "/openapi/v2/authenticated.json",
+        Audience::Admin => "/openapi/v2/admin.json",
+    }
+}
+
+/// `spec` as served under `/api/v2`, with `camelCase` schema properties
+///
+/// # Panics
+/// Panics if the document does not survive a JSON round trip, which would
+/// be a bug in `utoipa`
+#[must_use]
+pub fn camel_case(spec: &Spec) -> Spec {
+    let mut document = serde_json::to_value(spec).expect("OpenAPI document serializes");
+
+    if let Some(Value::Object(paths)) = document.get_mut("paths") {
+        *paths = std::mem::take(paths)
+            .into_iter()
+            .map(|(path, item)| match path.strip_prefix(SNAKE_CASE_PREFIX) {
+                Some(rest) => (format!("{CAMEL_CASE_PREFIX}{rest}"), item),
+                None => (path, item),
+            })
+            .collect();
+    }
+    rename_properties(&mut document);
+
+    serde_json::from_value(document).expect("OpenAPI document deserializes")
+}
+
+/// Rename the properties (and their `required` entries) of every schema
+fn rename_properties(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::Object(properties)) = object.get_mut("properties") {
+                *properties = std::mem::take(properties)
+                    .into_iter()
+                    .map(|(name, schema)| (to_camel_case(&name), schema))
+                    .collect();
+                if let Some(Value::Array(required)) = object.get_mut("required") {
+                    for name in required.iter_mut() {
+                        if let Value::String(name) = name {
+                            *name = to_camel_case(name);
+                        }
+                    }
+                }
+            }
+            if let Some(Value::String(name)) = object
+                .get_mut("discriminator")
+                .and_then(|discriminator| discriminator.get_mut("propertyName"))
+            {
+                *name = to_camel_case(name);
+            }
+            object.values_mut().for_each(rename_properties);
+        }
+        Value::Array(items) => items.iter_mut().for_each(rename_properties),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openapi::ApiDoc;
+    use crate::utils::json_case::to_snake_case;
+    use utoipa::OpenApi;
+
+    /// Names of the properties of every schema in `document`
+    fn property_names(document: &Value, names: &mut Vec<String>) {
+        match document {
+            Value::Object(object) => {
+                if let Some(Value::Object(properties)) = object.get("properties") {
+                    names.extend(properties.keys().cloned());
+                }
+                object
+                    .values()
+                    .for_each(|value| property_names(value, names));
+            }
+            Value::Array(items) => items.iter().for_each(|item| property_names(item, names)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_dto_fields_follow_the_snake_case_policy() {
+        let document = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let mut names = Vec::new();
+        property_names(&document, &mut names);
+        assert!(!names.is_empty());
+
+        for name in names {
+            assert!(
+                !name.chars().any(|c| c.is_ascii_uppercase()),
+                "{name} is not snake_case"
+            );
+            assert_eq!(
+                to_snake_case(&to_camel_case(&name)),
+                name,
+                "{name} does not survive the camelCase API"
+            );
+        }
+    }
+
+    #[test]
+    fn test_camel_case_document() {
+        let document = serde_json::to_value(camel_case(&ApiDoc::openapi())).unwrap();
+
+        assert!(document["paths"]["/api/v2/auth/login"].is_object());
+        assert!(document["paths"]["/api/v1/auth/login"].is_null());
+        assert!(document["paths"]["/health"].is_object());
+
+        let login = &document["components"]["schemas"]["LoginRequest"];
+        assert!(login["properties"]["usernameOrEmail"].is_object());
+        assert!(login["required"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from("usernameOrEmail")));
+    }
+}
@@ -8,9 +8,14 @@
 //!
 //! - **Paths**: All API endpoints from handlers
 //! - **Schemas**: Request/response models and enums
-//! - **Security**: Bearer token authentication scheme
+//! - **Security**: Bearer token, refresh cookie and (reserved) API key schemes
 //! - **Tags**: Endpoint categorization
 //!
+//! # Audiences
+//!
+//! The spec is served as one document per [`Audience`] (public,
+//! authenticated, admin) so clients only see the endpoints they may call.
+//!
 //! # Swagger UI
 //!
 //! Interactive API documentation available at:
@@ -18,14 +23,34 @@
 //! http://localhost:3000/swagger-ui
 //! ```
 //!
+//! The spec selector switches between the audience documents, served at
+//! `/openapi/public.json`, `/openapi/authenticated.json` and
+//! `/openapi/admin.json`.
+//!
+//! # Field Names
+//!
+//! Schemas describe the `snake_case` fields of `/api/v1`. With
+//! `API_JSON_CASE=camelCase` the `/api/v2` documents from
+//! [`json_case::camel_case`] are served at `/openapi/v2/<audience>.json` too.
+//!
 //! # Frontend Integration
 //!
-//! `OpenAPI` schema is written to `openapi/schema.json` at startup for
-//! frontend type generation:
+//! The server does not write anything at startup. Export the schema for
+//! frontend type generation with either the server subcommand or the
+//! standalone binary (no database needed):
 //!
 //! ```bash
+//! cobalt-stack-backend export-openapi --out ../openapi/schema.json
+//! cargo run --bin generate_openapi -- --out ../openapi/schema.json
+//!
+//! # Only the endpoints regular users can call:
+//! cobalt-stack-backend export-openapi --audience authenticated --out schema.json
+//!
+//! # The /api/v2 (camelCase) document:
+//! cobalt-stack-backend export-openapi --json-case camelCase --out schema.json
+//!
 //! # Frontend can generate types with:
-//! npx openapi-typescript ./backend/openapi/schema.json -o ./types/api.ts
+//! npx openapi-typescript ./openapi/schema.json -o ./types/api.ts
 //! ```
 //!
 //! # Adding New Endpoints
@@ -35,7 +60,10 @@
 //! 1. Add `#[utoipa::path(...)]` attribute to handler function
 //! 2. Add handler path to `paths(...)` in [`ApiDoc`]
 //! 3. Add request/response types to `schemas(...)` if needed
-//! 4. Restart server to regenerate schema
+//! 4. Declare the access of the route in
+//!    [`middleware::access::ROUTES`](crate::middleware::access::ROUTES); the
+//!    security requirement of the operation is generated from it
+//! 5. Re-export the schema (`make generate-openapi`)
 //!
 //! # Examples
 //!
@@ -49,8 +77,20 @@
 //! println!("{}", json);
 //! ```
 
+mod audience;
+pub mod json_case;
+pub mod typescript;
+
+pub use audience::Audience;
+
+use crate::config::JsonCase;
+
+use std::path::{Path, PathBuf};
 use utoipa::OpenApi;
 
+/// Where the schema is written when no `--out` is given
+pub const DEFAULT_SCHEMA_PATH: &str = "openapi/schema.json";
+
 /// `OpenAPI` 3.0 specification for the Cobalt Stack API.
 ///
 /// This struct defines the complete API documentation including all endpoints,
@@ -60,8 +100,8 @@ use utoipa::OpenApi;
 /// # Accessing the Spec
 ///
 /// - **Swagger UI**: <http://localhost:3000/swagger-ui>
-/// - **JSON Spec**: <http://localhost:3000/openapi.json>
-/// - **File Export**: `openapi/schema.json` (generated at startup)
+/// - **JSON Spec**: <http://localhost:3000/openapi/admin.json> (see [`Audience`])
+/// - **File Export**: `export-openapi --out <path>` (see [`write_openapi_schema`])
 ///
 /// # Sections
 ///
@@ -73,54 +113,245 @@ use utoipa::OpenApi;
 #[openapi(
     paths(
         crate::handlers::health::health_check,
+        crate::handlers::health::readiness_check,
+        crate::handlers::health::detailed_health_check,
         crate::handlers::auth::register,
         crate::handlers::auth::login,
         crate::handlers::auth::refresh_token,
         crate::handlers::auth::logout,
         crate::handlers::auth::get_current_user,
+        crate::handlers::auth::update_profile,
+        crate::handlers::auth::list_sessions,
+        crate::handlers::auth::revoke_session,
+        crate::handlers::auth::logout_all,
+        crate::handlers::auth::change_password,
+        crate::handlers::preferences::get_preferences,
+        crate::handlers::preferences::update_preferences,
         crate::handlers::auth::send_verification_email,
         crate::handlers::auth::verify_email,
+        crate::handlers::auth::list_trusted_devices,
+        crate::handlers::auth::remove_trusted_device,
+        crate::handlers::auth::confirm_device,
+        crate::handlers::guest::create_guest_token,
+        crate::handlers::guest::claim_guest,
+        crate::handlers::oauth::authorize,
+        crate::handlers::oauth::callback,
         crate::handlers::admin::list_users,
         crate::handlers::admin::get_user,
         crate::handlers::admin::disable_user,
         crate::handlers::admin::enable_user,
+        crate::handlers::admin::require_password_change,
+        crate::handlers::admin::create_user,
+        crate::handlers::admin::delete_user,
+        crate::handlers::admin::update_user_role,
+        crate::handlers::admin::get_user_roles,
+        crate::handlers::admin::assign_user_roles,
+        crate::handlers::admin::list_roles,
+        crate::handlers::admin::create_role,
+        crate::handlers::admin::delete_role,
         crate::handlers::admin::get_stats,
+        crate::handlers::admin::export_stats,
+        crate::handlers::admin::create_debug_token,
+        crate::handlers::admin::list_email_verifications,
+        crate::handlers::admin::resend_email_verification,
+        crate::handlers::admin::force_verify_user_email,
+        crate::handlers::admin::list_email_suppressions,
+        crate::handlers::admin::lift_email_suppression,
+        crate::handlers::admin::list_email_log,
+        crate::handlers::admin::create_backup,
+        crate::handlers::admin::restore_backup,
+        crate::handlers::admin::create_backup_export,
+        crate::handlers::admin::download_backup_export,
+        crate::handlers::admin::delete_backup_export,
+        crate::handlers::admin::system_doctor,
+        crate::handlers::admin::system_info,
+        crate::handlers::admin::get_runtime_switches,
+        crate::handlers::admin::update_runtime_switches,
+        crate::handlers::admin::list_access_logs,
+        crate::handlers::admin::list_audit_logs,
         crate::handlers::chat::create_session,
-        crate::handlers::chat::send_message,
+        crate::handlers::chat::send_message_v2,
+        crate::handlers::chat::stream_message,
+        crate::handlers::chat::chat_socket,
+        crate::handlers::chat::start_generation,
+        crate::handlers::chat::poll_generation,
+        crate::handlers::chat::create_job,
+        crate::handlers::chat::estimate_job,
+        crate::handlers::chat::get_job,
+        crate::handlers::chat::get_job_results,
+        crate::handlers::chat::list_webhooks,
+        crate::handlers::chat::create_webhook,
+        crate::handlers::chat::delete_webhook,
+        crate::handlers::chat::test_webhook,
         crate::handlers::chat::get_session_history,
+        crate::handlers::chat::import_messages,
         crate::handlers::chat::list_user_sessions,
         crate::handlers::chat::delete_session,
+        crate::handlers::chat::delete_message,
+        crate::handlers::chat::rename_session,
+        crate::handlers::chat::get_read_state,
+        crate::handlers::chat::mark_session_read,
+        crate::handlers::chat::list_annotations,
+        crate::handlers::chat::add_annotation,
+        crate::handlers::chat::remove_annotation,
+        crate::handlers::chat::create_share,
+        crate::handlers::chat::list_shares,
+        crate::handlers::chat::revoke_share,
+        crate::handlers::chat::view_shared_session,
+        crate::handlers::chat::get_chat_analytics,
+        crate::handlers::notifications::list_notifications,
+        crate::handlers::email::get_digest_preference,
+        crate::handlers::email::update_digest_preference,
+        crate::handlers::email::unsubscribe_page,
+        crate::handlers::email::unsubscribe,
+        crate::handlers::email::bounce_webhook,
+        crate::handlers::branding::get_branding,
+        crate::handlers::branding::update_branding,
+        crate::handlers::branding::reset_branding,
     ),
     components(
         schemas(
-            crate::handlers::health::HealthResponse,
-            crate::handlers::auth::RegisterRequest,
-            crate::handlers::auth::LoginRequest,
-            crate::handlers::auth::AuthResponse,
-            crate::handlers::auth::UserResponse,
-            crate::handlers::auth::ErrorResponse,
-            crate::handlers::auth::VerifyEmailRequest,
-            crate::handlers::auth::MessageResponse,
-            crate::handlers::admin::AdminUserResponse,
-            crate::handlers::admin::UserListResponse,
-            crate::handlers::admin::AdminStatsResponse,
-            crate::handlers::admin::MessageResponse,
-            crate::handlers::chat::dto::CreateSessionRequest,
-            crate::handlers::chat::dto::CreateSessionResponse,
-            crate::handlers::chat::dto::SendMessageRequest,
-            crate::handlers::chat::dto::SessionDto,
-            crate::handlers::chat::dto::MessageDto,
-            crate::handlers::chat::dto::GetHistoryResponse,
-            crate::handlers::chat::dto::ListSessionsResponse,
-            crate::handlers::chat::dto::DeleteSessionResponse,
+            crate::dto::health::HealthResponse,
+            crate::dto::health::ActiveModules,
+            crate::dto::health::ProviderHealth,
+            crate::dto::health::SchemaHealth,
+            crate::dto::health::DetailedHealthResponse,
+            crate::dto::health::DependencyHealth,
+            crate::dto::auth::RegisterRequest,
+            crate::dto::auth::LoginRequest,
+            crate::dto::auth::AuthResponse,
+            crate::dto::auth::ChangePasswordRequest,
+            crate::dto::auth::UpdateProfileRequest,
+            crate::dto::auth::UserResponse,
+            crate::dto::auth::Permission,
+            crate::dto::auth::QuotaSummary,
+            crate::dto::auth::SandboxStatus,
+            crate::dto::preferences::UserPreferences,
+            crate::dto::preferences::Theme,
+            crate::dto::preferences::NotificationPreferences,
+            crate::dto::preferences::UpdatePreferencesRequest,
+            crate::dto::preferences::UpdateNotificationPreferences,
+            crate::dto::ErrorResponse,
+            crate::dto::auth::VerifyEmailRequest,
+            crate::dto::auth::GuestTokenResponse,
+            crate::dto::auth::ClaimGuestRequest,
+            crate::dto::auth::ClaimGuestResponse,
+            crate::dto::auth::SendVerificationResponse,
+            crate::dto::auth::SessionResponse,
+            crate::dto::auth::SessionListResponse,
+            crate::dto::auth::TrustedDeviceResponse,
+            crate::dto::auth::TrustedDeviceListResponse,
+            crate::dto::auth::ConfirmDeviceRequest,
+            crate::dto::MessageResponse,
+            crate::dto::admin::AdminUserResponse,
+            crate::dto::admin::CreateUserRequest,
+            crate::dto::admin::CreatedUserResponse,
+            crate::dto::admin::UpdateUserRoleRequest,
+            crate::dto::admin::UserListResponse,
+            crate::dto::admin::PermissionResponse,
+            crate::dto::admin::RoleResponse,
+            crate::dto::admin::RoleListResponse,
+            crate::dto::admin::CreateRoleRequest,
+            crate::dto::admin::AssignRolesRequest,
+            crate::dto::admin::UserRolesResponse,
+            crate::dto::admin::AdminStatsResponse,
+            crate::dto::admin::DebugTokenRequest,
+            crate::dto::admin::DebugTokenResponse,
+            crate::dto::admin::VerificationStatus,
+            crate::dto::admin::EmailVerificationResponse,
+            crate::dto::admin::EmailVerificationListResponse,
+            crate::dto::admin::ForceVerifyRequest,
+            crate::dto::admin::EmailSuppressionResponse,
+            crate::dto::admin::EmailSuppressionListResponse,
+            crate::dto::admin::EmailLogResponse,
+            crate::dto::admin::EmailLogListResponse,
+            crate::dto::admin::CreateBackupRequest,
+            crate::dto::admin::RestoreBackupResponse,
+            crate::dto::admin::BackupExportResponse,
+            crate::dto::admin::DoctorReportResponse,
+            crate::dto::admin::DoctorFinding,
+            crate::dto::admin::DoctorSeverity,
+            crate::dto::admin::SystemInfoResponse,
+            crate::dto::admin::RuntimeSwitchesResponse,
+            crate::dto::admin::UpdateRuntimeSwitchesRequest,
+            crate::dto::admin::ConfigEntryResponse,
+            crate::dto::admin::ConfigEntrySource,
+            crate::dto::admin::AccessLogResponse,
+            crate::dto::admin::AccessLogListResponse,
+            crate::dto::admin::AuditLogResponse,
+            crate::dto::admin::AuditLogListResponse,
+            crate::dto::chat::CreateSessionRequest,
+            crate::dto::chat::CreateSessionResponse,
+            crate::dto::chat::SendMessageRequest,
+            crate::dto::chat::GenerationStartedResponse,
+            crate::dto::chat::GenerationStatusDto,
+            crate::dto::chat::GenerationPollResponse,
+            crate::dto::chat::CreateJobRequest,
+            crate::dto::chat::CreateJobResponse,
+            crate::dto::chat::JobCostEstimateDto,
+            crate::dto::chat::JobStatusDto,
+            crate::dto::chat::JobDto,
+            crate::dto::chat::JobItemStatusDto,
+            crate::dto::chat::JobItemDto,
+            crate::dto::chat::JobResultsResponse,
+            crate::dto::chat::CreateWebhookRequest,
+            crate::dto::chat::WebhookDto,
+            crate::dto::chat::ListWebhooksResponse,
+            crate::dto::chat::WebhookTestResponse,
+            crate::dto::chat::StreamLine,
+            crate::dto::chat::ChatErrorCode,
+            crate::dto::chat::ChatErrorResponse,
+            crate::dto::chat::PromptOverflowDto,
+            crate::dto::chat::WsClientMessage,
+            crate::dto::chat::WsServerMessage,
+            crate::dto::chat::TypingRole,
+            crate::dto::chat::UpdateSessionRequest,
+            crate::dto::chat::SessionDto,
+            crate::dto::chat::MessageDto,
+            crate::dto::chat::ImportMessageDto,
+            crate::dto::chat::ImportMessagesRequest,
+            crate::dto::chat::ImportMessagesResponse,
+            crate::dto::chat::GetHistoryResponse,
+            crate::dto::chat::ListSessionsResponse,
+            crate::dto::chat::DeleteSessionResponse,
+            crate::dto::chat::DeleteMessageResponse,
+            crate::dto::chat::MarkReadRequest,
+            crate::dto::chat::ReadStateResponse,
+            crate::dto::chat::AddAnnotationRequest,
+            crate::dto::chat::AnnotationDto,
+            crate::dto::chat::ListAnnotationsResponse,
+            crate::dto::chat::CreateShareRequest,
+            crate::dto::chat::ShareDto,
+            crate::dto::chat::ListSharesResponse,
+            crate::dto::chat::SharedConversationResponse,
+            crate::dto::chat::ChatAnalyticsResponse,
+            crate::dto::chat::DailyMessagesDto,
+            crate::dto::chat::ModelTokensDto,
+            crate::dto::chat::SessionActivityDto,
+            crate::dto::notifications::NotificationDto,
+            crate::dto::notifications::NotificationListResponse,
+            crate::dto::email::DigestPreferenceResponse,
+            crate::dto::email::UpdateDigestPreferenceRequest,
+            crate::dto::email::BounceWebhookResponse,
+            crate::dto::branding::BrandingResponse,
+            crate::dto::branding::UpdateBrandingRequest,
             crate::models::sea_orm_active_enums::UserRole,
         )
     ),
     tags(
-        (name = "health", description = "Health check endpoints"),
-        (name = "Authentication", description = "User authentication and email verification"),
-        (name = "Admin", description = "Admin user management endpoints"),
-        (name = "chat", description = "LLM chat session and message management")
+        (name = "health", description = "Health check endpoints. No authentication."),
+        (name = "Authentication", description = "User authentication and email verification. \
+            Register, login and email verification are public; refresh and logout use the \
+            `refresh_token` cookie; the other endpoints need a bearer access token."),
+        (name = "Admin", description = "Admin user management endpoints. Bearer access token \
+            of an admin user."),
+        (name = "chat", description = "LLM chat session and message management. Bearer access \
+            token, except viewing shared conversations."),
+        (name = "notifications", description = "User notification inbox. Bearer access token."),
+        (name = "email", description = "Email digest preferences and unsubscribe. Preferences \
+            need a bearer access token; unsubscribe is authorized by the token of the link."),
+        (name = "branding", description = "White-label branding of this deployment. Reading is \
+            public; changes need a bearer access token of an admin user.")
     ),
     info(
         title = "Cobalt Stack API",
@@ -134,70 +365,279 @@ use utoipa::OpenApi;
 )]
 pub struct ApiDoc;
 
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+};
 use utoipa::Modify;
 
-/// Security scheme modifier that adds Bearer token authentication.
+/// Security scheme of the JWT access token (`Authorization: Bearer <token>`)
+pub const BEARER_AUTH: &str = "bearer_auth";
+
+/// Security scheme of the HTTP-only `refresh_token` cookie
+pub const REFRESH_COOKIE: &str = "refresh_cookie";
+
+/// Security scheme of API keys (`X-API-Key` header)
+///
+/// Declared ahead of API key support so generated clients already carry the
+/// scheme; no operation accepts it yet.
+pub const API_KEY: &str = "api_key";
+
+/// Security scheme modifier that declares every authentication mode.
 ///
-/// This struct implements the `Modify` trait to add JWT Bearer authentication
-/// to the `OpenAPI` specification. The security scheme is referenced by protected
-/// endpoints using the `security(("bearer_auth" = []))` attribute.
+/// This struct implements the `Modify` trait to add the schemes to the
+/// `OpenAPI` specification, and sets the security requirement of every
+/// operation from the access declared for its route in
+/// [`middleware::access::ROUTES`](crate::middleware::access::ROUTES), the
+/// declaration that also decides its middleware:
+///
+/// - `bearer_auth`: JWT access token, for user and admin endpoints
+/// - `refresh_cookie`: refresh token cookie, for `/auth/refresh` and
+///   `/auth/logout`
+/// - `api_key`: reserved for API keys
+///
+/// Handlers do not repeat the requirement in a `security(...)` attribute.
 struct SecurityAddon;
 
 impl Modify for SecurityAddon {
     fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (path, item) in &mut openapi.paths.paths {
+            let Some(route) = crate::middleware::access::declared_operation(path) else {
+                continue;
+            };
+            let schemes = route.security_schemes();
+            let security = schemes.split_first().map(|(first, rest)| {
+                let requirement = rest.iter().fold(
+                    SecurityRequirement::new(*first, Vec::<String>::new()),
+                    |requirement, scheme| requirement.add(*scheme, Vec::<String>::new()),
+                );
+                vec![requirement]
+            });
+            for operation in audience::operations_mut(item).into_iter().flatten() {
+                operation.security.clone_from(&security);
+            }
+        }
+
         if let Some(components) = openapi.components.as_mut() {
             components.add_security_scheme(
-                "bearer_auth",
-                utoipa::openapi::security::SecurityScheme::Http(
-                    utoipa::openapi::security::Http::new(
-                        utoipa::openapi::security::HttpAuthScheme::Bearer,
-                    ),
+                BEARER_AUTH,
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .description(Some(
+                            "Access token from `POST /api/v1/auth/login` or \
+                             `POST /api/v1/auth/refresh`. Admin endpoints require \
+                             the token of an admin user.",
+                        ))
+                        .build(),
                 ),
             );
+            components.add_security_scheme(
+                REFRESH_COOKIE,
+                SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::with_description(
+                    "refresh_token",
+                    "HTTP-only cookie set by login and refresh. Browsers send it \
+                     automatically to the same origin; other clients must store \
+                     and resend it.",
+                ))),
+            );
+            components.add_security_scheme(
+                API_KEY,
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                    "X-API-Key",
+                    "Reserved for API keys. Not accepted by any endpoint yet.",
+                ))),
+            );
         }
     }
 }
 
 /// Write `OpenAPI` schema to file for frontend type generation.
 ///
-/// Generates the `OpenAPI` specification as JSON and writes it to
-/// `openapi/schema.json`. This file can be used by frontend tools
-/// like `openapi-typescript` to generate TypeScript types.
+/// Generates the `OpenAPI` document of `audience` as JSON and writes it to
+/// `path`, creating parent directories as needed. With [`JsonCase::Camel`]
+/// the document describes the `/api/v2` (`camelCase`) API instead. The file can be used by frontend
+/// tools like `openapi-typescript` to generate TypeScript types.
 ///
-/// # Returns
+/// # Errors
 ///
-/// - `Ok(())` - Schema successfully written
-/// - `Err(_)` - File I/O error
+/// Returns an error if the directory or file cannot be written.
 ///
 /// # Examples
 ///
 /// ```no_run
-/// use cobalt_stack_backend::openapi::write_openapi_schema;
+/// use cobalt_stack_backend::config::JsonCase;
+/// use cobalt_stack_backend::openapi::{write_openapi_schema, Audience, DEFAULT_SCHEMA_PATH};
+/// use std::path::Path;
 ///
-/// // Called at application startup
-/// write_openapi_schema().expect("Failed to write OpenAPI schema");
-/// println!("OpenAPI schema written to openapi/schema.json");
+/// write_openapi_schema(Path::new(DEFAULT_SCHEMA_PATH), Audience::Admin, JsonCase::Snake)
+///     .expect("Failed to write OpenAPI schema");
 /// ```
 ///
 /// # Frontend Usage
 ///
 /// ```bash
 /// # Generate TypeScript types from schema
-/// npx openapi-typescript ./backend/openapi/schema.json -o ./types/api.ts
+/// npx openapi-typescript ./openapi/schema.json -o ./types/api.ts
 ///
 /// # Use generated types in frontend
 /// import type { paths } from './types/api';
 /// type LoginRequest = paths['/api/auth/login']['post']['requestBody']['content']['application/json'];
 /// ```
-pub fn write_openapi_schema() -> Result<(), std::io::Error> {
-    let doc = ApiDoc::openapi();
-    let yaml = serde_json::to_string_pretty(&doc).unwrap();
+pub fn write_openapi_schema(
+    path: &Path,
+    audience: Audience,
+    json_case: JsonCase,
+) -> Result<(), std::io::Error> {
+    let doc = match json_case {
+        JsonCase::Snake => audience.document(),
+        JsonCase::Camel => json_case::camel_case(&audience.document()),
+    };
+    let json = serde_json::to_string_pretty(&doc).map_err(std::io::Error::other)?;
 
-    // Create openapi directory if it doesn't exist
-    std::fs::create_dir_all("openapi")?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
 
     // Write schema as JSON (easier for openapi-typescript to parse)
-    std::fs::write("openapi/schema.json", yaml)?;
+    std::fs::write(path, json)?;
 
     Ok(())
 }
+
+/// Arguments of `export-openapi`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportArgs {
+    pub out: PathBuf,
+    pub audience: Audience,
+    pub json_case: JsonCase,
+}
+
+/// Parse the arguments of `export-openapi` (`--out <path>`,
+/// `--audience <public|authenticated|admin>` and
+/// `--json-case <snake_case|camelCase>`, also as `--flag=value`)
+///
+/// Defaults to [`DEFAULT_SCHEMA_PATH`] and the full ([`Audience::Admin`])
+/// `snake_case` document.
+///
+/// # Errors
+///
+/// Returns a usage message for unknown arguments or missing values.
+pub fn parse_export_args<I>(args: I) -> Result<ExportArgs, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut export = ExportArgs {
+        out: PathBuf::from(DEFAULT_SCHEMA_PATH),
+        audience: Audience::Admin,
+        json_case: JsonCase::Snake,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{flag} requires a value"))
+        };
+        match flag.as_str() {
+            "--out" => export.out = PathBuf::from(value()?),
+            "--audience" => export.audience = value()?.parse()?,
+            "--json-case" => export.json_case = value()?.parse()?,
+            _ => {
+                return Err(format!(
+                    "unknown argument: {flag}\nusage: export-openapi [--out <path>] \
+                     [--audience <public|authenticated|admin>] \
+                     [--json-case <snake_case|camelCase>]"
+                ))
+            }
+        }
+    }
+    Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_export_args() {
+        let export = |out: &str, audience| ExportArgs {
+            out: PathBuf::from(out),
+            audience,
+            json_case: JsonCase::Snake,
+        };
+
+        assert_eq!(
+            parse_export_args(args(&[])),
+            Ok(export(DEFAULT_SCHEMA_PATH, Audience::Admin))
+        );
+        assert_eq!(
+            parse_export_args(args(&["--out", "/tmp/schema.json"])),
+            Ok(export("/tmp/schema.json", Audience::Admin))
+        );
+        assert_eq!(
+            parse_export_args(args(&["--out=dist/api.json", "--audience", "public"])),
+            Ok(export("dist/api.json", Audience::Public))
+        );
+        assert_eq!(
+            parse_export_args(args(&["--audience=authenticated"])),
+            Ok(export(DEFAULT_SCHEMA_PATH, Audience::Authenticated))
+        );
+        assert_eq!(
+            parse_export_args(args(&["--json-case", "camelCase"])),
+            Ok(ExportArgs {
+                json_case: JsonCase::Camel,
+                ..export(DEFAULT_SCHEMA_PATH, Audience::Admin)
+            })
+        );
+        assert!(parse_export_args(args(&["--out"])).is_err());
+        assert!(parse_export_args(args(&["--audience", "root"])).is_err());
+        assert!(parse_export_args(args(&["--verbose"])).is_err());
+    }
+
+    #[test]
+    fn test_write_openapi_schema_creates_parent_dirs() {
+        let dir = std::env::temp_dir().join(format!("openapi-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("nested").join("schema.json");
+
+        write_openapi_schema(&path, Audience::Admin, JsonCase::Snake).unwrap();
+
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(written["paths"]["/api/v1/auth/login"].is_object());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_operation_security_follows_route_declarations() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            let route = crate::middleware::access::declared_operation(path)
+                .unwrap_or_else(|| panic!("{path} is documented but has no access declaration"));
+            let expected: Vec<String> = route
+                .security_schemes()
+                .into_iter()
+                .map(String::from)
+                .collect();
+            for (method, operation) in item.as_object().unwrap() {
+                let schemes: Vec<String> = operation["security"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|requirement| requirement.as_object().unwrap().keys().cloned())
+                    .collect();
+                assert_eq!(schemes, expected, "security of {method} {path}");
+            }
+        }
+    }
+}
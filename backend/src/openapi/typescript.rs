@@ -0,0 +1,447 @@
+//! TypeScript types of an `OpenAPI` document.
+//!
+//! [`typescript_types`] renders the document in the shape `openapi-typescript`
+//! produces (`paths`, `components`, `operations`), so the frontend's
+//! `src/types/api.ts` can be fetched from a running development server
+//! (`GET /openapi/types.ts`) instead of regenerated from an exported schema.
+//!
+//! Only the schema features `utoipa` emits are translated: `$ref`, `type`
+//! (including `["string", "null"]` lists), `enum`, `const`, `oneOf` /
+//! `anyOf` / `allOf`, arrays, and objects with `properties` or
+//! `additionalProperties`. Anything else becomes `unknown`.
+
+use std::fmt::Write as _;
+
+use serde_json::{Map, Value};
+use utoipa::openapi::OpenApi as Spec;
+
+/// Path item methods, in the order `openapi-typescript` lists them
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Parameter locations of an operation
+const PARAMETER_LOCATIONS: [&str; 4] = ["query", "header", "path", "cookie"];
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// TypeScript module with the types of `spec`
+///
+/// # Panics
+/// Panics if the document cannot be serialized, which would be a bug in
+/// `utoipa`
+#[must_use]
+pub fn typescript_types(spec: &Spec) -> String {
+    let document = serde_json::to_value(spec).expect("OpenAPI document serializes");
+    let empty = Map::new();
+    let paths = document["paths"].as_object().unwrap_or(&empty);
+    let schemas = document["components"]["schemas"]
+        .as_object()
+        .unwrap_or(&empty);
+
+    let mut out = String::from(
+        "/**\n * This file was generated from the OpenAPI document by cobalt-stack-backend.\n \
+         * Do not make direct changes to the file.\n */\n\n",
+    );
+
+    out.push_str("export interface paths {\n");
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let _ = writeln!(out, "  {}: {{", key(path));
+        out.push_str("    parameters: {\n");
+        for location in PARAMETER_LOCATIONS {
+            let _ = writeln!(out, "      {location}?: never");
+        }
+        out.push_str("    }\n");
+        for method in METHODS {
+            let Some(operation) = item.get(method) else {
+                let _ = writeln!(out, "    {method}?: never");
+                continue;
+            };
+            out.push_str(&doc_comment(&summary_docs(operation), 2));
+            match operation["operationId"].as_str() {
+                Some(id) => {
+                    let _ = writeln!(out, "    {method}: operations[{}]", literal(id));
+                    operations.push((id, operation));
+                }
+                None => {
+                    let _ = writeln!(out, "    {method}: {}", operation_type(operation, 2));
+                }
+            }
+        }
+        out.push_str("  }\n");
+    }
+    out.push_str("}\n");
+    out.push_str("export type webhooks = Record<string, never>\n");
+
+    out.push_str("export interface components {\n  schemas: {\n");
+    for (name, schema) in schemas {
+        out.push_str(&doc_comment(&schema_docs(schema), 2));
+        let _ = writeln!(out, "    {}: {}", key(name), ts_type(schema, 2));
+    }
+    out.push_str("  }\n");
+    for section in [
+        "responses",
+        "parameters",
+        "requestBodies",
+        "headers",
+        "pathItems",
+    ] {
+        let _ = writeln!(out, "  {section}: never");
+    }
+    out.push_str("}\n");
+    out.push_str("export type $defs = Record<string, never>\n");
+
+    out.push_str("export interface operations {\n");
+    for (id, operation) in operations {
+        let _ = writeln!(out, "  {}: {}", key(id), operation_type(operation, 1));
+    }
+    out.push_str("}\n");
+
+    out
+}
+
+/// Parameters, request body and responses of an operation
+fn operation_type(operation: &Value, indent: usize) -> String {
+    let pad = "  ".repeat(indent + 1);
+    let mut out = String::from("{\n");
+
+    let _ = writeln!(out, "{pad}parameters: {{");
+    let parameters = operation["parameters"].as_array();
+    for location in PARAMETER_LOCATIONS {
+        let located: Vec<&Value> = parameters
+            .into_iter()
+            .flatten()
+            .filter(|parameter| parameter["in"] == location)
+            .collect();
+        if located.is_empty() {
+            let _ = writeln!(out, "{pad}  {location}?: never");
+            continue;
+        }
+        let required = located
+            .iter()
+            .any(|parameter| parameter["required"] == true);
+        let _ = writeln!(
+            out,
+            "{pad}  {location}{}: {{",
+            if required { "" } else { "?" }
+        );
+        for parameter in located {
+            let name = parameter["name"].as_str().unwrap_or_default();
+            let optional = if parameter["required"] == true {
+                ""
+            } else {
+                "?"
+            };
+            out.push_str(&doc_comment(&schema_docs(parameter), indent + 3));
+            let _ = writeln!(
+                out,
+                "{pad}    {}{optional}: {}",
+                key(name),
+                ts_type(&parameter["schema"], indent + 3)
+            );
+        }
+        let _ = writeln!(out, "{pad}  }}");
+    }
+    let _ = writeln!(out, "{pad}}}");
+
+    match operation.get("requestBody") {
+        Some(body) => {
+            let optional = if body["required"] == true { "" } else { "?" };
+            let _ = writeln!(
+                out,
+                "{pad}requestBody{optional}: {{\n{pad}  content: {}\n{pad}}}",
+                content_type(&body["content"], indent + 2)
+            );
+        }
+        None => {
+            let _ = writeln!(out, "{pad}requestBody?: never");
+        }
+    }
+
+    let _ = writeln!(out, "{pad}responses: {{");
+    let empty = Map::new();
+    for (status, response) in operation["responses"].as_object().unwrap_or(&empty) {
+        out.push_str(&doc_comment(&description_docs(response), indent + 2));
+        let _ = writeln!(out, "{pad}  {}: {{", key(status));
+        let _ = writeln!(
+            out,
+            "{pad}    headers: {{\n{pad}      [name: string]: unknown\n{pad}    }}"
+        );
+        match response.get("content") {
+            Some(content) => {
+                let _ = writeln!(
+                    out,
+                    "{pad}    content: {}",
+                    content_type(content, indent + 3)
+                );
+            }
+            None => {
+                let _ = writeln!(out, "{pad}    content?: never");
+            }
+        }
+        let _ = writeln!(out, "{pad}  }}");
+    }
+    let _ = writeln!(out, "{pad}}}");
+
+    let _ = write!(out, "{}}}", "  ".repeat(indent));
+    out
+}
+
+/// `{ 'application/json': T }` for a request or response `content` map
+fn content_type(content: &Value, indent: usize) -> String {
+    let pad = "  ".repeat(indent + 1);
+    let mut out = String::from("{\n");
+    for (media_type, media) in content.as_object().into_iter().flatten() {
+        let schema = media.get("schema").map_or_else(
+            || "unknown".to_string(),
+            |schema| ts_type(schema, indent + 1),
+        );
+        let _ = writeln!(out, "{pad}{}: {schema}", key(media_type));
+    }
+    let _ = write!(out, "{}}}", "  ".repeat(indent));
+    out
+}
+
+/// TypeScript type of a JSON schema, laid out for nesting at `indent`
+fn ts_type(schema: &Value, indent: usize) -> String {
+    if let Some(reference) = schema["$ref"].as_str() {
+        return reference.strip_prefix(SCHEMA_REF_PREFIX).map_or_else(
+            || "unknown".to_string(),
+            |name| format!("components['schemas'][{}]", literal(name)),
+        );
+    }
+    if let Some(value) = schema.get("const") {
+        return json_literal(value);
+    }
+    if let Some(values) = schema["enum"].as_array() {
+        return values
+            .iter()
+            .map(json_literal)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(variants) = schema["oneOf"]
+        .as_array()
+        .or_else(|| schema["anyOf"].as_array())
+    {
+        return variants
+            .iter()
+            .map(|variant| ts_type(variant, indent))
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(parts) = schema["allOf"].as_array() {
+        return parts
+            .iter()
+            .map(|part| ts_type(part, indent))
+            .collect::<Vec<_>>()
+            .join(" & ");
+    }
+
+    match &schema["type"] {
+        Value::String(kind) => single_type(schema, kind, indent),
+        Value::Array(kinds) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|kind| single_type(schema, kind, indent))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ if schema.get("properties").is_some() => object_type(schema, indent),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn single_type(schema: &Value, kind: &str, indent: usize) -> String {
+    match kind {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => {
+            let item = ts_type(&schema["items"], indent);
+            if item.contains(' ') && !item.starts_with('{') {
+                format!("({item})[]")
+            } else {
+                format!("{item}[]")
+            }
+        }
+        "object" => object_type(schema, indent),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn object_type(schema: &Value, indent: usize) -> String {
+    let properties = schema["properties"].as_object();
+    let additional = match &schema["additionalProperties"] {
+        Value::Bool(true) => Some("unknown".to_string()),
+        extra @ Value::Object(_) => Some(ts_type(extra, indent + 1)),
+        _ => None,
+    };
+    if properties.map_or(true, Map::is_empty) && additional.is_none() {
+        return "{\n".to_string()
+            + &"  ".repeat(indent + 1)
+            + "[key: string]: unknown\n"
+            + &"  ".repeat(indent)
+            + "}";
+    }
+
+    let pad = "  ".repeat(indent + 1);
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let mut out = String::from("{\n");
+    for (name, property) in properties.into_iter().flatten() {
+        let optional = if required.contains(&name.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        out.push_str(&doc_comment(&schema_docs(property), indent + 1));
+        let _ = writeln!(
+            out,
+            "{pad}{}{optional}: {}",
+            key(name),
+            ts_type(property, indent + 1)
+        );
+    }
+    if let Some(additional) = additional {
+        let _ = writeln!(out, "{pad}[key: string]: {additional}");
+    }
+    let _ = write!(out, "{}}}", "  ".repeat(indent));
+    out
+}
+
+/// Doc comment lines of a schema or parameter
+fn schema_docs(schema: &Value) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(format) = schema["format"]
+        .as_str()
+        .or_else(|| schema["schema"]["format"].as_str())
+    {
+        lines.push(format!("Format: {format}"));
+    }
+    lines.extend(description_docs(schema));
+    if schema["deprecated"] == true {
+        lines.push("@deprecated".to_string());
+    }
+    lines
+}
+
+fn description_docs(schema: &Value) -> Vec<String> {
+    schema["description"]
+        .as_str()
+        .filter(|description| !description.is_empty())
+        .map(|description| vec![format!("@description {description}")])
+        .unwrap_or_default()
+}
+
+fn summary_docs(operation: &Value) -> Vec<String> {
+    operation["summary"]
+        .as_str()
+        .map(|summary| vec![summary.to_string()])
+        .unwrap_or_default()
+}
+
+/// `/** ... */` indented like the member it documents (`level` steps deep)
+fn doc_comment(lines: &[String], level: usize) -> String {
+    let pad = "  ".repeat(level);
+    let lines: Vec<String> = lines
+        .iter()
+        .flat_map(|line| line.lines())
+        .map(|line| line.replace("*/", "*\\/"))
+        .collect();
+    match lines.as_slice() {
+        [] => String::new(),
+        [line] => format!("{pad}/** {line} */\n"),
+        lines => {
+            let mut out = format!("{pad}/**\n");
+            for line in lines {
+                let line = format!("{pad} * {line}");
+                let _ = writeln!(out, "{}", line.trim_end());
+            }
+            let _ = writeln!(out, "{pad} */");
+            out
+        }
+    }
+}
+
+/// Property name, quoted unless it is an identifier or a number
+fn key(name: &str) -> String {
+    let identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    let number = !name.is_empty() && name.chars().all(|c| c.is_ascii_digit());
+    if identifier || number {
+        name.to_string()
+    } else {
+        literal(name)
+    }
+}
+
+/// Single-quoted string literal
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn json_literal(value: &Value) -> String {
+    match value {
+        Value::String(value) => literal(value),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openapi::ApiDoc;
+    use serde_json::json;
+    use utoipa::OpenApi;
+
+    #[test]
+    fn test_schema_types() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "role"],
+            "properties": {
+                "disabled_at": { "type": ["string", "null"], "format": "date-time" },
+                "id": { "type": "string", "format": "uuid" },
+                "role": { "$ref": "#/components/schemas/UserRole" },
+                "tags": { "type": "array", "items": { "type": "string", "enum": ["a", "b"] } },
+            }
+        });
+
+        assert_eq!(
+            ts_type(&schema, 0),
+            "{\n  \
+               /** Format: date-time */\n  \
+               disabled_at?: string | null\n  \
+               /** Format: uuid */\n  \
+               id: string\n  \
+               role: components['schemas']['UserRole']\n  \
+               tags?: ('a' | 'b')[]\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn test_api_doc_types() {
+        let types = typescript_types(&ApiDoc::openapi());
+
+        assert!(types.contains("export interface paths {"));
+        assert!(types.contains("  '/api/v1/auth/login': {\n"));
+        assert!(types.contains("    post: operations['login']\n"));
+        assert!(types.contains("    LoginRequest: {\n"));
+        assert!(
+            types.contains("        'application/json': components['schemas']['LoginRequest']\n")
+        );
+        assert!(!types.contains("unknown[]"));
+    }
+}
@@ -0,0 +1,334 @@
+//! Listening socket selection: systemd socket activation, Unix socket, or TCP.
+//!
+//! [`Listener::bind`] uses the first available source:
+//!
+//! 1. A socket passed by systemd socket activation (`LISTEN_FDS`/`LISTEN_PID`),
+//!    either TCP or Unix stream
+//! 2. [`ServerConfig::unix_socket`] (`SERVER_UNIX_SOCKET_PATH`)
+//! 3. TCP on all interfaces at [`ServerConfig::port`]
+//!
+//! # Unix sockets
+//!
+//! A stale socket file left behind by a previous run is removed before
+//! binding, but only if nothing is accepting on it; regular files are never
+//! replaced. After binding, the file mode is set from
+//! `SERVER_UNIX_SOCKET_MODE` (default `660`) so a reverse proxy in the same
+//! group can connect:
+//!
+//! ```nginx
+//! upstream cobalt_backend {
+//!     server unix:/run/cobalt-stack/backend.sock;
+//! }
+//! ```
+//!
+//! # systemd
+//!
+//! With socket activation systemd owns the socket, including its permissions
+//! (`SocketMode=`, `SocketUser=`, `SocketGroup=`):
+//!
+//! ```ini
+//! # cobalt-stack-backend.socket
+//! [Socket]
+//! ListenStream=/run/cobalt-stack/backend.sock
+//! SocketMode=0660
+//! SocketGroup=www-data
+//!
+//! [Install]
+//! WantedBy=sockets.target
+//! ```
+
+use crate::config::ServerConfig;
+use listenfd::ListenFd;
+use std::{fmt, future::Future, io, net::SocketAddr};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream},
+};
+
+#[cfg(unix)]
+use crate::config::UnixSocketConfig;
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+
+/// A bound listening socket ready to be passed to [`serve`](super::serve)
+#[derive(Debug)]
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Bind the listener described by `config`, preferring an activated socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activated socket is unusable, the Unix socket
+    /// path is occupied by a live server or a non-socket file, or binding fails.
+    pub async fn bind(config: &ServerConfig) -> io::Result<Self> {
+        if let Some(listener) = Self::from_socket_activation()? {
+            return Ok(listener);
+        }
+
+        #[cfg(unix)]
+        if let Some(unix_socket) = &config.unix_socket {
+            return bind_unix(unix_socket).map(Self::Unix);
+        }
+
+        TcpListener::bind(("0.0.0.0", config.port))
+            .await
+            .map(Self::Tcp)
+    }
+
+    /// Take the first socket passed via systemd socket activation, if any.
+    ///
+    /// The `LISTEN_*` variables are cleared so child processes do not inherit them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the passed file descriptor is not a stream socket.
+    pub fn from_socket_activation() -> io::Result<Option<Self>> {
+        let mut fds = ListenFd::from_env();
+        if fds.len() > 1 {
+            tracing::warn!(
+                "{} sockets passed via LISTEN_FDS, only the first is used",
+                fds.len()
+            );
+        }
+
+        let tcp_error = match fds.take_tcp_listener(0) {
+            Ok(None) => return Ok(None),
+            Ok(Some(listener)) => {
+                listener.set_nonblocking(true)?;
+                return TcpListener::from_std(listener).map(|l| Some(Self::Tcp(l)));
+            }
+            Err(e) => e,
+        };
+
+        #[cfg(unix)]
+        if let Ok(Some(listener)) = fds.take_unix_listener(0) {
+            listener.set_nonblocking(true)?;
+            return UnixListener::from_std(listener).map(|l| Some(Self::Unix(l)));
+        }
+
+        Err(tcp_error)
+    }
+}
+
+impl From<TcpListener> for Listener {
+    fn from(listener: TcpListener) -> Self {
+        Self::Tcp(listener)
+    }
+}
+
+#[cfg(unix)]
+impl From<UnixListener> for Listener {
+    fn from(listener: UnixListener) -> Self {
+        Self::Unix(listener)
+    }
+}
+
+impl fmt::Display for Listener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(listener) => match listener.local_addr() {
+                Ok(addr) => write!(f, "{addr}"),
+                Err(_) => f.write_str("tcp socket"),
+            },
+            #[cfg(unix)]
+            Self::Unix(listener) => {
+                let addr = listener.local_addr().ok();
+                match addr.as_ref().and_then(|addr| addr.as_pathname()) {
+                    Some(path) => write!(f, "unix:{}", path.display()),
+                    None => f.write_str("unix socket"),
+                }
+            }
+        }
+    }
+}
+
+/// Bind a Unix socket at `config.path`, replacing a stale socket file.
+#[cfg(unix)]
+fn bind_unix(config: &UnixSocketConfig) -> io::Result<UnixListener> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    match std::fs::symlink_metadata(&config.path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            if std::os::unix::net::UnixStream::connect(&config.path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another process", config.path.display()),
+                ));
+            }
+            std::fs::remove_file(&config.path)?;
+        }
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", config.path.display()),
+            ));
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let listener = UnixListener::bind(&config.path)?;
+    std::fs::set_permissions(&config.path, std::fs::Permissions::from_mode(config.mode))?;
+    Ok(listener)
+}
+
+/// Remote end of an accepted connection
+#[derive(Debug, Clone)]
+pub struct Peer {
+    /// Socket address of TCP peers (`None` for Unix socket peers)
+    pub addr: Option<SocketAddr>,
+    /// Peer description for logs
+    pub description: String,
+}
+
+impl fmt::Display for Peer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+/// Listener types the accept loop can drive
+pub trait Accept: Send + Sync {
+    type Io: AsyncRead + AsyncWrite + Unpin + Send + 'static;
+
+    /// Accept a connection, returning the stream and its peer
+    fn accept_connection(&self) -> impl Future<Output = io::Result<(Self::Io, Peer)>> + Send;
+
+    /// Apply `TCP_NODELAY` where the transport supports it
+    fn set_nodelay(_io: &Self::Io) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Accept for TcpListener {
+    type Io = TcpStream;
+
+    async fn accept_connection(&self) -> io::Result<(TcpStream, Peer)> {
+        let (stream, addr) = self.accept().await?;
+        let peer = Peer {
+            addr: Some(addr),
+            description: addr.to_string(),
+        };
+        Ok((stream, peer))
+    }
+
+    fn set_nodelay(io: &TcpStream) -> io::Result<()> {
+        io.set_nodelay(true)
+    }
+}
+
+#[cfg(unix)]
+impl Accept for UnixListener {
+    type Io = UnixStream;
+
+    async fn accept_connection(&self) -> io::Result<(UnixStream, Peer)> {
+        let (stream, _) = self.accept().await?;
+        // Peers connecting over a Unix socket are almost always unnamed
+        let description = stream
+            .peer_cred()
+            .ok()
+            .and_then(|cred| cred.pid())
+            .map_or_else(
+                || "unix peer".to_string(),
+                |pid| format!("unix peer (pid {pid})"),
+            );
+        let peer = Peer {
+            addr: None,
+            description,
+        };
+        Ok((stream, peer))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn socket_config(mode: u32) -> (PathBuf, UnixSocketConfig) {
+        let dir = std::env::temp_dir().join(format!("cobalt-sock-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = UnixSocketConfig {
+            path: dir.join("backend.sock"),
+            mode,
+        };
+        (dir, config)
+    }
+
+    #[tokio::test]
+    async fn test_bind_unix_sets_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (dir, config) = socket_config(0o660);
+        let _listener = bind_unix(&config).unwrap();
+
+        let mode = std::fs::metadata(&config.path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o660);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bind_unix_replaces_stale_socket() {
+        let (dir, config) = socket_config(0o600);
+        drop(bind_unix(&config).unwrap());
+        assert!(config.path.exists());
+
+        assert!(bind_unix(&config).is_ok());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bind_unix_refuses_live_socket() {
+        let (dir, config) = socket_config(0o600);
+        let _live = bind_unix(&config).unwrap();
+
+        let err = bind_unix(&config).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bind_unix_refuses_regular_file() {
+        let (dir, config) = socket_config(0o600);
+        std::fs::write(&config.path, "not a socket").unwrap();
+
+        let err = bind_unix(&config).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(config.path.is_file());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_from_socket_activation() {
+        use std::os::fd::IntoRawFd;
+
+        assert!(Listener::from_socket_activation().unwrap().is_none());
+
+        let std_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = std_listener.local_addr().unwrap();
+        let fd = std_listener.into_raw_fd();
+
+        std::env::set_var("LISTEN_FDS", "1");
+        std::env::set_var("LISTEN_FDS_FIRST_FD", fd.to_string());
+        let listener = Listener::from_socket_activation().unwrap();
+        std::env::remove_var("LISTEN_FDS_FIRST_FD");
+
+        match listener {
+            Some(Listener::Tcp(listener)) => assert_eq!(listener.local_addr().unwrap(), addr),
+            other => panic!("expected activated TCP listener, got {other:?}"),
+        }
+        assert!(std::env::var("LISTEN_FDS").is_err());
+    }
+}
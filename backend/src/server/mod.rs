@@ -0,0 +1,536 @@
+//! HTTP server accept loop with connection-level tuning.
+//!
+//! Replaces `axum::serve` so that [`ServerConfig`] knobs (keep-alive, HTTP/2,
+//! `TCP_NODELAY`, connection limits) can be applied to every accepted socket.
+//!
+//! # Protocols
+//!
+//! Connections are auto-detected as HTTP/1.1 or HTTP/2 (prior knowledge).
+//! HTTP/1 upgrades (e.g. WebSocket) are supported. When HTTP/2 is disabled
+//! the server speaks HTTP/1.1 only.
+//!
+//! # TLS
+//!
+//! When [`ServerConfig::tls`] is set, connections are terminated with rustls
+//! (see [`tls`]) and ALPN negotiates `h2`/`http/1.1`. An optional plain-HTTP
+//! listener redirects to HTTPS (see [`redirect`]).
+//!
+//! # Listeners
+//!
+//! Besides TCP, the server can listen on a Unix domain socket or on a socket
+//! passed by systemd socket activation (see [`listener`]).
+//!
+//! # Shutdown
+//!
+//! [`serve_with_shutdown`] stops accepting once its [`Shutdown`] handle is
+//! triggered and drains open connections (see [`shutdown`]).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use axum::{routing::get, Router};
+//! use cobalt_stack_backend::{config::ServerConfig, server};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let config = ServerConfig::from_env();
+//! let app = Router::new().route("/", get(|| async { "ok" }));
+//! let listener = server::Listener::bind(&config).await?;
+//! let shutdown = server::Shutdown::new();
+//! tokio::spawn(server::shutdown::on_signal(shutdown.clone()));
+//! server::serve_with_shutdown(listener, app, &config, shutdown).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod listener;
+pub mod redirect;
+pub mod shutdown;
+pub mod tls;
+
+pub use listener::{Listener, Peer};
+pub use shutdown::Shutdown;
+
+use crate::config::ServerConfig;
+use axum::{extract::ConnectInfo, http::Request, Router};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo, TokioTimer},
+    server::conn::auto::Builder,
+    service::TowerToHyperService,
+};
+use listener::Accept;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::{mpsc, OwnedSemaphorePermit, Semaphore},
+};
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+
+/// Maximum time allowed for a client to complete the TLS handshake
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Build the hyper connection builder for the given configuration.
+fn connection_builder(config: &ServerConfig) -> Builder<TokioExecutor> {
+    let mut builder = Builder::new(TokioExecutor::new());
+
+    builder.http1().timer(TokioTimer::new());
+    match config.http1_keep_alive_timeout {
+        Some(timeout) => {
+            builder
+                .http1()
+                .keep_alive(true)
+                .header_read_timeout(timeout);
+        }
+        None => {
+            builder.http1().keep_alive(false);
+        }
+    }
+
+    if config.http2_enabled {
+        builder
+            .http2()
+            .timer(TokioTimer::new())
+            .max_concurrent_streams(config.http2_max_concurrent_streams);
+        builder
+    } else {
+        builder.http1_only()
+    }
+}
+
+/// Build the TLS acceptor and start `SIGHUP` certificate reloading.
+fn setup_tls(config: &ServerConfig) -> anyhow::Result<Option<TlsAcceptor>> {
+    let Some(tls_config) = &config.tls else {
+        return Ok(None);
+    };
+
+    tls::install_crypto_provider();
+    let key = tls::load_certified_key(&tls_config.cert_path, &tls_config.key_path)?;
+    let resolver = Arc::new(tls::ReloadableCertResolver::new(key));
+    let acceptor = tls::tls_acceptor(Arc::clone(&resolver), config.http2_enabled)?;
+
+    #[cfg(unix)]
+    tls::spawn_sighup_reload(resolver, tls_config.clone())?;
+
+    tracing::info!(
+        "TLS enabled with certificate {}",
+        tls_config.cert_path.display()
+    );
+    Ok(Some(acceptor))
+}
+
+/// Serve a single connection over any byte stream.
+///
+/// TCP peer addresses are added to each request as `ConnectInfo<SocketAddr>`
+/// (see [`crate::middleware::client_ip`]). Once `shutdown` begins, the
+/// connection is closed after its in-flight requests.
+async fn serve_connection<I>(
+    builder: Builder<TokioExecutor>,
+    io: I,
+    app: Router,
+    peer: Peer,
+    shutdown: Shutdown,
+) where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let addr = peer.addr;
+    let app = app.map_request(move |mut req: Request<_>| {
+        if let Some(addr) = addr {
+            req.extensions_mut().insert(ConnectInfo::<SocketAddr>(addr));
+        }
+        req
+    });
+    let service = TowerToHyperService::new(app);
+    let connection = builder.serve_connection_with_upgrades(TokioIo::new(io), service);
+    tokio::pin!(connection);
+    let result = tokio::select! {
+        result = connection.as_mut() => result,
+        () = shutdown.cancelled() => {
+            connection.as_mut().graceful_shutdown();
+            connection.await
+        }
+    };
+    if let Err(e) = result {
+        tracing::debug!("Connection from {} closed with error: {}", peer, e);
+    }
+}
+
+/// Serve `app` on `listener` forever.
+///
+/// See [`serve_with_shutdown`] to stop on a signal.
+///
+/// # Errors
+///
+/// Returns an error if TLS is configured but the certificate cannot be
+/// loaded.
+pub async fn serve(
+    listener: impl Into<Listener>,
+    app: Router,
+    config: &ServerConfig,
+) -> anyhow::Result<()> {
+    serve_with_shutdown(listener, app, config, Shutdown::new()).await
+}
+
+/// Serve `app` on `listener` until `shutdown` begins, then drain.
+///
+/// Each accepted connection is handled on its own task. When
+/// `max_connections` is set, accepting pauses while the limit is reached.
+/// When TLS is configured, the handshake runs on the connection task so slow
+/// clients cannot stall the accept loop.
+///
+/// Once `shutdown` begins, connections are still accepted and served for
+/// [`ServerConfig::shutdown_predrain`] while `/ready` fails. Then no more
+/// connections are accepted and open ones are closed after their in-flight
+/// requests. Returns when all have closed, or after
+/// [`ServerConfig::shutdown_timeout`] with the rest left to be dropped with
+/// the runtime.
+///
+/// # Errors
+///
+/// Returns an error if TLS is configured but the certificate cannot be
+/// loaded. Accept failures (e.g. file descriptor exhaustion) are logged and
+/// retried after a short delay.
+pub async fn serve_with_shutdown(
+    listener: impl Into<Listener>,
+    app: Router,
+    config: &ServerConfig,
+    shutdown: Shutdown,
+) -> anyhow::Result<()> {
+    let tls_acceptor = setup_tls(config)?;
+
+    tracing::info!(
+        http1_keep_alive_timeout = ?config.http1_keep_alive_timeout,
+        http2_enabled = config.http2_enabled,
+        http2_max_concurrent_streams = config.http2_max_concurrent_streams,
+        tcp_nodelay = config.tcp_nodelay,
+        max_connections = ?config.max_connections,
+        tls = tls_acceptor.is_some(),
+        shutdown_timeout = ?config.shutdown_timeout,
+        shutdown_predrain = ?config.shutdown_predrain,
+        "Server connection settings"
+    );
+
+    let drain = drain_after(&shutdown, config.shutdown_predrain);
+
+    match listener.into() {
+        Listener::Tcp(listener) => {
+            accept_loop(&listener, app, config, tls_acceptor, &drain).await;
+        }
+        #[cfg(unix)]
+        Listener::Unix(listener) => {
+            accept_loop(&listener, app, config, tls_acceptor, &drain).await;
+        }
+    }
+    Ok(())
+}
+
+/// Handle that begins `predrain` after `shutdown` does
+///
+/// Readiness follows `shutdown`, so load balancers see the instance go
+/// unready while the listener is still open.
+fn drain_after(shutdown: &Shutdown, predrain: Duration) -> Shutdown {
+    if predrain.is_zero() {
+        return shutdown.clone();
+    }
+    let drain = Shutdown::new();
+    let started = shutdown.cancelled();
+    tokio::spawn({
+        let drain = drain.clone();
+        async move {
+            started.await;
+            tracing::info!(
+                predrain = ?predrain,
+                "Unready, still accepting connections before draining"
+            );
+            tokio::time::sleep(predrain).await;
+            drain.begin();
+        }
+    });
+    drain
+}
+
+/// Accept connections from `listener`, spawning a task per connection, until
+/// `shutdown` begins; then wait for the open connections to close.
+async fn accept_loop<L: Accept>(
+    listener: &L,
+    app: Router,
+    config: &ServerConfig,
+    tls_acceptor: Option<TlsAcceptor>,
+    shutdown: &Shutdown,
+) {
+    let builder = connection_builder(config);
+    let limiter = config.max_connections.map(|n| Arc::new(Semaphore::new(n)));
+    // Every connection task holds a sender; `recv` returns `None` once all are dropped
+    let (open_tx, mut open_rx) = mpsc::channel::<()>(1);
+
+    loop {
+        // Reserve a connection slot before accepting so excess clients wait in the backlog
+        let permit: Option<OwnedSemaphorePermit> = match &limiter {
+            Some(limiter) => tokio::select! {
+                permit = Arc::clone(limiter).acquire_owned() => {
+                    Some(permit.expect("connection semaphore is never closed"))
+                }
+                () = shutdown.cancelled() => break,
+            },
+            None => None,
+        };
+
+        let accepted = tokio::select! {
+            accepted = listener.accept_connection() => accepted,
+            () = shutdown.cancelled() => break,
+        };
+        let (stream, peer) = match accepted {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!("Failed to accept connection: {}", e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        if config.tcp_nodelay {
+            if let Err(e) = L::set_nodelay(&stream) {
+                tracing::debug!("Failed to set TCP_NODELAY for {}: {}", peer, e);
+            }
+        }
+
+        let builder = builder.clone();
+        let app = app.clone();
+        let tls_acceptor = tls_acceptor.clone();
+        let shutdown = shutdown.clone();
+        let open = open_tx.clone();
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let _open = open;
+            match tls_acceptor {
+                Some(acceptor) => {
+                    match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await
+                    {
+                        Ok(Ok(tls_stream)) => {
+                            serve_connection(builder, tls_stream, app, peer, shutdown).await;
+                        }
+                        Ok(Err(e)) => {
+                            tracing::debug!("TLS handshake with {} failed: {}", peer, e);
+                        }
+                        Err(_) => {
+                            tracing::debug!("TLS handshake with {} timed out", peer);
+                        }
+                    }
+                }
+                None => serve_connection(builder, stream, app, peer, shutdown).await,
+            }
+        });
+    }
+
+    drop(open_tx);
+    tracing::info!("Stopped accepting connections, draining open ones");
+    if tokio::time::timeout(config.shutdown_timeout, open_rx.recv())
+        .await
+        .is_ok()
+    {
+        tracing::info!("All connections closed");
+    } else {
+        tracing::warn!(
+            timeout = ?config.shutdown_timeout,
+            "Connections still open after the shutdown timeout, closing them"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    async fn spawn_server(config: ServerConfig) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        tokio::spawn(async move { serve(listener, app, &config).await });
+        addr
+    }
+
+    async fn http1_get(addr: std::net::SocketAddr) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_serves_http1_request() {
+        let addr = spawn_server(ServerConfig::default()).await;
+        let response = http1_get(addr).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("ok"));
+    }
+
+    #[tokio::test]
+    async fn test_serves_http1_with_http2_disabled() {
+        let addr = spawn_server(ServerConfig {
+            http2_enabled: false,
+            http1_keep_alive_timeout: None,
+            ..ServerConfig::default()
+        })
+        .await;
+        let response = http1_get(addr).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[tokio::test]
+    async fn test_serves_https_request() {
+        use rustls::pki_types::{pem::PemObject, CertificateDer, ServerName};
+        use tokio_rustls::TlsConnector;
+
+        let dir = std::env::temp_dir().join(format!("cobalt-tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, cert.cert.pem()).unwrap();
+        std::fs::write(&key_path, cert.key_pair.serialize_pem()).unwrap();
+
+        let addr = spawn_server(ServerConfig {
+            tls: Some(crate::config::TlsConfig {
+                cert_path: cert_path.clone(),
+                key_path,
+                redirect_http_port: None,
+            }),
+            ..ServerConfig::default()
+        })
+        .await;
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots
+            .add(CertificateDer::from_pem_file(&cert_path).unwrap())
+            .unwrap();
+        let client_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+        let tcp = TcpStream::connect(addr).await.unwrap();
+        let mut stream = TlsConnector::from(Arc::new(client_config))
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        // Servers may close without close_notify; the bytes read so far are what matter
+        let _ = stream.read_to_end(&mut response).await;
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_serves_over_unix_socket() {
+        use tokio::net::{UnixListener, UnixStream};
+
+        let dir = std::env::temp_dir().join(format!("cobalt-sock-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("backend.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        tokio::spawn(async move { serve(listener, app, &ServerConfig::default()).await });
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_max_connections_releases_slots() {
+        let addr = spawn_server(ServerConfig {
+            max_connections: Some(1),
+            ..ServerConfig::default()
+        })
+        .await;
+        // Sequential connections must each get the single slot once the previous closes
+        for _ in 0..3 {
+            let response = http1_get(addr).await;
+            assert!(response.starts_with("HTTP/1.1 200 OK"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_in_flight_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/health",
+            get(|| async {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                "ok"
+            }),
+        );
+        let shutdown = Shutdown::new();
+        let server = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { serve_with_shutdown(listener, app, &ServerConfig::default(), shutdown).await }
+        });
+
+        let request = tokio::spawn(http1_get(addr));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        shutdown.begin();
+
+        // The request under way completes, then the server stops listening
+        assert!(request.await.unwrap().ends_with("ok"));
+        tokio::time::timeout(Duration::from_secs(1), server)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_keeps_accepting_during_predrain() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        let config = ServerConfig {
+            shutdown_predrain: Duration::from_millis(300),
+            ..ServerConfig::default()
+        };
+        let shutdown = Shutdown::new();
+        let server = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { serve_with_shutdown(listener, app, &config, shutdown).await }
+        });
+
+        shutdown.begin();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // New connections are still served until the pre-drain delay is over
+        assert!(http1_get(addr).await.ends_with("ok"));
+        assert!(!server.is_finished());
+        tokio::time::timeout(Duration::from_secs(1), server)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(TcpStream::connect(addr).await.is_err());
+    }
+}
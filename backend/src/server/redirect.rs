@@ -0,0 +1,111 @@
+//! Plain-HTTP listener that redirects every request to HTTPS.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Router,
+};
+use tokio::net::TcpListener;
+
+/// Build the HTTPS URL for a request received over plain HTTP.
+///
+/// The port is omitted when `https_port` is 443. Returns `None` when the
+/// request carries no usable `Host` header.
+#[must_use]
+pub fn https_location(headers: &HeaderMap, uri: &Uri, https_port: u16) -> Option<String> {
+    let host = headers.get(header::HOST)?.to_str().ok()?;
+    // Strip any port (IPv6 literals keep their brackets)
+    let hostname = match host.rfind(':') {
+        Some(idx) if !host[idx..].contains(']') => &host[..idx],
+        _ => host,
+    };
+    if hostname.is_empty() {
+        return None;
+    }
+
+    let path = uri.path_and_query().map_or("/", |pq| pq.as_str());
+    Some(if https_port == 443 {
+        format!("https://{hostname}{path}")
+    } else {
+        format!("https://{hostname}:{https_port}{path}")
+    })
+}
+
+async fn redirect_to_https(
+    State(https_port): State<u16>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    https_location(&headers, &uri, https_port).map_or_else(
+        || StatusCode::BAD_REQUEST.into_response(),
+        |location| Redirect::permanent(&location).into_response(),
+    )
+}
+
+/// Serve permanent (308) redirects to HTTPS on `listen_port`.
+///
+/// # Errors
+///
+/// Returns an error if the port cannot be bound or the server fails.
+pub async fn serve_https_redirect(listen_port: u16, https_port: u16) -> std::io::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", listen_port)).await?;
+    tracing::info!(
+        "Redirecting HTTP on port {} to HTTPS port {}",
+        listen_port,
+        https_port
+    );
+
+    let app = Router::new()
+        .fallback(redirect_to_https)
+        .with_state(https_port);
+    axum::serve(listener, app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(host: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, host.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_https_location_default_port() {
+        let uri: Uri = "/api/v1/auth/me?x=1".parse().unwrap();
+        assert_eq!(
+            https_location(&headers("example.com:80"), &uri, 443).as_deref(),
+            Some("https://example.com/api/v1/auth/me?x=1")
+        );
+    }
+
+    #[test]
+    fn test_https_location_custom_port() {
+        let uri: Uri = "/".parse().unwrap();
+        assert_eq!(
+            https_location(&headers("localhost:8080"), &uri, 8443).as_deref(),
+            Some("https://localhost:8443/")
+        );
+    }
+
+    #[test]
+    fn test_https_location_ipv6() {
+        let uri: Uri = "/health".parse().unwrap();
+        assert_eq!(
+            https_location(&headers("[::1]:80"), &uri, 443).as_deref(),
+            Some("https://[::1]/health")
+        );
+        assert_eq!(
+            https_location(&headers("[::1]"), &uri, 443).as_deref(),
+            Some("https://[::1]/health")
+        );
+    }
+
+    #[test]
+    fn test_https_location_missing_host() {
+        let uri: Uri = "/".parse().unwrap();
+        assert!(https_location(&HeaderMap::new(), &uri, 443).is_none());
+    }
+}
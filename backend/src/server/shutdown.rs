@@ -0,0 +1,142 @@
+//! Graceful shutdown on `SIGTERM`/`SIGINT`.
+//!
+//! A [`Shutdown`] handle is shared by everything that must react once the
+//! process is asked to stop:
+//!
+//! - the accept loop stops accepting after
+//!   [`ServerConfig::shutdown_predrain`], asks open connections to close after
+//!   their in-flight requests (HTTP/2 `GOAWAY`, HTTP/1 without keep-alive),
+//!   and waits for them up to [`ServerConfig::shutdown_timeout`]
+//! - chat reply streams (SSE, NDJSON) end with an error event and WebSocket
+//!   connections are closed with `1001 Going Away`; the partial replies are
+//!   saved as on a client disconnect
+//! - `/ready` answers `503`, so a load balancer still probing the instance
+//!   stops routing to it
+//!
+//! [`ServerConfig::shutdown_predrain`]: crate::config::ServerConfig::shutdown_predrain
+//! [`ServerConfig::shutdown_timeout`]: crate::config::ServerConfig::shutdown_timeout
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use cobalt_stack_backend::server::shutdown::{self, Shutdown};
+//!
+//! # async fn example() {
+//! let shutdown = Shutdown::new();
+//! tokio::spawn(shutdown::on_signal(shutdown.clone()));
+//!
+//! shutdown.cancelled().await;
+//! assert!(shutdown.is_shutting_down());
+//! # }
+//! ```
+
+use std::{future::Future, sync::Arc};
+use tokio::sync::watch;
+
+/// Shared flag raised once when the server starts shutting down
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    started: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            started: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Start shutting down; later calls do nothing
+    pub fn begin(&self) {
+        self.started.send_replace(true);
+    }
+
+    /// Whether [`begin`](Self::begin) has been called
+    #[must_use]
+    pub fn is_shutting_down(&self) -> bool {
+        *self.started.borrow()
+    }
+
+    /// Resolves once shutdown has begun (immediately if it already has)
+    ///
+    /// The future does not borrow the handle, so it can be stored in a
+    /// stream or moved to a task.
+    pub fn cancelled(&self) -> impl Future<Output = ()> + Send + 'static {
+        // Holding the sender keeps `wait_for` from failing once every
+        // handle is dropped, which would look like a shutdown
+        let sender = Arc::clone(&self.started);
+        let mut started = sender.subscribe();
+        async move {
+            let _ = started.wait_for(|started| *started).await;
+            drop(sender);
+        }
+    }
+}
+
+/// Wait for `SIGTERM` or `SIGINT` (Ctrl-C), then begin `shutdown`
+pub async fn on_signal(shutdown: Shutdown) {
+    let interrupt = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = interrupt => tracing::info!("Received SIGINT, shutting down"),
+        () = terminate => tracing::info!("Received SIGTERM, shutting down"),
+    }
+    shutdown.begin();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancelled_resolves_after_begin() {
+        let shutdown = Shutdown::new();
+        let cancelled = tokio::spawn(shutdown.cancelled());
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!cancelled.is_finished());
+        assert!(!shutdown.is_shutting_down());
+
+        shutdown.begin();
+        tokio::time::timeout(Duration::from_secs(1), cancelled)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(shutdown.is_shutting_down());
+
+        // Waiting after the fact resolves at once
+        tokio::time::timeout(Duration::from_secs(1), shutdown.cancelled())
+            .await
+            .unwrap();
+    }
+}
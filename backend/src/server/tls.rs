@@ -0,0 +1,231 @@
+//! Rustls TLS termination with hot-reloadable certificates.
+//!
+//! Certificates are loaded from PEM files and served through a
+//! [`ReloadableCertResolver`], which can swap the active certificate without
+//! dropping the listener or existing connections. On Unix, sending `SIGHUP`
+//! to the process reloads the files from disk, which pairs with external ACME
+//! clients (certbot, lego, ...) via a deploy hook:
+//!
+//! ```bash
+//! certbot renew --deploy-hook "pkill -HUP cobalt-stack-backend"
+//! ```
+
+use crate::config::TlsConfig;
+use anyhow::Context;
+use rustls::{
+    crypto::{ring::default_provider, CryptoProvider},
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+};
+use std::{
+    path::Path,
+    sync::{Arc, RwLock},
+};
+use tokio_rustls::TlsAcceptor;
+
+/// Load a certificate chain and private key from PEM files.
+///
+/// # Errors
+///
+/// Returns an error if either file cannot be read, contains no usable PEM
+/// item, or the key type is unsupported.
+pub fn load_certified_key(cert_path: &Path, key_path: &Path) -> anyhow::Result<CertifiedKey> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .with_context(|| format!("failed to open certificate {}", cert_path.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("invalid certificate PEM {}", cert_path.display()))?;
+    anyhow::ensure!(
+        !certs.is_empty(),
+        "no certificates found in {}",
+        cert_path.display()
+    );
+
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .with_context(|| format!("failed to load private key {}", key_path.display()))?;
+    let signing_key = default_provider()
+        .key_provider
+        .load_private_key(key)
+        .context("unsupported private key type")?;
+
+    Ok(CertifiedKey::new(certs, signing_key))
+}
+
+/// Certificate resolver whose certificate can be replaced at runtime.
+#[derive(Debug)]
+pub struct ReloadableCertResolver {
+    current: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ReloadableCertResolver {
+    #[must_use]
+    pub fn new(key: CertifiedKey) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(key)),
+        }
+    }
+
+    /// Replace the active certificate; new handshakes use it immediately.
+    pub fn set(&self, key: CertifiedKey) {
+        *self.current.write().expect("cert lock poisoned") = Arc::new(key);
+    }
+
+    /// Reload the certificate from `config` paths, keeping the old one on failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns the load error; the previously active certificate stays in use.
+    pub fn reload(&self, config: &TlsConfig) -> anyhow::Result<()> {
+        let key = load_certified_key(&config.cert_path, &config.key_path)?;
+        self.set(key);
+        Ok(())
+    }
+
+    fn current(&self) -> Arc<CertifiedKey> {
+        Arc::clone(&self.current.read().expect("cert lock poisoned"))
+    }
+}
+
+impl ResolvesServerCert for ReloadableCertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.current())
+    }
+}
+
+/// Build a TLS acceptor backed by `resolver`.
+///
+/// ALPN advertises `h2` only when HTTP/2 is enabled.
+///
+/// # Errors
+///
+/// Returns an error if the crypto provider rejects the protocol configuration.
+pub fn tls_acceptor(
+    resolver: Arc<ReloadableCertResolver>,
+    http2_enabled: bool,
+) -> anyhow::Result<TlsAcceptor> {
+    let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_cert_resolver(resolver);
+
+    config.alpn_protocols = if http2_enabled {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    };
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Install the process-wide default crypto provider (ring).
+///
+/// Safe to call multiple times.
+pub fn install_crypto_provider() {
+    let _ = CryptoProvider::install_default(default_provider());
+}
+
+/// Reload certificates from disk whenever the process receives `SIGHUP`.
+///
+/// # Errors
+///
+/// Returns an error if the signal handler cannot be registered.
+#[cfg(unix)]
+pub fn spawn_sighup_reload(
+    resolver: Arc<ReloadableCertResolver>,
+    config: TlsConfig,
+) -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match resolver.reload(&config) {
+                Ok(()) => tracing::info!("TLS certificate reloaded"),
+                Err(e) => tracing::error!("TLS certificate reload failed, keeping previous: {e:#}"),
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Write a fresh self-signed certificate and key to a temp directory.
+    fn write_self_signed(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+        let cert = rcgen::generate_simple_self_signed(vec![name.to_string()]).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, cert.cert.pem()).unwrap();
+        std::fs::write(&key_path, cert.key_pair.serialize_pem()).unwrap();
+        (cert_path, key_path)
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cobalt-tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_certified_key() {
+        let dir = temp_dir();
+        let (cert_path, key_path) = write_self_signed(&dir, "localhost");
+
+        let key = load_certified_key(&cert_path, &key_path).unwrap();
+        assert_eq!(key.cert.len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_certified_key_missing_file() {
+        let dir = temp_dir();
+        let result = load_certified_key(&dir.join("nope.pem"), &dir.join("nope.key"));
+        assert!(result.is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_reload_swaps_certificate_and_keeps_old_on_failure() {
+        let dir = temp_dir();
+        let (cert_path, key_path) = write_self_signed(&dir, "old.example.com");
+        let config = TlsConfig {
+            cert_path: cert_path.clone(),
+            key_path: key_path.clone(),
+            redirect_http_port: None,
+        };
+
+        let resolver =
+            ReloadableCertResolver::new(load_certified_key(&cert_path, &key_path).unwrap());
+        let before = resolver.current().cert[0].clone();
+
+        write_self_signed(&dir, "new.example.com");
+        resolver.reload(&config).unwrap();
+        let after = resolver.current().cert[0].clone();
+        assert_ne!(before, after);
+
+        std::fs::write(&cert_path, "garbage").unwrap();
+        assert!(resolver.reload(&config).is_err());
+        assert_eq!(resolver.current().cert[0], after);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_tls_acceptor_alpn() {
+        install_crypto_provider();
+        let dir = temp_dir();
+        let (cert_path, key_path) = write_self_signed(&dir, "localhost");
+        let resolver = Arc::new(ReloadableCertResolver::new(
+            load_certified_key(&cert_path, &key_path).unwrap(),
+        ));
+
+        assert!(tls_acceptor(Arc::clone(&resolver), true).is_ok());
+        assert!(tls_acceptor(resolver, false).is_ok());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,285 @@
+//! API access records for compliance trails.
+//!
+//! The access log middleware ([`crate::middleware::access_log`]) hands one
+//! [`AccessRecord`] per recorded request to the [`AccessLogger`], which
+//! batches records on a background task and passes each batch to an
+//! [`AccessLogSink`]:
+//!
+//! - [`DatabaseAccessLogSink`]: the `access_logs` table, queryable through
+//!   `GET /api/v1/admin/access-logs` and purged by [`purge_expired`]
+//! - [`TracingAccessLogSink`]: structured log events with target
+//!   `access_log`, for a log collector to ship to an external store
+//!
+//! Other stores (a SIEM, object storage) plug in by implementing the trait.
+//!
+//! Recording never delays a response: when the queue is full because the
+//! sink is down or too slow, records are dropped and counted in a warning.
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
+use std::{
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use uuid::Uuid;
+
+use crate::config::AccessLogConfig;
+use crate::models::{access_logs, prelude::AccessLogs};
+
+/// Records waiting to be written before new ones are dropped
+pub const QUEUE_CAPACITY: usize = 10_000;
+
+/// Largest number of records passed to a sink at once
+pub const MAX_BATCH_SIZE: usize = 500;
+
+/// One API request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessRecord {
+    /// When the request arrived
+    pub created_at: DateTime<Utc>,
+    pub method: String,
+    /// Request path, without the query string
+    pub path: String,
+    pub status: u16,
+    /// Time until the response headers were ready
+    pub latency: Duration,
+    /// User of a valid bearer token, if any
+    pub user_id: Option<Uuid>,
+    /// Client address, resolved through trusted proxies
+    pub ip: Option<IpAddr>,
+}
+
+/// Destination of access records
+#[async_trait]
+pub trait AccessLogSink: Send + Sync {
+    /// Write a batch of records
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch could not be stored; it is logged and
+    /// the records are lost.
+    async fn write(&self, records: &[AccessRecord]) -> anyhow::Result<()>;
+}
+
+/// Writes records to the `access_logs` table
+pub struct DatabaseAccessLogSink {
+    db: Arc<DatabaseConnection>,
+}
+
+impl DatabaseAccessLogSink {
+    #[must_use]
+    pub const fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AccessLogSink for DatabaseAccessLogSink {
+    async fn write(&self, records: &[AccessRecord]) -> anyhow::Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        AccessLogs::insert_many(records.iter().map(|record| access_logs::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            method: Set(record.method.clone()),
+            path: Set(record.path.clone()),
+            status: Set(i16::try_from(record.status).unwrap_or(i16::MAX)),
+            latency_ms: Set(i32::try_from(record.latency.as_millis()).unwrap_or(i32::MAX)),
+            user_id: Set(record.user_id),
+            ip: Set(record.ip.map(|ip| ip.to_string())),
+            created_at: Set(record.created_at.into()),
+        }))
+        .exec_without_returning(self.db.as_ref())
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Emits one `access_log` event per record
+pub struct TracingAccessLogSink;
+
+#[async_trait]
+impl AccessLogSink for TracingAccessLogSink {
+    async fn write(&self, records: &[AccessRecord]) -> anyhow::Result<()> {
+        for record in records {
+            tracing::info!(
+                target: "access_log",
+                timestamp = %record.created_at.to_rfc3339(),
+                method = %record.method,
+                path = %record.path,
+                status = record.status,
+                latency_ms = u64::try_from(record.latency.as_millis()).unwrap_or(u64::MAX),
+                user_id = record.user_id.map(|id| id.to_string()),
+                ip = record.ip.map(|ip| ip.to_string()),
+                "API access"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Decides which requests to record and queues them for the sink
+#[derive(Clone)]
+pub struct AccessLogger {
+    config: Arc<AccessLogConfig>,
+    sender: mpsc::Sender<AccessRecord>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl AccessLogger {
+    /// Start the background writer for `sink`
+    ///
+    /// Must be called from within a Tokio runtime.
+    #[must_use]
+    pub fn spawn(config: AccessLogConfig, sink: Arc<dyn AccessLogSink>) -> Self {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        tokio::spawn(write_batches(receiver, sink, Arc::clone(&dropped)));
+
+        Self {
+            config: Arc::new(config),
+            sender,
+            dropped,
+        }
+    }
+
+    /// Whether requests to `path` may be recorded at all
+    #[must_use]
+    pub fn covers(&self, path: &str) -> bool {
+        !self.config.is_excluded(path)
+    }
+
+    /// Whether a covered request that ended with `status` is recorded
+    ///
+    /// Errors are always recorded; other requests are sampled at the
+    /// configured rate.
+    #[must_use]
+    pub fn is_sampled(&self, status: u16) -> bool {
+        status >= 400
+            || self.config.sample_rate >= 1.0
+            || rand::random::<f64>() < self.config.sample_rate
+    }
+
+    /// Queue a record without waiting
+    pub fn record(&self, record: AccessRecord) {
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(record) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Pass queued records to the sink until every [`AccessLogger`] is dropped
+async fn write_batches(
+    mut receiver: mpsc::Receiver<AccessRecord>,
+    sink: Arc<dyn AccessLogSink>,
+    dropped: Arc<AtomicU64>,
+) {
+    let mut batch = Vec::with_capacity(MAX_BATCH_SIZE);
+    while receiver.recv_many(&mut batch, MAX_BATCH_SIZE).await > 0 {
+        if let Err(e) = sink.write(&batch).await {
+            tracing::error!("Failed to write {} access records: {}", batch.len(), e);
+        }
+        batch.clear();
+
+        let dropped = dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            tracing::warn!("Dropped {} access records: queue full", dropped);
+        }
+    }
+}
+
+/// Delete records older than `retention`
+///
+/// # Errors
+///
+/// Returns an error if the delete fails.
+pub async fn purge_expired(db: &DatabaseConnection, retention: Duration) -> Result<u64, DbErr> {
+    let Some(cutoff) = TimeDelta::from_std(retention)
+        .ok()
+        .and_then(|age| Utc::now().checked_sub_signed(age))
+    else {
+        return Ok(0);
+    };
+
+    let result = AccessLogs::delete_many()
+        .filter(access_logs::Column::CreatedAt.lt(cutoff))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AccessLogSinkKind;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct CollectingSink {
+        records: Mutex<Vec<AccessRecord>>,
+    }
+
+    #[async_trait]
+    impl AccessLogSink for CollectingSink {
+        async fn write(&self, records: &[AccessRecord]) -> anyhow::Result<()> {
+            self.records.lock().unwrap().extend_from_slice(records);
+            Ok(())
+        }
+    }
+
+    fn config(sample_rate: f64) -> AccessLogConfig {
+        AccessLogConfig {
+            sink: AccessLogSinkKind::Log,
+            sample_rate,
+            exclude_paths: vec!["/health".to_string()],
+            retention: None,
+            purge_interval: Duration::from_secs(3600),
+        }
+    }
+
+    fn record(path: &str) -> AccessRecord {
+        AccessRecord {
+            created_at: Utc::now(),
+            method: "GET".to_string(),
+            path: path.to_string(),
+            status: 200,
+            latency: Duration::from_millis(12),
+            user_id: None,
+            ip: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_reach_sink() {
+        let sink = Arc::new(CollectingSink::default());
+        let logger = AccessLogger::spawn(config(1.0), Arc::clone(&sink) as Arc<_>);
+
+        logger.record(record("/api/v1/auth/me"));
+        logger.record(record("/api/v1/chat/sessions"));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let records = sink.records.lock().unwrap().clone();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].path, "/api/v1/chat/sessions");
+    }
+
+    #[tokio::test]
+    async fn test_sampling_keeps_errors() {
+        let logger = AccessLogger::spawn(config(0.0), Arc::new(TracingAccessLogSink));
+
+        assert!(!logger.is_sampled(200));
+        assert!(logger.is_sampled(404));
+        assert!(logger.is_sampled(500));
+        assert!(!logger.covers("/health/ready"));
+        assert!(logger.covers("/api/v1/auth/login"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Precomputed user counts of the admin dashboard
+//!
+//! Counting users on every dashboard view loads the primary more the larger
+//! the user base gets. [`refresh`] counts them in a single query and stores
+//! the result in the single-row `admin_stats` table; the scheduler runs it
+//! every [`REFRESH_INTERVAL`] on every instance (the upsert makes concurrent
+//! runs harmless). [`load`] reads the stored counts, and only counts on the
+//! spot while no refresh has run yet.
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::{Expr, OnConflict, SimpleExpr},
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QuerySelect, Set,
+};
+use std::time::Duration;
+
+use crate::models::{
+    admin_stats,
+    prelude::{AdminStats, Users},
+    sea_orm_active_enums::UserRole,
+    users,
+};
+
+/// Primary key of the only `admin_stats` row
+const STATS_ID: i32 = 1;
+
+/// How often the counts are refreshed
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Count users and store the result
+///
+/// # Errors
+///
+/// Returns an error on database failure; the previous counts are kept
+pub async fn refresh(db: &DatabaseConnection) -> Result<admin_stats::Model, DbErr> {
+    // COUNT ignores the NULL of rows not matching the condition
+    let count_where = |condition: SimpleExpr| Expr::expr(Expr::case(condition, 1)).count();
+    let (total_users, verified_users, admin_users, disabled_users): (i64, i64, i64, i64) =
+        Users::find()
+            .select_only()
+            .column_as(Expr::col(users::Column::Id).count(), "total_users")
+            .column_as(
+                count_where(users::Column::EmailVerified.eq(true)),
+                "verified_users",
+            )
+            .column_as(
+                count_where(users::Column::Role.eq(UserRole::Admin)),
+                "admin_users",
+            )
+            .column_as(
+                count_where(users::Column::DisabledAt.is_not_null()),
+                "disabled_users",
+            )
+            .into_tuple()
+            .one(db)
+            .await?
+            .unwrap_or_default();
+
+    let stats = admin_stats::Model {
+        id: STATS_ID,
+        total_users,
+        verified_users,
+        admin_users,
+        disabled_users,
+        refreshed_at: Utc::now().into(),
+    };
+    let row = admin_stats::ActiveModel {
+        id: Set(stats.id),
+        total_users: Set(stats.total_users),
+        verified_users: Set(stats.verified_users),
+        admin_users: Set(stats.admin_users),
+        disabled_users: Set(stats.disabled_users),
+        refreshed_at: Set(stats.refreshed_at),
+    };
+
+    AdminStats::insert(row)
+        .on_conflict(
+            OnConflict::column(admin_stats::Column::Id)
+                .update_columns([
+                    admin_stats::Column::TotalUsers,
+                    admin_stats::Column::VerifiedUsers,
+                    admin_stats::Column::AdminUsers,
+                    admin_stats::Column::DisabledUsers,
+                    admin_stats::Column::RefreshedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(stats)
+}
+
+/// Stored counts, or fresh ones if none are stored yet
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn load(db: &DatabaseConnection) -> Result<admin_stats::Model, DbErr> {
+    match AdminStats::find_by_id(STATS_ID).one(db).await? {
+        Some(stats) => Ok(stats),
+        None => refresh(db).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    #[tokio::test]
+    async fn test_load_reads_stored_counts() {
+        let stored = admin_stats::Model {
+            id: STATS_ID,
+            total_users: 150,
+            verified_users: 120,
+            admin_users: 3,
+            disabled_users: 5,
+            refreshed_at: Utc::now().into(),
+        };
+        // A single query: nothing is counted while counts are stored
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[stored.clone()]])
+            .into_connection();
+
+        assert_eq!(load(&db).await.unwrap(), stored);
+        assert_eq!(db.into_transaction_log().len(), 1);
+    }
+}
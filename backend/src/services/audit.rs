@@ -0,0 +1,239 @@
+//! Audit trail of security-sensitive events.
+//!
+//! Sign-ins, sign-outs, token refreshes, password changes, email
+//! verifications and admin changes to accounts are written to the
+//! `audit_logs` table with who did it ([`AuditEntry::actor`]), to whom
+//! ([`AuditEntry::target`]) and from where (IP and User-Agent). Admins read
+//! them through `GET /api/v1/admin/audit-logs`.
+//!
+//! Unlike access records (see [`super::access_log`]), audit entries are
+//! written before the response is sent and are never sampled. A failed
+//! write is logged and does not fail the request it describes.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cobalt_stack_backend::services::audit::{self, AuditEntry, AuditEvent};
+//! # async fn example(db: &sea_orm::DatabaseConnection, admin_id: uuid::Uuid, user_id: uuid::Uuid) {
+//! audit::record(
+//!     db,
+//!     AuditEntry::new(AuditEvent::UserDisabled)
+//!         .actor(admin_id)
+//!         .target(user_id),
+//! )
+//! .await;
+//! # }
+//! ```
+
+use axum::http::HeaderMap;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use std::{fmt, net::IpAddr};
+use uuid::Uuid;
+
+use crate::middleware::client_ip::ClientIp;
+use crate::models::audit_logs;
+use crate::utils::user_agent;
+
+/// Kind of audited event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditEvent {
+    /// Password or sign-in provider login
+    LoginSucceeded,
+    /// Wrong username or password
+    LoginFailed,
+    /// One session signed out (`all: true` in the details for every session)
+    Logout,
+    /// Refresh token exchanged for new tokens
+    TokenRefreshed,
+    /// An already rotated refresh token was presented again; its token
+    /// family was revoked
+    RefreshTokenReused,
+    PasswordChanged,
+    /// Username or email changed by the user (`fields` in the details)
+    ProfileUpdated,
+    EmailVerified,
+    /// An admin created an account
+    UserCreated,
+    /// An admin deleted an account (`username` in the details)
+    UserDeleted,
+    /// An admin disabled an account
+    UserDisabled,
+    /// An admin enabled an account
+    UserEnabled,
+    /// An admin changed the roles of an account
+    RolesChanged,
+    /// An admin promoted an account to admin or demoted it
+    BaseRoleChanged,
+    /// An admin opened or closed registration or toggled read-only mode
+    SwitchesChanged,
+    /// An admin minted a scoped debug token (`role`, `ttl_minutes` in the
+    /// details)
+    DebugTokenMinted,
+}
+
+impl AuditEvent {
+    pub const ALL: [Self; 16] = [
+        Self::LoginSucceeded,
+        Self::LoginFailed,
+        Self::Logout,
+        Self::TokenRefreshed,
+        Self::RefreshTokenReused,
+        Self::PasswordChanged,
+        Self::ProfileUpdated,
+        Self::EmailVerified,
+        Self::UserCreated,
+        Self::UserDeleted,
+        Self::UserDisabled,
+        Self::UserEnabled,
+        Self::RolesChanged,
+        Self::BaseRoleChanged,
+        Self::SwitchesChanged,
+        Self::DebugTokenMinted,
+    ];
+
+    /// Name stored in the `event` column
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::LoginSucceeded => "auth.login",
+            Self::LoginFailed => "auth.login_failed",
+            Self::Logout => "auth.logout",
+            Self::TokenRefreshed => "auth.token_refreshed",
+            Self::RefreshTokenReused => "auth.refresh_token_reused",
+            Self::PasswordChanged => "auth.password_changed",
+            Self::ProfileUpdated => "auth.profile_updated",
+            Self::EmailVerified => "auth.email_verified",
+            Self::UserCreated => "admin.user_created",
+            Self::UserDeleted => "admin.user_deleted",
+            Self::UserDisabled => "admin.user_disabled",
+            Self::UserEnabled => "admin.user_enabled",
+            Self::RolesChanged => "admin.roles_changed",
+            Self::BaseRoleChanged => "admin.base_role_changed",
+            Self::SwitchesChanged => "admin.switches_changed",
+            Self::DebugTokenMinted => "admin.debug_token_minted",
+        }
+    }
+
+    /// Event stored as `name`
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == name)
+    }
+}
+
+impl fmt::Display for AuditEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One audited event, built with the methods below
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub event: AuditEvent,
+    /// User who did it (`None` when unknown, e.g. a failed login)
+    pub actor_id: Option<Uuid>,
+    /// User it was done to, if not the actor
+    pub target_id: Option<Uuid>,
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+    /// Event-specific fields
+    pub details: Option<serde_json::Value>,
+}
+
+impl AuditEntry {
+    #[must_use]
+    pub const fn new(event: AuditEvent) -> Self {
+        Self {
+            event,
+            actor_id: None,
+            target_id: None,
+            ip: None,
+            user_agent: None,
+            details: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn actor(mut self, user_id: Uuid) -> Self {
+        self.actor_id = Some(user_id);
+        self
+    }
+
+    #[must_use]
+    pub const fn target(mut self, user_id: Uuid) -> Self {
+        self.target_id = Some(user_id);
+        self
+    }
+
+    /// Client address and the (truncated) User-Agent of the request
+    #[must_use]
+    pub fn client(mut self, client_ip: ClientIp, headers: &HeaderMap) -> Self {
+        self.ip = client_ip.0;
+        self.user_agent = user_agent::from_headers(headers);
+        self
+    }
+
+    #[must_use]
+    pub fn details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// Write `entry` to the `audit_logs` table
+///
+/// Errors are logged, never returned: the event already happened.
+pub async fn record(db: &DatabaseConnection, entry: AuditEntry) {
+    let event = entry.event;
+    let row = audit_logs::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        event: Set(event.as_str().to_string()),
+        actor_id: Set(entry.actor_id),
+        target_id: Set(entry.target_id),
+        ip: Set(entry.ip.map(|ip| ip.to_string())),
+        user_agent: Set(entry.user_agent),
+        details: Set(entry.details),
+        created_at: Set(Utc::now().into()),
+    };
+    if let Err(e) = row.insert(db).await {
+        tracing::error!(event = %event, "Failed to write audit log entry: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::USER_AGENT;
+
+    #[test]
+    fn test_event_names_round_trip() {
+        for event in AuditEvent::ALL {
+            assert_eq!(AuditEvent::parse(event.as_str()), Some(event));
+        }
+        assert_eq!(AuditEvent::parse("auth.unknown"), None);
+    }
+
+    #[test]
+    fn test_entry_builder() {
+        let admin_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, "curl/8.7.1".parse().unwrap());
+
+        let entry = AuditEntry::new(AuditEvent::UserDisabled)
+            .actor(admin_id)
+            .target(user_id)
+            .client(ClientIp(Some([203, 0, 113, 7].into())), &headers);
+
+        assert_eq!(entry.actor_id, Some(admin_id));
+        assert_eq!(entry.target_id, Some(user_id));
+        assert_eq!(
+            entry.ip.map(|ip| ip.to_string()).as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(entry.user_agent.as_deref(), Some("curl/8.7.1"));
+        assert_eq!(entry.details, None);
+    }
+}
@@ -0,0 +1,86 @@
+//! Revoked access tokens.
+//!
+//! Access tokens are stateless and stay valid until `exp`. Logout adds the
+//! token's `jti` to the blacklist for the rest of its lifetime, and
+//! [`verify_access_token`](super::verify_access_token) rejects blacklisted
+//! tokens, so revocation takes effect on the next request.
+//!
+//! The blacklist lives in Valkey when it is connected (see
+//! [`ValkeyBlacklist`](crate::services::valkey::blacklist::ValkeyBlacklist)),
+//! shared by all instances; otherwise [`InMemoryBlacklist`] keeps it in the
+//! process, which only revokes the token on the instance that served the
+//! logout.
+
+use super::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Storage of revoked access token ids
+#[async_trait]
+pub trait TokenBlacklist: Send + Sync {
+    /// Revoke the token `jti` until `expires_at` (Unix timestamp), when it
+    /// would be rejected anyway
+    async fn add(&self, jti: Uuid, expires_at: i64) -> Result<()>;
+
+    /// Whether the token `jti` has been revoked
+    async fn contains(&self, jti: Uuid) -> Result<bool>;
+}
+
+/// Process-local [`TokenBlacklist`]
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryBlacklist {
+    revoked: Arc<Mutex<HashMap<Uuid, i64>>>,
+}
+
+impl InMemoryBlacklist {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TokenBlacklist for InMemoryBlacklist {
+    async fn add(&self, jti: Uuid, expires_at: i64) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let mut revoked = self.revoked.lock().unwrap();
+        revoked.retain(|_, until| *until > now);
+        if expires_at > now {
+            revoked.insert(jti, expires_at);
+        }
+        drop(revoked);
+        Ok(())
+    }
+
+    async fn contains(&self, jti: Uuid) -> Result<bool> {
+        let now = Utc::now().timestamp();
+        Ok(self
+            .revoked
+            .lock()
+            .unwrap()
+            .get(&jti)
+            .is_some_and(|until| *until > now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_revoked_until_expiry() {
+        let blacklist = InMemoryBlacklist::new();
+        let now = Utc::now().timestamp();
+        let (revoked, expired, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        blacklist.add(revoked, now + 60).await.unwrap();
+        blacklist.add(expired, now - 1).await.unwrap();
+
+        assert!(blacklist.contains(revoked).await.unwrap());
+        assert!(!blacklist.contains(expired).await.unwrap());
+        assert!(!blacklist.contains(other).await.unwrap());
+    }
+}
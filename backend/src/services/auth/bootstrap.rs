@@ -0,0 +1,206 @@
+//! Initial admin account of a new deployment
+//!
+//! With `BOOTSTRAP_ADMIN_EMAIL` and `BOOTSTRAP_ADMIN_PASSWORD` set (see
+//! [`BootstrapAdminConfig`]), startup makes sure the deployment has an admin,
+//! replacing the manual `seed-admin` step:
+//!
+//! 1. If an enabled admin exists, nothing is done, so the variables can stay
+//!    set after the first run
+//! 2. Otherwise, the account with the configured email is repaired: it
+//!    becomes an enabled, verified admin with the configured password
+//! 3. Otherwise, the account is created
+//!
+//! Either way the password must be changed at first login.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::hash_password;
+use crate::config::BootstrapAdminConfig;
+use crate::models::{prelude::Users, sea_orm_active_enums::UserRole, users};
+
+/// What [`bootstrap_admin`] did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootstrapOutcome {
+    /// An enabled admin exists, nothing was changed
+    AdminExists,
+    /// The admin account was created
+    Created(Uuid),
+    /// The account with the configured email was made an admin again
+    Repaired(Uuid),
+}
+
+/// Create or repair the configured admin account, unless an enabled admin
+/// exists
+///
+/// # Errors
+///
+/// Returns an error if the password is too weak (see [`hash_password`]),
+/// the username is taken by another account, or on database failure
+pub async fn bootstrap_admin(
+    db: &DatabaseConnection,
+    config: &BootstrapAdminConfig,
+) -> Result<BootstrapOutcome> {
+    if admin_exists(db).await? {
+        return Ok(BootstrapOutcome::AdminExists);
+    }
+
+    let password_hash =
+        hash_password(&config.password).context("BOOTSTRAP_ADMIN_PASSWORD is too weak")?;
+
+    match create_or_repair(db, config, password_hash).await {
+        Ok(outcome) => Ok(outcome),
+        // Another instance starting at the same time got there first
+        Err(_) if admin_exists(db).await? => Ok(BootstrapOutcome::AdminExists),
+        Err(e) => Err(e),
+    }
+}
+
+async fn admin_exists(db: &DatabaseConnection) -> Result<bool> {
+    let admins = Users::find()
+        .filter(users::Column::Role.eq(UserRole::Admin))
+        .filter(users::Column::DisabledAt.is_null())
+        .count(db)
+        .await?;
+    Ok(admins > 0)
+}
+
+async fn create_or_repair(
+    db: &DatabaseConnection,
+    config: &BootstrapAdminConfig,
+    password_hash: String,
+) -> Result<BootstrapOutcome> {
+    let now = Utc::now();
+    let txn = db.begin().await?;
+
+    let existing = Users::find()
+        .filter(users::Column::Email.eq(&config.email))
+        .one(&txn)
+        .await?;
+    let outcome = if let Some(user) = existing {
+        let mut user: users::ActiveModel = user.into();
+        user.role = Set(UserRole::Admin);
+        user.email_verified = Set(true);
+        user.disabled_at = Set(None);
+        user.password_hash = Set(Some(password_hash));
+        user.password_rotation_required = Set(true);
+        user.updated_at = Set(now.into());
+        let user = user.update(&txn).await?;
+        BootstrapOutcome::Repaired(user.id)
+    } else {
+        let user = users::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            username: Set(config.username.clone()),
+            email: Set(config.email.clone()),
+            password_hash: Set(Some(password_hash)),
+            role: Set(UserRole::Admin),
+            email_verified: Set(true),
+            password_rotation_required: Set(true),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .with_context(|| format!("Could not create admin account {}", config.username))?;
+        BootstrapOutcome::Created(user.id)
+    };
+
+    txn.commit().await?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use std::collections::BTreeMap;
+
+    fn config() -> BootstrapAdminConfig {
+        BootstrapAdminConfig {
+            email: "admin@example.com".to_string(),
+            username: "admin".to_string(),
+            password: "first-run-password".to_string(),
+        }
+    }
+
+    fn count(n: i64) -> [BTreeMap<String, sea_orm::Value>; 1] {
+        [BTreeMap::from([(
+            "num_items".to_string(),
+            sea_orm::Value::BigInt(Some(n)),
+        )])]
+    }
+
+    fn user(role: UserRole, disabled: bool) -> users::Model {
+        let now = Utc::now();
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "admin@example.com".to_string(),
+            password_hash: Some("hash".to_string()),
+            email_verified: false,
+            created_at: now.into(),
+            updated_at: now.into(),
+            role,
+            disabled_at: disabled.then(|| now.into()),
+            last_login_at: None,
+            tokens_not_before: None,
+            password_changed_at: None,
+            password_rotation_required: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_skipped_once_an_admin_exists() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([count(1)])
+            .into_connection();
+
+        let outcome = bootstrap_admin(&db, &config()).await.unwrap();
+
+        assert_eq!(outcome, BootstrapOutcome::AdminExists);
+        // No hashing, no write
+        assert_eq!(db.into_transaction_log().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_repairs_disabled_admin() {
+        let disabled = user(UserRole::Admin, true);
+        let repaired = users::Model {
+            disabled_at: None,
+            email_verified: true,
+            password_rotation_required: true,
+            ..disabled.clone()
+        };
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([count(0)])
+            .append_query_results([vec![disabled.clone()], vec![repaired]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let outcome = bootstrap_admin(&db, &config()).await.unwrap();
+
+        assert_eq!(outcome, BootstrapOutcome::Repaired(disabled.id));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_weak_password() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([count(0)])
+            .into_connection();
+        let config = BootstrapAdminConfig {
+            password: "short".to_string(),
+            ..config()
+        };
+
+        assert!(bootstrap_admin(&db, &config).await.is_err());
+    }
+}
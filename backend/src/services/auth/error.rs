@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -13,23 +13,37 @@ use serde_json::json;
 ///
 /// # Error Categories
 ///
-/// - **Authentication**: `InvalidCredentials`, `TokenExpired`, `InvalidToken`
-/// - **Authorization**: `EmailNotVerified`, `TokenBlacklisted`
-/// - **User Management**: `UserAlreadyExists`, `UserNotFound`
+/// - **Authentication**: `InvalidCredentials`, `TokenExpired`, `InvalidToken`,
+///   `InvalidDpopProof`
+/// - **Authorization**: `EmailNotVerified`, `PasswordExpired`, `TokenBlacklisted`,
+///   `TokenBindingMismatch`, `TokenReuseDetected`, `RegistrationClosed`
+/// - **User Management**: `UserAlreadyExists`, `UserNotFound`, `DeviceNotFound`,
+///   `SessionNotFound`
 /// - **Input Validation**: `InvalidInput`, `WeakPassword`
+/// - **Social Login**: `OAuthProviderError`, `OAuthAccountConflict`
 /// - **Infrastructure**: `DatabaseError`, `RedisError`, `InternalError`
-/// - **Rate Limiting**: `RateLimitExceeded`
+/// - **Rate Limiting**: `RateLimitExceeded`, `GuestLimitExceeded`, `VerificationCooldown`
 ///
 /// # HTTP Status Mapping
 ///
 /// | Error | HTTP Status |
 /// |-------|-------------|
 /// | `InvalidCredentials` | 401 Unauthorized |
+/// | `InvalidDpopProof` | 401 Unauthorized |
+/// | `TokenReuseDetected` | 401 Unauthorized |
 /// | `UserAlreadyExists` | 409 Conflict |
 /// | `UserNotFound` | 404 Not Found |
+/// | `DeviceNotFound` | 404 Not Found |
+/// | `SessionNotFound` | 404 Not Found |
 /// | `EmailNotVerified` | 403 Forbidden |
+/// | `PasswordExpired` | 403 Forbidden |
+/// | `RegistrationClosed` | 403 Forbidden |
 /// | `RateLimitExceeded` | 429 Too Many Requests |
+/// | `GuestLimitExceeded` | 429 Too Many Requests |
+/// | `VerificationCooldown` | 429 Too Many Requests (with `Retry-After`) |
 /// | `InvalidInput` | 400 Bad Request |
+/// | `OAuthAccountConflict` | 409 Conflict |
+/// | `OAuthProviderError` | 502 Bad Gateway |
 /// | `DatabaseError` | 500 Internal Server Error |
 ///
 /// # Examples
@@ -81,6 +95,22 @@ pub enum AuthError {
     #[error("User not found")]
     UserNotFound,
 
+    /// Trusted device not found among the user's devices.
+    ///
+    /// Returned when removing a device that does not exist or belongs to
+    /// another user.
+    /// Maps to HTTP 404 Not Found.
+    #[error("Device not found")]
+    DeviceNotFound,
+
+    /// Session not found among the user's active sessions.
+    ///
+    /// Returned when revoking a session that does not exist, has ended, or
+    /// belongs to another user.
+    /// Maps to HTTP 404 Not Found.
+    #[error("Session not found")]
+    SessionNotFound,
+
     /// JWT token has expired and is no longer valid.
     ///
     /// Returned when token's `exp` claim is in the past.
@@ -102,6 +132,28 @@ pub enum AuthError {
     #[error("Token blacklisted")]
     TokenBlacklisted,
 
+    /// Refresh token presented by a client other than the one it was bound to.
+    ///
+    /// Every session of the user has been revoked (suspected cookie theft).
+    /// Maps to HTTP 401 Unauthorized.
+    #[error("Token binding mismatch")]
+    TokenBindingMismatch,
+
+    /// An already rotated or revoked refresh token was presented again.
+    ///
+    /// Every token of its family has been revoked (suspected token theft);
+    /// the user's other sessions are kept.
+    /// Maps to HTTP 401 Unauthorized.
+    #[error("Refresh token reuse detected")]
+    TokenReuseDetected,
+
+    /// `DPoP` proof missing, malformed or not matching the request or token.
+    ///
+    /// Returned for tokens bound to a key (see [`super::dpop`]).
+    /// Maps to HTTP 401 Unauthorized.
+    #[error("Invalid DPoP proof")]
+    InvalidDpopProof,
+
     /// Too many authentication attempts from this IP/user.
     ///
     /// Returned when rate limit is exceeded (e.g., 5 login attempts in 15 minutes).
@@ -109,6 +161,22 @@ pub enum AuthError {
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
+    /// Too many guest accounts were created from this IP address.
+    ///
+    /// Returned by the guest token endpoint once the per-IP limit of the
+    /// window is reached.
+    /// Maps to HTTP 429 Too Many Requests.
+    #[error("Guest limit exceeded")]
+    GuestLimitExceeded,
+
+    /// A verification email was sent too recently.
+    ///
+    /// Returned when resending a verification email within the cooldown.
+    /// Maps to HTTP 429 Too Many Requests with a `Retry-After` header and
+    /// `retry_after_secs` in the body.
+    #[error("Verification email cooldown: retry after {retry_after_secs}s")]
+    VerificationCooldown { retry_after_secs: u64 },
+
     /// User's email address has not been verified.
     ///
     /// Returned when accessing protected resources requiring email verification.
@@ -116,6 +184,29 @@ pub enum AuthError {
     #[error("Email not verified")]
     EmailNotVerified,
 
+    /// User's password has expired and must be changed.
+    ///
+    /// Returned for any request but a password change made with an access
+    /// token carrying the `password_expired` claim.
+    /// Maps to HTTP 403 Forbidden.
+    #[error("Password expired")]
+    PasswordExpired,
+
+    /// An admin closed registration of new accounts.
+    ///
+    /// Returned by sign-up, and by social login for an identity without an
+    /// account. Existing accounts still sign in.
+    /// Maps to HTTP 403 Forbidden.
+    #[error("Registration closed")]
+    RegistrationClosed,
+
+    /// Email delivery is disabled on this deployment.
+    ///
+    /// Returned by email endpoints when `FEATURE_EMAIL_ENABLED=false`.
+    /// Maps to HTTP 404 Not Found.
+    #[error("Email is disabled")]
+    EmailDisabled,
+
     /// Password does not meet complexity requirements.
     ///
     /// Returned when password is too short, weak, or common.
@@ -130,6 +221,19 @@ pub enum AuthError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    /// A social login provider could not be reached or rejected the code.
+    ///
+    /// Maps to HTTP 502 Bad Gateway; the detail is only logged.
+    #[error("OAuth provider error: {0}")]
+    OAuthProviderError(String),
+
+    /// A social login matched a local account whose email is not verified.
+    ///
+    /// The account is not linked, since anyone could have registered the
+    /// address. Maps to HTTP 409 Conflict.
+    #[error("OAuth account conflict")]
+    OAuthAccountConflict,
+
     /// Database operation failed.
     ///
     /// Wraps `SeaORM` database errors. Details are logged but not exposed to client.
@@ -180,16 +284,48 @@ impl IntoResponse for AuthError {
             Self::InvalidCredentials => (StatusCode::UNAUTHORIZED, "Invalid credentials"),
             Self::UserAlreadyExists => (StatusCode::CONFLICT, "User already exists"),
             Self::UserNotFound => (StatusCode::NOT_FOUND, "User not found"),
+            Self::DeviceNotFound => (StatusCode::NOT_FOUND, "Device not found"),
+            Self::SessionNotFound => (StatusCode::NOT_FOUND, "Session not found"),
             Self::TokenExpired => (StatusCode::UNAUTHORIZED, "Token expired"),
             Self::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid token"),
-            Self::TokenBlacklisted => (StatusCode::UNAUTHORIZED, "Token has been revoked"),
+            Self::InvalidDpopProof => (StatusCode::UNAUTHORIZED, "Invalid DPoP proof"),
+            Self::TokenReuseDetected => (
+                StatusCode::UNAUTHORIZED,
+                "Refresh token reuse detected; sign in again",
+            ),
+            Self::TokenBlacklisted | Self::TokenBindingMismatch => {
+                (StatusCode::UNAUTHORIZED, "Token has been revoked")
+            }
             Self::RateLimitExceeded => (StatusCode::TOO_MANY_REQUESTS, "Too many login attempts"),
+            Self::GuestLimitExceeded => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many guest sessions from this address; try again later",
+            ),
+            Self::VerificationCooldown { .. } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Please wait before requesting another verification email",
+            ),
             Self::EmailNotVerified => (StatusCode::FORBIDDEN, "Email not verified"),
+            Self::PasswordExpired => (
+                StatusCode::FORBIDDEN,
+                "Password expired; change it to continue",
+            ),
+            Self::RegistrationClosed => (
+                StatusCode::FORBIDDEN,
+                "Registration of new accounts is closed",
+            ),
+            Self::EmailDisabled => (StatusCode::NOT_FOUND, "Email is disabled on this server"),
             Self::WeakPassword => (
                 StatusCode::BAD_REQUEST,
                 "Password does not meet security requirements",
             ),
             Self::InvalidInput(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
+            Self::OAuthProviderError(_) => (StatusCode::BAD_GATEWAY, "Sign-in provider failed"),
+            Self::OAuthAccountConflict => (
+                StatusCode::CONFLICT,
+                "An account with this email exists; sign in with your password and verify \
+                 your email to link it",
+            ),
             Self::DatabaseError(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Database operation failed",
@@ -209,6 +345,19 @@ impl IntoResponse for AuthError {
             Self::InternalError => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error"),
         };
 
+        if let Self::VerificationCooldown { retry_after_secs } = self {
+            let body = Json(json!({
+                "error": message,
+                "retry_after_secs": retry_after_secs,
+            }));
+            return (
+                status,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                body,
+            )
+                .into_response();
+        }
+
         let body = Json(json!({
             "error": message,
         }));
@@ -225,6 +374,22 @@ impl From<sea_orm::DbErr> for AuthError {
     }
 }
 
+/// Convert social login errors to `AuthError`
+impl From<crate::services::oauth::OAuthError> for AuthError {
+    fn from(err: crate::services::oauth::OAuthError) -> Self {
+        use crate::services::oauth::OAuthError;
+        match err {
+            OAuthError::Provider(msg) => Self::OAuthProviderError(msg),
+            OAuthError::NoVerifiedEmail => Self::InvalidInput(
+                "The sign-in provider reported no verified email address".to_string(),
+            ),
+            OAuthError::AccountConflict => Self::OAuthAccountConflict,
+            OAuthError::RegistrationClosed => Self::RegistrationClosed,
+            OAuthError::Database(err) => err.into(),
+        }
+    }
+}
+
 /// Application-level Result type using anyhow for flexible error propagation
 pub type Result<T> = anyhow::Result<T>;
 
@@ -262,10 +427,36 @@ mod tests {
         let response = AuthError::RateLimitExceeded.into_response();
         assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
 
+        let response = AuthError::EmailDisabled.into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = AuthError::TokenReuseDetected.into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = AuthError::RegistrationClosed.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
         let response = AuthError::DatabaseError("test".to_string()).into_response();
         assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    #[tokio::test]
+    async fn test_verification_cooldown_response() {
+        let response = AuthError::VerificationCooldown {
+            retry_after_secs: 42,
+        }
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "42");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["retry_after_secs"], 42);
+    }
+
     #[test]
     fn test_database_error_conversion() {
         let db_err = sea_orm::DbErr::Custom("test error".to_string());
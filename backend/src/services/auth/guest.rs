@@ -0,0 +1,297 @@
+//! Anonymous guest accounts for demo deployments
+//!
+//! With guest mode on (see [`GuestConfig`]), a visitor can chat without
+//! registering. [`create_guest`] creates a user without password, marked as a
+//! guest by its `guest_accounts` record, and the handler issues it a guest
+//! access token ([`create_guest_access_token`](super::create_guest_access_token)).
+//! The auth middleware accepts that token only on routes open to guests, and
+//! [`GuestQuota`] caps the messages the guest sends.
+//!
+//! After registering, the visitor hands the guest token to the claim endpoint:
+//! [`claim_guest`] moves the guest's chat sessions to the new account and
+//! deletes the guest. Guests not claimed before their token expires are
+//! deleted with their sessions by [`purge_expired`].
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, Query},
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    Set, TransactionTrait,
+};
+use std::{net::IpAddr, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::config::GuestConfig;
+use crate::models::{
+    chat_sessions, chat_usage, guest_accounts,
+    prelude::{ChatSessions, ChatUsage, GuestAccounts, Users},
+    sea_orm_active_enums::UserRole,
+    users,
+};
+
+/// How often expired guests are purged
+pub const PURGE_INTERVAL: Duration = Duration::from_secs(900);
+
+/// A newly created guest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestAccount {
+    pub user_id: Uuid,
+    pub username: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Create a guest user, unless `ip` already created
+/// [`GuestConfig::max_per_ip`] guests within [`GuestConfig::ip_window`]
+///
+/// Guests whose IP address is unknown share one allowance. Claimed and
+/// purged guests no longer count.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn create_guest(
+    db: &DatabaseConnection,
+    config: &GuestConfig,
+    ip: Option<IpAddr>,
+) -> Result<Option<GuestAccount>> {
+    let now = Utc::now();
+    let ip = ip.map(|ip| ip.to_string());
+
+    let window_start = now - chrono::Duration::from_std(config.ip_window)?;
+    let recent = GuestAccounts::find()
+        .filter(ip.as_deref().map_or_else(
+            || guest_accounts::Column::Ip.is_null(),
+            |ip| guest_accounts::Column::Ip.eq(ip),
+        ))
+        .filter(guest_accounts::Column::CreatedAt.gte(window_start))
+        .count(db)
+        .await?;
+    if recent >= u64::from(config.max_per_ip) {
+        tracing::warn!(
+            ip = ip.as_deref().unwrap_or("unknown"),
+            "Guest limit reached"
+        );
+        return Ok(None);
+    }
+
+    let id = Uuid::new_v4();
+    let simple = id.simple().to_string();
+    let username = format!("guest_{}", &simple[..12]);
+    let expires_at = now + chrono::Duration::from_std(config.token_ttl)?;
+
+    let txn = db.begin().await?;
+    users::ActiveModel {
+        id: Set(id),
+        username: Set(username.clone()),
+        // Reserved TLD: never deliverable, never a registered address
+        email: Set(format!("{simple}@guest.invalid")),
+        password_hash: Set(None),
+        role: Set(UserRole::User),
+        email_verified: Set(false),
+        password_rotation_required: Set(false),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    guest_accounts::ActiveModel {
+        user_id: Set(id),
+        ip: Set(ip.clone()),
+        messages_sent: Set(0),
+        expires_at: Set(expires_at.into()),
+        created_at: Set(now.into()),
+    }
+    .insert(&txn)
+    .await?;
+    txn.commit().await?;
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.guest_created",
+        user_id = %id,
+        ip = ip.as_deref().unwrap_or("unknown"),
+        "Guest created"
+    );
+
+    Ok(Some(GuestAccount {
+        user_id: id,
+        username,
+        expires_at,
+    }))
+}
+
+/// Move the chat sessions of guest `guest_id` to `owner_id` and delete the
+/// guest
+///
+/// Returns the number of sessions moved, or `None` if there is no unexpired
+/// guest with this id (already claimed, purged, or not a guest).
+///
+/// # Errors
+///
+/// Returns an error on database failure; nothing is moved then
+pub async fn claim_guest(
+    db: &DatabaseConnection,
+    guest_id: Uuid,
+    owner_id: Uuid,
+) -> Result<Option<u64>> {
+    let txn = db.begin().await?;
+
+    let guest = GuestAccounts::find_by_id(guest_id)
+        .filter(guest_accounts::Column::ExpiresAt.gt(Utc::now()))
+        .one(&txn)
+        .await?;
+    if guest.is_none() {
+        return Ok(None);
+    }
+
+    let sessions = ChatSessions::update_many()
+        .col_expr(chat_sessions::Column::UserId, Expr::value(owner_id))
+        .filter(chat_sessions::Column::UserId.eq(guest_id))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+    // Usage follows the sessions, so analytics and costs stay complete
+    ChatUsage::update_many()
+        .col_expr(chat_usage::Column::UserId, Expr::value(owner_id))
+        .filter(chat_usage::Column::UserId.eq(guest_id))
+        .exec(&txn)
+        .await?;
+    Users::delete_by_id(guest_id).exec(&txn).await?;
+
+    txn.commit().await?;
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.guest_claimed",
+        user_id = %owner_id,
+        guest_id = %guest_id,
+        sessions,
+        "Guest sessions claimed"
+    );
+
+    Ok(Some(sessions))
+}
+
+/// Delete guests whose token has expired, with their chat sessions
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn purge_expired(db: &DatabaseConnection) -> Result<u64> {
+    let expired = Query::select()
+        .column(guest_accounts::Column::UserId)
+        .from(GuestAccounts)
+        .and_where(guest_accounts::Column::ExpiresAt.lte(Utc::now()))
+        .to_owned();
+
+    Ok(Users::delete_many()
+        .filter(users::Column::Id.in_subquery(expired))
+        .exec(db)
+        .await?
+        .rows_affected)
+}
+
+/// Message allowance of guests
+#[derive(Clone)]
+pub struct GuestQuota {
+    db: Arc<DatabaseConnection>,
+    max_messages: u32,
+}
+
+impl GuestQuota {
+    #[must_use]
+    pub const fn new(db: Arc<DatabaseConnection>, max_messages: u32) -> Self {
+        Self { db, max_messages }
+    }
+
+    /// Count one message of guest `user_id`
+    ///
+    /// Returns `false`, counting nothing, once the guest has used its
+    /// allowance or no longer exists. Attempts count, whether or not the
+    /// message is then accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on database failure
+    pub async fn consume(&self, user_id: Uuid) -> Result<bool> {
+        let limit = i32::try_from(self.max_messages).unwrap_or(i32::MAX);
+        let counted = GuestAccounts::update_many()
+            .col_expr(
+                guest_accounts::Column::MessagesSent,
+                Expr::col(guest_accounts::Column::MessagesSent).add(1),
+            )
+            .filter(guest_accounts::Column::UserId.eq(user_id))
+            .filter(guest_accounts::Column::MessagesSent.lt(limit))
+            .filter(guest_accounts::Column::ExpiresAt.gt(Utc::now()))
+            .exec(self.db.as_ref())
+            .await?
+            .rows_affected;
+        Ok(counted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use std::collections::BTreeMap;
+
+    fn config() -> GuestConfig {
+        GuestConfig {
+            token_ttl: Duration::from_secs(3600),
+            max_messages: 5,
+            max_per_ip: 2,
+            ip_window: Duration::from_secs(3600),
+        }
+    }
+
+    fn exec(rows_affected: u64) -> MockExecResult {
+        MockExecResult {
+            last_insert_id: 0,
+            rows_affected,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_guest_limited_per_ip() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[BTreeMap::from([(
+                "num_items".to_string(),
+                sea_orm::Value::BigInt(Some(2)),
+            )])]])
+            .into_connection();
+
+        let created = create_guest(&db, &config(), Some("203.0.113.7".parse().unwrap()))
+            .await
+            .unwrap();
+
+        assert!(created.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_consume_stops_at_limit() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([exec(1), exec(0)])
+            .into_connection();
+        let quota = GuestQuota::new(Arc::new(db), 5);
+        let guest = Uuid::new_v4();
+
+        assert!(quota.consume(guest).await.unwrap());
+        assert!(!quota.consume(guest).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_claim_unknown_guest() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<guest_accounts::Model>::new()])
+            .into_connection();
+
+        let claimed = claim_guest(&db, Uuid::new_v4(), Uuid::new_v4())
+            .await
+            .unwrap();
+
+        assert_eq!(claimed, None);
+    }
+}
@@ -13,8 +13,13 @@
 //!
 //! - HMAC-SHA256 signature algorithm (HS256)
 //! - Configurable secret key from environment
-//! - Token expiration validation
+//! - Token expiration validation, with configurable clock leeway, required
+//!   `nbf` and a maximum token age (see [`TokenValidation`])
+//! - Per-user cutoff rejecting access tokens issued before a password change
+//!   (see [`NotBeforeStore`])
+//! - Revocation of single access tokens by `jti` on logout (see [`TokenBlacklist`])
 //! - Token rotation via jti tracking
+//! - Optional binding of tokens to a client-held key (see [`super::dpop`])
 //!
 //! # Examples
 //!
@@ -25,17 +30,17 @@
 //! };
 //! use uuid::Uuid;
 //!
-//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! // Load configuration
 //! let config = JwtConfig::from_env();
 //!
 //! // Create tokens
 //! let user_id = Uuid::new_v4();
 //! let access_token = create_access_token(user_id, "alice".to_string(), &config)?;
-//! let (refresh_token, jti) = create_refresh_token(user_id, &config)?;
+//! let (refresh_token, jti) = create_refresh_token(user_id.into(), &config)?;
 //!
 //! // Verify tokens
-//! let access_claims = verify_access_token(&access_token, &config)?;
+//! let access_claims = verify_access_token(&access_token, &config).await?;
 //! let refresh_claims = verify_refresh_token(&refresh_token, &config)?;
 //!
 //! assert_eq!(access_claims.sub, user_id);
@@ -44,10 +49,16 @@
 //! # }
 //! ```
 
+use super::blacklist::TokenBlacklist;
+use super::dpop::{Confirmation, DpopPolicy, DpopVerifier};
+use super::not_before::NotBeforeStore;
 use super::{AuthError, Result};
+use crate::domain::ids::{TokenId, UserId};
+use crate::models::sea_orm_active_enums::UserRole;
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// JWT claims for access tokens.
@@ -60,7 +71,16 @@ use uuid::Uuid;
 /// - `sub`: User ID (UUID) - standard JWT subject claim
 /// - `exp`: Expiration timestamp (Unix epoch) - standard JWT expiration claim
 /// - `iat`: Issued at timestamp (Unix epoch) - standard JWT issued-at claim
+/// - `nbf`: Not-before timestamp (Unix epoch) - standard JWT not-before claim
+/// - `jti`: Token ID (UUID) for revocation on logout - standard JWT ID claim
 /// - `username`: Username string for convenience (custom claim)
+/// - `scope`: Optional restriction for admin-minted debug tokens (custom claim)
+/// - `password_expired`: Token only accepted by the change-password endpoint
+///   (custom claim)
+/// - `guest`: Token of an anonymous guest, only accepted by routes open to
+///   guests (custom claim)
+/// - `cnf`: Key the token is bound to, only accepted with a `DPoP` proof
+///   (RFC 9449 confirmation claim)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AccessTokenClaims {
     /// User ID (subject of the token).
@@ -74,9 +94,49 @@ pub struct AccessTokenClaims {
     /// When the token was created.
     pub iat: i64,
 
+    /// Not valid before this Unix timestamp.
+    /// Set to `iat` on issued tokens; absent on tokens issued before it was added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
+
+    /// Token ID, the key of the token in the blacklist.
+    /// Set on issued tokens; absent on tokens issued before it was added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<Uuid>,
+
     /// Username for convenience in handlers.
     /// Avoids additional database lookups.
     pub username: String,
+
+    /// Scope restriction for debug tokens.
+    /// Absent on regular access tokens issued by login/refresh.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<TokenScope>,
+
+    /// Set when the user's password has expired.
+    /// Absent on regular access tokens; see [`create_password_change_token`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub password_expired: bool,
+
+    /// Set on tokens of guest-mode users.
+    /// Absent on regular access tokens; see [`create_guest_access_token`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub guest: bool,
+
+    /// Key of the client the token is bound to.
+    /// Absent on bearer tokens; see [`create_bound_access_token`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cnf: Option<Confirmation>,
+}
+
+/// Restriction embedded in short-lived debug tokens minted by admins.
+///
+/// Debug tokens always keep the minting admin as subject (no impersonation
+/// of other accounts); the scope only narrows what the token may do.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TokenScope {
+    /// Effective role granted by the token.
+    pub role: UserRole,
 }
 
 /// JWT claims for refresh tokens.
@@ -90,10 +150,11 @@ pub struct AccessTokenClaims {
 /// - `exp`: Expiration timestamp (Unix epoch) - standard JWT expiration claim
 /// - `iat`: Issued at timestamp (Unix epoch) - standard JWT issued-at claim
 /// - `jti`: Token ID (UUID) for rotation/revocation - standard JWT ID claim
+/// - `cnf`: Key the token is bound to, only refreshed with a `DPoP` proof
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RefreshTokenClaims {
     /// User ID (subject of the token).
-    pub sub: Uuid,
+    pub sub: UserId,
 
     /// Expiration time as Unix timestamp.
     /// Token is invalid after this time.
@@ -105,9 +166,17 @@ pub struct RefreshTokenClaims {
 
     /// Token ID for rotation tracking.
     /// Matches `refresh_tokens.id` in database.
-    pub jti: Uuid,
+    pub jti: TokenId,
+
+    /// Key of the client the token is bound to.
+    /// Absent on unbound tokens; see [`create_bound_refresh_token`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cnf: Option<Confirmation>,
 }
 
+/// Secret used when `JWT_SECRET` is unset (development only)
+pub const DEV_JWT_SECRET: &str = "dev_secret_key_change_in_production";
+
 /// JWT configuration loaded from environment variables.
 ///
 /// Controls token expiration times and signing secret.
@@ -118,11 +187,13 @@ pub struct RefreshTokenClaims {
 /// - `JWT_SECRET`: HMAC secret key (required in production)
 /// - `JWT_ACCESS_EXPIRY_MINUTES`: Access token lifetime (default: 30)
 /// - `JWT_REFRESH_EXPIRY_DAYS`: Refresh token lifetime (default: 7)
+/// - Access token validation, see [`TokenValidation::from_env`]
+/// - Key binding, see [`DpopPolicy::from_env`]
 ///
 /// # Examples
 ///
 /// ```no_run
-/// use cobalt_stack::services::auth::jwt::JwtConfig;
+/// use cobalt_stack::services::auth::jwt::{JwtConfig, TokenValidation};
 ///
 /// // Load from environment
 /// let config = JwtConfig::from_env();
@@ -132,6 +203,10 @@ pub struct RefreshTokenClaims {
 ///     secret: "test_secret".to_string(),
 ///     access_token_expiry_minutes: 15,
 ///     refresh_token_expiry_days: 7,
+///     validation: TokenValidation::default(),
+///     not_before: None,
+///     blacklist: None,
+///     dpop: None,
 /// };
 /// ```
 #[derive(Clone)]
@@ -147,6 +222,91 @@ pub struct JwtConfig {
     /// Refresh token lifetime in days.
     /// Longer lifetimes improve UX but increase risk if compromised.
     pub refresh_token_expiry_days: i64,
+
+    /// Clock and claim checks applied to access tokens.
+    pub validation: TokenValidation,
+
+    /// Per-user cutoff for access tokens (`None` skips the check).
+    /// Set in `main` once the database is connected.
+    pub not_before: Option<Arc<dyn NotBeforeStore>>,
+
+    /// Access tokens revoked by logout (`None` skips the check).
+    /// Set in `main`, on Valkey when it is connected.
+    pub blacklist: Option<Arc<dyn TokenBlacklist>>,
+
+    /// Checks `DPoP` proofs (`None` when `DPOP_ENABLED` is off).
+    /// Shared, so a proof is accepted once across clones of the config.
+    pub dpop: Option<Arc<DpopVerifier>>,
+}
+
+/// Clock and claim checks for access tokens, beyond signature and `exp`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenValidation {
+    /// Clock skew tolerated when checking `exp`, `nbf`, `iat` and token age
+    pub leeway_secs: u64,
+    /// Reject tokens without an `nbf` claim
+    pub require_nbf: bool,
+    /// Reject tokens issued longer ago than this, even if `exp` is later
+    pub max_age_secs: Option<i64>,
+}
+
+impl Default for TokenValidation {
+    /// The `jsonwebtoken` defaults: 60 seconds leeway, `nbf` optional
+    fn default() -> Self {
+        Self {
+            leeway_secs: 60,
+            require_nbf: false,
+            max_age_secs: None,
+        }
+    }
+}
+
+impl TokenValidation {
+    /// Load validation settings from environment variables
+    ///
+    /// - `JWT_STRICT`: Zero leeway and required `nbf`, for high-security
+    ///   deployments (default: false); overrides the two settings below
+    /// - `JWT_LEEWAY_SECS`: Tolerated clock skew (default: 60)
+    /// - `JWT_REQUIRE_NBF`: Reject tokens without `nbf` (default: false)
+    /// - `JWT_MAX_TOKEN_AGE_SECS`: Reject tokens older than this regardless
+    ///   of `exp` (default: unset)
+    ///
+    /// # Panics
+    /// Panics if a variable is set but cannot be parsed
+    #[must_use]
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().map(|value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|_| panic!("{name} has an invalid value: {value}"))
+            })
+        }
+
+        let defaults = Self::default();
+        let max_age_secs = var::<i64>("JWT_MAX_TOKEN_AGE_SECS");
+        assert!(
+            max_age_secs.map_or(true, |secs| secs > 0),
+            "JWT_MAX_TOKEN_AGE_SECS must be positive"
+        );
+
+        if var("JWT_STRICT").unwrap_or(false) {
+            return Self {
+                leeway_secs: 0,
+                require_nbf: true,
+                max_age_secs,
+            };
+        }
+        Self {
+            leeway_secs: var("JWT_LEEWAY_SECS").unwrap_or(defaults.leeway_secs),
+            require_nbf: var("JWT_REQUIRE_NBF").unwrap_or(defaults.require_nbf),
+            max_age_secs,
+        }
+    }
+
+    fn leeway(self) -> i64 {
+        i64::try_from(self.leeway_secs).unwrap_or(i64::MAX)
+    }
 }
 
 impl JwtConfig {
@@ -155,7 +315,7 @@ impl JwtConfig {
         Self {
             secret: std::env::var("JWT_SECRET").unwrap_or_else(|_| {
                 tracing::warn!("JWT_SECRET not set, using default (INSECURE FOR PRODUCTION!)");
-                "dev_secret_key_change_in_production".to_string()
+                DEV_JWT_SECRET.to_string()
             }),
             access_token_expiry_minutes: std::env::var("JWT_ACCESS_EXPIRY_MINUTES")
                 .ok()
@@ -165,25 +325,132 @@ impl JwtConfig {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(7),
+            validation: TokenValidation::from_env(),
+            not_before: None,
+            blacklist: None,
+            dpop: DpopPolicy::from_env().map(|policy| Arc::new(DpopVerifier::new(policy))),
         }
     }
 }
 
 /// Create an access token
 pub fn create_access_token(user_id: Uuid, username: String, config: &JwtConfig) -> Result<String> {
+    encode_access_token(
+        user_id,
+        username,
+        None,
+        false,
+        Duration::minutes(config.access_token_expiry_minutes),
+        config,
+    )
+}
+
+/// Create an access token for a user whose password has expired
+///
+/// The token carries the `password_expired` claim, so the auth middleware
+/// only lets it through to the change-password endpoint.
+pub fn create_password_change_token(
+    user_id: Uuid,
+    username: String,
+    config: &JwtConfig,
+) -> Result<String> {
+    encode_access_token(
+        user_id,
+        username,
+        None,
+        true,
+        Duration::minutes(config.access_token_expiry_minutes),
+        config,
+    )
+}
+
+/// Create a scoped access token with an explicit lifetime
+///
+/// Used for admin debug tokens; the caller is responsible for bounding `ttl`.
+pub fn create_scoped_access_token(
+    user_id: Uuid,
+    username: String,
+    scope: TokenScope,
+    ttl: Duration,
+    config: &JwtConfig,
+) -> Result<String> {
+    encode_access_token(user_id, username, Some(scope), false, ttl, config)
+}
+
+/// Create an access token for a guest-mode user
+///
+/// The token carries the `guest` claim, so the auth middleware only lets it
+/// through to routes open to guests. Guests get no refresh token; the token
+/// lives as long as the guest.
+pub fn create_guest_access_token(
+    user_id: Uuid,
+    username: String,
+    ttl: Duration,
+    config: &JwtConfig,
+) -> Result<String> {
+    let mut claims = access_token_claims(user_id, username, None, false, ttl);
+    claims.guest = true;
+    sign_access_token(&claims, config)
+}
+
+/// Create an access token bound to the client key with thumbprint `jkt`
+///
+/// The token carries the `cnf` claim, so the auth middleware only accepts it
+/// with a `DPoP` proof signed by that key. `password_expired` restricts it
+/// as [`create_password_change_token`] does.
+pub fn create_bound_access_token(
+    user_id: Uuid,
+    username: String,
+    password_expired: bool,
+    jkt: String,
+    config: &JwtConfig,
+) -> Result<String> {
+    let ttl = Duration::minutes(config.access_token_expiry_minutes);
+    let mut claims = access_token_claims(user_id, username, None, password_expired, ttl);
+    claims.cnf = Some(Confirmation { jkt });
+    sign_access_token(&claims, config)
+}
+
+fn encode_access_token(
+    user_id: Uuid,
+    username: String,
+    scope: Option<TokenScope>,
+    password_expired: bool,
+    ttl: Duration,
+    config: &JwtConfig,
+) -> Result<String> {
+    let claims = access_token_claims(user_id, username, scope, password_expired, ttl);
+    sign_access_token(&claims, config)
+}
+
+fn access_token_claims(
+    user_id: Uuid,
+    username: String,
+    scope: Option<TokenScope>,
+    password_expired: bool,
+    ttl: Duration,
+) -> AccessTokenClaims {
     let now = Utc::now();
-    let exp = now + Duration::minutes(config.access_token_expiry_minutes);
+    let exp = now + ttl;
 
-    let claims = AccessTokenClaims {
+    AccessTokenClaims {
         sub: user_id,
         username,
         exp: exp.timestamp(),
         iat: now.timestamp(),
-    };
+        nbf: Some(now.timestamp()),
+        jti: Some(Uuid::new_v4()),
+        scope,
+        password_expired,
+        guest: false,
+        cnf: None,
+    }
+}
 
+fn sign_access_token(claims: &AccessTokenClaims, config: &JwtConfig) -> Result<String> {
     encode(
         &Header::default(),
-        &claims,
+        claims,
         &EncodingKey::from_secret(config.secret.as_bytes()),
     )
     .map_err(|e| {
@@ -193,16 +460,36 @@ pub fn create_access_token(user_id: Uuid, username: String, config: &JwtConfig)
 }
 
 /// Create a refresh token
-pub fn create_refresh_token(user_id: Uuid, config: &JwtConfig) -> Result<(String, Uuid)> {
+pub fn create_refresh_token(user_id: UserId, config: &JwtConfig) -> Result<(String, TokenId)> {
+    encode_refresh_token(user_id, None, config)
+}
+
+/// Create a refresh token bound to the client key with thumbprint `jkt`
+///
+/// Refreshing it requires a `DPoP` proof signed by that key.
+pub fn create_bound_refresh_token(
+    user_id: UserId,
+    jkt: String,
+    config: &JwtConfig,
+) -> Result<(String, TokenId)> {
+    encode_refresh_token(user_id, Some(Confirmation { jkt }), config)
+}
+
+fn encode_refresh_token(
+    user_id: UserId,
+    cnf: Option<Confirmation>,
+    config: &JwtConfig,
+) -> Result<(String, TokenId)> {
     let now = Utc::now();
     let exp = now + Duration::days(config.refresh_token_expiry_days);
-    let jti = Uuid::new_v4();
+    let jti = TokenId::new();
 
     let claims = RefreshTokenClaims {
         sub: user_id,
         exp: exp.timestamp(),
         iat: now.timestamp(),
         jti,
+        cnf,
     };
 
     let token = encode(
@@ -219,11 +506,63 @@ pub fn create_refresh_token(user_id: Uuid, config: &JwtConfig) -> Result<(String
 }
 
 /// Verify and decode an access token
-pub fn verify_access_token(token: &str, config: &JwtConfig) -> Result<AccessTokenClaims> {
-    let token_data = decode::<AccessTokenClaims>(
+///
+/// Checks the signature and time claims (see [`decode_access_token`]), then
+/// rejects tokens issued before the user's not-before timestamp when
+/// [`JwtConfig::not_before`] is set, and revoked tokens when
+/// [`JwtConfig::blacklist`] is set.
+///
+/// # Errors
+/// - [`AuthError::TokenExpired`] if past `exp` or the maximum token age
+/// - [`AuthError::TokenBlacklisted`] if issued before the user's cutoff or
+///   revoked
+/// - [`AuthError::InvalidToken`] for any other validation failure
+pub async fn verify_access_token(token: &str, config: &JwtConfig) -> Result<AccessTokenClaims> {
+    let claims = decode_access_token(token, config)?;
+
+    if let Some(store) = &config.not_before {
+        if let Some(not_before) = store.not_before(claims.sub).await? {
+            // `iat` has second precision, so tokens issued in the same second
+            // as the cutoff (e.g. right after a password change) stay valid
+            if claims.iat < not_before.timestamp() {
+                tracing::debug!(user_id = %claims.sub, "Access token issued before user cutoff");
+                return Err(AuthError::TokenBlacklisted.into());
+            }
+        }
+    }
+
+    if let (Some(blacklist), Some(jti)) = (&config.blacklist, claims.jti) {
+        if blacklist.contains(jti).await? {
+            tracing::debug!(user_id = %claims.sub, "Access token revoked");
+            return Err(AuthError::TokenBlacklisted.into());
+        }
+    }
+
+    Ok(claims)
+}
+
+/// Decode an access token, checking its signature and time claims only
+///
+/// Applies [`JwtConfig::validation`] but not the per-user cutoff, so a
+/// token revoked by a password change still decodes. Use
+/// [`verify_access_token`] to authenticate requests.
+///
+/// # Errors
+/// - [`AuthError::TokenExpired`] if past `exp` or the maximum token age
+/// - [`AuthError::InvalidToken`] for any other validation failure
+pub fn decode_access_token(token: &str, config: &JwtConfig) -> Result<AccessTokenClaims> {
+    let rules = config.validation;
+    let mut validation = Validation::default();
+    validation.leeway = rules.leeway_secs;
+    validation.validate_nbf = true;
+    if rules.require_nbf {
+        validation.set_required_spec_claims(&["exp", "nbf"]);
+    }
+
+    let claims = decode::<AccessTokenClaims>(
         token,
         &DecodingKey::from_secret(config.secret.as_bytes()),
-        &Validation::default(),
+        &validation,
     )
     .map_err(|e| {
         tracing::debug!("JWT decoding failed: {:?}", e);
@@ -231,9 +570,21 @@ pub fn verify_access_token(token: &str, config: &JwtConfig) -> Result<AccessToke
             jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
             _ => AuthError::InvalidToken,
         }
-    })?;
+    })?
+    .claims;
 
-    Ok(token_data.claims)
+    let now = Utc::now().timestamp();
+    if claims.iat > now.saturating_add(rules.leeway()) {
+        tracing::debug!("JWT issued in the future: iat {}", claims.iat);
+        return Err(AuthError::InvalidToken.into());
+    }
+    if let Some(max_age) = rules.max_age_secs {
+        if now.saturating_sub(claims.iat) > max_age.saturating_add(rules.leeway()) {
+            return Err(AuthError::TokenExpired.into());
+        }
+    }
+
+    Ok(claims)
 }
 
 /// Verify and decode a refresh token
@@ -257,12 +608,17 @@ pub fn verify_refresh_token(token: &str, config: &JwtConfig) -> Result<RefreshTo
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::auth::blacklist::InMemoryBlacklist;
 
     fn test_config() -> JwtConfig {
         JwtConfig {
             secret: "test_secret_key".to_string(),
             access_token_expiry_minutes: 30,
             refresh_token_expiry_days: 7,
+            validation: TokenValidation::default(),
+            not_before: None,
+            blacklist: None,
+            dpop: None,
         }
     }
 
@@ -278,32 +634,93 @@ mod tests {
         assert_eq!(token.split('.').count(), 3);
     }
 
-    #[test]
-    fn test_verify_access_token_valid() {
+    #[tokio::test]
+    async fn test_verify_access_token_valid() {
         let config = test_config();
         let user_id = Uuid::new_v4();
         let username = "testuser".to_string();
 
         let token = create_access_token(user_id, username.clone(), &config).unwrap();
-        let claims = verify_access_token(&token, &config).unwrap();
+        let claims = verify_access_token(&token, &config).await.unwrap();
 
         assert_eq!(claims.sub, user_id);
         assert_eq!(claims.username, username);
         assert!(claims.exp > Utc::now().timestamp());
+        assert!(claims.scope.is_none());
     }
 
-    #[test]
-    fn test_verify_access_token_invalid() {
+    #[tokio::test]
+    async fn test_scoped_access_token_roundtrip() {
+        let config = test_config();
+        let user_id = Uuid::new_v4();
+        let scope = TokenScope {
+            role: UserRole::User,
+        };
+
+        let token = create_scoped_access_token(
+            user_id,
+            "admin".to_string(),
+            scope.clone(),
+            Duration::minutes(5),
+            &config,
+        )
+        .unwrap();
+        let claims = verify_access_token(&token, &config).await.unwrap();
+
+        assert_eq!(claims.sub, user_id);
+        assert_eq!(claims.scope, Some(scope));
+        let expected_exp = Utc::now().timestamp() + 300;
+        assert!((claims.exp - expected_exp).abs() < 5);
+    }
+
+    #[tokio::test]
+    async fn test_password_change_token_is_flagged() {
+        let config = test_config();
+        let user_id = Uuid::new_v4();
+
+        let token = create_password_change_token(user_id, "alice".to_string(), &config).unwrap();
+        let claims = verify_access_token(&token, &config).await.unwrap();
+        assert!(claims.password_expired);
+
+        // Regular tokens omit the claim
+        let token = create_access_token(user_id, "alice".to_string(), &config).unwrap();
+        let claims = verify_access_token(&token, &config).await.unwrap();
+        assert!(!claims.password_expired);
+    }
+
+    #[tokio::test]
+    async fn test_guest_token_is_flagged() {
+        let config = test_config();
+        let user_id = Uuid::new_v4();
+
+        let token = create_guest_access_token(
+            user_id,
+            "guest_1a2b3c".to_string(),
+            Duration::minutes(10),
+            &config,
+        )
+        .unwrap();
+        let claims = verify_access_token(&token, &config).await.unwrap();
+        assert!(claims.guest);
+        assert!(claims.exp - claims.iat <= 600);
+
+        let token = create_access_token(user_id, "alice".to_string(), &config).unwrap();
+        let claims = verify_access_token(&token, &config).await.unwrap();
+        assert!(!claims.guest);
+    }
+
+    #[tokio::test]
+    async fn test_verify_access_token_invalid() {
         let config = test_config();
-        let result = verify_access_token("invalid.token.here", &config);
+        let result = verify_access_token("invalid.token.here", &config).await;
 
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.to_string().contains("Invalid token"));
     }
 
-    #[test]
-    fn test_verify_access_token_wrong_secret() {
+    #[tokio::test]
+    async fn test_verify_access_token_wrong_secret() {
         let config = test_config();
         let user_id = Uuid::new_v4();
         let username = "testuser".to_string();
@@ -316,14 +733,14 @@ mod tests {
             ..config
         };
 
-        let result = verify_access_token(&token, &wrong_config);
+        let result = verify_access_token(&token, &wrong_config).await;
         assert!(result.is_err());
     }
 
     #[test]
     fn test_create_refresh_token() {
         let config = test_config();
-        let user_id = Uuid::new_v4();
+        let user_id = UserId::new();
 
         let (token, jti) = create_refresh_token(user_id, &config).unwrap();
 
@@ -331,13 +748,13 @@ mod tests {
         assert_eq!(token.split('.').count(), 3);
 
         // jti should be valid UUID
-        assert_ne!(jti, Uuid::nil());
+        assert_ne!(jti.into_uuid(), Uuid::nil());
     }
 
     #[test]
     fn test_verify_refresh_token_valid() {
         let config = test_config();
-        let user_id = Uuid::new_v4();
+        let user_id = UserId::new();
 
         let (token, jti) = create_refresh_token(user_id, &config).unwrap();
         let claims = verify_refresh_token(&token, &config).unwrap();
@@ -358,7 +775,7 @@ mod tests {
     #[test]
     fn test_refresh_tokens_have_different_jti() {
         let config = test_config();
-        let user_id = Uuid::new_v4();
+        let user_id = UserId::new();
 
         let (_, jti1) = create_refresh_token(user_id, &config).unwrap();
         let (_, jti2) = create_refresh_token(user_id, &config).unwrap();
@@ -373,17 +790,18 @@ mod tests {
         let _config = JwtConfig::from_env();
     }
 
-    #[test]
-    fn test_access_token_expiry() {
+    #[tokio::test]
+    async fn test_access_token_expiry() {
         let config = JwtConfig {
             secret: "test_secret".to_string(),
             access_token_expiry_minutes: 1,
             refresh_token_expiry_days: 7,
+            ..test_config()
         };
 
         let user_id = Uuid::new_v4();
         let token = create_access_token(user_id, "test".to_string(), &config).unwrap();
-        let claims = verify_access_token(&token, &config).unwrap();
+        let claims = verify_access_token(&token, &config).await.unwrap();
 
         let expected_exp = Utc::now().timestamp() + 60;
         // Allow 5 second tolerance
@@ -396,9 +814,10 @@ mod tests {
             secret: "test_secret".to_string(),
             access_token_expiry_minutes: 30,
             refresh_token_expiry_days: 1,
+            ..test_config()
         };
 
-        let user_id = Uuid::new_v4();
+        let user_id = UserId::new();
         let (token, _) = create_refresh_token(user_id, &config).unwrap();
         let claims = verify_refresh_token(&token, &config).unwrap();
 
@@ -406,4 +825,164 @@ mod tests {
                                                            // Allow 5 second tolerance
         assert!((claims.exp - expected_exp).abs() < 5);
     }
+
+    /// Sign access token claims as-is, bypassing `encode_access_token`
+    fn sign(claims: &AccessTokenClaims, config: &JwtConfig) -> String {
+        encode(
+            &Header::default(),
+            claims,
+            &EncodingKey::from_secret(config.secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    fn claims_issued_at(iat: i64) -> AccessTokenClaims {
+        AccessTokenClaims {
+            sub: Uuid::new_v4(),
+            exp: Utc::now().timestamp() + 3600,
+            iat,
+            nbf: None,
+            jti: None,
+            username: "alice".to_string(),
+            scope: None,
+            password_expired: false,
+            guest: false,
+            cnf: None,
+        }
+    }
+
+    #[test]
+    fn test_require_nbf() {
+        let config = JwtConfig {
+            validation: TokenValidation {
+                require_nbf: true,
+                ..TokenValidation::default()
+            },
+            ..test_config()
+        };
+        let now = Utc::now().timestamp();
+
+        let legacy = sign(&claims_issued_at(now), &config);
+        assert!(decode_access_token(&legacy, &config).is_err());
+
+        let issued = create_access_token(Uuid::new_v4(), "alice".to_string(), &config).unwrap();
+        assert!(decode_access_token(&issued, &config).is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode_has_no_leeway() {
+        let lenient = test_config();
+        let strict = JwtConfig {
+            validation: TokenValidation {
+                leeway_secs: 0,
+                require_nbf: true,
+                max_age_secs: None,
+            },
+            ..test_config()
+        };
+        let now = Utc::now().timestamp();
+
+        let mut not_yet_valid = claims_issued_at(now);
+        not_yet_valid.nbf = Some(now + 30);
+        let token = sign(&not_yet_valid, &lenient);
+        assert!(decode_access_token(&token, &lenient).is_ok());
+        assert!(decode_access_token(&token, &strict).is_err());
+
+        let mut just_expired = claims_issued_at(now - 600);
+        just_expired.nbf = Some(now - 600);
+        just_expired.exp = now - 30;
+        let token = sign(&just_expired, &lenient);
+        assert!(decode_access_token(&token, &lenient).is_ok());
+        assert!(decode_access_token(&token, &strict).is_err());
+    }
+
+    #[test]
+    fn test_max_token_age() {
+        let config = JwtConfig {
+            validation: TokenValidation {
+                max_age_secs: Some(600),
+                ..TokenValidation::default()
+            },
+            ..test_config()
+        };
+        let now = Utc::now().timestamp();
+
+        let fresh = sign(&claims_issued_at(now - 60), &config);
+        assert!(decode_access_token(&fresh, &config).is_ok());
+
+        // Still before `exp`, but older than the maximum age
+        let stale = sign(&claims_issued_at(now - 3600), &config);
+        let err = decode_access_token(&stale, &config).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AuthError>(),
+            Some(AuthError::TokenExpired)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_token_issued_in_future() {
+        let config = test_config();
+        let token = sign(&claims_issued_at(Utc::now().timestamp() + 3600), &config);
+
+        assert!(decode_access_token(&token, &config).is_err());
+    }
+
+    struct FixedNotBefore(Option<chrono::DateTime<Utc>>);
+
+    #[async_trait::async_trait]
+    impl NotBeforeStore for FixedNotBefore {
+        async fn not_before(&self, _user_id: Uuid) -> Result<Option<chrono::DateTime<Utc>>> {
+            Ok(self.0)
+        }
+
+        async fn bump(&self, _user_id: Uuid) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_token_issued_before_user_cutoff() {
+        let now = Utc::now();
+        let config = JwtConfig {
+            not_before: Some(Arc::new(FixedNotBefore(Some(now)))),
+            ..test_config()
+        };
+
+        let before = sign(&claims_issued_at(now.timestamp() - 60), &config);
+        let err = verify_access_token(&before, &config).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AuthError>(),
+            Some(AuthError::TokenBlacklisted)
+        ));
+        // Decoding alone does not consult the cutoff
+        assert!(decode_access_token(&before, &config).is_ok());
+
+        let after = sign(&claims_issued_at(now.timestamp()), &config);
+        assert!(verify_access_token(&after, &config).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_blacklisted_token() {
+        let blacklist = Arc::new(InMemoryBlacklist::new());
+        let config = JwtConfig {
+            blacklist: Some(blacklist.clone()),
+            ..test_config()
+        };
+        let token = create_access_token(Uuid::new_v4(), "alice".to_string(), &config).unwrap();
+        let claims = verify_access_token(&token, &config).await.unwrap();
+
+        blacklist
+            .add(claims.jti.unwrap(), claims.exp)
+            .await
+            .unwrap();
+
+        let err = verify_access_token(&token, &config).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AuthError>(),
+            Some(AuthError::TokenBlacklisted)
+        ));
+        // Other tokens of the user are unaffected
+        let other = create_access_token(claims.sub, "alice".to_string(), &config).unwrap();
+        assert!(verify_access_token(&other, &config).await.is_ok());
+    }
 }
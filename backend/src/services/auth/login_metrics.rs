@@ -0,0 +1,86 @@
+//! Sign-in counters in the Prometheus text format.
+//!
+//! Counted by sign-in method (`password` or the social login provider) and
+//! outcome, so a spike of failures (credential stuffing) or a provider that
+//! stopped working shows up on a dashboard.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Mutex, PoisonError},
+};
+
+/// Method label of username/email and password sign-ins
+pub const PASSWORD_METHOD: &str = "password";
+
+/// Sign-in attempts by method and outcome
+#[derive(Debug, Default)]
+pub struct LoginMetrics {
+    /// By method and outcome (`success` or `failure`)
+    attempts: Mutex<BTreeMap<(String, &'static str), u64>>,
+}
+
+impl LoginMetrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a sign-in attempt with `method`
+    pub fn record(&self, method: &str, success: bool) {
+        let outcome = if success { "success" } else { "failure" };
+        let mut attempts = self.attempts.lock().unwrap_or_else(PoisonError::into_inner);
+        *attempts.entry((method.to_string(), outcome)).or_default() += 1;
+    }
+
+    /// Attempts recorded with `method` and outcome
+    #[must_use]
+    pub fn attempts_total(&self, method: &str, success: bool) -> u64 {
+        let outcome = if success { "success" } else { "failure" };
+        let attempts = self.attempts.lock().unwrap_or_else(PoisonError::into_inner);
+        attempts
+            .get(&(method.to_string(), outcome))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP auth_logins_total Sign-in attempts by method and outcome.\n");
+        out.push_str("# TYPE auth_logins_total counter\n");
+        let attempts = self.attempts.lock().unwrap_or_else(PoisonError::into_inner);
+        for ((method, outcome), count) in &*attempts {
+            let _ = writeln!(
+                out,
+                "auth_logins_total{{method=\"{method}\",outcome=\"{outcome}\"}} {count}"
+            );
+        }
+        drop(attempts);
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_by_method_and_outcome() {
+        let metrics = LoginMetrics::new();
+        metrics.record(PASSWORD_METHOD, true);
+        metrics.record(PASSWORD_METHOD, false);
+        metrics.record(PASSWORD_METHOD, false);
+        metrics.record("github", true);
+
+        assert_eq!(metrics.attempts_total(PASSWORD_METHOD, false), 2);
+        assert_eq!(metrics.attempts_total("google", true), 0);
+
+        let output = metrics.render();
+        assert!(output.contains("auth_logins_total{method=\"password\",outcome=\"failure\"} 2"));
+        assert!(output.contains("auth_logins_total{method=\"github\",outcome=\"success\"} 1"));
+    }
+}
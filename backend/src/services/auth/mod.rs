@@ -9,10 +9,23 @@
 //!
 //! The authentication service is organized into submodules:
 //!
+//! - **blacklist**: Access tokens revoked by logout, checked until they expire
+//! - **bootstrap**: Initial admin account created at startup while there is no admin
+//! - **dpop**: Proof-of-possession binding tokens to a client-held key
 //! - **error**: Domain-specific error types and HTTP mapping
+//! - **guest**: Anonymous guest accounts of the guest mode, claimed by a
+//!   registered account
 //! - **jwt**: JSON Web Token creation and verification
+//! - **`login_metrics`**: Sign-in counters by method and outcome, rendered at `/metrics`
+//! - **`not_before`**: Per-user cutoff rejecting access tokens issued before
+//!   a password change
 //! - **password**: Secure password hashing and verification with Argon2
-//! - **`token_rotation`**: Refresh token rotation and revocation
+//! - **`password_expiry`**: Maximum password age and admin-required rotation
+//! - **`token_binding`**: Client fingerprints binding refresh tokens to a device
+//! - **`trusted_devices`**: Devices confirmed by email before they receive
+//!   refresh tokens
+//! - **`token_rotation`**: Refresh token rotation, revocation and reuse detection
+//!   on a pluggable [`TokenStore`] (database or Valkey)
 //!
 //! # Security Features
 //!
@@ -20,7 +33,13 @@
 //! - **JWT tokens**: Stateless authentication with access/refresh tokens
 //! - **Token rotation**: Automatic refresh token rotation prevents theft
 //! - **Token revocation**: Individual token revocation capability
+//! - **Access token cutoff**: Tokens issued before a user's not-before time are rejected
+//! - **Access token blacklist**: Logout revokes the current access token immediately
+//! - **Password expiry**: Expired passwords restrict access tokens to the
+//!   change-password endpoint
+//! - **Trusted devices**: New devices get no refresh token until confirmed by email
 //! - **Hash storage**: Refresh tokens stored as SHA-256 hashes
+//! - **Proof of possession**: Optional `DPoP` binding of tokens to a client key
 //!
 //! # Token Flow
 //!
@@ -29,7 +48,7 @@
 //! 2. Access protected resources with access token
 //! 3. Access token expires → Use refresh token to get new pair
 //! 4. Refresh token used → Old token revoked, new token issued (rotation)
-//! 5. Logout → Refresh token revoked
+//! 5. Logout → Refresh token revoked, access token blacklisted
 //! ```
 //!
 //! # Examples
@@ -60,16 +79,34 @@
 //! All service functions return [`Result<T>`] using domain-specific [`AuthError`] types.
 //! Errors are automatically mapped to appropriate HTTP status codes via `IntoResponse`.
 
+pub mod blacklist;
+pub mod bootstrap;
+pub mod dpop;
 pub mod error;
+pub mod guest;
 pub mod jwt;
+pub mod login_metrics;
+pub mod not_before;
 pub mod password;
+pub mod password_expiry;
+pub mod token_binding;
 pub mod token_rotation;
+pub mod trusted_devices;
 
+pub use blacklist::{InMemoryBlacklist, TokenBlacklist};
 pub use error::{AuthError, Result};
 pub use jwt::{
-    create_access_token, create_refresh_token, verify_access_token, verify_refresh_token, JwtConfig,
+    create_access_token, create_bound_access_token, create_bound_refresh_token,
+    create_guest_access_token, create_password_change_token, create_refresh_token,
+    create_scoped_access_token, decode_access_token, verify_access_token, verify_refresh_token,
+    JwtConfig, TokenScope, DEV_JWT_SECRET,
 };
+pub use login_metrics::LoginMetrics;
+pub use not_before::NotBeforeStore;
 pub use password::{hash_password, verify_password};
+pub use password_expiry::{password_status, PasswordExpiryPolicy, PasswordStatus};
 pub use token_rotation::{
-    revoke_refresh_token, rotate_refresh_token, store_refresh_token, validate_refresh_token,
+    list_active_sessions, revoke_refresh_token, revoke_user_session, rotate_refresh_token,
+    store_refresh_token, validate_refresh_token, SeaOrmTokenStore, TokenClient, TokenStore,
 };
+pub use trusted_devices::{DeviceClient, TrustedDevicePolicy};
@@ -0,0 +1,49 @@
+//! Per-user cutoff for access tokens.
+//!
+//! Access tokens are stateless and stay valid until `exp`. To sign a user out
+//! everywhere (e.g. after a password change) their cutoff is moved to now:
+//! [`verify_access_token`](super::verify_access_token) then rejects every
+//! token the user was issued before it. The cutoff lives in the
+//! `users.tokens_not_before` column, so it applies to all instances.
+//!
+//! The server checks it through
+//! [`UserLoader`](crate::middleware::current_user::UserLoader), which reads
+//! the cutoff from the user's row and its Valkey cache: checking every
+//! authenticated request costs no query of its own.
+
+use super::Result;
+use crate::models::{prelude::*, users};
+use async_trait::async_trait;
+use chrono::{DateTime, SubsecRound, Utc};
+use sea_orm::{sea_query::Expr, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+/// Storage of per-user access token cutoffs
+#[async_trait]
+pub trait NotBeforeStore: Send + Sync {
+    /// The user's cutoff, `None` if never set (or no such user)
+    async fn not_before(&self, user_id: Uuid) -> Result<Option<DateTime<Utc>>>;
+
+    /// Move the user's cutoff to now, rejecting every access token issued so far
+    async fn bump(&self, user_id: Uuid) -> Result<()>;
+}
+
+/// Move the cutoff of `user_id` to now in the `users` table
+///
+/// Cached copies of the row still hold the old cutoff; callers drop them.
+///
+/// # Errors
+/// Returns an error on database failure
+pub async fn bump(db: &DatabaseConnection, user_id: Uuid) -> Result<()> {
+    // Whole seconds, like `iat`: tokens issued later in the same second
+    // are kept (see `verify_access_token`)
+    Users::update_many()
+        .col_expr(
+            users::Column::TokensNotBefore,
+            Expr::value(Utc::now().trunc_subsecs(0)),
+        )
+        .filter(users::Column::Id.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
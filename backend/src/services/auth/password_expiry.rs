@@ -0,0 +1,188 @@
+//! Password maximum age and forced rotation.
+//!
+//! A password older than [`PasswordExpiryPolicy::max_age`] still signs the
+//! user in during the grace window, with a deadline in the login response.
+//! Past the grace window, or when an admin requires a rotation, login issues
+//! an access token flagged `password_expired` that only the change-password
+//! endpoint accepts.
+//!
+//! Accounts without a password (OAuth only) never expire.
+
+use crate::models::users;
+use chrono::{DateTime, Duration, Utc};
+
+/// Grace window after the maximum age when `PASSWORD_EXPIRY_GRACE_DAYS` is unset
+pub const DEFAULT_GRACE_DAYS: i64 = 7;
+
+/// Maximum password age, per `PASSWORD_MAX_AGE_DAYS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordExpiryPolicy {
+    /// Age after which the user is asked to change their password
+    pub max_age: Duration,
+    /// Time after `max_age` during which the user keeps full access
+    pub grace: Duration,
+}
+
+/// Where a user's password stands against the policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordStatus {
+    /// Within the maximum age, or no policy applies
+    Current,
+    /// Past the maximum age; full access until `deadline`
+    Grace { deadline: DateTime<Utc> },
+    /// Past the grace window or rotation required: change-password only
+    Expired,
+}
+
+impl PasswordExpiryPolicy {
+    /// Load the policy from environment variables
+    ///
+    /// - `PASSWORD_MAX_AGE_DAYS`: Maximum password age (default: unset,
+    ///   passwords never expire; `0` also disables the policy)
+    /// - `PASSWORD_EXPIRY_GRACE_DAYS`: Grace window after the maximum age
+    ///   (default: 7)
+    ///
+    /// Returns `None` when passwords never expire. Rotations required by an
+    /// admin are enforced either way.
+    ///
+    /// # Panics
+    /// Panics if a variable is set but is not a non-negative number of days
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        fn days(name: &str) -> Option<i64> {
+            std::env::var(name).ok().map(|value| {
+                value
+                    .parse()
+                    .ok()
+                    .filter(|days| *days >= 0)
+                    .unwrap_or_else(|| panic!("{name} must be a non-negative number of days"))
+            })
+        }
+
+        let max_age_days = days("PASSWORD_MAX_AGE_DAYS").filter(|days| *days > 0)?;
+        let grace_days = days("PASSWORD_EXPIRY_GRACE_DAYS").unwrap_or(DEFAULT_GRACE_DAYS);
+        Some(Self {
+            max_age: Duration::days(max_age_days),
+            grace: Duration::days(grace_days),
+        })
+    }
+}
+
+/// Status of `user`'s password at `now`
+#[must_use]
+pub fn password_status(
+    user: &users::Model,
+    policy: Option<&PasswordExpiryPolicy>,
+    now: DateTime<Utc>,
+) -> PasswordStatus {
+    if user.password_hash.is_none() {
+        return PasswordStatus::Current;
+    }
+    if user.password_rotation_required {
+        return PasswordStatus::Expired;
+    }
+    let Some(policy) = policy else {
+        return PasswordStatus::Current;
+    };
+
+    let changed_at = user.password_changed_at.unwrap_or(user.created_at);
+    let expires_at = changed_at.with_timezone(&Utc) + policy.max_age;
+    let deadline = expires_at + policy.grace;
+    if now < expires_at {
+        PasswordStatus::Current
+    } else if now < deadline {
+        PasswordStatus::Grace { deadline }
+    } else {
+        PasswordStatus::Expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::sea_orm_active_enums::UserRole;
+    use uuid::Uuid;
+
+    const POLICY: PasswordExpiryPolicy = PasswordExpiryPolicy {
+        max_age: Duration::days(90),
+        grace: Duration::days(7),
+    };
+
+    fn user_created(days_ago: i64) -> users::Model {
+        let created_at = Utc::now() - Duration::days(days_ago);
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password_hash: Some("hash".to_string()),
+            email_verified: true,
+            created_at: created_at.into(),
+            updated_at: created_at.into(),
+            role: UserRole::User,
+            disabled_at: None,
+            last_login_at: None,
+            tokens_not_before: None,
+            password_changed_at: None,
+            password_rotation_required: false,
+        }
+    }
+
+    #[test]
+    fn test_password_age_counts_from_last_change() {
+        let now = Utc::now();
+        let mut user = user_created(200);
+        assert_eq!(
+            password_status(&user, Some(&POLICY), now),
+            PasswordStatus::Expired
+        );
+
+        user.password_changed_at = Some((now - Duration::days(10)).into());
+        assert_eq!(
+            password_status(&user, Some(&POLICY), now),
+            PasswordStatus::Current
+        );
+    }
+
+    #[test]
+    fn test_grace_window_ends_at_deadline() {
+        let now = Utc::now();
+        let user = user_created(92);
+        let deadline = user.created_at.with_timezone(&Utc) + Duration::days(97);
+
+        assert_eq!(
+            password_status(&user, Some(&POLICY), now),
+            PasswordStatus::Grace { deadline }
+        );
+        assert_eq!(
+            password_status(&user, Some(&POLICY), deadline),
+            PasswordStatus::Expired
+        );
+    }
+
+    #[test]
+    fn test_required_rotation_expires_without_policy() {
+        let mut user = user_created(1);
+        assert_eq!(
+            password_status(&user, None, Utc::now()),
+            PasswordStatus::Current
+        );
+
+        user.password_rotation_required = true;
+        assert_eq!(
+            password_status(&user, None, Utc::now()),
+            PasswordStatus::Expired
+        );
+    }
+
+    #[test]
+    fn test_accounts_without_password_never_expire() {
+        let mut user = user_created(400);
+        user.password_hash = None;
+        user.password_rotation_required = true;
+
+        assert_eq!(
+            password_status(&user, Some(&POLICY), Utc::now()),
+            PasswordStatus::Current
+        );
+    }
+}
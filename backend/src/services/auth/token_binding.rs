@@ -0,0 +1,62 @@
+//! Binding refresh tokens to the client they were issued to.
+//!
+//! With `REFRESH_TOKEN_BINDING_ENABLED=true`, login hands the browser a
+//! long-lived, random device cookie next to the refresh token cookie. Each
+//! refresh token is stored with a fingerprint of that cookie and the
+//! client's `User-Agent`; a refresh presenting a different fingerprint means
+//! the token was replayed from another device, so
+//! [`validate_refresh_token`](super::validate_refresh_token) revokes the
+//! token's family, signing out the session it belongs to.
+//!
+//! Tokens stored without a fingerprint (issued before binding was enabled)
+//! are accepted once and bound on rotation.
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Cookie holding the device identifier
+pub const DEVICE_COOKIE: &str = "device_id";
+
+/// Lifetime of the device cookie
+pub const DEVICE_COOKIE_MAX_AGE_DAYS: i64 = 400;
+
+/// A new random device identifier
+#[must_use]
+pub fn new_device_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Fingerprint of a client: hex SHA-256 of its `User-Agent` and device cookie
+///
+/// A missing header or cookie hashes as empty, so it only matches a token
+/// issued to a client that also lacked it.
+#[must_use]
+pub fn fingerprint(user_agent: Option<&str>, device_id: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(user_agent.unwrap_or_default().as_bytes());
+    // Separator keeps ("ab", "c") and ("a", "bc") apart
+    hasher.update([0]);
+    hasher.update(device_id.unwrap_or_default().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        let device = new_device_id();
+        let chrome = fingerprint(Some("Chrome"), Some(&device));
+
+        assert_eq!(chrome.len(), 64);
+        assert_eq!(chrome, fingerprint(Some("Chrome"), Some(&device)));
+        assert_ne!(chrome, fingerprint(Some("Firefox"), Some(&device)));
+        assert_ne!(chrome, fingerprint(Some("Chrome"), Some(&new_device_id())));
+        assert_ne!(chrome, fingerprint(Some("Chrome"), None));
+        assert_ne!(
+            fingerprint(Some("ab"), Some("c")),
+            fingerprint(Some("a"), Some("bc"))
+        );
+    }
+}
@@ -1,63 +1,257 @@
+//! Refresh token rotation, revocation and reuse detection.
+//!
+//! Refresh tokens are kept in a [`TokenStore`]: the `refresh_tokens` table by
+//! default ([`SeaOrmTokenStore`]), or Valkey when the deployment runs with
+//! `REFRESH_TOKEN_STORE=valkey` (see
+//! [`ValkeyTokenStore`](crate::services::valkey::token_store::ValkeyTokenStore)).
+//! The functions in this module hold the rotation rules and work the same on
+//! either store.
+//!
+//! # Reuse detection
+//!
+//! A refresh token is single-use. Every token belongs to a family: the chain
+//! of tokens rotated from one sign-in, identified by the `jti` of its first
+//! token. Presenting a token that was already rotated or revoked means it was
+//! copied, so its whole family is revoked, an `audit` warning is logged and
+//! [`AuthError::TokenReuseDetected`] is returned. The user's other sessions
+//! stay signed in. Two requests racing to rotate the same token are treated
+//! the same way: the store's [`TokenStore::revoke`] is atomic, so only one of
+//! them wins.
+
 use super::{AuthError, Result};
+use crate::domain::ids::{TokenId, UserId};
 use crate::models::{prelude::*, refresh_tokens};
+use crate::utils::token::{constant_time_eq, hash_token, verify_token_hash};
+use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
-use sha2::{Digest, Sha256};
-use uuid::Uuid;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use std::sync::Arc;
+
+/// Outcome of [`TokenStore::revoke`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Revocation {
+    /// The token was active and is now revoked
+    Revoked,
+    /// The token had been revoked before
+    AlreadyRevoked,
+    /// No token with this id is stored
+    NotFound,
+}
 
-/// Store a refresh token in the database
+/// Storage of issued refresh tokens
 ///
-/// The token is hashed before storage for security
+/// Tokens are stored by hash only and keyed by their `jti`.
+#[async_trait]
+pub trait TokenStore: Send + Sync {
+    /// Save a newly issued token
+    async fn insert(&self, token: refresh_tokens::Model) -> Result<()>;
+
+    /// Look up a token by `jti`
+    async fn find(&self, jti: TokenId) -> Result<Option<refresh_tokens::Model>>;
+
+    /// Mark a token revoked
+    ///
+    /// Must be atomic: when two callers revoke the same active token, exactly
+    /// one of them gets [`Revocation::Revoked`].
+    async fn revoke(&self, jti: TokenId) -> Result<Revocation>;
+
+    /// Revoke every active token of a user, returning how many were revoked
+    async fn revoke_all(&self, user_id: UserId) -> Result<u64>;
+
+    /// Revoke every active token of one of a user's token families, returning
+    /// how many were revoked
+    async fn revoke_family(&self, user_id: UserId, family_id: TokenId) -> Result<u64>;
+
+    /// A user's unrevoked, unexpired tokens, newest first
+    async fn list_active(&self, user_id: UserId) -> Result<Vec<refresh_tokens::Model>>;
+
+    /// Delete tokens expired for more than `retention_days`, returning how many
+    async fn cleanup_expired(&self, retention_days: i64) -> Result<u64>;
+}
+
+/// [`TokenStore`] on the `refresh_tokens` table
+#[derive(Clone)]
+pub struct SeaOrmTokenStore {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmTokenStore {
+    #[must_use]
+    pub const fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TokenStore for SeaOrmTokenStore {
+    async fn insert(&self, token: refresh_tokens::Model) -> Result<()> {
+        let token: refresh_tokens::ActiveModel = token.into();
+        RefreshTokens::insert(token).exec(self.db.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn find(&self, jti: TokenId) -> Result<Option<refresh_tokens::Model>> {
+        Ok(RefreshTokens::find_by_id(jti.into_uuid())
+            .one(self.db.as_ref())
+            .await?)
+    }
+
+    async fn revoke(&self, jti: TokenId) -> Result<Revocation> {
+        // Conditional update, so concurrent revocations cannot both succeed
+        let result = RefreshTokens::update_many()
+            .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(refresh_tokens::Column::Id.eq(jti.into_uuid()))
+            .filter(refresh_tokens::Column::RevokedAt.is_null())
+            .exec(self.db.as_ref())
+            .await?;
+
+        if result.rows_affected > 0 {
+            return Ok(Revocation::Revoked);
+        }
+        Ok(match self.find(jti).await? {
+            Some(_) => Revocation::AlreadyRevoked,
+            None => Revocation::NotFound,
+        })
+    }
+
+    async fn revoke_all(&self, user_id: UserId) -> Result<u64> {
+        let result = RefreshTokens::update_many()
+            .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(refresh_tokens::Column::UserId.eq(user_id.into_uuid()))
+            .filter(refresh_tokens::Column::RevokedAt.is_null())
+            .exec(self.db.as_ref())
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn revoke_family(&self, user_id: UserId, family_id: TokenId) -> Result<u64> {
+        let result = RefreshTokens::update_many()
+            .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(refresh_tokens::Column::UserId.eq(user_id.into_uuid()))
+            .filter(refresh_tokens::Column::FamilyId.eq(family_id.into_uuid()))
+            .filter(refresh_tokens::Column::RevokedAt.is_null())
+            .exec(self.db.as_ref())
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn list_active(&self, user_id: UserId) -> Result<Vec<refresh_tokens::Model>> {
+        Ok(RefreshTokens::find()
+            .filter(refresh_tokens::Column::UserId.eq(user_id.into_uuid()))
+            .filter(refresh_tokens::Column::RevokedAt.is_null())
+            .filter(refresh_tokens::Column::ExpiresAt.gt(Utc::now()))
+            .order_by_desc(refresh_tokens::Column::CreatedAt)
+            .all(self.db.as_ref())
+            .await?)
+    }
+
+    async fn cleanup_expired(&self, retention_days: i64) -> Result<u64> {
+        let cutoff = Utc::now() - Duration::days(retention_days);
+
+        let result = RefreshTokens::delete_many()
+            .filter(refresh_tokens::Column::ExpiresAt.lt(cutoff))
+            .exec(self.db.as_ref())
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+/// Client a refresh token is issued to
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenClient<'a> {
+    /// Raw `User-Agent` header, shown as a device label in the sessions list
+    pub user_agent: Option<&'a str>,
+    /// Fingerprint to bind the token to (see [`super::token_binding`]),
+    /// `None` when binding is disabled
+    pub fingerprint: Option<&'a str>,
+    /// Client IP address, shown in the sessions list
+    pub ip: Option<&'a str>,
+}
+
+/// Store a refresh token
+///
+/// The token is hashed before storage for security. It starts a new token
+/// family named after its `jti`.
 pub async fn store_refresh_token(
-    db: &DatabaseConnection,
-    user_id: Uuid,
+    store: &dyn TokenStore,
+    user_id: UserId,
     token: &str,
-    jti: Uuid,
+    jti: TokenId,
     expires_in_days: i64,
+    client: TokenClient<'_>,
 ) -> Result<()> {
-    let token_hash = hash_token(token);
-    let expires_at = Utc::now() + Duration::days(expires_in_days);
-
-    let refresh_token = refresh_tokens::ActiveModel {
-        id: Set(jti),
-        user_id: Set(user_id),
-        token_hash: Set(token_hash),
-        expires_at: Set(expires_at.into()),
-        revoked_at: Set(None),
-        created_at: Set(Utc::now().into()),
-    };
+    store
+        .insert(issued_token(
+            user_id,
+            token,
+            jti,
+            expires_in_days,
+            client,
+            jti,
+            Utc::now(),
+        ))
+        .await
+}
 
-    refresh_token.insert(db).await?;
-    Ok(())
+/// Token issued at `now` in the family of a session signed in at
+/// `signed_in_at`
+fn issued_token(
+    user_id: UserId,
+    token: &str,
+    jti: TokenId,
+    expires_in_days: i64,
+    client: TokenClient<'_>,
+    family_id: TokenId,
+    signed_in_at: DateTime<Utc>,
+) -> refresh_tokens::Model {
+    let now = Utc::now();
+    refresh_tokens::Model {
+        id: jti.into_uuid(),
+        user_id: user_id.into_uuid(),
+        family_id: family_id.into_uuid(),
+        token_hash: hash_token(token),
+        expires_at: (now + Duration::days(expires_in_days)).into(),
+        revoked_at: None,
+        created_at: signed_in_at.into(),
+        user_agent: client.user_agent.map(ToString::to_string),
+        fingerprint: client.fingerprint.map(ToString::to_string),
+        ip: client.ip.map(ToString::to_string),
+        last_used_at: Some(now.into()),
+    }
 }
 
 /// Validate a refresh token
 ///
 /// Checks that:
-/// - Token exists in database
+/// - Token exists in the store
 /// - Token hash matches
+/// - Token is not revoked (a revoked token revokes its family and fails with
+///   [`AuthError::TokenReuseDetected`])
 /// - Token is not expired
-/// - Token is not revoked
+/// - With a `fingerprint`, the token is bound to it or not bound at all (a
+///   mismatch revokes the token's family and fails with
+///   [`AuthError::TokenBindingMismatch`])
 pub async fn validate_refresh_token(
-    db: &DatabaseConnection,
+    store: &dyn TokenStore,
     token: &str,
-    jti: Uuid,
-) -> Result<Uuid> {
-    let token_hash = hash_token(token);
-
-    let stored_token = RefreshTokens::find_by_id(jti)
-        .one(db)
-        .await?
-        .ok_or(AuthError::InvalidToken)?;
+    jti: TokenId,
+    fingerprint: Option<&str>,
+) -> Result<UserId> {
+    let stored_token = store.find(jti).await?.ok_or(AuthError::InvalidToken)?;
 
     // Check if token hash matches
-    if stored_token.token_hash != token_hash {
+    if !verify_token_hash(token, &stored_token.token_hash) {
         return Err(AuthError::InvalidToken.into());
     }
 
     // Check if token is revoked
     if stored_token.revoked_at.is_some() {
-        return Err(AuthError::TokenBlacklisted.into());
+        return Err(reuse_detected(store, &stored_token).await);
     }
 
     // Check if token is expired
@@ -67,101 +261,267 @@ pub async fn validate_refresh_token(
         return Err(AuthError::TokenExpired.into());
     }
 
-    Ok(stored_token.user_id)
+    // Check if token is presented by the client it was issued to
+    if let (Some(expected), Some(presented)) = (stored_token.fingerprint.as_deref(), fingerprint) {
+        if !constant_time_eq(expected.as_bytes(), presented.as_bytes()) {
+            return Err(binding_mismatch(store, &stored_token).await);
+        }
+    }
+
+    Ok(stored_token.owner())
 }
 
 /// Revoke a refresh token
 ///
-/// Marks the token as revoked in the database
-pub async fn revoke_refresh_token(db: &DatabaseConnection, jti: Uuid) -> Result<()> {
-    let stored_token = RefreshTokens::find_by_id(jti)
-        .one(db)
-        .await?
-        .ok_or(AuthError::InvalidToken)?;
-
-    let mut active_token: refresh_tokens::ActiveModel = stored_token.into();
-    active_token.revoked_at = Set(Some(Utc::now().into()));
-    active_token.update(db).await?;
-
-    Ok(())
+/// Revoking an already revoked token succeeds, so logging out twice is
+/// harmless.
+pub async fn revoke_refresh_token(store: &dyn TokenStore, jti: TokenId) -> Result<()> {
+    match store.revoke(jti).await? {
+        Revocation::Revoked | Revocation::AlreadyRevoked => Ok(()),
+        Revocation::NotFound => Err(AuthError::InvalidToken.into()),
+    }
 }
 
 /// Rotate refresh token (revoke old, issue new)
 ///
-/// This implements token rotation pattern for enhanced security
+/// This implements token rotation pattern for enhanced security. If the old
+/// token was revoked in the meantime (a concurrent rotation won), the reuse is
+/// treated like presenting a revoked token. The new token joins the family of
+/// the old one and keeps its sign-in time, so the session shows when it
+/// started.
 pub async fn rotate_refresh_token(
-    db: &DatabaseConnection,
-    old_jti: Uuid,
+    store: &dyn TokenStore,
+    old_jti: TokenId,
     new_token: &str,
-    new_jti: Uuid,
-    user_id: Uuid,
+    new_jti: TokenId,
+    user_id: UserId,
     expires_in_days: i64,
+    client: TokenClient<'_>,
 ) -> Result<()> {
+    let old_token = store.find(old_jti).await?.ok_or(AuthError::InvalidToken)?;
+
     // Revoke old token
-    revoke_refresh_token(db, old_jti).await?;
+    match store.revoke(old_jti).await? {
+        Revocation::Revoked => {}
+        Revocation::AlreadyRevoked => return Err(reuse_detected(store, &old_token).await),
+        Revocation::NotFound => return Err(AuthError::InvalidToken.into()),
+    }
 
     // Store new token
-    store_refresh_token(db, user_id, new_token, new_jti, expires_in_days).await?;
-
-    Ok(())
+    let signed_in_at = old_token.created_at.with_timezone(&Utc);
+    store
+        .insert(issued_token(
+            user_id,
+            new_token,
+            new_jti,
+            expires_in_days,
+            client,
+            old_token.family(),
+            signed_in_at,
+        ))
+        .await
 }
 
-/// Revoke all refresh tokens for a user (logout from all devices)
-pub async fn revoke_all_user_tokens(db: &DatabaseConnection, user_id: Uuid) -> Result<()> {
-    let tokens = RefreshTokens::find()
-        .filter(refresh_tokens::Column::UserId.eq(user_id))
-        .filter(refresh_tokens::Column::RevokedAt.is_null())
-        .all(db)
-        .await?;
+/// List a user's active sessions (unrevoked, unexpired refresh tokens),
+/// newest first
+pub async fn list_active_sessions(
+    store: &dyn TokenStore,
+    user_id: UserId,
+) -> Result<Vec<refresh_tokens::Model>> {
+    store.list_active(user_id).await
+}
 
-    for token in tokens {
-        let mut active_token: refresh_tokens::ActiveModel = token.into();
-        active_token.revoked_at = Set(Some(Utc::now().into()));
-        active_token.update(db).await?;
+/// Revoke one of a user's active sessions
+///
+/// Returns `false` if `jti` is not an active session of the user, so one
+/// user cannot probe or end the sessions of another.
+pub async fn revoke_user_session(
+    store: &dyn TokenStore,
+    user_id: UserId,
+    jti: TokenId,
+) -> Result<bool> {
+    let Some(token) = store.find(jti).await? else {
+        return Ok(false);
+    };
+    if token.owner() != user_id || token.expires_at < Utc::now() {
+        return Ok(false);
     }
+    Ok(store.revoke(jti).await? == Revocation::Revoked)
+}
 
+/// Revoke all refresh tokens for a user (logout from all devices)
+pub async fn revoke_all_user_tokens(store: &dyn TokenStore, user_id: UserId) -> Result<()> {
+    store.revoke_all(user_id).await?;
     Ok(())
 }
 
+/// Revoke every active refresh token of a user but `current`, returning how
+/// many were revoked
+///
+/// Signs out the user's other devices while keeping the session making the
+/// request; with no `current` session, every token is revoked.
+pub async fn revoke_other_user_tokens(
+    store: &dyn TokenStore,
+    user_id: UserId,
+    current: Option<TokenId>,
+) -> Result<u64> {
+    let mut revoked = 0;
+    for token in store.list_active(user_id).await? {
+        if Some(token.jti()) != current && store.revoke(token.jti()).await? == Revocation::Revoked {
+            revoked += 1;
+        }
+    }
+    Ok(revoked)
+}
+
 /// Clean up expired tokens (for maintenance tasks)
 ///
 /// Deletes tokens that have been expired for more than `retention_days`
-pub async fn cleanup_expired_tokens(db: &DatabaseConnection, retention_days: i64) -> Result<u64> {
-    let cutoff = Utc::now() - Duration::days(retention_days);
-
-    let result = RefreshTokens::delete_many()
-        .filter(refresh_tokens::Column::ExpiresAt.lt(cutoff))
-        .exec(db)
-        .await?;
+pub async fn cleanup_expired_tokens(store: &dyn TokenStore, retention_days: i64) -> Result<u64> {
+    store.cleanup_expired(retention_days).await
+}
 
-    Ok(result.rows_affected)
+/// Revoke the family of a revoked token that was presented again
+///
+/// Returns the error to answer the request with.
+async fn reuse_detected(store: &dyn TokenStore, token: &refresh_tokens::Model) -> anyhow::Error {
+    match store.revoke_family(token.owner(), token.family()).await {
+        Ok(revoked) => tracing::warn!(
+            target: "audit",
+            action = "auth.refresh_token_reuse",
+            user_id = %token.user_id,
+            jti = %token.id,
+            family_id = %token.family_id,
+            revoked,
+            "Revoked refresh token reused; its token family revoked"
+        ),
+        Err(e) => {
+            tracing::error!("Failed to revoke tokens after refresh token reuse: {}", e);
+            return e;
+        }
+    }
+    AuthError::TokenReuseDetected.into()
 }
 
-/// Hash a token for storage
+/// Revoke the family of a token presented by a client with another
+/// fingerprint
 ///
-/// Uses SHA-256 for token hashing
-fn hash_token(token: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(token.as_bytes());
-    format!("{:x}", hasher.finalize())
+/// Returns the error to answer the request with.
+async fn binding_mismatch(store: &dyn TokenStore, token: &refresh_tokens::Model) -> anyhow::Error {
+    match store.revoke_family(token.owner(), token.family()).await {
+        Ok(revoked) => tracing::warn!(
+            target: "audit",
+            action = "auth.refresh_token_binding_mismatch",
+            user_id = %token.user_id,
+            jti = %token.id,
+            family_id = %token.family_id,
+            revoked,
+            "Refresh token presented from another device; its token family revoked"
+        ),
+        Err(e) => {
+            tracing::error!("Failed to revoke tokens after binding mismatch: {}", e);
+            return e;
+        }
+    }
+    AuthError::TokenBindingMismatch.into()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sea_orm::{DatabaseBackend, MockDatabase};
+    use crate::services::auth::token_binding::fingerprint;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use std::cmp::Reverse;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    fn store(db: DatabaseConnection) -> SeaOrmTokenStore {
+        SeaOrmTokenStore::new(Arc::new(db))
+    }
+
+    /// In-memory store for exercising the rotation rules
+    #[derive(Default)]
+    struct MemoryTokenStore {
+        tokens: Mutex<HashMap<TokenId, refresh_tokens::Model>>,
+    }
+
+    #[async_trait]
+    impl TokenStore for MemoryTokenStore {
+        async fn insert(&self, token: refresh_tokens::Model) -> Result<()> {
+            self.tokens.lock().unwrap().insert(token.jti(), token);
+            Ok(())
+        }
+
+        async fn find(&self, jti: TokenId) -> Result<Option<refresh_tokens::Model>> {
+            Ok(self.tokens.lock().unwrap().get(&jti).cloned())
+        }
+
+        async fn revoke(&self, jti: TokenId) -> Result<Revocation> {
+            Ok(match self.tokens.lock().unwrap().get_mut(&jti) {
+                Some(token) if token.revoked_at.is_none() => {
+                    token.revoked_at = Some(Utc::now().into());
+                    Revocation::Revoked
+                }
+                Some(_) => Revocation::AlreadyRevoked,
+                None => Revocation::NotFound,
+            })
+        }
+
+        async fn revoke_all(&self, user_id: UserId) -> Result<u64> {
+            let mut revoked = 0;
+            for token in self.tokens.lock().unwrap().values_mut() {
+                if token.owner() == user_id && token.revoked_at.is_none() {
+                    token.revoked_at = Some(Utc::now().into());
+                    revoked += 1;
+                }
+            }
+            Ok(revoked)
+        }
+
+        async fn revoke_family(&self, user_id: UserId, family_id: TokenId) -> Result<u64> {
+            let mut revoked = 0;
+            for token in self.tokens.lock().unwrap().values_mut() {
+                if token.owner() == user_id
+                    && token.family() == family_id
+                    && token.revoked_at.is_none()
+                {
+                    token.revoked_at = Some(Utc::now().into());
+                    revoked += 1;
+                }
+            }
+            Ok(revoked)
+        }
+
+        async fn list_active(&self, user_id: UserId) -> Result<Vec<refresh_tokens::Model>> {
+            let mut tokens: Vec<_> = self
+                .tokens
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|token| token.owner() == user_id && token.revoked_at.is_none())
+                .cloned()
+                .collect();
+            tokens.sort_by_key(|token| Reverse(token.created_at));
+            Ok(tokens)
+        }
+
+        async fn cleanup_expired(&self, _retention_days: i64) -> Result<u64> {
+            Ok(0)
+        }
+    }
 
     fn mock_refresh_token(
-        id: Uuid,
-        user_id: Uuid,
+        id: TokenId,
+        user_id: UserId,
         token_hash: String,
         expired: bool,
         revoked: bool,
     ) -> refresh_tokens::Model {
         let now = Utc::now();
         refresh_tokens::Model {
-            id,
-            user_id,
+            id: id.into_uuid(),
+            user_id: user_id.into_uuid(),
+            family_id: id.into_uuid(),
             token_hash,
             expires_at: if expired {
                 (now - Duration::hours(1)).into()
@@ -170,6 +530,10 @@ mod tests {
             },
             revoked_at: if revoked { Some(now.into()) } else { None },
             created_at: now.into(),
+            user_agent: None,
+            fingerprint: None,
+            ip: None,
+            last_used_at: Some(now.into()),
         }
     }
 
@@ -203,8 +567,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_refresh_token_valid() {
-        let user_id = Uuid::new_v4();
-        let jti = Uuid::new_v4();
+        let user_id = UserId::new();
+        let jti = TokenId::new();
         let token = "test_token";
         let token_hash = hash_token(token);
 
@@ -214,7 +578,7 @@ mod tests {
             .append_query_results([[mock_token]])
             .into_connection();
 
-        let result = validate_refresh_token(&db, token, jti).await;
+        let result = validate_refresh_token(&store(db), token, jti, None).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), user_id);
     }
@@ -226,8 +590,8 @@ mod tests {
             .append_query_results(empty_results)
             .into_connection();
 
-        let jti = Uuid::new_v4();
-        let result = validate_refresh_token(&db, "any_token", jti).await;
+        let jti = TokenId::new();
+        let result = validate_refresh_token(&store(db), "any_token", jti, None).await;
 
         assert!(result.is_err());
         let err = result.unwrap_err();
@@ -236,8 +600,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_refresh_token_wrong_hash() {
-        let user_id = Uuid::new_v4();
-        let jti = Uuid::new_v4();
+        let user_id = UserId::new();
+        let jti = TokenId::new();
         let token_hash = hash_token("correct_token");
 
         let mock_token = mock_refresh_token(jti, user_id, token_hash, false, false);
@@ -246,36 +610,42 @@ mod tests {
             .append_query_results([[mock_token]])
             .into_connection();
 
-        let result = validate_refresh_token(&db, "wrong_token", jti).await;
+        let result = validate_refresh_token(&store(db), "wrong_token", jti, None).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Invalid token"));
     }
 
     #[tokio::test]
     async fn test_validate_refresh_token_revoked() {
-        let user_id = Uuid::new_v4();
-        let jti = Uuid::new_v4();
+        let user_id = UserId::new();
+        let jti = TokenId::new();
         let token = "test_token";
         let token_hash = hash_token(token);
 
         let mock_token = mock_refresh_token(jti, user_id, token_hash, false, true);
 
+        // The reused token revokes the rest of its family
         let db = MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results([[mock_token]])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 2,
+            }])
             .into_connection();
 
-        let result = validate_refresh_token(&db, token, jti).await;
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Token blacklisted"));
+        let err = validate_refresh_token(&store(db), token, jti, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AuthError>(),
+            Some(AuthError::TokenReuseDetected)
+        ));
     }
 
     #[tokio::test]
     async fn test_validate_refresh_token_expired() {
-        let user_id = Uuid::new_v4();
-        let jti = Uuid::new_v4();
+        let user_id = UserId::new();
+        let jti = TokenId::new();
         let token = "test_token";
         let token_hash = hash_token(token);
 
@@ -285,13 +655,269 @@ mod tests {
             .append_query_results([[mock_token]])
             .into_connection();
 
-        let result = validate_refresh_token(&db, token, jti).await;
+        let result = validate_refresh_token(&store(db), token, jti, None).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Token expired"));
     }
 
-    // Note: Write operation tests (store, revoke, rotate, cleanup) will be covered
-    // by integration tests in Phase 4 with actual database connections.
-    // Unit testing these with mock database is complex and provides limited value
-    // compared to integration tests that verify actual database behavior.
+    const AGENT: TokenClient<'static> = TokenClient {
+        user_agent: Some("agent"),
+        fingerprint: None,
+        ip: Some("203.0.113.7"),
+    };
+
+    #[tokio::test]
+    async fn test_revoke_user_session_checks_owner() {
+        let store = MemoryTokenStore::default();
+        let (user_id, other_id) = (UserId::new(), UserId::new());
+        let jti = TokenId::new();
+
+        store_refresh_token(&store, user_id, "token", jti, 7, AGENT)
+            .await
+            .unwrap();
+
+        assert!(!revoke_user_session(&store, other_id, jti).await.unwrap());
+        assert!(!revoke_user_session(&store, user_id, TokenId::new())
+            .await
+            .unwrap());
+        assert!(revoke_user_session(&store, user_id, jti).await.unwrap());
+        // Already ended
+        assert!(!revoke_user_session(&store, user_id, jti).await.unwrap());
+        assert!(list_active_sessions(&store, user_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rotation_keeps_sign_in_time() {
+        let store = MemoryTokenStore::default();
+        let user_id = UserId::new();
+        let (old_jti, new_jti) = (TokenId::new(), TokenId::new());
+
+        store_refresh_token(&store, user_id, "old", old_jti, 7, AGENT)
+            .await
+            .unwrap();
+        let signed_in_at = store.find(old_jti).await.unwrap().unwrap().created_at;
+        rotate_refresh_token(&store, old_jti, "new", new_jti, user_id, 7, AGENT)
+            .await
+            .unwrap();
+
+        let rotated = store.find(new_jti).await.unwrap().unwrap();
+        assert_eq!(rotated.created_at, signed_in_at);
+        assert!(rotated.last_used_at.unwrap() >= signed_in_at);
+        assert_eq!(rotated.ip.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[tokio::test]
+    async fn test_rotation_revokes_old_token() {
+        let store = MemoryTokenStore::default();
+        let user_id = UserId::new();
+        let (old_jti, new_jti) = (TokenId::new(), TokenId::new());
+
+        store_refresh_token(&store, user_id, "old", old_jti, 7, AGENT)
+            .await
+            .unwrap();
+        rotate_refresh_token(&store, old_jti, "new", new_jti, user_id, 7, AGENT)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            validate_refresh_token(&store, "new", new_jti, None)
+                .await
+                .unwrap(),
+            user_id
+        );
+        let sessions = list_active_sessions(&store, user_id).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].jti(), new_jti);
+    }
+
+    #[tokio::test]
+    async fn test_reused_token_revokes_its_family() {
+        let store = MemoryTokenStore::default();
+        let user_id = UserId::new();
+        let (old_jti, new_jti, other_jti) = (TokenId::new(), TokenId::new(), TokenId::new());
+
+        store_refresh_token(&store, user_id, "old", old_jti, 7, TokenClient::default())
+            .await
+            .unwrap();
+        store_refresh_token(
+            &store,
+            user_id,
+            "other",
+            other_jti,
+            7,
+            TokenClient::default(),
+        )
+        .await
+        .unwrap();
+        rotate_refresh_token(
+            &store,
+            old_jti,
+            "new",
+            new_jti,
+            user_id,
+            7,
+            TokenClient::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            store.find(new_jti).await.unwrap().unwrap().family(),
+            old_jti
+        );
+
+        // The rotated token is presented again
+        let err = validate_refresh_token(&store, "old", old_jti, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AuthError>(),
+            Some(AuthError::TokenReuseDetected)
+        ));
+
+        // Its successor is revoked, the session signed in separately is not
+        let sessions = list_active_sessions(&store, user_id).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].jti(), other_jti);
+    }
+
+    #[tokio::test]
+    async fn test_losing_rotation_race_is_reuse() {
+        let store = MemoryTokenStore::default();
+        let user_id = UserId::new();
+        let old_jti = TokenId::new();
+
+        store_refresh_token(&store, user_id, "old", old_jti, 7, TokenClient::default())
+            .await
+            .unwrap();
+        rotate_refresh_token(
+            &store,
+            old_jti,
+            "a",
+            TokenId::new(),
+            user_id,
+            7,
+            TokenClient::default(),
+        )
+        .await
+        .unwrap();
+
+        let err = rotate_refresh_token(
+            &store,
+            old_jti,
+            "b",
+            TokenId::new(),
+            user_id,
+            7,
+            TokenClient::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("Refresh token reuse detected"));
+        assert!(list_active_sessions(&store, user_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_other_user_tokens_keeps_current() {
+        let store = MemoryTokenStore::default();
+        let user_id = UserId::new();
+        let (current, other) = (TokenId::new(), TokenId::new());
+
+        for (token, jti) in [("current", current), ("other", other)] {
+            store_refresh_token(&store, user_id, token, jti, 7, AGENT)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            revoke_other_user_tokens(&store, user_id, Some(current))
+                .await
+                .unwrap(),
+            1
+        );
+        let sessions = list_active_sessions(&store, user_id).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].jti(), current);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_is_idempotent() {
+        let store = MemoryTokenStore::default();
+        let jti = TokenId::new();
+
+        store_refresh_token(
+            &store,
+            UserId::new(),
+            "token",
+            jti,
+            7,
+            TokenClient::default(),
+        )
+        .await
+        .unwrap();
+        revoke_refresh_token(&store, jti).await.unwrap();
+        revoke_refresh_token(&store, jti).await.unwrap();
+
+        let err = revoke_refresh_token(&store, TokenId::new())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid token"));
+    }
+
+    #[tokio::test]
+    async fn test_binding_mismatch_revokes_its_family() {
+        let store = MemoryTokenStore::default();
+        let user_id = UserId::new();
+        let (bound_jti, unbound_jti) = (TokenId::new(), TokenId::new());
+        let laptop = fingerprint(Some("Chrome"), Some("laptop"));
+        let phone = fingerprint(Some("Chrome"), Some("phone"));
+        let bound = TokenClient {
+            fingerprint: Some(&laptop),
+            ..AGENT
+        };
+
+        store_refresh_token(&store, user_id, "bound", bound_jti, 7, bound)
+            .await
+            .unwrap();
+        store_refresh_token(&store, user_id, "unbound", unbound_jti, 7, AGENT)
+            .await
+            .unwrap();
+
+        // Same client, no binding check, and an unbound token: accepted
+        for (token, jti, fingerprint) in [
+            ("bound", bound_jti, Some(laptop.as_str())),
+            ("bound", bound_jti, None),
+            ("unbound", unbound_jti, Some(phone.as_str())),
+        ] {
+            assert_eq!(
+                validate_refresh_token(&store, token, jti, fingerprint)
+                    .await
+                    .unwrap(),
+                user_id
+            );
+        }
+
+        let err = validate_refresh_token(&store, "bound", bound_jti, Some(&phone))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AuthError>(),
+            Some(AuthError::TokenBindingMismatch)
+        ));
+
+        // Sessions signed in separately stay active
+        let active: Vec<_> = list_active_sessions(&store, user_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|token| token.jti())
+            .collect();
+        assert_eq!(active, vec![unbound_jti]);
+    }
 }
@@ -0,0 +1,509 @@
+//! Trusted devices confirmed by email.
+//!
+//! With `TRUSTED_DEVICES_ENABLED=true`, a browser is identified by the same
+//! device cookie refresh token binding uses (see [`super::token_binding`]).
+//! Signing in from a device the user has not confirmed yet still returns an
+//! access token, but no refresh token: the account owner is emailed a link
+//! confirming the device, and only later sign-ins from a confirmed device
+//! get a long-lived session. Stolen credentials alone therefore yield at
+//! most one short-lived access token.
+//!
+//! Refreshing also requires a trusted device, so removing a device from the
+//! list ends its sessions at their next refresh. The device a user
+//! registers from is trusted right away.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::models::{prelude::TrustedDevices, trusted_devices, users};
+use crate::utils::token::{generate_verification_token, hash_token};
+
+/// Lifetime of a confirmation link when `TRUSTED_DEVICE_CONFIRMATION_TTL_MINUTES` is unset
+pub const DEFAULT_CONFIRMATION_TTL_MINUTES: i64 = 60;
+
+/// Minimum time between two confirmation emails for the same device
+pub const CONFIRMATION_RESEND_COOLDOWN_SECS: i64 = 60;
+
+/// Trusted device settings, per `TRUSTED_DEVICES_ENABLED`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedDevicePolicy {
+    /// How long a confirmation link stays valid
+    pub confirmation_ttl: Duration,
+    /// Base URL of the confirmation links (`APP_PUBLIC_URL`)
+    pub public_base_url: String,
+}
+
+/// Client a device record is created for
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeviceClient<'a> {
+    /// `User-Agent` header, if sent
+    pub user_agent: Option<&'a str>,
+    /// Client IP address, if known
+    pub ip: Option<&'a str>,
+}
+
+impl TrustedDevicePolicy {
+    /// Load the policy from environment variables
+    ///
+    /// - `TRUSTED_DEVICES_ENABLED`: Require email confirmation of new devices
+    ///   (default: false)
+    /// - `TRUSTED_DEVICE_CONFIRMATION_TTL_MINUTES`: Lifetime of confirmation
+    ///   links (default: 60)
+    /// - `APP_PUBLIC_URL`: Base URL of the links (default:
+    ///   `http://localhost:2727`)
+    ///
+    /// Returns `None` when trusted devices are disabled.
+    ///
+    /// # Panics
+    /// Panics if a variable is set but cannot be parsed
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("TRUSTED_DEVICES_ENABLED").is_ok_and(|value| {
+            value
+                .parse()
+                .expect("TRUSTED_DEVICES_ENABLED must be a boolean")
+        });
+        if !enabled {
+            return None;
+        }
+
+        let ttl_minutes = std::env::var("TRUSTED_DEVICE_CONFIRMATION_TTL_MINUTES").map_or(
+            DEFAULT_CONFIRMATION_TTL_MINUTES,
+            |value| {
+                value.parse().ok().filter(|minutes| *minutes > 0).expect(
+                    "TRUSTED_DEVICE_CONFIRMATION_TTL_MINUTES must be a positive number of minutes",
+                )
+            },
+        );
+        let public_base_url = std::env::var("APP_PUBLIC_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| "http://localhost:2727".to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        Some(Self {
+            confirmation_ttl: Duration::minutes(ttl_minutes),
+            public_base_url,
+        })
+    }
+
+    /// Link confirming the device a `token` was issued for
+    #[must_use]
+    pub fn confirmation_url(&self, token: &str) -> String {
+        format!("{}/confirm-device?token={token}", self.public_base_url)
+    }
+}
+
+/// Hash of a device cookie, as stored in `trusted_devices.device_hash`
+#[must_use]
+pub fn device_hash(device_id: &str) -> String {
+    hash_token(device_id)
+}
+
+/// Whether `user_id` has confirmed the device, recording its use if so
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn is_trusted_device(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    device_id: &str,
+) -> Result<bool> {
+    let Some(device) = find_device(db, user_id, device_id).await? else {
+        return Ok(false);
+    };
+    if device.confirmed_at.is_none() {
+        return Ok(false);
+    }
+
+    let mut active: trusted_devices::ActiveModel = device.into();
+    active.last_used_at = Set(Some(Utc::now().into()));
+    active.update(db).await?;
+    Ok(true)
+}
+
+/// Trust a device without confirmation
+///
+/// Used for the device a user registers from: the account was created
+/// there, so there is no other device to confirm it from.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn trust_device(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    device_id: &str,
+    client: DeviceClient<'_>,
+) -> Result<()> {
+    let now = Utc::now();
+    if let Some(device) = find_device(db, user_id, device_id).await? {
+        let mut active: trusted_devices::ActiveModel = device.into();
+        active.confirmation_token_hash = Set(None);
+        active.confirmation_expires_at = Set(None);
+        active.confirmed_at = Set(Some(now.into()));
+        active.last_used_at = Set(Some(now.into()));
+        active.update(db).await?;
+        return Ok(());
+    }
+
+    trusted_devices::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        device_hash: Set(device_hash(device_id)),
+        user_agent: Set(client.user_agent.map(ToString::to_string)),
+        ip: Set(client.ip.map(ToString::to_string)),
+        confirmation_token_hash: Set(None),
+        confirmation_expires_at: Set(None),
+        confirmation_sent_at: Set(None),
+        confirmed_at: Set(Some(now.into())),
+        last_used_at: Set(Some(now.into())),
+        created_at: Set(now.into()),
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+/// Issue a confirmation token for an untrusted device and hand it to `send`,
+/// unless one was sent for the device less than
+/// [`CONFIRMATION_RESEND_COOLDOWN_SECS`] ago
+///
+/// Returns whether a token was sent. A new token replaces the pending one,
+/// so only the newest link works. Runs in one transaction holding a lock
+/// on the user row; if `send` fails it is rolled back and no cooldown
+/// starts.
+///
+/// # Errors
+///
+/// Returns an error if the user does not exist, on database failure, or if
+/// `send` fails
+pub async fn request_device_confirmation<F>(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    device_id: &str,
+    client: DeviceClient<'_>,
+    ttl: Duration,
+    send: F,
+) -> Result<bool>
+where
+    F: FnOnce(&str) -> Result<()> + Send,
+{
+    let txn = db.begin().await?;
+
+    users::Entity::find_by_id(user_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("User not found"))?;
+
+    let now = Utc::now();
+    let existing = TrustedDevices::find()
+        .filter(trusted_devices::Column::UserId.eq(user_id))
+        .filter(trusted_devices::Column::DeviceHash.eq(device_hash(device_id)))
+        .one(&txn)
+        .await?;
+    if let Some(device) = &existing {
+        let sent_at = device.confirmation_sent_at.map(|at| at.with_timezone(&Utc));
+        if device.confirmed_at.is_some() || cooling_down(sent_at, now) {
+            return Ok(false);
+        }
+    }
+
+    let token = generate_verification_token();
+    let expires_at = now + ttl;
+    match existing {
+        Some(device) => {
+            let mut active: trusted_devices::ActiveModel = device.into();
+            active.user_agent = Set(client.user_agent.map(ToString::to_string));
+            active.ip = Set(client.ip.map(ToString::to_string));
+            active.confirmation_token_hash = Set(Some(hash_token(&token)));
+            active.confirmation_expires_at = Set(Some(expires_at.into()));
+            active.confirmation_sent_at = Set(Some(now.into()));
+            active.update(&txn).await?;
+        }
+        None => {
+            trusted_devices::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                device_hash: Set(device_hash(device_id)),
+                user_agent: Set(client.user_agent.map(ToString::to_string)),
+                ip: Set(client.ip.map(ToString::to_string)),
+                confirmation_token_hash: Set(Some(hash_token(&token))),
+                confirmation_expires_at: Set(Some(expires_at.into())),
+                confirmation_sent_at: Set(Some(now.into())),
+                confirmed_at: Set(None),
+                last_used_at: Set(None),
+                created_at: Set(now.into()),
+            }
+            .insert(&txn)
+            .await?;
+        }
+    }
+
+    send(&token)?;
+    txn.commit().await?;
+    Ok(true)
+}
+
+/// Confirm the device a confirmation token was issued for
+///
+/// Returns the confirmed device, or `None` if the token is unknown,
+/// expired or already used.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn confirm_device(
+    db: &DatabaseConnection,
+    token: &str,
+) -> Result<Option<trusted_devices::Model>> {
+    let now = Utc::now();
+    let Some(device) = TrustedDevices::find()
+        .filter(trusted_devices::Column::ConfirmationTokenHash.eq(hash_token(token)))
+        .filter(trusted_devices::Column::ConfirmationExpiresAt.gt(now))
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let mut active: trusted_devices::ActiveModel = device.into();
+    active.confirmation_token_hash = Set(None);
+    active.confirmation_expires_at = Set(None);
+    active.confirmed_at = Set(Some(now.into()));
+    Ok(Some(active.update(db).await?))
+}
+
+/// Confirmed devices of a user, most recently used first
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn list_trusted_devices(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<trusted_devices::Model>> {
+    Ok(TrustedDevices::find()
+        .filter(trusted_devices::Column::UserId.eq(user_id))
+        .filter(trusted_devices::Column::ConfirmedAt.is_not_null())
+        .order_by_desc(trusted_devices::Column::LastUsedAt)
+        .order_by_desc(trusted_devices::Column::ConfirmedAt)
+        .all(db)
+        .await?)
+}
+
+/// Remove a device of a user, confirmed or pending
+///
+/// Returns `false` if the user has no such device.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn remove_trusted_device(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<bool> {
+    let result = TrustedDevices::delete_many()
+        .filter(trusted_devices::Column::Id.eq(id))
+        .filter(trusted_devices::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+async fn find_device(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    device_id: &str,
+) -> Result<Option<trusted_devices::Model>> {
+    Ok(TrustedDevices::find()
+        .filter(trusted_devices::Column::UserId.eq(user_id))
+        .filter(trusted_devices::Column::DeviceHash.eq(device_hash(device_id)))
+        .one(db)
+        .await?)
+}
+
+fn cooling_down(sent_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    sent_at
+        .is_some_and(|sent_at| now - sent_at < Duration::seconds(CONFIRMATION_RESEND_COOLDOWN_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_url() {
+        let policy = TrustedDevicePolicy {
+            confirmation_ttl: Duration::minutes(DEFAULT_CONFIRMATION_TTL_MINUTES),
+            public_base_url: "https://app.example.com".to_string(),
+        };
+        assert_eq!(
+            policy.confirmation_url("abc"),
+            "https://app.example.com/confirm-device?token=abc"
+        );
+    }
+
+    #[test]
+    fn test_cooling_down() {
+        let now = Utc::now();
+        assert!(!cooling_down(None, now));
+        assert!(cooling_down(Some(now - Duration::seconds(10)), now));
+        assert!(!cooling_down(
+            Some(now - Duration::seconds(CONFIRMATION_RESEND_COOLDOWN_SECS)),
+            now
+        ));
+    }
+
+    #[cfg(feature = "demo")]
+    mod persistence {
+        use super::*;
+        use crate::models::sea_orm_active_enums::UserRole;
+
+        async fn setup() -> (std::sync::Arc<DatabaseConnection>, Uuid) {
+            let db = crate::demo::open().await.unwrap();
+            let now = Utc::now();
+            let user = users::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                username: Set("alice".to_string()),
+                email: Set("alice@example.com".to_string()),
+                password_hash: Set(None),
+                role: Set(UserRole::User),
+                email_verified: Set(true),
+                disabled_at: Set(None),
+                last_login_at: Set(None),
+                tokens_not_before: Set(None),
+                password_changed_at: Set(None),
+                password_rotation_required: Set(false),
+                created_at: Set(now.into()),
+                updated_at: Set(now.into()),
+            }
+            .insert(db.as_ref())
+            .await
+            .unwrap();
+            (db, user.id)
+        }
+
+        #[tokio::test]
+        async fn test_confirm_device() {
+            let (db, user_id) = setup().await;
+            let client = DeviceClient {
+                user_agent: Some("curl/8.7.1"),
+                ip: Some("203.0.113.7"),
+            };
+            assert!(!is_trusted_device(&db, user_id, "laptop").await.unwrap());
+
+            let mut sent = None;
+            let was_sent = request_device_confirmation(
+                &db,
+                user_id,
+                "laptop",
+                client,
+                Duration::minutes(60),
+                |token| {
+                    sent = Some(token.to_string());
+                    Ok(())
+                },
+            )
+            .await
+            .unwrap();
+            assert!(was_sent);
+
+            // Within the cooldown nothing is sent, and the device stays pending
+            let resent = request_device_confirmation(
+                &db,
+                user_id,
+                "laptop",
+                client,
+                Duration::minutes(60),
+                |_| panic!("sent during cooldown"),
+            )
+            .await
+            .unwrap();
+            assert!(!resent);
+            assert!(!is_trusted_device(&db, user_id, "laptop").await.unwrap());
+            assert!(list_trusted_devices(&db, user_id).await.unwrap().is_empty());
+
+            let token = sent.unwrap();
+            let device = confirm_device(&db, &token).await.unwrap().unwrap();
+            assert_eq!(device.user_id, user_id);
+            assert!(device.confirmation_token_hash.is_none());
+            assert!(confirm_device(&db, &token).await.unwrap().is_none());
+            assert!(is_trusted_device(&db, user_id, "laptop").await.unwrap());
+            assert!(!is_trusted_device(&db, user_id, "phone").await.unwrap());
+
+            let devices = list_trusted_devices(&db, user_id).await.unwrap();
+            assert_eq!(devices.len(), 1);
+            assert!(devices[0].last_used_at.is_some());
+
+            assert!(!remove_trusted_device(&db, Uuid::new_v4(), device.id)
+                .await
+                .unwrap());
+            assert!(remove_trusted_device(&db, user_id, device.id)
+                .await
+                .unwrap());
+            assert!(!is_trusted_device(&db, user_id, "laptop").await.unwrap());
+        }
+
+        #[tokio::test]
+        async fn test_failed_send_rolls_back() {
+            let (db, user_id) = setup().await;
+
+            let result = request_device_confirmation(
+                &db,
+                user_id,
+                "laptop",
+                DeviceClient::default(),
+                Duration::minutes(60),
+                |_| Err(anyhow::anyhow!("SMTP down")),
+            )
+            .await;
+            assert!(result.is_err());
+
+            // No cooldown started, so the next sign-in sends the email
+            let was_sent = request_device_confirmation(
+                &db,
+                user_id,
+                "laptop",
+                DeviceClient::default(),
+                Duration::minutes(60),
+                |_| Ok(()),
+            )
+            .await
+            .unwrap();
+            assert!(was_sent);
+        }
+
+        #[tokio::test]
+        async fn test_trust_device() {
+            let (db, user_id) = setup().await;
+
+            trust_device(&db, user_id, "laptop", DeviceClient::default())
+                .await
+                .unwrap();
+            assert!(is_trusted_device(&db, user_id, "laptop").await.unwrap());
+
+            // A trusted device needs no confirmation
+            let was_sent = request_device_confirmation(
+                &db,
+                user_id,
+                "laptop",
+                DeviceClient::default(),
+                Duration::minutes(60),
+                |_| panic!("sent for a trusted device"),
+            )
+            .await
+            .unwrap();
+            assert!(!was_sent);
+        }
+    }
+}
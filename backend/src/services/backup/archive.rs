@@ -0,0 +1,162 @@
+//! Encrypted archive container.
+//!
+//! Layout:
+//!
+//! ```text
+//! "CSBACKUP" | version (1 byte) | salt (16 bytes) | nonce (12 bytes) | ciphertext
+//! ```
+//!
+//! The key is derived from the passphrase with Argon2id and the payload is
+//! sealed with AES-256-GCM. The header is authenticated as associated data,
+//! so a tampered header fails decryption like a wrong passphrase does.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use argon2::Argon2;
+use rand::{rngs::OsRng, RngCore};
+
+use super::BackupError;
+
+/// Leading bytes identifying a backup archive
+pub const MAGIC: &[u8; 8] = b"CSBACKUP";
+
+/// Version of the container layout (not of the payload)
+pub const CONTAINER_VERSION: u8 = 1;
+
+/// Shortest passphrase accepted when creating an archive
+pub const MIN_PASSPHRASE_LENGTH: usize = 12;
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const HEADER_LENGTH: usize = MAGIC.len() + 1 + SALT_LENGTH + NONCE_LENGTH;
+
+/// Encrypt `plaintext` under a key derived from `passphrase`
+///
+/// # Errors
+///
+/// Returns `BackupError::WeakPassphrase` for passphrases shorter than
+/// [`MIN_PASSPHRASE_LENGTH`] characters.
+pub fn seal(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, BackupError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(BackupError::WeakPassphrase);
+    }
+
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut archive = Vec::with_capacity(HEADER_LENGTH + plaintext.len() + 16);
+    archive.extend_from_slice(MAGIC);
+    archive.push(CONTAINER_VERSION);
+    archive.extend_from_slice(&salt);
+    archive.extend_from_slice(&nonce);
+
+    let cipher = cipher(passphrase, &salt)?;
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &archive,
+            },
+        )
+        .map_err(|_| BackupError::Crypto("encryption failed".to_string()))?;
+    archive.extend_from_slice(&ciphertext);
+
+    Ok(archive)
+}
+
+/// Decrypt an archive produced by [`seal`]
+///
+/// # Errors
+///
+/// Returns `BackupError::InvalidArchive` if the data is not an archive,
+/// `BackupError::UnsupportedVersion` for unknown container versions and
+/// `BackupError::Decryption` for a wrong passphrase or tampered data.
+pub fn open(archive: &[u8], passphrase: &str) -> Result<Vec<u8>, BackupError> {
+    if archive.len() < HEADER_LENGTH || !archive.starts_with(MAGIC) {
+        return Err(BackupError::InvalidArchive);
+    }
+    let version = archive[MAGIC.len()];
+    if version != CONTAINER_VERSION {
+        return Err(BackupError::UnsupportedVersion(u32::from(version)));
+    }
+
+    let (header, ciphertext) = archive.split_at(HEADER_LENGTH);
+    let salt = &header[MAGIC.len() + 1..MAGIC.len() + 1 + SALT_LENGTH];
+    let nonce = &header[HEADER_LENGTH - NONCE_LENGTH..];
+
+    cipher(passphrase, salt)?
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| BackupError::Decryption)
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm, BackupError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| BackupError::Crypto(e.to_string()))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    #[test]
+    fn test_seal_open_round_trip() {
+        let archive = seal(b"payload", PASSPHRASE).unwrap();
+        assert!(archive.starts_with(MAGIC));
+        assert_eq!(open(&archive, PASSPHRASE).unwrap(), b"payload");
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_passphrase_and_tampering() {
+        let mut archive = seal(b"payload", PASSPHRASE).unwrap();
+        assert!(matches!(
+            open(&archive, "wrong passphrase!"),
+            Err(BackupError::Decryption)
+        ));
+
+        // Flipping a salt bit changes the key and the authenticated header
+        archive[MAGIC.len() + 1] ^= 1;
+        assert!(matches!(
+            open(&archive, PASSPHRASE),
+            Err(BackupError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn test_open_rejects_foreign_data_and_versions() {
+        assert!(matches!(
+            open(b"not a backup", PASSPHRASE),
+            Err(BackupError::InvalidArchive)
+        ));
+
+        let mut archive = seal(b"payload", PASSPHRASE).unwrap();
+        archive[MAGIC.len()] = CONTAINER_VERSION + 1;
+        assert!(matches!(
+            open(&archive, PASSPHRASE),
+            Err(BackupError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn test_seal_rejects_short_passphrase() {
+        assert!(matches!(
+            seal(b"payload", "short"),
+            Err(BackupError::WeakPassphrase)
+        ));
+    }
+}
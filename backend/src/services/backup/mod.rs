@@ -0,0 +1,431 @@
+//! Logical backup and restore for small deployments.
+//!
+//! A backup holds users (with their roles and password hashes), user
+//! preferences and chat data (sessions, messages, annotations, read states
+//! and share links) as JSON, sealed in an encrypted archive (see [`archive`]). It is
+//! meant for self-hosters without Postgres tooling; larger deployments
+//! should use `pg_dump`.
+//!
+//! Not included: refresh tokens, email verification tokens, OAuth links,
+//! branding overrides, access logs, scheduled report state and batch chat
+//! jobs. Restored users
+//! sign in again, and share links only keep working if the new instance uses
+//! the same `CHAT_SHARE_SECRET`.
+//!
+//! Restores are refused unless the target has applied exactly the same
+//! migrations as the source, and only go into a fresh instance (no chat data
+//! and no users other than the bootstrap admin being replaced).
+//!
+//! Available as admin endpoints (`/api/v1/admin/backup`, or
+//! `/api/v1/admin/backup/exports` to keep the archive in object storage for
+//! resumable download) and as the `backup` / `restore` subcommands of the
+//! server binary.
+
+pub mod archive;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    AccessMode, ActiveModelTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbErr,
+    EntityTrait, IntoActiveModel, IsolationLevel, PaginatorTrait, QuerySelect, Statement,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::models::{
+    chat_messages, chat_read_states, chat_sessions, chat_shares, chat_usage, message_annotations,
+    user_preferences, users,
+};
+
+pub use archive::MIN_PASSPHRASE_LENGTH;
+
+/// Version of the backup payload layout
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Largest archive accepted for restore over HTTP
+pub const MAX_ARCHIVE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Environment variable holding the passphrase for the CLI subcommands
+pub const PASSPHRASE_ENV: &str = "BACKUP_PASSPHRASE";
+
+/// Rows per `INSERT` statement during restore
+const INSERT_BATCH_SIZE: usize = 500;
+
+/// Errors from creating, reading or restoring a backup
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("Not a backup archive")]
+    InvalidArchive,
+
+    #[error("Unsupported backup version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Wrong passphrase or corrupted archive")]
+    Decryption,
+
+    #[error("Passphrase must be at least {MIN_PASSPHRASE_LENGTH} characters")]
+    WeakPassphrase,
+
+    #[error("Backup was taken at schema version {archive}, this instance is at {current}")]
+    SchemaMismatch { archive: String, current: String },
+
+    #[error("Could not determine the schema version; run the migrations first")]
+    SchemaUnknown,
+
+    #[error("Restore requires a fresh instance without chat data or other users")]
+    NotEmpty,
+
+    #[error("Invalid backup payload: {0}")]
+    InvalidPayload(#[from] serde_json::Error),
+
+    #[error("Encryption error: {0}")]
+    Crypto(String),
+
+    #[error(transparent)]
+    Database(#[from] DbErr),
+}
+
+/// Contents of a backup archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backup {
+    pub format_version: u32,
+    /// Latest migration applied on the source instance
+    pub schema_version: String,
+    pub created_at: DateTime<Utc>,
+    pub users: Vec<users::Model>,
+    pub user_preferences: Vec<user_preferences::Model>,
+    pub chat_sessions: Vec<chat_sessions::Model>,
+    pub chat_messages: Vec<chat_messages::Model>,
+    pub chat_read_states: Vec<chat_read_states::Model>,
+    pub chat_shares: Vec<chat_shares::Model>,
+    /// Absent from backups taken before usage was recorded
+    #[serde(default)]
+    pub chat_usage: Vec<chat_usage::Model>,
+    /// Absent from backups taken before messages could be annotated
+    #[serde(default)]
+    pub message_annotations: Vec<message_annotations::Model>,
+}
+
+/// Row counts written by a restore
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestoreSummary {
+    pub users: usize,
+    pub chat_sessions: usize,
+    pub chat_messages: usize,
+}
+
+/// A `backup` or `restore` command-line invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupCommand {
+    Backup { out: PathBuf },
+    Restore { input: PathBuf },
+}
+
+/// Read a consistent snapshot of everything a backup covers
+///
+/// # Errors
+///
+/// Returns an error if the schema version or any table cannot be read.
+pub async fn export(db: &DatabaseConnection) -> Result<Backup, BackupError> {
+    let schema_version = schema_version(db).await?;
+
+    let txn = db
+        .begin_with_config(
+            Some(IsolationLevel::RepeatableRead),
+            Some(AccessMode::ReadOnly),
+        )
+        .await?;
+    let backup = Backup {
+        format_version: FORMAT_VERSION,
+        schema_version,
+        created_at: Utc::now(),
+        users: users::Entity::find().all(&txn).await?,
+        user_preferences: user_preferences::Entity::find().all(&txn).await?,
+        chat_sessions: chat_sessions::Entity::find().all(&txn).await?,
+        chat_messages: chat_messages::Entity::find().all(&txn).await?,
+        chat_read_states: chat_read_states::Entity::find().all(&txn).await?,
+        chat_shares: chat_shares::Entity::find().all(&txn).await?,
+        chat_usage: chat_usage::Entity::find().all(&txn).await?,
+        message_annotations: message_annotations::Entity::find().all(&txn).await?,
+    };
+    txn.commit().await?;
+
+    Ok(backup)
+}
+
+/// Serialize and encrypt a backup
+///
+/// # Errors
+///
+/// Returns `BackupError::WeakPassphrase` for short passphrases.
+pub fn encode(backup: &Backup, passphrase: &str) -> Result<Vec<u8>, BackupError> {
+    archive::seal(&serde_json::to_vec(backup)?, passphrase)
+}
+
+/// Decrypt and parse an archive produced by [`encode`]
+///
+/// # Errors
+///
+/// Returns an error for foreign data, a wrong passphrase or an unsupported
+/// payload version.
+pub fn decode(data: &[u8], passphrase: &str) -> Result<Backup, BackupError> {
+    let backup: Backup = serde_json::from_slice(&archive::open(data, passphrase)?)?;
+    if backup.format_version != FORMAT_VERSION {
+        return Err(BackupError::UnsupportedVersion(backup.format_version));
+    }
+    Ok(backup)
+}
+
+/// Load a backup into a fresh instance
+///
+/// `replace_user` is the bootstrap account allowed to exist beforehand (the
+/// admin restoring over HTTP); it is deleted and replaced by the backed up
+/// users. Everything happens in one transaction.
+///
+/// # Errors
+///
+/// Returns `BackupError::SchemaMismatch` if the migrations differ from the
+/// source instance and `BackupError::NotEmpty` if the instance holds data.
+pub async fn restore(
+    db: &DatabaseConnection,
+    backup: &Backup,
+    replace_user: Option<Uuid>,
+) -> Result<RestoreSummary, BackupError> {
+    let current = schema_version(db).await?;
+    if current != backup.schema_version {
+        return Err(BackupError::SchemaMismatch {
+            archive: backup.schema_version.clone(),
+            current,
+        });
+    }
+
+    let txn = db.begin().await?;
+
+    let existing_users: Vec<Uuid> = users::Entity::find()
+        .select_only()
+        .column(users::Column::Id)
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    let has_sessions = chat_sessions::Entity::find().count(&txn).await? > 0;
+    if has_sessions || existing_users.iter().any(|id| Some(*id) != replace_user) {
+        return Err(BackupError::NotEmpty);
+    }
+    if let Some(user_id) = replace_user {
+        users::Entity::delete_by_id(user_id).exec(&txn).await?;
+    }
+
+    // Parents before children so foreign keys resolve
+    insert_rows::<users::Entity>(&txn, &backup.users).await?;
+    insert_rows::<user_preferences::Entity>(&txn, &backup.user_preferences).await?;
+    insert_rows::<chat_sessions::Entity>(&txn, &backup.chat_sessions).await?;
+    insert_rows::<chat_messages::Entity>(&txn, &backup.chat_messages).await?;
+    insert_rows::<chat_read_states::Entity>(&txn, &backup.chat_read_states).await?;
+    insert_rows::<chat_shares::Entity>(&txn, &backup.chat_shares).await?;
+    insert_rows::<chat_usage::Entity>(&txn, &backup.chat_usage).await?;
+    insert_rows::<message_annotations::Entity>(&txn, &backup.message_annotations).await?;
+
+    txn.commit().await?;
+
+    Ok(RestoreSummary {
+        users: backup.users.len(),
+        chat_sessions: backup.chat_sessions.len(),
+        chat_messages: backup.chat_messages.len(),
+    })
+}
+
+/// Latest migration recorded in `seaql_migrations`
+///
+/// # Errors
+///
+/// Returns `BackupError::SchemaUnknown` if no migration has been applied.
+pub async fn schema_version(db: &DatabaseConnection) -> Result<String, BackupError> {
+    let statement = Statement::from_string(
+        db.get_database_backend(),
+        "SELECT version FROM seaql_migrations ORDER BY version DESC LIMIT 1",
+    );
+    let row = db
+        .query_one(statement)
+        .await
+        .map_err(|_| BackupError::SchemaUnknown)?
+        .ok_or(BackupError::SchemaUnknown)?;
+    Ok(row.try_get("", "version")?)
+}
+
+/// Parse the arguments following the `backup` or `restore` subcommand
+///
+/// # Errors
+///
+/// Returns a usage message for unknown commands or arguments and a missing
+/// path.
+pub fn parse_command<I>(command: &str, args: I) -> Result<BackupCommand, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let (flag, usage) = match command {
+        "backup" => ("--out", "usage: backup --out <path>"),
+        "restore" => ("--in", "usage: restore --in <path>"),
+        _ => return Err(format!("unknown command: {command}")),
+    };
+
+    let mut path = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            path = args.next().map(PathBuf::from);
+        } else if let Some(value) = arg.strip_prefix(flag).and_then(|v| v.strip_prefix('=')) {
+            path = Some(PathBuf::from(value));
+        } else {
+            return Err(format!("unknown argument: {arg}\n{usage}"));
+        }
+    }
+    let path = path.ok_or_else(|| format!("{flag} requires a path\n{usage}"))?;
+
+    Ok(if command == "backup" {
+        BackupCommand::Backup { out: path }
+    } else {
+        BackupCommand::Restore { input: path }
+    })
+}
+
+async fn insert_rows<E>(txn: &DatabaseTransaction, rows: &[E::Model]) -> Result<(), DbErr>
+where
+    E: EntityTrait,
+    E::Model: IntoActiveModel<E::ActiveModel> + Clone + Sync,
+    E::ActiveModel: Send,
+{
+    for chunk in rows.chunks(INSERT_BATCH_SIZE) {
+        let models = chunk
+            .iter()
+            .map(|row| row.clone().into_active_model().reset_all());
+        E::insert_many(models).exec_without_returning(txn).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    fn empty_backup() -> Backup {
+        Backup {
+            format_version: FORMAT_VERSION,
+            schema_version: "m20250201_000001_create_user_preferences".to_string(),
+            created_at: Utc::now(),
+            users: Vec::new(),
+            user_preferences: Vec::new(),
+            chat_sessions: Vec::new(),
+            chat_messages: Vec::new(),
+            chat_read_states: Vec::new(),
+            chat_shares: Vec::new(),
+            chat_usage: Vec::new(),
+            message_annotations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let backup = empty_backup();
+        let data = encode(&backup, PASSPHRASE).unwrap();
+        assert_eq!(decode(&data, PASSPHRASE).unwrap(), backup);
+        assert!(matches!(
+            decode(&data, "another passphrase"),
+            Err(BackupError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_newer_format() {
+        let backup = Backup {
+            format_version: FORMAT_VERSION + 1,
+            ..empty_backup()
+        };
+        let data = encode(&backup, PASSPHRASE).unwrap();
+        assert!(matches!(
+            decode(&data, PASSPHRASE),
+            Err(BackupError::UnsupportedVersion(v)) if v == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let args = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            parse_command("backup", args(&["--out", "a.csbk"])),
+            Ok(BackupCommand::Backup {
+                out: PathBuf::from("a.csbk")
+            })
+        );
+        assert_eq!(
+            parse_command("restore", args(&["--in=b.csbk"])),
+            Ok(BackupCommand::Restore {
+                input: PathBuf::from("b.csbk")
+            })
+        );
+        assert!(parse_command("backup", args(&[])).is_err());
+        assert!(parse_command("restore", args(&["--out", "a.csbk"])).is_err());
+    }
+
+    #[cfg(feature = "demo")]
+    mod restore {
+        use super::*;
+
+        /// In-memory instance, migrated as far as `seaql_migrations` tells
+        async fn instance(seed: bool) -> std::sync::Arc<DatabaseConnection> {
+            let db = crate::demo::open().await.unwrap();
+            if seed {
+                crate::demo::seed(&db).await.unwrap();
+            }
+            db
+        }
+
+        #[tokio::test]
+        async fn test_export_restore_round_trip() {
+            let source = instance(true).await;
+            let backup = export(&source).await.unwrap();
+            assert_eq!(backup.users.len(), 1);
+            assert_eq!(backup.chat_messages.len(), 2);
+
+            let target = instance(false).await;
+            let backup = decode(&encode(&backup, PASSPHRASE).unwrap(), PASSPHRASE).unwrap();
+            let summary = restore(&target, &backup, None).await.unwrap();
+            assert_eq!(
+                summary,
+                RestoreSummary {
+                    users: 1,
+                    chat_sessions: 1,
+                    chat_messages: 2,
+                }
+            );
+
+            let restored = export(&target).await.unwrap();
+            assert_eq!(restored.users, backup.users);
+            assert_eq!(restored.chat_messages.len(), 2);
+        }
+
+        #[tokio::test]
+        async fn test_restore_refuses_populated_or_mismatched_instances() {
+            let source = instance(true).await;
+            let backup = export(&source).await.unwrap();
+
+            assert!(matches!(
+                restore(&source, &backup, None).await,
+                Err(BackupError::NotEmpty)
+            ));
+
+            let target = instance(false).await;
+            let stale = Backup {
+                schema_version: "m20250131_000001_create_branding_settings".to_string(),
+                ..backup
+            };
+            assert!(matches!(
+                restore(&target, &stale, None).await,
+                Err(BackupError::SchemaMismatch { .. })
+            ));
+        }
+    }
+}
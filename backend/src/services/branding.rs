@@ -0,0 +1,296 @@
+//! White-label branding.
+//!
+//! The effective branding is the [`BrandingConfig`] loaded from the
+//! environment with the admin's runtime overrides applied on top. Overrides
+//! live in the single-row `branding_settings` table, so changing the
+//! branding takes effect without a rebuild or restart.
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use sea_orm::{sea_query::OnConflict, DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+
+use crate::config::BrandingConfig;
+use crate::models::{branding_settings, prelude::BrandingSettings};
+
+/// Primary key of the only `branding_settings` row
+const SETTINGS_ID: i32 = 1;
+
+/// Maximum product name length
+pub const MAX_PRODUCT_NAME_LENGTH: usize = 100;
+
+/// Maximum logo URL length
+pub const MAX_LOGO_URL_LENGTH: usize = 2048;
+
+/// Branding fields changed at runtime; `None` keeps the configured default
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BrandingOverrides {
+    pub product_name: Option<String>,
+    pub logo_url: Option<String>,
+    pub support_email: Option<String>,
+    pub primary_color: Option<String>,
+    pub accent_color: Option<String>,
+}
+
+impl BrandingOverrides {
+    /// Trim every field and treat blank values as "no override"
+    #[must_use]
+    pub fn normalized(self) -> Self {
+        let clean = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        Self {
+            product_name: clean(self.product_name),
+            logo_url: clean(self.logo_url),
+            support_email: clean(self.support_email),
+            primary_color: clean(self.primary_color).map(|c| c.to_ascii_lowercase()),
+            accent_color: clean(self.accent_color).map(|c| c.to_ascii_lowercase()),
+        }
+    }
+}
+
+impl From<branding_settings::Model> for BrandingOverrides {
+    fn from(settings: branding_settings::Model) -> Self {
+        Self {
+            product_name: settings.product_name,
+            logo_url: settings.logo_url,
+            support_email: settings.support_email,
+            primary_color: settings.primary_color,
+            accent_color: settings.accent_color,
+        }
+    }
+}
+
+/// Apply `overrides` on top of the configured defaults
+#[must_use]
+pub fn apply(defaults: &BrandingConfig, overrides: &BrandingOverrides) -> BrandingConfig {
+    BrandingConfig {
+        product_name: overrides
+            .product_name
+            .clone()
+            .unwrap_or_else(|| defaults.product_name.clone()),
+        logo_url: overrides
+            .logo_url
+            .clone()
+            .or_else(|| defaults.logo_url.clone()),
+        support_email: overrides
+            .support_email
+            .clone()
+            .or_else(|| defaults.support_email.clone()),
+        primary_color: overrides
+            .primary_color
+            .clone()
+            .unwrap_or_else(|| defaults.primary_color.clone()),
+        accent_color: overrides
+            .accent_color
+            .clone()
+            .unwrap_or_else(|| defaults.accent_color.clone()),
+    }
+}
+
+/// Check that every branding field is well-formed
+///
+/// # Errors
+///
+/// Returns an error describing the first invalid field
+pub fn validate(branding: &BrandingConfig) -> Result<()> {
+    let name_length = branding.product_name.chars().count();
+    if name_length == 0 || name_length > MAX_PRODUCT_NAME_LENGTH {
+        bail!("Product name must be between 1 and {MAX_PRODUCT_NAME_LENGTH} characters");
+    }
+
+    if let Some(logo_url) = &branding.logo_url {
+        let allowed_scheme = ["https://", "http://", "/"]
+            .iter()
+            .any(|prefix| logo_url.starts_with(prefix));
+        if !allowed_scheme || logo_url.len() > MAX_LOGO_URL_LENGTH {
+            bail!("Logo URL must be an http(s) URL or a path starting with '/'");
+        }
+    }
+
+    if let Some(email) = &branding.support_email {
+        let valid = email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+        if !valid || email.len() > 255 {
+            bail!("Invalid support email format");
+        }
+    }
+
+    for (field, color) in [
+        ("Primary color", &branding.primary_color),
+        ("Accent color", &branding.accent_color),
+    ] {
+        if !is_hex_color(color) {
+            bail!("{field} must be a hex color like #2563eb");
+        }
+    }
+
+    Ok(())
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Load the effective branding
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn current(db: &DatabaseConnection, defaults: &BrandingConfig) -> Result<BrandingConfig> {
+    let overrides = BrandingSettings::find_by_id(SETTINGS_ID)
+        .one(db)
+        .await?
+        .map(BrandingOverrides::from)
+        .unwrap_or_default();
+
+    Ok(apply(defaults, &overrides))
+}
+
+/// Replace the runtime overrides
+///
+/// Fields left `None` fall back to the configured default. Callers validate
+/// the result of [`apply`] first.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn save_overrides(
+    db: &DatabaseConnection,
+    overrides: BrandingOverrides,
+    updated_by: Uuid,
+) -> Result<()> {
+    let settings = branding_settings::ActiveModel {
+        id: Set(SETTINGS_ID),
+        product_name: Set(overrides.product_name),
+        logo_url: Set(overrides.logo_url),
+        support_email: Set(overrides.support_email),
+        primary_color: Set(overrides.primary_color),
+        accent_color: Set(overrides.accent_color),
+        updated_by: Set(Some(updated_by)),
+        updated_at: Set(Utc::now().into()),
+    };
+
+    BrandingSettings::insert(settings)
+        .on_conflict(
+            OnConflict::column(branding_settings::Column::Id)
+                .update_columns([
+                    branding_settings::Column::ProductName,
+                    branding_settings::Column::LogoUrl,
+                    branding_settings::Column::SupportEmail,
+                    branding_settings::Column::PrimaryColor,
+                    branding_settings::Column::AccentColor,
+                    branding_settings::Column::UpdatedBy,
+                    branding_settings::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Drop every runtime override, restoring the configured defaults
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn reset(db: &DatabaseConnection) -> Result<()> {
+    BrandingSettings::delete_by_id(SETTINGS_ID).exec(db).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_overrides_only_set_fields() {
+        let defaults = BrandingConfig::default();
+        let overrides = BrandingOverrides {
+            product_name: Some("Acme Chat".to_string()),
+            accent_color: Some("#ff0000".to_string()),
+            ..BrandingOverrides::default()
+        };
+
+        let branding = apply(&defaults, &overrides);
+
+        assert_eq!(branding.product_name, "Acme Chat");
+        assert_eq!(branding.accent_color, "#ff0000");
+        assert_eq!(branding.primary_color, defaults.primary_color);
+        assert_eq!(branding.logo_url, None);
+    }
+
+    #[test]
+    fn test_normalized_drops_blank_values() {
+        let overrides = BrandingOverrides {
+            product_name: Some("  Acme  ".to_string()),
+            logo_url: Some("   ".to_string()),
+            primary_color: Some("#AABBCC".to_string()),
+            ..BrandingOverrides::default()
+        }
+        .normalized();
+
+        assert_eq!(overrides.product_name.as_deref(), Some("Acme"));
+        assert_eq!(overrides.logo_url, None);
+        assert_eq!(overrides.primary_color.as_deref(), Some("#aabbcc"));
+    }
+
+    #[test]
+    fn test_validate_defaults() {
+        assert!(validate(&BrandingConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_fields() {
+        let invalid = [
+            BrandingConfig {
+                product_name: "x".repeat(MAX_PRODUCT_NAME_LENGTH + 1),
+                ..BrandingConfig::default()
+            },
+            BrandingConfig {
+                logo_url: Some("javascript:alert(1)".to_string()),
+                ..BrandingConfig::default()
+            },
+            BrandingConfig {
+                support_email: Some("support".to_string()),
+                ..BrandingConfig::default()
+            },
+            BrandingConfig {
+                primary_color: "blue".to_string(),
+                ..BrandingConfig::default()
+            },
+            BrandingConfig {
+                accent_color: "#12345".to_string(),
+                ..BrandingConfig::default()
+            },
+        ];
+
+        for branding in invalid {
+            assert!(
+                validate(&branding).is_err(),
+                "{branding:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_custom_branding() {
+        let branding = BrandingConfig {
+            product_name: "Acme Chat".to_string(),
+            logo_url: Some("/static/logo.svg".to_string()),
+            support_email: Some("help@acme.example".to_string()),
+            primary_color: "#0F172A".to_string(),
+            accent_color: "#22c55e".to_string(),
+        };
+
+        assert!(validate(&branding).is_ok());
+    }
+}
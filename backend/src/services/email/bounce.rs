@@ -0,0 +1,265 @@
+//! Parsing of bounce and complaint reports posted by the mail provider
+//!
+//! Two formats are accepted:
+//!
+//! - **Amazon SES** notifications, delivered through an SNS HTTP(S)
+//!   subscription (the SNS envelope wraps the notification in `Message`) or
+//!   posted as is. Both the notification (`notificationType`) and the event
+//!   publishing (`eventType`) flavors are understood.
+//! - A **generic** report for SMTP relays without a standard format, e.g.
+//!   from a script reading the bounce mailbox:
+//!
+//!   ```json
+//!   { "type": "hard_bounce", "email": "user@example.com", "detail": "550 5.1.1" }
+//!   ```
+//!
+//!   `type` is `hard_bounce`, `soft_bounce` or `complaint`.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// What the provider reported for one recipient
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BounceKind {
+    /// The address does not accept mail and will not (unknown mailbox, domain)
+    HardBounce,
+    /// Delivery failed for now (mailbox full, greylisting)
+    SoftBounce,
+    /// The recipient marked the message as spam
+    Complaint,
+}
+
+/// One recipient of a report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BounceEvent {
+    pub email: String,
+    pub kind: BounceKind,
+    /// Diagnostic code or feedback type, when the provider gives one
+    pub detail: Option<String>,
+}
+
+/// A parsed webhook request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BounceReport {
+    /// SNS asks to confirm the subscription by visiting `subscribe_url`
+    SubscriptionConfirmation { subscribe_url: String },
+    /// Bounces and complaints, empty for other notifications (deliveries)
+    Events(Vec<BounceEvent>),
+}
+
+/// Parse a webhook request body
+///
+/// # Errors
+///
+/// Returns a description of the problem if the body is not JSON or matches
+/// none of the accepted formats.
+pub fn parse_report(body: &[u8]) -> Result<BounceReport, String> {
+    let value: Value = serde_json::from_slice(body).map_err(|e| format!("invalid JSON: {e}"))?;
+
+    // SNS envelope
+    if let Some(kind) = value.get("Type").and_then(Value::as_str) {
+        return match kind {
+            "SubscriptionConfirmation" => {
+                let subscribe_url = value
+                    .get("SubscribeURL")
+                    .and_then(Value::as_str)
+                    .ok_or("SubscriptionConfirmation without SubscribeURL")?;
+                Ok(BounceReport::SubscriptionConfirmation {
+                    subscribe_url: subscribe_url.to_string(),
+                })
+            }
+            "Notification" => {
+                let message = value
+                    .get("Message")
+                    .and_then(Value::as_str)
+                    .ok_or("SNS notification without Message")?;
+                let notification: Value = serde_json::from_str(message)
+                    .map_err(|e| format!("SNS Message is not JSON: {e}"))?;
+                parse_ses(&notification).map(BounceReport::Events)
+            }
+            _ => Ok(BounceReport::Events(Vec::new())),
+        };
+    }
+
+    if value.get("notificationType").is_some() || value.get("eventType").is_some() {
+        return parse_ses(&value).map(BounceReport::Events);
+    }
+
+    let generic: GenericReport =
+        serde_json::from_value(value).map_err(|e| format!("unrecognized report: {e}"))?;
+    Ok(BounceReport::Events(vec![BounceEvent {
+        email: generic.email,
+        kind: generic.kind,
+        detail: generic.detail,
+    }]))
+}
+
+#[derive(Deserialize)]
+struct GenericReport {
+    #[serde(rename = "type", deserialize_with = "deserialize_kind")]
+    kind: BounceKind,
+    email: String,
+    detail: Option<String>,
+}
+
+fn deserialize_kind<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BounceKind, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+        "hard_bounce" => Ok(BounceKind::HardBounce),
+        "soft_bounce" => Ok(BounceKind::SoftBounce),
+        "complaint" => Ok(BounceKind::Complaint),
+        other => Err(serde::de::Error::unknown_variant(
+            other,
+            &["hard_bounce", "soft_bounce", "complaint"],
+        )),
+    }
+}
+
+/// Recipients of an SES bounce or complaint notification
+fn parse_ses(notification: &Value) -> Result<Vec<BounceEvent>, String> {
+    let kind = notification
+        .get("notificationType")
+        .or_else(|| notification.get("eventType"))
+        .and_then(Value::as_str)
+        .ok_or("SES notification without notificationType")?;
+
+    let (recipients, kind, detail) = match kind {
+        "Bounce" => {
+            let bounce = &notification["bounce"];
+            // `Undetermined` bounces are retried by SES, like transient ones
+            let kind = if bounce["bounceType"] == "Permanent" {
+                BounceKind::HardBounce
+            } else {
+                BounceKind::SoftBounce
+            };
+            (
+                &bounce["bouncedRecipients"],
+                kind,
+                bounce["bounceSubType"].as_str(),
+            )
+        }
+        "Complaint" => {
+            let complaint = &notification["complaint"];
+            (
+                &complaint["complainedRecipients"],
+                BounceKind::Complaint,
+                complaint["complaintFeedbackType"].as_str(),
+            )
+        }
+        _ => return Ok(Vec::new()),
+    };
+
+    let recipients = recipients
+        .as_array()
+        .ok_or("SES notification without recipients")?;
+    Ok(recipients
+        .iter()
+        .filter_map(|recipient| {
+            let email = recipient["emailAddress"].as_str()?;
+            Some(BounceEvent {
+                email: email.to_string(),
+                kind,
+                detail: recipient["diagnosticCode"]
+                    .as_str()
+                    .or(detail)
+                    .map(str::to_string),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SES_BOUNCE: &str = r#"{
+        "notificationType": "Bounce",
+        "bounce": {
+            "bounceType": "Permanent",
+            "bounceSubType": "General",
+            "bouncedRecipients": [
+                {"emailAddress": "gone@example.com", "diagnosticCode": "smtp; 550 5.1.1 user unknown"}
+            ]
+        },
+        "mail": {"destination": ["gone@example.com"]}
+    }"#;
+
+    fn events(report: BounceReport) -> Vec<BounceEvent> {
+        match report {
+            BounceReport::Events(events) => events,
+            BounceReport::SubscriptionConfirmation { .. } => panic!("expected events"),
+        }
+    }
+
+    #[test]
+    fn test_parse_ses_bounce() {
+        assert_eq!(
+            events(parse_report(SES_BOUNCE.as_bytes()).unwrap()),
+            [BounceEvent {
+                email: "gone@example.com".to_string(),
+                kind: BounceKind::HardBounce,
+                detail: Some("smtp; 550 5.1.1 user unknown".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_sns_envelope() {
+        let envelope = serde_json::json!({
+            "Type": "Notification",
+            "Message": SES_BOUNCE,
+        });
+        let bounced = events(parse_report(envelope.to_string().as_bytes()).unwrap());
+        assert_eq!(bounced.len(), 1);
+        assert_eq!(bounced[0].kind, BounceKind::HardBounce);
+
+        let confirmation = serde_json::json!({
+            "Type": "SubscriptionConfirmation",
+            "SubscribeURL": "https://sns.example.com/confirm",
+        });
+        assert_eq!(
+            parse_report(confirmation.to_string().as_bytes()).unwrap(),
+            BounceReport::SubscriptionConfirmation {
+                subscribe_url: "https://sns.example.com/confirm".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_ses_complaint_and_transient_bounce() {
+        let complaint = serde_json::json!({
+            "eventType": "Complaint",
+            "complaint": {
+                "complaintFeedbackType": "abuse",
+                "complainedRecipients": [{"emailAddress": "angry@example.com"}]
+            }
+        });
+        let complained = events(parse_report(complaint.to_string().as_bytes()).unwrap());
+        assert_eq!(complained[0].kind, BounceKind::Complaint);
+        assert_eq!(complained[0].detail.as_deref(), Some("abuse"));
+
+        let transient = SES_BOUNCE.replace("Permanent", "Transient");
+        let bounced = events(parse_report(transient.as_bytes()).unwrap());
+        assert_eq!(bounced[0].kind, BounceKind::SoftBounce);
+
+        let delivery = serde_json::json!({"notificationType": "Delivery"});
+        assert!(events(parse_report(delivery.to_string().as_bytes()).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_parse_generic_report() {
+        let report = br#"{"type": "complaint", "email": "user@example.com"}"#;
+        assert_eq!(
+            events(parse_report(report).unwrap()),
+            [BounceEvent {
+                email: "user@example.com".to_string(),
+                kind: BounceKind::Complaint,
+                detail: None,
+            }]
+        );
+
+        assert!(parse_report(br#"{"type": "bounce", "email": "user@example.com"}"#).is_err());
+        assert!(parse_report(b"not json").is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! History of outbound emails.
+//!
+//! [`LoggingEmailSender`] wraps the configured sender and hands one
+//! [`EmailDelivery`] per email to the [`DeliveryLogger`], whose background
+//! worker writes them to the `email_log` table in batches. Support can then
+//! answer "I never got the verification email" from
+//! `GET /api/v1/admin/email-log`: whether the email was sent, when, with
+//! which provider message id, or why it failed.
+//!
+//! Mail dropped for a suppressed address never reaches this sender; the
+//! suppression list tells those cases apart.
+//!
+//! Logging never delays a send: when the queue is full because the database
+//! is down or too slow, entries are dropped and counted in a warning.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use uuid::Uuid;
+
+use super::{EmailMessage, EmailSender};
+use crate::models::{email_log, prelude::EmailLog};
+
+/// Entries waiting to be written before new ones are dropped
+pub const QUEUE_CAPACITY: usize = 1_000;
+
+/// Largest number of entries written at once
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Kind of email, as stored in `email_log.kind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailKind {
+    /// Email verification link
+    Verification,
+    /// Email about the recipient's account (sign-in alerts, reports)
+    Transactional,
+    /// Email the recipient can unsubscribe from (digests)
+    Bulk,
+}
+
+impl EmailKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Verification => "verification",
+            Self::Transactional => "transactional",
+            Self::Bulk => "bulk",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "verification" => Some(Self::Verification),
+            "transactional" => Some(Self::Transactional),
+            "bulk" => Some(Self::Bulk),
+            _ => None,
+        }
+    }
+
+    /// Kind of a rendered message
+    #[must_use]
+    pub const fn of(message: &EmailMessage) -> Self {
+        if message.unsubscribe_url.is_some() {
+            Self::Bulk
+        } else {
+            Self::Transactional
+        }
+    }
+}
+
+/// Delivery outcome, as stored in `email_log.status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Accepted by the provider
+    Sent,
+    /// Rejected by the provider or not sent at all
+    Failed,
+}
+
+impl DeliveryStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "sent" => Some(Self::Sent),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One outbound email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailDelivery {
+    /// When the email was handed to the provider
+    pub created_at: DateTime<Utc>,
+    pub kind: EmailKind,
+    /// Recipient address, lowercase
+    pub recipient: String,
+    pub template: Option<&'static str>,
+    /// Message id assigned by the provider, if it reports one
+    pub message_id: Option<String>,
+    pub status: DeliveryStatus,
+    /// Why delivery failed
+    pub error: Option<String>,
+}
+
+impl EmailDelivery {
+    fn new(
+        kind: EmailKind,
+        to: &str,
+        template: Option<&'static str>,
+        outcome: Result<Option<String>, String>,
+    ) -> Self {
+        let (message_id, status, error) = match outcome {
+            Ok(message_id) => (message_id, DeliveryStatus::Sent, None),
+            Err(error) => (None, DeliveryStatus::Failed, Some(error)),
+        };
+        Self {
+            created_at: Utc::now(),
+            kind,
+            recipient: to.trim().to_lowercase(),
+            template,
+            message_id,
+            status,
+            error,
+        }
+    }
+}
+
+/// Queues deliveries for the background writer
+#[derive(Clone)]
+pub struct DeliveryLogger {
+    sender: mpsc::Sender<EmailDelivery>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl DeliveryLogger {
+    /// Start the background writer to the `email_log` table
+    ///
+    /// Must be called from within a Tokio runtime.
+    #[must_use]
+    pub fn spawn(db: Arc<DatabaseConnection>) -> Self {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+        tokio::spawn(write_batches(receiver, db, Arc::clone(&dropped)));
+
+        Self { sender, dropped }
+    }
+
+    /// Queue a delivery without waiting
+    pub fn record(&self, delivery: EmailDelivery) {
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(delivery) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Write queued deliveries until every [`DeliveryLogger`] is dropped
+async fn write_batches(
+    mut receiver: mpsc::Receiver<EmailDelivery>,
+    db: Arc<DatabaseConnection>,
+    dropped: Arc<AtomicU64>,
+) {
+    let mut batch = Vec::with_capacity(MAX_BATCH_SIZE);
+    while receiver.recv_many(&mut batch, MAX_BATCH_SIZE).await > 0 {
+        if let Err(e) = write(&db, &batch).await {
+            tracing::error!("Failed to write {} email log entries: {}", batch.len(), e);
+        }
+        batch.clear();
+
+        let dropped = dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            tracing::warn!("Dropped {} email log entries: queue full", dropped);
+        }
+    }
+}
+
+async fn write(db: &DatabaseConnection, deliveries: &[EmailDelivery]) -> Result<()> {
+    EmailLog::insert_many(deliveries.iter().map(|delivery| email_log::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        kind: Set(delivery.kind.as_str().to_string()),
+        recipient: Set(delivery.recipient.clone()),
+        template: Set(delivery.template.map(str::to_string)),
+        message_id: Set(delivery.message_id.clone()),
+        status: Set(delivery.status.as_str().to_string()),
+        error: Set(delivery.error.clone()),
+        created_at: Set(delivery.created_at.into()),
+    }))
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Sender that records every email it sends in the delivery log
+pub struct LoggingEmailSender {
+    inner: Arc<dyn EmailSender + Send + Sync>,
+    logger: DeliveryLogger,
+}
+
+impl LoggingEmailSender {
+    #[must_use]
+    pub fn new(inner: Arc<dyn EmailSender + Send + Sync>, logger: DeliveryLogger) -> Self {
+        Self { inner, logger }
+    }
+}
+
+impl EmailSender for LoggingEmailSender {
+    fn send_verification_email(&self, to: &str, verification_url: &str) -> Result<()> {
+        let result = self.inner.send_verification_email(to, verification_url);
+        let outcome = result.as_ref().map(|()| None).map_err(ToString::to_string);
+        self.logger.record(EmailDelivery::new(
+            EmailKind::Verification,
+            to,
+            None,
+            outcome,
+        ));
+        result
+    }
+
+    fn send_email(&self, message: &EmailMessage) -> Result<()> {
+        self.send_email_with_id(message).map(|_| ())
+    }
+
+    fn send_email_with_id(&self, message: &EmailMessage) -> Result<Option<String>> {
+        let result = self.inner.send_email_with_id(message);
+        self.logger.record(EmailDelivery::new(
+            EmailKind::of(message),
+            &message.to,
+            message.template,
+            result
+                .as_ref()
+                .map(Clone::clone)
+                .map_err(ToString::to_string),
+        ));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+
+    struct ProviderSender;
+
+    impl EmailSender for ProviderSender {
+        fn send_verification_email(&self, _to: &str, _verification_url: &str) -> Result<()> {
+            bail!("mailbox unavailable")
+        }
+
+        fn send_email(&self, message: &EmailMessage) -> Result<()> {
+            self.send_email_with_id(message).map(|_| ())
+        }
+
+        fn send_email_with_id(&self, _message: &EmailMessage) -> Result<Option<String>> {
+            Ok(Some("msg-42".to_string()))
+        }
+    }
+
+    fn logger() -> (DeliveryLogger, mpsc::Receiver<EmailDelivery>) {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        let logger = DeliveryLogger {
+            sender,
+            dropped: Arc::new(AtomicU64::new(0)),
+        };
+        (logger, receiver)
+    }
+
+    #[test]
+    fn test_records_sent_message_with_provider_id() {
+        let (logger, mut receiver) = logger();
+        let sender = LoggingEmailSender::new(Arc::new(ProviderSender), logger);
+        let message = EmailMessage {
+            to: "Alice@Example.com".to_string(),
+            subject: "Subject".to_string(),
+            body: "Body".to_string(),
+            template: Some("weekly_digest"),
+            unsubscribe_url: Some("http://localhost/unsubscribe".to_string()),
+        };
+
+        sender.send_email(&message).unwrap();
+
+        let delivery = receiver.try_recv().unwrap();
+        assert_eq!(delivery.kind, EmailKind::Bulk);
+        assert_eq!(delivery.recipient, "alice@example.com");
+        assert_eq!(delivery.template, Some("weekly_digest"));
+        assert_eq!(delivery.message_id.as_deref(), Some("msg-42"));
+        assert_eq!(delivery.status, DeliveryStatus::Sent);
+    }
+
+    #[test]
+    fn test_records_failed_verification() {
+        let (logger, mut receiver) = logger();
+        let sender = LoggingEmailSender::new(Arc::new(ProviderSender), logger);
+
+        assert!(sender
+            .send_verification_email("bob@example.com", "token")
+            .is_err());
+
+        let delivery = receiver.try_recv().unwrap();
+        assert_eq!(delivery.kind, EmailKind::Verification);
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+        assert_eq!(delivery.error.as_deref(), Some("mailbox unavailable"));
+        assert_eq!(delivery.message_id, None);
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for kind in [
+            EmailKind::Verification,
+            EmailKind::Transactional,
+            EmailKind::Bulk,
+        ] {
+            assert_eq!(EmailKind::parse(kind.as_str()), Some(kind));
+        }
+        for status in [DeliveryStatus::Sent, DeliveryStatus::Failed] {
+            assert_eq!(DeliveryStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(EmailKind::parse("marketing"), None);
+    }
+}
@@ -0,0 +1,403 @@
+//! Weekly activity digest emails.
+//!
+//! Users opt in through the digest preference endpoint. The scheduler calls
+//! [`send_due_digests`] periodically; each due subscription is claimed with a
+//! conditional update before sending so concurrent instances never send the
+//! same digest twice. Digests with no activity and no notifications are
+//! skipped but still count as sent.
+//!
+//! Every digest carries an unsubscribe link that works without logging in.
+//! Opening it shows a confirmation page; the subscription only changes on
+//! `POST`, which mail clients send directly for the `List-Unsubscribe` and
+//! `List-Unsubscribe-Post` headers (RFC 8058 one-click).
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
+    JoinType, PaginatorTrait, QueryFilter, QuerySelect, RelationTrait, Set,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{templates::WEEKLY_DIGEST, EmailMessage, EmailSender};
+use crate::config::DigestConfig;
+use crate::models::{
+    chat_messages, chat_sessions, email_digest_subscriptions,
+    prelude::{ChatMessages, EmailDigestSubscriptions, Users},
+    users,
+};
+use crate::services::valkey::{notifications, notifications::Notification, ValkeyManager};
+use crate::utils::token::generate_verification_token;
+
+/// Chat activity of one user over a digest period
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DigestActivity {
+    /// Messages the user sent
+    pub messages_sent: u64,
+    /// Sessions with at least one message
+    pub active_sessions: u64,
+}
+
+impl DigestActivity {
+    /// Check whether there is anything to report
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.messages_sent == 0 && self.active_sessions == 0
+    }
+}
+
+/// Find a user's digest subscription
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn find_subscription(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Option<email_digest_subscriptions::Model>> {
+    Ok(EmailDigestSubscriptions::find_by_id(user_id)
+        .one(db)
+        .await?)
+}
+
+/// Turn the digest on or off for a user
+///
+/// The unsubscribe token is created with the subscription and kept when the
+/// digest is turned off, so links in earlier emails stay valid.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn set_subscription(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    enabled: bool,
+) -> Result<email_digest_subscriptions::Model> {
+    let now = Utc::now();
+
+    let model = match find_subscription(db, user_id).await? {
+        Some(existing) => {
+            let mut active: email_digest_subscriptions::ActiveModel = existing.into();
+            active.enabled = Set(enabled);
+            active.updated_at = Set(now.into());
+            active.update(db).await?
+        }
+        None => {
+            email_digest_subscriptions::ActiveModel {
+                user_id: Set(user_id),
+                enabled: Set(enabled),
+                unsubscribe_token: Set(generate_verification_token()),
+                last_sent_at: Set(None),
+                created_at: Set(now.into()),
+                updated_at: Set(now.into()),
+            }
+            .insert(db)
+            .await?
+        }
+    };
+
+    Ok(model)
+}
+
+/// Turn the digest off for the subscription owning `token`
+///
+/// Returns `false` if the token is unknown. Unsubscribing twice succeeds.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn unsubscribe(db: &DatabaseConnection, token: &str) -> Result<bool> {
+    let result = EmailDigestSubscriptions::update_many()
+        .col_expr(
+            email_digest_subscriptions::Column::Enabled,
+            Expr::value(false),
+        )
+        .col_expr(
+            email_digest_subscriptions::Column::UpdatedAt,
+            Expr::value(Utc::now()),
+        )
+        .filter(email_digest_subscriptions::Column::UnsubscribeToken.eq(token))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+/// Build the public one-click unsubscribe URL for a token
+#[must_use]
+pub fn unsubscribe_url(public_base_url: &str, token: &str) -> String {
+    format!("{public_base_url}/api/v1/email/unsubscribe?token={token}")
+}
+
+/// Collect a user's chat activity since `since`
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn collect_activity(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<DigestActivity> {
+    let user_messages = || {
+        ChatMessages::find()
+            .join(
+                JoinType::InnerJoin,
+                chat_messages::Relation::ChatSessions.def(),
+            )
+            .filter(chat_sessions::Column::UserId.eq(user_id))
+            .filter(chat_messages::Column::CreatedAt.gte(since))
+    };
+
+    let messages_sent = user_messages()
+        .filter(chat_messages::Column::Role.eq("user"))
+        .count(db)
+        .await?;
+
+    let active_sessions = user_messages()
+        .select_only()
+        .column(chat_messages::Column::SessionId)
+        .distinct()
+        .count(db)
+        .await?;
+
+    Ok(DigestActivity {
+        messages_sent,
+        active_sessions,
+    })
+}
+
+/// Render the digest email for a user
+///
+/// # Errors
+///
+/// Returns an error if the template fails to render
+pub fn render_digest(
+    user: &users::Model,
+    activity: DigestActivity,
+    notifications: &[Notification],
+    period: (DateTime<Utc>, DateTime<Utc>),
+    unsubscribe_url: &str,
+) -> Result<EmailMessage> {
+    let notification_lines = if notifications.is_empty() {
+        "- None".to_string()
+    } else {
+        notifications
+            .iter()
+            .map(|notification| format!("- {}", notification.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let vars = HashMap::from([
+        ("username", user.username.clone()),
+        ("period_start", period.0.format("%Y-%m-%d").to_string()),
+        ("period_end", period.1.format("%Y-%m-%d").to_string()),
+        ("messages_sent", activity.messages_sent.to_string()),
+        ("active_sessions", activity.active_sessions.to_string()),
+        ("notifications", notification_lines),
+        ("unsubscribe_url", unsubscribe_url.to_string()),
+    ]);
+    let (subject, body) = WEEKLY_DIGEST.render(&vars)?;
+
+    Ok(EmailMessage {
+        to: user.email.clone(),
+        subject,
+        body,
+        template: Some(WEEKLY_DIGEST.name),
+        unsubscribe_url: Some(unsubscribe_url.to_string()),
+    })
+}
+
+/// Send every digest that is due; returns the number of emails sent
+///
+/// Only verified, enabled accounts receive digests. A failed delivery is
+/// logged and not retried until the next period.
+///
+/// # Errors
+///
+/// Returns an error if due subscriptions cannot be loaded
+pub async fn send_due_digests(
+    db: &DatabaseConnection,
+    valkey: Option<&ValkeyManager>,
+    sender: &(dyn EmailSender + Send + Sync),
+    config: &DigestConfig,
+) -> Result<usize> {
+    let now = Utc::now();
+    let period = Duration::from_std(config.period)?;
+    let cutoff = now - period;
+    let due = Condition::any()
+        .add(email_digest_subscriptions::Column::LastSentAt.is_null())
+        .add(email_digest_subscriptions::Column::LastSentAt.lt(cutoff));
+
+    let subscriptions = EmailDigestSubscriptions::find()
+        .filter(email_digest_subscriptions::Column::Enabled.eq(true))
+        .filter(due.clone())
+        .find_also_related(Users)
+        .all(db)
+        .await?;
+
+    let mut sent = 0;
+    for (subscription, user) in subscriptions {
+        let Some(user) = user.filter(|u| u.email_verified && u.disabled_at.is_none()) else {
+            continue;
+        };
+
+        // Claim the digest; another instance may have sent it already
+        let claimed = EmailDigestSubscriptions::update_many()
+            .col_expr(
+                email_digest_subscriptions::Column::LastSentAt,
+                Expr::value(now),
+            )
+            .filter(email_digest_subscriptions::Column::UserId.eq(user.id))
+            .filter(due.clone())
+            .exec(db)
+            .await?;
+        if claimed.rows_affected == 0 {
+            continue;
+        }
+
+        let since = subscription
+            .last_sent_at
+            .map_or(cutoff, |at| at.with_timezone(&Utc));
+        let activity = collect_activity(db, user.id, since).await?;
+        let notifications = recent_notifications(valkey, user.id, since).await;
+        if activity.is_empty() && notifications.is_empty() {
+            continue;
+        }
+
+        let url = unsubscribe_url(&config.public_base_url, &subscription.unsubscribe_token);
+        let result = render_digest(&user, activity, &notifications, (since, now), &url)
+            .and_then(|message| sender.send_email(&message));
+        match result {
+            Ok(()) => sent += 1,
+            Err(e) => tracing::warn!("Failed to send digest to user {}: {}", user.id, e),
+        }
+    }
+
+    Ok(sent)
+}
+
+/// Notifications created after `since`; empty if Valkey is unavailable
+async fn recent_notifications(
+    valkey: Option<&ValkeyManager>,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+) -> Vec<Notification> {
+    let Some(valkey) = valkey else {
+        return Vec::new();
+    };
+
+    let list = async {
+        let mut conn = valkey.get_async_connection().await?;
+        notifications::list_notifications(&mut conn, user_id).await
+    };
+    match list.await {
+        Ok(list) => list.into_iter().filter(|n| n.created_at > since).collect(),
+        Err(e) => {
+            tracing::warn!("Failed to load notifications for digest: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::sea_orm_active_enums::UserRole;
+
+    fn user() -> users::Model {
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password_hash: None,
+            email_verified: true,
+            created_at: Utc::now().into(),
+            updated_at: Utc::now().into(),
+            role: UserRole::User,
+            disabled_at: None,
+            last_login_at: None,
+            tokens_not_before: None,
+            password_changed_at: None,
+            password_rotation_required: false,
+        }
+    }
+
+    #[test]
+    fn test_unsubscribe_url() {
+        assert_eq!(
+            unsubscribe_url("https://example.com", "abc"),
+            "https://example.com/api/v1/email/unsubscribe?token=abc"
+        );
+    }
+
+    #[test]
+    fn test_render_digest() {
+        let now = Utc::now();
+        let activity = DigestActivity {
+            messages_sent: 12,
+            active_sessions: 3,
+        };
+        let notifications = [Notification::new("quota_warning", "80% of quota used")];
+
+        let message = render_digest(
+            &user(),
+            activity,
+            &notifications,
+            (now - Duration::days(7), now),
+            "https://example.com/unsubscribe",
+        )
+        .unwrap();
+
+        assert_eq!(message.to, "alice@example.com");
+        assert!(message.body.contains("Hi alice"));
+        assert!(message.body.contains("Messages sent: 12"));
+        assert!(message.body.contains("- 80% of quota used"));
+        assert_eq!(
+            message.unsubscribe_url.as_deref(),
+            Some("https://example.com/unsubscribe")
+        );
+    }
+
+    #[test]
+    fn test_render_digest_without_notifications() {
+        let now = Utc::now();
+
+        let message = render_digest(
+            &user(),
+            DigestActivity::default(),
+            &[],
+            (now, now),
+            "https://example.com/unsubscribe",
+        )
+        .unwrap();
+
+        assert!(message.body.contains("- None"));
+    }
+
+    #[test]
+    fn test_activity_is_empty() {
+        assert!(DigestActivity::default().is_empty());
+        assert!(!DigestActivity {
+            messages_sent: 1,
+            active_sessions: 1,
+        }
+        .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_unknown_token() {
+        use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .into_connection();
+
+        assert!(!unsubscribe(&db, "unknown").await.unwrap());
+    }
+}
@@ -0,0 +1,208 @@
+//! New sign-in and session revocation notification emails.
+//!
+//! A login from a device (browser and operating system, see
+//! [`crate::utils::user_agent`]) that has no active session yet emails the
+//! account owner with the device, IP address and time. Devices are compared
+//! by label, not raw User-Agent, so browser updates do not trigger alerts.
+//!
+//! A refresh token replayed from another device (see
+//! [`crate::services::auth::token_binding`]) signs out the session it
+//! belongs to and emails the user the same details about the offending
+//! client.
+//!
+//! With trusted devices enabled (see
+//! [`crate::services::auth::trusted_devices`]), a sign-in from an unconfirmed
+//! device emails the same details with a link confirming the device instead.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use super::templates::{EmailTemplate, DEVICE_CONFIRMATION, NEW_LOGIN, SESSIONS_REVOKED};
+use super::EmailMessage;
+use crate::models::{refresh_tokens, users};
+use crate::utils::user_agent::device_label;
+
+/// Whether `user_agent` belongs to a device without an active session
+#[must_use]
+pub fn is_new_device(active_sessions: &[refresh_tokens::Model], user_agent: Option<&str>) -> bool {
+    let device = device_label(user_agent);
+    !active_sessions
+        .iter()
+        .any(|session| device_label(session.user_agent.as_deref()) == device)
+}
+
+/// Render the new sign-in email for `user`
+///
+/// # Errors
+///
+/// Returns an error if the template fails to render
+pub fn render_new_login(
+    user: &users::Model,
+    device: &str,
+    ip: &str,
+    at: DateTime<Utc>,
+) -> Result<EmailMessage> {
+    render_device_alert(&NEW_LOGIN, user, device, ip, at)
+}
+
+/// Render the email telling `user` their sessions were revoked after a
+/// refresh token was used from `device`
+///
+/// # Errors
+///
+/// Returns an error if the template fails to render
+pub fn render_sessions_revoked(
+    user: &users::Model,
+    device: &str,
+    ip: &str,
+    at: DateTime<Utc>,
+) -> Result<EmailMessage> {
+    render_device_alert(&SESSIONS_REVOKED, user, device, ip, at)
+}
+
+/// Render the email asking `user` to confirm the device they signed in from
+///
+/// # Errors
+///
+/// Returns an error if the template fails to render
+pub fn render_device_confirmation(
+    user: &users::Model,
+    device: &str,
+    ip: &str,
+    at: DateTime<Utc>,
+    confirmation_url: &str,
+    valid_minutes: i64,
+) -> Result<EmailMessage> {
+    let extra = [
+        ("confirmation_url", confirmation_url.to_string()),
+        ("valid_minutes", valid_minutes.to_string()),
+    ];
+    render_device_email(&DEVICE_CONFIRMATION, user, device, ip, at, extra)
+}
+
+fn render_device_alert(
+    template: &EmailTemplate,
+    user: &users::Model,
+    device: &str,
+    ip: &str,
+    at: DateTime<Utc>,
+) -> Result<EmailMessage> {
+    render_device_email(template, user, device, ip, at, [])
+}
+
+fn render_device_email<const N: usize>(
+    template: &EmailTemplate,
+    user: &users::Model,
+    device: &str,
+    ip: &str,
+    at: DateTime<Utc>,
+    extra: [(&str, String); N],
+) -> Result<EmailMessage> {
+    let mut vars = HashMap::from([
+        ("username", user.username.clone()),
+        ("device", device.to_string()),
+        ("ip", ip.to_string()),
+        ("time", at.format("%Y-%m-%d %H:%M UTC").to_string()),
+    ]);
+    vars.extend(extra);
+    let (subject, body) = template.render(&vars)?;
+
+    Ok(EmailMessage {
+        to: user.email.clone(),
+        subject,
+        body,
+        template: Some(template.name),
+        unsubscribe_url: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::sea_orm_active_enums::UserRole;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    const MAC_CHROME: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) \
+                              AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36";
+
+    fn session(user_agent: Option<&str>) -> refresh_tokens::Model {
+        let now = Utc::now().into();
+        refresh_tokens::Model {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            family_id: Uuid::new_v4(),
+            token_hash: "hash".to_string(),
+            expires_at: now,
+            revoked_at: None,
+            created_at: now,
+            user_agent: user_agent.map(ToString::to_string),
+            fingerprint: None,
+            ip: None,
+            last_used_at: Some(now),
+        }
+    }
+
+    #[test]
+    fn test_is_new_device_compares_labels() {
+        let sessions = [session(Some(MAC_CHROME)), session(None)];
+
+        let upgraded = MAC_CHROME.replace("Chrome/126", "Chrome/127");
+        assert!(!is_new_device(&sessions, Some(&upgraded)));
+        assert!(!is_new_device(&sessions, None));
+        assert!(is_new_device(&sessions, Some("curl/8.7.1")));
+        assert!(is_new_device(&[], Some(MAC_CHROME)));
+    }
+
+    #[test]
+    fn test_render_new_login() {
+        let now = Utc::now().into();
+        let user = users::Model {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password_hash: None,
+            role: UserRole::User,
+            email_verified: true,
+            disabled_at: None,
+            last_login_at: None,
+            tokens_not_before: None,
+            password_changed_at: None,
+            password_rotation_required: false,
+            created_at: now,
+            updated_at: now,
+        };
+        let at = Utc.with_ymd_and_hms(2025, 2, 3, 14, 5, 0).unwrap();
+
+        let message = render_new_login(&user, "Chrome on macOS", "203.0.113.7", at).unwrap();
+
+        assert_eq!(message.to, "alice@example.com");
+        assert!(message.subject.contains("New sign-in"));
+        assert!(message.body.contains("Device: Chrome on macOS"));
+        assert!(message.body.contains("IP address: 203.0.113.7"));
+        assert!(message.body.contains("Time: 2025-02-03 14:05 UTC"));
+        assert_eq!(message.unsubscribe_url, None);
+
+        let revoked =
+            render_sessions_revoked(&user, "Firefox on Linux", "198.51.100.2", at).unwrap();
+        assert!(revoked.subject.contains("signed out"));
+        assert!(revoked.body.contains("Device: Firefox on Linux"));
+        assert!(revoked.body.contains("IP address: 198.51.100.2"));
+
+        let confirmation = render_device_confirmation(
+            &user,
+            "curl",
+            "198.51.100.2",
+            at,
+            "http://localhost:2727/confirm-device?token=abc",
+            60,
+        )
+        .unwrap();
+        assert!(confirmation.subject.contains("Confirm the new device"));
+        assert!(confirmation
+            .body
+            .contains("http://localhost:2727/confirm-device?token=abc"));
+        assert!(confirmation.body.contains("expires in 60 minutes"));
+    }
+}
@@ -1,14 +1,24 @@
 //! Email delivery service for user communication.
 //!
 //! This module provides email sending functionality with support for both
-//! mock (development) and production SMTP implementations. Currently handles
-//! email verification but designed to be extensible for other email types.
+//! mock (development) and production SMTP implementations. Handles email
+//! verification, the weekly activity digest and new sign-in alerts, and stops
+//! sending to addresses that bounce or complain.
 //!
 //! # Architecture
 //!
 //! - **`EmailSender` trait**: Abstraction for different email backends
 //! - **`MockEmailSender`**: Development implementation that logs to console
 //! - **verification**: Email verification token management
+//! - **templates**: Placeholder-based subject/body templates
+//! - **digest**: Weekly activity digest and unsubscribe handling
+//! - **`login_alert`**: New sign-in notifications for unrecognized devices and
+//!   confirmation links for untrusted ones
+//! - **bounce**: Parsing of provider bounce and complaint reports
+//! - **suppression**: Suppression list and the `SuppressingEmailSender`
+//!   wrapper that skips suppressed addresses
+//! - **`delivery_log`**: The `email_log` table of sent and failed emails and
+//!   the `LoggingEmailSender` wrapper that fills it
 //!
 //! # Usage
 //!
@@ -16,21 +26,67 @@
 //! use cobalt_stack_backend::services::email::{EmailSender, MockEmailSender};
 //!
 //! let sender = MockEmailSender;
-//! sender.send_verification_email("user@example.com", "abc123token").unwrap();
+//! sender
+//!     .send_verification_email("user@example.com", "https://app.example.com/verify-email?token=abc")
+//!     .unwrap();
 //! ```
 //!
 //! # Future Extensions
 //!
 //! - SMTP implementation for production email delivery
-//! - Template-based email rendering
 //! - Password reset emails
 //! - Welcome emails
-//! - Notification emails
 
+pub mod bounce;
+pub mod delivery_log;
+pub mod digest;
+pub mod login_alert;
+pub mod suppression;
+pub mod templates;
 mod verification;
 
 use anyhow::Result;
-pub use verification::{create_verification_token, verify_email_token};
+use std::collections::HashMap;
+pub use verification::{
+    create_verification_token, force_verify_email, resend_verification_token, verify_email_token,
+    ResendOutcome, RESEND_COOLDOWN_SECS,
+};
+
+/// A rendered email ready for delivery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    /// Recipient email address
+    pub to: String,
+    /// Subject line
+    pub subject: String,
+    /// Plain-text body
+    pub body: String,
+    /// Name of the template the message was rendered from
+    pub template: Option<&'static str>,
+    /// One-click unsubscribe URL for the `List-Unsubscribe` header (RFC 8058)
+    pub unsubscribe_url: Option<String>,
+}
+
+/// `List-Unsubscribe-Post` value marking the `List-Unsubscribe` URL as
+/// one-click, so mail clients `POST` to it instead of opening it (RFC 8058)
+pub const LIST_UNSUBSCRIBE_POST: &str = "List-Unsubscribe=One-Click";
+
+impl EmailMessage {
+    /// Headers a sender must add for the unsubscribe URL
+    ///
+    /// Empty for messages without one. Sending `List-Unsubscribe` without
+    /// `List-Unsubscribe-Post` would make clients open the URL with `GET`,
+    /// which only shows a confirmation page.
+    #[must_use]
+    pub fn list_unsubscribe_headers(&self) -> Vec<(&'static str, String)> {
+        self.unsubscribe_url.as_ref().map_or_else(Vec::new, |url| {
+            vec![
+                ("List-Unsubscribe", format!("<{url}>")),
+                ("List-Unsubscribe-Post", LIST_UNSUBSCRIBE_POST.to_string()),
+            ]
+        })
+    }
+}
 
 /// Abstraction for email sending implementations.
 ///
@@ -48,12 +104,12 @@ pub use verification::{create_verification_token, verify_email_token};
 /// ```
 /// use cobalt_stack_backend::services::email::{EmailSender, MockEmailSender};
 ///
-/// fn send_verification(sender: &impl EmailSender, email: &str, token: &str) {
-///     sender.send_verification_email(email, token).unwrap();
+/// fn send_verification(sender: &impl EmailSender, email: &str, url: &str) {
+///     sender.send_verification_email(email, url).unwrap();
 /// }
 ///
 /// let mock_sender = MockEmailSender;
-/// send_verification(&mock_sender, "user@example.com", "abc123");
+/// send_verification(&mock_sender, "user@example.com", "myapp://verify?token=abc123");
 /// ```
 pub trait EmailSender {
     /// Send an email verification link to the user.
@@ -61,13 +117,38 @@ pub trait EmailSender {
     /// # Arguments
     ///
     /// * `to` - Recipient email address
-    /// * `token` - Verification token to include in link
+    /// * `verification_url` - Link to open, built for the user's client (see
+    ///   [`crate::config::VerificationLinkConfig`]); rendered into
+    ///   [`templates::VERIFY_EMAIL`]
     ///
     /// # Returns
     ///
     /// - `Ok(())` - Email sent successfully (or logged for mock)
     /// - `Err(_)` - Email delivery failed
-    fn send_verification_email(&self, to: &str, token: &str) -> Result<()>;
+    fn send_verification_email(&self, to: &str, verification_url: &str) -> Result<()>;
+
+    /// Send a rendered email.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())` - Email sent successfully (or logged for mock)
+    /// - `Err(_)` - Email delivery failed
+    fn send_email(&self, message: &EmailMessage) -> Result<()>;
+
+    /// Send a rendered email, returning the message id assigned by the
+    /// provider when it reports one.
+    ///
+    /// The delivery log records the id, so a delivery can be looked up in the
+    /// provider's console. Senders without ids keep the default.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(id))` - Email accepted by the provider under `id`
+    /// - `Ok(None)` - Email sent, the sender has no message ids
+    /// - `Err(_)` - Email delivery failed
+    fn send_email_with_id(&self, message: &EmailMessage) -> Result<Option<String>> {
+        self.send_email(message).map(|()| None)
+    }
 }
 
 /// Mock email sender for development and testing.
@@ -81,19 +162,35 @@ pub trait EmailSender {
 /// use cobalt_stack_backend::services::email::{EmailSender, MockEmailSender};
 ///
 /// let sender = MockEmailSender;
-/// sender.send_verification_email("test@example.com", "abc123").unwrap();
+/// sender
+///     .send_verification_email("test@example.com", "http://localhost:2727/verify-email?token=abc123")
+///     .unwrap();
 /// // Logs: "📧 [MOCK EMAIL] Sending verification email to: test@example.com"
 /// // Logs: "📧 [MOCK EMAIL] Verification link: http://localhost:2727/verify-email?token=abc123"
 /// ```
 pub struct MockEmailSender;
 
 impl EmailSender for MockEmailSender {
-    fn send_verification_email(&self, to: &str, token: &str) -> Result<()> {
+    fn send_verification_email(&self, to: &str, verification_url: &str) -> Result<()> {
+        let vars = HashMap::from([("verification_url", verification_url.to_string())]);
+        let (_, body) = templates::VERIFY_EMAIL.render(&vars)?;
+
         tracing::info!("📧 [MOCK EMAIL] Sending verification email to: {}", to);
+        tracing::info!("📧 [MOCK EMAIL] Verification link: {}", verification_url);
+        tracing::debug!("📧 [MOCK EMAIL] Body:\n{}", body);
+        Ok(())
+    }
+
+    fn send_email(&self, message: &EmailMessage) -> Result<()> {
         tracing::info!(
-            "📧 [MOCK EMAIL] Verification link: http://localhost:2727/verify-email?token={}",
-            token
+            "📧 [MOCK EMAIL] Sending \"{}\" to: {}",
+            message.subject,
+            message.to
         );
+        for (name, value) in message.list_unsubscribe_headers() {
+            tracing::info!("📧 [MOCK EMAIL] {}: {}", name, value);
+        }
+        tracing::debug!("📧 [MOCK EMAIL] Body:\n{}", message.body);
         Ok(())
     }
 }
@@ -125,4 +222,44 @@ mod tests {
         let result = sender.send_verification_email("test@example.com", &long_token);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_mock_email_sender_sends_message() {
+        let sender = MockEmailSender;
+        let message = EmailMessage {
+            to: "test@example.com".to_string(),
+            subject: "Subject".to_string(),
+            body: "Body".to_string(),
+            template: None,
+            unsubscribe_url: Some("http://localhost/unsubscribe".to_string()),
+        };
+        assert!(sender.send_email(&message).is_ok());
+    }
+
+    #[test]
+    fn test_list_unsubscribe_headers() {
+        let mut message = EmailMessage {
+            to: "test@example.com".to_string(),
+            subject: "Subject".to_string(),
+            body: "Body".to_string(),
+            template: None,
+            unsubscribe_url: Some("http://localhost/unsubscribe".to_string()),
+        };
+        assert_eq!(
+            message.list_unsubscribe_headers(),
+            [
+                (
+                    "List-Unsubscribe",
+                    "<http://localhost/unsubscribe>".to_string()
+                ),
+                (
+                    "List-Unsubscribe-Post",
+                    "List-Unsubscribe=One-Click".to_string()
+                ),
+            ]
+        );
+
+        message.unsubscribe_url = None;
+        assert!(message.list_unsubscribe_headers().is_empty());
+    }
 }
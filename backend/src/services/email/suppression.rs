@@ -0,0 +1,431 @@
+//! Suppression of bounced and complained email addresses.
+//!
+//! The bounce webhook calls [`record_event`] for each recipient the mail
+//! provider reports. Hard bounces and complaints add the address to the
+//! `email_suppressions` table; soft bounces are left to the provider's
+//! retries. A hard-bounced address is also marked unverified on the accounts
+//! using it, and a complaint turns off the weekly digest of those accounts.
+//!
+//! [`SuppressingEmailSender`] wraps the configured sender and drops mail to
+//! suppressed addresses. [`EmailSender`] is synchronous, so it checks an
+//! in-memory [`SuppressionList`]; the list is updated on every change made by
+//! this instance and reloaded periodically to pick up changes made by others.
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    sea_query::{Expr, Func, OnConflict},
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, Set,
+};
+use std::{
+    collections::HashSet,
+    sync::{Arc, PoisonError, RwLock},
+};
+use uuid::Uuid;
+
+use super::{
+    bounce::{BounceEvent, BounceKind},
+    EmailMessage, EmailSender,
+};
+use crate::models::{
+    email_digest_subscriptions, email_suppressions,
+    prelude::{EmailDigestSubscriptions, EmailSuppressions, Users},
+    users,
+};
+
+/// Why an address is suppressed, as stored in `email_suppressions.reason`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionReason {
+    Bounce,
+    Complaint,
+}
+
+impl SuppressionReason {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Bounce => "bounce",
+            Self::Complaint => "complaint",
+        }
+    }
+
+    /// Parse a stored or filtered reason
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "bounce" => Some(Self::Bounce),
+            "complaint" => Some(Self::Complaint),
+            _ => None,
+        }
+    }
+}
+
+/// In-memory copy of the suppressed addresses
+#[derive(Debug, Default)]
+pub struct SuppressionList {
+    /// Lowercase addresses
+    emails: RwLock<HashSet<String>>,
+}
+
+impl SuppressionList {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether mail to `email` must not be sent
+    #[must_use]
+    pub fn contains(&self, email: &str) -> bool {
+        self.emails
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(&normalize(email))
+    }
+
+    /// Number of suppressed addresses
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.emails
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Check whether no address is suppressed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Replace the list with the addresses in the database
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on database failure
+    pub async fn refresh(&self, db: &DatabaseConnection) -> Result<usize> {
+        let emails: HashSet<String> = EmailSuppressions::find()
+            .select_only()
+            .column(email_suppressions::Column::Email)
+            .into_tuple::<String>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+        let count = emails.len();
+        *self.emails.write().unwrap_or_else(PoisonError::into_inner) = emails;
+        Ok(count)
+    }
+
+    fn insert(&self, email: &str) {
+        self.emails
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(normalize(email));
+    }
+
+    fn remove(&self, email: &str) {
+        self.emails
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&normalize(email));
+    }
+}
+
+fn normalize(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Apply a bounce or complaint report for one recipient
+///
+/// Returns the reason the address is now suppressed for, or `None` for soft
+/// bounces, which are ignored.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn record_event(
+    db: &DatabaseConnection,
+    list: &SuppressionList,
+    event: &BounceEvent,
+) -> Result<Option<SuppressionReason>> {
+    let reason = match event.kind {
+        BounceKind::HardBounce => SuppressionReason::Bounce,
+        BounceKind::Complaint => SuppressionReason::Complaint,
+        BounceKind::SoftBounce => return Ok(None),
+    };
+    let email = normalize(&event.email);
+    let now = Utc::now();
+
+    EmailSuppressions::insert(email_suppressions::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        email: Set(email.clone()),
+        reason: Set(reason.as_str().to_string()),
+        detail: Set(event.detail.clone()),
+        reports: Set(1),
+        last_reported_at: Set(now.into()),
+        created_at: Set(now.into()),
+    })
+    .on_conflict(
+        OnConflict::column(email_suppressions::Column::Email)
+            .update_columns([
+                email_suppressions::Column::Reason,
+                email_suppressions::Column::Detail,
+                email_suppressions::Column::LastReportedAt,
+            ])
+            .value(
+                email_suppressions::Column::Reports,
+                Expr::col((EmailSuppressions, email_suppressions::Column::Reports)).add(1),
+            )
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    list.insert(&email);
+
+    // Registered addresses keep their case, so match them case-insensitively
+    let user_ids: Vec<Uuid> = Users::find()
+        .select_only()
+        .column(users::Column::Id)
+        .filter(Expr::expr(Func::lower(Expr::col(users::Column::Email))).eq(email.as_str()))
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let affected = if user_ids.is_empty() {
+        0
+    } else {
+        match reason {
+            SuppressionReason::Bounce => {
+                Users::update_many()
+                    .col_expr(users::Column::EmailVerified, Expr::value(false))
+                    .col_expr(users::Column::UpdatedAt, Expr::value(now))
+                    .filter(users::Column::Id.is_in(user_ids.clone()))
+                    .filter(users::Column::EmailVerified.eq(true))
+                    .exec(db)
+                    .await?
+                    .rows_affected
+            }
+            SuppressionReason::Complaint => {
+                EmailDigestSubscriptions::update_many()
+                    .col_expr(
+                        email_digest_subscriptions::Column::Enabled,
+                        Expr::value(false),
+                    )
+                    .col_expr(
+                        email_digest_subscriptions::Column::UpdatedAt,
+                        Expr::value(now),
+                    )
+                    .filter(email_digest_subscriptions::Column::UserId.is_in(user_ids.clone()))
+                    .filter(email_digest_subscriptions::Column::Enabled.eq(true))
+                    .exec(db)
+                    .await?
+                    .rows_affected
+            }
+        }
+    };
+
+    tracing::info!(
+        target: "audit",
+        action = "email.suppressed",
+        email = %email,
+        reason = reason.as_str(),
+        detail = event.detail.as_deref().unwrap_or(""),
+        accounts = user_ids.len(),
+        affected,
+        "Email address suppressed after provider report"
+    );
+
+    Ok(Some(reason))
+}
+
+/// Remove an address from the suppression list
+///
+/// Accounts un-verified by the bounce stay unverified; the user verifies the
+/// address again once mail reaches it. Returns the removed suppression, or
+/// `None` if there is none with this id.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn lift(
+    db: &DatabaseConnection,
+    list: &SuppressionList,
+    id: Uuid,
+    admin_id: Uuid,
+) -> Result<Option<email_suppressions::Model>> {
+    let Some(suppression) = EmailSuppressions::find_by_id(id).one(db).await? else {
+        return Ok(None);
+    };
+
+    EmailSuppressions::delete_by_id(id).exec(db).await?;
+    list.remove(&suppression.email);
+
+    tracing::info!(
+        target: "audit",
+        action = "email.suppression_lifted",
+        admin_id = %admin_id,
+        email = %suppression.email,
+        reason = %suppression.reason,
+        "Email suppression lifted"
+    );
+
+    Ok(Some(suppression))
+}
+
+/// Sender that drops mail to suppressed addresses
+///
+/// Dropped mail counts as sent: callers such as the digest must not retry it.
+pub struct SuppressingEmailSender {
+    inner: Arc<dyn EmailSender + Send + Sync>,
+    list: Arc<SuppressionList>,
+}
+
+impl SuppressingEmailSender {
+    #[must_use]
+    pub fn new(inner: Arc<dyn EmailSender + Send + Sync>, list: Arc<SuppressionList>) -> Self {
+        Self { inner, list }
+    }
+
+    fn suppressed(&self, to: &str) -> bool {
+        let suppressed = self.list.contains(to);
+        if suppressed {
+            tracing::info!(to = %to, "Skipping email to suppressed address");
+        }
+        suppressed
+    }
+}
+
+impl EmailSender for SuppressingEmailSender {
+    fn send_verification_email(&self, to: &str, verification_url: &str) -> Result<()> {
+        if self.suppressed(to) {
+            return Ok(());
+        }
+        self.inner.send_verification_email(to, verification_url)
+    }
+
+    fn send_email(&self, message: &EmailMessage) -> Result<()> {
+        if self.suppressed(&message.to) {
+            return Ok(());
+        }
+        self.inner.send_email(message)
+    }
+
+    fn send_email_with_id(&self, message: &EmailMessage) -> Result<Option<String>> {
+        if self.suppressed(&message.to) {
+            return Ok(None);
+        }
+        self.inner.send_email_with_id(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingSender {
+        sent: AtomicUsize,
+    }
+
+    impl EmailSender for CountingSender {
+        fn send_verification_email(&self, _to: &str, _verification_url: &str) -> Result<()> {
+            self.sent.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn send_email(&self, _message: &EmailMessage) -> Result<()> {
+            self.sent.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    fn message(to: &str) -> EmailMessage {
+        EmailMessage {
+            to: to.to_string(),
+            subject: "Subject".to_string(),
+            body: "Body".to_string(),
+            template: None,
+            unsubscribe_url: None,
+        }
+    }
+
+    #[test]
+    fn test_list_ignores_case() {
+        let list = SuppressionList::new();
+        list.insert(" Gone@Example.com");
+        assert!(list.contains("gone@example.com"));
+        assert!(list.contains("GONE@EXAMPLE.COM"));
+        assert_eq!(list.len(), 1);
+
+        list.remove("GONE@example.com");
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_sender_skips_suppressed_addresses() {
+        let inner = Arc::new(CountingSender::default());
+        let list = Arc::new(SuppressionList::new());
+        list.insert("gone@example.com");
+        let sender = SuppressingEmailSender::new(inner.clone(), list);
+
+        sender.send_email(&message("Gone@example.com")).unwrap();
+        sender
+            .send_verification_email("gone@example.com", "token")
+            .unwrap();
+        assert_eq!(inner.sent.load(Ordering::Relaxed), 0);
+
+        sender.send_email(&message("ok@example.com")).unwrap();
+        sender
+            .send_verification_email("ok@example.com", "token")
+            .unwrap();
+        assert_eq!(inner.sent.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_reason_round_trip() {
+        for reason in [SuppressionReason::Bounce, SuppressionReason::Complaint] {
+            assert_eq!(SuppressionReason::parse(reason.as_str()), Some(reason));
+        }
+        assert_eq!(SuppressionReason::parse("spam"), None);
+    }
+
+    #[tokio::test]
+    async fn test_soft_bounce_is_ignored() {
+        // No queries are expected
+        let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let list = SuppressionList::new();
+        let event = BounceEvent {
+            email: "busy@example.com".to_string(),
+            kind: BounceKind::SoftBounce,
+            detail: None,
+        };
+
+        assert_eq!(record_event(&db, &list, &event).await.unwrap(), None);
+        assert!(list.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hard_bounce_suppresses_unregistered_address() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .append_query_results([Vec::<users::Model>::new()])
+            .into_connection();
+        let list = SuppressionList::new();
+        let event = BounceEvent {
+            email: "Gone@Example.com".to_string(),
+            kind: BounceKind::HardBounce,
+            detail: Some("550 5.1.1".to_string()),
+        };
+
+        assert_eq!(
+            record_event(&db, &list, &event).await.unwrap(),
+            Some(SuppressionReason::Bounce)
+        );
+        assert!(list.contains("gone@example.com"));
+    }
+}
@@ -0,0 +1,217 @@
+//! Email template engine.
+//!
+//! Templates are plain text with `{{name}}` placeholders. Rendering fails on
+//! unknown or unterminated placeholders so a typo never reaches a user's
+//! inbox as literal braces.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+/// Subject and body template for one kind of email
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmailTemplate {
+    /// Identifier recorded in the delivery log
+    pub name: &'static str,
+    pub subject: &'static str,
+    pub body: &'static str,
+}
+
+impl EmailTemplate {
+    /// Render subject and body with the given variables
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a placeholder has no variable or is unterminated
+    pub fn render<S: BuildHasher>(
+        &self,
+        vars: &HashMap<&str, String, S>,
+    ) -> Result<(String, String)> {
+        Ok((render(self.subject, vars)?, render(self.body, vars)?))
+    }
+}
+
+/// Email address verification link
+pub const VERIFY_EMAIL: EmailTemplate = EmailTemplate {
+    name: "verify_email",
+    subject: "Verify your Cobalt Stack email address",
+    body: "Welcome to Cobalt Stack!
+
+Confirm your email address by opening this link:
+{{verification_url}}
+
+The link expires in 24 hours. If you did not create an account, you can
+ignore this email.
+",
+};
+
+/// Weekly activity digest
+pub const WEEKLY_DIGEST: EmailTemplate = EmailTemplate {
+    name: "weekly_digest",
+    subject: "Your weekly Cobalt Stack digest",
+    body: "Hi {{username}},
+
+Here is your activity for the week of {{period_start}} to {{period_end}}:
+
+- Messages sent: {{messages_sent}}
+- Conversations active: {{active_sessions}}
+
+Notifications since your last digest:
+{{notifications}}
+
+You are receiving this because you turned on the weekly digest.
+Unsubscribe: {{unsubscribe_url}}
+",
+};
+
+/// Periodic activity report for administrators
+pub const ADMIN_STATS_REPORT: EmailTemplate = EmailTemplate {
+    name: "admin_stats_report",
+    subject: "Cobalt Stack report: {{period_start}} to {{period_end}}",
+    body: "Hi {{username}},
+
+Here is the activity on Cobalt Stack from {{period_start}} to {{period_end}}:
+
+- New signups: {{signups}}
+- Active users: {{active_users}}
+- Conversations started: {{chat_sessions}}
+- Chat messages: {{chat_messages}}
+- LLM cost: ${{llm_cost}}
+
+LLM usage by model:
+{{models}}
+
+You are receiving this because you are an administrator and the scheduled
+stats report is enabled. The same figures are available as CSV from
+GET /api/v1/admin/stats/export.
+",
+};
+
+/// Sign-in from a device without an active session
+pub const NEW_LOGIN: EmailTemplate = EmailTemplate {
+    name: "new_login",
+    subject: "New sign-in to your Cobalt Stack account",
+    body: "Hi {{username}},
+
+Your account was just signed in to from a new device:
+
+- Device: {{device}}
+- IP address: {{ip}}
+- Time: {{time}}
+
+If this was you, no action is needed. If not, change your password right away.
+",
+};
+
+/// Session signed out after its refresh token was replayed from another device
+pub const SESSIONS_REVOKED: EmailTemplate = EmailTemplate {
+    name: "sessions_revoked",
+    subject: "A Cobalt Stack session was signed out",
+    body: "Hi {{username}},
+
+A sign-in token of your account was used from a device it was not issued to,
+so the session it belonged to was signed out:
+
+- Device: {{device}}
+- IP address: {{ip}}
+- Time: {{time}}
+
+Sign in again to continue. If you did not switch browsers or clear your
+cookies, someone may have copied your session: change your password right away.
+",
+};
+
+/// Confirmation link for a sign-in from a device that is not trusted yet
+pub const DEVICE_CONFIRMATION: EmailTemplate = EmailTemplate {
+    name: "device_confirmation",
+    subject: "Confirm the new device signing in to your Cobalt Stack account",
+    body: "Hi {{username}},
+
+Your account was just signed in to from a device you have not confirmed yet:
+
+- Device: {{device}}
+- IP address: {{ip}}
+- Time: {{time}}
+
+If this was you, confirm the device to stay signed in on it:
+{{confirmation_url}}
+
+The link expires in {{valid_minutes}} minutes. If this was not you, do not
+open the link and change your password right away: someone knows it.
+",
+};
+
+/// Replace every `{{name}}` in `template` with its variable
+///
+/// # Errors
+///
+/// Returns an error if a placeholder has no variable or is unterminated
+pub fn render<S: BuildHasher>(template: &str, vars: &HashMap<&str, String, S>) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow!("Unterminated placeholder in email template"))?;
+        let name = after[..end].trim();
+        let value = vars
+            .get(name)
+            .ok_or_else(|| anyhow!("Missing email template variable: {name}"))?;
+        output.push_str(value);
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_substitutes_variables() {
+        let vars = HashMap::from([("name", "Alice".to_string()), ("count", "3".to_string())]);
+
+        let output = render("Hi {{name}}, you have {{ count }} new", &vars).unwrap();
+
+        assert_eq!(output, "Hi Alice, you have 3 new");
+    }
+
+    #[test]
+    fn test_render_rejects_missing_variable() {
+        let result = render("Hi {{name}}", &HashMap::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_render_rejects_unterminated_placeholder() {
+        let vars = HashMap::from([("name", "Alice".to_string())]);
+        assert!(render("Hi {{name", &vars).is_err());
+    }
+
+    #[test]
+    fn test_weekly_digest_renders() {
+        let vars = HashMap::from([
+            ("username", "alice".to_string()),
+            ("period_start", "2025-01-01".to_string()),
+            ("period_end", "2025-01-08".to_string()),
+            ("messages_sent", "12".to_string()),
+            ("active_sessions", "2".to_string()),
+            ("notifications", "- none".to_string()),
+            (
+                "unsubscribe_url",
+                "http://localhost/unsubscribe".to_string(),
+            ),
+        ]);
+
+        let (subject, body) = WEEKLY_DIGEST.render(&vars).unwrap();
+
+        assert!(subject.contains("weekly"));
+        assert!(body.contains("Messages sent: 12"));
+        assert!(body.contains("Unsubscribe: http://localhost/unsubscribe"));
+    }
+}
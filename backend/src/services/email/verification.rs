@@ -3,12 +3,52 @@
 use crate::models::{email_verifications, users};
 use crate::utils::token::{generate_verification_token, hash_token};
 use anyhow::Result;
-use chrono::{Duration, Utc};
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, TransactionTrait,
+};
 use uuid::Uuid;
 
+/// Minimum time between two verification emails to the same user
+pub const RESEND_COOLDOWN_SECS: i64 = 60;
+
+/// Result of [`resend_verification_token`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResendOutcome {
+    /// A new token was sent; the next resend is possible after `cooldown_secs`
+    Sent { cooldown_secs: u64 },
+    /// The previous email is too recent; nothing was sent
+    CoolingDown { retry_after_secs: u64 },
+}
+
+/// Seconds left until another email may be sent, if any
+///
+/// Rounds up so a client waiting the returned time is never rejected.
+#[must_use]
+pub fn cooldown_remaining(
+    last_sent_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    cooldown: Duration,
+) -> Option<u64> {
+    let remaining = last_sent_at + cooldown - now;
+    if remaining <= Duration::zero() {
+        return None;
+    }
+    let millis = u64::try_from(remaining.num_milliseconds()).unwrap_or(0);
+    Some(millis.div_ceil(1000).max(1))
+}
+
 /// Create a verification token for a user
-pub async fn create_verification_token(db: &DatabaseConnection, user_id: Uuid) -> Result<String> {
+///
+/// `client` is the client the link is built for (see
+/// [`VerificationLinkConfig::client`](crate::config::VerificationLinkConfig::client));
+/// resends build the same kind of link.
+pub async fn create_verification_token(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    client: Option<&str>,
+) -> Result<String> {
     // Generate token and hash it
     let token = generate_verification_token();
     let token_hash = hash_token(&token);
@@ -24,6 +64,7 @@ pub async fn create_verification_token(db: &DatabaseConnection, user_id: Uuid) -
         expires_at: Set(expires_at.into()),
         verified_at: Set(None),
         created_at: Set(Utc::now().into()),
+        client: Set(client.map(str::to_string)),
     };
 
     verification.insert(db).await?;
@@ -31,6 +72,117 @@ pub async fn create_verification_token(db: &DatabaseConnection, user_id: Uuid) -
     Ok(token)
 }
 
+/// Issue a fresh verification token and hand it to `send`, unless the last
+/// one was issued less than `cooldown` ago
+///
+/// `send` also gets the client of the previous token, so the new link opens
+/// the same client.
+///
+/// Runs in one transaction holding a lock on the user row, so concurrent
+/// requests cannot both pass the cooldown. Older outstanding tokens are
+/// expired, leaving only the newest link valid. If `send` fails the
+/// transaction is rolled back: older links keep working and no cooldown
+/// starts, so the request can be retried right away.
+///
+/// # Errors
+///
+/// Returns an error if the user does not exist, on database failure, or if
+/// `send` fails
+pub async fn resend_verification_token<F>(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    cooldown: Duration,
+    send: F,
+) -> Result<ResendOutcome>
+where
+    F: FnOnce(&str, Option<&str>) -> Result<()> + Send,
+{
+    let txn = db.begin().await?;
+
+    users::Entity::find_by_id(user_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("User not found"))?;
+
+    let now = Utc::now();
+    let latest = email_verifications::Entity::find()
+        .filter(email_verifications::Column::UserId.eq(user_id))
+        .order_by_desc(email_verifications::Column::CreatedAt)
+        .one(&txn)
+        .await?;
+    if let Some(retry_after_secs) = latest.as_ref().and_then(|verification| {
+        cooldown_remaining(verification.created_at.with_timezone(&Utc), now, cooldown)
+    }) {
+        return Ok(ResendOutcome::CoolingDown { retry_after_secs });
+    }
+    let client = latest.and_then(|verification| verification.client);
+
+    // Expire outstanding tokens so only the newest link works
+    email_verifications::Entity::update_many()
+        .col_expr(email_verifications::Column::ExpiresAt, Expr::value(now))
+        .filter(email_verifications::Column::UserId.eq(user_id))
+        .filter(email_verifications::Column::VerifiedAt.is_null())
+        .filter(email_verifications::Column::ExpiresAt.gt(now))
+        .exec(&txn)
+        .await?;
+
+    let token = generate_verification_token();
+    email_verifications::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        token_hash: Set(hash_token(&token)),
+        expires_at: Set((now + Duration::hours(24)).into()),
+        verified_at: Set(None),
+        created_at: Set(now.into()),
+        client: Set(client.clone()),
+    }
+    .insert(&txn)
+    .await?;
+
+    send(&token, client.as_deref())?;
+    txn.commit().await?;
+
+    Ok(ResendOutcome::Sent {
+        cooldown_secs: u64::try_from(cooldown.num_seconds()).unwrap_or(0),
+    })
+}
+
+/// Mark a user's email as verified without a token
+///
+/// Used by support when the user cannot receive the verification email.
+/// Outstanding tokens are expired so previously sent links stop working.
+///
+/// # Errors
+///
+/// Returns an error if the user does not exist or on database failure
+pub async fn force_verify_email(db: &DatabaseConnection, user_id: Uuid) -> Result<()> {
+    let txn = db.begin().await?;
+
+    let user = users::Entity::find_by_id(user_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("User not found"))?;
+
+    let now = Utc::now();
+    email_verifications::Entity::update_many()
+        .col_expr(email_verifications::Column::ExpiresAt, Expr::value(now))
+        .filter(email_verifications::Column::UserId.eq(user_id))
+        .filter(email_verifications::Column::VerifiedAt.is_null())
+        .filter(email_verifications::Column::ExpiresAt.gt(now))
+        .exec(&txn)
+        .await?;
+
+    let mut active_user: users::ActiveModel = user.into();
+    active_user.email_verified = Set(true);
+    active_user.update(&txn).await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
 /// Verify an email token and mark user as verified
 pub async fn verify_email_token(db: &DatabaseConnection, token: &str) -> Result<Uuid> {
     let token_hash = hash_token(token);
@@ -73,6 +225,28 @@ pub async fn verify_email_token(db: &DatabaseConnection, token: &str) -> Result<
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_remaining() {
+        let now = Utc::now();
+        let cooldown = Duration::seconds(RESEND_COOLDOWN_SECS);
+
+        assert_eq!(cooldown_remaining(now, now, cooldown), Some(60));
+        assert_eq!(
+            cooldown_remaining(now - Duration::milliseconds(59_500), now, cooldown),
+            Some(1)
+        );
+        assert_eq!(
+            cooldown_remaining(now - Duration::seconds(45), now, cooldown),
+            Some(15)
+        );
+        assert_eq!(cooldown_remaining(now - cooldown, now, cooldown), None);
+        assert_eq!(
+            cooldown_remaining(now - Duration::hours(1), now, cooldown),
+            None
+        );
+    }
 
     // Note: These tests would require a test database setup
     // For now, we define the test structure but won't run them without DB
@@ -14,8 +14,26 @@
 //!
 //! # Modules
 //!
+//! - **`access_log`**: API access records with pluggable sinks and retention
+//! - **`admin_stats`**: Precomputed user counts of the admin dashboard
+//! - **audit**: Audit trail of sign-ins, password changes and admin actions
 //! - **auth**: Authentication services (JWT, passwords, token rotation)
-//! - **email**: Email delivery services (verification emails)
+//! - **backup**: Encrypted logical backup and restore
+//! - **branding**: White-label branding defaults and runtime overrides
+//! - **doctor**: Configuration checks with actionable findings
+//! - **`effective_config`**: Settings in effect, with secrets masked
+//! - **email**: Email delivery services (verification emails, weekly digest)
+//! - **oauth**: Social login with Google and GitHub
+//! - **preferences**: User preference storage and validation
+//! - **rbac**: Permissions, custom roles and their assignment to users
+//! - **`request_timing`**: Database, Valkey and provider time spent by a request
+//! - **`runtime_switches`**: Registration and read-only switches flipped at runtime
+//! - **sandbox**: Demo accounts deleted after a retention period
+//! - **scheduler**: Periodic background jobs
+//! - **`schema_version`**: Applied migrations against the ones of this binary
+//! - **`stats_report`**: Admin activity report (CSV export, scheduled email)
+//! - **tokenizer**: Per-model token counting with cached tokenizers
+//! - **`user_admin`**: Account creation, deletion and admin promotion by admins
 //! - **valkey**: Valkey/Redis caching services (blacklist, rate limiting)
 //!
 //! # Service Layer Benefits
@@ -25,6 +43,24 @@
 //! - **Maintainability**: Changes to business rules isolated from HTTP concerns
 //! - **Domain Clarity**: Service names express business intent
 
+pub mod access_log;
+pub mod admin_stats;
+pub mod audit;
 pub mod auth;
+pub mod backup;
+pub mod branding;
+pub mod doctor;
+pub mod effective_config;
 pub mod email;
+pub mod oauth;
+pub mod preferences;
+pub mod rbac;
+pub mod request_timing;
+pub mod runtime_switches;
+pub mod sandbox;
+pub mod scheduler;
+pub mod schema_version;
+pub mod stats_report;
+pub mod tokenizer;
+pub mod user_admin;
 pub mod valkey;
@@ -0,0 +1,244 @@
+//! Sign in with GitHub
+
+use async_trait::async_trait;
+use reqwest::{header::ACCEPT, Client, Url};
+use serde::Deserialize;
+
+use super::{OAuthError, OAuthIdentity, OAuthProvider};
+use crate::config::OAuthClientConfig;
+
+const AUTHORIZE_URL: &str = "https://github.com/login/oauth/authorize";
+const TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const API_URL: &str = "https://api.github.com";
+
+/// GitHub answers a rejected code with 200 and an `error` field
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct User {
+    id: u64,
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct Email {
+    #[serde(rename = "email")]
+    address: String,
+    primary: bool,
+    verified: bool,
+}
+
+/// GitHub accounts
+pub struct GitHubProvider {
+    client: Client,
+    config: OAuthClientConfig,
+    token_url: String,
+    api_url: String,
+}
+
+impl GitHubProvider {
+    #[must_use]
+    pub fn new(client: Client, config: OAuthClientConfig) -> Self {
+        Self {
+            client,
+            config,
+            token_url: TOKEN_URL.to_string(),
+            api_url: API_URL.to_string(),
+        }
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        access_token: &str,
+    ) -> Result<T, OAuthError> {
+        Ok(self
+            .client
+            .get(format!("{}{path}", self.api_url))
+            .bearer_auth(access_token)
+            .header(ACCEPT, "application/vnd.github+json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+/// The primary address, if verified
+///
+/// The public profile email is not used: GitHub does not say whether it is
+/// verified.
+fn primary_verified_email(emails: Vec<Email>) -> Option<String> {
+    emails
+        .into_iter()
+        .find(|email| email.primary && email.verified)
+        .map(|email| email.address)
+}
+
+#[async_trait]
+impl OAuthProvider for GitHubProvider {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String {
+        Url::parse_with_params(
+            AUTHORIZE_URL,
+            [
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", redirect_uri),
+                ("scope", "read:user user:email"),
+                ("state", state),
+            ],
+        )
+        .expect("authorize URL is valid")
+        .into()
+    }
+
+    async fn identify(&self, code: &str, redirect_uri: &str) -> Result<OAuthIdentity, OAuthError> {
+        let token: TokenResponse = self
+            .client
+            .post(&self.token_url)
+            .header(ACCEPT, "application/json")
+            .form(&[
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let access_token = token.access_token.ok_or_else(|| {
+            OAuthError::Provider(token.error.unwrap_or_else(|| "no access token".to_string()))
+        })?;
+
+        let user: User = self.get("/user", &access_token).await?;
+        let emails: Vec<Email> = self.get("/user/emails", &access_token).await?;
+
+        Ok(OAuthIdentity {
+            provider_user_id: user.id.to_string(),
+            email: primary_verified_email(emails),
+            username_hint: user.login,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn provider(server: &MockServer) -> GitHubProvider {
+        GitHubProvider {
+            token_url: format!("{}/login/oauth/access_token", server.uri()),
+            api_url: server.uri(),
+            ..GitHubProvider::new(
+                Client::new(),
+                OAuthClientConfig {
+                    client_id: "client".to_string(),
+                    client_secret: "secret".to_string(),
+                },
+            )
+        }
+    }
+
+    fn email(address: &str, primary: bool, verified: bool) -> Email {
+        Email {
+            address: address.to_string(),
+            primary,
+            verified,
+        }
+    }
+
+    #[test]
+    fn test_primary_verified_email() {
+        assert_eq!(
+            primary_verified_email(vec![
+                email("old@example.com", false, true),
+                email("main@example.com", true, true),
+            ]),
+            Some("main@example.com".to_string())
+        );
+        assert_eq!(
+            primary_verified_email(vec![
+                email("old@example.com", false, true),
+                email("main@example.com", true, false),
+            ]),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_identify() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/login/oauth/access_token"))
+            .and(header("accept", "application/json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "access_token": "gho_1" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .and(header("authorization", "Bearer gho_1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "id": 583_231, "login": "octocat" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/user/emails"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "email": "octocat@example.com", "primary": true, "verified": true }
+            ])))
+            .mount(&server)
+            .await;
+
+        let identity = provider(&server)
+            .identify("abc", "https://app.example.com/oauth/github/callback")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            identity,
+            OAuthIdentity {
+                provider_user_id: "583231".to_string(),
+                email: Some("octocat@example.com".to_string()),
+                username_hint: "octocat".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_identify_rejected_code() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/login/oauth/access_token"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "error": "bad_verification_code" })),
+            )
+            .mount(&server)
+            .await;
+
+        let result = provider(&server)
+            .identify("bad", "https://app.example.com")
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::Provider(e)) if e == "bad_verification_code"));
+    }
+}
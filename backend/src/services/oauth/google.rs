@@ -0,0 +1,202 @@
+//! Sign in with Google (`OpenID` Connect)
+
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+
+use super::{OAuthError, OAuthIdentity, OAuthProvider};
+use crate::config::OAuthClientConfig;
+
+const AUTHORIZE_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const USERINFO_URL: &str = "https://openidconnect.googleapis.com/v1/userinfo";
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+/// Google accounts
+pub struct GoogleProvider {
+    client: Client,
+    config: OAuthClientConfig,
+    token_url: String,
+    userinfo_url: String,
+}
+
+impl GoogleProvider {
+    #[must_use]
+    pub fn new(client: Client, config: OAuthClientConfig) -> Self {
+        Self {
+            client,
+            config,
+            token_url: TOKEN_URL.to_string(),
+            userinfo_url: USERINFO_URL.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl OAuthProvider for GoogleProvider {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String {
+        Url::parse_with_params(
+            AUTHORIZE_URL,
+            [
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", redirect_uri),
+                ("response_type", "code"),
+                ("scope", "openid email profile"),
+                ("state", state),
+            ],
+        )
+        .expect("authorize URL is valid")
+        .into()
+    }
+
+    async fn identify(&self, code: &str, redirect_uri: &str) -> Result<OAuthIdentity, OAuthError> {
+        let token: TokenResponse = self
+            .client
+            .post(&self.token_url)
+            .form(&[
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+                ("code", code),
+                ("grant_type", "authorization_code"),
+                ("redirect_uri", redirect_uri),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let info: UserInfo = self
+            .client
+            .get(&self.userinfo_url)
+            .bearer_auth(&token.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let email = info.email.filter(|_| info.email_verified);
+        let username_hint = email
+            .as_deref()
+            .and_then(|email| email.split('@').next())
+            .or(info.name.as_deref())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(OAuthIdentity {
+            provider_user_id: info.sub,
+            email,
+            username_hint,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{body_string_contains, header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn provider(server: &MockServer) -> GoogleProvider {
+        GoogleProvider {
+            token_url: format!("{}/token", server.uri()),
+            userinfo_url: format!("{}/userinfo", server.uri()),
+            ..GoogleProvider::new(
+                Client::new(),
+                OAuthClientConfig {
+                    client_id: "client".to_string(),
+                    client_secret: "secret".to_string(),
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn test_authorize_url() {
+        let provider = GoogleProvider::new(
+            Client::new(),
+            OAuthClientConfig {
+                client_id: "client".to_string(),
+                client_secret: "secret".to_string(),
+            },
+        );
+
+        let url = provider.authorize_url("https://app.example.com/oauth/google/callback", "s1");
+
+        assert!(url.starts_with(AUTHORIZE_URL));
+        assert!(url.contains("client_id=client"));
+        assert!(url.contains("state=s1"));
+        assert!(url.contains("scope=openid+email+profile"));
+        assert!(!url.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_identify_drops_unverified_email() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("code=abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "at",
+                "token_type": "Bearer"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/userinfo"))
+            .and(header("authorization", "Bearer at"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sub": "1234",
+                "email": "jane@example.com",
+                "email_verified": false,
+                "name": "Jane"
+            })))
+            .mount(&server)
+            .await;
+
+        let identity = provider(&server)
+            .identify("abc", "https://app.example.com/oauth/google/callback")
+            .await
+            .unwrap();
+
+        assert_eq!(identity.provider_user_id, "1234");
+        assert_eq!(identity.email, None);
+        assert_eq!(identity.username_hint, "Jane");
+    }
+
+    #[tokio::test]
+    async fn test_identify_rejected_code() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let result = provider(&server)
+            .identify("bad", "https://app.example.com")
+            .await;
+
+        assert!(matches!(result, Err(OAuthError::Provider(_))));
+    }
+}
@@ -0,0 +1,377 @@
+//! Social login with `OAuth2` providers
+//!
+//! A provider ([`OAuthProvider`]) builds the URL the browser is sent to and
+//! turns the code it comes back with into an [`OAuthIdentity`]. [`sign_in`]
+//! then finds the local account for that identity, using the existing
+//! `o_auth_accounts` table:
+//!
+//! 1. An identity linked before signs in to its account
+//! 2. Otherwise, a local account with the same verified email is linked,
+//!    provided the local address is verified too
+//! 3. Otherwise, a new account without password is created and linked
+//!
+//! Provider tokens are not kept: the identity is all sign-in needs.
+
+pub mod github;
+pub mod google;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::Rng;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, Set, TransactionTrait,
+};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::config::{OAuthConfig, SandboxConfig};
+use crate::models::{
+    o_auth_accounts,
+    prelude::{OAuthAccounts, Users},
+    sea_orm_active_enums::UserRole,
+    users,
+};
+use crate::services::sandbox;
+
+pub use github::GitHubProvider;
+pub use google::GoogleProvider;
+
+/// Timeout of each request to a provider
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// User of a provider, as reported after authorization
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthIdentity {
+    /// Stable id of the user at the provider
+    pub provider_user_id: String,
+    /// Email address, only if the provider verified it
+    pub email: Option<String>,
+    /// Preferred username for a new account
+    pub username_hint: String,
+}
+
+/// Social login failures
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    /// The provider could not be reached or rejected the request
+    #[error("provider request failed: {0}")]
+    Provider(String),
+    /// A new account needs an email the provider verified
+    #[error("provider reported no verified email")]
+    NoVerifiedEmail,
+    /// The email belongs to a local account whose address is not verified
+    #[error("email belongs to an unverified account")]
+    AccountConflict,
+    /// A new account is needed but registration is closed
+    #[error("registration is closed")]
+    RegistrationClosed,
+    #[error(transparent)]
+    Database(#[from] DbErr),
+}
+
+impl From<reqwest::Error> for OAuthError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Provider(e.to_string())
+    }
+}
+
+/// An `OAuth2` authorization code provider
+#[async_trait]
+pub trait OAuthProvider: Send + Sync {
+    /// Name used in URLs and in `o_auth_accounts.provider`
+    fn name(&self) -> &'static str;
+
+    /// Where to send the browser to authorize
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String;
+
+    /// Exchange the authorization code and fetch the user
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OAuthError::Provider`] if the code is rejected or a request
+    /// fails
+    async fn identify(&self, code: &str, redirect_uri: &str) -> Result<OAuthIdentity, OAuthError>;
+}
+
+/// The configured providers
+#[derive(Clone)]
+pub struct OAuthProviders {
+    providers: BTreeMap<&'static str, Arc<dyn OAuthProvider>>,
+    public_base_url: String,
+}
+
+impl OAuthProviders {
+    /// Providers enabled in `config`, sharing one HTTP client
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built
+    pub fn from_config(config: &OAuthConfig) -> Result<Self, OAuthError> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("cobalt-stack/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        let mut providers: Vec<Arc<dyn OAuthProvider>> = Vec::new();
+        if let Some(google) = &config.google {
+            providers.push(Arc::new(GoogleProvider::new(
+                client.clone(),
+                google.clone(),
+            )));
+        }
+        if let Some(github) = &config.github {
+            providers.push(Arc::new(GitHubProvider::new(client, github.clone())));
+        }
+        Ok(Self::new(providers, config.public_base_url.clone()))
+    }
+
+    #[must_use]
+    pub fn new(providers: Vec<Arc<dyn OAuthProvider>>, public_base_url: String) -> Self {
+        Self {
+            providers: providers.into_iter().map(|p| (p.name(), p)).collect(),
+            public_base_url,
+        }
+    }
+
+    /// The provider called `name`, if configured
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn OAuthProvider>> {
+        self.providers.get(name)
+    }
+
+    /// Names of the configured providers
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.providers.keys().copied()
+    }
+
+    /// Page the provider redirects to, which forwards `code` and `state` to
+    /// the callback endpoint
+    #[must_use]
+    pub fn redirect_uri(&self, provider: &str) -> String {
+        format!("{}/oauth/{provider}/callback", self.public_base_url)
+    }
+}
+
+/// How [`sign_in`] found the account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OAuthLink {
+    /// The identity was linked before
+    Existing,
+    /// The identity was linked to the account with its email
+    Linked,
+    /// A new account was created for the identity
+    Created,
+}
+
+impl OAuthLink {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Existing => "existing",
+            Self::Linked => "linked",
+            Self::Created => "created",
+        }
+    }
+}
+
+/// Find or create the account of `identity` at `provider`
+///
+/// A new account is enrolled in the sandbox when `sandbox` is set.
+///
+/// # Errors
+///
+/// Returns [`OAuthError::AccountConflict`] if the email belongs to an account
+/// whose address is not verified, [`OAuthError::NoVerifiedEmail`] if a new
+/// account is needed but the provider verified no email,
+/// [`OAuthError::RegistrationClosed`] if a new account is needed but
+/// `registration_open` is false, or a database error
+pub async fn sign_in(
+    db: &DatabaseConnection,
+    provider: &str,
+    identity: &OAuthIdentity,
+    registration_open: bool,
+    sandbox: Option<&SandboxConfig>,
+) -> Result<(users::Model, OAuthLink), OAuthError> {
+    let linked = OAuthAccounts::find()
+        .filter(o_auth_accounts::Column::Provider.eq(provider))
+        .filter(o_auth_accounts::Column::ProviderUserId.eq(&identity.provider_user_id))
+        .find_also_related(Users)
+        .one(db)
+        .await?;
+    if let Some((_, Some(user))) = linked {
+        return Ok((user, OAuthLink::Existing));
+    }
+
+    let email = identity
+        .email
+        .as_deref()
+        .ok_or(OAuthError::NoVerifiedEmail)?;
+    let existing = Users::find()
+        .filter(users::Column::Email.eq(email))
+        .one(db)
+        .await?;
+
+    // Anyone can register an address; only its verified owner is linked
+    if existing.as_ref().is_some_and(|user| !user.email_verified) {
+        return Err(OAuthError::AccountConflict);
+    }
+
+    if existing.is_none() && !registration_open {
+        return Err(OAuthError::RegistrationClosed);
+    }
+
+    let txn = db.begin().await?;
+    let (user, link) = if let Some(user) = existing {
+        (user, OAuthLink::Linked)
+    } else {
+        let username = available_username(db, &identity.username_hint).await?;
+        let now = Utc::now();
+        let user = users::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            username: Set(username),
+            email: Set(email.to_string()),
+            password_hash: Set(None),
+            role: Set(UserRole::User),
+            email_verified: Set(true),
+            password_rotation_required: Set(false),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        if let Some(config) = sandbox {
+            sandbox::enroll(&txn, user.id, config).await?;
+        }
+        (user, OAuthLink::Created)
+    };
+
+    o_auth_accounts::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        provider: Set(provider.to_string()),
+        provider_user_id: Set(identity.provider_user_id.clone()),
+        access_token: Set(None),
+        refresh_token: Set(None),
+        expires_at: Set(None),
+        created_at: Set(Utc::now().into()),
+    }
+    .insert(&txn)
+    .await?;
+    txn.commit().await?;
+
+    Ok((user, link))
+}
+
+/// `hint` made a valid username, with a random suffix if it is taken
+async fn available_username(db: &DatabaseConnection, hint: &str) -> Result<String, DbErr> {
+    let base = sanitize_username(hint);
+    let taken = Users::find()
+        .filter(users::Column::Username.eq(&base))
+        .count(db)
+        .await?;
+    if taken == 0 {
+        return Ok(base);
+    }
+
+    let suffix: u32 = rand::thread_rng().gen_range(1000..10000);
+    Ok(format!("{}_{suffix}", truncate(&base, 45)))
+}
+
+/// Letters, digits, `_`, `-` and `.` of `hint`, 3 to 50 characters long
+fn sanitize_username(hint: &str) -> String {
+    let name: String = hint
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .collect();
+    let name = truncate(&name, 50).to_string();
+    if name.len() < 3 {
+        format!("user_{name}")
+    } else {
+        name
+    }
+}
+
+fn truncate(s: &str, max: usize) -> &str {
+    // ASCII only, so any index is a char boundary
+    &s[..s.len().min(max)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    fn identity(email: Option<&str>) -> OAuthIdentity {
+        OAuthIdentity {
+            provider_user_id: "42".to_string(),
+            email: email.map(str::to_string),
+            username_hint: "octocat".to_string(),
+        }
+    }
+
+    fn user(email_verified: bool) -> users::Model {
+        let now = Utc::now();
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password_hash: Some("hash".to_string()),
+            email_verified,
+            created_at: now.into(),
+            updated_at: now.into(),
+            role: UserRole::User,
+            disabled_at: None,
+            last_login_at: None,
+            tokens_not_before: None,
+            password_changed_at: None,
+            password_rotation_required: false,
+        }
+    }
+
+    #[test]
+    fn test_sanitize_username() {
+        assert_eq!(sanitize_username("Jane Doe"), "JaneDoe");
+        assert_eq!(sanitize_username("李"), "user_");
+        assert_eq!(sanitize_username(&"a".repeat(80)).len(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_unverified_account_is_not_linked() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<o_auth_accounts::Model>::new()])
+            .append_query_results([vec![user(false)]])
+            .into_connection();
+
+        let identity = identity(Some("alice@example.com"));
+        let result = sign_in(&db, "github", &identity, true, None).await;
+
+        assert!(matches!(result, Err(OAuthError::AccountConflict)));
+    }
+
+    #[tokio::test]
+    async fn test_new_account_needs_verified_email() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<o_auth_accounts::Model>::new()])
+            .into_connection();
+
+        let result = sign_in(&db, "github", &identity(None), true, None).await;
+
+        assert!(matches!(result, Err(OAuthError::NoVerifiedEmail)));
+    }
+
+    #[tokio::test]
+    async fn test_new_account_needs_open_registration() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<o_auth_accounts::Model>::new()])
+            .append_query_results([Vec::<users::Model>::new()])
+            .into_connection();
+
+        let identity = identity(Some("alice@example.com"));
+        let result = sign_in(&db, "github", &identity, false, None).await;
+
+        assert!(matches!(result, Err(OAuthError::RegistrationClosed)));
+    }
+}
@@ -0,0 +1,308 @@
+//! User preference storage.
+//!
+//! Preferences are stored as one JSONB document per user. Users without a
+//! row, and keys missing from a stored document, get the defaults from
+//! [`UserPreferences::default`].
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use sea_orm::{sea_query::OnConflict, DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+
+use crate::domain::chat::preferences::{is_valid_temperature, MAX_PERSONA_LENGTH, MAX_TEMPERATURE};
+use crate::dto::preferences::{UpdatePreferencesRequest, UserPreferences};
+use crate::models::{prelude::UserPreferences as UserPreferencesEntity, user_preferences};
+
+/// Maximum size of a serialized preferences document (and of a request body)
+///
+/// Leaves room for a persona of [`MAX_PERSONA_LENGTH`] multi-byte characters.
+pub const MAX_PREFERENCES_BYTES: usize = 8192;
+
+/// Maximum default model identifier length
+pub const MAX_DEFAULT_MODEL_LENGTH: usize = 100;
+
+/// Maximum language tag length (RFC 5646 recommends supporting 35)
+pub const MAX_LANGUAGE_TAG_LENGTH: usize = 35;
+
+/// Apply a partial update; omitted keys keep their current value
+#[must_use]
+pub fn apply_update(
+    mut preferences: UserPreferences,
+    update: UpdatePreferencesRequest,
+) -> UserPreferences {
+    if let Some(theme) = update.theme {
+        preferences.theme = theme;
+    }
+    if let Some(default_model) = update.default_model {
+        let default_model = default_model.trim();
+        preferences.default_model = (!default_model.is_empty()).then(|| default_model.to_string());
+    }
+    if let Some(temperature) = update.temperature {
+        preferences.temperature = temperature;
+    }
+    if let Some(persona) = update.persona {
+        let persona = persona.trim();
+        preferences.persona = (!persona.is_empty()).then(|| persona.to_string());
+    }
+    if let Some(language) = update.language {
+        preferences.language = language.trim().to_string();
+    }
+    if let Some(notifications) = update.notifications {
+        if let Some(quota_warnings) = notifications.quota_warnings {
+            preferences.notifications.quota_warnings = quota_warnings;
+        }
+        if let Some(in_app) = notifications.in_app {
+            preferences.notifications.in_app = in_app;
+        }
+    }
+    preferences
+}
+
+/// Check field formats and the stored document size
+///
+/// # Errors
+///
+/// Returns an error describing the first invalid field
+pub fn validate(preferences: &UserPreferences) -> Result<()> {
+    if let Some(model) = &preferences.default_model {
+        let allowed = |c: char| c.is_ascii_alphanumeric() || "-_.:/".contains(c);
+        if model.len() > MAX_DEFAULT_MODEL_LENGTH || !model.chars().all(allowed) {
+            bail!("Invalid default model identifier");
+        }
+    }
+
+    if preferences
+        .temperature
+        .is_some_and(|temperature| !is_valid_temperature(temperature))
+    {
+        bail!("Temperature must be between 0 and {MAX_TEMPERATURE}");
+    }
+
+    if preferences
+        .persona
+        .as_ref()
+        .is_some_and(|persona| persona.chars().count() > MAX_PERSONA_LENGTH)
+    {
+        bail!("Persona must not exceed {MAX_PERSONA_LENGTH} characters");
+    }
+
+    if !is_language_tag(&preferences.language) {
+        bail!("Language must be a BCP 47 tag such as 'en' or 'pt-BR'");
+    }
+
+    if serde_json::to_vec(preferences)?.len() > MAX_PREFERENCES_BYTES {
+        bail!("Preferences must not exceed {MAX_PREFERENCES_BYTES} bytes");
+    }
+
+    Ok(())
+}
+
+/// Loose BCP 47 check: a 2-3 letter primary subtag followed by
+/// alphanumeric subtags of 1-8 characters
+fn is_language_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary_ok = subtags.next().is_some_and(|primary| {
+        (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_alphabetic())
+    });
+
+    tag.len() <= MAX_LANGUAGE_TAG_LENGTH
+        && primary_ok
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+/// Load a user's preferences
+///
+/// A stored document that no longer parses is logged and replaced by the
+/// defaults rather than failing the request.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn load(db: &DatabaseConnection, user_id: Uuid) -> Result<UserPreferences> {
+    let Some(row) = UserPreferencesEntity::find_by_id(user_id).one(db).await? else {
+        return Ok(UserPreferences::default());
+    };
+
+    Ok(serde_json::from_value(row.preferences).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring unreadable preferences of user {}: {}", user_id, e);
+        UserPreferences::default()
+    }))
+}
+
+/// Store a user's preferences, replacing the previous document
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn save(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    preferences: &UserPreferences,
+) -> Result<()> {
+    let row = user_preferences::ActiveModel {
+        user_id: Set(user_id),
+        preferences: Set(serde_json::to_value(preferences)?),
+        updated_at: Set(Utc::now().into()),
+    };
+
+    UserPreferencesEntity::insert(row)
+        .on_conflict(
+            OnConflict::column(user_preferences::Column::UserId)
+                .update_columns([
+                    user_preferences::Column::Preferences,
+                    user_preferences::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::preferences::{Theme, UpdateNotificationPreferences};
+
+    #[test]
+    fn test_stored_document_defaults_missing_keys() {
+        let preferences: UserPreferences =
+            serde_json::from_value(serde_json::json!({ "theme": "dark" })).unwrap();
+
+        assert_eq!(preferences.theme, Theme::Dark);
+        assert_eq!(preferences.language, "en");
+        assert!(preferences.notifications.quota_warnings);
+    }
+
+    #[test]
+    fn test_apply_update_is_partial() {
+        let update = UpdatePreferencesRequest {
+            default_model: Some("gpt-4o".to_string()),
+            notifications: Some(UpdateNotificationPreferences {
+                quota_warnings: Some(false),
+                in_app: None,
+            }),
+            ..UpdatePreferencesRequest::default()
+        };
+
+        let preferences = apply_update(UserPreferences::default(), update);
+
+        assert_eq!(preferences.theme, Theme::System);
+        assert_eq!(preferences.default_model.as_deref(), Some("gpt-4o"));
+        assert!(!preferences.notifications.quota_warnings);
+        assert!(preferences.notifications.in_app);
+    }
+
+    #[test]
+    fn test_apply_update_clears_default_model() {
+        let current = UserPreferences {
+            default_model: Some("gpt-4o".to_string()),
+            ..UserPreferences::default()
+        };
+        let update = UpdatePreferencesRequest {
+            default_model: Some(String::new()),
+            ..UpdatePreferencesRequest::default()
+        };
+
+        assert_eq!(apply_update(current, update).default_model, None);
+    }
+
+    #[test]
+    fn test_update_rejects_unknown_keys() {
+        let result = serde_json::from_value::<UpdatePreferencesRequest>(
+            serde_json::json!({ "theme": "dark", "font_size": 14 }),
+        );
+        assert!(result.is_err());
+
+        let result = serde_json::from_value::<UpdatePreferencesRequest>(
+            serde_json::json!({ "theme": "blue" }),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_language() {
+        for valid in ["en", "pt-BR", "zh-Hant-TW", "ast"] {
+            let preferences = UserPreferences {
+                language: valid.to_string(),
+                ..UserPreferences::default()
+            };
+            assert!(validate(&preferences).is_ok(), "{valid} should be valid");
+        }
+
+        for invalid in ["", "e", "english", "en_US", "en-", "en-toolongsubtag"] {
+            let preferences = UserPreferences {
+                language: invalid.to_string(),
+                ..UserPreferences::default()
+            };
+            assert!(
+                validate(&preferences).is_err(),
+                "{invalid} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_update_chat_defaults() {
+        let current = UserPreferences {
+            temperature: Some(0.2),
+            persona: Some("Be brief.".to_string()),
+            ..UserPreferences::default()
+        };
+
+        // Omitted keys are kept
+        let update: UpdatePreferencesRequest =
+            serde_json::from_value(serde_json::json!({ "theme": "dark" })).unwrap();
+        let kept = apply_update(current.clone(), update);
+        assert_eq!(kept.temperature, Some(0.2));
+        assert_eq!(kept.persona.as_deref(), Some("Be brief."));
+
+        // null and blank reset
+        let update: UpdatePreferencesRequest =
+            serde_json::from_value(serde_json::json!({ "temperature": null, "persona": "  " }))
+                .unwrap();
+        let reset = apply_update(current, update);
+        assert_eq!(reset.temperature, None);
+        assert_eq!(reset.persona, None);
+    }
+
+    #[test]
+    fn test_validate_chat_defaults() {
+        let preferences = UserPreferences {
+            temperature: Some(2.5),
+            ..UserPreferences::default()
+        };
+        assert!(validate(&preferences).is_err());
+
+        let preferences = UserPreferences {
+            persona: Some("é".repeat(MAX_PERSONA_LENGTH)),
+            temperature: Some(1.0),
+            ..UserPreferences::default()
+        };
+        assert!(validate(&preferences).is_ok());
+
+        let preferences = UserPreferences {
+            persona: Some("x".repeat(MAX_PERSONA_LENGTH + 1)),
+            ..UserPreferences::default()
+        };
+        assert!(validate(&preferences).is_err());
+    }
+
+    #[test]
+    fn test_validate_default_model() {
+        let preferences = UserPreferences {
+            default_model: Some("x".repeat(MAX_DEFAULT_MODEL_LENGTH + 1)),
+            ..UserPreferences::default()
+        };
+        assert!(validate(&preferences).is_err());
+
+        let preferences = UserPreferences {
+            default_model: Some("<script>".to_string()),
+            ..UserPreferences::default()
+        };
+        assert!(validate(&preferences).is_err());
+    }
+}
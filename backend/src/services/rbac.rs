@@ -0,0 +1,604 @@
+//! Fine-grained permissions and custom roles
+//!
+//! Authorization beyond the base role of `users.role`:
+//!
+//! - **Permissions** are named `resource:action` (e.g. `users:write`). The
+//!   catalog is [`PERMISSIONS`]; [`sync_permissions`] writes it to the
+//!   `permissions` table at startup, so adding one is a code change only.
+//! - **Roles** are named sets of permissions created by admins
+//!   (`roles` and `role_permissions`) and assigned to users (`user_roles`).
+//! - The base role still counts: an enabled admin holds every permission,
+//!   an enabled user only those of their assigned roles, a disabled account
+//!   none.
+//!
+//! [`PermissionChecker`] resolves what a user holds. Admin routes require
+//! [`ADMIN_ACCESS`] (see [`crate::middleware::admin`]) plus the permission
+//! of the operation, checked by
+//! [`crate::middleware::permission::require_permission`]. A role granting
+//! `admin:access` and `users:read` thus makes a support agent who can look
+//! users up but not disable them.
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::{
+    permissions,
+    prelude::{Permissions, RolePermissions, Roles, UserRoles, Users},
+    role_permissions, roles,
+    sea_orm_active_enums::UserRole,
+    user_roles, users,
+};
+use crate::services::auth::TokenScope;
+
+/// Reach the admin API at all
+pub const ADMIN_ACCESS: &str = "admin:access";
+/// List and view user accounts
+pub const USERS_READ: &str = "users:read";
+/// Disable, enable and force password changes of user accounts
+pub const USERS_WRITE: &str = "users:write";
+/// Create and delete roles, and assign them to users
+pub const ROLES_WRITE: &str = "roles:write";
+/// View and export activity statistics
+pub const STATS_READ: &str = "stats:read";
+/// Manage verification emails, suppressions and the email log
+pub const EMAIL_MANAGE: &str = "email:manage";
+/// Create, download and restore backups
+pub const BACKUP_MANAGE: &str = "backup:manage";
+/// View configuration checks, version and settings
+pub const SYSTEM_READ: &str = "system:read";
+/// Close registration and put the API in read-only mode
+pub const SYSTEM_WRITE: &str = "system:write";
+/// View recorded API requests
+pub const ACCESS_LOGS_READ: &str = "access_logs:read";
+/// View the audit trail of security events
+pub const AUDIT_LOGS_READ: &str = "audit_logs:read";
+/// Change the branding
+pub const BRANDING_WRITE: &str = "branding:write";
+/// Mint scoped debug tokens
+pub const DEBUG_TOKENS: &str = "tokens:debug";
+
+/// Every permission known to the backend, with its description
+pub const PERMISSIONS: &[(&str, &str)] = &[
+    (ADMIN_ACCESS, "Reach the admin API"),
+    (USERS_READ, "List and view user accounts"),
+    (
+        USERS_WRITE,
+        "Disable, enable and force password changes of user accounts",
+    ),
+    (
+        ROLES_WRITE,
+        "Create and delete roles and assign them to users",
+    ),
+    (STATS_READ, "View and export activity statistics"),
+    (
+        EMAIL_MANAGE,
+        "Manage verification emails, suppressions and the email log",
+    ),
+    (BACKUP_MANAGE, "Create, download and restore backups"),
+    (
+        SYSTEM_READ,
+        "View configuration checks, version and settings",
+    ),
+    (
+        SYSTEM_WRITE,
+        "Close registration and put the API in read-only mode",
+    ),
+    (ACCESS_LOGS_READ, "View recorded API requests"),
+    (AUDIT_LOGS_READ, "View the audit trail of security events"),
+    (BRANDING_WRITE, "Change the branding"),
+    (DEBUG_TOKENS, "Mint scoped debug tokens"),
+];
+
+/// Names of the base roles, which custom roles cannot take
+const RESERVED_ROLE_NAMES: &[&str] = &["admin", "user"];
+
+/// Longest role name
+pub const MAX_ROLE_NAME_LENGTH: usize = 64;
+
+/// Errors from managing roles
+#[derive(Debug, thiserror::Error)]
+pub enum RbacError {
+    #[error("Role names are 1 to {MAX_ROLE_NAME_LENGTH} lowercase letters, digits, '-' or '_', other than 'admin' and 'user'")]
+    InvalidRoleName,
+
+    #[error("Unknown permission '{0}'")]
+    UnknownPermission(String),
+
+    #[error("A role named '{0}' already exists")]
+    RoleExists(String),
+
+    #[error("Role {0} not found")]
+    RoleNotFound(Uuid),
+
+    #[error("User {0} not found")]
+    UserNotFound(Uuid),
+
+    #[error(transparent)]
+    Database(#[from] DbErr),
+}
+
+/// Whether `name` is in [`PERMISSIONS`]
+#[must_use]
+pub fn is_known_permission(name: &str) -> bool {
+    PERMISSIONS.iter().any(|(known, _)| *known == name)
+}
+
+/// Check a custom role name
+///
+/// # Errors
+///
+/// Returns [`RbacError::InvalidRoleName`] for an empty, too long or
+/// reserved name, or one with other characters than lowercase ASCII
+/// letters, digits, `-` and `_`
+pub fn validate_role_name(name: &str) -> Result<(), RbacError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_ROLE_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_'))
+        && !RESERVED_ROLE_NAMES.contains(&name);
+    if valid {
+        Ok(())
+    } else {
+        Err(RbacError::InvalidRoleName)
+    }
+}
+
+/// Permissions a user holds
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GrantedPermissions {
+    /// Every permission (enabled admins)
+    all: bool,
+    names: BTreeSet<String>,
+}
+
+impl GrantedPermissions {
+    /// Every permission, including ones added later
+    #[must_use]
+    pub const fn all() -> Self {
+        Self {
+            all: true,
+            names: BTreeSet::new(),
+        }
+    }
+
+    /// The given permissions
+    #[must_use]
+    pub fn from_names(names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            all: false,
+            names: names.into_iter().collect(),
+        }
+    }
+
+    /// Permissions of a user with base role `role` and assigned roles
+    /// granting `assigned`, used with a token limited to `scope`
+    #[must_use]
+    pub fn resolve(
+        role: &UserRole,
+        disabled: bool,
+        scope: Option<&TokenScope>,
+        assigned: impl IntoIterator<Item = String>,
+    ) -> Self {
+        // A token scoped to another role than the user's grants nothing
+        // beyond the base permissions of that role, which are none
+        if disabled || scope.is_some_and(|scope| scope.role != *role) {
+            return Self::default();
+        }
+        match role {
+            UserRole::Admin => Self::all(),
+            UserRole::User => Self::from_names(assigned),
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, permission: &str) -> bool {
+        self.all || self.names.contains(permission)
+    }
+
+    /// Names of the permissions held, sorted
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        if self.all {
+            let mut names: Vec<String> = PERMISSIONS
+                .iter()
+                .map(|(name, _)| (*name).to_string())
+                .collect();
+            names.sort();
+            return names;
+        }
+        self.names.iter().cloned().collect()
+    }
+}
+
+/// Resolves the permissions of users
+#[derive(Clone)]
+pub struct PermissionChecker {
+    db: Arc<DatabaseConnection>,
+}
+
+impl PermissionChecker {
+    #[must_use]
+    pub const fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// Permissions of `user` through a token limited to `scope`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on database failure
+    pub async fn granted(
+        &self,
+        user: &users::Model,
+        scope: Option<&TokenScope>,
+    ) -> Result<GrantedPermissions, DbErr> {
+        // Admins and disabled accounts need no lookup
+        let assigned = if user.role == UserRole::User && user.disabled_at.is_none() {
+            assigned_permissions(self.db.as_ref(), user.id).await?
+        } else {
+            Vec::new()
+        };
+        Ok(GrantedPermissions::resolve(
+            &user.role,
+            user.disabled_at.is_some(),
+            scope,
+            assigned,
+        ))
+    }
+
+    /// Permissions of the user `user_id`, `None` if there is no such user
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on database failure
+    pub async fn granted_to(
+        &self,
+        user_id: Uuid,
+        scope: Option<&TokenScope>,
+    ) -> Result<Option<GrantedPermissions>, DbErr> {
+        let Some(user) = Users::find_by_id(user_id).one(self.db.as_ref()).await? else {
+            return Ok(None);
+        };
+        self.granted(&user, scope).await.map(Some)
+    }
+}
+
+/// Permissions granted by the roles assigned to `user_id`
+async fn assigned_permissions(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<String>, DbErr> {
+    let role_ids: Vec<Uuid> = UserRoles::find()
+        .select_only()
+        .column(user_roles::Column::RoleId)
+        .filter(user_roles::Column::UserId.eq(user_id))
+        .into_tuple()
+        .all(db)
+        .await?;
+    if role_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    RolePermissions::find()
+        .select_only()
+        .column(role_permissions::Column::Permission)
+        .distinct()
+        .filter(role_permissions::Column::RoleId.is_in(role_ids))
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+/// Write [`PERMISSIONS`] to the `permissions` table
+///
+/// Permissions no longer in the catalog are removed, along with their
+/// grants.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn sync_permissions(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let catalog = PERMISSIONS
+        .iter()
+        .map(|(name, description)| permissions::ActiveModel {
+            name: Set((*name).to_string()),
+            description: Set((*description).to_string()),
+        });
+    Permissions::insert_many(catalog)
+        .on_conflict(
+            OnConflict::column(permissions::Column::Name)
+                .update_column(permissions::Column::Description)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Permissions::delete_many()
+        .filter(permissions::Column::Name.is_not_in(PERMISSIONS.iter().map(|(name, _)| *name)))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// A custom role and the permissions it grants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleWithPermissions {
+    pub role: roles::Model,
+    /// Sorted permission names
+    pub permissions: Vec<String>,
+}
+
+/// Create a role granting `permissions`
+///
+/// # Errors
+///
+/// Returns an error if the name is invalid or taken, a permission is
+/// unknown, or on database failure
+pub async fn create_role(
+    db: &DatabaseConnection,
+    name: &str,
+    description: Option<String>,
+    permissions: &[String],
+    created_by: Uuid,
+) -> Result<RoleWithPermissions, RbacError> {
+    validate_role_name(name)?;
+    if let Some(unknown) = permissions.iter().find(|p| !is_known_permission(p)) {
+        return Err(RbacError::UnknownPermission(unknown.clone()));
+    }
+    let permissions: BTreeSet<String> = permissions.iter().cloned().collect();
+
+    let txn = db.begin().await?;
+    let taken = Roles::find()
+        .filter(roles::Column::Name.eq(name))
+        .one(&txn)
+        .await?
+        .is_some();
+    if taken {
+        return Err(RbacError::RoleExists(name.to_string()));
+    }
+
+    let role = roles::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        name: Set(name.to_string()),
+        description: Set(description),
+        created_by: Set(Some(created_by)),
+        created_at: Set(Utc::now().into()),
+    }
+    .insert(&txn)
+    .await?;
+
+    if !permissions.is_empty() {
+        RolePermissions::insert_many(permissions.iter().map(|permission| {
+            role_permissions::ActiveModel {
+                role_id: Set(role.id),
+                permission: Set(permission.clone()),
+            }
+        }))
+        .exec(&txn)
+        .await?;
+    }
+    txn.commit().await?;
+
+    Ok(RoleWithPermissions {
+        role,
+        permissions: permissions.into_iter().collect(),
+    })
+}
+
+/// Every custom role, by name
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn list_roles(db: &DatabaseConnection) -> Result<Vec<RoleWithPermissions>, DbErr> {
+    let roles = Roles::find()
+        .order_by_asc(roles::Column::Name)
+        .all(db)
+        .await?;
+    with_permissions(db, roles).await
+}
+
+/// Delete a role, unassigning it from its holders; `false` if there is none
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn delete_role(db: &DatabaseConnection, role_id: Uuid) -> Result<bool, DbErr> {
+    let result = Roles::delete_by_id(role_id).exec(db).await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// Custom roles assigned to `user_id`, by name
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn roles_of(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<Vec<RoleWithPermissions>, DbErr> {
+    let role_ids: Vec<Uuid> = UserRoles::find()
+        .select_only()
+        .column(user_roles::Column::RoleId)
+        .filter(user_roles::Column::UserId.eq(user_id))
+        .into_tuple()
+        .all(db)
+        .await?;
+    let roles = Roles::find()
+        .filter(roles::Column::Id.is_in(role_ids))
+        .order_by_asc(roles::Column::Name)
+        .all(db)
+        .await?;
+    with_permissions(db, roles).await
+}
+
+/// Replace the custom roles of `user_id` with `role_ids`
+///
+/// # Errors
+///
+/// Returns an error if the user or one of the roles does not exist, or on
+/// database failure
+pub async fn set_user_roles(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    role_ids: &[Uuid],
+) -> Result<Vec<RoleWithPermissions>, RbacError> {
+    let role_ids: BTreeSet<Uuid> = role_ids.iter().copied().collect();
+
+    let txn = db.begin().await?;
+    if Users::find_by_id(user_id).one(&txn).await?.is_none() {
+        return Err(RbacError::UserNotFound(user_id));
+    }
+    let found: BTreeSet<Uuid> = Roles::find()
+        .select_only()
+        .column(roles::Column::Id)
+        .filter(roles::Column::Id.is_in(role_ids.iter().copied()))
+        .into_tuple::<Uuid>()
+        .all(&txn)
+        .await?
+        .into_iter()
+        .collect();
+    if let Some(missing) = role_ids.difference(&found).next() {
+        return Err(RbacError::RoleNotFound(*missing));
+    }
+
+    UserRoles::delete_many()
+        .filter(user_roles::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    if !role_ids.is_empty() {
+        let now = Utc::now();
+        UserRoles::insert_many(role_ids.iter().map(|role_id| user_roles::ActiveModel {
+            user_id: Set(user_id),
+            role_id: Set(*role_id),
+            created_at: Set(now.into()),
+        }))
+        .exec(&txn)
+        .await?;
+    }
+    txn.commit().await?;
+
+    Ok(roles_of(db, user_id).await?)
+}
+
+/// Attach the permissions of each role
+async fn with_permissions(
+    db: &DatabaseConnection,
+    roles: Vec<roles::Model>,
+) -> Result<Vec<RoleWithPermissions>, DbErr> {
+    let grants = RolePermissions::find()
+        .filter(role_permissions::Column::RoleId.is_in(roles.iter().map(|role| role.id)))
+        .all(db)
+        .await?;
+    let mut by_role: HashMap<Uuid, BTreeSet<String>> = HashMap::new();
+    for grant in grants {
+        by_role
+            .entry(grant.role_id)
+            .or_default()
+            .insert(grant.permission);
+    }
+
+    Ok(roles
+        .into_iter()
+        .map(|role| RoleWithPermissions {
+            permissions: by_role
+                .remove(&role.id)
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            role,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(permissions: &[&str]) -> Vec<String> {
+        permissions.iter().map(|p| (*p).to_string()).collect()
+    }
+
+    #[test]
+    fn test_admins_hold_every_permission() {
+        let granted = GrantedPermissions::resolve(&UserRole::Admin, false, None, Vec::new());
+        for (permission, _) in PERMISSIONS {
+            assert!(granted.contains(permission));
+        }
+        assert_eq!(granted.names().len(), PERMISSIONS.len());
+    }
+
+    #[test]
+    fn test_users_hold_their_assigned_permissions() {
+        let granted = GrantedPermissions::resolve(
+            &UserRole::User,
+            false,
+            None,
+            names(&[ADMIN_ACCESS, USERS_READ]),
+        );
+        assert!(granted.contains(ADMIN_ACCESS));
+        assert!(granted.contains(USERS_READ));
+        assert!(!granted.contains(USERS_WRITE));
+        assert_eq!(granted.names(), names(&[ADMIN_ACCESS, USERS_READ]));
+    }
+
+    #[test]
+    fn test_disabled_accounts_hold_nothing() {
+        let admin = GrantedPermissions::resolve(&UserRole::Admin, true, None, Vec::new());
+        let user = GrantedPermissions::resolve(&UserRole::User, true, None, names(&[USERS_READ]));
+        assert_eq!(admin, GrantedPermissions::default());
+        assert_eq!(user, GrantedPermissions::default());
+    }
+
+    #[test]
+    fn test_scoped_tokens_drop_to_the_scoped_role() {
+        let scope = TokenScope {
+            role: UserRole::User,
+        };
+        let granted =
+            GrantedPermissions::resolve(&UserRole::Admin, false, Some(&scope), Vec::new());
+        assert!(!granted.contains(ADMIN_ACCESS));
+
+        let scope = TokenScope {
+            role: UserRole::Admin,
+        };
+        let granted =
+            GrantedPermissions::resolve(&UserRole::Admin, false, Some(&scope), Vec::new());
+        assert!(granted.contains(ADMIN_ACCESS));
+    }
+
+    #[test]
+    fn test_role_names() {
+        for valid in ["support", "billing-read", "tier_2", "a"] {
+            assert!(validate_role_name(valid).is_ok(), "{valid}");
+        }
+        let too_long = "a".repeat(MAX_ROLE_NAME_LENGTH + 1);
+        for invalid in [
+            "",
+            "admin",
+            "user",
+            "Support",
+            "two words",
+            "a:b",
+            &too_long,
+        ] {
+            assert!(validate_role_name(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_permission_catalog() {
+        assert!(is_known_permission(USERS_WRITE));
+        assert!(!is_known_permission("users:*"));
+        let unique: BTreeSet<&str> = PERMISSIONS.iter().map(|(name, _)| *name).collect();
+        assert_eq!(unique.len(), PERMISSIONS.len());
+    }
+}
@@ -0,0 +1,176 @@
+//! Time spent in dependencies while handling a request.
+//!
+//! [`scope`] runs a request with a fresh [`RequestTimings`] in a task-local;
+//! dependency calls made by the request add their duration to it:
+//!
+//! - **database**: every query, through the metric callback installed on the
+//!   connection ([`record`] as `sea_orm` reports it)
+//! - **valkey**: every command sent on a pooled
+//!   [`crate::services::valkey::AsyncConnection`]
+//! - **provider**: opening the LLM provider stream, wrapped in [`timed`]
+//!
+//! Calls made outside a scope (background jobs, spawned tasks) are not
+//! recorded. [`timed`] also wraps the call in a `dependency` span, so the
+//! same breakdown shows up in traces.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use cobalt_stack_backend::services::request_timing::{self, Dependency};
+//!
+//! # async fn example() {
+//! let ((), timings) = request_timing::scope(async {
+//!     request_timing::timed(Dependency::Provider, async { /* call */ }).await;
+//! })
+//! .await;
+//! assert_eq!(timings.provider.calls, 1);
+//! # }
+//! ```
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tracing::Instrument;
+
+/// Dependency a request waits on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dependency {
+    Database,
+    Valkey,
+    Provider,
+}
+
+impl Dependency {
+    /// Name used in spans and log fields
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Database => "db",
+            Self::Valkey => "valkey",
+            Self::Provider => "provider",
+        }
+    }
+
+    const fn index(self) -> usize {
+        match self {
+            Self::Database => 0,
+            Self::Valkey => 1,
+            Self::Provider => 2,
+        }
+    }
+}
+
+/// Time spent in one dependency
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DependencyTime {
+    /// Sum of the call durations
+    pub total: Duration,
+    /// Number of calls
+    pub calls: u32,
+}
+
+/// Breakdown of a finished request, returned by [`scope`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimingBreakdown {
+    pub database: DependencyTime,
+    pub valkey: DependencyTime,
+    pub provider: DependencyTime,
+}
+
+/// Accumulator shared by the calls of one request
+#[derive(Debug, Default)]
+pub struct RequestTimings {
+    nanos: [AtomicU64; 3],
+    calls: [AtomicU32; 3],
+}
+
+impl RequestTimings {
+    fn add(&self, dependency: Dependency, elapsed: Duration) {
+        let index = dependency.index();
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.nanos[index].fetch_add(nanos, Ordering::Relaxed);
+        self.calls[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self, dependency: Dependency) -> DependencyTime {
+        let index = dependency.index();
+        DependencyTime {
+            total: Duration::from_nanos(self.nanos[index].load(Ordering::Relaxed)),
+            calls: self.calls[index].load(Ordering::Relaxed),
+        }
+    }
+
+    fn breakdown(&self) -> TimingBreakdown {
+        TimingBreakdown {
+            database: self.get(Dependency::Database),
+            valkey: self.get(Dependency::Valkey),
+            provider: self.get(Dependency::Provider),
+        }
+    }
+}
+
+tokio::task_local! {
+    static TIMINGS: Arc<RequestTimings>;
+}
+
+/// Run `future` with its own timings and return them with its output
+pub async fn scope<F: Future>(future: F) -> (F::Output, TimingBreakdown) {
+    let timings = Arc::new(RequestTimings::default());
+    let output = TIMINGS.scope(Arc::clone(&timings), future).await;
+    (output, timings.breakdown())
+}
+
+/// Add a call of `elapsed` to the current request, if any
+pub fn record(dependency: Dependency, elapsed: Duration) {
+    let _ = TIMINGS.try_with(|timings| timings.add(dependency, elapsed));
+}
+
+/// Run `future` in a `dependency` span and record its duration
+pub async fn timed<F: Future>(dependency: Dependency, future: F) -> F::Output {
+    let started = Instant::now();
+    let output = future
+        .instrument(tracing::debug_span!(
+            "dependency",
+            name = dependency.as_str()
+        ))
+        .await;
+    record(dependency, started.elapsed());
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scope_collects_dependency_time() {
+        let ((), timings) = scope(async {
+            record(Dependency::Database, Duration::from_millis(3));
+            record(Dependency::Database, Duration::from_millis(4));
+            timed(Dependency::Valkey, async {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            })
+            .await;
+        })
+        .await;
+
+        assert_eq!(timings.database.calls, 2);
+        assert_eq!(timings.database.total, Duration::from_millis(7));
+        assert_eq!(timings.valkey.calls, 1);
+        assert!(timings.valkey.total >= Duration::from_millis(5));
+        assert_eq!(timings.provider, DependencyTime::default());
+    }
+
+    #[tokio::test]
+    async fn test_record_outside_scope_is_ignored() {
+        record(Dependency::Provider, Duration::from_secs(1));
+
+        let ((), timings) = scope(async {}).await;
+        assert_eq!(timings, TimingBreakdown::default());
+    }
+}
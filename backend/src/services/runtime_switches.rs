@@ -0,0 +1,223 @@
+//! Registration and read-only switches flipped at runtime
+//!
+//! The switches live in the single-row `runtime_switches` table. Each
+//! instance keeps a copy in [`RuntimeSwitches`], read on every request
+//! without a query: [`RuntimeSwitches::refresh`], run by the scheduler every
+//! [`REFRESH_INTERVAL`], picks up changes made through another instance, and
+//! [`RuntimeSwitches::update`] applies a change to its own instance at once.
+
+use chrono::Utc;
+use sea_orm::{sea_query::OnConflict, DatabaseConnection, DbErr, EntityTrait, Set};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::models::{prelude::RuntimeSwitches as RuntimeSwitchesEntity, runtime_switches};
+
+/// Primary key of the only `runtime_switches` row
+const SWITCHES_ID: i32 = 1;
+
+/// How often each instance reloads the switches
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Maximum read-only reason length
+pub const MAX_REASON_LENGTH: usize = 500;
+
+/// State of the switches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Switches {
+    /// Whether new accounts can be registered (sign-up and social login)
+    pub registration_open: bool,
+    /// Whether mutating requests are rejected
+    pub read_only: bool,
+    /// Told to clients whose request was rejected while read-only
+    pub read_only_reason: Option<String>,
+}
+
+impl Default for Switches {
+    fn default() -> Self {
+        Self {
+            registration_open: true,
+            read_only: false,
+            read_only_reason: None,
+        }
+    }
+}
+
+impl From<runtime_switches::Model> for Switches {
+    fn from(row: runtime_switches::Model) -> Self {
+        Self {
+            registration_open: row.registration_open,
+            read_only: row.read_only,
+            read_only_reason: row.read_only_reason,
+        }
+    }
+}
+
+/// Switches of this instance, kept in sync with the database
+pub struct RuntimeSwitches {
+    db: Arc<DatabaseConnection>,
+    current: RwLock<Switches>,
+}
+
+impl RuntimeSwitches {
+    /// Start with the defaults until the first [`refresh`](Self::refresh)
+    #[must_use]
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+            current: RwLock::new(Switches::default()),
+        }
+    }
+
+    /// Switches in effect
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    #[must_use]
+    pub fn current(&self) -> Switches {
+        self.current.read().expect("switches lock poisoned").clone()
+    }
+
+    /// Whether new accounts can be registered
+    #[must_use]
+    pub fn registration_open(&self) -> bool {
+        self.current().registration_open
+    }
+
+    /// Reload the switches from the database
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on database failure; the previous switches stay in
+    /// effect
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    pub async fn refresh(&self) -> Result<(), DbErr> {
+        let switches = RuntimeSwitchesEntity::find_by_id(SWITCHES_ID)
+            .one(self.db.as_ref())
+            .await?
+            .map(Switches::from)
+            .unwrap_or_default();
+
+        let mut current = self.current.write().expect("switches lock poisoned");
+        if *current != switches {
+            tracing::info!(?switches, "Runtime switches changed");
+            *current = switches;
+        }
+        drop(current);
+        Ok(())
+    }
+
+    /// Store `switches` and apply them to this instance
+    ///
+    /// Other instances apply them within [`REFRESH_INTERVAL`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on database failure; nothing is changed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    pub async fn update(&self, switches: Switches, updated_by: Uuid) -> Result<(), DbErr> {
+        let row = runtime_switches::ActiveModel {
+            id: Set(SWITCHES_ID),
+            registration_open: Set(switches.registration_open),
+            read_only: Set(switches.read_only),
+            read_only_reason: Set(switches.read_only_reason.clone()),
+            updated_by: Set(Some(updated_by)),
+            updated_at: Set(Utc::now().into()),
+        };
+
+        RuntimeSwitchesEntity::insert(row)
+            .on_conflict(
+                OnConflict::column(runtime_switches::Column::Id)
+                    .update_columns([
+                        runtime_switches::Column::RegistrationOpen,
+                        runtime_switches::Column::ReadOnly,
+                        runtime_switches::Column::ReadOnlyReason,
+                        runtime_switches::Column::UpdatedBy,
+                        runtime_switches::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(self.db.as_ref())
+            .await?;
+
+        *self.current.write().expect("switches lock poisoned") = switches;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+    fn row(registration_open: bool, read_only: bool) -> runtime_switches::Model {
+        runtime_switches::Model {
+            id: SWITCHES_ID,
+            registration_open,
+            read_only,
+            read_only_reason: read_only.then(|| "Maintenance".to_string()),
+            updated_by: None,
+            updated_at: Utc::now().into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_without_row_keeps_defaults() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<runtime_switches::Model>::new()])
+            .into_connection();
+        let switches = RuntimeSwitches::new(Arc::new(db));
+
+        switches.refresh().await.unwrap();
+
+        assert_eq!(switches.current(), Switches::default());
+        assert!(switches.registration_open());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_applies_stored_switches() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[row(false, true)]])
+            .into_connection();
+        let switches = RuntimeSwitches::new(Arc::new(db));
+
+        switches.refresh().await.unwrap();
+
+        let current = switches.current();
+        assert!(!current.registration_open);
+        assert!(current.read_only);
+        assert_eq!(current.read_only_reason.as_deref(), Some("Maintenance"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_keeps_previous_switches() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .append_query_errors([DbErr::Custom("connection lost".to_string())])
+            .into_connection();
+        let switches = RuntimeSwitches::new(Arc::new(db));
+        let closed = Switches {
+            registration_open: false,
+            ..Switches::default()
+        };
+
+        switches
+            .update(closed.clone(), Uuid::new_v4())
+            .await
+            .unwrap();
+        assert!(switches.refresh().await.is_err());
+
+        assert_eq!(switches.current(), closed);
+    }
+}
@@ -0,0 +1,149 @@
+//! Sandbox mode for public demo deployments
+//!
+//! With sandbox mode on (see [`SandboxConfig`]), every account created by
+//! sign-up or social login is enrolled by [`enroll`]: it expires
+//! [`SandboxConfig::retention`] after creation and chats under the sandbox
+//! limits. [`purge_expired`], run by the scheduler, deletes expired accounts;
+//! their sessions, messages and other data go with them through the
+//! cascading foreign keys. Accounts that existed before sandbox mode was
+//! turned on, and admins created at startup, are never enrolled.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Query, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set,
+};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::SandboxConfig;
+use crate::models::{
+    prelude::{SandboxAccounts, Users},
+    sandbox_accounts, users,
+};
+
+/// How often expired sandbox accounts are purged
+pub const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Mark `user_id`, just created, as a sandbox account
+///
+/// Returns when the account expires. Run it in the transaction creating the
+/// user, so no account escapes the purge.
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn enroll<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    config: &SandboxConfig,
+) -> Result<DateTime<Utc>, DbErr> {
+    let now = Utc::now();
+    let retention = chrono::Duration::from_std(config.retention)
+        .map_err(|e| DbErr::Custom(format!("Invalid sandbox retention: {e}")))?;
+    let expires_at = now + retention;
+
+    SandboxAccounts::insert(sandbox_accounts::ActiveModel {
+        user_id: Set(user_id),
+        expires_at: Set(expires_at.into()),
+        created_at: Set(now.into()),
+    })
+    .exec_without_returning(db)
+    .await?;
+
+    tracing::info!(
+        target: "audit",
+        action = "auth.sandbox_enrolled",
+        user_id = %user_id,
+        expires_at = %expires_at,
+        "Sandbox account created"
+    );
+
+    Ok(expires_at)
+}
+
+/// When sandbox account `user_id` expires, `None` if it is not one
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn expires_at(db: &DatabaseConnection, user_id: Uuid) -> Result<Option<DateTime<Utc>>> {
+    Ok(SandboxAccounts::find_by_id(user_id)
+        .one(db)
+        .await?
+        .map(|account| account.expires_at.with_timezone(&Utc)))
+}
+
+/// Delete expired sandbox accounts with all their data
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn purge_expired(db: &DatabaseConnection) -> Result<u64> {
+    let expired = Query::select()
+        .column(sandbox_accounts::Column::UserId)
+        .from(SandboxAccounts)
+        .and_where(sandbox_accounts::Column::ExpiresAt.lte(Utc::now()))
+        .to_owned();
+
+    Ok(Users::delete_many()
+        .filter(users::Column::Id.in_subquery(expired))
+        .exec(db)
+        .await?
+        .rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+    fn config() -> SandboxConfig {
+        SandboxConfig {
+            retention: Duration::from_secs(7 * 86_400),
+            banner: "Sandbox".to_string(),
+            rate_limit_per_minute: 5,
+            daily_message_quota: 20,
+        }
+    }
+
+    fn exec(rows_affected: u64) -> MockExecResult {
+        MockExecResult {
+            last_insert_id: 0,
+            rows_affected,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enroll_sets_expiry_after_retention() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([exec(1)])
+            .into_connection();
+
+        let before = Utc::now();
+        let expires_at = enroll(&db, Uuid::new_v4(), &config()).await.unwrap();
+
+        let retention = expires_at - before;
+        assert!(retention >= chrono::Duration::days(7));
+        assert!(retention < chrono::Duration::days(7) + chrono::Duration::minutes(1));
+    }
+
+    #[tokio::test]
+    async fn test_expires_at_of_regular_account() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<sandbox_accounts::Model>::new()])
+            .into_connection();
+
+        assert_eq!(expires_at(&db, Uuid::new_v4()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_purge_reports_deleted_accounts() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results([exec(3)])
+            .into_connection();
+
+        assert_eq!(purge_expired(&db).await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,65 @@
+//! Background job scheduler.
+//!
+//! Runs periodic jobs on the Tokio runtime. A job that fails is logged and
+//! retried on the next tick; a slow job delays the next tick instead of
+//! overlapping with itself.
+//!
+//! Jobs run on every instance, so they must be safe to run concurrently
+//! (e.g. by claiming work with a conditional update).
+
+use std::{future::Future, time::Duration};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+/// Run `job` every `period`, starting immediately
+pub fn spawn_periodic<F, Fut>(name: &'static str, period: Duration, mut job: F) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        tracing::info!(
+            job = name,
+            period_secs = period.as_secs(),
+            "Scheduled job started"
+        );
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = job().await {
+                tracing::error!(job = name, "Scheduled job failed: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[tokio::test]
+    async fn test_job_runs_repeatedly_and_survives_errors() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&runs);
+
+        let handle = spawn_periodic("test", Duration::from_millis(10), move || {
+            let counter = Arc::clone(&counter);
+            async move {
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    anyhow::bail!("first run fails");
+                }
+                Ok(())
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        handle.abort();
+
+        assert!(runs.load(Ordering::SeqCst) >= 3);
+    }
+}
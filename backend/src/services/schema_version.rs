@@ -0,0 +1,206 @@
+//! Applied migrations against the ones this binary was built with.
+//!
+//! Entities are written for the schema of the last migration in
+//! [`migration::Migrator`]. A database with pending migrations (deployed
+//! before `sea-orm-cli migrate up`) or with migrations this binary does not
+//! know (migrated by a newer release during a rolling deploy) disagrees with
+//! them, which only shows up as query errors on the routes touching the
+//! changed tables. [`check_schema`] compares the two sets so startup,
+//! `/health/ready` and the doctor can report it instead.
+//!
+//! The check only reads `seaql_migrations`; it never creates or migrates
+//! anything.
+
+use std::fmt;
+
+use migration::{Alias, Migrator, MigratorTrait, Query};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr};
+
+/// How the database schema relates to this binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaState {
+    /// Every known migration is applied, and nothing else
+    UpToDate,
+    /// Migrations of this binary are not applied yet
+    Behind,
+    /// Migrations unknown to this binary are applied (a newer binary is needed)
+    Ahead,
+}
+
+impl SchemaState {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::UpToDate => "up_to_date",
+            Self::Behind => "behind",
+            Self::Ahead => "ahead",
+        }
+    }
+}
+
+impl fmt::Display for SchemaState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Difference between the applied and the known migrations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaStatus {
+    /// Number of migrations this binary knows
+    pub expected: usize,
+    /// Number of migrations recorded in the database
+    pub applied: usize,
+    /// Known but not applied, oldest first
+    pub pending: Vec<String>,
+    /// Applied but unknown to this binary, oldest first
+    pub unknown: Vec<String>,
+}
+
+impl SchemaStatus {
+    /// Compare the `applied` migration names with the `known` ones
+    #[must_use]
+    pub fn compare(known: &[String], applied: &[String]) -> Self {
+        let mut unknown: Vec<String> = applied
+            .iter()
+            .filter(|name| !known.contains(name))
+            .cloned()
+            .collect();
+        unknown.sort();
+        Self {
+            expected: known.len(),
+            applied: applied.len(),
+            pending: known
+                .iter()
+                .filter(|name| !applied.contains(name))
+                .cloned()
+                .collect(),
+            unknown,
+        }
+    }
+
+    /// Unknown migrations win over pending ones: only a newer binary can
+    /// serve such a database
+    #[must_use]
+    pub fn state(&self) -> SchemaState {
+        if !self.unknown.is_empty() {
+            SchemaState::Ahead
+        } else if !self.pending.is_empty() {
+            SchemaState::Behind
+        } else {
+            SchemaState::UpToDate
+        }
+    }
+
+    /// Log what differs and how to fix it
+    pub fn log_diagnostics(&self) {
+        match self.state() {
+            SchemaState::UpToDate => tracing::info!(
+                migrations = self.applied,
+                "Database schema matches this binary"
+            ),
+            SchemaState::Behind => tracing::warn!(
+                pending = ?self.pending,
+                "Database schema is behind this binary: {} of {} migrations are not applied; \
+                 run `sea-orm-cli migrate up`",
+                self.pending.len(),
+                self.expected
+            ),
+            SchemaState::Ahead => tracing::warn!(
+                unknown = ?self.unknown,
+                pending = ?self.pending,
+                "Database schema is ahead of this binary: {} applied migrations are unknown to \
+                 it; deploy the release that added them",
+                self.unknown.len()
+            ),
+        }
+    }
+}
+
+/// Names of the migrations this binary was built with, oldest first
+#[must_use]
+pub fn known_migrations() -> Vec<String> {
+    Migrator::migrations()
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect()
+}
+
+/// Names of the migrations recorded in the database
+///
+/// # Errors
+///
+/// Returns an error if the migration table cannot be read, including when
+/// it does not exist because the database was never migrated.
+pub async fn applied_migrations(db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+    let select = Query::select()
+        .column(Alias::new("version"))
+        .from(Migrator::migration_table_name())
+        .to_owned();
+    db.query_all(db.get_database_backend().build(&select))
+        .await?
+        .iter()
+        .map(|row| row.try_get::<String>("", "version"))
+        .collect()
+}
+
+/// Compare the migrations applied to `db` with the ones of this binary
+///
+/// # Errors
+///
+/// Returns an error if the migration table cannot be read.
+pub async fn check_schema(db: &DatabaseConnection) -> Result<SchemaStatus, DbErr> {
+    let applied = applied_migrations(db).await?;
+    Ok(SchemaStatus::compare(&known_migrations(), &applied))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_compare_migrations() {
+        let known = names(&["m1", "m2", "m3"]);
+
+        let status = SchemaStatus::compare(&known, &names(&["m1", "m2", "m3"]));
+        assert_eq!(status.state(), SchemaState::UpToDate);
+
+        let status = SchemaStatus::compare(&known, &names(&["m1"]));
+        assert_eq!(status.state(), SchemaState::Behind);
+        assert_eq!(status.pending, names(&["m2", "m3"]));
+
+        let status = SchemaStatus::compare(&known, &names(&["m4", "m1", "m2"]));
+        assert_eq!(status.state(), SchemaState::Ahead);
+        assert_eq!(status.unknown, names(&["m4"]));
+        assert_eq!(status.pending, names(&["m3"]));
+    }
+
+    #[test]
+    fn test_known_migrations_are_ordered() {
+        let known = known_migrations();
+        assert!(!known.is_empty());
+        assert!(known.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[cfg(feature = "demo")]
+    #[tokio::test]
+    async fn test_check_schema_reads_applied_migrations() {
+        let db = crate::demo::open().await.unwrap();
+        let status = check_schema(&db).await.unwrap();
+        assert_eq!(status.state(), SchemaState::UpToDate);
+
+        db.execute_unprepared("DELETE FROM seaql_migrations WHERE version LIKE 'm202502%'")
+            .await
+            .unwrap();
+        let status = check_schema(&db).await.unwrap();
+        assert_eq!(status.state(), SchemaState::Behind);
+        assert!(status
+            .pending
+            .iter()
+            .all(|name| name.starts_with("m202502")));
+    }
+}
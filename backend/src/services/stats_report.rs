@@ -0,0 +1,514 @@
+//! Activity reports for administrators.
+//!
+//! [`collect_report`] summarizes a period: signups, active users, chat volume
+//! and LLM usage per model, priced with the `cost_per_million_*_tokens` rates
+//! of `models.toml`. Admins download it as CSV from
+//! `GET /api/v1/admin/stats/export`; with `ADMIN_STATS_REPORT_ENABLED=true`
+//! the scheduler also emails it to every admin once per period
+//! ([`send_due_report`]).
+//!
+//! Active users are users who signed in or sent a chat message during the
+//! period. Only the latest sign-in of each user is stored, so reports on
+//! older periods miss users whose only activity there was a sign-in.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, JoinType, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait, Set,
+};
+use std::collections::HashMap;
+
+use crate::config::StatsReportConfig;
+use crate::infrastructure::llm::ModelRegistry;
+use crate::models::{
+    chat_messages, chat_sessions, chat_usage,
+    prelude::{ChatMessages, ChatSessions, ChatUsage, ScheduledReports, Users},
+    scheduled_reports,
+    sea_orm_active_enums::UserRole,
+    users,
+};
+use crate::services::email::{templates::ADMIN_STATS_REPORT, EmailMessage, EmailSender};
+
+/// `scheduled_reports` row of the emailed report
+pub const REPORT_NAME: &str = "admin_stats";
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// Prices of the configured models, by model ID
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelPricing {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl ModelPricing {
+    /// Prices of every model in the registry, enabled or not, so usage
+    /// recorded before a model was disabled is still priced
+    #[must_use]
+    pub fn from_registry(registry: &ModelRegistry) -> Self {
+        Self {
+            prices: registry
+                .models()
+                .map(|model| {
+                    (
+                        model.id.clone(),
+                        ModelPrice {
+                            input_per_million: model.cost_per_million_input_tokens,
+                            output_per_million: model.cost_per_million_output_tokens,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Cost in USD of the tokens, or `None` for a model without a price
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn cost(&self, model: &str, prompt_tokens: u64, completion_tokens: u64) -> Option<f64> {
+        self.prices.get(model).map(|price| {
+            (prompt_tokens as f64).mul_add(
+                price.input_per_million,
+                completion_tokens as f64 * price.output_per_million,
+            ) / 1_000_000.0
+        })
+    }
+}
+
+impl FromIterator<(String, ModelPrice)> for ModelPricing {
+    fn from_iter<I: IntoIterator<Item = (String, ModelPrice)>>(iter: I) -> Self {
+        Self {
+            prices: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// LLM usage of one model over a report period
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelUsage {
+    pub model: String,
+    /// Assistant replies generated
+    pub replies: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// `None` when the model has no price in `models.toml`
+    pub cost_usd: Option<f64>,
+}
+
+/// Activity over a report period
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsReport {
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    /// Accounts created
+    pub signups: u64,
+    /// Users who signed in or sent a chat message
+    pub active_users: u64,
+    /// Conversations started
+    pub chat_sessions: u64,
+    /// Messages from users and replies
+    pub chat_messages: u64,
+    /// Usage per model, most tokens first
+    pub models: Vec<ModelUsage>,
+}
+
+impl StatsReport {
+    /// Total cost of the priced models
+    #[must_use]
+    pub fn llm_cost_usd(&self) -> f64 {
+        self.models.iter().filter_map(|usage| usage.cost_usd).sum()
+    }
+
+    /// `metric,value` rows, followed by `prompt_tokens:<model>`,
+    /// `completion_tokens:<model>` and `llm_cost_usd:<model>` per model (cost
+    /// left empty when the model has no price)
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![
+            ("period_start".to_string(), self.period_start.to_rfc3339()),
+            ("period_end".to_string(), self.period_end.to_rfc3339()),
+            ("signups".to_string(), self.signups.to_string()),
+            ("active_users".to_string(), self.active_users.to_string()),
+            ("chat_sessions".to_string(), self.chat_sessions.to_string()),
+            ("chat_messages".to_string(), self.chat_messages.to_string()),
+            (
+                "llm_replies".to_string(),
+                self.models
+                    .iter()
+                    .map(|usage| usage.replies)
+                    .sum::<u64>()
+                    .to_string(),
+            ),
+            (
+                "llm_cost_usd".to_string(),
+                format!("{:.4}", self.llm_cost_usd()),
+            ),
+        ];
+        for usage in &self.models {
+            rows.push((
+                format!("prompt_tokens:{}", usage.model),
+                usage.prompt_tokens.to_string(),
+            ));
+            rows.push((
+                format!("completion_tokens:{}", usage.model),
+                usage.completion_tokens.to_string(),
+            ));
+            rows.push((
+                format!("llm_cost_usd:{}", usage.model),
+                usage
+                    .cost_usd
+                    .map(|cost| format!("{cost:.4}"))
+                    .unwrap_or_default(),
+            ));
+        }
+
+        let mut csv = String::from("metric,value\n");
+        for (metric, value) in rows {
+            csv.push_str(&csv_field(&metric));
+            csv.push(',');
+            csv.push_str(&csv_field(&value));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Summarize activity from `start` (inclusive) to `end` (exclusive)
+///
+/// # Errors
+///
+/// Returns an error on database failure
+pub async fn collect_report(
+    db: &DatabaseConnection,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    pricing: &ModelPricing,
+) -> Result<StatsReport, DbErr> {
+    let signups = Users::find()
+        .filter(users::Column::CreatedAt.gte(start))
+        .filter(users::Column::CreatedAt.lt(end))
+        .count(db)
+        .await?;
+
+    let chatting_users = ChatMessages::find()
+        .select_only()
+        .column(chat_sessions::Column::UserId)
+        .join(
+            JoinType::InnerJoin,
+            chat_messages::Relation::ChatSessions.def(),
+        )
+        .filter(chat_messages::Column::Role.eq("user"))
+        .filter(chat_messages::Column::CreatedAt.gte(start))
+        .filter(chat_messages::Column::CreatedAt.lt(end))
+        .into_query();
+    let active_users = Users::find()
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(users::Column::LastLoginAt.gte(start))
+                        .add(users::Column::LastLoginAt.lt(end)),
+                )
+                .add(users::Column::Id.in_subquery(chatting_users)),
+        )
+        .count(db)
+        .await?;
+
+    let chat_sessions = ChatSessions::find()
+        .filter(chat_sessions::Column::CreatedAt.gte(start))
+        .filter(chat_sessions::Column::CreatedAt.lt(end))
+        .count(db)
+        .await?;
+
+    let chat_messages = ChatMessages::find()
+        .filter(chat_messages::Column::CreatedAt.gte(start))
+        .filter(chat_messages::Column::CreatedAt.lt(end))
+        .count(db)
+        .await?;
+
+    let prompt_tokens = Expr::col(chat_usage::Column::PromptTokens).sum();
+    let completion_tokens = Expr::col(chat_usage::Column::CompletionTokens).sum();
+    let rows: Vec<(String, i64, i64, i64)> = ChatUsage::find()
+        .select_only()
+        .column(chat_usage::Column::Model)
+        .column_as(Expr::col(chat_usage::Column::Id).count(), "replies")
+        .column_as(prompt_tokens.clone(), "prompt_tokens")
+        .column_as(completion_tokens.clone(), "completion_tokens")
+        .filter(chat_usage::Column::CreatedAt.gte(start))
+        .filter(chat_usage::Column::CreatedAt.lt(end))
+        .group_by(chat_usage::Column::Model)
+        .order_by_desc(prompt_tokens.add(completion_tokens))
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let models = rows
+        .into_iter()
+        .map(|(model, replies, prompt_tokens, completion_tokens)| {
+            let prompt_tokens = u64::try_from(prompt_tokens).unwrap_or(0);
+            let completion_tokens = u64::try_from(completion_tokens).unwrap_or(0);
+            ModelUsage {
+                cost_usd: pricing.cost(&model, prompt_tokens, completion_tokens),
+                model,
+                replies: u64::try_from(replies).unwrap_or(0),
+                prompt_tokens,
+                completion_tokens,
+            }
+        })
+        .collect();
+
+    Ok(StatsReport {
+        period_start: start,
+        period_end: end,
+        signups,
+        active_users,
+        chat_sessions,
+        chat_messages,
+        models,
+    })
+}
+
+/// Render the report email for one admin
+///
+/// # Errors
+///
+/// Returns an error if the template fails to render
+pub fn render_report(admin: &users::Model, report: &StatsReport) -> Result<EmailMessage> {
+    let model_lines = if report.models.is_empty() {
+        "- None".to_string()
+    } else {
+        report
+            .models
+            .iter()
+            .map(|usage| {
+                let cost = usage
+                    .cost_usd
+                    .map_or_else(|| "no price".to_string(), |cost| format!("${cost:.2}"));
+                format!(
+                    "- {}: {} replies, {} prompt / {} completion tokens, {}",
+                    usage.model, usage.replies, usage.prompt_tokens, usage.completion_tokens, cost
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let vars = HashMap::from([
+        ("username", admin.username.clone()),
+        (
+            "period_start",
+            report.period_start.format("%Y-%m-%d").to_string(),
+        ),
+        (
+            "period_end",
+            report.period_end.format("%Y-%m-%d").to_string(),
+        ),
+        ("signups", report.signups.to_string()),
+        ("active_users", report.active_users.to_string()),
+        ("chat_sessions", report.chat_sessions.to_string()),
+        ("chat_messages", report.chat_messages.to_string()),
+        ("llm_cost", format!("{:.2}", report.llm_cost_usd())),
+        ("models", model_lines),
+    ]);
+    let (subject, body) = ADMIN_STATS_REPORT.render(&vars)?;
+
+    Ok(EmailMessage {
+        to: admin.email.clone(),
+        subject,
+        body,
+        template: Some(ADMIN_STATS_REPORT.name),
+        unsubscribe_url: None,
+    })
+}
+
+/// Email the report to every admin if a period has passed since the last
+/// one; returns the number of emails sent
+///
+/// The report is claimed with a conditional update before sending, so
+/// concurrent instances never send the same report twice. It covers the time
+/// since the previous report (one period for the first report). Only
+/// verified, enabled admin accounts receive it; a failed delivery is logged
+/// and not retried.
+///
+/// # Errors
+///
+/// Returns an error if the report cannot be claimed or collected
+pub async fn send_due_report(
+    db: &DatabaseConnection,
+    sender: &(dyn EmailSender + Send + Sync),
+    config: &StatsReportConfig,
+    pricing: &ModelPricing,
+) -> Result<usize> {
+    let now = Utc::now();
+    let cutoff = now - Duration::from_std(config.period)?;
+
+    ScheduledReports::insert(scheduled_reports::ActiveModel {
+        name: Set(REPORT_NAME.to_string()),
+        last_sent_at: Set(None),
+    })
+    .on_conflict(
+        OnConflict::column(scheduled_reports::Column::Name)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    let last_sent_at = ScheduledReports::find_by_id(REPORT_NAME)
+        .one(db)
+        .await?
+        .and_then(|report| report.last_sent_at);
+    if last_sent_at.is_some_and(|at| at > cutoff) {
+        return Ok(0);
+    }
+
+    // Claim the report; another instance may have sent it already
+    let unchanged = last_sent_at.map_or_else(
+        || scheduled_reports::Column::LastSentAt.is_null(),
+        |at| scheduled_reports::Column::LastSentAt.eq(at),
+    );
+    let claimed = ScheduledReports::update_many()
+        .col_expr(scheduled_reports::Column::LastSentAt, Expr::value(now))
+        .filter(scheduled_reports::Column::Name.eq(REPORT_NAME))
+        .filter(unchanged)
+        .exec(db)
+        .await?;
+    if claimed.rows_affected == 0 {
+        return Ok(0);
+    }
+
+    let start = last_sent_at.map_or(cutoff, |at| at.with_timezone(&Utc));
+    let report = collect_report(db, start, now, pricing).await?;
+
+    let admins = Users::find()
+        .filter(users::Column::Role.eq(UserRole::Admin))
+        .filter(users::Column::EmailVerified.eq(true))
+        .filter(users::Column::DisabledAt.is_null())
+        .all(db)
+        .await?;
+
+    let mut sent = 0;
+    for admin in admins {
+        let result = render_report(&admin, &report).and_then(|message| sender.send_email(&message));
+        match result {
+            Ok(()) => sent += 1,
+            Err(e) => tracing::warn!("Failed to send stats report to admin {}: {}", admin.id, e),
+        }
+    }
+
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pricing() -> ModelPricing {
+        std::iter::once((
+            "gpt-4o".to_string(),
+            ModelPrice {
+                input_per_million: 2.5,
+                output_per_million: 10.0,
+            },
+        ))
+        .collect()
+    }
+
+    fn report() -> StatsReport {
+        let end = Utc::now();
+        let pricing = pricing();
+        StatsReport {
+            period_start: end - Duration::days(7),
+            period_end: end,
+            signups: 4,
+            active_users: 12,
+            chat_sessions: 9,
+            chat_messages: 80,
+            models: vec![
+                ModelUsage {
+                    model: "gpt-4o".to_string(),
+                    replies: 30,
+                    prompt_tokens: 200_000,
+                    completion_tokens: 50_000,
+                    cost_usd: pricing.cost("gpt-4o", 200_000, 50_000),
+                },
+                ModelUsage {
+                    model: "local,model".to_string(),
+                    replies: 10,
+                    prompt_tokens: 1_000,
+                    completion_tokens: 500,
+                    cost_usd: pricing.cost("local,model", 1_000, 500),
+                },
+            ],
+        }
+    }
+
+    fn admin() -> users::Model {
+        users::Model {
+            id: uuid::Uuid::new_v4(),
+            username: "root".to_string(),
+            email: "root@example.com".to_string(),
+            password_hash: None,
+            email_verified: true,
+            created_at: Utc::now().into(),
+            updated_at: Utc::now().into(),
+            role: UserRole::Admin,
+            disabled_at: None,
+            last_login_at: None,
+            tokens_not_before: None,
+            password_changed_at: None,
+            password_rotation_required: false,
+        }
+    }
+
+    #[test]
+    fn test_cost() {
+        let pricing = pricing();
+
+        assert_eq!(pricing.cost("gpt-4o", 1_000_000, 100_000), Some(3.5));
+        assert_eq!(pricing.cost("unknown", 1_000, 1_000), None);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = report().to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "metric,value");
+        assert!(lines.contains(&"signups,4"));
+        assert!(lines.contains(&"llm_replies,40"));
+        assert!(lines.contains(&"llm_cost_usd,1.0000"));
+        assert!(lines.contains(&"prompt_tokens:gpt-4o,200000"));
+        assert!(lines.contains(&"\"llm_cost_usd:local,model\","));
+    }
+
+    #[test]
+    fn test_render_report() {
+        let message = render_report(&admin(), &report()).unwrap();
+
+        assert_eq!(message.to, "root@example.com");
+        assert!(message.body.contains("New signups: 4"));
+        assert!(message.body.contains("LLM cost: $1.00"));
+        assert!(message
+            .body
+            .contains("- gpt-4o: 30 replies, 200000 prompt / 50000 completion tokens, $1.00"));
+        assert!(message.body.contains("- local,model: 10 replies"));
+        assert!(message.body.contains("no price"));
+        assert!(message.unsubscribe_url.is_none());
+    }
+}
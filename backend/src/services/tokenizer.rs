@@ -0,0 +1,247 @@
+//! Token counting per model.
+//!
+//! [`TokenizerService`] maps a model id to a [`Tokenizer`] and caches the
+//! result, so the (expensive) BPE tables are loaded once per encoding and the
+//! model lookup once per model. It is shared by chat (message token counts)
+//! and the signed `POST /internal/tokens/count` endpoint used by sidecar
+//! services such as the RAG ingester.
+//!
+//! GPT models use their tiktoken encoding. Other models (Llama, Qwen, ...)
+//! fall back to [`DEFAULT_ENCODING`], which gives an estimate good enough for
+//! context budgeting; register an exact tokenizer with
+//! [`TokenizerService::register`] where it matters.
+//!
+//! # Examples
+//!
+//! ```
+//! use cobalt_stack_backend::services::tokenizer::TokenizerService;
+//!
+//! let tokenizer = TokenizerService::new().for_model("gpt-4o").unwrap();
+//! assert_eq!(tokenizer.encoding(), "o200k_base");
+//! assert_eq!(tokenizer.count_tokens("hello world"), 2);
+//! ```
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+use tiktoken_rs::{tokenizer::Tokenizer as Encoding, CoreBPE};
+
+/// Encoding used for models tiktoken does not know
+pub const DEFAULT_ENCODING: &str = "cl100k_base";
+
+/// Model ids cached at most; later unknown ids are resolved on every call
+/// (ids come from request bodies, so the cache must not grow unbounded)
+pub const MAX_CACHED_MODELS: usize = 256;
+
+/// Counts tokens of text for one model family
+pub trait Tokenizer: Send + Sync {
+    /// Name of the encoding, e.g. `cl100k_base`
+    fn encoding(&self) -> &str;
+
+    /// Number of tokens `text` encodes to
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// [`Tokenizer`] backed by a tiktoken BPE encoding
+pub struct TiktokenTokenizer {
+    encoding: &'static str,
+    bpe: CoreBPE,
+}
+
+impl TiktokenTokenizer {
+    /// Load the BPE tables of an encoding
+    ///
+    /// # Errors
+    /// Returns an error if the bundled encoding data cannot be parsed
+    pub fn new(encoding: Encoding) -> Result<Self> {
+        Ok(Self {
+            encoding: encoding_name(encoding),
+            bpe: tiktoken_rs::get_bpe_from_tokenizer(encoding)?,
+        })
+    }
+}
+
+impl Tokenizer for TiktokenTokenizer {
+    fn encoding(&self) -> &str {
+        self.encoding
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.bpe.encode_with_special_tokens(text).len()
+    }
+}
+
+/// Resolves and caches the tokenizer of each model
+#[derive(Default)]
+pub struct TokenizerService {
+    /// Tokenizers by model id, including registered ones
+    models: RwLock<HashMap<String, Arc<dyn Tokenizer>>>,
+    /// Loaded tiktoken encodings, shared by the models using them
+    encodings: RwLock<HashMap<Encoding, Arc<dyn Tokenizer>>>,
+}
+
+impl TokenizerService {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `tokenizer` for `model` instead of the tiktoken lookup
+    pub fn register(&self, model: impl Into<String>, tokenizer: Arc<dyn Tokenizer>) {
+        self.models
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(model.into(), tokenizer);
+    }
+
+    /// Tokenizer for a model id
+    ///
+    /// # Errors
+    /// Returns an error if the model's encoding cannot be loaded
+    pub fn for_model(&self, model: &str) -> Result<Arc<dyn Tokenizer>> {
+        let cached = self
+            .models
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(model)
+            .cloned();
+        if let Some(tokenizer) = cached {
+            return Ok(tokenizer);
+        }
+
+        let tokenizer = match tiktoken_rs::tokenizer::get_tokenizer(model) {
+            Some(encoding) => self.for_encoding(encoding)?,
+            None => self.default_tokenizer()?,
+        };
+
+        let mut models = self.models.write().unwrap_or_else(PoisonError::into_inner);
+        if models.len() < MAX_CACHED_MODELS {
+            models.insert(model.to_string(), Arc::clone(&tokenizer));
+        }
+        drop(models);
+
+        Ok(tokenizer)
+    }
+
+    /// Tokenizer for text not tied to a model ([`DEFAULT_ENCODING`])
+    ///
+    /// # Errors
+    /// Returns an error if the encoding cannot be loaded
+    pub fn default_tokenizer(&self) -> Result<Arc<dyn Tokenizer>> {
+        self.for_encoding(Encoding::Cl100kBase)
+    }
+
+    /// Count the tokens of `text` for a model
+    ///
+    /// # Errors
+    /// Returns an error if the model's encoding cannot be loaded
+    pub fn count_tokens(&self, model: &str, text: &str) -> Result<usize> {
+        Ok(self.for_model(model)?.count_tokens(text))
+    }
+
+    fn for_encoding(&self, encoding: Encoding) -> Result<Arc<dyn Tokenizer>> {
+        let loaded = self
+            .encodings
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&encoding)
+            .cloned();
+        if let Some(tokenizer) = loaded {
+            return Ok(tokenizer);
+        }
+
+        tracing::debug!(encoding = encoding_name(encoding), "Loading tokenizer");
+        let tokenizer: Arc<dyn Tokenizer> = Arc::new(TiktokenTokenizer::new(encoding)?);
+        // Another caller may have loaded it meanwhile; keep the first
+        Ok(Arc::clone(
+            self.encodings
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(encoding)
+                .or_insert(tokenizer),
+        ))
+    }
+}
+
+/// Token count as stored on chat messages
+#[must_use]
+pub fn to_token_count(count: usize) -> i32 {
+    i32::try_from(count).unwrap_or(i32::MAX)
+}
+
+const fn encoding_name(encoding: Encoding) -> &'static str {
+    match encoding {
+        Encoding::O200kBase => "o200k_base",
+        Encoding::Cl100kBase => "cl100k_base",
+        Encoding::P50kBase => "p50k_base",
+        Encoding::R50kBase | Encoding::Gpt2 => "r50k_base",
+        Encoding::P50kEdit => "p50k_edit",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct WordTokenizer;
+
+    impl Tokenizer for WordTokenizer {
+        fn encoding(&self) -> &'static str {
+            "words"
+        }
+
+        fn count_tokens(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    #[test]
+    fn test_models_resolve_to_encodings() {
+        let service = TokenizerService::new();
+
+        assert_eq!(
+            service.for_model("gpt-4o").unwrap().encoding(),
+            "o200k_base"
+        );
+        assert_eq!(
+            service.for_model("gpt-4").unwrap().encoding(),
+            "cl100k_base"
+        );
+        assert_eq!(
+            service
+                .for_model("Meta-Llama-3.3-70B-Instruct")
+                .unwrap()
+                .encoding(),
+            DEFAULT_ENCODING
+        );
+        assert_eq!(service.count_tokens("gpt-4", "hello world").unwrap(), 2);
+        assert_eq!(service.count_tokens("gpt-4", "").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_tokenizers_are_cached_and_shared() {
+        let service = TokenizerService::new();
+
+        let first = service.for_model("gpt-4").unwrap();
+        let again = service.for_model("gpt-4").unwrap();
+        let same_encoding = service.for_model("gpt-3.5-turbo").unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+        assert!(Arc::ptr_eq(&first, &same_encoding));
+        assert_eq!(service.encodings.read().unwrap().len(), 1);
+
+        for i in 0..MAX_CACHED_MODELS + 10 {
+            service.for_model(&format!("custom-{i}")).unwrap();
+        }
+        assert_eq!(service.models.read().unwrap().len(), MAX_CACHED_MODELS);
+    }
+
+    #[test]
+    fn test_registered_tokenizer_wins() {
+        let service = TokenizerService::new();
+        service.register("gpt-4", Arc::new(WordTokenizer));
+
+        let tokenizer = service.for_model("gpt-4").unwrap();
+        assert_eq!(tokenizer.encoding(), "words");
+        assert_eq!(tokenizer.count_tokens("one two three"), 3);
+    }
+}
@@ -0,0 +1,284 @@
+//! Account creation, deletion and base role changes by admins
+//!
+//! - [`create_user`] adds an account with a random temporary password, which
+//!   must be changed at first login (like a required password change)
+//! - [`delete_user`] removes an account for good: chat sessions, tokens,
+//!   verifications and every other row referencing the user are deleted by
+//!   the foreign keys (`ON DELETE CASCADE`). Audit and access log entries
+//!   keep the bare id.
+//! - [`set_base_role`] promotes a user to admin or demotes an admin
+//!
+//! Deleting or demoting the last enabled admin would lock everyone out of the
+//! admin API, so it is refused with [`UserAdminError::LastAdmin`].
+//! The check locks the enabled admins' rows, so two admins demoting each other
+//! at the same time cannot both succeed.
+//!
+//! Only the base admin role counts towards the check. Users holding
+//! `admin:access` through a custom role (see [`crate::services::rbac`]) are
+//! not counted, since such a role may lack `users:write` or `roles:write` and
+//! could not promote anyone back. The check is thus conservative: the last
+//! base admin stays protected even when custom role holders remain.
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, DbErr,
+    EntityTrait, QueryFilter, QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::models::{prelude::Users, sea_orm_active_enums::UserRole, users};
+use crate::services::auth::hash_password;
+use crate::utils::token::{TokenEncoding, TokenGenerator, MIN_TOKEN_BYTES};
+
+/// Temporary passwords of accounts created by an admin (22 characters)
+pub const TEMPORARY_PASSWORD: TokenGenerator =
+    TokenGenerator::new(MIN_TOKEN_BYTES).encoding(TokenEncoding::Base64Url);
+
+/// Errors from managing accounts
+#[derive(Debug, thiserror::Error)]
+pub enum UserAdminError {
+    #[error("Username already taken")]
+    UsernameTaken,
+
+    #[error("Email already registered")]
+    EmailTaken,
+
+    #[error("User {0} not found")]
+    UserNotFound(Uuid),
+
+    #[error("The last enabled admin cannot be deleted or demoted")]
+    LastAdmin,
+
+    #[error(transparent)]
+    Database(#[from] DbErr),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Account to create
+#[derive(Debug, Clone)]
+pub struct NewUser {
+    pub username: String,
+    pub email: String,
+    pub role: UserRole,
+    /// Skip email verification (the admin vouches for the address)
+    pub email_verified: bool,
+}
+
+/// Create an account with a temporary password
+///
+/// Returns the account and its temporary password, which is not stored in
+/// clear and cannot be retrieved again.
+///
+/// # Errors
+///
+/// Returns [`UserAdminError::UsernameTaken`] or
+/// [`UserAdminError::EmailTaken`] for an existing account, or an error on
+/// database failure
+pub async fn create_user(
+    db: &DatabaseConnection,
+    new_user: NewUser,
+) -> Result<(users::Model, String), UserAdminError> {
+    let existing = Users::find()
+        .filter(
+            Condition::any()
+                .add(users::Column::Username.eq(&new_user.username))
+                .add(users::Column::Email.eq(&new_user.email)),
+        )
+        .one(db)
+        .await?;
+    if let Some(existing) = existing {
+        return Err(if existing.username == new_user.username {
+            UserAdminError::UsernameTaken
+        } else {
+            UserAdminError::EmailTaken
+        });
+    }
+
+    let password = TEMPORARY_PASSWORD.generate();
+    let now = Utc::now();
+    let user = users::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        username: Set(new_user.username),
+        email: Set(new_user.email),
+        password_hash: Set(Some(hash_password(&password)?)),
+        role: Set(new_user.role),
+        email_verified: Set(new_user.email_verified),
+        password_rotation_required: Set(true),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok((user, password))
+}
+
+/// Delete an account with all its data
+///
+/// # Errors
+///
+/// Returns [`UserAdminError::UserNotFound`], [`UserAdminError::LastAdmin`],
+/// or an error on database failure
+pub async fn delete_user(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<users::Model, UserAdminError> {
+    let txn = db.begin().await?;
+
+    let user = find_user(&txn, user_id).await?;
+    ensure_other_admin(&txn, &user).await?;
+    Users::delete_by_id(user_id).exec(&txn).await?;
+
+    txn.commit().await?;
+    Ok(user)
+}
+
+/// Change the base role of an account
+///
+/// Returns the account before the change; setting the role it already has
+/// changes nothing.
+///
+/// # Errors
+///
+/// Returns [`UserAdminError::UserNotFound`], [`UserAdminError::LastAdmin`]
+/// when demoting the last enabled admin, or an error on database failure
+pub async fn set_base_role(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    role: UserRole,
+) -> Result<users::Model, UserAdminError> {
+    let txn = db.begin().await?;
+
+    let user = find_user(&txn, user_id).await?;
+    if user.role == role {
+        return Ok(user);
+    }
+    if role != UserRole::Admin {
+        ensure_other_admin(&txn, &user).await?;
+    }
+
+    let mut active_user: users::ActiveModel = user.clone().into();
+    active_user.role = Set(role);
+    active_user.updated_at = Set(Utc::now().into());
+    active_user.update(&txn).await?;
+
+    txn.commit().await?;
+    Ok(user)
+}
+
+async fn find_user(
+    txn: &DatabaseTransaction,
+    user_id: Uuid,
+) -> Result<users::Model, UserAdminError> {
+    Users::find_by_id(user_id)
+        .one(txn)
+        .await?
+        .ok_or(UserAdminError::UserNotFound(user_id))
+}
+
+/// Fail if `user` is the only enabled admin
+async fn ensure_other_admin(
+    txn: &DatabaseTransaction,
+    user: &users::Model,
+) -> Result<(), UserAdminError> {
+    if user.role != UserRole::Admin || user.disabled_at.is_some() {
+        return Ok(());
+    }
+
+    let admins: Vec<Uuid> = Users::find()
+        .select_only()
+        .column(users::Column::Id)
+        .filter(users::Column::Role.eq(UserRole::Admin))
+        .filter(users::Column::DisabledAt.is_null())
+        .lock_exclusive()
+        .into_tuple()
+        .all(txn)
+        .await?;
+    if admins.iter().all(|id| *id == user.id) {
+        return Err(UserAdminError::LastAdmin);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use std::collections::BTreeMap;
+
+    fn user(role: UserRole) -> users::Model {
+        let now = Utc::now();
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password_hash: Some("hash".to_string()),
+            email_verified: true,
+            created_at: now.into(),
+            updated_at: now.into(),
+            role,
+            disabled_at: None,
+            last_login_at: None,
+            tokens_not_before: None,
+            password_changed_at: None,
+            password_rotation_required: false,
+        }
+    }
+
+    fn ids(ids: &[Uuid]) -> Vec<BTreeMap<String, sea_orm::Value>> {
+        ids.iter()
+            .map(|id| BTreeMap::from([("id".to_string(), sea_orm::Value::from(*id))]))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_demoting_last_admin_is_refused() {
+        let admin = user(UserRole::Admin);
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[admin.clone()]])
+            .append_query_results([ids(&[admin.id])])
+            .into_connection();
+
+        let result = set_base_role(&db, admin.id, UserRole::User).await;
+
+        assert!(matches!(result, Err(UserAdminError::LastAdmin)));
+    }
+
+    #[tokio::test]
+    async fn test_deleting_admin_with_another_admin() {
+        let admin = user(UserRole::Admin);
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[admin.clone()]])
+            .append_query_results([ids(&[admin.id, Uuid::new_v4()])])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let deleted = delete_user(&db, admin.id).await.unwrap();
+
+        assert_eq!(deleted.id, admin.id);
+    }
+
+    #[tokio::test]
+    async fn test_create_user_rejects_taken_username() {
+        let existing = user(UserRole::User);
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([[existing.clone()]])
+            .into_connection();
+        let new_user = NewUser {
+            username: existing.username,
+            email: "other@example.com".to_string(),
+            role: UserRole::User,
+            email_verified: false,
+        };
+
+        let result = create_user(&db, new_user).await;
+
+        assert!(matches!(result, Err(UserAdminError::UsernameTaken)));
+    }
+}
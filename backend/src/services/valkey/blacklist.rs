@@ -2,11 +2,14 @@
 //!
 //! This module provides functionality to blacklist JWT access tokens, enabling
 //! immediate token revocation for logout, security incidents, or administrative
-//! actions. Uses Valkey/Redis with automatic TTL expiry.
+//! actions. Uses Valkey/Redis with automatic TTL expiry. [`ValkeyBlacklist`]
+//! is the [`TokenBlacklist`] checked by the auth middleware when Valkey is
+//! connected.
 //!
 //! # Architecture
 //!
-//! - **Key Format**: `blacklist:{token}` stored with value `1`
+//! - **Key Format**: `blacklist:{jti}` stored with value `1`, keyed by the
+//!   token ID claim
 //! - **TTL Management**: Tokens automatically expire when they would naturally expire
 //! - **Fast Lookup**: O(1) Redis GET operation for blacklist checks
 //! - **Memory Efficient**: Expired entries automatically removed by Redis
@@ -23,30 +26,42 @@
 //! - Only blacklist access tokens, not refresh tokens (use database for refresh tokens)
 //! - TTL must match access token expiry to prevent premature removal
 //! - Blacklist checks add small latency to protected endpoints
-//! - Consider connection pooling for high-traffic applications
+//! - Connections come from the pool of [`ValkeyManager`]
 //!
 //! # Examples
 //!
 //! ```no_run
 //! use cobalt_stack_backend::services::valkey::blacklist::{add_to_blacklist, is_blacklisted};
-//! use redis::Client;
+//! use cobalt_stack_backend::services::valkey::ValkeyManager;
+//! use uuid::Uuid;
 //!
-//! # fn example() -> anyhow::Result<()> {
-//! let client = Client::open("redis://127.0.0.1/")?;
-//! let mut conn = client.get_connection()?;
+//! # async fn example() -> anyhow::Result<()> {
+//! let manager = ValkeyManager::new("redis://127.0.0.1/")?;
+//! let mut conn = manager.get_async_connection().await?;
+//! let (revoked, valid) = (Uuid::new_v4(), Uuid::new_v4());
 //!
 //! // Blacklist token for 30 minutes (1800 seconds)
-//! add_to_blacklist(&mut conn, "expired_token_123", 1800)?;
+//! add_to_blacklist(&mut conn, revoked, 1800).await?;
 //!
 //! // Check if token is blacklisted
-//! assert!(is_blacklisted(&mut conn, "expired_token_123")?);
-//! assert!(!is_blacklisted(&mut conn, "valid_token_456")?);
+//! assert!(is_blacklisted(&mut conn, revoked).await?);
+//! assert!(!is_blacklisted(&mut conn, valid).await?);
 //! # Ok(())
 //! # }
 //! ```
 
 use anyhow::Result;
-use redis::{Commands, Connection};
+use async_trait::async_trait;
+use chrono::Utc;
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use super::{AsyncConnection, ValkeyManager};
+use crate::services::auth::blacklist::TokenBlacklist;
+
+fn blacklist_key(jti: Uuid) -> String {
+    format!("blacklist:{jti}")
+}
 
 /// Add a JWT access token to the blacklist with automatic expiry.
 ///
@@ -56,8 +71,8 @@ use redis::{Commands, Connection};
 ///
 /// # Arguments
 ///
-/// * `conn` - Active Valkey/Redis connection
-/// * `token` - JWT access token to blacklist (full token string)
+/// * `conn` - Pooled Valkey/Redis connection
+/// * `jti` - Token ID (`jti` claim) of the access token to blacklist
 /// * `ttl` - Time to live in seconds (should match token's exp - now)
 ///
 /// # Returns
@@ -69,15 +84,16 @@ use redis::{Commands, Connection};
 ///
 /// ```no_run
 /// use cobalt_stack_backend::services::valkey::blacklist::add_to_blacklist;
-/// use redis::Client;
+/// use cobalt_stack_backend::services::valkey::ValkeyManager;
+/// use uuid::Uuid;
 ///
-/// # fn example() -> anyhow::Result<()> {
-/// let client = Client::open("redis://127.0.0.1/")?;
-/// let mut conn = client.get_connection()?;
+/// # async fn example() -> anyhow::Result<()> {
+/// let manager = ValkeyManager::new("redis://127.0.0.1/")?;
+/// let mut conn = manager.get_async_connection().await?;
 ///
 /// // Blacklist token that expires in 30 minutes
-/// let token = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...";
-/// add_to_blacklist(&mut conn, token, 1800)?;
+/// let jti = Uuid::new_v4();
+/// add_to_blacklist(&mut conn, jti, 1800).await?;
 /// # Ok(())
 /// # }
 /// ```
@@ -88,10 +104,10 @@ use redis::{Commands, Connection};
 /// - Setting TTL too short allows token to work after removal from blacklist
 /// - Setting TTL too long wastes Redis memory unnecessarily
 /// - Use this for access tokens only (refresh tokens use database revocation)
-pub fn add_to_blacklist(conn: &mut Connection, token: &str, ttl: i64) -> Result<()> {
-    let key = format!("blacklist:{token}");
+pub async fn add_to_blacklist(conn: &mut AsyncConnection, jti: Uuid, ttl: i64) -> Result<()> {
     #[allow(clippy::cast_sign_loss)]
-    conn.set_ex::<_, _, ()>(&key, 1, ttl as u64)?;
+    conn.set_ex::<_, _, ()>(blacklist_key(jti), 1, ttl as u64)
+        .await?;
     Ok(())
 }
 
@@ -103,8 +119,8 @@ pub fn add_to_blacklist(conn: &mut Connection, token: &str, ttl: i64) -> Result<
 ///
 /// # Arguments
 ///
-/// * `conn` - Active Valkey/Redis connection
-/// * `token` - JWT access token to check (full token string)
+/// * `conn` - Pooled Valkey/Redis connection
+/// * `jti` - Token ID (`jti` claim) of the access token to check
 ///
 /// # Returns
 ///
@@ -116,20 +132,21 @@ pub fn add_to_blacklist(conn: &mut Connection, token: &str, ttl: i64) -> Result<
 ///
 /// ```no_run
 /// use cobalt_stack_backend::services::valkey::blacklist::{add_to_blacklist, is_blacklisted};
-/// use redis::Client;
+/// use cobalt_stack_backend::services::valkey::ValkeyManager;
+/// use uuid::Uuid;
 ///
-/// # fn example() -> anyhow::Result<()> {
-/// let client = Client::open("redis://127.0.0.1/")?;
-/// let mut conn = client.get_connection()?;
+/// # async fn example() -> anyhow::Result<()> {
+/// let manager = ValkeyManager::new("redis://127.0.0.1/")?;
+/// let mut conn = manager.get_async_connection().await?;
 ///
-/// let token = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...";
+/// let jti = Uuid::new_v4();
 ///
 /// // Initially not blacklisted
-/// assert!(!is_blacklisted(&mut conn, token)?);
+/// assert!(!is_blacklisted(&mut conn, jti).await?);
 ///
 /// // After blacklisting
-/// add_to_blacklist(&mut conn, token, 1800)?;
-/// assert!(is_blacklisted(&mut conn, token)?);
+/// add_to_blacklist(&mut conn, jti, 1800).await?;
+/// assert!(is_blacklisted(&mut conn, jti).await?);
 /// # Ok(())
 /// # }
 /// ```
@@ -138,7 +155,7 @@ pub fn add_to_blacklist(conn: &mut Connection, token: &str, ttl: i64) -> Result<
 ///
 /// - O(1) time complexity (Redis EXISTS command)
 /// - Typical latency: <1ms on local Redis
-/// - Consider connection pooling for concurrent requests
+/// - Does not block the runtime while waiting for Valkey
 ///
 /// # Error Handling
 ///
@@ -146,14 +163,47 @@ pub fn add_to_blacklist(conn: &mut Connection, token: &str, ttl: i64) -> Result<
 /// security requirements, you may want to:
 /// - Fail secure: reject all requests if blacklist check fails
 /// - Fail open: allow requests if blacklist check fails (risky)
-pub fn is_blacklisted(conn: &mut Connection, token: &str) -> Result<bool> {
-    let key = format!("blacklist:{token}");
-    let exists: bool = conn.exists(&key)?;
+pub async fn is_blacklisted(conn: &mut AsyncConnection, jti: Uuid) -> Result<bool> {
+    let exists: bool = conn.exists(blacklist_key(jti)).await?;
     Ok(exists)
 }
 
+/// [`TokenBlacklist`] on Valkey, shared by all instances
+///
+/// Valkey errors fail the check, so revoked tokens are never let through
+/// while Valkey is unreachable.
+#[derive(Clone)]
+pub struct ValkeyBlacklist {
+    valkey: ValkeyManager,
+}
+
+impl ValkeyBlacklist {
+    #[must_use]
+    pub const fn new(valkey: ValkeyManager) -> Self {
+        Self { valkey }
+    }
+}
+
+#[async_trait]
+impl TokenBlacklist for ValkeyBlacklist {
+    async fn add(&self, jti: Uuid, expires_at: i64) -> Result<()> {
+        let ttl = expires_at - Utc::now().timestamp();
+        if ttl <= 0 {
+            return Ok(());
+        }
+        let mut conn = self.valkey.get_async_connection().await?;
+        add_to_blacklist(&mut conn, jti, ttl).await
+    }
+
+    async fn contains(&self, jti: Uuid) -> Result<bool> {
+        let mut conn = self.valkey.get_async_connection().await?;
+        is_blacklisted(&mut conn, jti).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     // Note: These are integration tests requiring actual Valkey instance
     // Run with: docker-compose up -d valkey
@@ -162,19 +212,16 @@ mod tests {
     #[test]
     fn test_blacklist_key_format() {
         // Unit test: verify key format without Valkey connection
-        let token = "test_token_123";
-        let expected_key = "blacklist:test_token_123";
-        let key = format!("blacklist:{token}");
-        assert_eq!(key, expected_key);
+        let jti = Uuid::nil();
+        let expected_key = "blacklist:00000000-0000-0000-0000-000000000000";
+        assert_eq!(blacklist_key(jti), expected_key);
     }
 
     #[test]
     fn test_blacklist_key_uniqueness() {
         // Unit test: verify different tokens create different keys
-        let token1 = "token_abc";
-        let token2 = "token_xyz";
-        let key1 = format!("blacklist:{token1}");
-        let key2 = format!("blacklist:{token2}");
+        let key1 = blacklist_key(Uuid::new_v4());
+        let key2 = blacklist_key(Uuid::new_v4());
         assert_ne!(key1, key2);
     }
 
@@ -15,11 +15,15 @@
 //! Rate limits are loaded from environment variables via `ChatConfig`:
 //! - `CHAT_RATE_LIMIT_PER_MINUTE` - Messages per minute (default: 20)
 //! - `CHAT_DAILY_MESSAGE_QUOTA` - Messages per day (default: 100)
+//! - `CHAT_QUOTA_WARNING_THRESHOLDS` - Daily quota percentages that trigger a
+//!   soft warning (default: 80)
 
 use anyhow::Result;
-use redis::{Commands, Connection};
+use redis::AsyncCommands;
 use uuid::Uuid;
 
+use super::AsyncConnection;
+
 /// Rate limit check result with detailed information
 #[derive(Debug, Clone)]
 pub struct RateLimitResult {
@@ -62,6 +66,8 @@ pub struct ChatRateLimitConfig {
     pub rate_limit_per_minute: u64,
     /// Messages allowed per day
     pub daily_message_quota: u64,
+    /// Percentages of the daily quota at which users are warned
+    pub quota_warning_thresholds: Vec<u8>,
 }
 
 impl Default for ChatRateLimitConfig {
@@ -69,6 +75,7 @@ impl Default for ChatRateLimitConfig {
         Self {
             rate_limit_per_minute: 20,
             daily_message_quota: 100,
+            quota_warning_thresholds: vec![80],
         }
     }
 }
@@ -80,32 +87,32 @@ impl Default for ChatRateLimitConfig {
 ///
 /// # Arguments
 ///
-/// * `conn` - Active Redis connection
+/// * `conn` - Pooled Redis connection
 /// * `user_id` - User UUID to rate limit
 /// * `config` - Rate limit configuration
 ///
 /// # Returns
 ///
 /// `RateLimitResult` with details about the check
-pub fn check_chat_rate_limit(
-    conn: &mut Connection,
+pub async fn check_chat_rate_limit(
+    conn: &mut AsyncConnection,
     user_id: Uuid,
     config: &ChatRateLimitConfig,
 ) -> Result<RateLimitResult> {
     // Check per-minute rate limit first (fast fail)
-    let minute_result = check_per_minute_limit(conn, user_id, config.rate_limit_per_minute)?;
+    let minute_result = check_per_minute_limit(conn, user_id, config.rate_limit_per_minute).await?;
     if minute_result.exceeded {
         return Ok(minute_result);
     }
 
     // Check daily quota
-    let daily_result = check_daily_quota(conn, user_id, config.daily_message_quota)?;
+    let daily_result = check_daily_quota(conn, user_id, config.daily_message_quota).await?;
     if daily_result.exceeded {
         return Ok(daily_result);
     }
 
     // Both checks passed - increment counters
-    increment_chat_counters(conn, user_id)?;
+    increment_chat_counters(conn, user_id).await?;
 
     Ok(RateLimitResult {
         exceeded: false,
@@ -117,18 +124,18 @@ pub fn check_chat_rate_limit(
 }
 
 /// Check per-minute rate limit without incrementing
-fn check_per_minute_limit(
-    conn: &mut Connection,
+async fn check_per_minute_limit(
+    conn: &mut AsyncConnection,
     user_id: Uuid,
     limit: u64,
 ) -> Result<RateLimitResult> {
     let key = format!("ratelimit:chat:user:{}:minute", user_id);
-    let count: Option<u64> = conn.get(&key)?;
+    let count: Option<u64> = conn.get(&key).await?;
     let current = count.unwrap_or(0);
 
     if current >= limit {
         // Get TTL for retry_after
-        let ttl: i64 = conn.ttl(&key)?;
+        let ttl: i64 = conn.ttl(&key).await?;
         Ok(RateLimitResult {
             exceeded: true,
             limit_type: Some(LimitType::PerMinute),
@@ -148,18 +155,18 @@ fn check_per_minute_limit(
 }
 
 /// Check daily quota without incrementing
-fn check_daily_quota(
-    conn: &mut Connection,
+async fn check_daily_quota(
+    conn: &mut AsyncConnection,
     user_id: Uuid,
     limit: u64,
 ) -> Result<RateLimitResult> {
     let key = format!("quota:chat:user:{}:daily", user_id);
-    let count: Option<u64> = conn.get(&key)?;
+    let count: Option<u64> = conn.get(&key).await?;
     let current = count.unwrap_or(0);
 
     if current >= limit {
         // Get TTL for retry_after
-        let ttl: i64 = conn.ttl(&key)?;
+        let ttl: i64 = conn.ttl(&key).await?;
         Ok(RateLimitResult {
             exceeded: true,
             limit_type: Some(LimitType::Daily),
@@ -179,26 +186,26 @@ fn check_daily_quota(
 }
 
 /// Increment both rate limit counters
-fn increment_chat_counters(conn: &mut Connection, user_id: Uuid) -> Result<()> {
+async fn increment_chat_counters(conn: &mut AsyncConnection, user_id: Uuid) -> Result<()> {
     let minute_key = format!("ratelimit:chat:user:{}:minute", user_id);
     let daily_key = format!("quota:chat:user:{}:daily", user_id);
 
     // Increment per-minute counter
-    let minute_count: Option<u64> = conn.get(&minute_key)?;
+    let minute_count: Option<u64> = conn.get(&minute_key).await?;
     if minute_count.is_none() {
         // First message in this minute - set with TTL
-        conn.set_ex::<_, _, ()>(&minute_key, 1, 60)?;
+        conn.set_ex::<_, _, ()>(&minute_key, 1, 60).await?;
     } else {
-        conn.incr::<_, _, ()>(&minute_key, 1)?;
+        conn.incr::<_, _, ()>(&minute_key, 1).await?;
     }
 
     // Increment daily counter
-    let daily_count: Option<u64> = conn.get(&daily_key)?;
+    let daily_count: Option<u64> = conn.get(&daily_key).await?;
     if daily_count.is_none() {
         // First message today - set with TTL (24 hours)
-        conn.set_ex::<_, _, ()>(&daily_key, 1, 86400)?;
+        conn.set_ex::<_, _, ()>(&daily_key, 1, 86400).await?;
     } else {
-        conn.incr::<_, _, ()>(&daily_key, 1)?;
+        conn.incr::<_, _, ()>(&daily_key, 1).await?;
     }
 
     Ok(())
@@ -209,23 +216,67 @@ fn increment_chat_counters(conn: &mut Connection, user_id: Uuid) -> Result<()> {
 /// # Returns
 ///
 /// Tuple of (per_minute_count, daily_count)
-pub fn get_chat_usage(conn: &mut Connection, user_id: Uuid) -> Result<(u64, u64)> {
+pub async fn get_chat_usage(conn: &mut AsyncConnection, user_id: Uuid) -> Result<(u64, u64)> {
     let minute_key = format!("ratelimit:chat:user:{}:minute", user_id);
     let daily_key = format!("quota:chat:user:{}:daily", user_id);
 
-    let minute_count: Option<u64> = conn.get(&minute_key)?;
-    let daily_count: Option<u64> = conn.get(&daily_key)?;
+    let minute_count: Option<u64> = conn.get(&minute_key).await?;
+    let daily_count: Option<u64> = conn.get(&daily_key).await?;
 
     Ok((minute_count.unwrap_or(0), daily_count.unwrap_or(0)))
 }
 
+/// Highest warning threshold (percent of `limit`) reached by `used`
+///
+/// Returns `None` if no threshold has been reached or the quota is zero.
+#[must_use]
+pub fn quota_warning_threshold(used: u64, limit: u64, thresholds: &[u8]) -> Option<u8> {
+    if limit == 0 {
+        return None;
+    }
+    thresholds
+        .iter()
+        .copied()
+        .filter(|&threshold| used.saturating_mul(100) >= limit.saturating_mul(u64::from(threshold)))
+        .max()
+}
+
+/// Record that a user was warned at `threshold` in the current daily window
+///
+/// The marker expires together with the daily quota counter, so each
+/// threshold warns at most once per window.
+///
+/// # Returns
+///
+/// `true` the first time the threshold is recorded in this window
+pub async fn mark_quota_warning(
+    conn: &mut AsyncConnection,
+    user_id: Uuid,
+    threshold: u8,
+) -> Result<bool> {
+    let daily_key = format!("quota:chat:user:{user_id}:daily");
+    let warned_key = format!("quota:chat:user:{user_id}:warned:{threshold}");
+
+    let ttl: i64 = conn.ttl(&daily_key).await?;
+    let created: Option<String> = redis::cmd("SET")
+        .arg(&warned_key)
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(if ttl > 0 { ttl } else { 86400 })
+        .query_async(conn)
+        .await?;
+
+    Ok(created.is_some())
+}
+
 /// Reset rate limits for a user (admin function)
-pub fn reset_chat_rate_limit(conn: &mut Connection, user_id: Uuid) -> Result<()> {
+pub async fn reset_chat_rate_limit(conn: &mut AsyncConnection, user_id: Uuid) -> Result<()> {
     let minute_key = format!("ratelimit:chat:user:{}:minute", user_id);
     let daily_key = format!("quota:chat:user:{}:daily", user_id);
 
-    conn.del::<_, ()>(&minute_key)?;
-    conn.del::<_, ()>(&daily_key)?;
+    conn.del::<_, ()>(&minute_key).await?;
+    conn.del::<_, ()>(&daily_key).await?;
 
     Ok(())
 }
@@ -239,6 +290,19 @@ mod tests {
         let config = ChatRateLimitConfig::default();
         assert_eq!(config.rate_limit_per_minute, 20);
         assert_eq!(config.daily_message_quota, 100);
+        assert_eq!(config.quota_warning_thresholds, vec![80]);
+    }
+
+    #[test]
+    fn test_quota_warning_threshold() {
+        let thresholds = [80, 95];
+
+        assert_eq!(quota_warning_threshold(79, 100, &thresholds), None);
+        assert_eq!(quota_warning_threshold(80, 100, &thresholds), Some(80));
+        assert_eq!(quota_warning_threshold(96, 100, &thresholds), Some(95));
+        assert_eq!(quota_warning_threshold(4, 5, &thresholds), Some(80));
+        assert_eq!(quota_warning_threshold(10, 0, &thresholds), None);
+        assert_eq!(quota_warning_threshold(100, 100, &[]), None);
     }
 
     #[test]
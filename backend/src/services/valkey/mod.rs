@@ -9,11 +9,19 @@
 //! - **blacklist**: JWT access token revocation via blacklist
 //! - **`rate_limit`**: Login attempt rate limiting by IP address
 //! - **`chat_rate_limit`**: Chat message rate limiting and daily quotas
+//! - **notifications**: Per-user notification inbox (e.g. quota warnings)
+//! - **`provider_health`**: Latest synthetic probe result per LLM provider
+//! - **`token_store`**: Refresh token store for `REFRESH_TOKEN_STORE=valkey`
+//! - **`user_cache`**: Short-lived copies of user rows read by authenticated requests
 //!
 //! # Connection Management
 //!
-//! [`ValkeyManager`] provides connection pooling and management for Valkey operations.
-//! In production, consider using a connection pool like `r2d2` or `bb8-redis`.
+//! [`ValkeyManager`] hands out async connections from a `deadpool-redis`
+//! pool ([`ValkeyManager::get_async_connection`]), sized by
+//! `VALKEY_POOL_MAX_SIZE`. The token blacklist, the login and chat rate
+//! limits, the notification inbox and the user cache use it.
+//! [`ValkeyManager::get_connection`] still opens a blocking connection per
+//! call for the other services.
 //!
 //! # Configuration
 //!
@@ -27,9 +35,9 @@
 //! ```no_run
 //! use cobalt_stack_backend::services::valkey::ValkeyManager;
 //!
-//! # fn example() -> anyhow::Result<()> {
+//! # async fn example() -> anyhow::Result<()> {
 //! let manager = ValkeyManager::new("redis://127.0.0.1:6379")?;
-//! let mut conn = manager.get_connection()?;
+//! let mut conn = manager.get_async_connection().await?;
 //!
 //! // Use connection for blacklist or rate limit operations
 //! # Ok(())
@@ -45,39 +53,96 @@
 
 pub mod blacklist;
 pub mod chat_rate_limit;
+pub mod notifications;
+pub mod provider_health;
 pub mod rate_limit;
+pub mod token_store;
+pub mod user_cache;
 
-use redis::Client;
+use deadpool_redis::{Config, Pool, PoolConfig, Runtime};
+use redis::{aio::ConnectionLike, Client, Cmd, Pipeline, RedisFuture, Value};
 use std::sync::Arc;
 
+use crate::infrastructure::fault_injection::{Fault, FaultInjector};
+use crate::services::request_timing::{self, Dependency};
+
+/// Pooled async connection, returned to the pool when dropped
+///
+/// Commands are timed for the latency breakdown of the request sending them
+/// (see [`crate::services::request_timing`]).
+pub struct AsyncConnection(deadpool_redis::Connection);
+
+impl ConnectionLike for AsyncConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(request_timing::timed(
+            Dependency::Valkey,
+            self.0.req_packed_command(cmd),
+        ))
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(request_timing::timed(
+            Dependency::Valkey,
+            self.0.req_packed_commands(cmd, offset, count),
+        ))
+    }
+
+    fn get_db(&self) -> i64 {
+        self.0.get_db()
+    }
+}
+
+/// Pool size when `VALKEY_POOL_MAX_SIZE` is not set
+pub const DEFAULT_POOL_MAX_SIZE: usize = 16;
+
+/// Pool size from `VALKEY_POOL_MAX_SIZE`, [`DEFAULT_POOL_MAX_SIZE`] if unset
+/// or not a positive number
+#[must_use]
+pub fn pool_size_from_env() -> usize {
+    std::env::var("VALKEY_POOL_MAX_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&size| size > 0)
+        .unwrap_or(DEFAULT_POOL_MAX_SIZE)
+}
+
 /// Connection manager for Valkey/Redis operations.
 ///
-/// Provides connection creation and management for Valkey services.
-/// For production use, consider using a connection pool library like
-/// `r2d2-redis` or `bb8-redis` for better performance and resource management.
+/// Holds a pool of async connections shared by every clone, and the client
+/// used for the remaining blocking connections.
 ///
 /// # Examples
 ///
 /// ```no_run
 /// use cobalt_stack_backend::services::valkey::ValkeyManager;
 ///
-/// # fn example() -> anyhow::Result<()> {
+/// # async fn example() -> anyhow::Result<()> {
 /// // Create manager from environment variable
 /// let url = std::env::var("VALKEY_URL").unwrap_or("redis://127.0.0.1:6379".to_string());
 /// let manager = ValkeyManager::new(&url)?;
 ///
-/// // Get connection for operations
-/// let mut conn = manager.get_connection()?;
+/// // Borrow a pooled connection for operations
+/// let mut conn = manager.get_async_connection().await?;
 /// # Ok(())
 /// # }
 /// ```
 #[derive(Clone)]
 pub struct ValkeyManager {
     client: Arc<Client>,
+    pool: Pool,
+    faults: Option<Arc<FaultInjector>>,
 }
 
 impl ValkeyManager {
-    /// Create a new Valkey connection manager.
+    /// Create a new Valkey connection manager with a pool of
+    /// [`DEFAULT_POOL_MAX_SIZE`] connections.
+    ///
+    /// No connection is opened until one is requested.
     ///
     /// # Arguments
     ///
@@ -106,20 +171,52 @@ impl ValkeyManager {
     /// # }
     /// ```
     pub fn new(url: &str) -> anyhow::Result<Self> {
+        Self::with_pool_size(url, DEFAULT_POOL_MAX_SIZE)
+    }
+
+    /// Create a manager whose pool holds at most `max_size` connections
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an invalid URL
+    pub fn with_pool_size(url: &str, max_size: usize) -> anyhow::Result<Self> {
         let client = Client::open(url)?;
+        let mut config = Config::from_url(url);
+        config.pool = Some(PoolConfig::new(max_size));
+        let pool = config.create_pool(Some(Runtime::Tokio1))?;
         Ok(Self {
             client: Arc::new(client),
+            pool,
+            faults: None,
         })
     }
 
-    /// Get a connection to Valkey/Redis.
+    /// Refuse a share of connections, as configured in `faults`
+    #[must_use]
+    pub fn with_faults(mut self, faults: Arc<FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    fn inject_fault(&self) -> anyhow::Result<()> {
+        if self
+            .faults
+            .as_ref()
+            .is_some_and(|faults| faults.inject(Fault::ValkeyUnavailable))
+        {
+            anyhow::bail!("Connection refused (injected fault)");
+        }
+        Ok(())
+    }
+
+    /// Borrow an async connection from the pool.
     ///
-    /// Creates a new connection from the client. For production applications,
-    /// consider using a connection pool to avoid connection overhead.
+    /// Waits for a free connection once the pool is exhausted, and opens a
+    /// new one when a pooled connection was closed.
     ///
     /// # Returns
     ///
-    /// - `Ok(Connection)` - Active Redis connection
+    /// - `Ok(AsyncConnection)` - Pooled connection, returned on drop
     /// - `Err(_)` - Connection failed (network, authentication, etc.)
     ///
     /// # Examples
@@ -128,16 +225,47 @@ impl ValkeyManager {
     /// use cobalt_stack_backend::services::valkey::ValkeyManager;
     /// use cobalt_stack_backend::services::valkey::blacklist;
     ///
-    /// # fn example() -> anyhow::Result<()> {
+    /// # async fn example() -> anyhow::Result<()> {
     /// let manager = ValkeyManager::new("redis://127.0.0.1:6379")?;
-    /// let mut conn = manager.get_connection()?;
+    /// let mut conn = manager.get_async_connection().await?;
     ///
     /// // Use connection for operations
-    /// blacklist::add_to_blacklist(&mut conn, "token123", 1800)?;
+    /// blacklist::add_to_blacklist(&mut conn, uuid::Uuid::new_v4(), 1800).await?;
     /// # Ok(())
     /// # }
     /// ```
+    pub async fn get_async_connection(&self) -> anyhow::Result<AsyncConnection> {
+        self.inject_fault()?;
+        Ok(AsyncConnection(self.pool.get().await?))
+    }
+
+    /// Open a blocking connection to Valkey/Redis.
+    ///
+    /// Creates a new connection from the client and blocks the calling
+    /// thread on every command. Prefer [`get_async_connection`] on request
+    /// paths.
+    ///
+    /// [`get_async_connection`]: Self::get_async_connection
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Connection)` - Active Redis connection
+    /// - `Err(_)` - Connection failed (network, authentication, etc.)
     pub fn get_connection(&self) -> anyhow::Result<redis::Connection> {
+        self.inject_fault()?;
         Ok(self.client.get_connection()?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pool_connects_on_demand() {
+        // Nothing listens on port 1, so only borrowing a connection fails
+        let manager = ValkeyManager::new("redis://127.0.0.1:1").unwrap();
+
+        assert!(manager.get_async_connection().await.is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Per-user notification inbox.
+//!
+//! Notifications are short messages the server wants a user to see outside
+//! the request that produced them (e.g. "80% of your daily chat quota used").
+//!
+//! # Architecture
+//!
+//! - **Key Format**: `notifications:user:{user_id}` holding a JSON list, newest first
+//! - **Retention**: At most [`MAX_NOTIFICATIONS`] entries, expired after [`RETENTION_SECS`]
+//!   without new notifications
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::AsyncConnection;
+
+/// Maximum notifications kept per user
+pub const MAX_NOTIFICATIONS: isize = 50;
+
+/// Seconds an inbox is kept after the last notification (7 days)
+pub const RETENTION_SECS: i64 = 7 * 86400;
+
+/// A notification stored in a user's inbox
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: Uuid,
+    /// Machine-readable category, e.g. `quota_warning`
+    pub kind: String,
+    /// Human-readable text
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    /// Create a notification stamped with the current time
+    #[must_use]
+    pub fn new(kind: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind: kind.into(),
+            message: message.into(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+fn inbox_key(user_id: Uuid) -> String {
+    format!("notifications:user:{user_id}")
+}
+
+/// Add a notification to the front of a user's inbox
+///
+/// # Errors
+///
+/// Returns an error on Redis connection or serialization failure
+pub async fn push_notification(
+    conn: &mut AsyncConnection,
+    user_id: Uuid,
+    notification: &Notification,
+) -> Result<()> {
+    let key = inbox_key(user_id);
+    let payload = serde_json::to_string(notification)?;
+
+    conn.lpush::<_, _, ()>(&key, payload).await?;
+    conn.ltrim::<_, ()>(&key, 0, MAX_NOTIFICATIONS - 1).await?;
+    conn.expire::<_, ()>(&key, RETENTION_SECS).await?;
+
+    Ok(())
+}
+
+/// List a user's notifications, newest first
+///
+/// Entries that fail to deserialize are skipped.
+///
+/// # Errors
+///
+/// Returns an error on Redis connection failure
+pub async fn list_notifications(
+    conn: &mut AsyncConnection,
+    user_id: Uuid,
+) -> Result<Vec<Notification>> {
+    let entries: Vec<String> = conn
+        .lrange(inbox_key(user_id), 0, MAX_NOTIFICATIONS - 1)
+        .await?;
+
+    Ok(entries
+        .iter()
+        .filter_map(|entry| serde_json::from_str(entry).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inbox_key_format() {
+        let user_id = Uuid::new_v4();
+        assert_eq!(inbox_key(user_id), format!("notifications:user:{user_id}"));
+    }
+
+    #[test]
+    fn test_notification_round_trip() {
+        let notification = Notification::new("quota_warning", "80% used");
+
+        let json = serde_json::to_string(&notification).unwrap();
+        let parsed: Notification = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, notification);
+        assert_eq!(parsed.kind, "quota_warning");
+    }
+}
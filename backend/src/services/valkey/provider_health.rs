@@ -0,0 +1,122 @@
+//! Latest synthetic probe result per LLM provider.
+//!
+//! Results are written by the scheduled provider probe and read by the
+//! readiness endpoint, so every instance sees the same provider health.
+//!
+//! # Architecture
+//!
+//! - **Key Format**: `llm:probe:{provider}` holding the latest result as JSON
+//! - **Expiry**: Set by the writer (a few probe intervals) so results of a
+//!   stopped prober disappear instead of going stale
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use redis::{Commands, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of one synthetic completion against a provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderProbe {
+    /// Provider key from the model registry (e.g. `sambanova`)
+    pub provider: String,
+    /// Registry model ID used for the probe
+    pub model: String,
+    /// Whether the completion succeeded within the timeout
+    pub healthy: bool,
+    /// Time until the completion finished or failed
+    pub latency_ms: u64,
+    /// Failure reason, if any
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+fn probe_key(provider: &str) -> String {
+    format!("llm:probe:{provider}")
+}
+
+/// Store the latest probe result of a provider
+///
+/// # Errors
+///
+/// Returns an error on Redis connection or serialization failure
+pub fn record_probe(conn: &mut Connection, probe: &ProviderProbe, ttl_secs: u64) -> Result<()> {
+    let payload = serde_json::to_string(probe)?;
+    conn.set_ex::<_, _, ()>(probe_key(&probe.provider), payload, ttl_secs)?;
+    Ok(())
+}
+
+/// Load the latest probe result of each provider that has one
+///
+/// Entries that fail to deserialize are skipped.
+///
+/// # Errors
+///
+/// Returns an error on Redis connection failure
+pub fn latest_probes(conn: &mut Connection, providers: &[String]) -> Result<Vec<ProviderProbe>> {
+    if providers.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let keys: Vec<String> = providers.iter().map(|p| probe_key(p)).collect();
+    let entries: Vec<Option<String>> = conn.mget(keys)?;
+
+    Ok(entries
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| serde_json::from_str(&entry).ok())
+        .collect())
+}
+
+/// Check whether chat can serve requests according to the probes
+///
+/// Chat is available while at least one provider passes its probe. Without
+/// any result yet (first probe pending or results expired) nothing is known
+/// to be broken, so this returns `true`.
+#[must_use]
+pub fn any_provider_healthy(probes: &[ProviderProbe]) -> bool {
+    probes.is_empty() || probes.iter().any(|probe| probe.healthy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(provider: &str, healthy: bool) -> ProviderProbe {
+        ProviderProbe {
+            provider: provider.to_string(),
+            model: "model".to_string(),
+            healthy,
+            latency_ms: 120,
+            error: (!healthy).then(|| "timeout".to_string()),
+            checked_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_probe_key() {
+        assert_eq!(probe_key("sambanova"), "llm:probe:sambanova");
+    }
+
+    #[test]
+    fn test_any_provider_healthy() {
+        assert!(any_provider_healthy(&[]));
+        assert!(any_provider_healthy(&[
+            probe("sambanova", false),
+            probe("azure", true)
+        ]));
+        assert!(!any_provider_healthy(&[
+            probe("sambanova", false),
+            probe("azure", false)
+        ]));
+    }
+
+    #[test]
+    fn test_probe_round_trips_through_json() {
+        let original = probe("azure", false);
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ProviderProbe>(&json).unwrap(),
+            original
+        );
+    }
+}
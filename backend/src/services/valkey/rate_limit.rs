@@ -30,17 +30,17 @@
 //! use cobalt_stack_backend::services::valkey::rate_limit::{
 //!     check_rate_limit, reset_rate_limit, RateLimitConfig
 //! };
-//! use redis::Client;
+//! use cobalt_stack_backend::services::valkey::ValkeyManager;
 //!
-//! # fn example() -> anyhow::Result<()> {
-//! let client = Client::open("redis://127.0.0.1/")?;
-//! let mut conn = client.get_connection()?;
+//! # async fn example() -> anyhow::Result<()> {
+//! let manager = ValkeyManager::new("redis://127.0.0.1/")?;
+//! let mut conn = manager.get_async_connection().await?;
 //! let config = RateLimitConfig::default();
 //!
 //! let ip = "192.168.1.100";
 //!
 //! // Check rate limit before processing login
-//! if check_rate_limit(&mut conn, ip, &config)? {
+//! if check_rate_limit(&mut conn, ip, &config).await? {
 //!     // Rate limit exceeded - reject request
 //!     return Err(anyhow::anyhow!("Too many login attempts"));
 //! }
@@ -48,13 +48,15 @@
 //! // Process login attempt...
 //!
 //! // On successful login, optionally reset counter
-//! reset_rate_limit(&mut conn, ip)?;
+//! reset_rate_limit(&mut conn, ip).await?;
 //! # Ok(())
 //! # }
 //! ```
 
 use anyhow::Result;
-use redis::{Commands, Connection};
+use redis::AsyncCommands;
+
+use super::AsyncConnection;
 
 /// Configuration for rate limiting behavior.
 ///
@@ -102,7 +104,7 @@ impl Default for RateLimitConfig {
 ///
 /// # Arguments
 ///
-/// * `conn` - Active Valkey/Redis connection
+/// * `conn` - Pooled Valkey/Redis connection
 /// * `ip` - IP address to rate limit (typically from request)
 /// * `config` - Rate limit configuration (attempts and window)
 ///
@@ -123,17 +125,17 @@ impl Default for RateLimitConfig {
 ///
 /// ```no_run
 /// use cobalt_stack_backend::services::valkey::rate_limit::{check_rate_limit, RateLimitConfig};
-/// use redis::Client;
+/// use cobalt_stack_backend::services::valkey::ValkeyManager;
 ///
-/// # fn example() -> anyhow::Result<()> {
-/// let client = Client::open("redis://127.0.0.1/")?;
-/// let mut conn = client.get_connection()?;
+/// # async fn example() -> anyhow::Result<()> {
+/// let manager = ValkeyManager::new("redis://127.0.0.1/")?;
+/// let mut conn = manager.get_async_connection().await?;
 /// let config = RateLimitConfig::default();
 ///
 /// let ip = "203.0.113.42";
 ///
 /// // Check before login attempt
-/// if check_rate_limit(&mut conn, ip, &config)? {
+/// if check_rate_limit(&mut conn, ip, &config).await? {
 ///     // Return 429 Too Many Requests
 ///     println!("Rate limit exceeded for IP: {}", ip);
 /// } else {
@@ -147,14 +149,18 @@ impl Default for RateLimitConfig {
 /// # Security Notes
 ///
 /// - Always call this BEFORE processing the request
-/// - Use `X-Forwarded-For` header carefully (can be spoofed)
-/// - Consider using real client IP from trusted proxy headers
+/// - Key on [`ClientIp`](crate::middleware::client_ip::ClientIp), never on raw
+///   `X-Forwarded-For` (clients can spoof it unless it comes from a trusted proxy)
 /// - Combine with other security measures (CAPTCHA after N failures)
-pub fn check_rate_limit(conn: &mut Connection, ip: &str, config: &RateLimitConfig) -> Result<bool> {
+pub async fn check_rate_limit(
+    conn: &mut AsyncConnection,
+    ip: &str,
+    config: &RateLimitConfig,
+) -> Result<bool> {
     let key = format!("ratelimit:login:{ip}");
 
     // Get current count
-    let count: Option<u32> = conn.get(&key)?;
+    let count: Option<u32> = conn.get(&key).await?;
 
     match count {
         Some(current) if current >= config.max_attempts => {
@@ -163,13 +169,14 @@ pub fn check_rate_limit(conn: &mut Connection, ip: &str, config: &RateLimitConfi
         }
         Some(_current) => {
             // Increment counter
-            conn.incr::<_, _, ()>(&key, 1)?;
+            conn.incr::<_, _, ()>(&key, 1).await?;
             Ok(false)
         }
         None => {
             // First attempt - set counter and TTL
             #[allow(clippy::cast_sign_loss)]
-            conn.set_ex::<_, _, ()>(&key, 1, config.window_seconds as u64)?;
+            conn.set_ex::<_, _, ()>(&key, 1, config.window_seconds as u64)
+                .await?;
             Ok(false)
         }
     }
@@ -183,7 +190,7 @@ pub fn check_rate_limit(conn: &mut Connection, ip: &str, config: &RateLimitConfi
 ///
 /// # Arguments
 ///
-/// * `conn` - Active Valkey/Redis connection
+/// * `conn` - Pooled Valkey/Redis connection
 /// * `ip` - IP address to reset
 ///
 /// # Returns
@@ -195,16 +202,16 @@ pub fn check_rate_limit(conn: &mut Connection, ip: &str, config: &RateLimitConfi
 ///
 /// ```no_run
 /// use cobalt_stack_backend::services::valkey::rate_limit::reset_rate_limit;
-/// use redis::Client;
+/// use cobalt_stack_backend::services::valkey::ValkeyManager;
 ///
-/// # fn example() -> anyhow::Result<()> {
-/// let client = Client::open("redis://127.0.0.1/")?;
-/// let mut conn = client.get_connection()?;
+/// # async fn example() -> anyhow::Result<()> {
+/// let manager = ValkeyManager::new("redis://127.0.0.1/")?;
+/// let mut conn = manager.get_async_connection().await?;
 ///
 /// let ip = "203.0.113.42";
 ///
 /// // Reset counter after successful login
-/// reset_rate_limit(&mut conn, ip)?;
+/// reset_rate_limit(&mut conn, ip).await?;
 /// println!("Rate limit reset for IP: {}", ip);
 /// # Ok(())
 /// # }
@@ -216,9 +223,9 @@ pub fn check_rate_limit(conn: &mut Connection, ip: &str, config: &RateLimitConfi
 /// - **Admin Override**: Manually unblock a user/IP
 /// - **False Positive**: Clear counter for legitimate users
 /// - **Testing**: Reset between test cases
-pub fn reset_rate_limit(conn: &mut Connection, ip: &str) -> Result<()> {
+pub async fn reset_rate_limit(conn: &mut AsyncConnection, ip: &str) -> Result<()> {
     let key = format!("ratelimit:login:{ip}");
-    conn.del::<_, ()>(&key)?;
+    conn.del::<_, ()>(&key).await?;
     Ok(())
 }
 
@@ -229,7 +236,7 @@ pub fn reset_rate_limit(conn: &mut Connection, ip: &str) -> Result<()> {
 ///
 /// # Arguments
 ///
-/// * `conn` - Active Valkey/Redis connection
+/// * `conn` - Pooled Valkey/Redis connection
 /// * `ip` - IP address to check
 ///
 /// # Returns
@@ -241,14 +248,14 @@ pub fn reset_rate_limit(conn: &mut Connection, ip: &str) -> Result<()> {
 ///
 /// ```no_run
 /// use cobalt_stack_backend::services::valkey::rate_limit::get_attempt_count;
-/// use redis::Client;
+/// use cobalt_stack_backend::services::valkey::ValkeyManager;
 ///
-/// # fn example() -> anyhow::Result<()> {
-/// let client = Client::open("redis://127.0.0.1/")?;
-/// let mut conn = client.get_connection()?;
+/// # async fn example() -> anyhow::Result<()> {
+/// let manager = ValkeyManager::new("redis://127.0.0.1/")?;
+/// let mut conn = manager.get_async_connection().await?;
 ///
 /// let ip = "203.0.113.42";
-/// let count = get_attempt_count(&mut conn, ip)?;
+/// let count = get_attempt_count(&mut conn, ip).await?;
 ///
 /// println!("IP {} has {} failed attempts", ip, count);
 /// # Ok(())
@@ -261,9 +268,9 @@ pub fn reset_rate_limit(conn: &mut Connection, ip: &str) -> Result<()> {
 /// - **Logging**: Include attempt count in security logs
 /// - **UI Display**: Show "X attempts remaining" message
 /// - **Analytics**: Collect rate limit statistics
-pub fn get_attempt_count(conn: &mut Connection, ip: &str) -> Result<u32> {
+pub async fn get_attempt_count(conn: &mut AsyncConnection, ip: &str) -> Result<u32> {
     let key = format!("ratelimit:login:{ip}");
-    let count: Option<u32> = conn.get(&key)?;
+    let count: Option<u32> = conn.get(&key).await?;
     Ok(count.unwrap_or(0))
 }
 
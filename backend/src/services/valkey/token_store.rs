@@ -0,0 +1,304 @@
+//! Refresh token store kept entirely in Valkey.
+//!
+//! Lets a deployment issue and rotate refresh tokens without writing to the
+//! database on every login and refresh. Selected with
+//! `REFRESH_TOKEN_STORE=valkey`; the rotation rules are the shared ones in
+//! [`token_rotation`](crate::services::auth::token_rotation).
+//!
+//! # Architecture
+//!
+//! - **Key Format**: `refresh_token:{jti}` holding a hash of the token fields
+//! - **User Index**: `refresh_tokens:user:{user_id}` holding the set of the user's `jti`s
+//! - **Expiry**: A token hash expires with the token; revoked tokens stay
+//!   (with `revoked_at` set) until then, so reuse is still detected. The user
+//!   index lives as long as the user's newest token, and ids of expired
+//!   tokens are dropped from it when read.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use redis::{AsyncCommands, Script};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use super::{AsyncConnection, ValkeyManager};
+use crate::domain::ids::{TokenId, UserId};
+use crate::models::refresh_tokens;
+use crate::services::auth::token_rotation::{Revocation, TokenStore};
+
+/// Set `revoked_at` unless already set: 1 revoked, 0 already revoked, -1 missing
+const REVOKE_SCRIPT: &str = r"
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    return -1
+end
+return redis.call('HSETNX', KEYS[1], 'revoked_at', ARGV[1])
+";
+
+fn token_key(jti: TokenId) -> String {
+    format!("refresh_token:{jti}")
+}
+
+fn user_index_key(user_id: UserId) -> String {
+    format!("refresh_tokens:user:{user_id}")
+}
+
+/// [`TokenStore`] on Valkey
+#[derive(Clone)]
+pub struct ValkeyTokenStore {
+    valkey: ValkeyManager,
+}
+
+impl ValkeyTokenStore {
+    #[must_use]
+    pub const fn new(valkey: ValkeyManager) -> Self {
+        Self { valkey }
+    }
+
+    /// Revoke one token atomically
+    async fn revoke_with(conn: &mut AsyncConnection, jti: TokenId) -> Result<Revocation> {
+        let outcome: i64 = Script::new(REVOKE_SCRIPT)
+            .key(token_key(jti))
+            .arg(Utc::now().to_rfc3339())
+            .invoke_async(conn)
+            .await?;
+
+        Ok(match outcome {
+            1 => Revocation::Revoked,
+            0 => Revocation::AlreadyRevoked,
+            _ => Revocation::NotFound,
+        })
+    }
+
+    /// Load all tokens of a user, dropping ids whose token has expired
+    async fn user_tokens(
+        conn: &mut AsyncConnection,
+        user_id: UserId,
+    ) -> Result<Vec<refresh_tokens::Model>> {
+        let index = user_index_key(user_id);
+        let jtis: Vec<String> = conn.smembers(&index).await?;
+        let jtis: Vec<TokenId> = jtis.iter().filter_map(|jti| jti.parse().ok()).collect();
+        if jtis.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for jti in &jtis {
+            pipe.hgetall(token_key(*jti));
+        }
+        let entries: Vec<HashMap<String, String>> = pipe.query_async(conn).await?;
+
+        let mut tokens = Vec::with_capacity(jtis.len());
+        let mut stale = Vec::new();
+        for (jti, fields) in jtis.into_iter().zip(entries) {
+            match from_fields(jti, &fields) {
+                Some(token) => tokens.push(token),
+                None => stale.push(jti.to_string()),
+            }
+        }
+        if !stale.is_empty() {
+            conn.srem::<_, _, ()>(&index, stale).await?;
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[async_trait]
+impl TokenStore for ValkeyTokenStore {
+    async fn insert(&self, token: refresh_tokens::Model) -> Result<()> {
+        let mut conn = self.valkey.get_async_connection().await?;
+        let key = token_key(token.jti());
+        let index = user_index_key(token.owner());
+        let expires_at = token.expires_at.timestamp();
+
+        redis::pipe()
+            .atomic()
+            .hset_multiple(&key, &to_fields(&token))
+            .ignore()
+            .expire_at(&key, expires_at)
+            .ignore()
+            .sadd(&index, token.id.to_string())
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await?;
+
+        // Keep the index as long as the longest-lived token
+        let remaining = expires_at - Utc::now().timestamp();
+        let ttl: i64 = conn.ttl(&index).await?;
+        if ttl < remaining {
+            conn.expire::<_, ()>(&index, remaining).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn find(&self, jti: TokenId) -> Result<Option<refresh_tokens::Model>> {
+        let mut conn = self.valkey.get_async_connection().await?;
+        let fields: HashMap<String, String> = conn.hgetall(token_key(jti)).await?;
+        Ok(from_fields(jti, &fields))
+    }
+
+    async fn revoke(&self, jti: TokenId) -> Result<Revocation> {
+        let mut conn = self.valkey.get_async_connection().await?;
+        Self::revoke_with(&mut conn, jti).await
+    }
+
+    async fn revoke_all(&self, user_id: UserId) -> Result<u64> {
+        let mut conn = self.valkey.get_async_connection().await?;
+
+        let mut revoked = 0;
+        for token in Self::user_tokens(&mut conn, user_id).await? {
+            if Self::revoke_with(&mut conn, token.jti()).await? == Revocation::Revoked {
+                revoked += 1;
+            }
+        }
+        Ok(revoked)
+    }
+
+    async fn revoke_family(&self, user_id: UserId, family_id: TokenId) -> Result<u64> {
+        let mut conn = self.valkey.get_async_connection().await?;
+
+        let mut revoked = 0;
+        for token in Self::user_tokens(&mut conn, user_id).await? {
+            if token.family() == family_id
+                && Self::revoke_with(&mut conn, token.jti()).await? == Revocation::Revoked
+            {
+                revoked += 1;
+            }
+        }
+        Ok(revoked)
+    }
+
+    async fn list_active(&self, user_id: UserId) -> Result<Vec<refresh_tokens::Model>> {
+        let mut conn = self.valkey.get_async_connection().await?;
+        let now = Utc::now();
+
+        let mut tokens: Vec<_> = Self::user_tokens(&mut conn, user_id)
+            .await?
+            .into_iter()
+            .filter(|token| token.revoked_at.is_none() && token.expires_at > now)
+            .collect();
+        tokens.sort_by_key(|token| Reverse(token.created_at));
+        Ok(tokens)
+    }
+
+    /// Nothing to do: Valkey expires tokens itself
+    async fn cleanup_expired(&self, _retention_days: i64) -> Result<u64> {
+        Ok(0)
+    }
+}
+
+/// Hash fields of a stored token
+fn to_fields(token: &refresh_tokens::Model) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("user_id", token.user_id.to_string()),
+        ("family_id", token.family_id.to_string()),
+        ("token_hash", token.token_hash.clone()),
+        ("expires_at", token.expires_at.to_rfc3339()),
+        ("created_at", token.created_at.to_rfc3339()),
+    ];
+    if let Some(revoked_at) = token.revoked_at {
+        fields.push(("revoked_at", revoked_at.to_rfc3339()));
+    }
+    if let Some(user_agent) = &token.user_agent {
+        fields.push(("user_agent", user_agent.clone()));
+    }
+    if let Some(fingerprint) = &token.fingerprint {
+        fields.push(("fingerprint", fingerprint.clone()));
+    }
+    if let Some(ip) = &token.ip {
+        fields.push(("ip", ip.clone()));
+    }
+    if let Some(last_used_at) = token.last_used_at {
+        fields.push(("last_used_at", last_used_at.to_rfc3339()));
+    }
+    fields
+}
+
+/// Token from its hash fields, `None` if missing or malformed
+///
+/// Tokens stored without a family start their own.
+fn from_fields(jti: TokenId, fields: &HashMap<String, String>) -> Option<refresh_tokens::Model> {
+    let timestamp = |name: &str| -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(fields.get(name)?).ok()
+    };
+
+    Some(refresh_tokens::Model {
+        id: jti.into_uuid(),
+        user_id: fields.get("user_id")?.parse().ok()?,
+        family_id: match fields.get("family_id") {
+            Some(family_id) => family_id.parse().ok()?,
+            None => jti.into_uuid(),
+        },
+        token_hash: fields.get("token_hash")?.clone(),
+        expires_at: timestamp("expires_at")?,
+        revoked_at: timestamp("revoked_at"),
+        created_at: timestamp("created_at")?,
+        user_agent: fields.get("user_agent").cloned(),
+        fingerprint: fields.get("fingerprint").cloned(),
+        ip: fields.get("ip").cloned(),
+        last_used_at: timestamp("last_used_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    fn fields_map(token: &refresh_tokens::Model) -> HashMap<String, String> {
+        to_fields(token)
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn test_key_format() {
+        let (jti, user_id) = (TokenId::new(), UserId::new());
+        assert_eq!(token_key(jti), format!("refresh_token:{jti}"));
+        assert_eq!(
+            user_index_key(user_id),
+            format!("refresh_tokens:user:{user_id}")
+        );
+    }
+
+    #[test]
+    fn test_fields_round_trip() {
+        let now = Utc::now();
+        let mut token = refresh_tokens::Model {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            family_id: Uuid::new_v4(),
+            token_hash: "abc123".to_string(),
+            expires_at: (now + Duration::days(7)).into(),
+            revoked_at: None,
+            created_at: now.into(),
+            user_agent: Some("Firefox".to_string()),
+            fingerprint: Some("f1ae".to_string()),
+            ip: Some("203.0.113.7".to_string()),
+            last_used_at: Some(now.into()),
+        };
+
+        assert_eq!(
+            from_fields(token.jti(), &fields_map(&token)),
+            Some(token.clone())
+        );
+
+        token.revoked_at = Some(now.into());
+        token.user_agent = None;
+        token.fingerprint = None;
+        token.ip = None;
+        token.last_used_at = None;
+        assert_eq!(
+            from_fields(token.jti(), &fields_map(&token)),
+            Some(token.clone())
+        );
+    }
+
+    #[test]
+    fn test_missing_token_has_no_fields() {
+        assert_eq!(from_fields(TokenId::new(), &HashMap::new()), None);
+    }
+}
@@ -0,0 +1,96 @@
+//! Short-lived copies of user rows shared by all instances.
+//!
+//! Authenticated requests read the caller's `users` row (role, disabled flag,
+//! email status); [`UserLoader`](crate::middleware::current_user::UserLoader)
+//! looks here before querying the database, so bursts of requests by the same
+//! user cost one query every [`ttl_from_env`] seconds.
+//!
+//! # Architecture
+//!
+//! - **Key Format**: `user:{id}` holding the row as JSON, without the
+//!   password hash
+//! - **Expiry**: A few seconds, so writes not calling [`invalidate`] show up
+//!   shortly
+//! - **Invalidation**: Handlers changing the role, the disabled flag, the
+//!   username or the email of a user delete the entry
+
+use anyhow::Result;
+use redis::AsyncCommands;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::AsyncConnection;
+use crate::models::users;
+
+/// Expiry when `USER_CACHE_TTL_SECS` is not set
+pub const DEFAULT_TTL: Duration = Duration::from_secs(5);
+
+/// Expiry from `USER_CACHE_TTL_SECS` ([`DEFAULT_TTL`] if unset); `None` if
+/// set to `0`, which turns the cache off
+///
+/// # Panics
+/// Panics if `USER_CACHE_TTL_SECS` is not a number of seconds
+#[must_use]
+pub fn ttl_from_env() -> Option<Duration> {
+    let ttl = std::env::var("USER_CACHE_TTL_SECS").map_or(DEFAULT_TTL, |value| {
+        Duration::from_secs(value.trim().parse().unwrap_or_else(|_| {
+            panic!("USER_CACHE_TTL_SECS must be a number of seconds, got {value:?}")
+        }))
+    });
+    (!ttl.is_zero()).then_some(ttl)
+}
+
+fn user_key(user_id: Uuid) -> String {
+    format!("user:{user_id}")
+}
+
+/// Cached row of a user, if any
+///
+/// Entries that fail to deserialize (e.g. written before a schema change)
+/// count as missing.
+///
+/// # Errors
+///
+/// Returns an error on Redis connection failure
+pub async fn get(conn: &mut AsyncConnection, user_id: Uuid) -> Result<Option<users::Model>> {
+    let entry: Option<String> = conn.get(user_key(user_id)).await?;
+    Ok(entry.and_then(|entry| serde_json::from_str(&entry).ok()))
+}
+
+/// Cache the row of a user for `ttl`; the password hash is left out
+///
+/// # Errors
+///
+/// Returns an error on Redis connection or serialization failure
+pub async fn set(conn: &mut AsyncConnection, user: &users::Model, ttl: Duration) -> Result<()> {
+    let payload = serde_json::to_string(&users::Model {
+        password_hash: None,
+        ..user.clone()
+    })?;
+    conn.set_ex::<_, _, ()>(user_key(user.id), payload, ttl.as_secs().max(1))
+        .await?;
+    Ok(())
+}
+
+/// Drop the cached row of a user, so the next request reads the database
+///
+/// # Errors
+///
+/// Returns an error on Redis connection failure
+pub async fn invalidate(conn: &mut AsyncConnection, user_id: Uuid) -> Result<()> {
+    conn.del::<_, ()>(user_key(user_id)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_key() {
+        assert_eq!(
+            user_key(Uuid::nil()),
+            "user:00000000-0000-0000-0000-000000000000"
+        );
+    }
+}
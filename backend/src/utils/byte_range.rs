@@ -0,0 +1,168 @@
+//! HTTP `Range` requests for resumable downloads.
+//!
+//! [`requested_range`] decides which part of a file of known size a `GET`
+//! should return. Only single byte ranges are served; a multi-range or
+//! malformed `Range` header is ignored and the whole file is sent, as RFC
+//! 9110 allows. An `If-Range` naming another `ETag` also gets the whole
+//! file, so a client resuming a download of a file that changed meanwhile
+//! does not splice two versions together.
+//!
+//! # Examples
+//!
+//! ```
+//! use axum::http::{header, HeaderMap};
+//! use cobalt_stack_backend::utils::byte_range::{requested_range, RangeRequest};
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert(header::RANGE, "bytes=500-".parse().unwrap());
+//! assert_eq!(
+//!     requested_range(&headers, 1000, "\"abc\""),
+//!     RangeRequest::Partial(500..1000)
+//! );
+//! ```
+
+use axum::http::{header, HeaderMap};
+use std::ops::Range;
+
+/// What part of a file to send
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// The whole file (`200 OK`)
+    Full,
+    /// The bytes of the range, end exclusive (`206 Partial Content`)
+    Partial(Range<u64>),
+    /// The range lies outside the file (`416 Range Not Satisfiable`)
+    Unsatisfiable,
+}
+
+/// Part of a file of `size` bytes with entity tag `etag` requested by
+/// `headers`
+#[must_use]
+pub fn requested_range(headers: &HeaderMap, size: u64, etag: &str) -> RangeRequest {
+    let Some(range) = headers.get(header::RANGE).and_then(|v| v.to_str().ok()) else {
+        return RangeRequest::Full;
+    };
+    if let Some(if_range) = headers.get(header::IF_RANGE) {
+        // Strong comparison; a date or weak tag never matches
+        if if_range.to_str().ok().map(str::trim) != Some(etag) {
+            return RangeRequest::Full;
+        }
+    }
+    parse_range(range, size)
+}
+
+/// `Content-Range` value of a partial response
+#[must_use]
+pub fn content_range(range: &Range<u64>, size: u64) -> String {
+    format!("bytes {}-{}/{size}", range.start, range.end - 1)
+}
+
+/// `Content-Range` value of a `416` response
+#[must_use]
+pub fn unsatisfied_content_range(size: u64) -> String {
+    format!("bytes */{size}")
+}
+
+/// Parse a `Range` header value against a file of `size` bytes
+fn parse_range(value: &str, size: u64) -> RangeRequest {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return RangeRequest::Full;
+    };
+
+    let range = match (first.trim(), last.trim()) {
+        // Suffix range: the last `n` bytes
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return RangeRequest::Unsatisfiable,
+            Ok(suffix) => size.saturating_sub(suffix)..size,
+            Err(_) => return RangeRequest::Full,
+        },
+        (first, "") => match first.parse::<u64>() {
+            Ok(first) => first..size,
+            Err(_) => return RangeRequest::Full,
+        },
+        (first, last) => match (first.parse::<u64>(), last.parse::<u64>()) {
+            (Ok(first), Ok(last)) if first <= last => first..last.saturating_add(1).min(size),
+            _ => return RangeRequest::Full,
+        },
+    };
+
+    if range.start >= size {
+        RangeRequest::Unsatisfiable
+    } else {
+        RangeRequest::Partial(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(value: &str, size: u64) -> RangeRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, value.parse().unwrap());
+        requested_range(&headers, size, "\"etag\"")
+    }
+
+    #[test]
+    fn test_single_ranges() {
+        assert_eq!(range("bytes=0-99", 1000), RangeRequest::Partial(0..100));
+        assert_eq!(range("bytes=900-", 1000), RangeRequest::Partial(900..1000));
+        assert_eq!(range("bytes=-100", 1000), RangeRequest::Partial(900..1000));
+        // Ends past the file are clamped
+        assert_eq!(
+            range("bytes=990-2000", 1000),
+            RangeRequest::Partial(990..1000)
+        );
+        assert_eq!(range("bytes=-5000", 1000), RangeRequest::Partial(0..1000));
+    }
+
+    #[test]
+    fn test_unsatisfiable_ranges() {
+        assert_eq!(range("bytes=1000-", 1000), RangeRequest::Unsatisfiable);
+        assert_eq!(range("bytes=1000-1001", 1000), RangeRequest::Unsatisfiable);
+        assert_eq!(range("bytes=-0", 1000), RangeRequest::Unsatisfiable);
+        assert_eq!(range("bytes=0-", 0), RangeRequest::Unsatisfiable);
+    }
+
+    #[test]
+    fn test_ignored_ranges_send_everything() {
+        assert_eq!(
+            requested_range(&HeaderMap::new(), 1000, "\"etag\""),
+            RangeRequest::Full
+        );
+        assert_eq!(range("bytes=0-1,5-9", 1000), RangeRequest::Full);
+        assert_eq!(range("items=0-1", 1000), RangeRequest::Full);
+        assert_eq!(range("bytes=9-1", 1000), RangeRequest::Full);
+        assert_eq!(range("bytes=a-", 1000), RangeRequest::Full);
+    }
+
+    #[test]
+    fn test_if_range() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, "bytes=10-".parse().unwrap());
+        headers.insert(header::IF_RANGE, "\"etag\"".parse().unwrap());
+        assert_eq!(
+            requested_range(&headers, 100, "\"etag\""),
+            RangeRequest::Partial(10..100)
+        );
+
+        // The file changed since the client started downloading
+        headers.insert(header::IF_RANGE, "\"other\"".parse().unwrap());
+        assert_eq!(
+            requested_range(&headers, 100, "\"etag\""),
+            RangeRequest::Full
+        );
+    }
+
+    #[test]
+    fn test_content_range() {
+        assert_eq!(content_range(&(0..100), 1000), "bytes 0-99/1000");
+        assert_eq!(unsatisfied_content_range(1000), "bytes */1000");
+    }
+}
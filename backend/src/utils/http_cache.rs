@@ -0,0 +1,162 @@
+//! Conditional JSON responses for rarely changing endpoints.
+//!
+//! [`cached_json`] adds `Cache-Control` and a strong `ETag` (a hash of the
+//! serialized body) to a JSON response, and answers `304 Not Modified` when
+//! the client's `If-None-Match` already names that `ETag`. Because the `ETag` is
+//! computed from the content, any change (such as an admin editing the
+//! branding) produces a new one without explicit invalidation, on every
+//! replica.
+//!
+//! # Examples
+//!
+//! ```
+//! use axum::http::{header, HeaderMap, StatusCode};
+//! use cobalt_stack_backend::utils::http_cache::cached_json;
+//! use std::time::Duration;
+//!
+//! let max_age = Duration::from_secs(60);
+//! let response = cached_json(&HeaderMap::new(), max_age, &["a", "b"]);
+//! assert_eq!(response.status(), StatusCode::OK);
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert(header::IF_NONE_MATCH, response.headers()[header::ETAG].clone());
+//! let revalidated = cached_json(&headers, max_age, &["a", "b"]);
+//! assert_eq!(revalidated.status(), StatusCode::NOT_MODIFIED);
+//! ```
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// Bytes of the SHA-256 digest kept in the `ETag`
+const ETAG_BYTES: usize = 16;
+
+/// JSON response for `body`, or `304 Not Modified` if the client has it
+///
+/// Clients may reuse the response for `max_age` (revalidating afterwards);
+/// a zero `max_age` makes them revalidate on every use.
+pub fn cached_json<T: Serialize>(
+    request_headers: &HeaderMap,
+    max_age: Duration,
+    body: &T,
+) -> Response {
+    let json = match serde_json::to_vec(body) {
+        Ok(json) => json,
+        Err(e) => {
+            tracing::error!("Failed to serialize cached response: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let etag = etag(&json);
+    let cache_headers = [
+        (header::CACHE_CONTROL, cache_control(max_age)),
+        (header::ETAG, etag.clone()),
+    ];
+
+    if matches_etag(request_headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    (
+        cache_headers,
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        )],
+        json,
+    )
+        .into_response()
+}
+
+/// `Cache-Control` for shared, revalidated responses
+fn cache_control(max_age: Duration) -> HeaderValue {
+    if max_age.is_zero() {
+        return HeaderValue::from_static("no-cache");
+    }
+    HeaderValue::from_str(&format!(
+        "public, max-age={}, must-revalidate",
+        max_age.as_secs()
+    ))
+    .unwrap_or_else(|_| HeaderValue::from_static("no-cache"))
+}
+
+/// Strong `ETag` of a serialized body
+fn etag(body: &[u8]) -> HeaderValue {
+    let digest = Sha256::digest(body);
+    let tag = format!("\"{}\"", hex::encode(&digest[..ETAG_BYTES]));
+    HeaderValue::from_str(&tag).expect("hex ETag is a valid header value")
+}
+
+/// Whether `If-None-Match` names `etag` (weak comparison, as RFC 9110 asks)
+fn matches_etag(request_headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+
+    request_headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn if_none_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_response_carries_cache_headers() {
+        let response = cached_json(&HeaderMap::new(), Duration::from_secs(300), &"hello");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=300, must-revalidate"
+        );
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let etag = response.headers()[header::ETAG].to_str().unwrap();
+        assert_eq!(etag.len(), ETAG_BYTES * 2 + 2);
+
+        let zero = cached_json(&HeaderMap::new(), Duration::ZERO, &"hello");
+        assert_eq!(zero.headers()[header::CACHE_CONTROL], "no-cache");
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let max_age = Duration::from_secs(60);
+        let response = cached_json(&HeaderMap::new(), max_age, &"v1");
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        for value in [
+            etag.clone(),
+            format!("W/{etag}"),
+            format!("\"other\", {etag}"),
+            "*".to_string(),
+        ] {
+            let response = cached_json(&if_none_match(&value), max_age, &"v1");
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED, "{value}");
+            assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        }
+
+        // Changed content gets a new ETag
+        let changed = cached_json(&if_none_match(&etag), max_age, &"v2");
+        assert_eq!(changed.status(), StatusCode::OK);
+        assert_ne!(changed.headers()[header::ETAG], etag.as_str());
+    }
+}
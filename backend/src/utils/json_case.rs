@@ -0,0 +1,127 @@
+//! Field name case conversion for JSON documents
+//!
+//! DTOs serialize their fields in `snake_case` (see [`crate::dto`]); these
+//! helpers translate whole documents to and from `camelCase` for the
+//! camelCase API (see [`crate::middleware::json_case`]).
+
+use serde_json::{Map, Value};
+
+/// `snake_case` name in `camelCase` (`expires_in` → `expiresIn`)
+///
+/// Names that are not lowercase `snake_case` (`X-Header`, `chat:use`, a
+/// leading underscore) are returned unchanged.
+#[must_use]
+pub fn to_camel_case(name: &str) -> String {
+    let is_snake = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !is_snake {
+        return name.to_string();
+    }
+
+    let mut camel = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper_next = true;
+        } else if upper_next {
+            camel.push(c.to_ascii_uppercase());
+            upper_next = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+/// `camelCase` name in `snake_case` (`expiresIn` → `expires_in`)
+///
+/// Names that are not `camelCase` (`snake_case` ones included) are returned
+/// unchanged, so clients may send either.
+#[must_use]
+pub fn to_snake_case(name: &str) -> String {
+    let is_camel = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric());
+    if !is_camel {
+        return name.to_string();
+    }
+
+    let mut snake = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Rename every object key in `value`, at any depth
+///
+/// String values are left alone: only field names change.
+#[must_use]
+pub fn rename_keys(value: Value, rename: fn(&str) -> String) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| (rename(&key), rename_keys(value, rename)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| rename_keys(item, rename))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_case_conversion_round_trips() {
+        for (snake, camel) in [
+            ("expires_in", "expiresIn"),
+            ("password_change_deadline", "passwordChangeDeadline"),
+            ("id", "id"),
+            ("ttl_minutes", "ttlMinutes"),
+        ] {
+            assert_eq!(to_camel_case(snake), camel);
+            assert_eq!(to_snake_case(camel), snake);
+        }
+    }
+
+    #[test]
+    fn test_other_names_are_kept() {
+        assert_eq!(to_camel_case("chat:use"), "chat:use");
+        assert_eq!(to_camel_case("_private"), "_private");
+        assert_eq!(to_snake_case("expires_in"), "expires_in");
+        assert_eq!(to_snake_case("Content-Type"), "Content-Type");
+    }
+
+    #[test]
+    fn test_rename_keys_leaves_values() {
+        let document = json!({
+            "token_type": "Bearer",
+            "sessions": [{ "user_agent": "curl", "current": true }],
+            "permissions": ["admin:email_verifications"],
+        });
+
+        assert_eq!(
+            rename_keys(document, to_camel_case),
+            json!({
+                "tokenType": "Bearer",
+                "sessions": [{ "userAgent": "curl", "current": true }],
+                "permissions": ["admin:email_verifications"],
+            })
+        );
+    }
+}
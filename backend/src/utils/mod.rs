@@ -1,11 +1,25 @@
 //! Utility functions module.
 //!
 //! This module provides general-purpose utility functions used throughout
-//! the application. Currently contains token generation and hashing utilities
-//! for email verification.
+//! the application: secure random token generation, hashing and
+//! verification, User-Agent parsing for session device labels, conditional
+//! (`ETag`) JSON responses, byte ranges of resumable downloads, and the query
+//! parameters shared by list endpoints.
 //!
 //! # Modules
 //!
-//! - **token**: Cryptographic token generation and hashing for email verification
+//! - **`byte_range`**: `Range`/`If-Range` requests and `Content-Range` values
+//! - **`http_cache`**: `Cache-Control`/`ETag` headers and `304 Not Modified` responses
+//! - **`json_case`**: `snake_case`/`camelCase` field names of JSON documents
+//! - **pagination**: Page, sort and filter parameters of list endpoints
+//! - **token**: Random tokens (length, encoding, prefix), hashing and constant-time checks,
+//!   re-exported from the domain crate
+//! - **`user_agent`**: Friendly device labels ("Chrome on macOS") from User-Agent headers
 
-pub mod token;
+pub mod byte_range;
+pub mod http_cache;
+pub mod json_case;
+pub mod pagination;
+pub mod user_agent;
+
+pub use cobalt_stack_domain::token;
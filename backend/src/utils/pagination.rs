@@ -0,0 +1,382 @@
+//! Shared query parameters of list endpoints.
+//!
+//! - `?page=2&per_page=50` ([`Pagination`]): 1-based page, `per_page`
+//!   clamped to 1 through [`MAX_PER_PAGE`]
+//! - `?sort=username:asc,created_at:desc` ([`Sort`])
+//! - `?filter=role:admin,email_verified:true` ([`Filters`])
+//!
+//! Sort and filter fields are checked against an allowlist (a [`QueryField`]
+//! enum per endpoint), so a typo is answered with 400 and the allowed fields
+//! instead of being ignored. All three are extractors and implement
+//! [`IntoParams`], so `params(Pagination, Sort<F>, Filters<F>)` documents them
+//! in the `OpenAPI` spec. Free-text search stays a separate `search`
+//! parameter, since its value may contain `,` and `:`.
+
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+};
+use serde::Deserialize;
+use utoipa::{
+    openapi::{
+        path::{Parameter, ParameterBuilder, ParameterIn},
+        schema::{ObjectBuilder, Type},
+        Required,
+    },
+    IntoParams,
+};
+
+/// Items per page when `per_page` is omitted
+pub const DEFAULT_PER_PAGE: u64 = 20;
+
+/// Largest accepted `per_page`; larger values are clamped
+pub const MAX_PER_PAGE: u64 = 100;
+
+/// Page of a list endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Pagination {
+    /// Page number (1-based)
+    #[serde(default = "default_page")]
+    #[param(minimum = 1, default = 1)]
+    pub page: u64,
+
+    /// Number of items per page (at most 100)
+    #[serde(default = "default_per_page")]
+    #[param(minimum = 1, maximum = 100, default = 20)]
+    pub per_page: u64,
+}
+
+const fn default_page() -> u64 {
+    1
+}
+
+const fn default_per_page() -> u64 {
+    DEFAULT_PER_PAGE
+}
+
+impl Pagination {
+    /// Pagination with `page` raised to 1 and `per_page` clamped to
+    /// `1..=MAX_PER_PAGE`
+    #[must_use]
+    pub fn new(page: u64, per_page: u64) -> Self {
+        Self {
+            page: page.max(1),
+            per_page: per_page.clamp(1, MAX_PER_PAGE),
+        }
+    }
+
+    /// 0-based page index, as taken by `SeaORM`'s `fetch_page`
+    #[must_use]
+    pub const fn index(&self) -> u64 {
+        self.page - 1
+    }
+
+    /// Number of pages needed for `total` items
+    #[must_use]
+    pub const fn total_pages(&self, total: u64) -> u64 {
+        total.div_ceil(self.per_page)
+    }
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self::new(default_page(), DEFAULT_PER_PAGE)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Pagination {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(raw) = Query::<Self>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.body_text()))?;
+        Ok(Self::new(raw.page, raw.per_page))
+    }
+}
+
+/// Field of a list endpoint that clients may sort or filter by
+pub trait QueryField: Sized + Copy + Send + Sync + 'static {
+    /// Query names and the fields they select
+    const FIELDS: &'static [(&'static str, Self)];
+
+    /// Look up a field by its query name
+    #[must_use]
+    fn from_name(name: &str) -> Option<Self> {
+        Self::FIELDS
+            .iter()
+            .find(|(field_name, _)| *field_name == name)
+            .map(|(_, field)| *field)
+    }
+
+    /// Query names of all fields, for error messages and docs
+    #[must_use]
+    fn names() -> String {
+        Self::FIELDS
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Sort direction of one sort field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl From<SortDirection> for sea_orm::Order {
+    fn from(direction: SortDirection) -> Self {
+        match direction {
+            SortDirection::Asc => Self::Asc,
+            SortDirection::Desc => Self::Desc,
+        }
+    }
+}
+
+/// `?sort=field:asc,other:desc` (direction defaults to `asc`), most
+/// significant field first; empty when the parameter is absent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sort<F>(pub Vec<(F, SortDirection)>);
+
+impl<F: QueryField + PartialEq> Sort<F> {
+    /// Parse a `sort` parameter value
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the sortable fields for an unknown field, and
+    /// an error for an unknown direction or a field given twice.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut fields: Vec<(F, SortDirection)> = Vec::new();
+        for item in value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+        {
+            let (name, direction) = item.split_once(':').unwrap_or((item, "asc"));
+            let field = F::from_name(name).ok_or_else(|| {
+                format!("Cannot sort by `{name}`; sortable fields: {}", F::names())
+            })?;
+            let direction = match direction {
+                "asc" => SortDirection::Asc,
+                "desc" => SortDirection::Desc,
+                _ => {
+                    return Err(format!(
+                        "Sort direction of `{name}` must be `asc` or `desc`"
+                    ))
+                }
+            };
+            if fields.iter().any(|(known, _)| *known == field) {
+                return Err(format!("`{name}` is sorted by more than once"));
+            }
+            fields.push((field, direction));
+        }
+        Ok(Self(fields))
+    }
+
+    /// The requested sort, or `default` when none was given
+    #[must_use]
+    pub fn or(self, field: F, direction: SortDirection) -> Vec<(F, SortDirection)> {
+        if self.0.is_empty() {
+            vec![(field, direction)]
+        } else {
+            self.0
+        }
+    }
+}
+
+/// `?filter=field:value,other:value`; empty when the parameter is absent
+///
+/// Values are passed on as strings: the endpoint parses them for its field
+/// and answers 400 when they do not fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filters<F>(pub Vec<(F, String)>);
+
+impl<F: QueryField + PartialEq> Filters<F> {
+    /// Parse a `filter` parameter value
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the filterable fields for an unknown field,
+    /// and an error for a missing value or a field given twice.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut filters: Vec<(F, String)> = Vec::new();
+        for item in value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+        {
+            let (name, value) = item
+                .split_once(':')
+                .filter(|(_, value)| !value.is_empty())
+                .ok_or_else(|| format!("Filter `{item}` must be `field:value`"))?;
+            let field = F::from_name(name).ok_or_else(|| {
+                format!(
+                    "Cannot filter by `{name}`; filterable fields: {}",
+                    F::names()
+                )
+            })?;
+            if filters.iter().any(|(known, _)| *known == field) {
+                return Err(format!("`{name}` is filtered by more than once"));
+            }
+            filters.push((field, value.to_string()));
+        }
+        Ok(Self(filters))
+    }
+}
+
+#[derive(Deserialize)]
+struct SortQuery {
+    sort: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FilterQuery {
+    filter: Option<String>,
+}
+
+#[async_trait]
+impl<S, F> FromRequestParts<S> for Sort<F>
+where
+    S: Send + Sync,
+    F: QueryField + PartialEq,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<SortQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.body_text()))?;
+        Self::parse(query.sort.as_deref().unwrap_or_default())
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))
+    }
+}
+
+#[async_trait]
+impl<S, F> FromRequestParts<S> for Filters<F>
+where
+    S: Send + Sync,
+    F: QueryField + PartialEq,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<FilterQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.body_text()))?;
+        Self::parse(query.filter.as_deref().unwrap_or_default())
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))
+    }
+}
+
+impl<F: QueryField> IntoParams for Sort<F> {
+    fn into_params(_parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        vec![string_query_param(
+            "sort",
+            format!(
+                "Comma-separated `field:asc` or `field:desc`, most significant first; fields: {}",
+                F::names()
+            ),
+        )]
+    }
+}
+
+impl<F: QueryField> IntoParams for Filters<F> {
+    fn into_params(_parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        vec![string_query_param(
+            "filter",
+            format!(
+                "Comma-separated `field:value` conditions, all of which must match; fields: {}",
+                F::names()
+            ),
+        )]
+    }
+}
+
+fn string_query_param(name: &str, description: String) -> Parameter {
+    ParameterBuilder::new()
+        .name(name)
+        .parameter_in(ParameterIn::Query)
+        .required(Required::False)
+        .description(Some(description))
+        .schema(Some(ObjectBuilder::new().schema_type(Type::String)))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Field {
+        Name,
+        CreatedAt,
+    }
+
+    impl QueryField for Field {
+        const FIELDS: &'static [(&'static str, Self)] =
+            &[("name", Self::Name), ("created_at", Self::CreatedAt)];
+    }
+
+    #[test]
+    fn test_pagination_is_normalized() {
+        assert_eq!(Pagination::new(0, 0), Pagination::new(1, 1));
+        assert_eq!(Pagination::new(3, 500).per_page, MAX_PER_PAGE);
+        assert_eq!(Pagination::default().per_page, DEFAULT_PER_PAGE);
+        assert_eq!(Pagination::new(3, 20).index(), 2);
+    }
+
+    #[test]
+    fn test_total_pages() {
+        let pagination = Pagination::new(1, 20);
+
+        assert_eq!(pagination.total_pages(0), 0);
+        assert_eq!(pagination.total_pages(20), 1);
+        assert_eq!(pagination.total_pages(21), 2);
+    }
+
+    #[test]
+    fn test_parse_sort() {
+        let sort = Sort::<Field>::parse("created_at:desc, name").unwrap();
+        assert_eq!(
+            sort.0,
+            vec![
+                (Field::CreatedAt, SortDirection::Desc),
+                (Field::Name, SortDirection::Asc)
+            ]
+        );
+
+        assert!(Sort::<Field>::parse("")
+            .unwrap()
+            .or(Field::Name, SortDirection::Desc)
+            .eq(&[(Field::Name, SortDirection::Desc)]));
+        assert!(Sort::<Field>::parse("email:asc")
+            .unwrap_err()
+            .contains("name, created_at"));
+        assert!(Sort::<Field>::parse("name:up").is_err());
+        assert!(Sort::<Field>::parse("name,name:desc").is_err());
+    }
+
+    #[test]
+    fn test_parse_filters() {
+        let filters = Filters::<Field>::parse("name:a:b,created_at:2024").unwrap();
+        assert_eq!(
+            filters.0,
+            vec![
+                (Field::Name, "a:b".to_string()),
+                (Field::CreatedAt, "2024".to_string())
+            ]
+        );
+
+        assert!(Filters::<Field>::parse("").unwrap().0.is_empty());
+        assert!(Filters::<Field>::parse("name").is_err());
+        assert!(Filters::<Field>::parse("name:").is_err());
+        assert!(Filters::<Field>::parse("email:x").is_err());
+        assert!(Filters::<Field>::parse("name:a,name:b").is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! User-Agent parsing into friendly device labels.
+//!
+//! Sessions store the raw `User-Agent` header; this module turns it into a
+//! label such as "Chrome on macOS" for the sessions list and new sign-in
+//! emails. Detection only covers common browsers and operating systems and is
+//! meant for display, never for security decisions (clients can send any
+//! User-Agent).
+//!
+//! # Examples
+//!
+//! ```
+//! use cobalt_stack_backend::utils::user_agent::device_label;
+//!
+//! let ua = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 \
+//!           (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36";
+//! assert_eq!(device_label(Some(ua)), "Chrome on macOS");
+//! assert_eq!(device_label(None), "Unknown device");
+//! ```
+
+use axum::http::{header::USER_AGENT, HeaderMap};
+use std::fmt;
+
+/// Longest User-Agent stored with a session, in characters
+pub const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Browser markers, checked in order (Chromium-based browsers also send
+/// `Chrome/`, and nearly everything sends `Safari/`)
+const BROWSERS: &[(&str, &str)] = &[
+    ("Edg/", "Edge"),
+    ("EdgA/", "Edge"),
+    ("EdgiOS/", "Edge"),
+    ("OPR/", "Opera"),
+    ("SamsungBrowser/", "Samsung Internet"),
+    ("Firefox/", "Firefox"),
+    ("FxiOS/", "Firefox"),
+    ("CriOS/", "Chrome"),
+    ("Chromium/", "Chromium"),
+    ("Chrome/", "Chrome"),
+    ("Version/", "Safari"),
+    ("curl/", "curl"),
+    ("PostmanRuntime/", "Postman"),
+];
+
+/// Operating system markers, checked in order (iPads claim to be macOS,
+/// Android and Chromebooks claim to be Linux)
+const OPERATING_SYSTEMS: &[(&str, &str)] = &[
+    ("iPhone", "iOS"),
+    ("iPad", "iPadOS"),
+    ("Android", "Android"),
+    ("CrOS", "ChromeOS"),
+    ("Windows", "Windows"),
+    ("Macintosh", "macOS"),
+    ("Mac OS X", "macOS"),
+    ("Linux", "Linux"),
+];
+
+/// Browser and operating system detected from a User-Agent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Device {
+    pub browser: Option<&'static str>,
+    pub os: Option<&'static str>,
+}
+
+impl Device {
+    /// Detect the browser and operating system of a User-Agent
+    #[must_use]
+    pub fn parse(user_agent: &str) -> Self {
+        let find = |markers: &[(&str, &'static str)]| {
+            markers
+                .iter()
+                .find(|(marker, _)| user_agent.contains(marker))
+                .map(|(_, name)| *name)
+        };
+        Self {
+            browser: find(BROWSERS),
+            os: find(OPERATING_SYSTEMS),
+        }
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.browser, self.os) {
+            (Some(browser), Some(os)) => write!(f, "{browser} on {os}"),
+            (Some(browser), None) => f.write_str(browser),
+            (None, Some(os)) => write!(f, "Unknown browser on {os}"),
+            (None, None) => f.write_str("Unknown device"),
+        }
+    }
+}
+
+/// Friendly label for a stored User-Agent ("Unknown device" when absent)
+#[must_use]
+pub fn device_label(user_agent: Option<&str>) -> String {
+    user_agent
+        .map(Device::parse)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The request's User-Agent, truncated to [`MAX_USER_AGENT_LENGTH`]
+/// characters for storage
+#[must_use]
+pub fn from_headers(headers: &HeaderMap) -> Option<String> {
+    let user_agent = headers.get(USER_AGENT)?.to_str().ok()?.trim();
+    (!user_agent.is_empty()).then(|| user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_common_browsers() {
+        let cases = [
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/126.0.0.0 Safari/537.36 Edg/126.0.0.0",
+                "Edge on Windows",
+            ),
+            (
+                "Mozilla/5.0 (X11; Linux x86_64; rv:127.0) Gecko/20100101 Firefox/127.0",
+                "Firefox on Linux",
+            ),
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_5) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/17.5 Safari/605.1.15",
+                "Safari on macOS",
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) CriOS/126.0.6478.54 Mobile/15E148 Safari/604.1",
+                "Chrome on iOS",
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/126.0.0.0 Mobile Safari/537.36",
+                "Chrome on Android",
+            ),
+            ("curl/8.7.1", "curl"),
+            (
+                "Mozilla/5.0 (X11; Linux x86_64)",
+                "Unknown browser on Linux",
+            ),
+            ("", "Unknown device"),
+        ];
+
+        for (user_agent, label) in cases {
+            assert_eq!(device_label(Some(user_agent)), label, "{user_agent}");
+        }
+    }
+
+    #[test]
+    fn test_from_headers_truncates() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(from_headers(&headers), None);
+
+        headers.insert(USER_AGENT, HeaderValue::from_static("  "));
+        assert_eq!(from_headers(&headers), None);
+
+        let long = "a".repeat(MAX_USER_AGENT_LENGTH + 10);
+        headers.insert(USER_AGENT, HeaderValue::from_str(&long).unwrap());
+        assert_eq!(
+            from_headers(&headers).map(|ua| ua.len()),
+            Some(MAX_USER_AGENT_LENGTH)
+        );
+    }
+}
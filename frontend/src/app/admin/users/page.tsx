@@ -51,10 +51,13 @@ export default function AdminUsersPage() {
         per_page: '10',
       })
 
+      const filters: string[] = []
+      if (roleFilter !== 'all') filters.push(`role:${roleFilter}`)
+      if (verifiedFilter === 'verified') filters.push('email_verified:true')
+      if (verifiedFilter === 'unverified') filters.push('email_verified:false')
+
       if (search) params.append('search', search)
-      if (roleFilter !== 'all') params.append('role', roleFilter)
-      if (verifiedFilter === 'verified') params.append('email_verified', 'true')
-      if (verifiedFilter === 'unverified') params.append('email_verified', 'false')
+      if (filters.length > 0) params.append('filter', filters.join(','))
 
       const response = await fetch(`${env.apiUrl}/api/v1/admin/users?${params}`, {
         headers: {
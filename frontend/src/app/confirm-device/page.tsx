@@ -0,0 +1,122 @@
+'use client'
+
+import { CheckCircle2, Loader2, XCircle } from 'lucide-react'
+import Link from 'next/link'
+import { useRouter, useSearchParams } from 'next/navigation'
+import { Suspense, useEffect, useState } from 'react'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { env } from '@/lib/env'
+
+type ConfirmationState = 'confirming' | 'success' | 'error'
+
+function ConfirmDeviceContent() {
+  const router = useRouter()
+  const searchParams = useSearchParams()
+  const token = searchParams.get('token')
+
+  const [state, setState] = useState<ConfirmationState>('confirming')
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    const confirmDevice = async () => {
+      if (!token) {
+        setState('error')
+        setError('Confirmation token is missing')
+        return
+      }
+
+      try {
+        const response = await fetch(`${env.apiUrl}/api/v1/auth/devices/confirm`, {
+          method: 'POST',
+          headers: {
+            'Content-Type': 'application/json',
+          },
+          body: JSON.stringify({ token }),
+        })
+
+        if (!response.ok) {
+          const errorData = await response.json().catch(() => ({
+            error: 'Device confirmation failed',
+          }))
+          throw new Error(errorData.error || 'Device confirmation failed')
+        }
+
+        setState('success')
+      } catch (err) {
+        setState('error')
+        setError(err instanceof Error ? err.message : 'Device confirmation failed')
+      }
+    }
+
+    confirmDevice()
+  }, [token])
+
+  return (
+    <div className="flex min-h-screen items-center justify-center p-4">
+      <Card className="w-full max-w-md">
+        <CardHeader className="text-center">
+          <div className="mx-auto mb-4">
+            {state === 'confirming' && <Loader2 className="h-16 w-16 animate-spin text-primary" />}
+            {state === 'success' && <CheckCircle2 className="h-16 w-16 text-green-600" />}
+            {state === 'error' && <XCircle className="h-16 w-16 text-destructive" />}
+          </div>
+          <CardTitle>
+            {state === 'confirming' && 'Confirming Device'}
+            {state === 'success' && 'Device Confirmed'}
+            {state === 'error' && 'Confirmation Failed'}
+          </CardTitle>
+          <CardDescription>
+            {state === 'confirming' && 'Please wait while we confirm your device...'}
+            {state === 'success' && 'Your device has been successfully confirmed!'}
+            {state === 'error' && 'We could not confirm your device.'}
+          </CardDescription>
+        </CardHeader>
+
+        <CardContent className="space-y-4">
+          {state === 'error' && error && (
+            <div className="rounded-md bg-destructive/15 p-3 text-sm text-destructive">{error}</div>
+          )}
+
+          {state === 'success' && (
+            <div className="space-y-4">
+              <p className="text-center text-sm text-muted-foreground">
+                Sign in again on that device to stay signed in.
+              </p>
+              <Button className="w-full" onClick={() => router.push('/login')}>
+                Go to Login
+              </Button>
+            </div>
+          )}
+
+          {state === 'error' && (
+            <div className="space-y-4">
+              <p className="text-center text-sm text-muted-foreground">
+                The confirmation link may have expired or is invalid.
+              </p>
+              <div className="flex flex-col gap-2">
+                <Button className="w-full" variant="outline" asChild>
+                  <Link href="/login">Back to Login</Link>
+                </Button>
+              </div>
+            </div>
+          )}
+        </CardContent>
+      </Card>
+    </div>
+  )
+}
+
+export default function ConfirmDevicePage() {
+  return (
+    <Suspense
+      fallback={
+        <div className="flex min-h-screen items-center justify-center">
+          <Loader2 className="h-8 w-8 animate-spin text-primary" />
+        </div>
+      }
+    >
+      <ConfirmDeviceContent />
+    </Suspense>
+  )
+}